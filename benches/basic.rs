@@ -129,6 +129,94 @@ fn draw_triangle(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn draw_many_uniforms(b: &mut Bencher) {
+    let display = support::build_display();
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { position: [-0.5, -0.5] },
+                Vertex { position: [ 0.0,  0.5] },
+                Vertex { position: [ 0.5, -0.5] },
+            ]
+        ).unwrap()
+    };
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                uniform mat4 model;
+                uniform mat4 view;
+                uniform mat4 projection;
+
+                void main() {
+                    gl_Position = projection * view * model * vec4(position, 0.0, 1.0);
+                }
+            ",
+
+            fragment: "
+                #version 140
+
+                uniform vec4 color0;
+                uniform vec4 color1;
+                uniform vec4 color2;
+                uniform vec4 color3;
+                uniform float intensity;
+
+                out vec4 f_color;
+
+                void main() {
+                    f_color = (color0 + color1 + color2 + color3) * intensity;
+                }
+            ",
+        },
+    ).unwrap();
+
+    let identity = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0f32],
+    ];
+    let color = [1.0, 1.0, 1.0, 1.0f32];
+    let intensities: Vec<f32> = (0 .. 100).map(|i| i as f32 / 100.0).collect();
+
+    b.iter(|| {
+        let mut target = display.draw();
+
+        for intensity in intensities.iter() {
+            let uniforms = uniform! {
+                model: identity,
+                view: identity,
+                projection: identity,
+                color0: color,
+                color1: color,
+                color2: color,
+                color3: color,
+                intensity: *intensity,
+            };
+
+            target.draw(&vertex_buffer,
+                        &glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList),
+                        &program, &uniforms, &Default::default()).unwrap();
+        }
+
+        target.finish().unwrap();
+    });
+}
+
 #[bench]
 fn build_buffer(b: &mut Bencher) {
     let display = support::build_context();
@@ -1,9 +1,18 @@
 extern crate glium;
 extern crate libc;
 
+use glium::{glutin, DisplayBuild};
+
 use std::ptr;
 use std::rc::Rc;
 
+/// Builds a hidden window with a real OpenGL context.
+///
+/// The dummy backend of `build_context` doesn't provide the functions required to draw.
+pub fn build_display() -> glium::Display {
+    glutin::WindowBuilder::new().with_visibility(false).build_glium().unwrap()
+}
+
 /// Builds a context with dummy OpenGL functions.
 pub fn build_context() -> Rc<glium::backend::Context> {
     unsafe {
@@ -38,9 +38,13 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_AMD_depth_clamp_separate",
             "GL_APPLE_vertex_array_object",
             "GL_ARB_bindless_texture",
+            "GL_ARB_blend_func_extended",
             "GL_ARB_buffer_storage",
+            "GL_ARB_clear_texture",
+            "GL_ARB_color_buffer_float",
             "GL_ARB_compute_shader",
             "GL_ARB_copy_buffer",
+            "GL_ARB_copy_image",
             "GL_ARB_debug_output",
             "GL_ARB_depth_texture",
             "GL_ARB_direct_state_access",
@@ -51,8 +55,10 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_ES3_2_compatibility",
             "GL_ARB_framebuffer_sRGB",
             "GL_ARB_geometry_shader4",
+            "GL_ARB_gl_spirv",
             "GL_ARB_gpu_shader_fp64",
             "GL_ARB_gpu_shader_int64",
+            "GL_ARB_internalformat_query2",
             "GL_ARB_invalidate_subdata",
             "GL_ARB_multi_draw_indirect",
             "GL_ARB_occlusion_query",
@@ -60,7 +66,9 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_robustness",
             "GL_ARB_shader_image_load_store",
             "GL_ARB_shader_objects",
+            "GL_ARB_sparse_texture",
             "GL_ARB_texture_buffer_object",
+            "GL_ARB_texture_filter_minmax",
             "GL_ARB_texture_float",
             "GL_ARB_texture_multisample",
             "GL_ARB_texture_rg",
@@ -87,9 +95,13 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_EXT_texture_sRGB",
             "GL_EXT_transform_feedback",
             "GL_GREMEDY_string_marker",
+            "GL_INTEL_conservative_rasterization",
             "GL_KHR_robustness",
             "GL_NVX_gpu_memory_info",
             "GL_NV_conditional_render",
+            "GL_NV_conservative_raster",
+            "GL_NV_conservative_raster_dilate",
+            "GL_NV_texture_barrier",
             "GL_NV_vertex_attrib_integer_64bit",
         ],
     );
@@ -121,6 +133,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_OES_packed_depth_stencil",
             "GL_OES_primitive_bounding_box",
             "GL_OES_rgb8_rgba8",
+            "GL_OES_standard_derivatives",
             "GL_OES_texture_buffer",
             "GL_OES_texture_npot",
             "GL_OES_vertex_array_object",
@@ -22,6 +22,7 @@ enum TextureDimensions {
     Texture1dArray,
     Texture2dArray,
     Texture2dMultisampleArray,
+    TextureRectangle,
     Cubemap,
     CubemapArray,
 }
@@ -114,6 +115,7 @@ pub fn build_texture_file<W: Write>(mut dest: &mut W) {
     build_texture(dest, TextureType::Depth, TextureDimensions::Texture2dMultisampleArray);
     build_texture(dest, TextureType::Stencil, TextureDimensions::Texture2dMultisampleArray);
     build_texture(dest, TextureType::DepthStencil, TextureDimensions::Texture2dMultisampleArray);
+    build_texture(dest, TextureType::Regular, TextureDimensions::TextureRectangle);
     build_texture(dest, TextureType::Regular, TextureDimensions::Cubemap);
     build_texture(dest, TextureType::Compressed, TextureDimensions::Cubemap);
     build_texture(dest, TextureType::Srgb, TextureDimensions::Cubemap);
@@ -157,6 +159,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
             TextureDimensions::Texture1dArray => "texture1d_array",
             TextureDimensions::Texture2dArray => "texture2d_array",
             TextureDimensions::Texture2dMultisampleArray => "texture2d_multisample_array",
+            TextureDimensions::TextureRectangle => "texture_rectangle",
             TextureDimensions::Cubemap => "cubemap",
             TextureDimensions::CubemapArray => "cubemap_array",
         };
@@ -186,6 +189,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
             TextureDimensions::Texture1dArray => "Texture1dArray",
             TextureDimensions::Texture2dArray => "Texture2dArray",
             TextureDimensions::Texture2dMultisampleArray => "Texture2dMultisampleArray",
+            TextureDimensions::TextureRectangle => "TextureRectangle",
             TextureDimensions::Cubemap => "Cubemap",
             TextureDimensions::CubemapArray => "CubemapArray",
         };
@@ -196,7 +200,8 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
     // the trait corresponding to the data source
     let data_source_trait = match dimensions {
         TextureDimensions::Texture1d | TextureDimensions::Texture1dArray => "Texture1dDataSource",
-        TextureDimensions::Texture2d | TextureDimensions::Texture2dArray => "Texture2dDataSource",
+        TextureDimensions::Texture2d | TextureDimensions::Texture2dArray |
+        TextureDimensions::TextureRectangle => "Texture2dDataSource",
         TextureDimensions::Texture3d => "Texture3dDataSource",
         TextureDimensions::Texture2dMultisample | TextureDimensions::Texture2dMultisampleArray |
         TextureDimensions::Cubemap | TextureDimensions::CubemapArray => {
@@ -250,6 +255,8 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
 
     let mipmap_default = match ty {
         TextureType::Compressed | TextureType::CompressedSrgb => "CompressedMipmapsOption::NoMipmap",
+        // rectangle textures can't have mipmaps
+        _ if dimensions == TextureDimensions::TextureRectangle => "MipmapsOption::NoMipmap",
         _ => "MipmapsOption::AutoGeneratedMipmaps",
     };
 
@@ -262,6 +269,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
         TextureDimensions::Texture1dArray => "width: u32, array_size: u32",
         TextureDimensions::Texture2dArray => "width: u32, height: u32, array_size: u32",
         TextureDimensions::Texture2dMultisampleArray => "width: u32, height: u32, array_size: u32, samples: u32",
+        TextureDimensions::TextureRectangle => "width: u32, height: u32",
         TextureDimensions::Cubemap => "dimension: u32",
         TextureDimensions::CubemapArray => "dimension: u32, array_size: u32",
     };
@@ -288,6 +296,9 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
         TextureDimensions::Texture2dMultisampleArray => {
             "Dimensions::Texture2dMultisampleArray { width: width, height: height, array_size: array_size, samples: samples }"
         },
+        TextureDimensions::TextureRectangle => {
+            "Dimensions::TextureRectangle { width: width, height: height }"
+        },
         TextureDimensions::Cubemap => {
             "Dimensions::Cubemap { dimension: dimension }"
         },
@@ -318,7 +329,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
             use texture::{{RawImage1d, RawImage2d, RawImage3d, CubeLayer}};
             use texture::pixel::PixelValue;
 
-            use image_format::{{ClientFormatAny, TextureFormatRequest}};
+            use image_format::{{ClientFormat, ClientFormatAny, TextureFormatRequest}};
             use image_format::{{UncompressedFloatFormat, UncompressedIntFormat}};
             use image_format::{{CompressedFormat, DepthFormat, DepthStencilFormat, StencilFormat}};
             use image_format::{{CompressedSrgbFormat, SrgbFormat, UncompressedUintFormat}};
@@ -340,7 +351,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
     (write!(dest, "{}", match dimensions {
         TextureDimensions::Texture1d | TextureDimensions::Texture2d |
         TextureDimensions::Texture2dMultisample | TextureDimensions::Texture3d |
-        TextureDimensions::Cubemap => "A ",
+        TextureDimensions::TextureRectangle | TextureDimensions::Cubemap => "A ",
         TextureDimensions::Texture1dArray | TextureDimensions::Texture2dArray |
         TextureDimensions::Texture2dMultisampleArray |
         TextureDimensions::CubemapArray => "An array of ",
@@ -355,12 +366,13 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
             "two-dimensional "
         },
         TextureDimensions::Texture3d => "three-dimensional ",
+        TextureDimensions::TextureRectangle => "rectangle ",
         TextureDimensions::Cubemap | TextureDimensions::CubemapArray => "cube ",
     })).unwrap();
     (write!(dest, "{}", match dimensions {
         TextureDimensions::Texture1d | TextureDimensions::Texture2d |
         TextureDimensions::Texture2dMultisample | TextureDimensions::Texture3d |
-        TextureDimensions::Cubemap => "texture ",
+        TextureDimensions::TextureRectangle | TextureDimensions::Cubemap => "texture ",
         TextureDimensions::Texture1dArray | TextureDimensions::Texture2dArray |
         TextureDimensions::Texture2dMultisampleArray |
         TextureDimensions::CubemapArray => "textures ",
@@ -414,11 +426,17 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
 
     // `UniformValue` trait impl
     {
-        match ty {
+        let sampleable = match ty {
             TextureType::Regular | TextureType::Compressed |
             TextureType::Srgb | TextureType::CompressedSrgb |
-            TextureType::Integral | TextureType::Unsigned | TextureType::Depth => {
-                (writeln!(dest, "
+            TextureType::Integral | TextureType::Unsigned | TextureType::Depth => true,
+            // sampling a depth-stencil texture returns its depth component
+            TextureType::DepthStencil => dimensions == TextureDimensions::Texture2d,
+            _ => false,
+        };
+
+        if sampleable {
+            (writeln!(dest, "
                             impl<'a> AsUniformValue for &'a {myname} {{
                                 #[inline]
                                 fn as_uniform_value(&self) -> UniformValue {{
@@ -455,8 +473,6 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                                 }}
                             }}
                         ", myname = name)).unwrap();
-            },
-            _ => ()
         }
     }
 
@@ -528,7 +544,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
     if !dimensions.is_multisample() && !dimensions.is_cube() {
         let param = match dimensions {
             TextureDimensions::Texture1d | TextureDimensions::Texture2d |
-            TextureDimensions::Texture3d => "T",
+            TextureDimensions::Texture3d | TextureDimensions::TextureRectangle => "T",
 
             TextureDimensions::Texture1dArray |
             TextureDimensions::Texture2dArray => "Vec<T>",
@@ -538,6 +554,8 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
 
         let gen_doc = if is_compressed {
             "/// No mipmap level (except for the main level) will be allocator nor generated."
+        } else if dimensions == TextureDimensions::TextureRectangle {
+            "/// Rectangle textures can't have mipmaps, so only the main level is allocated."
         } else {
             "/// This function will automatically generate all mipmaps of the texture."
         };
@@ -561,7 +579,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
     if !dimensions.is_multisample() && !dimensions.is_cube() {
         let param = match dimensions {
             TextureDimensions::Texture1d | TextureDimensions::Texture2d |
-            TextureDimensions::Texture3d => "T",
+            TextureDimensions::Texture3d | TextureDimensions::TextureRectangle => "T",
 
             TextureDimensions::Texture1dArray |
             TextureDimensions::Texture2dArray => "Vec<T>",
@@ -618,7 +636,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
     if !dimensions.is_multisample() && !dimensions.is_cube() {
         let param = match dimensions {
             TextureDimensions::Texture1d | TextureDimensions::Texture2d |
-            TextureDimensions::Texture3d => "T",
+            TextureDimensions::Texture3d | TextureDimensions::TextureRectangle => "T",
 
             TextureDimensions::Texture1dArray |
             TextureDimensions::Texture2dArray => "Vec<T>",
@@ -626,8 +644,22 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
             _ => unreachable!()
         };
 
+        let online_compression_doc = if is_compressed {
+            "
+                ///
+                /// The data is uncompressed and is compressed by the driver during the upload.
+                /// You can then call `read_compressed_data` to retrieve the compressed blocks
+                /// and cache them, so that they can be uploaded later with `with_compressed_data`.
+                ///
+                /// The quality of the compression depends on the driver and is usually lower
+                /// than what an offline compressor produces. OpenGL ES doesn't support this, in
+                /// which case `TextureCreationError::FormatNotSupported` is returned."
+        } else {
+            ""
+        };
+
         (writeln!(dest, "
-                /// Builds a new texture with a specific format.
+                /// Builds a new texture with a specific format.{online_compression_doc}
                 #[inline]
                 pub fn with_format<'a, F: ?Sized, T>(facade: &F, data: {param},
                                           format: {format}, mipmaps: {mipmaps})
@@ -638,14 +670,49 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                 }}
             ", data_source_trait = data_source_trait, param = param,
                format = relevant_format, name = name,
-               mipmaps = mipmaps_option_ty)).unwrap();
+               mipmaps = mipmaps_option_ty,
+               online_compression_doc = online_compression_doc)).unwrap();
+    }
+
+    // writing the `with_format_raw` function
+    if !is_compressed && dimensions == TextureDimensions::Texture2d {
+        (writeln!(dest, "
+                /// Builds a new texture with a specific format from raw bytes.
+                ///
+                /// The layout of `data` is described by `client_format`, which doesn't need to
+                /// match `format`. For example you can upload `U8U8U8` data to a texture whose
+                /// format is `U8U8U8U8`.
+                ///
+                /// The data must start by the bottom-left hand corner pixel and progress
+                /// left-to-right and bottom-to-top, without any padding between rows.
+                ///
+                /// Returns `TextureCreationError::DataSizeMismatch` if the length of `data`
+                /// is not equal to `width * height * client_format.get_size()`.
+                pub fn with_format_raw<F: ?Sized>(facade: &F, data: &[u8], width: u32, height: u32,
+                                                  client_format: ClientFormat, format: {format},
+                                                  mipmaps: {mipmaps})
+                                                  -> Result<{name}, TextureCreationError>
+                                                  where F: Facade
+                {{
+                    if data.len() != width as usize * height as usize * client_format.get_size() {{
+                        return Err(TextureCreationError::DataSizeMismatch);
+                    }}
+
+                    let format = TextureFormatRequest::Specific(format.to_texture_format());
+                    let client_format = ClientFormatAny::ClientFormat(client_format);
+                    Ok({name}(try!(any::new_texture(facade, format,
+                                                    Some((client_format, Cow::Borrowed(data))),
+                                                    mipmaps.into(), {dim_params_passing}))))
+                }}
+            ", format = relevant_format, name = name, mipmaps = mipmaps_option_ty,
+               dim_params_passing = dimensions_parameters_passing)).unwrap();
     }
 
     // writing the `new_impl` function
     if !dimensions.is_multisample() && !dimensions.is_cube() {
         let param = match dimensions {
             TextureDimensions::Texture1d | TextureDimensions::Texture2d |
-            TextureDimensions::Texture3d => "T",
+            TextureDimensions::Texture3d | TextureDimensions::TextureRectangle => "T",
 
             TextureDimensions::Texture1dArray |
             TextureDimensions::Texture2dArray => "Vec<T>",
@@ -675,7 +742,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                     let RawImage1d {{ data, width, format: client_format }} = data.into_raw();
                 ")).unwrap(),
 
-            TextureDimensions::Texture2d => (write!(dest, "
+            TextureDimensions::Texture2d | TextureDimensions::TextureRectangle => (write!(dest, "
                     let RawImage2d {{ data, width, height, format: client_format }} =
                                             data.into_raw();
                 ")).unwrap(),
@@ -785,6 +852,11 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                 /// If `owned` is true, this reference will take ownership of the texture and be responsible
                 /// for cleaning it up. Otherwise, the texture must be cleaned up externally, but only
                 /// after this reference's lifetime has ended.
+                ///
+                /// # Safety
+                ///
+                /// The id must be the name of a valid texture object in the share group of the
+                /// context, and its format and dimensions must match `format` and `ty`.
                 pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
                                                  format: {format},
                                                  id: gl::types::GLuint,
@@ -827,6 +899,152 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
             ")).unwrap();
     }
 
+    // writing the `copy_region_from` function
+    if dimensions == TextureDimensions::Texture2d &&
+       (ty == TextureType::Regular ||
+        ty == TextureType::Integral ||
+        ty == TextureType::Unsigned)
+    {
+        (write!(dest, "
+                /// Copies a rectangle of another texture to this texture, without going through
+                /// the client memory.
+                ///
+                /// `dest_offset` is the position of the bottom-left corner of the rectangle in
+                /// this texture. This is useful to assemble a texture atlas from individual
+                /// textures.
+                ///
+                /// The formats of both textures must have the same number of bits per texel,
+                /// otherwise `CopyImageError::IncompatibleFormats` is returned.
+                ///
+                /// ## Low-level information
+                ///
+                /// This function uses `glCopyImageSubData` if it is supported (see
+                /// `TextureAnyImage::copy_to`). Otherwise both textures are attached to
+                /// framebuffers and the rectangle is blitted with the `Nearest` filter, in which
+                /// case the texels are converted if the formats differ and the regions must not
+                /// overlap if `source` is this texture.
+                ///
+                /// # Panic
+                ///
+                /// Panics if the source rectangle is out of the bounds of `source`, or if the
+                /// copied rectangle is out of the bounds of this texture.
+                ///
+                pub fn copy_region_from(&self, source: &{name}, source_rect: &Rect,
+                                        dest_offset: (u32, u32))
+                                        -> Result<(), ::texture::CopyImageError>
+                {{
+                    let src = source.0.main_level().first_layer().into_image(None).unwrap();
+                    let dst = self.0.main_level().first_layer().into_image(None).unwrap();
+
+                    match src.copy_to(source_rect, dst, dest_offset) {{
+                        Err(::texture::CopyImageError::NotSupported) => (),
+                        result => return result,
+                    }};
+
+                    let target = ::BlitTarget {{
+                        left: dest_offset.0,
+                        bottom: dest_offset.1,
+                        width: source_rect.width as i32,
+                        height: source_rect.height as i32,
+                    }};
+
+                    ::Surface::blit_color(&source.as_surface(), source_rect, &self.as_surface(),
+                                          &target, ::uniforms::MagnifySamplerFilter::Nearest);
+                    Ok(())
+                }}
+            ", name = name)).unwrap();
+    }
+
+    // writing the `new_from_framebuffer` and `new_from_frame` functions
+    if dimensions == TextureDimensions::Texture2d && ty == TextureType::Regular {
+        (write!(dest, "
+                /// Builds a new texture with the dimensions of `rect`, and copies this area of
+                /// the first color attachment of a framebuffer to it.
+                ///
+                /// This is handy to take a snapshot of a scene, for example to blend it with
+                /// the next scene during a transition. Contrary to `copy_region_from`, the
+                /// texture doesn't need to exist beforehand.
+                ///
+                /// The format of the texture is chosen by the implementation to match the format
+                /// of the color buffer that is read. Like the framebuffer, the texture has its
+                /// origin at the bottom-left hand corner, so the content is not flipped: the
+                /// bottom row of `rect` is the first row of the texture.
+                ///
+                /// The texture has no mipmap.
+                ///
+                /// ## Low-level information
+                ///
+                /// This function uses `glCopyTexImage2D` with the unsized `GL_RGBA` internal
+                /// format. On OpenGL ES the framebuffer must have an alpha channel.
+                ///
+                /// # Panic
+                ///
+                /// Panics if `rect` is out of the bounds of the framebuffer, or if the
+                /// framebuffer doesn't have a floating-point color attachment.
+                ///
+                pub fn new_from_framebuffer<F: ?Sized>(facade: &F,
+                                                       source: &framebuffer::SimpleFrameBuffer,
+                                                       rect: &Rect) -> {name}
+                                                       where F: Facade
+                {{
+                    let dimensions = ::Surface::get_dimensions(source);
+                    {name}(any::new_from_framebuffer(facade,
+                                                     ::FboAttachments::get_attachments(source),
+                                                     dimensions, rect))
+                }}
+
+                /// Same as `new_from_framebuffer`, but copies an area of the back buffer of
+                /// the frame.
+                ///
+                /// This reads what has been drawn on the frame so far.
+                ///
+                pub fn new_from_frame<F: ?Sized>(facade: &F, source: &::Frame, rect: &Rect)
+                                                 -> {name} where F: Facade
+                {{
+                    let dimensions = ::Surface::get_dimensions(source);
+                    {name}(any::new_from_framebuffer(facade,
+                                                     ::FboAttachments::get_attachments(source),
+                                                     dimensions, rect))
+                }}
+            ", name = name)).unwrap();
+    }
+
+    // writing the `generate_mipmaps` functions
+    if dimensions == TextureDimensions::Texture2d &&
+       (ty == TextureType::Regular || ty == TextureType::Srgb)
+    {
+        (write!(dest, "
+                /// Regenerates the content of all the mipmaps from the main level.
+                ///
+                /// Call this after rendering to the main level of a texture, for example a
+                /// reflection probe that is updated every frame. Does nothing if the texture
+                /// has no mipmap.
+                ///
+                /// ## Low-level information
+                ///
+                /// With OpenGL 4.5 or `GL_ARB_direct_state_access`, this function uses
+                /// `glGenerateTextureMipmap` and doesn't need to bind the texture.
+                ///
+                #[inline]
+                pub fn generate_mipmaps(&self) {{
+                    unsafe {{ self.0.generate_mipmaps(); }}
+                }}
+
+                /// Regenerates the content of the mipmaps `base_level + 1` to `max_level` from
+                /// the mipmap `base_level`.
+                ///
+                /// The other mipmaps are left untouched. Returns an error if the range is out of
+                /// the levels of the texture, or if the backend can't restrict the levels that
+                /// are generated.
+                #[inline]
+                pub fn generate_mipmaps_range(&self, base_level: u32, max_level: u32)
+                                              -> Result<(), ::texture::LevelsRangeError>
+                {{
+                    unsafe {{ self.0.generate_mipmaps_range(base_level, max_level) }}
+                }}
+            ")).unwrap();
+    }
+
     // writing the `get_mipmap_levels` function
     (write!(dest, "
             /// Returns the number of mipmap levels of the texture.
@@ -952,7 +1170,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                 ///
                 /// ## Panic
                 ///
-                /// Panics if the the dimensions of `data` don't match the `Rect`.
+                /// Panics if the dimensions of `data` don't match the `Rect`.
                 {compressed_restrictions}
                 #[inline]
                 pub fn write<'a, T>(&self, rect: Rect, data: T) where T: {data_source_trait}<'a> {{
@@ -962,6 +1180,34 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                 compressed_restrictions = compressed_restrictions)).unwrap();
     }
 
+    // writing the `write_face` function
+    if dimensions == TextureDimensions::Cubemap &&
+            (ty == TextureType::Regular || ty == TextureType::Srgb ||
+             ty == TextureType::Integral || ty == TextureType::Unsigned)
+    {
+        (write!(dest, r#"
+                /// Uploads some data in a face of the cubemap. The dimensions of the data must be
+                /// equal to the dimensions of the cubemap.
+                ///
+                /// Mipmaps are regenerated if the cubemap was created with automatically
+                /// generated mipmaps.
+                ///
+                /// ## Panic
+                ///
+                /// Panics if the dimensions of `data` don't match the dimensions of the
+                /// cubemap.
+                #[inline]
+                pub fn write_face<'a, T>(&self, face: CubeLayer, data: T)
+                                         where T: Texture2dDataSource<'a>
+                {{
+                    let dimension = self.width();
+                    let rect = Rect {{ left: 0, bottom: 0, width: dimension, height: dimension }};
+                    self.0.main_level().first_layer().into_image(Some(face)).unwrap()
+                          .raw_write(&rect, data.into_raw()).unwrap()
+                }}
+            "#)).unwrap();
+    }
+
     // writing the `write_compressed_data` function
     // TODO: implement for other types too
     if dimensions == TextureDimensions::Texture2d && is_compressed
@@ -975,7 +1221,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                 ///
                 /// ## Panic
                 ///
-                /// Panics if the the dimensions of `data` don't match the `Rect`.
+                /// Panics if the dimensions of `data` don't match the `Rect`.
                 ///
                 /// Calling this will result in a panic of type INVALID_OPERATION error if `Rect::width`
                 /// or `Rect::height` is not equal to 0 (border), or if the written dimensions do not match
@@ -1156,7 +1402,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                     ///
                     /// ## Panic
                     ///
-                    /// Panics if the the dimensions of `data` don't match the `Rect`.
+                    /// Panics if the dimensions of `data` don't match the `Rect`.
                     {compressed_restrictions}
                     pub fn write<'a, T>(&self, rect: Rect, data: T) where T: {data_source_trait}<'a> {{
                         let RawImage2d {{ data, width, height, format: client_format }} =
@@ -1186,7 +1432,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                     ///
                     /// ## Panic
                     ///
-                    /// Panics if the the dimensions of `data` don't match the `Rect`.
+                    /// Panics if the dimensions of `data` don't match the `Rect`.
                     ///
                     /// Calling this will result in a panic of type INVALID_OPERATION error if `Rect::width`
                     /// or `Rect::height` is not equal to 0 (border), or if the written dimensions do not match
@@ -1422,8 +1668,8 @@ fn write_dimensions_getters<W: Write>(mut dest: &mut W, dimensions: TextureDimen
     match dimensions {
         TextureDimensions::Texture2d | TextureDimensions::Texture2dMultisample |
         TextureDimensions::Texture3d | TextureDimensions::Texture2dArray |
-        TextureDimensions::Texture2dMultisampleArray | TextureDimensions::Cubemap |
-        TextureDimensions::CubemapArray => {
+        TextureDimensions::Texture2dMultisampleArray | TextureDimensions::TextureRectangle |
+        TextureDimensions::Cubemap | TextureDimensions::CubemapArray => {
             writeln!(dest, r#"
                 /// Returns the height of that image.
                 #[inline]
@@ -1479,7 +1725,8 @@ fn write_dimensions_getters<W: Write>(mut dest: &mut W, dimensions: TextureDimen
 
     match dimensions {
         TextureDimensions::Texture2d | TextureDimensions::Texture2dMultisample |
-        TextureDimensions::Texture2dArray | TextureDimensions::Texture2dMultisampleArray => {
+        TextureDimensions::Texture2dArray | TextureDimensions::Texture2dMultisampleArray |
+        TextureDimensions::TextureRectangle => {
             writeln!(dest, r#"
                 /// Returns the width and height of that image.
                 #[inline]
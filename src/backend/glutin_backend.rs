@@ -10,10 +10,14 @@ Only available if the 'glutin' feature is enabled.
 */
 pub extern crate glutin;
 
+use ContextRequirements;
 use DisplayBuild;
 use Frame;
 use GliumCreationError;
+use Profile;
+use RequirementsNotMetError;
 use SwapBuffersError;
+use version::{Api, Version};
 
 use debug;
 use context;
@@ -172,6 +176,24 @@ impl DisplayBuild for glutin::WindowBuilder<'static> {
         Ok(display)
     }
 
+    fn build_glium_with_requirements(self, requirements: ContextRequirements)
+                                     -> Result<GlutinFacade, GliumCreationError<glutin::CreationError>>
+    {
+        let builder = match gl_request(&requirements) {
+            Some(request) => self.with_gl(request),
+            None => self,
+        };
+
+        let builder = match gl_profile(&requirements) {
+            Some(profile) => builder.with_gl_profile(profile),
+            None => builder,
+        };
+
+        let display = try!(builder.build_glium());
+        try!(check_requirements(&display, &requirements));
+        Ok(display)
+    }
+
     fn rebuild_glium(self, display: &GlutinFacade) -> Result<(), GliumCreationError<glutin::CreationError>> {
         let mut existing_window = Option::as_ref(&display.backend)
                                          .expect("can't rebuild a headless display").borrow_mut();
@@ -210,11 +232,65 @@ impl<'a> DisplayBuild for glutin::HeadlessRendererBuilder<'a> {
         Ok(display)
     }
 
+    fn build_glium_with_requirements(self, requirements: ContextRequirements)
+                                     -> Result<GlutinFacade, GliumCreationError<glutin::CreationError>>
+    {
+        let builder = match gl_request(&requirements) {
+            Some(request) => self.with_gl(request),
+            None => self,
+        };
+
+        let builder = match gl_profile(&requirements) {
+            Some(profile) => builder.with_gl_profile(profile),
+            None => builder,
+        };
+
+        let display = try!(builder.build_glium());
+        try!(check_requirements(&display, &requirements));
+        Ok(display)
+    }
+
     fn rebuild_glium(self, _: &GlutinFacade) -> Result<(), GliumCreationError<glutin::CreationError>> {
         unimplemented!()
     }
 }
 
+/// Turns the required version into a request for glutin.
+fn gl_request(requirements: &ContextRequirements) -> Option<glutin::GlRequest> {
+    match requirements.version {
+        Some(Version(Api::Gl, major, minor)) =>
+            Some(glutin::GlRequest::Specific(glutin::Api::OpenGl, (major, minor))),
+        Some(Version(Api::GlEs, major, minor)) =>
+            Some(glutin::GlRequest::Specific(glutin::Api::OpenGlEs, (major, minor))),
+        None => None,
+    }
+}
+
+/// Turns the required profile into a request for glutin.
+fn gl_profile(requirements: &ContextRequirements) -> Option<glutin::GlProfile> {
+    match requirements.profile {
+        Some(Profile::Core) => Some(glutin::GlProfile::Core),
+        Some(Profile::Compatibility) => Some(glutin::GlProfile::Compatibility),
+        None => None,
+    }
+}
+
+/// Checks the context that has been obtained. Glutin doesn't allow requesting a
+/// forward-compatible context, and may return a more recent version than requested.
+fn check_requirements(display: &GlutinFacade, requirements: &ContextRequirements)
+                      -> Result<(), GliumCreationError<glutin::CreationError>>
+{
+    requirements.check(backend::Facade::get_context(display))
+                .map_err(|err| GliumCreationError::RequirementsNotMet(err))
+}
+
+impl From<RequirementsNotMetError> for GliumCreationError<glutin::CreationError> {
+    #[inline]
+    fn from(err: RequirementsNotMetError) -> GliumCreationError<glutin::CreationError> {
+        GliumCreationError::RequirementsNotMet(err.0)
+    }
+}
+
 /// An implementation of the `Backend` trait for a glutin window.
 pub struct GlutinWindowBackend {
     window: glutin::Window,
@@ -24,7 +24,15 @@ use context::ExtensionsList;
 use version::Version;
 
 pub use context::Context;
+pub use context::{BindingPointRegistry, BindingPointsExhaustedError};
 pub use context::ReleaseBehavior;
+pub use context::{FormatSupport, FormatSupportLevel};
+pub use context::GlStateSnapshot;
+pub use context::RenderStateGuard;
+pub use context::BoundTextureInfo;
+pub use context::{HintTarget, HintNotSupportedError};
+pub use context::TextureBarrierNotSupportedError;
+pub use context::VideoMemoryInfo;
 
 #[cfg(feature = "glutin")]
 pub mod glutin_backend;
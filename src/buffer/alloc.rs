@@ -1,6 +1,7 @@
 use backend::Facade;
 use context::CommandContext;
 use context::Context;
+use context::ResourceKind;
 use version::Version;
 use CapabilitiesSource;
 use ContextExt;
@@ -485,6 +486,8 @@ impl Alloc {
             } else {
                 unreachable!();
             }
+
+            ctxt.state.pending_uploads = true;
         }
     }
 
@@ -520,6 +523,7 @@ impl Alloc {
                 let flags = match self.creation_mode {
                     BufferMode::Default | BufferMode::Immutable => gl::STATIC_DRAW,
                     BufferMode::Persistent | BufferMode::Dynamic => gl::DYNAMIC_DRAW,
+                    BufferMode::Stream => gl::STREAM_DRAW,
                 };
 
                 if ctxt.version >= &Version(Api::Gl, 1, 5) ||
@@ -617,7 +621,9 @@ impl Alloc {
                                 temporary_buffer, 0, size_bytes).unwrap();
                 }
 
-                map_buffer(&mut ctxt, temporary_buffer, self.ty, 0 .. size_bytes, true, true)
+                // the temporary buffer only receives the access bits that were requested, so that
+                // write-only mappings never download the existing content
+                map_buffer(&mut ctxt, temporary_buffer, self.ty, 0 .. size_bytes, read, write)
                                     .expect("Buffer mapping is not supported by the backend")
             };
 
@@ -756,7 +762,8 @@ impl Alloc {
                                        -> WriteMapping<D> where D: Content
     {
         WriteMapping {
-            mapping: self.map_impl(bytes_range, false, true)
+            mapping: self.map_impl(bytes_range, false, true),
+            dirty: None,
         }
     }
 
@@ -812,6 +819,17 @@ impl Alloc {
                                                 size_to_read as gl::types::GLsizeiptr,
                                                 output as *mut _ as *mut _);
 
+                } else if ctxt.version >= &Version(Api::GlEs, 3, 0) {
+                    // `glGetBufferSubData` doesn't exist on OpenGL ES, so we map the buffer instead
+                    let ptr = map_buffer(&mut ctxt, self.id, self.ty, range.clone(), true, false)
+                                        .unwrap();
+                    if ptr.is_null() {
+                        return Err(ReadError::NotSupported);
+                    }
+                    ptr::copy_nonoverlapping(ptr as *const u8, output as *mut D as *mut u8,
+                                             size_to_read);
+                    unmap_buffer(&mut ctxt, self.id, self.ty);
+
                 } else if ctxt.version >= &Version(Api::GlEs, 1, 0) {
                     return Err(ReadError::NotSupported);
 
@@ -904,6 +922,38 @@ enum MappingImpl<'b, D: ?Sized> {
 
 unsafe impl<'a, D: ?Sized> Sync for MappingImpl<'a, D> where D: Send + Sync {}
 
+impl<'a, D: ?Sized> MappingImpl<'a, D> {
+    /// Flushes the given byte ranges of the mapping, relative to its start. The whole mapping
+    /// will no longer be flushed when it is destroyed.
+    ///
+    /// Does nothing for temporary buffers, as they are entirely flushed and copied to the real
+    /// buffer when the mapping is destroyed.
+    unsafe fn flush_ranges(&mut self, ranges: &[Range<usize>]) {
+        match self {
+            &mut MappingImpl::PersistentMapping { buffer, offset_bytes,
+                                                  ref mut needs_flushing, .. } =>
+            {
+                let mut ctxt = buffer.context.make_current();
+                for range in ranges {
+                    flush_range(&mut ctxt, buffer.id, buffer.ty,
+                                offset_bytes + range.start .. offset_bytes + range.end);
+                }
+                *needs_flushing = false;
+            },
+
+            &mut MappingImpl::TemporaryBuffer { .. } => (),
+
+            &mut MappingImpl::RegularMapping { ref buffer, ref mut needs_flushing, .. } => {
+                let mut ctxt = buffer.context.make_current();
+                for range in ranges {
+                    flush_range(&mut ctxt, buffer.id, buffer.ty, range.clone());
+                }
+                *needs_flushing = false;
+            },
+        }
+    }
+}
+
 impl<'a, D: ?Sized> Drop for MappingImpl<'a, D> {
     fn drop(&mut self) {
         match self {
@@ -1028,11 +1078,46 @@ impl<'a, D: ?Sized> Deref for ReadMapping<'a, D> where D: Content {
 }
 
 /// A mapping of a buffer for write only.
+///
+/// By default the whole mapping is flushed when it is destroyed. If you call `mark_dirty`,
+/// only the ranges that have been marked as dirty are flushed instead.
 pub struct WriteMapping<'b, D: ?Sized> where D: Content {
     mapping: MappingImpl<'b, D>,
+    // byte ranges that need to be flushed, or `None` if the whole mapping must be flushed
+    dirty: Option<Vec<Range<usize>>>,
 }
 
 impl<'b, D: ?Sized> WriteMapping<'b, D> where D: Content {
+    /// Flushes the ranges that have been marked as dirty with `mark_dirty`.
+    ///
+    /// Overlapping and adjacent ranges are merged together, so that the number of calls to
+    /// `glFlushMappedBufferRange` is minimal. Does nothing if `mark_dirty` has never been called,
+    /// in which case the whole mapping is flushed when it is destroyed.
+    pub fn flush(&mut self) {
+        let mut ranges = match self.dirty {
+            Some(ref mut dirty) => mem::replace(dirty, Vec::new()),
+            None => return,
+        };
+
+        ranges.sort_by(|a, b| a.start.cmp(&b.start));
+
+        let mut coalesced: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            if let Some(last) = coalesced.last_mut() {
+                if range.start <= last.end {
+                    if range.end > last.end {
+                        last.end = range.end;
+                    }
+                    continue;
+                }
+            }
+
+            coalesced.push(range);
+        }
+
+        unsafe { self.mapping.flush_ranges(&coalesced); }
+    }
+
     #[inline]
     fn get_slice(&mut self) -> &mut D {
         match self.mapping {
@@ -1082,6 +1167,38 @@ impl<'b, D> WriteMapping<'b, [D]> where [D]: Content, D: Copy {
         let slice = self.get_slice();
         slice[index] = value;
     }
+
+    /// Marks a range of elements as modified.
+    ///
+    /// After this function has been called once, only the ranges that have been marked as dirty
+    /// are flushed, either when calling `flush` or when the mapping is destroyed. This avoids
+    /// flushing a large buffer when only a few elements have changed. Elements that you modify
+    /// without marking them as dirty may not be visible to the GPU.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range is out of the mapping.
+    ///
+    pub fn mark_dirty(&mut self, range: Range<usize>) {
+        assert!(range.start <= range.end && range.end <= self.len(),
+                "The dirty range is out of the mapping");
+
+        if self.dirty.is_none() {
+            self.dirty = Some(Vec::new());
+        }
+
+        if range.start != range.end {
+            let size = mem::size_of::<D>();
+            self.dirty.as_mut().unwrap().push(range.start * size .. range.end * size);
+        }
+    }
+}
+
+impl<'b, D: ?Sized> Drop for WriteMapping<'b, D> where D: Content {
+    #[inline]
+    fn drop(&mut self) {
+        self.flush();
+    }
 }
 
 /// Returns true if reading from a buffer is supported by the backend.
@@ -1095,6 +1212,9 @@ pub fn is_buffer_read_supported<C: ?Sized>(ctxt: &C) -> bool where C: Capabiliti
     } else if ctxt.get_extensions().gl_arb_vertex_buffer_object {
         true
 
+    } else if ctxt.get_version() >= &Version(Api::GlEs, 3, 0) {
+        true
+
     } else if ctxt.get_version() >= &Version(Api::GlEs, 1, 0) {
         false
 
@@ -1150,6 +1270,11 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
         ptr::null()
     };
 
+    // the data is uploaded when the storage of the buffer is created
+    if data.is_some() {
+        ctxt.state.pending_uploads = true;
+    }
+
     // if the `size` is 0 bytes then we use 1 instead, otherwise nvidia drivers complain
     // note that according to glium the size of the buffer will remain 0
     let size = match size {
@@ -1161,12 +1286,14 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
     let mutable_storage_flags = match mode {
         BufferMode::Persistent | BufferMode::Dynamic => gl::DYNAMIC_DRAW,
         BufferMode::Default | BufferMode::Immutable => gl::STATIC_DRAW,
+        BufferMode::Stream => gl::STREAM_DRAW,
     };
 
     // the flags to use if `glBufferStorage` is supported
     let immutable_storage_flags = match mode {
         BufferMode::Default => gl::DYNAMIC_STORAGE_BIT | gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
         BufferMode::Dynamic => gl::DYNAMIC_STORAGE_BIT | gl::CLIENT_STORAGE_BIT | gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
+        BufferMode::Stream => gl::DYNAMIC_STORAGE_BIT | gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
         BufferMode::Persistent => gl::MAP_PERSISTENT_BIT | gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
         BufferMode::Immutable => 0,
     };
@@ -1174,7 +1301,7 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
     // if true, there is a possibility that the buffer won't be modifiable with regular OpenGL
     // function calls
     let could_be_immutable = match mode {
-        BufferMode::Default | BufferMode::Dynamic => false,
+        BufferMode::Default | BufferMode::Dynamic | BufferMode::Stream => false,
         BufferMode::Immutable | BufferMode::Persistent => true,
     };
 
@@ -1262,6 +1389,8 @@ unsafe fn create_buffer<D: ?Sized>(mut ctxt: &mut CommandContext, size: usize, d
         return Err(BufferCreationError::OutOfMemory);
     }
 
+    ctxt.resource_created(ResourceKind::Buffer, id);
+
     let persistent_mapping = if let BufferMode::Persistent = mode {
         if immutable {
             let ptr = if ctxt.version >= &Version(Api::Gl, 4, 5) {
@@ -1669,6 +1798,8 @@ unsafe fn destroy_buffer(mut ctxt: &mut CommandContext, id: gl::types::GLuint) {
         }
     }
 
+    ctxt.resource_destroyed(ResourceKind::Buffer, id);
+
     if ctxt.version >= &Version(Api::Gl, 1, 5) ||
         ctxt.version >= &Version(Api::GlEs, 2, 0)
     {
@@ -232,6 +232,20 @@ pub enum BufferMode {
     ///
     Dynamic,
 
+    /// The mode to use when you replace the whole content of a buffer every frame and use it
+    /// only a few times, for example vertices generated on the CPU for particles or text.
+    ///
+    /// Contrary to `Persistent`, the buffer is not mapped and the data is uploaded with regular
+    /// function calls.
+    ///
+    /// # Implementation
+    ///
+    /// Tries to use `glBufferStorage` with the `GL_DYNAMIC_STORAGE_BIT` flag.
+    ///
+    /// If this function is not available, falls back to `glBufferData` with `GL_STREAM_DRAW`.
+    ///
+    Stream,
+
     /// Optimized for when you modify a buffer exactly once per frame. You can modify it more than
     /// once per frame, but if you modify it too often things will slow down.
     ///
@@ -13,7 +13,10 @@ use BufferSliceExt;
 use GlObject;
 
 use context::Context;
+use context::BindingPointsExhaustedError;
 use context::CommandContext;
+use context::LabeledObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use std::rc::Rc;
 use ContextExt;
 
@@ -48,6 +51,14 @@ impl<T: ?Sized> GlObject for Buffer<T> where T: Content {
     }
 }
 
+impl<T: ?Sized> Labeled for Buffer<T> where T: Content {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.get_context().make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::BUFFER, self.get_id()), label)
+    }
+}
+
 impl<T: ?Sized> Buffer<T> where T: Content {
     /// Builds a new buffer containing the given data. The size of the buffer is equal to the size
     /// of the data.
@@ -99,6 +110,23 @@ impl<T: ?Sized> Buffer<T> where T: Content {
         self.alloc.as_ref().unwrap().uses_persistent_mapping()
     }
 
+    /// Binds this buffer to the binding point that the context's `BindingPointRegistry` assigns
+    /// to the uniform block named `name`. Programs on which `bind_shared_uniform_blocks` has been
+    /// called then read this block from this buffer.
+    ///
+    /// This should be called once per frame, as binding another buffer to the same point or
+    /// writing to the buffer from a shader requires binding it again.
+    pub fn bind_to_shared_uniform_block(&self, name: &str)
+                                        -> Result<(), BindingPointsExhaustedError>
+    {
+        let alloc = self.alloc.as_ref().unwrap();
+        let point = try!(alloc.get_context().get_binding_point_registry().get_binding_point(name));
+
+        let mut ctxt = alloc.get_context().make_current();
+        alloc.prepare_and_bind_for_uniform(&mut ctxt, point, 0 .. alloc.get_size());
+        Ok(())
+    }
+
     /// Uploads some data in this buffer.
     ///
     /// # Implementation
@@ -140,6 +168,18 @@ impl<T: ?Sized> Buffer<T> where T: Content {
     }
 
     /// Reads the content of the buffer.
+    ///
+    /// To read only a part of the buffer, call `read` on a slice of it.
+    ///
+    /// This function blocks until the GPU has finished all the operations that write to the
+    /// buffer, then copies the data. If you read a buffer every frame, for example the output of
+    /// transform feedback or of a compute shader, prefer copying it to another buffer, creating a
+    /// `SyncFence`, and only reading the copy once the fence has been signaled.
+    ///
+    /// # Implementation
+    ///
+    /// Calls `glGetBufferSubData`, or maps the buffer on OpenGL ES where this function doesn't
+    /// exist. Returns `ReadError::NotSupported` on OpenGL ES 2.
     pub fn read(&self) -> Result<T::Owned, ReadError> {
         self.fence.as_ref().unwrap().wait(&mut self.alloc.as_ref().unwrap().get_context().make_current(),
                                           0 .. self.get_size());
@@ -167,6 +207,17 @@ impl<T: ?Sized> Buffer<T> where T: Content {
         unsafe { self.alloc.as_mut().unwrap().map(0 .. size) }
     }
 
+    /// Maps the buffer in memory for both reading and writing.
+    ///
+    /// This is the same as `map`. Prefer `map_read` or `map_write` when you only need one of the
+    /// two, as a read-write mapping may force the implementation to download the content of the
+    /// buffer even if you only write to it.
+    ///
+    #[inline]
+    pub fn map_read_write(&mut self) -> Mapping<T> {
+        self.map()
+    }
+
     /// Maps the buffer in memory for reading.
     ///
     /// # Implementation
@@ -175,7 +226,7 @@ impl<T: ?Sized> Buffer<T> where T: Content {
     ///   returns a pointer to the existing mapping.
     /// - For immutable buffers, creates a temporary buffer containing the data of the buffer and
     ///   maps it.
-    /// - For other types, calls `glMapBuffer` or `glMapSubBuffer`.
+    /// - For other types, calls `glMapBufferRange` with `GL_MAP_READ_BIT`.
     ///
     pub fn map_read(&mut self) -> ReadMapping<T> {
         self.fence.as_ref().unwrap().wait(&mut self.alloc.as_ref().unwrap().get_context().make_current(),
@@ -192,8 +243,11 @@ impl<T: ?Sized> Buffer<T> where T: Content {
     ///   returns a pointer to the existing mapping.
     /// - For immutable buffers, creates a temporary buffer and
     ///   maps it. When the mapping object is destroyed, copies the content of the temporary buffer
-    ///   to the real buffer.
-    /// - For other types, calls `glMapBuffer` or `glMapSubBuffer`.
+    ///   to the real buffer. The existing content is never copied to the temporary buffer.
+    /// - For other types, calls `glMapBufferRange` with `GL_MAP_WRITE_BIT` only.
+    ///
+    /// The initial content of the mapping is undefined. You should write every element of the
+    /// mapping, as elements that are left untouched can end up with undefined values.
     ///
     pub fn map_write(&mut self) -> WriteMapping<T> {
         self.fence.as_ref().unwrap().wait(&mut self.alloc.as_ref().unwrap().get_context().make_current(),
@@ -360,6 +414,27 @@ impl<T> Buffer<[T]> where [T]: Content, T: Copy {
         self.alloc.as_ref().unwrap().get_size() / mem::size_of::<T>()
     }
 
+    /// Writes the elements produced by an iterator in this buffer, without building an
+    /// intermediate `Vec`.
+    ///
+    /// The buffer is mapped for writing only, see `map_write`, and the elements are written
+    /// directly in the mapping.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the length of the iterator is different from the length of this buffer.
+    pub fn write_from_iter<I>(&mut self, iter: I)
+                              where I: IntoIterator<Item = T>, I::IntoIter: ExactSizeIterator
+    {
+        let iter = iter.into_iter();
+        assert_eq!(iter.len(), self.len());
+
+        let mut mapping = self.map_write();
+        for (index, value) in iter.enumerate() {
+            mapping.set(index, value);
+        }
+    }
+
     /// Builds a slice of this subbuffer. Returns `None` if out of range.
     ///
     /// This method builds an object that represents a slice of the buffer. No actual operation
@@ -369,6 +444,13 @@ impl<T> Buffer<[T]> where [T]: Content, T: Copy {
         self.as_slice().slice(range)
     }
 
+    /// Builds a slice containing only the element at the given index. Returns `None` if out of
+    /// range.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<BufferSlice<T>> {
+        self.as_slice().get(index)
+    }
+
     /// Builds a slice of this subbuffer. Returns `None` if out of range.
     ///
     /// This method builds an object that represents a slice of the buffer. No actual operation
@@ -551,6 +633,8 @@ impl<'a, T: ?Sized> BufferSlice<'a, T> where T: Content + 'a {
     }
 
     /// Reads the content of the buffer.
+    ///
+    /// See `Buffer::read` for more details.
     pub fn read(&self) -> Result<T::Owned, ReadError> {
         self.fence.wait(&mut self.alloc.get_context().make_current(),
                         self.bytes_start .. self.bytes_end);
@@ -676,6 +760,27 @@ impl<'a, T> BufferSlice<'a, [T]> where [T]: Content + 'a {
     }
 }
 
+impl<'a, T> BufferSlice<'a, [T]> where T: Copy + 'a {
+    /// Builds a slice containing only the element at the given index. Returns `None` if out of
+    /// range.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<BufferSlice<'a, T>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let start = self.bytes_start + index * mem::size_of::<T>();
+
+        Some(BufferSlice {
+            alloc: self.alloc,
+            bytes_start: start,
+            bytes_end: start + mem::size_of::<T>(),
+            fence: self.fence,
+            marker: PhantomData,
+        })
+    }
+}
+
 impl<'a, T> BufferSlice<'a, [T]> where T: PixelValue + 'a {
     /// Reads the content of the buffer.
     #[inline]
@@ -793,7 +898,7 @@ impl<'a, T: ?Sized> BufferExt for BufferSlice<'a, T> where T: Content {
 
     #[inline]
     fn prepare_and_bind_for_uniform(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_uniform(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_uniform(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
@@ -841,6 +946,17 @@ impl<'a, T: ?Sized> BufferMutSlice<'a, T> where T: Content + 'a {
         unsafe { self.alloc.map(self.bytes_start .. self.bytes_end) }
     }
 
+    /// Maps the buffer in memory for both reading and writing.
+    ///
+    /// This is the same as `map`. Prefer `map_read` or `map_write` when you only need one of the
+    /// two, as a read-write mapping may force the implementation to download the content of the
+    /// buffer even if you only write to it.
+    ///
+    #[inline]
+    pub fn map_read_write(self) -> Mapping<'a, T> {
+        self.map()
+    }
+
     /// Maps the buffer in memory for reading.
     ///
     /// # Implementation
@@ -849,7 +965,7 @@ impl<'a, T: ?Sized> BufferMutSlice<'a, T> where T: Content + 'a {
     ///   returns a pointer to the existing mapping.
     /// - For immutable buffers, creates a temporary buffer containing the data of the buffer and
     ///   maps it.
-    /// - For other types, calls `glMapBuffer` or `glMapSubBuffer`.
+    /// - For other types, calls `glMapBufferRange` with `GL_MAP_READ_BIT`.
     ///
     #[inline]
     pub fn map_read(self) -> ReadMapping<'a, T> {
@@ -865,8 +981,12 @@ impl<'a, T: ?Sized> BufferMutSlice<'a, T> where T: Content + 'a {
     /// - For persistent-mapped buffers, waits until the data is no longer accessed by the GPU then
     ///   returns a pointer to the existing mapping.
     /// - For immutable buffers, creates a temporary buffer and maps it. When the mapping object
-    ///   is destroyed, copies the content of the temporary buffer to the real buffer.
-    /// - For other types, calls `glMapBuffer` or `glMapSubBuffer`.
+    ///   is destroyed, copies the content of the temporary buffer to the real buffer. The
+    ///   existing content is never copied to the temporary buffer.
+    /// - For other types, calls `glMapBufferRange` with `GL_MAP_WRITE_BIT` only.
+    ///
+    /// The initial content of the mapping is undefined. You should write every element of the
+    /// mapping, as elements that are left untouched can end up with undefined values.
     ///
     #[inline]
     pub fn map_write(self) -> WriteMapping<'a, T> {
@@ -1079,6 +1199,26 @@ pub struct BufferAny {
 }
 
 impl BufferAny {
+    /// Builds a new buffer from raw bytes. The buffer is considered to contain elements of
+    /// `elements_size` bytes each.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `elements_size` is 0 or if the length of `data` is not a multiple of
+    /// `elements_size`.
+    pub fn new_raw<F: ?Sized>(facade: &F, data: &[u8], elements_size: usize, ty: BufferType,
+                              mode: BufferMode) -> Result<BufferAny, BufferCreationError>
+                              where F: Facade
+    {
+        assert!(elements_size != 0);
+        assert!(data.len() % elements_size == 0);
+
+        let buffer = try!(Buffer::new(facade, data, ty, mode));
+        let mut buffer: BufferAny = buffer.into();
+        buffer.elements_size = elements_size;
+        Ok(buffer)
+    }
+
     /// Builds a slice-any containing the whole subbuffer.
     #[inline]
     pub fn as_slice_any(&self) -> BufferAnySlice {
@@ -1157,6 +1297,14 @@ impl<T: ?Sized> From<Buffer<T>> for BufferAny where T: Content + Send + 'static
     }
 }
 
+impl Labeled for BufferAny {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.get_context().make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::BUFFER, self.alloc.get_id()), label)
+    }
+}
+
 impl Drop for BufferAny {
     #[inline]
     fn drop(&mut self) {
@@ -1385,7 +1533,7 @@ impl<'a> BufferExt for BufferAnySlice<'a> {
 
     #[inline]
     fn prepare_and_bind_for_uniform(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_uniform(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_uniform(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
@@ -0,0 +1,94 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::BuildHasherDefault;
+
+use fnv::FnvHasher;
+
+/// Assigns stable uniform buffer binding points to uniform block names. Obtained with
+/// `Context::get_binding_point_registry`.
+///
+/// When many programs share a uniform block, for example a `Camera` block containing the
+/// projection and view matrices, passing the same buffer as a uniform to each draw call is
+/// redundant. Instead you can call `Program::bind_shared_uniform_blocks` once after creating
+/// each program, and `Buffer::bind_to_shared_uniform_block` once per frame. All the programs
+/// then read the block from the same binding point, whatever the order in which they have been
+/// created.
+///
+/// The binding points are assigned starting from the highest one, while the uniform buffers
+/// passed to draw calls use the lowest ones. A block that is passed as a uniform to a draw call
+/// uses the buffer passed to that draw call instead of the shared one.
+pub struct BindingPointRegistry {
+    /// Value of `GL_MAX_UNIFORM_BUFFER_BINDINGS`.
+    max_bindings: u32,
+
+    /// The binding point assigned to each block name.
+    points: RefCell<HashMap<String, u32, BuildHasherDefault<FnvHasher>>>,
+}
+
+/// Builds a new registry where no binding point is assigned.
+#[inline]
+pub fn new(max_bindings: u32) -> BindingPointRegistry {
+    BindingPointRegistry {
+        max_bindings: max_bindings,
+        points: RefCell::new(HashMap::with_hasher(Default::default())),
+    }
+}
+
+impl BindingPointRegistry {
+    /// Returns the binding point of the uniform block with the given name, assigning a new one
+    /// if the name has never been seen.
+    ///
+    /// Returns an error if all the binding points are used, or if uniform buffers are not
+    /// supported by the backend.
+    pub fn get_binding_point(&self, name: &str) -> Result<u32, BindingPointsExhaustedError> {
+        let mut points = self.points.borrow_mut();
+
+        if let Some(&point) = points.get(name) {
+            return Ok(point);
+        }
+
+        let num_used = points.len() as u32;
+        if num_used >= self.max_bindings {
+            return Err(BindingPointsExhaustedError);
+        }
+
+        let point = self.max_bindings - 1 - num_used;
+        points.insert(name.to_owned(), point);
+        Ok(point)
+    }
+
+    /// Returns the binding point of the uniform block with the given name, or `None` if it hasn't
+    /// been assigned one.
+    #[inline]
+    pub fn get_assigned_binding_point(&self, name: &str) -> Option<u32> {
+        self.points.borrow().get(name).cloned()
+    }
+}
+
+impl fmt::Debug for BindingPointRegistry {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "BindingPointRegistry({:?})", *self.points.borrow())
+    }
+}
+
+/// Error that can happen when assigning a binding point to a uniform block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BindingPointsExhaustedError;
+
+impl fmt::Display for BindingPointsExhaustedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for BindingPointsExhaustedError {
+    #[inline]
+    fn description(&self) -> &str {
+        "All the uniform buffer binding points are already assigned, or uniform buffers are not \
+         supported by the backend"
+    }
+}
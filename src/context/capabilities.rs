@@ -17,7 +17,7 @@ use CapabilitiesSource;
 use image_format::TextureFormat;
 
 /// Describes the OpenGL context profile.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Profile {
     /// The context uses only future-compatible functions and definitions.
     Core,
@@ -91,9 +91,16 @@ pub struct Capabilities {
     /// `glActiveTexture` must be between `GL_TEXTURE0` and `GL_TEXTURE0` + this value - 1.
     pub max_combined_texture_image_units: gl::types::GLint,
 
+    /// Maximum number of textures that can be bound to image units at the same time.
+    ///
+    /// `0` if image units are not supported.
+    pub max_image_units: gl::types::GLint,
+
     /// Maximum value for `GL_TEXTURE_MAX_ANISOTROPY_EXT​`.
     ///
-    /// `None` if the extension is not supported by the hardware.
+    /// `None` if anisotropic filtering is not supported by the hardware, ie. if neither OpenGL
+    /// 4.6, `GL_ARB_texture_filter_anisotropic` nor `GL_EXT_texture_filter_anisotropic` are
+    /// available.
     pub max_texture_max_anisotropy: Option<gl::types::GLfloat>,
 
     /// Maximum size of a buffer texture. `None` if this is not supported.
@@ -102,9 +109,17 @@ pub struct Capabilities {
     /// Maximum width and height of `glViewport`.
     pub max_viewport_dims: (gl::types::GLint, gl::types::GLint),
 
+    /// Minimum and maximum diameter of points. The `point_size` draw parameter and the values
+    /// written to `gl_PointSize` are clamped to this range.
+    pub point_size_range: (gl::types::GLfloat, gl::types::GLfloat),
+
     /// Maximum number of elements that can be passed with `glDrawBuffers`.
     pub max_draw_buffers: gl::types::GLint,
 
+    /// Maximum number of vertex attributes of a program. Attribute locations must be inferior
+    /// to this value.
+    pub max_vertex_attribs: gl::types::GLint,
+
     /// Maximum number of vertices per patch. `None` if tessellation is not supported.
     pub max_patch_vertices: Option<gl::types::GLint>,
 
@@ -120,6 +135,19 @@ pub struct Capabilities {
     /// Number of available buffer bind points for `GL_UNIFORM_BUFFER`.
     pub max_indexed_uniform_buffer: gl::types::GLint,
 
+    /// Maximum total number of shader storage buffers, image units and fragment shader outputs
+    /// that can be used at the same time by all the stages of a program.
+    ///
+    /// `0` if shader storage buffers are not supported.
+    pub max_combined_shader_output_resources: gl::types::GLint,
+
+    /// Required alignment in bytes of the offset passed to `glBindBufferRange` for
+    /// `GL_UNIFORM_BUFFER`. `None` if uniform buffers are not supported.
+    pub uniform_buffer_offset_alignment: Option<gl::types::GLint>,
+
+    /// Maximum size in bytes of a uniform block. `None` if uniform buffers are not supported.
+    pub max_uniform_block_size: Option<gl::types::GLint>,
+
     /// Number of work groups for compute shaders.
     pub max_compute_work_group_count: (gl::types::GLint, gl::types::GLint, gl::types::GLint),
 
@@ -137,6 +165,10 @@ pub struct Capabilities {
 
     /// Maximum samples of an empty framebuffer. `None` if not supported.
     pub max_framebuffer_samples: Option<gl::types::GLint>,
+
+    /// Maximum number of samples of a multisample texture or renderbuffer. `None` if
+    /// multisampling is not supported.
+    pub max_samples: Option<gl::types::GLint>,
 }
 
 /// Information about an internal format.
@@ -146,6 +178,56 @@ pub struct FormatInfos {
     pub multisamples: Option<Vec<gl::types::GLint>>,
 }
 
+/// Detailed information about the support of an internal format, as returned by
+/// `Context::format_support`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatSupport {
+    /// True if the format can be used to create a texture at all.
+    pub supported: bool,
+
+    /// Whether the format can be attached to a framebuffer and rendered to.
+    pub framebuffer_renderable: FormatSupportLevel,
+
+    /// Whether blending is supported when rendering to this format.
+    pub framebuffer_blend: FormatSupportLevel,
+
+    /// True if linear filtering can be used when sampling from this format.
+    pub filterable: bool,
+
+    /// Number of samples supported for multisampling, in descending order. Empty if
+    /// multisampling is not supported.
+    pub samples: Vec<u32>,
+
+    /// The format and type (for example `GL_RGBA` and `GL_UNSIGNED_BYTE`) that the
+    /// implementation prefers for uploading data. Using them avoids a conversion by the driver.
+    /// `None` if the implementation didn't tell.
+    pub preferred_upload_format: Option<(gl::types::GLenum, gl::types::GLenum)>,
+}
+
+/// Level of support of an operation on an internal format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FormatSupportLevel {
+    /// The operation is not supported.
+    None,
+
+    /// The operation is supported but with a caveat, such as a reduced performance.
+    Caveat,
+
+    /// The operation is fully supported.
+    Full,
+}
+
+impl FormatSupportLevel {
+    #[inline]
+    fn from_glenum(value: gl::types::GLint) -> FormatSupportLevel {
+        match value as gl::types::GLenum {
+            gl::FULL_SUPPORT => FormatSupportLevel::Full,
+            gl::CAVEAT_SUPPORT => FormatSupportLevel::Caveat,
+            _ => FormatSupportLevel::None,
+        }
+    }
+}
+
 /// Defines what happens when you change the current context.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ReleaseBehavior {
@@ -397,7 +479,21 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             val
         },
 
-        max_texture_max_anisotropy: if !extensions.gl_ext_texture_filter_anisotropic {
+        max_image_units: if version >= &Version(Api::Gl, 4, 2) ||
+                            version >= &Version(Api::GlEs, 3, 1) ||
+                            extensions.gl_arb_shader_image_load_store
+        {
+            let mut val = 0;
+            gl.GetIntegerv(gl::MAX_IMAGE_UNITS, &mut val);
+            val
+        } else {
+            0
+        },
+
+        max_texture_max_anisotropy: if !(version >= &Version(Api::Gl, 4, 6)) &&
+                                       !extensions.gl_arb_texture_filter_anisotropic &&
+                                       !extensions.gl_ext_texture_filter_anisotropic
+        {
             None
 
         } else {
@@ -430,6 +526,28 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             (val[0], val[1])
         },
 
+        point_size_range: {
+            // `GL_ALIASED_POINT_SIZE_RANGE` has been removed from core profiles, where
+            // `GL_POINT_SIZE_RANGE` is the only range
+            let core = if version >= &Version(Api::Gl, 3, 2) {
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::CONTEXT_PROFILE_MASK, &mut val);
+                (val as gl::types::GLenum & gl::CONTEXT_CORE_PROFILE_BIT) != 0
+            } else {
+                false
+            };
+
+            let mut val: [gl::types::GLfloat; 2] = [ 1.0, 1.0 ];
+            if version >= &Version(Api::GlEs, 2, 0) ||
+               (version >= &Version(Api::Gl, 1, 2) && !core)
+            {
+                gl.GetFloatv(gl::ALIASED_POINT_SIZE_RANGE, val.as_mut_ptr());
+            } else {
+                gl.GetFloatv(gl::POINT_SIZE_RANGE, val.as_mut_ptr());
+            }
+            (val[0], val[1])
+        },
+
         max_draw_buffers: {
             if version >= &Version(Api::Gl, 2, 0) ||
                 version >= &Version(Api::GlEs, 3, 0) ||
@@ -443,6 +561,19 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        max_vertex_attribs: {
+            if version >= &Version(Api::Gl, 2, 0) ||
+                version >= &Version(Api::GlEs, 2, 0) ||
+                extensions.gl_arb_vertex_shader
+            {
+                let mut val = 0;
+                gl.GetIntegerv(gl::MAX_VERTEX_ATTRIBS, &mut val);
+                val
+            } else {
+                0
+            }
+        },
+
         max_patch_vertices: if version >= &Version(Api::Gl, 4, 0) ||
             extensions.gl_arb_tessellation_shader
         {
@@ -498,6 +629,42 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        max_combined_shader_output_resources: {
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_storage_buffer_object
+            {
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::MAX_COMBINED_SHADER_OUTPUT_RESOURCES, &mut val);
+                val
+            } else {
+                0
+            }
+        },
+
+        uniform_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_uniform_buffer_object
+            {
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                Some(val)
+            } else {
+                None
+            }
+        },
+
+        max_uniform_block_size: {
+            if version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_uniform_buffer_object
+            {
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::MAX_UNIFORM_BLOCK_SIZE, &mut val);
+                Some(val)
+            } else {
+                None
+            }
+        },
+
         max_compute_work_group_count: if version >= &Version(Api::Gl, 4, 3) ||
                                          version >= &Version(Api::GlEs, 3, 1) ||
                                          extensions.gl_arb_compute_shader
@@ -582,6 +749,19 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        max_samples: {
+            if version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_framebuffer_object || extensions.gl_ext_framebuffer_multisample
+            {
+                let mut val = 0;
+                gl.GetIntegerv(gl::MAX_SAMPLES, &mut val);
+                Some(val)
+
+            } else {
+                None
+            }
+        },
+
         renderer: renderer,
     }
 }
@@ -747,3 +927,61 @@ pub fn get_internal_format(gl: &gl::Gl, version: &Version, extensions: &Extensio
         }
     }
 }
+
+/// Queries detailed informations about an internal format with `glGetInternalformativ`.
+///
+/// Returns `None` if neither OpenGL 4.3 nor `GL_ARB_internalformat_query2` is available.
+pub fn get_format_support(gl: &gl::Gl, version: &Version, extensions: &ExtensionsList,
+                          format: TextureFormat) -> Option<FormatSupport>
+{
+    if !(version >= &Version(Api::Gl, 4, 3) || extensions.gl_arb_internalformat_query2) {
+        return None;
+    }
+
+    let internal_format = format.to_glenum();
+
+    let query = |pname: gl::types::GLenum| -> gl::types::GLint {
+        unsafe {
+            let mut value = 0;
+            gl.GetInternalformativ(gl::TEXTURE_2D, internal_format, pname, 1, &mut value);
+            value
+        }
+    };
+
+    let supported = query(gl::INTERNALFORMAT_SUPPORTED) == gl::TRUE as gl::types::GLint;
+
+    let samples = unsafe {
+        let mut num = 0;
+        gl.GetInternalformativ(gl::TEXTURE_2D_MULTISAMPLE, internal_format,
+                               gl::NUM_SAMPLE_COUNTS, 1, &mut num);
+
+        if num >= 1 {
+            let mut samples = vec![0; num as usize];
+            gl.GetInternalformativ(gl::TEXTURE_2D_MULTISAMPLE, internal_format, gl::SAMPLES,
+                                   num, samples.as_mut_ptr());
+            samples.into_iter().map(|s| s as u32).collect()
+        } else {
+            Vec::new()
+        }
+    };
+
+    let preferred_upload_format = {
+        let format = query(gl::TEXTURE_IMAGE_FORMAT);
+        let ty = query(gl::TEXTURE_IMAGE_TYPE);
+
+        if format != 0 && ty != 0 {
+            Some((format as gl::types::GLenum, ty as gl::types::GLenum))
+        } else {
+            None
+        }
+    };
+
+    Some(FormatSupport {
+        supported: supported,
+        framebuffer_renderable: FormatSupportLevel::from_glenum(query(gl::FRAMEBUFFER_RENDERABLE)),
+        framebuffer_blend: FormatSupportLevel::from_glenum(query(gl::FRAMEBUFFER_BLEND)),
+        filterable: query(gl::FILTER) == gl::TRUE as gl::types::GLint,
+        samples: samples,
+        preferred_upload_format: preferred_upload_format,
+    })
+}
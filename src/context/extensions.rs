@@ -55,9 +55,13 @@ extensions! {
     "GL_APPLE_sync" => gl_apple_sync,
     "GL_APPLE_vertex_array_object" => gl_apple_vertex_array_object,
     "GL_ARB_bindless_texture" => gl_arb_bindless_texture,
+    "GL_ARB_blend_func_extended" => gl_arb_blend_func_extended,
     "GL_ARB_buffer_storage" => gl_arb_buffer_storage,
+    "GL_ARB_clear_texture" => gl_arb_clear_texture,
+    "GL_ARB_color_buffer_float" => gl_arb_color_buffer_float,
     "GL_ARB_compute_shader" => gl_arb_compute_shader,
     "GL_ARB_copy_buffer" => gl_arb_copy_buffer,
+    "GL_ARB_copy_image" => gl_arb_copy_image,
     "GL_ARB_debug_output" => gl_arb_debug_output,
     "GL_ARB_depth_clamp" => gl_arb_depth_clamp,
     "GL_ARB_depth_texture" => gl_arb_depth_texture,
@@ -75,10 +79,12 @@ extensions! {
     "GL_ARB_framebuffer_sRGB" => gl_arb_framebuffer_srgb,
     "GL_ARB_geometry_shader4" => gl_arb_geometry_shader4,
     "GL_ARB_get_program_binary" => gl_arb_get_programy_binary,
+    "GL_ARB_gl_spirv" => gl_arb_gl_spirv,
     "GL_ARB_gpu_shader_fp64" => gl_arb_gpu_shader_fp64,
     "GL_ARB_gpu_shader_int64" => gl_arb_gpu_shader_int64,
     "GL_ARB_instanced_arrays" => gl_arb_instanced_arrays,
     "GL_ARB_internalformat_query" => gl_arb_internalformat_query,
+    "GL_ARB_internalformat_query2" => gl_arb_internalformat_query2,
     "GL_ARB_invalidate_subdata" => gl_arb_invalidate_subdata,
     "GL_ARB_occlusion_query" => gl_arb_occlusion_query,
     "GL_ARB_occlusion_query2" => gl_arb_occlusion_query2,
@@ -91,37 +97,48 @@ extensions! {
     "GL_ARB_robustness" => gl_arb_robustness,
     "GL_ARB_robust_buffer_access_behavior" => gl_arb_robust_buffer_access_behavior,
     "GL_ARB_sampler_objects" => gl_arb_sampler_objects,
+    "GL_ARB_shader_draw_parameters" => gl_arb_shader_draw_parameters,
     "GL_ARB_shader_image_load_store" => gl_arb_shader_image_load_store,
     "GL_ARB_shader_objects" => gl_arb_shader_objects,
     "GL_ARB_shader_storage_buffer_object" => gl_arb_shader_storage_buffer_object,
     "GL_ARB_shader_subroutine" => gl_arb_shader_subroutine,
+    "GL_ARB_sparse_texture" => gl_arb_sparse_texture,
+    "GL_ARB_stencil_texturing" => gl_arb_stencil_texturing,
     "GL_ARB_sync" => gl_arb_sync,
     "GL_ARB_tessellation_shader" => gl_arb_tessellation_shader,
     "GL_ARB_texture_buffer_object" => gl_arb_texture_buffer_object,
     "GL_ARB_texture_buffer_object_rgb32" => gl_arb_texture_buffer_object_rgb32,
     "GL_ARB_texture_compression_bptc" => gl_arb_texture_compression_bptc,
+    "GL_ARB_texture_barrier" => gl_arb_texture_barrier,
     "GL_ARB_texture_cube_map" => gl_arb_texture_cube_map,
     "GL_ARB_texture_cube_map_array" => gl_arb_texture_cube_map_array,
+    "GL_ARB_texture_filter_anisotropic" => gl_arb_texture_filter_anisotropic,
+    "GL_ARB_texture_filter_minmax" => gl_arb_texture_filter_minmax,
     "GL_ARB_texture_float" => gl_arb_texture_float,
+    "GL_ARB_texture_mirror_clamp_to_edge" => gl_arb_texture_mirror_clamp_to_edge,
     "GL_ARB_texture_multisample" => gl_arb_texture_multisample,
     "GL_ARB_texture_non_power_of_two" => gl_arb_texture_non_power_of_two,
     "GL_ARB_texture_rg" => gl_arb_texture_rg,
     "GL_ARB_texture_rgb10_a2ui" => gl_arb_texture_rgb10_a2ui,
     "GL_ARB_texture_stencil8" => gl_arb_texture_stencil8,
     "GL_ARB_texture_storage" => gl_arb_texture_storage,
+    "GL_ARB_texture_swizzle" => gl_arb_texture_swizzle,
     "GL_ARB_timer_query" => gl_arb_timer_query,
     "GL_ARB_transform_feedback3" => gl_arb_transform_feedback3,
     "GL_ARB_uniform_buffer_object" => gl_arb_uniform_buffer_object,
     "GL_ARB_vertex_array_object" => gl_arb_vertex_array_object,
+    "GL_ARB_vertex_attrib_binding" => gl_arb_vertex_attrib_binding,
     "GL_ARB_vertex_buffer_object" => gl_arb_vertex_buffer_object,
     "GL_ARB_vertex_half_float" => gl_arb_vertex_half_float,
     "GL_ARB_vertex_shader" => gl_arb_vertex_shader,
     "GL_ARB_vertex_type_10f_11f_11f_rev" => gl_arb_vertex_type_10f_11f_11f_rev,
     "GL_ARB_vertex_type_2_10_10_10_rev" => gl_arb_vertex_type_2_10_10_10_rev,
+    "GL_ARB_viewport_array" => gl_arb_viewport_array,
     "GL_ARM_rgba8" => gl_arm_rgba8,
     "GL_ATI_meminfo" => gl_ati_meminfo,
     "GL_ATI_draw_buffers" => gl_ati_draw_buffers,
     "GL_ATI_texture_float" => gl_ati_texture_float,
+    "GL_ATI_texture_mirror_once" => gl_ati_texture_mirror_once,
     "GL_EXT_blend_minmax" => gl_ext_blend_minmax,
     "GL_EXT_buffer_storage" => gl_ext_buffer_storage,
     "GL_EXT_debug_marker" => gl_ext_debug_marker,
@@ -152,27 +169,35 @@ extensions! {
     "GL_EXT_texture_cube_map_array" => gl_ext_texture_cube_map_array,
     "GL_EXT_texture_filter_anisotropic" => gl_ext_texture_filter_anisotropic,
     "GL_EXT_texture_integer" => gl_ext_texture_integer,
+    "GL_EXT_texture_mirror_clamp" => gl_ext_texture_mirror_clamp,
+    "GL_EXT_texture_mirror_clamp_to_edge" => gl_ext_texture_mirror_clamp_to_edge,
     "GL_EXT_texture_shared_exponent" => gl_ext_texture_shared_exponent,
     "GL_EXT_texture_snorm" => gl_ext_texture_snorm,
     "GL_EXT_texture_sRGB" => gl_ext_texture_srgb,
+    "GL_EXT_texture_swizzle" => gl_ext_texture_swizzle,
     "GL_EXT_transform_feedback" => gl_ext_transform_feedback,
     "GL_GREMEDY_string_marker" => gl_gremedy_string_marker,
+    "GL_INTEL_conservative_rasterization" => gl_intel_conservative_rasterization,
     "GL_KHR_debug" => gl_khr_debug,
     "GL_KHR_context_flush_control" => gl_khr_context_flush_control,
     "GL_KHR_robustness" => gl_khr_robustness,
     "GL_KHR_robust_buffer_access_behavior" => gl_khr_robust_buffer_access_behavior,
     "GL_NV_fbo_color_attachments" => gl_nv_fbo_color_attachments,
     "GL_NV_conditional_render" => gl_nv_conditional_render,
+    "GL_NV_conservative_raster" => gl_nv_conservative_raster,
+    "GL_NV_conservative_raster_dilate" => gl_nv_conservative_raster_dilate,
     "GL_NV_copy_buffer" => gl_nv_copy_buffer,
     "GL_NV_depth_clamp" => gl_nv_depth_clamp,
     "GL_NV_framebuffer_multisample" => gl_nv_framebuffer_multisample,
     "GL_NV_half_float" => gl_nv_half_float,
     "GL_NV_internalformat_sample_query" => gl_nv_internalformat_sample_query,
+    "GL_NV_mesh_shader" => gl_nv_mesh_shader,
     "GL_NV_pixel_buffer_object" => gl_nv_pixel_buffer_object,
     "GL_NV_read_depth" => gl_nv_read_depth,
     "GL_NV_read_stencil" => gl_nv_read_stencil,
     "GL_NV_read_depth_stencil" => gl_nv_read_depth_stencil,
     "GL_NV_texture_array" => gl_nv_texture_array,
+    "GL_NV_texture_barrier" => gl_nv_texture_barrier,
     "GL_NV_vertex_attrib_integer_64bit" => gl_nv_vertex_attrib_integer_64bit,
     "GL_NVX_gpu_memory_info" => gl_nvx_gpu_memory_info,
     "GL_OES_depth_texture" => gl_oes_depth_texture,
@@ -183,6 +208,7 @@ extensions! {
     "GL_OES_packed_depth_stencil" => gl_oes_packed_depth_stencil,
     "GL_OES_primitive_bounding_box" => gl_oes_primitive_bounding_box,
     "GL_OES_rgb8_rgba8" => gl_oes_rgb8_rgba8,
+    "GL_OES_standard_derivatives" => gl_oes_standard_derivatives,
     "GL_OES_stencil1" => gl_oes_stencil1,
     "GL_OES_stencil4" => gl_oes_stencil4,
     "GL_OES_tessellation_shader" => gl_oes_tessellation_shader,
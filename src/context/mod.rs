@@ -5,6 +5,10 @@ use gl;
 use backtrace;
 
 use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
 use std::mem;
 use std::ptr;
 use std::str;
@@ -15,6 +19,8 @@ use std::ffi::CStr;
 use std::rc::Rc;
 use std::os::raw;
 use std::hash::BuildHasherDefault;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use fnv::FnvHasher;
 
@@ -22,33 +28,60 @@ use GliumCreationError;
 use SwapBuffersError;
 use CapabilitiesSource;
 use ContextExt;
+use GlObject;
+use TextureExt;
+use ToGlEnum;
 use backend::Backend;
 use version;
 use version::Api;
 use version::Version;
 
 use debug;
+use draw_parameters::Smooth;
 use fbo;
+use image_format::TextureFormat;
+use framebuffer::FramebufferBinding;
 use ops;
+use sync;
 use sampler_object;
 use texture;
 use uniforms;
 use vertex_array_object;
 
+pub use self::binding_points::{BindingPointRegistry, BindingPointsExhaustedError};
 pub use self::capabilities::{ReleaseBehavior, Capabilities, Profile};
+pub use self::requirements::{ContextRequirements, RequirementsNotMetError};
+pub use self::capabilities::{FormatSupport, FormatSupportLevel};
+pub use self::render_state::RenderStateGuard;
+pub use self::snapshot::GlStateSnapshot;
 pub use self::extensions::ExtensionsList;
 pub use self::state::GlState;
 
+mod binding_points;
 mod capabilities;
 mod extensions;
+mod render_state;
+mod requirements;
+mod snapshot;
 mod state;
 
+/// Id of the messages that glium inserts in the debug output with `DEBUG_SOURCE_THIRD_PARTY`.
+pub const GLIUM_DEBUG_MESSAGE_ID: gl::types::GLuint = 0x676c6d;
+
 /// Stores the state and information required for glium to execute commands. Most public glium
 /// functions require passing a `Rc<Context>`.
 pub struct Context {
     /// Contains the pointers to OpenGL functions.
     gl: gl::Gl,
 
+    /// Pointer to `glDrawMeshTasksNV`, which is missing from the generated bindings. `None` if
+    /// `GL_NV_mesh_shader` is not supported.
+    draw_mesh_tasks_nv: Option<DrawMeshTasksNvFn>,
+
+    /// Pointer to `glSpecializeShader`, which is missing from the generated bindings, or to
+    /// `glSpecializeShaderARB`. `None` if SPIR-V is not supported.
+    specialize_shader: Option<SpecializeShaderFn>,
+
     /// The current state of the OpenGL state machine. Contains for example which buffer is bound
     /// to which bind point, whether depth testing is activated, etc.
     state: RefCell<GlState>,
@@ -79,6 +112,25 @@ pub struct Context {
     /// be set to `false` in some situations, like compiling/linking shaders.
     report_debug_output_errors: Cell<bool>,
 
+    /// What to do with the errors returned by `glGetError`.
+    error_behavior: Cell<GlErrorBehavior>,
+
+    /// When to call `glGetError`, and the operations that have been performed since the last
+    /// time it has been called.
+    error_check: ErrorCheck,
+
+    /// Callbacks passed to `set_resource_created_callback` and
+    /// `set_resource_destroyed_callback`.
+    resource_callbacks: RefCell<ResourceCallbacks>,
+
+    /// Whether glium must assume that the state was modified by closures passed to
+    /// `exec_in_context` and `exec_gl`.
+    state_trust: Cell<StateTrust>,
+
+    /// True if `notify_made_current` has been called since the last time the context has been
+    /// made current by glium.
+    made_current_externally: Cell<bool>,
+
     /// We maintain a cache of FBOs.
     /// The `Option` is here in order to destroy the container. It must be filled at all time
     /// is a normal situation.
@@ -90,20 +142,57 @@ pub struct Context {
     /// We maintain a list of samplers for each possible behavior.
     samplers: RefCell<HashMap<uniforms::SamplerBehavior, sampler_object::SamplerObject, BuildHasherDefault<FnvHasher>>>,
 
-    /// List of texture handles that are resident. We need to call `MakeTextureHandleResidentARB`
-    /// when rebuilding the context.
-    resident_texture_handles: RefCell<Vec<gl::types::GLuint64>>,
+    /// List of texture handles that are resident, and the number of objects that need each of
+    /// them to stay resident. We need to call `MakeTextureHandleResidentARB` when rebuilding the
+    /// context.
+    resident_texture_handles: RefCell<HashMap<gl::types::GLuint64, usize,
+                                              BuildHasherDefault<FnvHasher>>>,
 
     /// List of images handles that are resident. We need to call `MakeImageHandleResidentARB`
     /// when rebuilding the context.
     resident_image_handles: RefCell<Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
+
+    /// Maximum number of frames that the CPU is allowed to be ahead of the GPU, as passed to
+    /// `set_max_frames_in_flight`.
+    max_frames_in_flight: Cell<Option<u32>>,
+
+    /// Fences inserted after each buffer swap, from the oldest to the most recent.
+    frame_fences: RefCell<VecDeque<sync::LinearSyncFence>>,
+
+    /// Fence inserted after the latest texture or buffer uploads, as waited for by
+    /// `flush_uploads`.
+    upload_fence: RefCell<Option<sync::LinearSyncFence>>,
+
+    /// Time spent waiting for the fences of previous frames during the last buffer swap.
+    last_frame_wait_time: Cell<Duration>,
+
+    /// Binding points of the uniform blocks that are shared between programs.
+    binding_points: BindingPointRegistry,
 }
 
+/// Signature of `glDrawMeshTasksNV`.
+pub type DrawMeshTasksNvFn = extern "system" fn(first: gl::types::GLuint,
+                                                count: gl::types::GLuint);
+
+/// Signature of `glSpecializeShader` and `glSpecializeShaderARB`.
+pub type SpecializeShaderFn = extern "system" fn(shader: gl::types::GLuint,
+                                                 entry_point: *const gl::types::GLchar,
+                                                 num_constants: gl::types::GLuint,
+                                                 constant_indices: *const gl::types::GLuint,
+                                                 constant_values: *const gl::types::GLuint);
+
 /// This struct is a guard that is returned when you want to access the OpenGL backend.
 pub struct CommandContext<'a> {
     /// Source of OpenGL function pointers.
     pub gl: &'a gl::Gl,
 
+    /// Pointer to `glDrawMeshTasksNV`, or `None` if `GL_NV_mesh_shader` is not supported.
+    pub draw_mesh_tasks_nv: Option<DrawMeshTasksNvFn>,
+
+    /// Pointer to `glSpecializeShader` or `glSpecializeShaderARB`, or `None` if SPIR-V is not
+    /// supported.
+    pub specialize_shader: Option<SpecializeShaderFn>,
+
     /// Refers to the state of the OpenGL backend. Maintained between multiple calls.
     /// **Must** be synchronized with the real state of the backend.
     pub state: RefMut<'a, GlState>,
@@ -121,6 +210,21 @@ pub struct CommandContext<'a> {
     /// reported to the user (by panicking).
     pub report_debug_output_errors: &'a Cell<bool>,
 
+    /// What to do with the errors returned by `glGetError` when the command context is
+    /// destroyed.
+    error_behavior: GlErrorBehavior,
+
+    /// When to call `glGetError`.
+    error_check: &'a ErrorCheck,
+
+    /// Name of the operation performed with this context, added to the log of operations when
+    /// the context is destroyed if `glGetError` is only called from time to time. `None` if the
+    /// operation must not be logged.
+    operation: Option<&'static str>,
+
+    /// Callbacks to call when an OpenGL object is created or destroyed.
+    resource_callbacks: &'a RefCell<ResourceCallbacks>,
+
     /// The list of vertex array objects.
     pub vertex_array_objects: &'a vertex_array_object::VertexAttributesSystem,
 
@@ -130,12 +234,17 @@ pub struct CommandContext<'a> {
     /// The list of samplers.
     pub samplers: RefMut<'a, HashMap<uniforms::SamplerBehavior, sampler_object::SamplerObject, BuildHasherDefault<FnvHasher>>>,
 
-    /// List of texture handles that need to be made resident.
-    pub resident_texture_handles: RefMut<'a, Vec<gl::types::GLuint64>>,
+    /// List of texture handles that need to be made resident, and the number of objects that
+    /// need each of them.
+    pub resident_texture_handles: RefMut<'a, HashMap<gl::types::GLuint64, usize,
+                                                     BuildHasherDefault<FnvHasher>>>,
 
     /// List of image handles and their access that need to be made resident.
     pub resident_image_handles: RefMut<'a, Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
 
+    /// Fence inserted after the latest texture or buffer uploads.
+    pub upload_fence: RefMut<'a, Option<sync::LinearSyncFence>>,
+
     /// This marker is here to prevent `CommandContext` from implementing `Send`
     // TODO: use this when possible
     //impl<'a, 'b> !Send for CommandContext<'a, 'b> {}
@@ -170,6 +279,25 @@ impl Context {
         try!(check_gl_compatibility(&version, &extensions));
 
         let capabilities = capabilities::get_capabilities(&gl, &version, &extensions);
+
+        let draw_mesh_tasks_nv = if extensions.gl_nv_mesh_shader {
+            let ptr = backend.get_proc_address("glDrawMeshTasksNV");
+            if ptr.is_null() { None } else { Some(mem::transmute(ptr)) }
+        } else {
+            None
+        };
+
+        // SPIR-V is core since OpenGL 4.6, whose functions are not in the generated bindings
+        let specialize_shader = if version >= Version(Api::Gl, 4, 6) {
+            let ptr = backend.get_proc_address("glSpecializeShader");
+            if ptr.is_null() { None } else { Some(mem::transmute(ptr)) }
+        } else if extensions.gl_arb_gl_spirv {
+            let ptr = backend.get_proc_address("glSpecializeShaderARB");
+            if ptr.is_null() { None } else { Some(mem::transmute(ptr)) }
+        } else {
+            None
+        };
+
         let report_debug_output_errors = Cell::new(true);
 
         let vertex_array_objects = vertex_array_object::VertexAttributesSystem::new();
@@ -179,8 +307,9 @@ impl Context {
             map.reserve(16);
             map
         });
-        let resident_texture_handles = RefCell::new(Vec::new());
+        let resident_texture_handles = RefCell::new(HashMap::with_hasher(Default::default()));
         let resident_image_handles = RefCell::new(Vec::new());
+        let binding_points = binding_points::new(capabilities.max_indexed_uniform_buffer as u32);
 
         let (debug_callback, synchronous) = match callback_behavior {
             DebugCallbackBehavior::Ignore => (None, false),
@@ -197,12 +326,26 @@ impl Context {
 
         let context = Rc::new(Context {
             gl: gl,
+            draw_mesh_tasks_nv: draw_mesh_tasks_nv,
+            specialize_shader: specialize_shader,
             state: gl_state,
             version: version,
             extensions: extensions,
             capabilities: capabilities,
             debug_callback: debug_callback,
             report_debug_output_errors: report_debug_output_errors,
+            error_behavior: Cell::new(GlErrorBehavior::Ignore),
+            error_check: ErrorCheck {
+                interval: Cell::new(GlErrorCheckInterval::EachOperation),
+                operations: RefCell::new(VecDeque::with_capacity(OPERATIONS_LOG_LEN)),
+                num_operations: Cell::new(0),
+            },
+            resource_callbacks: RefCell::new(ResourceCallbacks {
+                created: None,
+                destroyed: None,
+            }),
+            state_trust: Cell::new(StateTrust::Verify),
+            made_current_externally: Cell::new(false),
             backend: RefCell::new(Box::new(backend)),
             check_current_context: check_current_context,
             framebuffer_objects: Some(framebuffer_objects),
@@ -210,6 +353,11 @@ impl Context {
             samplers: samplers,
             resident_texture_handles: resident_texture_handles,
             resident_image_handles: resident_image_handles,
+            max_frames_in_flight: Cell::new(None),
+            frame_fences: RefCell::new(VecDeque::new()),
+            upload_fence: RefCell::new(None),
+            last_frame_wait_time: Cell::new(Duration::new(0, 0)),
+            binding_points: binding_points,
         });
 
         if context.debug_callback.is_some() {
@@ -260,7 +408,7 @@ impl Context {
 
         // making textures resident
         let textures = self.resident_texture_handles.borrow();
-        for &texture in textures.iter() {
+        for &texture in textures.keys() {
             self.gl.MakeTextureHandleResidentARB(texture);
         }
 
@@ -279,6 +427,12 @@ impl Context {
             return Err(SwapBuffersError::ContextLost);
         }
 
+        if self.error_behavior.get() != GlErrorBehavior::Ignore &&
+           self.error_check.interval.get() == GlErrorCheckInterval::EachFrame
+        {
+            self.make_current().report_deferred_errors();
+        }
+
         // Note: This is a work-around for the FRAPS software.
         //       The Fraps software calls `glClear` with scissoring and reads the image of the
         //       current framebuffer.
@@ -316,10 +470,110 @@ impl Context {
         let err = backend.swap_buffers();
         if let Err(SwapBuffersError::ContextLost) = err {
             self.state.borrow_mut().lost_context = true;
+            return err;
         }
+
+        self.wait_frames_in_flight();
+
         err
     }
 
+    /// Ends the current frame like `swap_buffers` does, but without presenting anything.
+    ///
+    /// The errors are checked and the commands queue is flushed. This is useful when another
+    /// system, like a VR runtime or the application embedding glium, is in charge of presenting
+    /// what has been drawn.
+    pub fn end_frame_without_swap(&self) -> Result<(), SwapBuffersError> {
+        if self.state.borrow().lost_context {
+            return Err(SwapBuffersError::ContextLost);
+        }
+
+        if self.error_behavior.get() != GlErrorBehavior::Ignore &&
+           self.error_check.interval.get() == GlErrorCheckInterval::EachFrame
+        {
+            self.make_current().report_deferred_errors();
+        }
+
+        self.flush();
+        self.wait_frames_in_flight();
+        Ok(())
+    }
+
+    /// Inserts a fence after the frame that has just ended, and waits for the frame that ended
+    /// `max_frames_in_flight` frames ago.
+    fn wait_frames_in_flight(&self) {
+        if let Some(max) = self.max_frames_in_flight.get() {
+            let mut ctxt = self.make_current();
+            let mut fences = self.frame_fences.borrow_mut();
+
+            if let Ok(fence) = unsafe { sync::new_linear_sync_fence(&mut ctxt) } {
+                fences.push_back(fence);
+            }
+
+            let before = Instant::now();
+            while fences.len() > max as usize {
+                let fence = fences.pop_front().unwrap();
+                unsafe { sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt) };
+            }
+            self.last_frame_wait_time.set(before.elapsed());
+        }
+    }
+
+    /// Limits the number of frames that the CPU can submit before the GPU has finished
+    /// executing them.
+    ///
+    /// When a value is set, a fence is inserted in the commands queue after each buffer swap.
+    /// Before returning, `swap_buffers` then waits until the fence of the frame that was
+    /// swapped `max` frames ago has been signaled. This bounds the input latency without having
+    /// to call `finish()` every frame. A value of `0` waits for the frame that has just been
+    /// swapped, which is equivalent to calling `finish()`.
+    ///
+    /// Passing `None` (the default) disables this mechanism and lets the driver decide how many
+    /// frames can be queued.
+    ///
+    /// Returns an error if the backend doesn't support fences, in which case the previous value
+    /// is kept.
+    pub fn set_max_frames_in_flight(&self, max: Option<u32>)
+                                    -> Result<(), sync::SyncNotSupportedError>
+    {
+        let mut ctxt = self.make_current();
+
+        if max.is_some() && !(ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                              ctxt.extensions.gl_arb_sync || ctxt.extensions.gl_apple_sync)
+        {
+            return Err(sync::SyncNotSupportedError);
+        }
+
+        self.max_frames_in_flight.set(max);
+
+        if max.is_none() {
+            for fence in self.frame_fences.borrow_mut().drain(..) {
+                unsafe { sync::destroy_linear_sync_fence(&mut ctxt, fence) };
+            }
+            self.last_frame_wait_time.set(Duration::new(0, 0));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the value passed to `set_max_frames_in_flight`.
+    #[inline]
+    pub fn get_max_frames_in_flight(&self) -> Option<u32> {
+        self.max_frames_in_flight.get()
+    }
+
+    /// Returns the time that the last call to `swap_buffers` spent waiting for the GPU to finish
+    /// previous frames.
+    ///
+    /// This is always zero if `set_max_frames_in_flight` hasn't been called. A wait time that is
+    /// consistently high means that the application is GPU-bound and that the limit could be
+    /// raised, while a wait time of zero means that the limit has no effect.
+    #[inline]
+    pub fn get_last_frame_wait_time(&self) -> Duration {
+        self.last_frame_wait_time.get()
+    }
+
     /// DEPRECATED. Use `get_opengl_version` instead.
     #[inline]
     pub fn get_version(&self) -> &Version {
@@ -462,13 +716,20 @@ impl Context {
 
     /// Releases the shader compiler, indicating that no new programs will be created for a while.
     ///
-    /// This method is a no-op if it's not available in the implementation.
+    /// This calls `glReleaseShaderCompiler`, which allows the driver to free the memory used by
+    /// its GLSL compiler. Call it once all your programs have been built, for example at the end
+    /// of a loading screen. Building a program afterwards still works, but the driver has to load
+    /// the compiler again, which makes the next compilation slower.
+    ///
+    /// This method is a no-op if it's not available in the implementation. It requires OpenGL 4.1,
+    /// OpenGL ES 2.0 or `GL_ARB_ES2_compatibility`.
     pub fn release_shader_compiler(&self) {
         unsafe {
             let ctxt = self.make_current();
 
             if ctxt.version >= &Version(Api::GlEs, 2, 0) ||
-                ctxt.version >= &Version(Api::Gl, 4, 1)
+                ctxt.version >= &Version(Api::Gl, 4, 1) ||
+                ctxt.extensions.gl_arb_es2_compatibility
             {
                 if !ctxt.capabilities.supported_glsl_versions.is_empty() {
                     ctxt.gl.ReleaseShaderCompiler();
@@ -501,6 +762,189 @@ impl Context {
         }
     }
 
+    /// Returns informations about the video memory of the GPU.
+    ///
+    /// This is a best-effort heuristic. The values are queried with the
+    /// `GL_NVX_gpu_memory_info` (NVIDIA) or `GL_ATI_meminfo` (AMD) extensions, and `None` is
+    /// returned if neither is available. The values are not necessarily up-to-date, and other
+    /// applications can use video memory at any time. You should only use them to decide when to
+    /// evict resources, for example in a texture streaming system.
+    pub fn video_memory_info(&self) -> Option<VideoMemoryInfo> {
+        unsafe {
+            let ctxt = self.make_current();
+
+            if ctxt.extensions.gl_nvx_gpu_memory_info {
+                let mut dedicated = 0;
+                let mut total = 0;
+                let mut available = 0;
+                ctxt.gl.GetIntegerv(gl::GPU_MEMORY_INFO_DEDICATED_VIDMEM_NVX, &mut dedicated);
+                ctxt.gl.GetIntegerv(gl::GPU_MEMORY_INFO_TOTAL_AVAILABLE_MEMORY_NVX, &mut total);
+                ctxt.gl.GetIntegerv(gl::GPU_MEMORY_INFO_CURRENT_AVAILABLE_VIDMEM_NVX,
+                                    &mut available);
+
+                Some(VideoMemoryInfo {
+                    total: Some(total as usize),
+                    available: available as usize,
+                    dedicated: Some(dedicated as usize),
+                })
+
+            } else if ctxt.extensions.gl_ati_meminfo {
+                // the first value is the total amount of free memory in the pool
+                let mut value: [gl::types::GLint; 4] = mem::uninitialized();
+                ctxt.gl.GetIntegerv(gl::TEXTURE_FREE_MEMORY_ATI, value.as_mut_ptr());
+
+                Some(VideoMemoryInfo {
+                    total: None,
+                    available: value[0] as usize,
+                    dedicated: None,
+                })
+
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns detailed informations about the support of an internal format by the
+    /// implementation, such as whether it can be rendered to, filtered or blended, and which
+    /// format and type are the fastest to upload data with.
+    ///
+    /// This lets you pick the best format at runtime instead of relying on fallbacks.
+    ///
+    /// Returns `None` if the implementation doesn't support querying these informations
+    /// (OpenGL 4.3 or `GL_ARB_internalformat_query2` are required).
+    pub fn format_support(&self, format: TextureFormat) -> Option<FormatSupport> {
+        let ctxt = self.make_current();
+        capabilities::get_format_support(ctxt.gl, ctxt.version, ctxt.extensions, format)
+    }
+
+    /// Queries the current state of OpenGL, such as the bound program, textures and framebuffer,
+    /// and the blending, depth and stencil parameters.
+    ///
+    /// This function is slow, as it requires synchronizing with the OpenGL implementation, and
+    /// should only be used for debugging. The values are queried from OpenGL and not from
+    /// glium's internal cache of the state.
+    pub fn capture_gl_state(&self) -> GlStateSnapshot {
+        let mut ctxt = self.make_current();
+        snapshot::capture(&mut ctxt)
+    }
+
+    /// Saves the blending, depth, stencil, face culling, scissor and viewport parameters, along
+    /// with the current program and vertex array object. They are restored when the returned
+    /// guard is destroyed.
+    ///
+    /// Use this around rendering code that doesn't come from glium, for example a GUI renderer,
+    /// so that each side doesn't see the state left by the other. Like `capture_gl_state`, this
+    /// queries OpenGL and is therefore slow-ish.
+    #[inline]
+    pub fn save_render_state(&self) -> RenderStateGuard {
+        RenderStateGuard::new(self)
+    }
+
+    /// Returns the registry that assigns binding points to the uniform blocks that are shared
+    /// between programs.
+    #[inline]
+    pub fn get_binding_point_registry(&self) -> &BindingPointRegistry {
+        &self.binding_points
+    }
+
+    /// Returns, for each texture unit, the texture that is bound to it and how it is sampled.
+    ///
+    /// Contrary to `capture_gl_state`, the values are taken from glium's internal cache of the
+    /// state, which makes this function cheap. Units that are empty, or whose content is unknown
+    /// because the state has been modified behind glium's back, are `None`.
+    ///
+    /// The `feedback_loop` field tells you whether the texture is also attached to the
+    /// framebuffer that is currently bound for drawing. Sampling from a texture while rendering
+    /// to it is undefined behavior in OpenGL and usually results in garbage without any error.
+    pub fn bound_textures(&self) -> Vec<Option<BoundTextureInfo>> {
+        const UNKNOWN: gl::types::GLuint = !0;
+
+        let mut ctxt = self.make_current();
+
+        let (draw_framebuffer, _) = fbo::get_bound_framebuffers(&mut ctxt);
+        let attached = fbo::FramebuffersContainer::get_attached_textures(&ctxt,
+                                                                         draw_framebuffer);
+
+        ctxt.state.texture_units.iter().map(|unit| {
+            if unit.texture == 0 || unit.texture == UNKNOWN {
+                return None;
+            }
+
+            let sampler = if unit.sampler == UNKNOWN { 0 } else { unit.sampler };
+            let sampler_behavior = ctxt.samplers.iter()
+                                       .find(|&(_, s)| sampler != 0 && s.get_id() == sampler)
+                                       .map(|(behavior, _)| *behavior);
+
+            Some(BoundTextureInfo {
+                texture: unit.texture,
+                target: unit.target,
+                sampler: sampler,
+                sampler_behavior: sampler_behavior,
+                feedback_loop: attached.iter().any(|&t| t == unit.texture),
+            })
+        }).collect()
+    }
+
+    /// Changes the value of one of the implementation-defined hints.
+    ///
+    /// Hints let you choose whether the implementation should favor speed or quality for some
+    /// operations. They are only hints and the implementation may disregard them.
+    ///
+    /// Returns an error if the backend doesn't support this hint.
+    pub fn set_hint(&self, target: HintTarget, mode: Smooth)
+                    -> Result<(), HintNotSupportedError>
+    {
+        let mut ctxt = self.make_current();
+
+        let core = match ctxt.capabilities.profile {
+            Some(Profile::Core) => true,
+            _ => false,
+        };
+
+        let supported = match target {
+            HintTarget::FragmentShaderDerivative => {
+                ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                ctxt.extensions.gl_arb_fragment_shader ||
+                ctxt.extensions.gl_oes_standard_derivatives
+            },
+            HintTarget::TextureCompression => {
+                ctxt.version >= &Version(Api::Gl, 1, 3) && !core
+            },
+            HintTarget::GenerateMipmap => {
+                (ctxt.version >= &Version(Api::Gl, 1, 4) && !core) ||
+                ctxt.version >= &Version(Api::GlEs, 2, 0)
+            },
+        };
+
+        if !supported {
+            return Err(HintNotSupportedError);
+        }
+
+        let mode = mode.to_glenum();
+
+        let (name, cache) = match target {
+            HintTarget::FragmentShaderDerivative => {
+                (gl::FRAGMENT_SHADER_DERIVATIVE_HINT,
+                 &mut ctxt.state.fragment_shader_derivative_hint)
+            },
+            HintTarget::TextureCompression => {
+                (gl::TEXTURE_COMPRESSION_HINT, &mut ctxt.state.texture_compression_hint)
+            },
+            HintTarget::GenerateMipmap => {
+                (gl::GENERATE_MIPMAP_HINT, &mut ctxt.state.generate_mipmap_hint)
+            },
+        };
+
+        if *cache != mode {
+            unsafe { ctxt.gl.Hint(name, mode); }
+            *cache = mode;
+        }
+
+        Ok(())
+    }
+
     /// Reads the content of the front buffer.
     ///
     /// You will only see the data that has finished being drawn.
@@ -540,21 +984,217 @@ impl Context {
                                             where T: Send + 'static,
                                             F: FnOnce() -> T + 'a
     {
-        let _ctxt = self.make_current();
-        action()
+        let mut ctxt = self.make_current();
+        let result = action();
+
+        if self.state_trust.get() == StateTrust::Verify {
+            ctxt.state.invalidate_bindings();
+        }
+
+        result
+    }
+
+    /// Execute an arbitrary closure with the OpenGL context active, giving it a function that
+    /// loads OpenGL function pointers from the backend.
+    ///
+    /// This is useful to call an OpenGL function that glium doesn't expose. The loader takes
+    /// the name of a function, for example `"glMemoryBarrier"`, and returns a null pointer if
+    /// the function isn't available.
+    ///
+    /// Like with `exec_in_context`, the objects bindings are marked as unknown afterwards,
+    /// unless the state trust is `StateTrust::TrustCache`, in which case `invalidate_state` must
+    /// be called after changing bindings. **If `action` manipulates any other OpenGL state, like
+    /// enabled capabilities, it must be restored before `action` completes.**
+    #[inline]
+    pub unsafe fn exec_gl<T, F>(&self, action: F) -> T
+                                where F: FnOnce(&Fn(&str) -> *const raw::c_void) -> T
+    {
+        let mut ctxt = self.make_current();
+        let result = {
+            let backend = self.backend.borrow();
+            let load = |symbol: &str| backend.get_proc_address(symbol) as *const raw::c_void;
+            action(&load)
+        };
+
+        if self.state_trust.get() == StateTrust::Verify {
+            ctxt.state.invalidate_bindings();
+        }
+
+        result
+    }
+
+    /// Marks the bindings of glium's cache of the OpenGL state as unknown, so that glium binds
+    /// its objects again the next time it needs them.
+    ///
+    /// With `StateTrust::TrustCache`, this must be called after running raw OpenGL code that
+    /// modifies bindings. This covers the objects bindings, the viewport, the scissor box and the
+    /// pixel store alignments.
+    #[inline]
+    pub fn invalidate_state(&self) {
+        let mut ctxt = self.make_current();
+        ctxt.state.invalidate_bindings();
+    }
+
+    /// Informs glium that its OpenGL context has been made current by someone else after
+    /// another context was current.
+    ///
+    /// Glium's cache of the OpenGL state assumes that glium is the only user of the context.
+    /// When glium itself detects that the context isn't current anymore, which it only does if
+    /// the context has been built with `check_current_context`, it marks the objects bindings
+    /// as unknown and queries the rest of the cached state (enabled capabilities, blending,
+    /// depth and stencil parameters, viewport, etc.) from OpenGL before its next operation.
+    /// Call this function if your application or another library switches between contexts
+    /// without glium noticing, for example when sharing objects between contexts or when the
+    /// check is disabled.
+    ///
+    /// This doesn't make the context current, and doesn't call any OpenGL function. The cache
+    /// is updated the next time glium uses the context.
+    #[inline]
+    pub fn notify_made_current(&self) {
+        self.made_current_externally.set(true);
+    }
+
+    /// Changes whether glium trusts its cache of the OpenGL state after running closures
+    /// passed to `exec_in_context` and `exec_gl`, and after its context has been made current
+    /// again.
+    ///
+    /// With the default value, `StateTrust::Verify`, glium marks the objects bindings as
+    /// unknown after running such a closure, which costs a few redundant bindings in the next
+    /// operation. When it detects that another context was current, it also queries the whole
+    /// state from OpenGL again, which costs dozens of `glGet` calls.
+    ///
+    /// With `StateTrust::TrustCache`, glium skips both: it assumes that nothing has been
+    /// modified, and you must call `invalidate_state` yourself whenever your raw OpenGL code
+    /// changes a binding, or `notify_made_current` if anything else may have used the context.
+    /// This is only useful for applications that don't mix glium with raw OpenGL code or that
+    /// carefully track what this code modifies.
+    #[inline]
+    pub fn set_state_trust(&self, trust: StateTrust) {
+        self.state_trust.set(trust);
+    }
+
+    /// Returns the value passed to `set_state_trust`.
+    #[inline]
+    pub fn get_state_trust(&self) -> StateTrust {
+        self.state_trust.get()
+    }
+
+    /// Returns the framebuffer objects that are currently bound for drawing and for reading.
+    ///
+    /// Glium binds the framebuffers it needs before each operation, so this is mostly useful
+    /// when mixing glium with raw OpenGL code. See also `FramebufferGuard`.
+    pub fn current_framebuffer(&self) -> FramebufferBinding {
+        let mut ctxt = self.make_current();
+        let (draw, read) = fbo::get_bound_framebuffers(&mut ctxt);
+
+        FramebufferBinding {
+            draw: draw,
+            read: read,
+        }
     }
 
     /// Asserts that there are no OpenGL errors pending.
     ///
-    /// This function should be used in tests.
+    /// All the pending errors are retreived with `glGetError`, and the panic message contains
+    /// their names along with the version and the vendor of the OpenGL implementation.
+    ///
+    /// This function should be used in tests. Calling it after each operation lets you find
+    /// which operation triggers an error first.
     pub fn assert_no_error(&self, user_msg: Option<&str>) {
-        let mut ctxt = self.make_current();
+        let errors = get_pending_errors(&mut self.make_current());
+        if errors.is_empty() {
+            return;
+        }
 
-        match (::get_gl_error(&mut ctxt), user_msg) {
-            (Some(msg), None) => panic!("{}", msg),
-            (Some(msg), Some(user_msg)) => panic!("{} : {}", user_msg, msg),
-            (None, _) => ()
-        };
+        let errors = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
+        let details = format!("{} (OpenGL version: {:?}, vendor: {})", errors,
+                              self.get_opengl_version(), self.capabilities.vendor);
+
+        match user_msg {
+            Some(user_msg) => panic!("{} : {}", user_msg, details),
+            None => panic!("{}", details),
+        }
+    }
+
+    /// Retreives the errors pending in `glGetError` and returns the first one, or `None` if
+    /// there is no error.
+    ///
+    /// Contrary to `assert_no_error`, this function doesn't panic. The other pending errors are
+    /// discarded, so that the next call only returns the errors triggered in the meantime.
+    pub fn last_error(&self) -> Option<GlError> {
+        get_pending_errors(&mut self.make_current()).into_iter().next()
+    }
+
+    /// Changes what glium does with the errors reported by `glGetError`.
+    ///
+    /// With a value other than `GlErrorBehavior::Ignore`, glium calls `glGetError` at the end of
+    /// each of its operations and reports the errors along with the name of the operation that
+    /// most likely triggered them. This is useful on platforms that don't support the debug
+    /// output, where an error would otherwise go unnoticed.
+    ///
+    /// The default value is `GlErrorBehavior::Ignore`, in which case `glGetError` is never called.
+    #[inline]
+    pub fn set_error_behavior(&self, behavior: GlErrorBehavior) {
+        self.error_behavior.set(behavior);
+    }
+
+    /// Returns the value passed to `set_error_behavior`.
+    #[inline]
+    pub fn get_error_behavior(&self) -> GlErrorBehavior {
+        self.error_behavior.get()
+    }
+
+    /// Changes how often glium calls `glGetError` when the error behavior is not
+    /// `GlErrorBehavior::Ignore`.
+    ///
+    /// Calling `glGetError` after each operation forces some implementations to synchronize
+    /// with the GPU, which is slow. With `GlErrorCheckInterval::EachFrame` or
+    /// `GlErrorCheckInterval::Operations`, glium only calls `glGetError` from time to time
+    /// and keeps a log of the last operations that it has performed in the meantime. When an
+    /// error is detected, this log is reported along with the error so that you can find the
+    /// operation that triggered it.
+    ///
+    /// The errors that are pending when the interval is changed are reported immediately.
+    ///
+    /// The default value is `GlErrorCheckInterval::EachOperation`.
+    pub fn set_error_check_interval(&self, interval: GlErrorCheckInterval) {
+        if self.error_behavior.get() != GlErrorBehavior::Ignore {
+            self.make_current().report_deferred_errors();
+        }
+
+        self.error_check.interval.set(interval);
+    }
+
+    /// Returns the value passed to `set_error_check_interval`.
+    #[inline]
+    pub fn get_error_check_interval(&self) -> GlErrorCheckInterval {
+        self.error_check.interval.get()
+    }
+
+    /// Sets a callback that is called whenever glium creates an OpenGL object, with the kind
+    /// and the id of the object. Pass `None` to remove the callback.
+    ///
+    /// Along with `set_resource_destroyed_callback`, this allows external tools, for example
+    /// frame capture tools, to keep track of the objects that are alive.
+    ///
+    /// Shader objects, which only exist while a program is being built, and sync objects are
+    /// not reported.
+    ///
+    /// The callback is called while glium is executing OpenGL commands, and therefore must not
+    /// use glium.
+    #[inline]
+    pub fn set_resource_created_callback(&self, callback: Option<ResourceCallback>) {
+        self.resource_callbacks.borrow_mut().created = callback;
+    }
+
+    /// Sets a callback that is called whenever glium destroys an OpenGL object, with the kind
+    /// and the id of the object. Pass `None` to remove the callback.
+    ///
+    /// The callback is called while glium is executing OpenGL commands, and therefore must not
+    /// use glium.
+    #[inline]
+    pub fn set_resource_destroyed_callback(&self, callback: Option<ResourceCallback>) {
+        self.resource_callbacks.borrow_mut().destroyed = callback;
     }
 
     /// DEPRECATED. Renamed `finish`.
@@ -592,6 +1232,156 @@ impl Context {
         unsafe { ctxt.gl.Flush(); }
     }
 
+    /// Calls `glTextureBarrier`, which allows the next draw command to sample a texture that is
+    /// attached to the framebuffer it draws to.
+    ///
+    /// This can be used for programmable blending, in other words to implement in the fragment
+    /// shader a blending mode that the fixed-function blending doesn't support. The rules are
+    /// strict, and breaking them gives undefined results:
+    ///
+    /// - Each fragment must only read the texel that corresponds to its own position, and must
+    ///   not read the texels written by other fragments of the same draw command.
+    /// - A texel must be written at most once between two barriers. If the primitives of a
+    ///   draw command overlap, or if two draw commands touch the same pixels, you must call
+    ///   this function between them.
+    ///
+    /// Sampling an attached texture normally produces a `DrawError::FeedbackLoop` in debug
+    /// mode. This error isn't produced for the first draw command after a barrier.
+    ///
+    /// Returns `Err` if the backend doesn't support OpenGL 4.5, `GL_ARB_texture_barrier` or
+    /// `GL_NV_texture_barrier`.
+    pub fn texture_barrier(&self) -> Result<(), TextureBarrierNotSupportedError> {
+        let mut ctxt = self.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_texture_barrier {
+            unsafe { ctxt.gl.TextureBarrier(); }
+        } else if ctxt.extensions.gl_nv_texture_barrier {
+            unsafe { ctxt.gl.TextureBarrierNV(); }
+        } else {
+            return Err(TextureBarrierNotSupportedError);
+        }
+
+        ctxt.state.latest_texture_barrier = ctxt.state.next_draw_call_id;
+        Ok(())
+    }
+
+    /// Waits until the texture and buffer uploads that have been issued so far have finished
+    /// being executed.
+    ///
+    /// This is meant to be called at the end of a loading screen, once all the resources have
+    /// been created, so that the first frames don't stall on uploads that are still in
+    /// progress.
+    ///
+    /// Glium inserts a fence in the commands queue after the latest uploads, before the next
+    /// draw command, and this function waits for that fence to be signaled. The draw commands
+    /// that have been submitted after the uploads are not waited for. Since OpenGL executes
+    /// commands in order, the commands that have been issued before the uploads are however
+    /// finished when this function returns.
+    ///
+    /// If the backend doesn't support fences, this function calls `finish()` instead.
+    pub fn flush_uploads(&self) {
+        let mut ctxt = self.make_current();
+
+        unsafe {
+            sync::fence_pending_uploads(&mut ctxt);
+
+            if ctxt.state.pending_uploads {
+                ctxt.gl.Finish();
+                ctxt.state.pending_uploads = false;
+            }
+
+            let fence = ctxt.upload_fence.take();
+            if let Some(fence) = fence {
+                sync::wait_linear_sync_fence_and_drop(fence, &mut ctxt);
+            }
+        }
+    }
+
+    /// Unbinds the texture that is bound to an image unit, if any.
+    ///
+    /// Textures stay bound to the image units used by a draw call or a compute dispatch until
+    /// the unit is used again. Glium calls `glMemoryBarrier` when needed before sampling a
+    /// texture that a shader has written to, but you can call this function to make sure that
+    /// no shader can access the texture through the image unit anymore.
+    ///
+    /// Does nothing if the backend doesn't support image units.
+    pub fn unbind_image(&self, unit: u32) {
+        let mut ctxt = self.make_current();
+
+        let bound = match ctxt.state.image_units.get(unit as usize) {
+            Some(&texture) => texture != 0,
+            None => false,
+        };
+
+        if !bound {
+            return;
+        }
+
+        unsafe {
+            ctxt.gl.BindImageTexture(unit, 0, 0, gl::FALSE, 0, gl::READ_ONLY, gl::R8);
+        }
+
+        ctxt.state.image_units[unit as usize] = 0;
+    }
+
+    /// Binds a texture to a texture unit and reserves this unit for it.
+    ///
+    /// Glium chooses the texture units of the textures used by each draw command, and reuses
+    /// the unit that already contains a texture when possible. When all the units are in use,
+    /// the texture that has been used the least recently is replaced. Pinning long-lived
+    /// textures that are used by most draw commands, such as a shadow map or an environment map,
+    /// makes sure that they are never replaced, so that they don't need to be bound again.
+    ///
+    /// A texture can only be pinned to one unit at a time. The other textures are only bound to
+    /// this unit if no other unit is available. The texture is unpinned when it is destroyed.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `unit` is superior or equal to `max_combined_texture_image_units`.
+    pub fn pin_texture(&self, texture: &texture::TextureAny, unit: u32) {
+        let mut ctxt = self.make_current();
+
+        assert!((unit as gl::types::GLint) < ctxt.capabilities.max_combined_texture_image_units,
+                "The texture unit is out of range");
+
+        let id = texture.get_texture_id();
+
+        for content in ctxt.state.texture_units.iter_mut() {
+            if content.pinned_texture == id {
+                content.pinned_texture = 0;
+            }
+        }
+
+        for _ in ctxt.state.texture_units.len() .. unit as usize + 1 {
+            ctxt.state.texture_units.push(Default::default());
+        }
+
+        ctxt.state.texture_units[unit as usize].pinned_texture = id;
+
+        if ctxt.state.texture_units[unit as usize].texture != id {
+            if ctxt.state.active_texture != unit {
+                unsafe { ctxt.gl.ActiveTexture(unit + gl::TEXTURE0) };
+                ctxt.state.active_texture = unit;
+            }
+
+            texture.bind_to_current(&mut ctxt);
+        }
+    }
+
+    /// Releases the texture unit that has been reserved for a texture with `pin_texture`.
+    ///
+    /// The texture stays bound to the unit until glium needs it for another texture.
+    pub fn unpin_texture(&self, texture: &texture::TextureAny) {
+        let mut ctxt = self.make_current();
+        let id = texture.get_texture_id();
+
+        for content in ctxt.state.texture_units.iter_mut() {
+            if content.pinned_texture == id {
+                content.pinned_texture = 0;
+            }
+        }
+    }
+
     /// Inserts a debugging string in the commands queue. If you use an OpenGL debugger, you will
     /// be able to see that string.
     ///
@@ -638,28 +1428,53 @@ impl ContextExt for Context {
     }
 
     fn make_current(&self) -> CommandContext {
+        let mut switched = false;
+
         if self.check_current_context {
             let backend = self.backend.borrow();
             if !backend.is_current() {
                 unsafe { backend.make_current() };
                 debug_assert!(backend.is_current());
+                switched = true;
             }
         }
 
-        CommandContext {
+        // with `StateTrust::TrustCache`, nothing else uses this context, so switching back to it
+        // doesn't require querying the state again
+        let reload_state = (switched && self.state_trust.get() == StateTrust::Verify) ||
+                           self.made_current_externally.get();
+        self.made_current_externally.set(false);
+
+        let mut ctxt = CommandContext {
             gl: &self.gl,
+            draw_mesh_tasks_nv: self.draw_mesh_tasks_nv,
+            specialize_shader: self.specialize_shader,
             state: self.state.borrow_mut(),
             version: &self.version,
             extensions: &self.extensions,
             capabilities: &self.capabilities,
             report_debug_output_errors: &self.report_debug_output_errors,
+            error_behavior: self.error_behavior.get(),
+            error_check: &self.error_check,
+            operation: Some("an OpenGL operation"),
+            resource_callbacks: &self.resource_callbacks,
             vertex_array_objects: &self.vertex_array_objects,
             framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
             samplers: self.samplers.borrow_mut(),
             resident_texture_handles: self.resident_texture_handles.borrow_mut(),
             resident_image_handles: self.resident_image_handles.borrow_mut(),
+            upload_fence: self.upload_fence.borrow_mut(),
             marker: PhantomData,
+        };
+
+        // another context was current, which may have been used by code that doesn't go
+        // through glium and may have changed any part of the state
+        if reload_state {
+            ctxt.state.invalidate_bindings();
+            unsafe { render_state::reload(&mut ctxt) };
         }
+
+        ctxt
     }
 
     #[inline]
@@ -699,16 +1514,23 @@ impl Drop for Context {
 
             let mut ctxt = CommandContext {
                 gl: &self.gl,
+                draw_mesh_tasks_nv: self.draw_mesh_tasks_nv,
+                specialize_shader: self.specialize_shader,
                 state: self.state.borrow_mut(),
                 version: &self.version,
                 extensions: &self.extensions,
                 capabilities: &self.capabilities,
                 report_debug_output_errors: &self.report_debug_output_errors,
+                error_behavior: GlErrorBehavior::Ignore,
+                error_check: &self.error_check,
+                operation: None,
+                resource_callbacks: &self.resource_callbacks,
                 vertex_array_objects: &self.vertex_array_objects,
                 framebuffer_objects: self.framebuffer_objects.as_ref().unwrap(),
                 samplers: self.samplers.borrow_mut(),
                 resident_texture_handles: self.resident_texture_handles.borrow_mut(),
                 resident_image_handles: self.resident_image_handles.borrow_mut(),
+                upload_fence: self.upload_fence.borrow_mut(),
                 marker: PhantomData,
             };
 
@@ -719,6 +1541,15 @@ impl Drop for Context {
                 s.destroy(&mut ctxt);
             }
 
+            for fence in self.frame_fences.borrow_mut().drain(..) {
+                sync::destroy_linear_sync_fence(&mut ctxt, fence);
+            }
+
+            let upload_fence = ctxt.upload_fence.take();
+            if let Some(fence) = upload_fence {
+                sync::destroy_linear_sync_fence(&mut ctxt, fence);
+            }
+
             // disabling callback
             if ctxt.state.enabled_debug_output != Some(false) {
                 if ctxt.version >= &Version(Api::Gl, 4,5) || ctxt.extensions.gl_khr_debug {
@@ -792,6 +1623,496 @@ fn check_gl_compatibility<T>(version: &Version, extensions: &ExtensionsList)
     }
 }
 
+/// Error returned by `glGetError`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GlError {
+    /// `GL_INVALID_ENUM`.
+    InvalidEnum,
+    /// `GL_INVALID_VALUE`.
+    InvalidValue,
+    /// `GL_INVALID_OPERATION`.
+    InvalidOperation,
+    /// `GL_INVALID_FRAMEBUFFER_OPERATION`.
+    InvalidFramebufferOperation,
+    /// `GL_OUT_OF_MEMORY`.
+    OutOfMemory,
+    /// `GL_STACK_UNDERFLOW`.
+    StackUnderflow,
+    /// `GL_STACK_OVERFLOW`.
+    StackOverflow,
+    /// `GL_CONTEXT_LOST`.
+    ContextLost,
+    /// A value that isn't defined by the specifications.
+    Unknown(gl::types::GLenum),
+}
+
+impl GlError {
+    /// Turns a value returned by `glGetError` into a `GlError`. Returns `None` for
+    /// `GL_NO_ERROR`.
+    pub fn from_glenum(value: gl::types::GLenum) -> Option<GlError> {
+        match value {
+            gl::NO_ERROR => None,
+            gl::INVALID_ENUM => Some(GlError::InvalidEnum),
+            gl::INVALID_VALUE => Some(GlError::InvalidValue),
+            gl::INVALID_OPERATION => Some(GlError::InvalidOperation),
+            gl::INVALID_FRAMEBUFFER_OPERATION => Some(GlError::InvalidFramebufferOperation),
+            gl::OUT_OF_MEMORY => Some(GlError::OutOfMemory),
+            gl::STACK_UNDERFLOW => Some(GlError::StackUnderflow),
+            gl::STACK_OVERFLOW => Some(GlError::StackOverflow),
+            gl::CONTEXT_LOST => Some(GlError::ContextLost),
+            value => Some(GlError::Unknown(value)),
+        }
+    }
+}
+
+impl fmt::Display for GlError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GlError::Unknown(value) => write!(fmt, "{} ({:#x})", self.description(), value),
+            _ => write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for GlError {
+    fn description(&self) -> &str {
+        match *self {
+            GlError::InvalidEnum => "GL_INVALID_ENUM",
+            GlError::InvalidValue => "GL_INVALID_VALUE",
+            GlError::InvalidOperation => "GL_INVALID_OPERATION",
+            GlError::InvalidFramebufferOperation => "GL_INVALID_FRAMEBUFFER_OPERATION",
+            GlError::OutOfMemory => "GL_OUT_OF_MEMORY",
+            GlError::StackUnderflow => "GL_STACK_UNDERFLOW",
+            GlError::StackOverflow => "GL_STACK_OVERFLOW",
+            GlError::ContextLost => "GL_CONTEXT_LOST",
+            GlError::Unknown(_) => "Unknown glGetError return value",
+        }
+    }
+}
+
+/// Calls `glGetError` until there is no error left and returns the errors.
+fn get_pending_errors(ctxt: &mut CommandContext) -> Vec<GlError> {
+    let mut errors = Vec::new();
+
+    // some implementations keep returning `GL_CONTEXT_LOST`, so the number of calls is limited
+    for _ in 0 .. 32 {
+        match GlError::from_glenum(unsafe { ctxt.gl.GetError() }) {
+            Some(err) => errors.push(err),
+            None => break,
+        }
+    }
+
+    errors
+}
+
+/// Describes what glium should do with the errors returned by `glGetError`.
+///
+/// See `Context::set_error_behavior`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GlErrorBehavior {
+    /// Don't call `glGetError`. This is the default behavior.
+    Ignore,
+
+    /// Print the errors on stderr.
+    Log,
+
+    /// Panic when an error is detected.
+    Panic,
+}
+
+impl Default for GlErrorBehavior {
+    #[inline]
+    fn default() -> GlErrorBehavior {
+        GlErrorBehavior::Ignore
+    }
+}
+
+/// Describes when glium should call `glGetError`.
+///
+/// See `Context::set_error_check_interval`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GlErrorCheckInterval {
+    /// Call `glGetError` after each operation. This is the default behavior.
+    EachOperation,
+
+    /// Call `glGetError` once per frame, when the buffers are swapped.
+    EachFrame,
+
+    /// Call `glGetError` once every `N` operations.
+    Operations(u32),
+}
+
+impl Default for GlErrorCheckInterval {
+    #[inline]
+    fn default() -> GlErrorCheckInterval {
+        GlErrorCheckInterval::EachOperation
+    }
+}
+
+/// Kind of an OpenGL object created or destroyed by glium.
+///
+/// See `Context::set_resource_created_callback`.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// A buffer object.
+    Buffer,
+    /// A texture object.
+    Texture,
+    /// A renderbuffer object.
+    Renderbuffer,
+    /// A framebuffer object.
+    Framebuffer,
+    /// A program object.
+    Program,
+    /// A vertex array object.
+    VertexArray,
+    /// A sampler object.
+    Sampler,
+    /// A query object.
+    Query,
+    /// A transform feedback object.
+    TransformFeedback,
+}
+
+/// Callback called when an OpenGL object is created or destroyed.
+///
+/// See `Context::set_resource_created_callback`.
+pub type ResourceCallback = Box<FnMut(ResourceKind, gl::types::GLuint)>;
+
+/// Callbacks passed to `Context::set_resource_created_callback` and
+/// `Context::set_resource_destroyed_callback`.
+struct ResourceCallbacks {
+    created: Option<ResourceCallback>,
+    destroyed: Option<ResourceCallback>,
+}
+
+/// Maximum number of operations that are remembered between two calls to `glGetError`.
+const OPERATIONS_LOG_LEN: usize = 32;
+
+/// State of the error checking when `glGetError` isn't called after each operation.
+struct ErrorCheck {
+    /// The value passed to `Context::set_error_check_interval`.
+    interval: Cell<GlErrorCheckInterval>,
+
+    /// Names of the most recent operations since the last call to `glGetError`, from the oldest
+    /// to the most recent. Contains at most `OPERATIONS_LOG_LEN` elements.
+    operations: RefCell<VecDeque<&'static str>>,
+
+    /// Total number of operations since the last call to `glGetError`, including those that
+    /// are no longer in `operations`.
+    num_operations: Cell<u32>,
+}
+
+/// Whether glium assumes that its cache of the OpenGL state is always correct.
+///
+/// See `Context::set_state_trust`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StateTrust {
+    /// Bindings are marked as unknown after running raw OpenGL code through `exec_in_context`
+    /// or `exec_gl`, and the state is queried again after another context was current. This is
+    /// the default behavior.
+    Verify,
+
+    /// The cache is never invalidated or queried again unless `Context::invalidate_state` or
+    /// `Context::notify_made_current` is called.
+    TrustCache,
+}
+
+impl Default for StateTrust {
+    #[inline]
+    fn default() -> StateTrust {
+        StateTrust::Verify
+    }
+}
+
+/// Content of a texture unit, as returned by `Context::bound_textures`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BoundTextureInfo {
+    /// OpenGL id of the texture.
+    pub texture: gl::types::GLuint,
+
+    /// Bind point of the texture, like `GL_TEXTURE_2D` or `GL_TEXTURE_CUBE_MAP`.
+    pub target: gl::types::GLenum,
+
+    /// OpenGL id of the sampler object bound to the unit, or `0` if there is none.
+    pub sampler: gl::types::GLuint,
+
+    /// Sampling parameters of the sampler object, if it has been created by glium.
+    pub sampler_behavior: Option<uniforms::SamplerBehavior>,
+
+    /// True if the texture is also attached to the framebuffer currently bound for drawing.
+    pub feedback_loop: bool,
+}
+
+/// Informations about the video memory, as returned by `Context::video_memory_info`.
+///
+/// All the values are in kilobytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VideoMemoryInfo {
+    /// Total amount of memory available to the GPU, including the memory that is shared with
+    /// the system. `None` if unknown.
+    pub total: Option<usize>,
+
+    /// Amount of video memory that is currently free.
+    ///
+    /// With `GL_ATI_meminfo`, this is the amount of free memory in the pool used for textures.
+    pub available: usize,
+
+    /// Amount of memory that is dedicated to the GPU. `None` if unknown.
+    pub dedicated: Option<usize>,
+}
+
+/// Behavior of the implementation that can be controlled with `Context::set_hint`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HintTarget {
+    /// Accuracy of the derivative functions in fragment shaders, such as `dFdx`, `dFdy` and
+    /// `fwidth`.
+    FragmentShaderDerivative,
+
+    /// Quality of the compression when uploading data to a compressed texture.
+    ///
+    /// Not available on core profiles or OpenGL ES.
+    TextureCompression,
+
+    /// Quality of the filtering used when generating mipmaps.
+    ///
+    /// Not available on core profiles.
+    GenerateMipmap,
+}
+
+/// Error that can happen when calling `Context::texture_barrier`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TextureBarrierNotSupportedError;
+
+impl fmt::Display for TextureBarrierNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for TextureBarrierNotSupportedError {
+    #[inline]
+    fn description(&self) -> &str {
+        "Texture barriers are not supported by the backend"
+    }
+}
+
+/// Error that can happen when calling `Context::set_hint`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HintNotSupportedError;
+
+impl fmt::Display for HintNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for HintNotSupportedError {
+    #[inline]
+    fn description(&self) -> &str {
+        "This hint is not supported by the backend"
+    }
+}
+
+impl<'a> CommandContext<'a> {
+    /// Gives a name to an object with `glObjectLabel`, or to a sync object with
+    /// `glObjectPtrLabel`.
+    pub fn set_object_label(&self, object: LabeledObject, label: &str)
+                            -> Result<(), debug::LabelingNotSupportedError>
+    {
+        let length = label.len() as gl::types::GLsizei;
+        let label = label.as_ptr() as *const gl::types::GLchar;
+
+        if self.version >= &Version(Api::Gl, 4, 3) || self.version >= &Version(Api::GlEs, 3, 2) ||
+           (self.version >= &Version(Api::Gl, 1, 0) && self.extensions.gl_khr_debug)
+        {
+            match object {
+                LabeledObject::Name(identifier, id) => unsafe {
+                    self.gl.ObjectLabel(identifier, id, length, label);
+                },
+                LabeledObject::Sync(sync) => unsafe {
+                    self.gl.ObjectPtrLabel(sync as *const _, length, label);
+                },
+            }
+
+            Ok(())
+
+        } else if self.extensions.gl_khr_debug {
+            match object {
+                LabeledObject::Name(identifier, id) => unsafe {
+                    self.gl.ObjectLabelKHR(identifier, id, length, label);
+                },
+                LabeledObject::Sync(sync) => unsafe {
+                    self.gl.ObjectPtrLabelKHR(sync as *const _, length, label);
+                },
+            }
+
+            Ok(())
+
+        } else {
+            Err(debug::LabelingNotSupportedError)
+        }
+    }
+
+    /// Calls the callback passed to `Context::set_resource_created_callback`, if any.
+    #[inline]
+    pub fn resource_created(&self, kind: ResourceKind, id: gl::types::GLuint) {
+        if let Some(ref mut callback) = self.resource_callbacks.borrow_mut().created {
+            callback(kind, id);
+        }
+    }
+
+    /// Calls the callback passed to `Context::set_resource_destroyed_callback`, if any.
+    #[inline]
+    pub fn resource_destroyed(&self, kind: ResourceKind, id: gl::types::GLuint) {
+        if let Some(ref mut callback) = self.resource_callbacks.borrow_mut().destroyed {
+            callback(kind, id);
+        }
+    }
+
+    /// Calls `glGetError` until there's no error left and reports the errors according to the
+    /// behavior passed to `Context::set_error_behavior`. Does nothing if the behavior is
+    /// `GlErrorBehavior::Ignore`.
+    ///
+    /// `operation` is the name of the glium operation that has just been performed. If
+    /// `glGetError` is only called from time to time, this only gives a name to the operation
+    /// that is added to the log of operations when the `CommandContext` is destroyed.
+    pub fn report_errors(&mut self, operation: &'static str) {
+        if self.error_behavior == GlErrorBehavior::Ignore {
+            return;
+        }
+
+        if self.error_check.interval.get() != GlErrorCheckInterval::EachOperation {
+            self.operation = Some(operation);
+            return;
+        }
+
+        // the number of iterations is bounded in case of a lost context, where some
+        // implementations keep returning an error
+        for _ in 0 .. 16 {
+            let error = match ::get_gl_error(self) {
+                Some(error) => error,
+                None => return,
+            };
+
+            match self.error_behavior {
+                GlErrorBehavior::Ignore => unreachable!(),
+                GlErrorBehavior::Log => {
+                    let _ = writeln!(io::stderr(), "OpenGL error {} after {}", error, operation);
+                },
+                GlErrorBehavior::Panic => {
+                    // panicking while already panicking would abort the process
+                    if !thread::panicking() {
+                        panic!("OpenGL error {} after {}", error, operation);
+                    }
+                },
+            }
+        }
+    }
+
+    /// Calls `glGetError` until there's no error left and reports the errors along with the
+    /// operations that have been logged by `report_errors` since the last check. Clears the log.
+    pub fn report_deferred_errors(&mut self) {
+        if self.error_behavior == GlErrorBehavior::Ignore {
+            return;
+        }
+
+        let mut errors = Vec::new();
+
+        // the number of iterations is bounded in case of a lost context, where some
+        // implementations keep returning an error
+        for _ in 0 .. 16 {
+            match ::get_gl_error(self) {
+                Some(error) => errors.push(error),
+                None => break,
+            }
+        }
+
+        let num_operations = self.error_check.num_operations.get();
+        self.error_check.num_operations.set(0);
+        let operations = self.error_check.operations.borrow_mut().drain(..)
+                                                     .collect::<Vec<_>>();
+
+        // the check itself isn't an operation of the next log
+        self.operation = None;
+
+        if errors.is_empty() {
+            return;
+        }
+
+        let message = format!("OpenGL error {} during the last {} operation(s); most recent \
+                               operations, from the oldest to the newest: {}",
+                              errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", "),
+                              num_operations, operations.join(", "));
+
+        match self.error_behavior {
+            GlErrorBehavior::Ignore => unreachable!(),
+            GlErrorBehavior::Log => {
+                let _ = writeln!(io::stderr(), "{}", message);
+            },
+            GlErrorBehavior::Panic => {
+                // panicking while already panicking would abort the process
+                if !thread::panicking() {
+                    panic!("{}", message);
+                }
+            },
+        }
+    }
+}
+
+/// Object that can be passed to `CommandContext::set_object_label`.
+#[derive(Debug, Copy, Clone)]
+pub enum LabeledObject {
+    /// An object designated by its namespace, for example `GL_TEXTURE`, and its name.
+    Name(gl::types::GLenum, gl::types::GLuint),
+    /// A sync object.
+    Sync(gl::types::GLsync),
+}
+
+impl<'a> Drop for CommandContext<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.error_behavior == GlErrorBehavior::Ignore {
+            return;
+        }
+
+        let threshold = match self.error_check.interval.get() {
+            GlErrorCheckInterval::EachOperation => {
+                self.report_errors("an OpenGL operation");
+                return;
+            },
+            GlErrorCheckInterval::EachFrame => None,
+            GlErrorCheckInterval::Operations(n) => Some(n),
+        };
+
+        // when errors are only checked from time to time, every operation that goes through a
+        // `CommandContext` is logged here so that it can be reported with the next error
+        let operation = match self.operation {
+            Some(operation) => operation,
+            None => return,
+        };
+
+        {
+            let mut operations = self.error_check.operations.borrow_mut();
+            if operations.len() >= OPERATIONS_LOG_LEN {
+                operations.pop_front();
+            }
+            operations.push_back(operation);
+        }
+
+        let num_operations = self.error_check.num_operations.get().saturating_add(1);
+        self.error_check.num_operations.set(num_operations);
+
+        if let Some(threshold) = threshold {
+            if num_operations >= threshold {
+                self.report_deferred_errors();
+            }
+        }
+    }
+}
+
 /// Describes the behavior that the debug output should have.
 pub enum DebugCallbackBehavior {
     /// Don't do anything. This is the default behavior in release.
@@ -825,9 +2146,18 @@ impl Default for DebugCallbackBehavior {
 }
 
 /// The callback corresponding to `DebugMessageOnError`.
-fn default_debug_callback(_: debug::Source, ty: debug::MessageType, severity: debug::Severity,
-                          _: u32, report_debug_output_errors: bool, message: &str)
+fn default_debug_callback(source: debug::Source, ty: debug::MessageType, severity: debug::Severity,
+                          id: u32, report_debug_output_errors: bool, message: &str)
 {
+    // messages inserted by glium itself are warnings about the way it is used, while the ones
+    // inserted by other libraries are none of our business
+    if let debug::Source::ThirdParty = source {
+        if id == GLIUM_DEBUG_MESSAGE_ID && report_debug_output_errors {
+            println!("Glium warning: {}", message);
+        }
+        return;
+    }
+
     match severity {
         debug::Severity::Medium => (),
         debug::Severity::High => (),
@@ -0,0 +1,453 @@
+use gl;
+
+use std::mem;
+
+use context::CommandContext;
+use context::Context;
+use context::Profile;
+use ContextExt;
+use Handle;
+use version::Api;
+use version::Version;
+
+/// Saves the part of the OpenGL state that is used for drawing when created, and restores it
+/// when destroyed. Returned by `Context::save_render_state`.
+///
+/// The saved state contains the blending, depth, stencil, face culling, scissor, color mask and
+/// viewport parameters, along with the current program and vertex array object. This is the
+/// state that glium and most rendering libraries modify, which allows you to surround code that
+/// doesn't expect its state to be changed, like a GUI renderer or a video overlay.
+///
+/// The values are queried from OpenGL and not from glium's cache of the state, and the cache
+/// is updated when they are restored. This works in both directions: glium can draw without
+/// disturbing the code around it, and foreign code can draw without disturbing glium.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// {
+///     let _guard = display.save_render_state();
+///     // ... foreign rendering code ...
+/// }
+///
+/// // the state is restored here
+/// ```
+pub struct RenderStateGuard<'a> {
+    context: &'a Context,
+    state: RenderState,
+}
+
+struct RenderState {
+    blend: bool,
+    blend_equation: (gl::types::GLenum, gl::types::GLenum),
+    blend_func: (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum, gl::types::GLenum),
+    blend_color: (f32, f32, f32, f32),
+    depth_test: bool,
+    depth_func: gl::types::GLenum,
+    depth_mask: bool,
+    stencil_test: bool,
+    stencil_func_front: (gl::types::GLenum, gl::types::GLint, gl::types::GLuint),
+    stencil_func_back: (gl::types::GLenum, gl::types::GLint, gl::types::GLuint),
+    stencil_mask_front: gl::types::GLuint,
+    stencil_mask_back: gl::types::GLuint,
+    stencil_op_front: (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum),
+    stencil_op_back: (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum),
+    cull_face: bool,
+    cull_face_mode: gl::types::GLenum,
+    front_face: gl::types::GLenum,
+    scissor_test: bool,
+    scissor: (gl::types::GLint, gl::types::GLint, gl::types::GLsizei, gl::types::GLsizei),
+    color_mask: (gl::types::GLboolean, gl::types::GLboolean, gl::types::GLboolean,
+                 gl::types::GLboolean),
+    viewport: (gl::types::GLint, gl::types::GLint, gl::types::GLsizei, gl::types::GLsizei),
+    // `None` if programs or vertex array objects are not supported
+    program: Option<gl::types::GLuint>,
+    vertex_array: Option<gl::types::GLuint>,
+}
+
+impl<'a> RenderStateGuard<'a> {
+    /// Saves the current state.
+    pub fn new(context: &'a Context) -> RenderStateGuard<'a> {
+        let mut ctxt = context.make_current();
+
+        RenderStateGuard {
+            context: context,
+            state: unsafe { capture(&mut ctxt) },
+        }
+    }
+
+    /// Restores the state that has been saved and destroys the guard. This is the same as
+    /// dropping it.
+    #[inline]
+    pub fn restore(self) {
+        // the state is restored by the destructor
+    }
+}
+
+impl<'a> Drop for RenderStateGuard<'a> {
+    #[inline]
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+        unsafe { restore(&mut ctxt, &self.state) };
+    }
+}
+
+fn supports_programs(ctxt: &CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 2, 0) || ctxt.version >= &Version(Api::GlEs, 2, 0)
+}
+
+fn supports_vertex_array_objects(ctxt: &CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+    ctxt.extensions.gl_arb_vertex_array_object || ctxt.extensions.gl_oes_vertex_array_object ||
+    ctxt.extensions.gl_apple_vertex_array_object
+}
+
+unsafe fn capture(ctxt: &mut CommandContext) -> RenderState {
+    let gl = ctxt.gl;
+
+    let get_enum = |name| -> gl::types::GLenum {
+        let mut value = 0;
+        gl.GetIntegerv(name, &mut value);
+        value as gl::types::GLenum
+    };
+
+    let get_int = |name| -> gl::types::GLint {
+        let mut value = 0;
+        gl.GetIntegerv(name, &mut value);
+        value
+    };
+
+    let get_rect = |name| {
+        let mut value: [gl::types::GLint; 4] = mem::uninitialized();
+        gl.GetIntegerv(name, value.as_mut_ptr());
+        (value[0], value[1], value[2], value[3])
+    };
+
+    let mut blend_color: [gl::types::GLfloat; 4] = mem::uninitialized();
+    gl.GetFloatv(gl::BLEND_COLOR, blend_color.as_mut_ptr());
+
+    let mut color_mask: [gl::types::GLboolean; 4] = mem::uninitialized();
+    gl.GetBooleanv(gl::COLOR_WRITEMASK, color_mask.as_mut_ptr());
+
+    let mut depth_mask = 0;
+    gl.GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+
+    RenderState {
+        blend: gl.IsEnabled(gl::BLEND) != 0,
+        blend_equation: (get_enum(gl::BLEND_EQUATION_RGB), get_enum(gl::BLEND_EQUATION_ALPHA)),
+        blend_func: (get_enum(gl::BLEND_SRC_RGB), get_enum(gl::BLEND_DST_RGB),
+                     get_enum(gl::BLEND_SRC_ALPHA), get_enum(gl::BLEND_DST_ALPHA)),
+        blend_color: (blend_color[0], blend_color[1], blend_color[2], blend_color[3]),
+        depth_test: gl.IsEnabled(gl::DEPTH_TEST) != 0,
+        depth_func: get_enum(gl::DEPTH_FUNC),
+        depth_mask: depth_mask != 0,
+        stencil_test: gl.IsEnabled(gl::STENCIL_TEST) != 0,
+        stencil_func_front: (get_enum(gl::STENCIL_FUNC), get_int(gl::STENCIL_REF),
+                             get_enum(gl::STENCIL_VALUE_MASK)),
+        stencil_func_back: (get_enum(gl::STENCIL_BACK_FUNC), get_int(gl::STENCIL_BACK_REF),
+                            get_enum(gl::STENCIL_BACK_VALUE_MASK)),
+        stencil_mask_front: get_enum(gl::STENCIL_WRITEMASK),
+        stencil_mask_back: get_enum(gl::STENCIL_BACK_WRITEMASK),
+        stencil_op_front: (get_enum(gl::STENCIL_FAIL), get_enum(gl::STENCIL_PASS_DEPTH_FAIL),
+                           get_enum(gl::STENCIL_PASS_DEPTH_PASS)),
+        stencil_op_back: (get_enum(gl::STENCIL_BACK_FAIL),
+                          get_enum(gl::STENCIL_BACK_PASS_DEPTH_FAIL),
+                          get_enum(gl::STENCIL_BACK_PASS_DEPTH_PASS)),
+        cull_face: gl.IsEnabled(gl::CULL_FACE) != 0,
+        cull_face_mode: get_enum(gl::CULL_FACE_MODE),
+        front_face: get_enum(gl::FRONT_FACE),
+        scissor_test: gl.IsEnabled(gl::SCISSOR_TEST) != 0,
+        scissor: get_rect(gl::SCISSOR_BOX),
+        color_mask: (color_mask[0], color_mask[1], color_mask[2], color_mask[3]),
+        viewport: get_rect(gl::VIEWPORT),
+        program: if supports_programs(ctxt) {
+            Some(get_enum(gl::CURRENT_PROGRAM))
+        } else {
+            None
+        },
+        vertex_array: if supports_vertex_array_objects(ctxt) {
+            Some(get_enum(gl::VERTEX_ARRAY_BINDING))
+        } else {
+            None
+        },
+    }
+}
+
+unsafe fn set_enabled(ctxt: &CommandContext, cap: gl::types::GLenum, enabled: bool) {
+    if enabled {
+        ctxt.gl.Enable(cap);
+    } else {
+        ctxt.gl.Disable(cap);
+    }
+}
+
+unsafe fn restore(ctxt: &mut CommandContext, state: &RenderState) {
+    set_enabled(ctxt, gl::BLEND, state.blend);
+    ctxt.gl.BlendEquationSeparate(state.blend_equation.0, state.blend_equation.1);
+    ctxt.gl.BlendFuncSeparate(state.blend_func.0, state.blend_func.1, state.blend_func.2,
+                              state.blend_func.3);
+    let (r, g, b, a) = state.blend_color;
+    ctxt.gl.BlendColor(r, g, b, a);
+
+    set_enabled(ctxt, gl::DEPTH_TEST, state.depth_test);
+    ctxt.gl.DepthFunc(state.depth_func);
+    ctxt.gl.DepthMask(if state.depth_mask { gl::TRUE } else { gl::FALSE });
+
+    set_enabled(ctxt, gl::STENCIL_TEST, state.stencil_test);
+    let (func, reference, mask) = state.stencil_func_front;
+    ctxt.gl.StencilFuncSeparate(gl::FRONT, func, reference, mask);
+    let (func, reference, mask) = state.stencil_func_back;
+    ctxt.gl.StencilFuncSeparate(gl::BACK, func, reference, mask);
+    ctxt.gl.StencilMaskSeparate(gl::FRONT, state.stencil_mask_front);
+    ctxt.gl.StencilMaskSeparate(gl::BACK, state.stencil_mask_back);
+    let (fail, depth_fail, pass) = state.stencil_op_front;
+    ctxt.gl.StencilOpSeparate(gl::FRONT, fail, depth_fail, pass);
+    let (fail, depth_fail, pass) = state.stencil_op_back;
+    ctxt.gl.StencilOpSeparate(gl::BACK, fail, depth_fail, pass);
+
+    set_enabled(ctxt, gl::CULL_FACE, state.cull_face);
+    ctxt.gl.CullFace(state.cull_face_mode);
+    // glium doesn't track `glFrontFace`, and never changes it
+    ctxt.gl.FrontFace(state.front_face);
+
+    set_enabled(ctxt, gl::SCISSOR_TEST, state.scissor_test);
+    let (x, y, width, height) = state.scissor;
+    ctxt.gl.Scissor(x, y, width, height);
+
+    let (r, g, b, a) = state.color_mask;
+    ctxt.gl.ColorMask(r, g, b, a);
+
+    let (x, y, width, height) = state.viewport;
+    ctxt.gl.Viewport(x, y, width, height);
+
+    if let Some(program) = state.program {
+        ctxt.gl.UseProgram(program);
+    }
+
+    if let Some(vertex_array) = state.vertex_array {
+        if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+            ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+            ctxt.extensions.gl_arb_vertex_array_object
+        {
+            ctxt.gl.BindVertexArray(vertex_array);
+        } else if ctxt.extensions.gl_oes_vertex_array_object {
+            ctxt.gl.BindVertexArrayOES(vertex_array);
+        } else if ctxt.extensions.gl_apple_vertex_array_object {
+            ctxt.gl.BindVertexArrayAPPLE(vertex_array);
+        }
+    }
+
+    update_cache(ctxt, state);
+}
+
+/// Writes the values of `state` in glium's cache of the OpenGL state.
+fn update_cache(ctxt: &mut CommandContext, state: &RenderState) {
+    ctxt.state.enabled_blend = state.blend;
+    ctxt.state.enabled_blend_indexed = None;
+    ctxt.state.blend_equation = state.blend_equation;
+    ctxt.state.blend_func = state.blend_func;
+    ctxt.state.blend_color = state.blend_color;
+
+    ctxt.state.enabled_depth_test = state.depth_test;
+    ctxt.state.depth_func = state.depth_func;
+    ctxt.state.depth_mask = state.depth_mask;
+
+    ctxt.state.enabled_stencil_test = state.stencil_test;
+    ctxt.state.stencil_func_front = state.stencil_func_front;
+    ctxt.state.stencil_func_back = state.stencil_func_back;
+    ctxt.state.stencil_mask_front = state.stencil_mask_front;
+    ctxt.state.stencil_mask_back = state.stencil_mask_back;
+    ctxt.state.stencil_op_front = state.stencil_op_front;
+    ctxt.state.stencil_op_back = state.stencil_op_back;
+
+    ctxt.state.enabled_cull_face = state.cull_face;
+    ctxt.state.cull_face = state.cull_face_mode;
+
+    ctxt.state.enabled_scissor_test = state.scissor_test;
+    ctxt.state.scissor = Some(state.scissor);
+
+    ctxt.state.color_mask = state.color_mask;
+
+    ctxt.state.viewport = Some(state.viewport);
+    ctxt.state.viewport_f = None;
+
+    if let Some(program) = state.program {
+        ctxt.state.program = Handle::Id(program);
+    }
+
+    if let Some(vertex_array) = state.vertex_array {
+        ctxt.state.vertex_array = vertex_array;
+    }
+}
+
+/// Updates all of glium's cache of the OpenGL state with the values that OpenGL currently uses.
+///
+/// This is used when another context may have been current, in which case code that doesn't go
+/// through glium may have changed anything. On top of the state saved by `RenderStateGuard`,
+/// this queries the other capabilities, parameters and clear values that glium caches, as long
+/// as the backend supports them. Values that the backend doesn't support can't have been
+/// changed either. The objects bindings must be marked as unknown separately with
+/// `GlState::invalidate_bindings`.
+pub unsafe fn reload(ctxt: &mut CommandContext) {
+    let state = capture(ctxt);
+    update_cache(ctxt, &state);
+
+    let gl = ctxt.gl;
+
+    let is_enabled = |cap| gl.IsEnabled(cap) != 0;
+
+    let get_enum = |name| -> gl::types::GLenum {
+        let mut value = 0;
+        gl.GetIntegerv(name, &mut value);
+        value as gl::types::GLenum
+    };
+
+    let get_int = |name| -> gl::types::GLint {
+        let mut value = 0;
+        gl.GetIntegerv(name, &mut value);
+        value
+    };
+
+    let get_float = |name| -> gl::types::GLfloat {
+        let mut value = 0.0;
+        gl.GetFloatv(name, &mut value);
+        value
+    };
+
+    let core = match ctxt.capabilities.profile {
+        Some(Profile::Core) => true,
+        _ => false,
+    };
+
+    ctxt.state.enabled_dither = is_enabled(gl::DITHER);
+    ctxt.state.enabled_polygon_offset_fill = is_enabled(gl::POLYGON_OFFSET_FILL);
+    ctxt.state.enabled_sample_alpha_to_coverage = is_enabled(gl::SAMPLE_ALPHA_TO_COVERAGE);
+    ctxt.state.enabled_sample_coverage = is_enabled(gl::SAMPLE_COVERAGE);
+
+    let mut clear_color: [gl::types::GLfloat; 4] = mem::uninitialized();
+    gl.GetFloatv(gl::COLOR_CLEAR_VALUE, clear_color.as_mut_ptr());
+    ctxt.state.clear_color = (clear_color[0], clear_color[1], clear_color[2], clear_color[3]);
+    ctxt.state.clear_depth = get_float(gl::DEPTH_CLEAR_VALUE);
+    ctxt.state.clear_stencil = get_int(gl::STENCIL_CLEAR_VALUE);
+
+    let mut depth_range: [gl::types::GLfloat; 2] = mem::uninitialized();
+    gl.GetFloatv(gl::DEPTH_RANGE, depth_range.as_mut_ptr());
+    ctxt.state.depth_range = (depth_range[0], depth_range[1]);
+
+    ctxt.state.line_width = get_float(gl::LINE_WIDTH);
+    ctxt.state.active_texture = get_enum(gl::ACTIVE_TEXTURE) - gl::TEXTURE0;
+
+    // the parameters below only exist on desktop OpenGL
+    if ctxt.version >= &Version(Api::Gl, 1, 0) {
+        ctxt.state.enabled_multisample = is_enabled(gl::MULTISAMPLE);
+        ctxt.state.enabled_line_smooth = is_enabled(gl::LINE_SMOOTH);
+        ctxt.state.enabled_polygon_smooth = is_enabled(gl::POLYGON_SMOOTH);
+        ctxt.state.smooth.0 = get_enum(gl::LINE_SMOOTH_HINT);
+        ctxt.state.smooth.1 = get_enum(gl::POLYGON_SMOOTH_HINT);
+        ctxt.state.point_size = get_float(gl::POINT_SIZE);
+
+        // the compatibility profile returns the modes of the front and back faces
+        let mut polygon_mode: [gl::types::GLint; 2] = [0; 2];
+        gl.GetIntegerv(gl::POLYGON_MODE, polygon_mode.as_mut_ptr());
+        ctxt.state.polygon_mode = polygon_mode[0] as gl::types::GLenum;
+
+        if !core {
+            ctxt.state.enabled_point_smooth = is_enabled(gl::POINT_SMOOTH);
+            ctxt.state.smooth.2 = get_enum(gl::POINT_SMOOTH_HINT);
+            ctxt.state.texture_compression_hint = get_enum(gl::TEXTURE_COMPRESSION_HINT);
+        }
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 2, 0) {
+        ctxt.state.enabled_program_point_size = is_enabled(gl::PROGRAM_POINT_SIZE);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 2, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+       ctxt.extensions.gl_arb_fragment_shader || ctxt.extensions.gl_oes_standard_derivatives
+    {
+        ctxt.state.fragment_shader_derivative_hint =
+                                            get_enum(gl::FRAGMENT_SHADER_DERIVATIVE_HINT);
+    }
+
+    if (ctxt.version >= &Version(Api::Gl, 1, 4) && !core) ||
+       ctxt.version >= &Version(Api::GlEs, 2, 0)
+    {
+        ctxt.state.generate_mipmap_hint = get_enum(gl::GENERATE_MIPMAP_HINT);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+       ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
+    {
+        ctxt.state.enabled_framebuffer_srgb = is_enabled(gl::FRAMEBUFFER_SRGB);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_ext_transform_feedback {
+        ctxt.state.enabled_rasterizer_discard = is_enabled(gl::RASTERIZER_DISCARD);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 1) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+       ctxt.extensions.gl_arb_es3_compatibility
+    {
+        ctxt.state.enabled_primitive_fixed_restart =
+                                            is_enabled(gl::PRIMITIVE_RESTART_FIXED_INDEX);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+       ctxt.extensions.gl_arb_texture_multisample
+    {
+        ctxt.state.enabled_sample_mask = is_enabled(gl::SAMPLE_MASK);
+        let mut sample_mask = 0;
+        gl.GetIntegeri_v(gl::SAMPLE_MASK_VALUE, 0, &mut sample_mask);
+        ctxt.state.sample_mask = sample_mask as gl::types::GLbitfield;
+    }
+
+    if ctxt.extensions.gl_amd_depth_clamp_separate {
+        ctxt.state.enabled_depth_clamp_near = is_enabled(gl::DEPTH_CLAMP_NEAR_AMD);
+        ctxt.state.enabled_depth_clamp_far = is_enabled(gl::DEPTH_CLAMP_FAR_AMD);
+    } else if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
+              ctxt.extensions.gl_nv_depth_clamp
+    {
+        let enabled = is_enabled(gl::DEPTH_CLAMP);
+        ctxt.state.enabled_depth_clamp_near = enabled;
+        ctxt.state.enabled_depth_clamp_far = enabled;
+    }
+
+    if ctxt.extensions.gl_nv_conservative_raster {
+        ctxt.state.enabled_conservative_rasterization =
+                                            is_enabled(gl::CONSERVATIVE_RASTERIZATION_NV);
+    } else if ctxt.extensions.gl_intel_conservative_rasterization {
+        ctxt.state.enabled_conservative_rasterization =
+                                            is_enabled(gl::CONSERVATIVE_RASTERIZATION_INTEL);
+    }
+
+    if ctxt.extensions.gl_nv_conservative_raster_dilate {
+        ctxt.state.conservative_raster_dilate = get_float(gl::CONSERVATIVE_RASTER_DILATE_NV);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 2) || ctxt.extensions.gl_arb_provoking_vertex ||
+       ctxt.extensions.gl_ext_provoking_vertex
+    {
+        ctxt.state.provoking_vertex = get_enum(gl::PROVOKING_VERTEX);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_color_buffer_float {
+        ctxt.state.clamp_color = get_enum(gl::CLAMP_READ_COLOR);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+       ctxt.extensions.gl_arb_tessellation_shader
+    {
+        ctxt.state.patch_patch_vertices = get_int(gl::PATCH_VERTICES);
+    }
+
+    if ctxt.version >= &Version(Api::GlEs, 3, 2) || ctxt.extensions.gl_arb_es3_2_compatibility ||
+       ctxt.extensions.gl_oes_primitive_bounding_box ||
+       ctxt.extensions.gl_ext_primitive_bounding_box
+    {
+        let mut bbox: [gl::types::GLfloat; 8] = mem::uninitialized();
+        gl.GetFloatv(gl::PRIMITIVE_BOUNDING_BOX, bbox.as_mut_ptr());
+        ctxt.state.primitive_bounding_box = (bbox[0], bbox[1], bbox[2], bbox[3],
+                                             bbox[4], bbox[5], bbox[6], bbox[7]);
+    }
+}
@@ -0,0 +1,137 @@
+use std::error::Error;
+use std::fmt;
+
+use context::Context;
+use context::Profile;
+use CapabilitiesSource;
+use version::Version;
+
+/// Version, profile and flags that an OpenGL context must have.
+///
+/// Pass this to `DisplayBuild::build_glium_with_requirements` to make sure that you get a context
+/// with a known feature set, instead of whatever the driver creates by default. The requirements
+/// are forwarded to the backend, and the context that has been obtained is then checked against
+/// them. If it doesn't satisfy them, building the display fails with
+/// `GliumCreationError::RequirementsNotMet`, whose message contains the version that has been
+/// obtained.
+///
+/// # Example
+///
+/// ```no_run
+/// # extern crate glium;
+/// # fn main() {
+/// use glium::{Api, ContextRequirements, DisplayBuild, Profile, Version};
+///
+/// let requirements = ContextRequirements::new()
+///                         .with_gl_version_request(Version(Api::Gl, 4, 3))
+///                         .with_profile(Profile::Core);
+///
+/// let display = glium::glutin::WindowBuilder::new()
+///                         .build_glium_with_requirements(requirements)
+///                         .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ContextRequirements {
+    /// Minimum version of the context. The API (OpenGL or OpenGL ES) must match too.
+    pub version: Option<Version>,
+
+    /// Profile of the context. Only relevant for OpenGL 3.2 and above.
+    pub profile: Option<Profile>,
+
+    /// Whether the context must be forward-compatible, which means that the deprecated functions
+    /// are removed from it, or must not be.
+    ///
+    /// Backends can't always request this flag, in which case it is only checked.
+    pub forward_compatible: Option<bool>,
+}
+
+impl ContextRequirements {
+    /// Builds requirements that any context satisfies.
+    #[inline]
+    pub fn new() -> ContextRequirements {
+        Default::default()
+    }
+
+    /// Requires a minimum version of OpenGL or OpenGL ES.
+    #[inline]
+    pub fn with_gl_version_request(mut self, version: Version) -> ContextRequirements {
+        self.version = Some(version);
+        self
+    }
+
+    /// Requires a core or a compatibility profile.
+    #[inline]
+    pub fn with_profile(mut self, profile: Profile) -> ContextRequirements {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Requires the context to be forward-compatible or not.
+    #[inline]
+    pub fn with_forward_compatible(mut self, forward_compatible: bool) -> ContextRequirements {
+        self.forward_compatible = Some(forward_compatible);
+        self
+    }
+
+    /// Checks whether a context satisfies the requirements.
+    ///
+    /// Returns a description of the requirements that are not satisfied otherwise.
+    pub fn check(&self, context: &Context) -> Result<(), String> {
+        let mut result = Vec::with_capacity(0);
+
+        let version = *context.get_opengl_version();
+        if let Some(required) = self.version {
+            if !(version >= required) {
+                result.push(format!("Version {:?} has been requested, but {:?} has been \
+                                     obtained", required, version));
+            }
+        }
+
+        let capabilities = context.get_capabilities();
+
+        if let Some(required) = self.profile {
+            if capabilities.profile != Some(required) {
+                result.push(format!("A {:?} profile has been requested, but {:?} has been \
+                                     obtained with version {:?}", required,
+                                    capabilities.profile, version));
+            }
+        }
+
+        if let Some(required) = self.forward_compatible {
+            if capabilities.forward_compatible != required {
+                result.push(format!("A context with forward compatibility set to {} has been \
+                                     requested, but it is {} with version {:?}", required,
+                                    capabilities.forward_compatible, version));
+            }
+        }
+
+        if result.is_empty() {
+            Ok(())
+        } else {
+            Err(result.join("\n"))
+        }
+    }
+}
+
+/// Error that can be returned by the default implementation of
+/// `DisplayBuild::build_glium_with_requirements` when the context doesn't satisfy the
+/// requirements.
+///
+/// Contains the list of the requirements that are not satisfied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequirementsNotMetError(pub String);
+
+impl fmt::Display for RequirementsNotMetError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}: {}", self.description(), self.0)
+    }
+}
+
+impl Error for RequirementsNotMetError {
+    #[inline]
+    fn description(&self) -> &str {
+        "The context doesn't satisfy the requirements"
+    }
+}
@@ -0,0 +1,205 @@
+use gl;
+
+use std::mem;
+
+use context::CommandContext;
+use version::Api;
+use version::Version;
+
+/// State of the OpenGL context, as returned by `Context::capture_gl_state`.
+///
+/// The values are queried from the OpenGL implementation and not from glium's cache of the
+/// state, which means that you can use this struct to detect state that has been modified
+/// behind glium's back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlStateSnapshot {
+    /// Value of `GL_CURRENT_PROGRAM`.
+    pub program: gl::types::GLuint,
+
+    /// Framebuffer currently bound to `GL_DRAW_FRAMEBUFFER`.
+    pub draw_framebuffer: gl::types::GLuint,
+
+    /// Framebuffer currently bound to `GL_READ_FRAMEBUFFER`.
+    pub read_framebuffer: gl::types::GLuint,
+
+    /// Value of `GL_ACTIVE_TEXTURE`.
+    pub active_texture: gl::types::GLenum,
+
+    /// Texture bound to `GL_TEXTURE_2D` for each texture unit.
+    pub textures_2d: Vec<gl::types::GLuint>,
+
+    /// Whether `GL_BLEND` is enabled.
+    pub blend: bool,
+
+    /// Whether `GL_BLEND` is enabled for each draw buffer, as returned by `glIsEnabledi`. Empty
+    /// if the backend doesn't support OpenGL 3.0 or OpenGL ES 3.2.
+    pub blend_indexed: Vec<bool>,
+
+    /// Values of `GL_BLEND_EQUATION_RGB` and `GL_BLEND_EQUATION_ALPHA`.
+    pub blend_equation: (gl::types::GLenum, gl::types::GLenum),
+
+    /// Values of `GL_BLEND_SRC_RGB`, `GL_BLEND_DST_RGB`, `GL_BLEND_SRC_ALPHA` and
+    /// `GL_BLEND_DST_ALPHA`.
+    pub blend_func: (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum, gl::types::GLenum),
+
+    /// Whether `GL_DEPTH_TEST` is enabled.
+    pub depth_test: bool,
+
+    /// Value of `GL_DEPTH_FUNC`.
+    pub depth_func: gl::types::GLenum,
+
+    /// Value of `GL_DEPTH_WRITEMASK`.
+    pub depth_mask: bool,
+
+    /// Whether `GL_STENCIL_TEST` is enabled.
+    pub stencil_test: bool,
+
+    /// Function, reference value and mask of the stencil test for front faces.
+    pub stencil_func_front: (gl::types::GLenum, gl::types::GLint, gl::types::GLuint),
+
+    /// Function, reference value and mask of the stencil test for back faces.
+    pub stencil_func_back: (gl::types::GLenum, gl::types::GLint, gl::types::GLuint),
+
+    /// Values of `GL_STENCIL_WRITEMASK` and `GL_STENCIL_BACK_WRITEMASK`.
+    pub stencil_write_mask: (gl::types::GLuint, gl::types::GLuint),
+
+    /// Value of `GL_COLOR_WRITEMASK`.
+    pub color_mask: (bool, bool, bool, bool),
+
+    /// Whether `GL_CULL_FACE` is enabled.
+    pub cull_face: bool,
+
+    /// Value of `GL_CULL_FACE_MODE`.
+    pub cull_face_mode: gl::types::GLenum,
+
+    /// Value of `GL_FRONT_FACE`.
+    pub front_face: gl::types::GLenum,
+
+    /// Whether `GL_SCISSOR_TEST` is enabled.
+    pub scissor_test: bool,
+
+    /// Value of `GL_SCISSOR_BOX`, as `(left, bottom, width, height)`.
+    pub scissor: (gl::types::GLint, gl::types::GLint, gl::types::GLint, gl::types::GLint),
+
+    /// Value of `GL_VIEWPORT`, as `(left, bottom, width, height)`.
+    pub viewport: (gl::types::GLint, gl::types::GLint, gl::types::GLint, gl::types::GLint),
+}
+
+macro_rules! diff_fields {
+    ($a:expr, $b:expr, $($field:ident),+) => ({
+        let mut result = Vec::new();
+        $(
+            if $a.$field != $b.$field {
+                result.push(format!("{}: {:?} -> {:?}", stringify!($field), $a.$field,
+                                    $b.$field));
+            }
+        )+
+        result
+    });
+}
+
+impl GlStateSnapshot {
+    /// Returns a description of each value that differs between `self` and `other`, in the
+    /// form `name: old -> new`.
+    ///
+    /// Capturing the state before and after a draw call and comparing the two snapshots is an
+    /// easy way to see what a draw call changes.
+    pub fn diff(&self, other: &GlStateSnapshot) -> Vec<String> {
+        diff_fields!(self, other, program, draw_framebuffer, read_framebuffer, active_texture,
+                     textures_2d, blend, blend_indexed, blend_equation, blend_func, depth_test,
+                     depth_func, depth_mask, stencil_test, stencil_func_front, stencil_func_back,
+                     stencil_write_mask, color_mask, cull_face, cull_face_mode, front_face,
+                     scissor_test, scissor, viewport)
+    }
+}
+
+/// Queries the current state of the context.
+pub fn capture(ctxt: &mut CommandContext) -> GlStateSnapshot {
+    unsafe {
+        let gl = ctxt.gl;
+
+        let get_enum = |name| -> gl::types::GLenum {
+            let mut value = 0;
+            gl.GetIntegerv(name, &mut value);
+            value as gl::types::GLenum
+        };
+
+        let get_int = |name| -> gl::types::GLint {
+            let mut value = 0;
+            gl.GetIntegerv(name, &mut value);
+            value
+        };
+
+        let get_rect = |name| {
+            let mut value: [gl::types::GLint; 4] = mem::uninitialized();
+            gl.GetIntegerv(name, value.as_mut_ptr());
+            (value[0], value[1], value[2], value[3])
+        };
+
+        let (draw_framebuffer, read_framebuffer) =
+            if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+               ctxt.extensions.gl_arb_framebuffer_object
+            {
+                (get_enum(gl::DRAW_FRAMEBUFFER_BINDING), get_enum(gl::READ_FRAMEBUFFER_BINDING))
+            } else {
+                let fb = get_enum(gl::FRAMEBUFFER_BINDING);
+                (fb, fb)
+            };
+
+        let active_texture = get_enum(gl::ACTIVE_TEXTURE);
+        let textures_2d = (0 .. ctxt.capabilities.max_combined_texture_image_units).map(|unit| {
+            gl.ActiveTexture(gl::TEXTURE0 + unit as gl::types::GLenum);
+            get_enum(gl::TEXTURE_BINDING_2D)
+        }).collect();
+        gl.ActiveTexture(active_texture);
+
+        let blend_indexed = if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                               ctxt.version >= &Version(Api::GlEs, 3, 2)
+        {
+            (0 .. ctxt.capabilities.max_draw_buffers).map(|index| {
+                gl.IsEnabledi(gl::BLEND, index as gl::types::GLuint) != 0
+            }).collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut color_mask: [gl::types::GLboolean; 4] = mem::uninitialized();
+        gl.GetBooleanv(gl::COLOR_WRITEMASK, color_mask.as_mut_ptr());
+
+        let mut depth_mask = 0;
+        gl.GetBooleanv(gl::DEPTH_WRITEMASK, &mut depth_mask);
+
+        GlStateSnapshot {
+            program: get_enum(gl::CURRENT_PROGRAM),
+            draw_framebuffer: draw_framebuffer,
+            read_framebuffer: read_framebuffer,
+            active_texture: active_texture,
+            textures_2d: textures_2d,
+            blend: gl.IsEnabled(gl::BLEND) != 0,
+            blend_indexed: blend_indexed,
+            blend_equation: (get_enum(gl::BLEND_EQUATION_RGB),
+                             get_enum(gl::BLEND_EQUATION_ALPHA)),
+            blend_func: (get_enum(gl::BLEND_SRC_RGB), get_enum(gl::BLEND_DST_RGB),
+                         get_enum(gl::BLEND_SRC_ALPHA), get_enum(gl::BLEND_DST_ALPHA)),
+            depth_test: gl.IsEnabled(gl::DEPTH_TEST) != 0,
+            depth_func: get_enum(gl::DEPTH_FUNC),
+            depth_mask: depth_mask != 0,
+            stencil_test: gl.IsEnabled(gl::STENCIL_TEST) != 0,
+            stencil_func_front: (get_enum(gl::STENCIL_FUNC), get_int(gl::STENCIL_REF),
+                                 get_enum(gl::STENCIL_VALUE_MASK)),
+            stencil_func_back: (get_enum(gl::STENCIL_BACK_FUNC), get_int(gl::STENCIL_BACK_REF),
+                                get_enum(gl::STENCIL_BACK_VALUE_MASK)),
+            stencil_write_mask: (get_enum(gl::STENCIL_WRITEMASK),
+                                 get_enum(gl::STENCIL_BACK_WRITEMASK)),
+            color_mask: (color_mask[0] != 0, color_mask[1] != 0, color_mask[2] != 0,
+                         color_mask[3] != 0),
+            cull_face: gl.IsEnabled(gl::CULL_FACE) != 0,
+            cull_face_mode: get_enum(gl::CULL_FACE_MODE),
+            front_face: get_enum(gl::FRONT_FACE),
+            scissor_test: gl.IsEnabled(gl::SCISSOR_TEST) != 0,
+            scissor: get_rect(gl::SCISSOR_BOX),
+            viewport: get_rect(gl::VIEWPORT),
+        }
+    }
+}
@@ -15,6 +15,11 @@ pub struct GlState {
     /// Whether GL_BLEND is enabled
     pub enabled_blend: bool,
 
+    /// If `Some`, GL_BLEND has been enabled or disabled for individual draw buffers with
+    /// `glEnablei` and `glDisablei`, and each bit tells whether it is enabled for the
+    /// corresponding draw buffer. `enabled_blend` must be ignored in this situation.
+    pub enabled_blend_indexed: Option<u32>,
+
     /// Whether GL_CULL_FACE is enabled
     pub enabled_cull_face: bool,
 
@@ -24,6 +29,9 @@ pub struct GlState {
     /// Whether GL_DEBUG_OUTPUT_SYNCHRONOUS is enabled
     pub enabled_debug_output_synchronous: bool,
 
+    /// Whether GL_CONSERVATIVE_RASTERIZATION_NV or GL_CONSERVATIVE_RASTERIZATION_INTEL is enabled
+    pub enabled_conservative_rasterization: bool,
+
     /// Whether GL_DEPTH_TEST is enabled
     pub enabled_depth_test: bool,
 
@@ -57,12 +65,18 @@ pub struct GlState {
     /// Whether GL_SAMPLE_COVERAGE is enabled
     pub enabled_sample_coverage: bool,
 
+    /// Whether GL_SAMPLE_MASK is enabled
+    pub enabled_sample_mask: bool,
+
     /// Whether GL_SCISSOR_TEST is enabled
     pub enabled_scissor_test: bool,
 
     /// Whether GL_STENCIL_TEST is enabled
     pub enabled_stencil_test: bool,
 
+    /// Whether GL_POINT_SMOOTH is enabled
+    pub enabled_point_smooth: bool,
+
     /// Whether GL_LINE_SMOOTH is enabled
     pub enabled_line_smooth: bool,
 
@@ -195,6 +209,10 @@ pub struct GlState {
     pub viewport: Option<(gl::types::GLint, gl::types::GLint,
                           gl::types::GLsizei, gl::types::GLsizei)>,
 
+    /// The latest values passed to `glViewportIndexedf` for the viewport 0. `None` means unknown
+    /// or that `glViewport` has been called since then.
+    pub viewport_f: Option<(f32, f32, f32, f32)>,
+
     /// The latest values passed to `glScissor`. `None` means unknown.
     pub scissor: Option<(gl::types::GLint, gl::types::GLint,
                          gl::types::GLsizei, gl::types::GLsizei)>,
@@ -211,8 +229,20 @@ pub struct GlState {
     /// The latest value passed to `glPolygonMode`.
     pub polygon_mode: gl::types::GLenum,
 
-    /// The latest value passed to `glHint` for smoothing.
-    pub smooth: (gl::types::GLenum, gl::types::GLenum),
+    /// The latest value passed to `glSampleMaski` for the first word of the mask.
+    pub sample_mask: gl::types::GLbitfield,
+
+    /// The latest values passed to `glHint` for smoothing lines, polygons and points.
+    pub smooth: (gl::types::GLenum, gl::types::GLenum, gl::types::GLenum),
+
+    /// The latest value passed to `glHint` with `GL_FRAGMENT_SHADER_DERIVATIVE_HINT`.
+    pub fragment_shader_derivative_hint: gl::types::GLenum,
+
+    /// The latest value passed to `glHint` with `GL_TEXTURE_COMPRESSION_HINT`.
+    pub texture_compression_hint: gl::types::GLenum,
+
+    /// The latest value passed to `glHint` with `GL_GENERATE_MIPMAP_HINT`.
+    pub generate_mipmap_hint: gl::types::GLenum,
 
     /// The latest value passed to `glProvokingVertex`.
     pub provoking_vertex: gl::types::GLenum,
@@ -237,6 +267,10 @@ pub struct GlState {
     /// List of texture units.
     pub texture_units: SmallVec<[TextureUnitState ; 32]>,
 
+    /// Id of the texture bound to each image unit, or 0 if no texture is bound. The list only
+    /// contains the image units that have been used.
+    pub image_units: SmallVec<[gl::types::GLuint ; 8]>,
+
     /// Current query being used for GL_SAMPLES_PASSED​.
     pub samples_passed_query: gl::types::GLuint,
 
@@ -246,8 +280,8 @@ pub struct GlState {
     /// Current query being used for GL_ANY_SAMPLES_PASSED​_CONSERVATIVE.
     pub any_samples_passed_conservative_query: gl::types::GLuint,
 
-    /// Current query being used for GL_PRIMITIVES_GENERATED​.
-    pub primitives_generated_query: gl::types::GLuint,
+    /// Current queries being used for GL_PRIMITIVES_GENERATED​, indexed by vertex stream.
+    pub primitives_generated_query: [gl::types::GLuint; 4],
 
     /// Current query being used for GL_TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN​.
     pub transform_feedback_primitives_written_query: gl::types::GLuint,
@@ -266,9 +300,29 @@ pub struct GlState {
     // TODO: move this inside transform feedback objects
     pub transform_feedback_paused: bool,
 
+    /// The buffer that is written by the current transform feedback session, or `0` if
+    /// `transform_feedback_enabled` is None.
+    pub transform_feedback_buffer: gl::types::GLuint,
+
+    /// Transform feedback objects that have been unbound while capturing. Their capture has
+    /// been paused and is resumed when they are bound again. Contains the id of the object,
+    /// the buffer being written and the primitive type.
+    pub paused_transform_feedback_objects: Vec<(gl::types::GLuint, gl::types::GLuint,
+                                                gl::types::GLenum)>,
+
+    /// The latest transform feedback object bound to `GL_TRANSFORM_FEEDBACK`.
+    pub transform_feedback_object: gl::types::GLuint,
+
     /// The latest value passed to `glPrimitiveBoundingBox`.
     pub primitive_bounding_box: (f32, f32, f32, f32, f32, f32, f32, f32),
 
+    /// The latest value of `GL_CONSERVATIVE_RASTER_DILATE_NV`.
+    pub conservative_raster_dilate: f32,
+
+    /// Whether textures or buffers have been uploaded since the latest fence inserted for
+    /// `Context::flush_uploads`.
+    pub pending_uploads: bool,
+
     /// Current draw call ID.
     /// We maintain a counter that is incremented at each draw call.
     pub next_draw_call_id: u64,
@@ -328,6 +382,9 @@ pub struct GlState {
     /// The draw call ID of the latest call to `glMemoryBarrier` with
     /// `GL_QUERY_BUFFER_BARRIER_BIT`.
     pub latest_memory_barrier_query_buffer: u64,
+
+    /// The draw call ID of the latest call to `glTextureBarrier`.
+    pub latest_texture_barrier: u64,
 }
 
 /// State of a texture unit (the one designated by `glActiveTexture`).
@@ -336,8 +393,18 @@ pub struct TextureUnitState {
     /// Id of the texture.
     pub texture: gl::types::GLuint,
 
+    /// Bind point of the texture, like `GL_TEXTURE_2D`. Irrelevant if `texture` is `0`.
+    pub target: gl::types::GLenum,
+
     /// Id of the sampler.
     pub sampler: gl::types::GLuint,
+
+    /// Id of the latest draw call or compute dispatch that has used the texture of this unit.
+    pub latest_use: u64,
+
+    /// Id of the texture that has been pinned to this unit with `Context::pin_texture`, or `0`.
+    /// Other textures are only bound to this unit if there is no other choice.
+    pub pinned_texture: gl::types::GLuint,
 }
 
 /// State of an indexed buffer target (`glBindBufferRange`/`glBindBufferBase`).
@@ -353,6 +420,67 @@ pub struct IndexedBufferState {
     pub size: gl::types::GLsizeiptr,
 }
 
+impl GlState {
+    /// Marks all the objects bindings, the viewport, the scissor box and the pixel store
+    /// alignments as unknown, so that glium binds or sets them again the next time it needs them.
+    ///
+    /// This should be called after performing raw OpenGL calls that modify some bindings without
+    /// updating the corresponding fields. Enabled capabilities (blending, depth test, etc.) and
+    /// the active texture unit can't be marked as unknown and must always be either restored or
+    /// reported by updating the corresponding fields.
+    pub fn invalidate_bindings(&mut self) {
+        // glium never uses an object whose name is `!0`, so any binding will be redone
+        const UNKNOWN: gl::types::GLuint = !0;
+
+        self.program = match self.program {
+            Handle::Id(_) => Handle::Id(UNKNOWN),
+            Handle::Handle(_) => Handle::Handle(UNKNOWN as gl::types::GLhandleARB),
+        };
+
+        self.vertex_array = UNKNOWN;
+        self.array_buffer_binding = UNKNOWN;
+        self.pixel_pack_buffer_binding = UNKNOWN;
+        self.pixel_unpack_buffer_binding = UNKNOWN;
+        self.uniform_buffer_binding = UNKNOWN;
+        self.copy_read_buffer_binding = UNKNOWN;
+        self.copy_write_buffer_binding = UNKNOWN;
+        self.dispatch_indirect_buffer_binding = UNKNOWN;
+        self.draw_indirect_buffer_binding = UNKNOWN;
+        self.query_buffer_binding = UNKNOWN;
+        self.texture_buffer_binding = UNKNOWN;
+        self.atomic_counter_buffer_binding = UNKNOWN;
+        self.shader_storage_buffer_binding = UNKNOWN;
+
+        for binding in self.indexed_uniform_buffer_bindings.iter_mut()
+                           .chain(self.indexed_atomic_counter_buffer_bindings.iter_mut())
+                           .chain(self.indexed_shader_storage_buffer_bindings.iter_mut())
+                           .chain(self.indexed_transform_feedback_buffer_bindings.iter_mut())
+        {
+            binding.buffer = UNKNOWN;
+        }
+
+        self.read_framebuffer = UNKNOWN;
+        self.draw_framebuffer = UNKNOWN;
+        self.default_framebuffer_read = None;
+        self.renderbuffer = UNKNOWN;
+
+        for unit in self.texture_units.iter_mut() {
+            unit.texture = UNKNOWN;
+            unit.sampler = UNKNOWN;
+        }
+
+        for unit in self.image_units.iter_mut() {
+            *unit = UNKNOWN;
+        }
+
+        self.viewport = None;
+        self.viewport_f = None;
+        self.scissor = None;
+        self.pixel_store_unpack_alignment = 0;
+        self.pixel_store_pack_alignment = 0;
+    }
+}
+
 /// Builds the `GlState` corresponding to a newly-created OpenGL context.
 impl Default for GlState {
     fn default() -> GlState {
@@ -366,6 +494,8 @@ impl Default for GlState {
             lost_context: false,
 
             enabled_blend: false,
+            enabled_blend_indexed: None,
+            enabled_conservative_rasterization: false,
             enabled_cull_face: false,
             enabled_debug_output: None,
             enabled_debug_output_synchronous: false,
@@ -379,8 +509,10 @@ impl Default for GlState {
             enabled_rasterizer_discard: false,
             enabled_sample_alpha_to_coverage: false,
             enabled_sample_coverage: false,
+            enabled_sample_mask: false,
             enabled_scissor_test: false,
             enabled_stencil_test: false,
+            enabled_point_smooth: false,
             enabled_line_smooth: false,
             enabled_polygon_smooth: false,
             enabled_primitive_fixed_restart: false,
@@ -425,12 +557,17 @@ impl Default for GlState {
             blend_func: (gl::ONE, gl::ZERO, gl::ONE, gl::ZERO),
             blend_color: (0.0, 0.0, 0.0, 0.0),
             viewport: None,
+            viewport_f: None,
             scissor: None,
             line_width: 1.0,
             point_size: 1.0,
             cull_face: gl::BACK,
             polygon_mode: gl::FILL,
-            smooth: (gl::DONT_CARE, gl::DONT_CARE),
+            sample_mask: !0,
+            smooth: (gl::DONT_CARE, gl::DONT_CARE, gl::DONT_CARE),
+            fragment_shader_derivative_hint: gl::DONT_CARE,
+            texture_compression_hint: gl::DONT_CARE,
+            generate_mipmap_hint: gl::DONT_CARE,
             provoking_vertex: gl::LAST_VERTEX_CONVENTION,
             pixel_store_unpack_alignment: 4,
             pixel_store_pack_alignment: 4,
@@ -438,17 +575,23 @@ impl Default for GlState {
             patch_patch_vertices: 3,
             active_texture: 0,
             texture_units: small_vec_one(),
+            image_units: SmallVec::new(),
             samples_passed_query: 0,
             any_samples_passed_query: 0,
             any_samples_passed_conservative_query: 0,
-            primitives_generated_query: 0,
+            primitives_generated_query: [0; 4],
             transform_feedback_primitives_written_query: 0,
             time_elapsed_query: 0,
             conditional_render: None,
             transform_feedback_enabled: None,
             transform_feedback_paused: false,
+            transform_feedback_buffer: 0,
+            paused_transform_feedback_objects: Vec::new(),
+            transform_feedback_object: 0,
             primitive_bounding_box: (-1.0, -1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0),
+            conservative_raster_dilate: 0.0,
 
+            pending_uploads: false,
             next_draw_call_id: 1,
             latest_memory_barrier_vertex_attrib_array: 1,
             latest_memory_barrier_element_array: 1,
@@ -464,6 +607,7 @@ impl Default for GlState {
             latest_memory_barrier_atomic_counter: 1,
             latest_memory_barrier_shader_storage: 1,
             latest_memory_barrier_query_buffer: 1,
+            latest_texture_barrier: 0,
         }
     }
 }
@@ -473,7 +617,10 @@ impl Default for TextureUnitState {
     fn default() -> TextureUnitState {
         TextureUnitState {
             texture: 0,
+            target: 0,
             sampler: 0,
+            latest_use: 0,
+            pinned_texture: 0,
         }
     }
 }
@@ -5,15 +5,58 @@
 
 use backend::Facade;
 use context::Context;
+use context::LabeledObject;
+use context::ResourceKind;
 use ContextExt;
 use version::Api;
 use version::Version;
 use gl;
+use std::error::Error;
+use std::fmt;
 use std::rc::Rc;
 use std::mem;
 
 pub use context::DebugCallbackBehavior;
 
+/// Objects that can be given a name.
+///
+/// The name is shown by OpenGL debuggers like RenderDoc and is used in the messages of the
+/// debug output, which makes it easier to know which object a message is about.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let texture: glium::Texture2d = unsafe { std::mem::uninitialized() };
+/// use glium::debug::Labeled;
+/// texture.label("shadow map").ok();
+/// ```
+pub trait Labeled {
+    /// Gives a name to the object.
+    ///
+    /// Returns `Err` if the backend doesn't support this functionnality, which requires
+    /// OpenGL 4.3, OpenGL ES 3.2 or the `GL_KHR_debug` extension. You can choose whether
+    /// to call `.unwrap()` if you want to make sure that it works, or `.ok()` if you don't care.
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError>;
+}
+
+/// Error that happens when the backend doesn't support naming objects.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LabelingNotSupportedError;
+
+impl fmt::Display for LabelingNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for LabelingNotSupportedError {
+    #[inline]
+    fn description(&self) -> &str {
+        "Naming objects is not supported by the backend"
+    }
+}
+
 /// Represents a callback that can be used for the debug output feature of OpenGL.
 ///
 /// The first three parameters are self-explanatory. The fourth parameter is an identifier for this
@@ -150,6 +193,10 @@ impl TimestampQuery {
             None
         };
 
+        if let Some(id) = id {
+            ctxt.resource_created(ResourceKind::Query, id);
+        }
+
         id.map(|q| TimestampQuery {
             context: facade.get_context().clone(),
             id: q
@@ -191,6 +238,7 @@ impl TimestampQuery {
         use std::mem;
 
         let ctxt = self.context.make_current();
+        ctxt.resource_destroyed(ResourceKind::Query, self.id);
 
         if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
             unsafe {
@@ -213,3 +261,11 @@ impl TimestampQuery {
         }
     }
 }
+
+impl Labeled for TimestampQuery {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.context.make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::QUERY, self.id), label)
+    }
+}
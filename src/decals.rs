@@ -0,0 +1,393 @@
+/*!
+Deferred decals projected on the existing content of a scene.
+
+*This module is only available if the `decals` feature of glium is enabled.*
+
+A decal is a texture that is projected on the geometry that has already been drawn, for example
+to add bullet holes or footprints without modifying the meshes. The `DecalPass` draws a box
+that represents the volume of the decal, and for each pixel covered by the box it reconstructs
+the position of the scene from a depth texture and samples the decal texture at this position.
+
+The volume of a decal is described by a *decal projection matrix*, which transforms world
+coordinates into the `[-1, 1]` cube of the decal. The `x` and `y` coordinates are used to sample
+the texture, and the `z` coordinate is the direction in which the decal is projected. An
+orthographic projection gives a box, and a perspective projection gives a frustum.
+
+## Stencil
+
+Only the pixels where the scene intersects the volume of the decal must be modified. The pass
+is done in two steps:
+
+ - The faces of the volume are drawn without modifying the color buffer. With the depth test
+   enabled, back faces increment the stencil buffer and front faces decrement it when they are
+   behind the scene (this is the so-called *z-fail* technique). A non-zero value remains where
+   the scene is inside the volume, even if the camera itself is inside.
+ - The back faces of the volume are then drawn where the stencil value is not zero, and the
+   stencil value is reset to zero at the same time.
+
+This means that the surface you draw on must have a depth-stencil buffer that contains the depth
+of the scene, and that the stencil buffer must be filled with zeroes.
+
+OpenGL forbids sampling a texture that is attached to the framebuffer that is being drawn
+to. The depth texture that you pass to `draw` must therefore be a separate copy of the depth of
+the scene, usually a texture of the G-buffer.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let mut target: glium::framebuffer::SimpleFrameBuffer = unsafe { std::mem::uninitialized() };
+# let depth: glium::texture::DepthTexture2d = unsafe { std::mem::uninitialized() };
+# let decal: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+# let view_projection = [[0.0; 4]; 4];
+# let decal_projection = [[0.0; 4]; 4];
+let decals = glium::decals::DecalPass::new(&display).unwrap();
+decals.draw(&mut target, &depth, view_projection, decal_projection, &decal).unwrap();
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use draw_parameters::{Blend, BackfaceCullingMode, Depth, DepthTest, DrawParameters};
+use draw_parameters::{Stencil, StencilOperation, StencilTest};
+use index::{self, IndexBuffer, PrimitiveType};
+use program::{Program, ProgramChooserCreationError};
+use texture::{DepthTexture2d, Texture2d};
+use uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerWrapFunction};
+use vertex::{self, VertexBuffer};
+use DrawError;
+use Surface;
+
+#[derive(Copy, Clone)]
+struct CubeVertex {
+    position: [f32; 3],
+}
+
+implement_vertex!(CubeVertex, position);
+
+/// Draws decals on a surface that contains the depth of a scene.
+///
+/// Building a `DecalPass` compiles programs and allocates buffers, so you should build it once
+/// and reuse it for each frame.
+pub struct DecalPass {
+    mark_program: Program,
+    decal_program: Program,
+    vertex_buffer: VertexBuffer<CubeVertex>,
+    index_buffer: IndexBuffer<u16>,
+}
+
+impl DecalPass {
+    /// Builds the programs and the buffers used to draw decals.
+    ///
+    /// The shaders require GLSL 1.40.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<DecalPass, DecalError> where F: Facade {
+        let mark_program = try!(program!(facade,
+            140 => {
+                vertex: VERTEX_SHADER,
+                fragment: "
+                    #version 140
+
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = vec4(0.0);
+                    }
+                "
+            },
+        ));
+
+        let decal_program = try!(program!(facade,
+            140 => {
+                vertex: VERTEX_SHADER,
+                fragment: "
+                    #version 140
+
+                    uniform sampler2D scene_depth;
+                    uniform sampler2D decal_texture;
+                    uniform mat4 inverse_view_projection;
+                    uniform mat4 decal_projection;
+                    uniform vec2 target_size;
+
+                    out vec4 f_color;
+
+                    void main() {
+                        vec2 uv = gl_FragCoord.xy / target_size;
+                        float depth = texture(scene_depth, uv).r;
+
+                        vec4 world = inverse_view_projection * vec4(uv * 2.0 - 1.0,
+                                                                    depth * 2.0 - 1.0, 1.0);
+                        world /= world.w;
+
+                        vec4 decal = decal_projection * world;
+                        decal.xyz /= decal.w;
+
+                        // not discarding, so that the stencil value is reset anyway
+                        if (any(greaterThan(abs(decal.xyz), vec3(1.0)))) {
+                            f_color = vec4(0.0);
+                            return;
+                        }
+
+                        f_color = texture(decal_texture, decal.xy * 0.5 + 0.5);
+                    }
+                "
+            },
+        ));
+
+        let vertex_buffer = try!(VertexBuffer::new(facade, &[
+            CubeVertex { position: [-1.0, -1.0, -1.0] },
+            CubeVertex { position: [1.0, -1.0, -1.0] },
+            CubeVertex { position: [1.0, 1.0, -1.0] },
+            CubeVertex { position: [-1.0, 1.0, -1.0] },
+            CubeVertex { position: [-1.0, -1.0, 1.0] },
+            CubeVertex { position: [1.0, -1.0, 1.0] },
+            CubeVertex { position: [1.0, 1.0, 1.0] },
+            CubeVertex { position: [-1.0, 1.0, 1.0] },
+        ]));
+
+        // faces are counter-clockwise when seen from the outside
+        let index_buffer = try!(IndexBuffer::new(facade, PrimitiveType::TrianglesList, &[
+            4, 5, 6, 4, 6, 7,
+            1, 0, 3, 1, 3, 2,
+            5, 1, 2, 5, 2, 6,
+            0, 4, 7, 0, 7, 3,
+            7, 6, 2, 7, 2, 3,
+            0, 1, 5, 0, 5, 4u16,
+        ]));
+
+        Ok(DecalPass {
+            mark_program: mark_program,
+            decal_program: decal_program,
+            vertex_buffer: vertex_buffer,
+            index_buffer: index_buffer,
+        })
+    }
+
+    /// Projects a decal on the content of a surface.
+    ///
+    /// - `scene_depth` contains the depth of the scene, and must have the same dimensions as
+    ///   the surface.
+    /// - `view_projection` is the matrix that was used to draw the scene, and transforms world
+    ///   coordinates into normalized device coordinates.
+    /// - `decal_projection` transforms world coordinates into the `[-1, 1]` cube of the decal.
+    /// - `decal_texture` is alpha-blended over the surface.
+    ///
+    /// The matrices are column-major, like the matrices passed as uniforms. The surface must
+    /// have a depth-stencil buffer that contains the depth of the scene and whose stencil is
+    /// filled with zeroes, which is still the case when this function returns.
+    pub fn draw<S>(&self, surface: &mut S, scene_depth: &DepthTexture2d,
+                   view_projection: [[f32; 4]; 4], decal_projection: [[f32; 4]; 4],
+                   decal_texture: &Texture2d) -> Result<(), DecalError>
+                   where S: Surface
+    {
+        let inverse_view_projection = try!(invert(&view_projection)
+                                               .ok_or(DecalError::NonInvertibleMatrix));
+        let inverse_decal_projection = try!(invert(&decal_projection)
+                                                .ok_or(DecalError::NonInvertibleMatrix));
+        let matrix = multiply(&view_projection, &inverse_decal_projection);
+
+        // first step: marking the pixels where the scene is inside the volume
+        let mark_params = DrawParameters {
+            depth: Depth {
+                test: DepthTest::IfLess,
+                write: false,
+                .. Default::default()
+            },
+            stencil: Stencil {
+                pass_depth_fail_operation_clockwise: StencilOperation::IncrementWrap,
+                pass_depth_fail_operation_counter_clockwise: StencilOperation::DecrementWrap,
+                .. Default::default()
+            },
+            color_mask: (false, false, false, false),
+            backface_culling: BackfaceCullingMode::CullingDisabled,
+            .. Default::default()
+        };
+
+        try!(surface.draw(&self.vertex_buffer, &self.index_buffer, &self.mark_program,
+                          &uniform! { matrix: matrix }, &mark_params));
+
+        // second step: drawing the decal on the marked pixels and clearing the stencil
+        let decal_params = DrawParameters {
+            stencil: Stencil {
+                test_clockwise: StencilTest::IfNotEqual { mask: 0xffffffff },
+                depth_pass_operation_clockwise: StencilOperation::Zero,
+                test_counter_clockwise: StencilTest::IfNotEqual { mask: 0xffffffff },
+                depth_pass_operation_counter_clockwise: StencilOperation::Zero,
+                .. Default::default()
+            },
+            blend: Blend::alpha_blending(),
+            backface_culling: BackfaceCullingMode::CullCounterClockwise,
+            .. Default::default()
+        };
+
+        let (width, height) = surface.get_dimensions();
+
+        let uniforms = uniform! {
+            matrix: matrix,
+            inverse_view_projection: inverse_view_projection,
+            decal_projection: decal_projection,
+            target_size: [width as f32, height as f32],
+            scene_depth: scene_depth.sampled()
+                                    .minify_filter(MinifySamplerFilter::Nearest)
+                                    .magnify_filter(MagnifySamplerFilter::Nearest),
+            decal_texture: decal_texture.sampled()
+                                        .wrap_function(SamplerWrapFunction::Clamp),
+        };
+
+        try!(surface.draw(&self.vertex_buffer, &self.index_buffer, &self.decal_program,
+                          &uniforms, &decal_params));
+
+        Ok(())
+    }
+}
+
+/// Vertex shader shared by both steps. Places the unit cube in the volume of the decal.
+const VERTEX_SHADER: &'static str = "
+    #version 140
+
+    uniform mat4 matrix;
+
+    in vec3 position;
+
+    void main() {
+        gl_Position = matrix * vec4(position, 1.0);
+    }
+";
+
+/// Multiplies two column-major matrices.
+fn multiply(a: &[[f32; 4]; 4], b: &[[f32; 4]; 4]) -> [[f32; 4]; 4] {
+    let mut result = [[0.0; 4]; 4];
+    for col in 0 .. 4 {
+        for row in 0 .. 4 {
+            result[col][row] = (0 .. 4).map(|k| a[k][row] * b[col][k]).sum();
+        }
+    }
+    result
+}
+
+/// Inverts a matrix. Returns `None` if its determinant is zero.
+///
+/// The inverse of the transpose is the transpose of the inverse, so this works for both
+/// column-major and row-major matrices.
+fn invert(m: &[[f32; 4]; 4]) -> Option<[[f32; 4]; 4]> {
+    // determinant of the 3x3 matrix made of the given columns and rows
+    let minor = |c0: usize, c1: usize, c2: usize, r0: usize, r1: usize, r2: usize| -> f32 {
+        m[c0][r0] * (m[c1][r1] * m[c2][r2] - m[c2][r1] * m[c1][r2]) -
+        m[c1][r0] * (m[c0][r1] * m[c2][r2] - m[c2][r1] * m[c0][r2]) +
+        m[c2][r0] * (m[c0][r1] * m[c1][r2] - m[c1][r1] * m[c0][r2])
+    };
+
+    let others = |i: usize| -> (usize, usize, usize) {
+        match i {
+            0 => (1, 2, 3),
+            1 => (0, 2, 3),
+            2 => (0, 1, 3),
+            _ => (0, 1, 2),
+        }
+    };
+
+    let mut result = [[0.0; 4]; 4];
+    for col in 0 .. 4 {
+        for row in 0 .. 4 {
+            let (c0, c1, c2) = others(col);
+            let (r0, r1, r2) = others(row);
+            let sign = if (col + row) % 2 == 0 { 1.0 } else { -1.0 };
+            // the adjugate is the transpose of the cofactor matrix
+            result[row][col] = sign * minor(c0, c1, c2, r0, r1, r2);
+        }
+    }
+
+    let determinant: f32 = (0 .. 4).map(|row| m[0][row] * result[row][0]).sum();
+    if determinant == 0.0 {
+        return None;
+    }
+
+    for col in result.iter_mut() {
+        for value in col.iter_mut() {
+            *value /= determinant;
+        }
+    }
+
+    Some(result)
+}
+
+/// Error that can happen while building or drawing decals.
+#[derive(Debug)]
+pub enum DecalError {
+    /// Failed to compile the shaders.
+    ProgramCreationError(ProgramChooserCreationError),
+
+    /// Failed to create the vertex buffer of the volume.
+    VertexBufferCreationError(vertex::BufferCreationError),
+
+    /// Failed to create the index buffer of the volume.
+    IndexBufferCreationError(index::BufferCreationError),
+
+    /// The view-projection matrix or the decal projection matrix can't be inverted.
+    NonInvertibleMatrix,
+
+    /// Failed to draw on the surface.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for DecalError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for DecalError {
+    fn description(&self) -> &str {
+        use self::DecalError::*;
+        match *self {
+            ProgramCreationError(_) =>
+                "Failed to compile the shaders",
+            VertexBufferCreationError(_) =>
+                "Failed to create the vertex buffer of the volume",
+            IndexBufferCreationError(_) =>
+                "Failed to create the index buffer of the volume",
+            NonInvertibleMatrix =>
+                "The view-projection matrix or the decal projection matrix can't be inverted",
+            DrawError(_) =>
+                "Failed to draw on the surface",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        use self::DecalError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            VertexBufferCreationError(ref err) => Some(err),
+            IndexBufferCreationError(ref err) => Some(err),
+            NonInvertibleMatrix => None,
+            DrawError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramChooserCreationError> for DecalError {
+    #[inline]
+    fn from(err: ProgramChooserCreationError) -> DecalError {
+        DecalError::ProgramCreationError(err)
+    }
+}
+
+impl From<vertex::BufferCreationError> for DecalError {
+    #[inline]
+    fn from(err: vertex::BufferCreationError) -> DecalError {
+        DecalError::VertexBufferCreationError(err)
+    }
+}
+
+impl From<index::BufferCreationError> for DecalError {
+    #[inline]
+    fn from(err: index::BufferCreationError) -> DecalError {
+        DecalError::IndexBufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for DecalError {
+    #[inline]
+    fn from(err: DrawError) -> DecalError {
+        DecalError::DrawError(err)
+    }
+}
@@ -1,3 +1,5 @@
+use std::cmp;
+
 use context::CommandContext;
 use version::Api;
 use version::Version;
@@ -12,19 +14,51 @@ use gl;
 /// you to choose how it should merge the two.
 ///
 /// If you want to add transparent objects one over another, use
-/// `Blend::alpha_blending()`.
+/// `Blend::alpha_blending()`, or `Blend::premultiplied_alpha()` if the colors are already
+/// multiplied by their alpha. `straight_alpha()` also keeps a correct alpha in the target, and
+/// `additive()`, `multiply()`, `screen()` and `disabled()` provide other common effects.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Blend {
     /// The blending function for color channels.
     pub color: BlendingFunction,
     /// The blending function for alpha channels.
     pub alpha: BlendingFunction,
-    /// A constant color that can be used in the blending functions.
+    /// A constant color that can be used in the blending functions, through the
+    /// `ConstantColor`, `OneMinusConstantColor`, `ConstantAlpha` and `OneMinusConstantAlpha`
+    /// factors.
+    ///
+    /// It is ignored if the blending functions don't use any of these factors.
     pub constant_value: (f32, f32, f32, f32),
 }
 
 impl Blend {
-    /// Returns a blend effect to add transparent objects over others.
+    /// Returns true if one of the blending factors uses the second output of the fragment shader.
+    ///
+    /// See `LinearBlendingFactor::uses_dual_source`.
+    #[inline]
+    pub fn uses_dual_source(&self) -> bool {
+        fn check(function: &BlendingFunction) -> bool {
+            match *function {
+                BlendingFunction::AlwaysReplace |
+                BlendingFunction::Min |
+                BlendingFunction::Max => false,
+                BlendingFunction::Addition { source, destination } |
+                BlendingFunction::Subtraction { source, destination } |
+                BlendingFunction::ReverseSubtraction { source, destination } =>
+                    source.uses_dual_source() || destination.uses_dual_source(),
+            }
+        }
+
+        check(&self.color) || check(&self.alpha)
+    }
+
+    /// Returns a blend effect to add transparent objects over others, when the color
+    /// components of the fragments are not multiplied by their alpha ("straight" alpha).
+    ///
+    /// The alpha channel is blended like the color channels, which means that the resulting
+    /// alpha is `src_alpha * src_alpha + dst_alpha * (1 - src_alpha)`. This is fine when the
+    /// alpha of the target is ignored, for example when drawing on an opaque window. Use
+    /// `straight_alpha()` if the alpha of the target matters.
     pub fn alpha_blending() -> Blend {
         Blend {
             color: BlendingFunction::Addition {
@@ -38,6 +72,108 @@ impl Blend {
             constant_value: (0.0, 0.0, 0.0, 0.0)
         }
     }
+
+    /// Returns a blend effect to add transparent objects over others, when the color
+    /// components of the fragments are not multiplied by their alpha, that also keeps a correct
+    /// alpha in the target.
+    ///
+    /// The colors are blended like with `alpha_blending()`, but the resulting alpha is
+    /// `src_alpha + dst_alpha * (1 - src_alpha)`, which is the coverage of the two layers. This
+    /// means that the content of a transparent render target has premultiplied colors
+    /// afterwards, and must be composited with `premultiplied_alpha()`.
+    pub fn straight_alpha() -> Blend {
+        Blend {
+            color: BlendingFunction::Addition {
+                source: LinearBlendingFactor::SourceAlpha,
+                destination: LinearBlendingFactor::OneMinusSourceAlpha,
+            },
+            alpha: BlendingFunction::Addition {
+                source: LinearBlendingFactor::One,
+                destination: LinearBlendingFactor::OneMinusSourceAlpha,
+            },
+            .. Default::default()
+        }
+    }
+
+    /// Returns a blend effect to add transparent objects over others, when the color
+    /// components of the fragments are already multiplied by their alpha.
+    ///
+    /// The source color is added as it is and the destination is attenuated by the source
+    /// alpha, for both the color and the alpha channels. Use this for textures with
+    /// premultiplied alpha and for render targets that have been drawn to with
+    /// `straight_alpha()`.
+    pub fn premultiplied_alpha() -> Blend {
+        Blend {
+            color: BlendingFunction::Addition {
+                source: LinearBlendingFactor::One,
+                destination: LinearBlendingFactor::OneMinusSourceAlpha,
+            },
+            alpha: BlendingFunction::Addition {
+                source: LinearBlendingFactor::One,
+                destination: LinearBlendingFactor::OneMinusSourceAlpha,
+            },
+            .. Default::default()
+        }
+    }
+
+    /// Returns a blend effect that adds the source to the destination, for example for lights
+    /// or particles.
+    ///
+    /// The source is not multiplied by its alpha. Multiply the output of your fragment shader
+    /// by the alpha if you want the opacity to be taken into account.
+    pub fn additive() -> Blend {
+        Blend {
+            color: BlendingFunction::Addition {
+                source: LinearBlendingFactor::One,
+                destination: LinearBlendingFactor::One,
+            },
+            alpha: BlendingFunction::Addition {
+                source: LinearBlendingFactor::One,
+                destination: LinearBlendingFactor::One,
+            },
+            .. Default::default()
+        }
+    }
+
+    /// Returns a blend effect that multiplies the destination by the source, which darkens
+    /// the destination. The alpha of the destination is left untouched.
+    pub fn multiply() -> Blend {
+        Blend {
+            color: BlendingFunction::Addition {
+                source: LinearBlendingFactor::DestinationColor,
+                destination: LinearBlendingFactor::Zero,
+            },
+            alpha: BlendingFunction::Addition {
+                source: LinearBlendingFactor::Zero,
+                destination: LinearBlendingFactor::One,
+            },
+            .. Default::default()
+        }
+    }
+
+    /// Returns a blend effect that inverts both colors, multiplies them and inverts the result,
+    /// which lightens the destination. The alpha channels are combined like with
+    /// `premultiplied_alpha()`.
+    pub fn screen() -> Blend {
+        Blend {
+            color: BlendingFunction::Addition {
+                source: LinearBlendingFactor::One,
+                destination: LinearBlendingFactor::OneMinusSourceColor,
+            },
+            alpha: BlendingFunction::Addition {
+                source: LinearBlendingFactor::One,
+                destination: LinearBlendingFactor::OneMinusSourceAlpha,
+            },
+            .. Default::default()
+        }
+    }
+
+    /// Returns a blend effect that overwrites the destination with the source. This is the
+    /// same as the default value.
+    #[inline]
+    pub fn disabled() -> Blend {
+        Default::default()
+    }
 }
 
 impl Default for Blend {
@@ -51,7 +187,7 @@ impl Default for Blend {
 }
 
 /// Function that the GPU will use for blending.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum BlendingFunction {
     /// Simply overwrite the destination pixel with the source pixel.
     ///
@@ -119,7 +255,7 @@ pub enum BlendingFunction {
 }
 
 /// Indicates which value to multiply each component with.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum LinearBlendingFactor {
     /// Multiply the source or destination component by zero, which always
     /// gives `0.0`.
@@ -165,22 +301,69 @@ pub enum LinearBlendingFactor {
     OneMinusDestinationAlpha,
 
     /// Multiply the source or destination component by the corresponding value
-    /// in `Blend::const_value`.
+    /// in `Blend::constant_value`.
     ConstantColor,
 
-    /// Multiply the source or destination compoent by `1.0` minus the corresponding
-    /// value in `Blend::const_value`.
+    /// Multiply the source or destination component by `1.0` minus the corresponding
+    /// value in `Blend::constant_value`.
     OneMinusConstantColor,
 
-    /// Multiply the source or destination component by the alpha value of `Blend::const_value`.
+    /// Multiply the source or destination component by the alpha value of
+    /// `Blend::constant_value`.
     ConstantAlpha,
 
-    /// Multiply the source or destination componet by `1.0` minus the alpha value of
-    /// `Blend::const_value`.
+    /// Multiply the source or destination component by `1.0` minus the alpha value of
+    /// `Blend::constant_value`.
     OneMinusConstantAlpha,
+
+    /// Multiply the source or destination component by its corresponding value
+    /// in the second output of the fragment shader.
+    ///
+    /// This is called *dual-source blending* and requires OpenGL 3.3 or
+    /// `GL_ARB_blend_func_extended`. The program must have been created with
+    /// `LinkOptions::dual_source_outputs`, or the second output must be declared with
+    /// `layout(location = 0, index = 1)`. Dual-source blending can't be used when drawing to
+    /// multiple render targets.
+    SourceOneColor,
+
+    /// Equivalent to `1 - SourceOneColor`.
+    OneMinusSourceOneColor,
+
+    /// Multiply the source or destination component by the alpha value of the second output of
+    /// the fragment shader.
+    ///
+    /// See `SourceOneColor`.
+    SourceOneAlpha,
+
+    /// Equivalent to `1 - SourceOneAlpha`.
+    OneMinusSourceOneAlpha,
 }
 
 impl LinearBlendingFactor {
+    /// Returns true if the factor uses `Blend::constant_value`.
+    #[inline]
+    pub fn uses_constant_value(&self) -> bool {
+        match *self {
+            LinearBlendingFactor::ConstantColor |
+            LinearBlendingFactor::OneMinusConstantColor |
+            LinearBlendingFactor::ConstantAlpha |
+            LinearBlendingFactor::OneMinusConstantAlpha => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the factor uses the second output of the fragment shader.
+    #[inline]
+    pub fn uses_dual_source(&self) -> bool {
+        match *self {
+            LinearBlendingFactor::SourceOneColor |
+            LinearBlendingFactor::OneMinusSourceOneColor |
+            LinearBlendingFactor::SourceOneAlpha |
+            LinearBlendingFactor::OneMinusSourceOneAlpha => true,
+            _ => false,
+        }
+    }
+
     fn to_glenum(&self) -> gl::types::GLenum {
         match *self {
             LinearBlendingFactor::Zero => gl::ZERO,
@@ -198,11 +381,17 @@ impl LinearBlendingFactor {
             LinearBlendingFactor::OneMinusConstantColor => gl::ONE_MINUS_CONSTANT_COLOR,
             LinearBlendingFactor::ConstantAlpha => gl::CONSTANT_ALPHA,
             LinearBlendingFactor::OneMinusConstantAlpha => gl::ONE_MINUS_CONSTANT_ALPHA,
+            LinearBlendingFactor::SourceOneColor => gl::SRC1_COLOR,
+            LinearBlendingFactor::OneMinusSourceOneColor => gl::ONE_MINUS_SRC1_COLOR,
+            LinearBlendingFactor::SourceOneAlpha => gl::SRC1_ALPHA,
+            LinearBlendingFactor::OneMinusSourceOneAlpha => gl::ONE_MINUS_SRC1_ALPHA,
         }
     }
 }
 
-pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), DrawError> {
+pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend, targets: Option<u32>)
+                     -> Result<(), DrawError>
+{
     #[inline(always)]
     fn blend_eq(ctxt: &mut CommandContext, blending_function: BlendingFunction)
                 -> Result<gl::types::GLenum, DrawError>
@@ -256,16 +445,14 @@ pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), Draw
            (blend.color, blend.alpha)
     {
         // Both color and alpha always replace. This equals no blending.
-        if ctxt.state.enabled_blend {
+        if ctxt.state.enabled_blend || ctxt.state.enabled_blend_indexed.is_some() {
             unsafe { ctxt.gl.Disable(gl::BLEND); }
             ctxt.state.enabled_blend = false;
+            ctxt.state.enabled_blend_indexed = None;
         }
 
     } else {
-        if !ctxt.state.enabled_blend {
-            unsafe { ctxt.gl.Enable(gl::BLEND); }
-            ctxt.state.enabled_blend = true;
-        }
+        try!(sync_blend_enable(ctxt, targets));
 
         let (color_eq, alpha_eq) = (try!(blend_eq(ctxt, blend.color)),
                                     try!(blend_eq(ctxt, blend.alpha)));
@@ -280,23 +467,20 @@ pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), Draw
         let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha)
             .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
 
-        // Updating the blending color if necessary.
-        if color_factor_src == LinearBlendingFactor::ConstantColor ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_dst == LinearBlendingFactor::ConstantColor ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha
+        if color_factor_src.uses_dual_source() || color_factor_dst.uses_dual_source() ||
+           alpha_factor_src.uses_dual_source() || alpha_factor_dst.uses_dual_source()
+        {
+            if !(ctxt.version >= &Version(Api::Gl, 3, 3)) &&
+               !ctxt.extensions.gl_arb_blend_func_extended
+            {
+                return Err(DrawError::BlendingParameterNotSupported);
+            }
+        }
+
+        // Updating the blending color if necessary. The value is ignored if no factor uses it,
+        // in order to avoid useless state changes.
+        if color_factor_src.uses_constant_value() || color_factor_dst.uses_constant_value() ||
+           alpha_factor_src.uses_constant_value() || alpha_factor_dst.uses_constant_value()
         {
             if ctxt.state.blend_color != blend.constant_value {
                 let (r, g, b, a) = blend.constant_value;
@@ -325,3 +509,47 @@ pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), Draw
 
     Ok(())
 }
+
+/// Enables `GL_BLEND` on all the draw buffers if `targets` is `None`, or only on the draw
+/// buffers whose bit is set otherwise.
+fn sync_blend_enable(ctxt: &mut CommandContext, targets: Option<u32>) -> Result<(), DrawError> {
+    let targets = match targets {
+        None => {
+            if !ctxt.state.enabled_blend || ctxt.state.enabled_blend_indexed.is_some() {
+                unsafe { ctxt.gl.Enable(gl::BLEND); }
+                ctxt.state.enabled_blend = true;
+                ctxt.state.enabled_blend_indexed = None;
+            }
+
+            return Ok(());
+        },
+        Some(t) => t,
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0)) && !(ctxt.version >= &Version(Api::GlEs, 3, 2)) {
+        return Err(DrawError::BlendingParameterNotSupported);
+    }
+
+    let num_buffers = cmp::min(ctxt.capabilities.max_draw_buffers, 32) as u32;
+    let all_buffers = if num_buffers == 32 { !0 } else { (1 << num_buffers) - 1 };
+    let targets = targets & all_buffers;
+
+    let current = match ctxt.state.enabled_blend_indexed {
+        Some(current) => current,
+        None if ctxt.state.enabled_blend => all_buffers,
+        None => 0,
+    };
+
+    for index in 0 .. num_buffers {
+        let bit = 1 << index;
+
+        if (targets & bit) != 0 && (current & bit) == 0 {
+            unsafe { ctxt.gl.Enablei(gl::BLEND, index); }
+        } else if (targets & bit) == 0 && (current & bit) != 0 {
+            unsafe { ctxt.gl.Disablei(gl::BLEND, index); }
+        }
+    }
+
+    ctxt.state.enabled_blend_indexed = Some(targets);
+    Ok(())
+}
@@ -32,11 +32,16 @@ pub struct Depth {
     /// specify here.
     ///
     /// The two values must be between `0.0` and `1.0`, anything outside this range will result
-    /// in a panic. By default the depth range is `(0.0, 1.0)`.
+    /// in a `DrawError::InvalidDepthRange` error. By default the depth range is `(0.0, 1.0)`.
     ///
     /// The first value of the tuple must be the "near" value, where `-1.0` will be mapped.
     /// The second value must be the "far" value, where `1.0` will be mapped.
-    /// It is possible for the "near" value to be greater than the "far" value.
+    /// It is possible for the "near" value to be greater than the "far" value, which reverses
+    /// the mapping.
+    ///
+    /// Since the range is part of the draw parameters, you can draw an overlay in a small
+    /// range such as `(0.0, 0.1)` and the rest of the scene in `(0.1, 1.0)`, so that the overlay
+    /// is always in front of the scene without having to clear the depth buffer in between.
     pub range: (f32, f32),
 
     /// Sets whether the depth values of samples should be clamped to `0.0` and `1.0`.
@@ -77,7 +82,7 @@ impl Default for Depth {
 ///
 /// If you don't have a depth buffer available, you can only pass `Overwrite`. Glium detects if
 /// you pass any other value and reports an error.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum DepthTest {
     /// Never replace the target pixel.
     ///
@@ -140,7 +145,7 @@ impl DepthTest {
 }
 
 /// Specifies whether the depth value of samples should be clamped to `0.0` or `1.0`.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum DepthClamp {
     /// Do not clamp. Samples with values outside of the `[0.0, 1.0]` range will be discarded.
     ///
@@ -244,8 +249,9 @@ pub fn sync_depth(ctxt: &mut CommandContext, depth: &Depth) -> Result<(), DrawEr
     }
 
     // depth range
-    if depth.range.0 < 0.0 || depth.range.0 > 1.0 ||
-       depth.range.1 < 0.0 || depth.range.1 > 1.0
+    // written this way so that NaNs are rejected as well
+    if !(depth.range.0 >= 0.0 && depth.range.0 <= 1.0 &&
+         depth.range.1 >= 0.0 && depth.range.1 <= 1.0)
     {
         return Err(DrawError::InvalidDepthRange);
     }
@@ -253,7 +259,11 @@ pub fn sync_depth(ctxt: &mut CommandContext, depth: &Depth) -> Result<(), DrawEr
     if depth.range != ctxt.state.depth_range {
         // TODO: WebGL requires depth.range.1 > depth.range.0
         unsafe {
-            ctxt.gl.DepthRange(depth.range.0 as f64, depth.range.1 as f64);
+            if ctxt.version >= &Version(Api::GlEs, 2, 0) {
+                ctxt.gl.DepthRangef(depth.range.0, depth.range.1);
+            } else {
+                ctxt.gl.DepthRange(depth.range.0 as f64, depth.range.1 as f64);
+            }
         }
         ctxt.state.depth_range = depth.range;
     }
@@ -0,0 +1,115 @@
+use gl;
+use std::mem;
+
+use GlObject;
+use Handle;
+use Rect;
+use program::Program;
+
+use draw_parameters::{DrawParameters, BlendingFunction, DepthTest, DepthClamp};
+use draw_parameters::{BackfaceCullingMode, PolygonMode, Smooth, ProvokingVertex};
+use draw_parameters::{StencilTest, StencilOperation};
+
+/// A key that identifies the state of the pipeline used by a draw call.
+///
+/// This key can be compared, ordered and hashed. You can collect your draw calls alongside
+/// with their key, sort them, and submit them in order. Draw calls that share the same program,
+/// textures and parameters will then follow each other, which minimizes the number of state
+/// changes.
+///
+/// The ordering puts the most expensive changes first: draw calls are sorted by program, then by
+/// textures, then by blending, depth and stencil parameters, then by the rest of the parameters.
+///
+/// Queries, conditional rendering and transform feedback are not part of the key.
+///
+/// # Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+/// # let texture: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+/// use glium::draw_parameters::PipelineStateKey;
+///
+/// let params = glium::DrawParameters::default();
+/// let mut key = PipelineStateKey::new(&program, &params);
+/// key.add_texture(&texture);
+///
+/// let mut queue = vec![(key, 0)];
+/// queue.sort();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PipelineStateKey {
+    program: Handle,
+    textures: Vec<gl::types::GLuint>,
+    blend: (BlendingFunction, BlendingFunction, [u32; 4], Option<u32>),
+    depth: (DepthTest, bool, [u32; 2], DepthClamp),
+    stencil: [(StencilTest, i32, u32, StencilOperation, StencilOperation, StencilOperation); 2],
+    color_mask: (bool, bool, bool, bool),
+    rasterizer: (BackfaceCullingMode, PolygonMode, Option<u32>, Option<u32>, bool, Option<u32>,
+                 bool),
+    rectangles: (Option<Rect>, Option<[u32; 4]>, Option<Rect>),
+    misc: (bool, Option<Smooth>, ProvokingVertex, [u32; 8], bool, bool, Option<u32>),
+}
+
+impl PipelineStateKey {
+    /// Builds the key of a draw call that uses this program and these parameters.
+    pub fn new(program: &Program, params: &DrawParameters) -> PipelineStateKey {
+        let stencil = &params.stencil;
+        let bbox = &params.primitive_bounding_box;
+
+        PipelineStateKey {
+            program: program.get_id(),
+            textures: Vec::new(),
+            blend: (params.blend.color, params.blend.alpha, [
+                bits(params.blend.constant_value.0),
+                bits(params.blend.constant_value.1),
+                bits(params.blend.constant_value.2),
+                bits(params.blend.constant_value.3),
+            ], params.blend_targets),
+            depth: (params.depth.test, params.depth.write,
+                    [bits(params.depth.range.0), bits(params.depth.range.1)],
+                    params.depth.clamp),
+            stencil: [
+                (stencil.test_clockwise, stencil.reference_value_clockwise,
+                 stencil.write_mask_clockwise, stencil.fail_operation_clockwise,
+                 stencil.pass_depth_fail_operation_clockwise,
+                 stencil.depth_pass_operation_clockwise),
+                (stencil.test_counter_clockwise, stencil.reference_value_counter_clockwise,
+                 stencil.write_mask_counter_clockwise, stencil.fail_operation_counter_clockwise,
+                 stencil.pass_depth_fail_operation_counter_clockwise,
+                 stencil.depth_pass_operation_counter_clockwise),
+            ],
+            color_mask: params.color_mask,
+            rasterizer: (params.backface_culling, params.polygon_mode,
+                         params.line_width.map(bits),
+                         params.point_size.map(bits),
+                         params.multisampling, params.sample_mask, params.dithering),
+            rectangles: (params.viewport, params.viewport_f.map(|v| {
+                [bits(v.x), bits(v.y), bits(v.width), bits(v.height)]
+            }), params.scissor),
+            misc: (params.draw_primitives, params.smooth, params.provoking_vertex, [
+                bits(bbox.0.start), bits(bbox.0.end),
+                bits(bbox.1.start), bits(bbox.1.end),
+                bits(bbox.2.start), bits(bbox.2.end),
+                bits(bbox.3.start), bits(bbox.3.end),
+            ], params.primitive_restart_index, params.conservative_rasterization,
+            params.conservative_rasterization_dilate.map(bits)),
+        }
+    }
+
+    /// Adds a texture that is used by the draw call to the key.
+    ///
+    /// Textures are compared in the order in which they have been added.
+    #[inline]
+    pub fn add_texture<T: ?Sized>(&mut self, texture: &T)
+                                  where T: GlObject<Id = gl::types::GLuint>
+    {
+        self.textures.push(texture.get_id());
+    }
+}
+
+/// Returns the binary representation of a float, so that it can be compared and hashed.
+#[inline]
+fn bits(value: f32) -> u32 {
+    unsafe { mem::transmute(value) }
+}
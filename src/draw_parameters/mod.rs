@@ -61,6 +61,9 @@
 //!
 //! For the same reasons, as soon as you call `is_ready` on a query it will stop being usable.
 //!
+//! Passing two queries that count the primitives generated on the same vertex stream also
+//! results in a `WrongQueryOperation` error.
+//!
 use gl;
 use context;
 use context::Context;
@@ -81,6 +84,7 @@ use std::ops::Range;
 
 pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor};
 pub use self::depth::{Depth, DepthTest, DepthClamp};
+pub use self::key::PipelineStateKey;
 pub use self::query::{QueryCreationError};
 pub use self::query::{SamplesPassedQuery, TimeElapsedQuery, PrimitivesGeneratedQuery};
 pub use self::query::{AnySamplesPassedQuery, TransformFeedbackPrimitivesWrittenQuery};
@@ -88,6 +92,7 @@ pub use self::stencil::{StencilTest, StencilOperation, Stencil};
 
 mod blend;
 mod depth;
+mod key;
 mod query;
 mod stencil;
 
@@ -132,7 +137,7 @@ mod stencil;
 /// By doing so you can use backface culling to discard all the triangles that are not
 /// facing the screen, and increase your framerate.
 ///
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum BackfaceCullingMode {
     /// All triangles are always drawn.
     CullingDisabled,
@@ -163,7 +168,7 @@ pub enum BackfaceCullingMode {
 ///  </g>
 /// </svg>
 ///
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum PolygonMode {
     /// Only draw a single point at each vertex.
     ///
@@ -192,8 +197,10 @@ impl ToGlEnum for PolygonMode {
 
 /// Specifies a hint for the smoothing.
 ///
+/// This is also used by `Context::set_hint`.
+///
 /// Note that this is just a hint and the driver may disregard it.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Smooth {
     /// The most efficient option should be chosen.
     Fastest,
@@ -217,7 +224,7 @@ impl ToGlEnum for Smooth {
 }
 
 /// The vertex to use for flat shading.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ProvokingVertex {
     /// Use the last vertex of each primitive.
     LastVertex,
@@ -228,6 +235,21 @@ pub enum ProvokingVertex {
     FirstVertex,
 }
 
+/// A viewport whose position and dimensions are in floating point.
+///
+/// See the `viewport_f` member of `DrawParameters`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewportf {
+    /// Distance between the left of the surface and the left of the viewport.
+    pub x: f32,
+    /// Distance between the bottom of the surface and the bottom of the viewport.
+    pub y: f32,
+    /// Width of the viewport.
+    pub width: f32,
+    /// Height of the viewport.
+    pub height: f32,
+}
+
 /// Represents the parameters to use when drawing.
 ///
 /// Example:
@@ -243,6 +265,10 @@ pub enum ProvokingVertex {
 /// };
 /// ```
 ///
+/// The parameters of a draw call don't depend on the previous draw calls. Each member that
+/// is left to its default value is reset to its default state, with the exception of
+/// `line_width` and `point_size` whose default value means "don't care".
+///
 #[derive(Clone, Debug)]
 pub struct DrawParameters<'a> {
     /// How the fragment will interact with the depth buffer.
@@ -255,6 +281,18 @@ pub struct DrawParameters<'a> {
     /// being written.
     pub blend: Blend,
 
+    /// The fragment shader outputs on which `blend` is applied, or `None` to apply it to all of
+    /// them.
+    ///
+    /// Bit `i` corresponds to the draw buffer `i`, in other words to the output whose location
+    /// is `i`. The outputs whose bit is not set replace the existing pixels, as if `blend` was
+    /// `Blend::default()`. This allows you for example to blend a color output while writing
+    /// normals or identifiers to the other color attachments of a `MultiOutputFrameBuffer`.
+    ///
+    /// A value other than `None` requires OpenGL 3.0 or OpenGL ES 3.2, otherwise drawing
+    /// returns `DrawError::BlendingParameterNotSupported`. The default value is `None`.
+    pub blend_targets: Option<u32>,
+
     /// Allows you to disable some color components.
     ///
     /// This affects all attachments to the framebuffer. It's at the same level as the
@@ -273,6 +311,12 @@ pub struct DrawParameters<'a> {
     /// Diameter in pixels of the points to draw when drawing points.
     ///
     /// `None` means "don't care". Use this when you don't draw points.
+    ///
+    /// The value is clamped by the implementation to the range returned by
+    /// `capabilities().point_size_range`. It is ignored if the program has been created with
+    /// `uses_point_size` set to true, in which case the size is the value written to
+    /// `gl_PointSize` by the shader. OpenGL ES doesn't support this parameter and always uses
+    /// `gl_PointSize`.
     pub point_size: Option<f32>,
 
     /// Whether or not the GPU should filter out some faces.
@@ -295,6 +339,17 @@ pub struct DrawParameters<'a> {
     /// creating the window.
     pub multisampling: bool,
 
+    /// If `Some`, enables `GL_SAMPLE_MASK` and sets its value. Each bit of the mask corresponds
+    /// to a sample of the multisampled target, and the samples whose bit is `0` are not
+    /// written. The default value is `None`, which writes all the samples.
+    ///
+    /// The mask is combined with the value written to `gl_SampleMask` by the fragment shader,
+    /// if any. Only the first 32 samples can be masked.
+    ///
+    /// This requires OpenGL 3.2, OpenGL ES 3.1 or `GL_ARB_texture_multisample`. If it is not
+    /// supported, drawing returns `SampleMaskNotSupported`.
+    pub sample_mask: Option<u32>,
+
     /// Whether dithering is activated. Default value is `true`.
     ///
     /// Dithering will smoothen the transition between colors in your color buffer.
@@ -308,9 +363,24 @@ pub struct DrawParameters<'a> {
     ///
     /// You can specify a viewport greater than the target if you want to stretch the image.
     ///
-    /// `None` means "use the whole surface".
+    /// `None` means "use the whole surface". The viewport is then set to the dimensions of the
+    /// surface that is being drawn to, and not to the dimensions of the window. For example
+    /// drawing to a small texture will cover the entire texture, even if the previous draw
+    /// command targeted a larger surface or used a custom viewport.
+    ///
+    /// An explicit value always takes precedence over the dimensions of the surface.
     pub viewport: Option<Rect>,
 
+    /// A viewport whose position and dimensions are in floating point. Default is `None`.
+    ///
+    /// If specified, this takes precedence over `viewport`. This allows placing the viewport
+    /// with a sub-pixel precision, for example to jitter the projection for temporal
+    /// anti-aliasing or when rendering at a fractional scale.
+    ///
+    /// Requires OpenGL 4.1 or `GL_ARB_viewport_array`. If the backend doesn't support it, the
+    /// values are rounded to the nearest integers and passed to `glViewport` instead.
+    pub viewport_f: Option<Viewportf>,
+
     /// If specified, only pixels in this rect will be displayed. Default is `None`.
     ///
     /// This is different from a viewport. The image will stretch to fill the viewport, but
@@ -340,6 +410,16 @@ pub struct DrawParameters<'a> {
     /// If set, the number of primitives generated is added to the total stored inside the query.
     pub primitives_generated_query: Option<&'a PrimitivesGeneratedQuery>,
 
+    /// Additional queries that count the primitives generated on each vertex stream.
+    ///
+    /// Each query counts the primitives of the stream it has been built for with
+    /// `PrimitivesGeneratedQuery::new_indexed`. This is useful with geometry shaders that emit
+    /// to multiple streams, where you need to know how many primitives each stream produced.
+    ///
+    /// Two queries, including `primitives_generated_query`, can't count the same stream during
+    /// a draw command.
+    pub stream_primitives_generated_queries: &'a [&'a PrimitivesGeneratedQuery],
+
     /// If set, the number of vertices written by transform feedback.
     pub transform_feedback_primitives_written_query:
                                     Option<&'a TransformFeedbackPrimitivesWrittenQuery>,
@@ -353,7 +433,13 @@ pub struct DrawParameters<'a> {
 
     /// If set, then the generated primitives will be smoothed.
     ///
-    /// Note that blending needs to be enabled for this to work.
+    /// Smoothing works by computing the coverage of each pixel and multiplying the alpha value of
+    /// the fragment by it. This means that blending needs to be enabled for this to work,
+    /// usually with `Blend::alpha_blending()`. When drawing to a multisampled target, smoothing
+    /// is ignored and `multisampling` should be used instead.
+    ///
+    /// Smooth points are not available in the core profile and return `SmoothingNotSupported`.
+    /// Smoothing is not available at all on OpenGL ES.
     pub smooth: Option<Smooth>,
 
     /// In your vertex shader or geometry shader, you have the possibility to mark some output
@@ -386,6 +472,22 @@ pub struct DrawParameters<'a> {
     /// If the backend does not support GL_PRIMITIVE_RESTART_FIXED_INDEX, an Error 
     /// of type `FixedIndexRestartingNotSupported` will be returned.
     pub primitive_restart_index: bool,
+
+    /// If `true`, every pixel that is touched even partially by a primitive is rasterized,
+    /// instead of only the pixels whose center is covered. The default value is `false`.
+    ///
+    /// This is useful for voxelization and some global illumination techniques. It requires
+    /// either `GL_NV_conservative_raster` or `GL_INTEL_conservative_rasterization`. If neither
+    /// is supported, drawing returns `ConservativeRasterizationNotSupported`.
+    pub conservative_rasterization: bool,
+
+    /// Amount by which primitives are dilated when conservative rasterization is enabled,
+    /// in pixels. The default value is `None`, which uses the implementation's default of `0.0`.
+    ///
+    /// This requires `GL_NV_conservative_raster_dilate`. If it is not supported, drawing returns
+    /// `ConservativeRasterizationNotSupported`. It is ignored if `conservative_rasterization`
+    /// is `false`.
+    pub conservative_rasterization_dilate: Option<f32>,
 }
 
 /// Condition whether to render or not.
@@ -432,19 +534,23 @@ impl<'a> Default for DrawParameters<'a> {
             depth: Depth::default(),
             stencil: Default::default(),
             blend: Default::default(),
+            blend_targets: None,
             color_mask: (true, true, true, true),
             line_width: None,
             point_size: None,
             backface_culling: BackfaceCullingMode::CullingDisabled,
             polygon_mode: PolygonMode::Fill,
             multisampling: true,
+            sample_mask: None,
             dithering: true,
             viewport: None,
+            viewport_f: None,
             scissor: None,
             draw_primitives: true,
             samples_passed_query: None,
             time_elapsed_query: None,
             primitives_generated_query: None,
+            stream_primitives_generated_queries: &[],
             transform_feedback_primitives_written_query: None,
             condition: None,
             transform_feedback: None,
@@ -452,14 +558,16 @@ impl<'a> Default for DrawParameters<'a> {
             provoking_vertex: ProvokingVertex::LastVertex,
             primitive_bounding_box: (-1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0, -1.0 .. 1.0),
             primitive_restart_index: false,
+            conservative_rasterization: false,
+            conservative_rasterization_dilate: None,
         }
     }
 }
 
 /// DEPRECATED. Checks parameters and returns an error if something is wrong.
 pub fn validate(context: &Context, params: &DrawParameters) -> Result<(), DrawError> {
-    if params.depth.range.0 < 0.0 || params.depth.range.0 > 1.0 ||
-       params.depth.range.1 < 0.0 || params.depth.range.1 > 1.0
+    if !(params.depth.range.0 >= 0.0 && params.depth.range.0 <= 1.0 &&
+         params.depth.range.1 >= 0.0 && params.depth.range.1 <= 1.0)
     {
         return Err(DrawError::InvalidDepthRange);
     }
@@ -479,25 +587,29 @@ pub fn sync(ctxt: &mut context::CommandContext, draw_parameters: &DrawParameters
 {
     try!(depth::sync_depth(ctxt, &draw_parameters.depth));
     stencil::sync_stencil(ctxt, &draw_parameters.stencil);
-    try!(blend::sync_blending(ctxt, draw_parameters.blend));
+    try!(blend::sync_blending(ctxt, draw_parameters.blend, draw_parameters.blend_targets));
     sync_color_mask(ctxt, draw_parameters.color_mask);
     sync_line_width(ctxt, draw_parameters.line_width);
     sync_point_size(ctxt, draw_parameters.point_size);
     sync_polygon_mode(ctxt, draw_parameters.backface_culling, draw_parameters.polygon_mode);
     sync_multisampling(ctxt, draw_parameters.multisampling);
+    try!(sync_sample_mask(ctxt, draw_parameters.sample_mask));
     sync_dithering(ctxt, draw_parameters.dithering);
-    sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.scissor,
-                          dimensions);
+    sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.viewport_f,
+                          draw_parameters.scissor, dimensions);
     try!(sync_rasterizer_discard(ctxt, draw_parameters.draw_primitives));
     try!(sync_queries(ctxt, draw_parameters.samples_passed_query,
                       draw_parameters.time_elapsed_query,
                       draw_parameters.primitives_generated_query,
+                      draw_parameters.stream_primitives_generated_queries,
                       draw_parameters.transform_feedback_primitives_written_query));
     sync_conditional_render(ctxt, draw_parameters.condition);
     try!(sync_smooth(ctxt, draw_parameters.smooth, primitives_types));
     try!(sync_provoking_vertex(ctxt, draw_parameters.provoking_vertex));
     sync_primitive_bounding_box(ctxt, &draw_parameters.primitive_bounding_box);
     try!(sync_primitive_restart_index(ctxt, draw_parameters.primitive_restart_index));
+    try!(sync_conservative_rasterization(ctxt, draw_parameters.conservative_rasterization,
+                                         draw_parameters.conservative_rasterization_dilate));
 
     Ok(())
 }
@@ -531,6 +643,11 @@ fn sync_line_width(ctxt: &mut context::CommandContext, line_width: Option<f32>)
 }
 
 fn sync_point_size(ctxt: &mut context::CommandContext, point_size: Option<f32>) {
+    // `glPointSize` doesn't exist in OpenGL ES
+    if ctxt.version >= &Version(Api::GlEs, 2, 0) {
+        return;
+    }
+
     if let Some(point_size) = point_size {
         if ctxt.state.point_size != point_size {
             unsafe {
@@ -600,6 +717,40 @@ fn sync_multisampling(ctxt: &mut context::CommandContext, multisampling: bool) {
     }
 }
 
+fn sync_sample_mask(ctxt: &mut context::CommandContext, mask: Option<u32>)
+                    -> Result<(), DrawError>
+{
+    let supported = ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                    ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+                    ctxt.extensions.gl_arb_texture_multisample;
+
+    match mask {
+        Some(mask) => {
+            if !supported {
+                return Err(DrawError::SampleMaskNotSupported);
+            }
+
+            if !ctxt.state.enabled_sample_mask {
+                unsafe { ctxt.gl.Enable(gl::SAMPLE_MASK); }
+                ctxt.state.enabled_sample_mask = true;
+            }
+
+            if ctxt.state.sample_mask != mask {
+                unsafe { ctxt.gl.SampleMaski(0, mask); }
+                ctxt.state.sample_mask = mask;
+            }
+        },
+        None => {
+            if ctxt.state.enabled_sample_mask {
+                unsafe { ctxt.gl.Disable(gl::SAMPLE_MASK); }
+                ctxt.state.enabled_sample_mask = false;
+            }
+        },
+    }
+
+    Ok(())
+}
+
 fn sync_dithering(ctxt: &mut context::CommandContext, dithering: bool) {
     if ctxt.state.enabled_dither != dithering {
         unsafe {
@@ -615,40 +766,70 @@ fn sync_dithering(ctxt: &mut context::CommandContext, dithering: bool) {
 }
 
 fn sync_viewport_scissor(ctxt: &mut context::CommandContext, viewport: Option<Rect>,
-                         scissor: Option<Rect>, surface_dimensions: (u32, u32))
+                         viewport_f: Option<Viewportf>, scissor: Option<Rect>,
+                         surface_dimensions: (u32, u32))
 {
-    // viewport
-    if let Some(viewport) = viewport {
-        assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
-                "Viewport dimensions are too large");
-        assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
-                "Viewport dimensions are too large");
-
-        let viewport = (viewport.left as gl::types::GLint, viewport.bottom as gl::types::GLint,
-                        viewport.width as gl::types::GLsizei,
-                        viewport.height as gl::types::GLsizei);
-
-        if ctxt.state.viewport != Some(viewport) {
-            unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
-            ctxt.state.viewport = Some(viewport);
-        }
+    // floating-point viewport
+    let viewport = match viewport_f {
+        Some(viewport_f) => {
+            assert!(viewport_f.width >= 0.0 && viewport_f.height >= 0.0,
+                    "Viewport dimensions can't be negative");
+            assert!(viewport_f.width <= ctxt.capabilities.max_viewport_dims.0 as f32,
+                    "Viewport dimensions are too large");
+            assert!(viewport_f.height <= ctxt.capabilities.max_viewport_dims.1 as f32,
+                    "Viewport dimensions are too large");
+
+            if ctxt.version >= &Version(Api::Gl, 4, 1) || ctxt.extensions.gl_arb_viewport_array {
+                let value = (viewport_f.x, viewport_f.y, viewport_f.width, viewport_f.height);
+
+                if ctxt.state.viewport_f != Some(value) {
+                    unsafe { ctxt.gl.ViewportIndexedf(0, value.0, value.1, value.2, value.3); }
+                    ctxt.state.viewport_f = Some(value);
+                    ctxt.state.viewport = None;
+                }
 
-    } else {
-        assert!(surface_dimensions.0 <= ctxt.capabilities.max_viewport_dims.0 as u32,
-                "Viewport dimensions are too large");
-        assert!(surface_dimensions.1 <= ctxt.capabilities.max_viewport_dims.1 as u32,
-                "Viewport dimensions are too large");
+                sync_scissor(ctxt, scissor);
+                return;
+            }
 
-        let viewport = (0, 0, surface_dimensions.0 as gl::types::GLsizei,
-                        surface_dimensions.1 as gl::types::GLsizei);
+            // falling back to the integer viewport, whose origin can be negative as well
+            (viewport_f.x.round() as gl::types::GLint, viewport_f.y.round() as gl::types::GLint,
+             viewport_f.width.round() as gl::types::GLsizei,
+             viewport_f.height.round() as gl::types::GLsizei)
+        },
 
-        if ctxt.state.viewport != Some(viewport) {
-            unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
-            ctxt.state.viewport = Some(viewport);
-        }
+        None => {
+            if let Some(viewport) = viewport {
+                assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
+                        "Viewport dimensions are too large");
+                assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
+                        "Viewport dimensions are too large");
+
+                (viewport.left as gl::types::GLint, viewport.bottom as gl::types::GLint,
+                 viewport.width as gl::types::GLsizei, viewport.height as gl::types::GLsizei)
+
+            } else {
+                assert!(surface_dimensions.0 <= ctxt.capabilities.max_viewport_dims.0 as u32,
+                        "Viewport dimensions are too large");
+                assert!(surface_dimensions.1 <= ctxt.capabilities.max_viewport_dims.1 as u32,
+                        "Viewport dimensions are too large");
+
+                (0, 0, surface_dimensions.0 as gl::types::GLsizei,
+                 surface_dimensions.1 as gl::types::GLsizei)
+            }
+        },
+    };
+
+    if ctxt.state.viewport != Some(viewport) {
+        unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
+        ctxt.state.viewport = Some(viewport);
+        ctxt.state.viewport_f = None;
     }
 
-    // scissor
+    sync_scissor(ctxt, scissor);
+}
+
+fn sync_scissor(ctxt: &mut context::CommandContext, scissor: Option<Rect>) {
     if let Some(scissor) = scissor {
         let scissor = (scissor.left as gl::types::GLint, scissor.bottom as gl::types::GLint,
                        scissor.width as gl::types::GLsizei,
@@ -709,6 +890,7 @@ fn sync_queries(ctxt: &mut context::CommandContext,
                 samples_passed_query: Option<SamplesQueryParam>,
                 time_elapsed_query: Option<&TimeElapsedQuery>,
                 primitives_generated_query: Option<&PrimitivesGeneratedQuery>,
+                stream_primitives_generated_queries: &[&PrimitivesGeneratedQuery],
                 transform_feedback_primitives_written_query:
                                             Option<&TransformFeedbackPrimitivesWrittenQuery>)
                 -> Result<(), DrawError>
@@ -727,10 +909,26 @@ fn sync_queries(ctxt: &mut context::CommandContext,
         TimeElapsedQuery::end_time_elapsed_query(ctxt);
     }
 
-    if let Some(primitives_generated_query) = primitives_generated_query {
-        try!(primitives_generated_query.begin_query(ctxt));
-    } else {
-        TimeElapsedQuery::end_primitives_generated_query(ctxt);
+    {
+        let queries = primitives_generated_query.into_iter()
+                            .chain(stream_primitives_generated_queries.iter().cloned());
+
+        let mut streams = [false; 4];
+        for query in queries {
+            let stream = query.get_stream() as usize;
+            if streams[stream] {
+                return Err(DrawError::WrongQueryOperation);
+            }
+
+            try!(query.begin_query(ctxt));
+            streams[stream] = true;
+        }
+
+        for (stream, &used) in streams.iter().enumerate() {
+            if !used {
+                TimeElapsedQuery::end_primitives_generated_query(ctxt, stream as gl::types::GLuint);
+            }
+        }
     }
 
     if let Some(tfq) = transform_feedback_primitives_written_query {
@@ -774,8 +972,21 @@ fn sync_smooth(ctxt: &mut context::CommandContext,
 
         match primitive_type {
             // point
-            PrimitiveType::Points =>
-                return Err(DrawError::SmoothingNotSupported),
+            PrimitiveType::Points => unsafe {
+                if let Some(context::Profile::Core) = ctxt.capabilities.profile {
+                    return Err(DrawError::SmoothingNotSupported);
+                }
+
+                if !ctxt.state.enabled_point_smooth {
+                    ctxt.state.enabled_point_smooth = true;
+                    ctxt.gl.Enable(gl::POINT_SMOOTH);
+                }
+
+                if ctxt.state.smooth.2 != hint {
+                    ctxt.state.smooth.2 = hint;
+                    ctxt.gl.Hint(gl::POINT_SMOOTH_HINT, hint);
+                }
+            },
 
             // line
             PrimitiveType::LinesList | PrimitiveType::LinesListAdjacency |
@@ -809,7 +1020,12 @@ fn sync_smooth(ctxt: &mut context::CommandContext,
         else {
           match primitive_type {
             // point
-            PrimitiveType::Points => (),
+            PrimitiveType::Points => unsafe {
+                if ctxt.state.enabled_point_smooth {
+                    ctxt.state.enabled_point_smooth = false;
+                    ctxt.gl.Disable(gl::POINT_SMOOTH);
+                }
+            },
 
             // line
             PrimitiveType::LinesList | PrimitiveType::LinesListAdjacency |
@@ -897,6 +1113,10 @@ fn sync_primitive_restart_index(ctxt: &mut context::CommandContext,
                                 enabled: bool)
                                 -> Result<(), DrawError>
 {
+    if ctxt.state.enabled_primitive_fixed_restart == enabled {
+        return Ok(());
+    }
+
     // TODO: use GL_PRIMITIVE_RESTART (if possible) if 
     // GL_PRIMITIVE_RESTART_FIXED_INDEX is not supported
     if ctxt.version >= &Version(Api::Gl, 3, 1)   || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
@@ -915,4 +1135,43 @@ fn sync_primitive_restart_index(ctxt: &mut context::CommandContext,
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+fn sync_conservative_rasterization(ctxt: &mut context::CommandContext, enabled: bool,
+                                   dilate: Option<f32>) -> Result<(), DrawError>
+{
+    let cap = if ctxt.extensions.gl_nv_conservative_raster {
+        gl::CONSERVATIVE_RASTERIZATION_NV
+    } else if ctxt.extensions.gl_intel_conservative_rasterization {
+        gl::CONSERVATIVE_RASTERIZATION_INTEL
+    } else if enabled {
+        return Err(DrawError::ConservativeRasterizationNotSupported);
+    } else {
+        return Ok(());
+    };
+
+    if enabled {
+        let dilate = dilate.unwrap_or(0.0);
+        if dilate != ctxt.state.conservative_raster_dilate {
+            if !ctxt.extensions.gl_nv_conservative_raster_dilate {
+                return Err(DrawError::ConservativeRasterizationNotSupported);
+            }
+
+            unsafe { ctxt.gl.ConservativeRasterParameterfNV(gl::CONSERVATIVE_RASTER_DILATE_NV,
+                                                            dilate); }
+            ctxt.state.conservative_raster_dilate = dilate;
+        }
+    }
+
+    if ctxt.state.enabled_conservative_rasterization != enabled {
+        if enabled {
+            unsafe { ctxt.gl.Enable(cap); }
+        } else {
+            unsafe { ctxt.gl.Disable(cap); }
+        }
+
+        ctxt.state.enabled_conservative_rasterization = enabled;
+    }
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use backend::Facade;
 use context::Context;
 use context::CommandContext;
+use context::ResourceKind;
+use context::LabeledObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use ContextExt;
 use DrawError;
 use ToGlEnum;
@@ -27,6 +30,9 @@ pub struct RawQuery {
     id: gl::types::GLuint,
     ty: QueryType,
 
+    // index of the vertex stream that the query counts, only used for `PrimitivesGenerated`
+    index: gl::types::GLuint,
+
     // true means that this query has already been used or is being used to get data
     // this is important to know because we want to avoid erasing data
     has_been_used: Cell<bool>,
@@ -191,14 +197,52 @@ impl RawQuery {
             id
         };
 
+        ctxt.resource_created(ResourceKind::Query, id);
+
         Ok(RawQuery {
             context: context,
             id: id,
             ty: ty,
+            index: 0,
             has_been_used: Cell::new(false),
         })
     }
 
+    /// Builds a new query that counts the values of a specific vertex stream.
+    ///
+    /// Only `PrimitivesGenerated` queries can target a stream other than `0`, and doing so
+    /// requires OpenGL 4.0 or `GL_ARB_transform_feedback3`.
+    pub fn new_indexed<F: ?Sized>(facade: &F, ty: QueryType, index: u32)
+                                  -> Result<RawQuery, QueryCreationError> where F: Facade
+    {
+        if index == 0 {
+            return RawQuery::new(facade, ty);
+        }
+
+        match ty {
+            QueryType::PrimitivesGenerated => (),
+            _ => return Err(QueryCreationError::NotSupported)
+        };
+
+        {
+            let ctxt = facade.get_context().make_current();
+
+            if !(ctxt.version >= &Version(Api::Gl, 4, 0)) &&
+               !ctxt.extensions.gl_arb_transform_feedback3
+            {
+                return Err(QueryCreationError::NotSupported);
+            }
+
+            if index as usize >= ctxt.state.primitives_generated_query.len() {
+                return Err(QueryCreationError::NotSupported);
+            }
+        }
+
+        let mut query = try!(RawQuery::new(facade, ty));
+        query.index = index;
+        Ok(query)
+    }
+
     /// Queries the counter to see if the result is already available.
     pub fn is_ready(&self) -> bool {
         let mut ctxt = self.context.make_current();
@@ -361,9 +405,9 @@ impl RawQuery {
             ctxt.state.any_samples_passed_conservative_query = 0;
         }
 
-        if ctxt.state.primitives_generated_query == self.id {
-            unsafe { raw_end_query(ctxt, gl::PRIMITIVES_GENERATED) };
-            ctxt.state.primitives_generated_query = 0;
+        if ctxt.state.primitives_generated_query[self.index as usize] == self.id {
+            unsafe { raw_end_query_indexed(ctxt, gl::PRIMITIVES_GENERATED, self.index) };
+            ctxt.state.primitives_generated_query[self.index as usize] = 0;
         }
 
         if ctxt.state.transform_feedback_primitives_written_query == self.id {
@@ -389,6 +433,8 @@ impl Drop for RawQuery {
             }
         }
 
+        ctxt.resource_destroyed(ResourceKind::Query, self.id);
+
         unsafe {
             if ctxt.version >= &Version(Api::Gl, 1, 5) ||
                ctxt.version >= &Version(Api::GlEs, 3, 0)
@@ -516,20 +562,22 @@ impl QueryExt for RawQuery {
             QueryType::Timestamp => panic!(),
 
             QueryType::PrimitivesGenerated => {
-                if ctxt.state.primitives_generated_query != self.id {
+                let stream = self.index as usize;
+
+                if ctxt.state.primitives_generated_query[stream] != self.id {
                     if self.has_been_used.get() {
                         return Err(DrawError::WrongQueryOperation);
                     }
 
                     unsafe {
-                        if ctxt.state.primitives_generated_query != 0 {
-                            raw_end_query(ctxt, gl::PRIMITIVES_GENERATED);
+                        if ctxt.state.primitives_generated_query[stream] != 0 {
+                            raw_end_query_indexed(ctxt, gl::PRIMITIVES_GENERATED, self.index);
                         }
-                        raw_begin_query(ctxt, gl::PRIMITIVES_GENERATED, self.id);
+                        raw_begin_query_indexed(ctxt, gl::PRIMITIVES_GENERATED, self.index, self.id);
                     }
 
                     self.has_been_used.set(true);
-                    ctxt.state.primitives_generated_query = self.id;
+                    ctxt.state.primitives_generated_query[stream] = self.id;
                 }
             },
 
@@ -581,10 +629,10 @@ impl QueryExt for RawQuery {
     }
 
     #[inline]
-    fn end_primitives_generated_query(ctxt: &mut CommandContext) {
-        if ctxt.state.primitives_generated_query != 0 {
-            ctxt.state.primitives_generated_query = 0;
-            unsafe { raw_end_query(ctxt, gl::PRIMITIVES_GENERATED); }
+    fn end_primitives_generated_query(ctxt: &mut CommandContext, stream: gl::types::GLuint) {
+        if ctxt.state.primitives_generated_query[stream as usize] != 0 {
+            ctxt.state.primitives_generated_query[stream as usize] = 0;
+            unsafe { raw_end_query_indexed(ctxt, gl::PRIMITIVES_GENERATED, stream); }
         }
     }
 
@@ -666,6 +714,14 @@ impl fmt::Debug for RawQuery {
     }
 }
 
+impl Labeled for RawQuery {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.context.make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::QUERY, self.id), label)
+    }
+}
+
 impl GlObject for RawQuery {
     type Id = gl::types::GLuint;
 
@@ -721,6 +777,52 @@ unsafe fn raw_end_query(ctxt: &mut CommandContext, ty: gl::types::GLenum) {
     }
 }
 
+/// Calls `glBeginQueryIndexed`, or `glBeginQuery` if the index is `0`.
+///
+/// # Unsafe
+///
+/// The type of query must be guaranteed to be supported by the backend.
+/// The id of the query must be valid.
+/// If the index is not `0`, the backend must support indexed queries.
+///
+unsafe fn raw_begin_query_indexed(ctxt: &mut CommandContext, ty: gl::types::GLenum,
+                                  index: gl::types::GLuint, id: gl::types::GLuint)
+{
+    if index == 0 {
+        raw_begin_query(ctxt, ty, id);
+
+    } else if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+              ctxt.extensions.gl_arb_transform_feedback3
+    {
+        ctxt.gl.BeginQueryIndexed(ty, index, id);
+
+    } else {
+        unreachable!();
+    }
+}
+
+/// Calls `glEndQueryIndexed`, or `glEndQuery` if the index is `0`.
+///
+/// # Unsafe
+///
+/// The type of query must be guaranteed to be supported by the backend.
+/// If the index is not `0`, the backend must support indexed queries.
+unsafe fn raw_end_query_indexed(ctxt: &mut CommandContext, ty: gl::types::GLenum,
+                                index: gl::types::GLuint)
+{
+    if index == 0 {
+        raw_end_query(ctxt, ty);
+
+    } else if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+              ctxt.extensions.gl_arb_transform_feedback3
+    {
+        ctxt.gl.EndQueryIndexed(ty, index);
+
+    } else {
+        unreachable!();
+    }
+}
+
 macro_rules! impl_helper {
     ($name:ident, $ret:ty, $get_fn:ident) => {
         impl $name {
@@ -767,6 +869,13 @@ macro_rules! impl_helper {
             }
         }
 
+        impl Labeled for $name {
+            #[inline]
+            fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+                self.query.label(label)
+            }
+        }
+
         impl QueryExt for $name {
             #[inline]
             fn begin_query(&self, ctxt: &mut CommandContext) -> Result<(), DrawError> {
@@ -784,8 +893,10 @@ macro_rules! impl_helper {
             }
 
             #[inline]
-            fn end_primitives_generated_query(ctxt: &mut CommandContext) {
-                RawQuery::end_primitives_generated_query(ctxt)
+            fn end_primitives_generated_query(ctxt: &mut CommandContext,
+                                              stream: gl::types::GLuint)
+            {
+                RawQuery::end_primitives_generated_query(ctxt, stream)
             }
 
             #[inline]
@@ -895,6 +1006,11 @@ impl_helper!(AnySamplesPassedQuery, bool, get_bool);
 
 /// Query that allows you to know the number of primitives generated by the geometry shader.
 /// Will stay at `0` if you use it without any active geometry shader.
+///
+/// By default the query counts the primitives emitted to the vertex stream `0`. Use
+/// `new_indexed` to build a query that counts the primitives emitted to another stream by a
+/// geometry shader that uses multiple streams, and pass it through the
+/// `stream_primitives_generated_queries` member of the draw parameters.
 #[derive(Debug)]
 pub struct PrimitivesGeneratedQuery {
     query: RawQuery,
@@ -909,6 +1025,25 @@ impl PrimitivesGeneratedQuery {
         RawQuery::new(facade, QueryType::PrimitivesGenerated)
                                                     .map(|q| PrimitivesGeneratedQuery { query: q })
     }
+
+    /// Builds a new query that counts the primitives emitted to the vertex stream `stream`.
+    ///
+    /// Streams other than `0` require OpenGL 4.0 or `GL_ARB_transform_feedback3`. Only the
+    /// streams `0` to `3` are supported.
+    #[inline]
+    pub fn new_indexed<F: ?Sized>(facade: &F, stream: u32)
+                                  -> Result<PrimitivesGeneratedQuery, QueryCreationError>
+                                  where F: Facade
+    {
+        RawQuery::new_indexed(facade, QueryType::PrimitivesGenerated, stream)
+                                                    .map(|q| PrimitivesGeneratedQuery { query: q })
+    }
+
+    /// Returns the vertex stream whose primitives are counted by this query.
+    #[inline]
+    pub fn get_stream(&self) -> u32 {
+        self.query.index
+    }
 }
 
 impl_helper!(PrimitivesGeneratedQuery, u32, get_u32);
@@ -134,7 +134,7 @@ impl Default for Stencil {
 /// test. The general equation is `(ref & mask) CMP (stencil & mask)`, where `ref` is the reference
 /// value (`stencil_reference_value_clockwise` or `stencil_reference_value_counter_clockwise`),
 /// `CMP` is the comparison chosen, and `stencil` is the current value in the stencil buffer.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum StencilTest {
     /// The stencil test always passes.
     AlwaysPass,
@@ -180,7 +180,7 @@ pub enum StencilTest {
 }
 
 /// Specificies which operation the GPU will do depending on the result of the stencil test.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[repr(u32)]    // GLenum
 pub enum StencilOperation {
     /// Keeps the value currently in the stencil buffer.
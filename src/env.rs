@@ -0,0 +1,436 @@
+/*!
+Helpers to build environment maps for image-based lighting.
+
+*This module is only available if the `env` feature of glium is enabled.*
+
+Image-based lighting usually requires three textures:
+
+ - An environment cubemap, often built from an equirectangular HDR panorama with
+   `equirect_to_cubemap`.
+ - An irradiance cubemap, built with `convolve_irradiance`, that contains the diffuse lighting
+   received by a surface facing each direction.
+ - A prefiltered cubemap, built with `prefilter_specular`, whose mipmaps contain the specular
+   lighting for increasing roughnesses. The mipmap level `n` corresponds to a roughness of
+   `n / (levels - 1)`, where `levels` is the value returned by `get_mipmap_levels()`.
+
+All the passes are done on the GPU with regular draw commands, and require GLSL 1.40. The
+created cubemaps use the `F16F16F16F16` format.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let panorama: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+let environment = glium::env::equirect_to_cubemap(&display, &panorama).unwrap();
+let irradiance = glium::env::convolve_irradiance(&display, &environment).unwrap();
+let specular = glium::env::prefilter_specular(&display, &environment).unwrap();
+```
+
+*/
+use std::cmp;
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use framebuffer::{SimpleFrameBuffer, ValidationError};
+use index::{NoIndices, PrimitiveType};
+use program::ProgramChooserCreationError;
+use texture::{Cubemap, CubeLayer, Texture2d, MipmapsOption, UncompressedFloatFormat};
+use texture::TextureCreationError;
+use uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerWrapFunction};
+use vertex::EmptyVertexAttributes;
+use DrawError;
+use Surface;
+
+/// Dimension of the cubemaps created by `convolve_irradiance`.
+const IRRADIANCE_DIMENSION: u32 = 32;
+
+/// Maximum dimension of the cubemaps created by `prefilter_specular`.
+const PREFILTERED_MAX_DIMENSION: u32 = 256;
+
+/// Number of mipmap levels of the cubemaps created by `prefilter_specular`, if the dimension
+/// allows it.
+const PREFILTERED_LEVELS: u32 = 5;
+
+/// Builds a cubemap from an equirectangular panorama.
+///
+/// The dimension of each face is half of the height of the panorama. The cubemap has a full
+/// chain of mipmaps, which are generated from the main level.
+pub fn equirect_to_cubemap<F: ?Sized>(facade: &F, hdr_texture: &Texture2d)
+                                      -> Result<Cubemap, EnvMapError> where F: Facade
+{
+    let dimension = cmp::max(1, hdr_texture.get_height().unwrap_or(1) / 2);
+    let cubemap = try!(Cubemap::empty_with_format(facade, UncompressedFloatFormat::F16F16F16F16,
+                                                  MipmapsOption::EmptyMipmaps, dimension));
+
+    let program = try!(program!(facade,
+        140 => {
+            vertex: VERTEX_SHADER,
+            fragment: "
+                #version 140
+
+                uniform sampler2D source;
+                uniform vec3 face_forward;
+                uniform vec3 face_right;
+                uniform vec3 face_up;
+
+                in vec2 v_position;
+                out vec4 f_color;
+
+                const float PI = 3.14159265359;
+
+                void main() {
+                    vec3 dir = normalize(face_forward + v_position.x * face_right +
+                                         v_position.y * face_up);
+                    vec2 uv = vec2(atan(dir.z, dir.x) / (2.0 * PI) + 0.5,
+                                   asin(clamp(dir.y, -1.0, 1.0)) / PI + 0.5);
+                    f_color = vec4(texture(source, uv).rgb, 1.0);
+                }
+            "
+        },
+    ));
+
+    let source = hdr_texture.sampled()
+                            .wrap_function(SamplerWrapFunction::Repeat)
+                            .minify_filter(MinifySamplerFilter::Linear)
+                            .magnify_filter(MagnifySamplerFilter::Linear);
+
+    for &face in FACES.iter() {
+        let (forward, right, up) = face_basis(face);
+        let mut framebuffer = try!(SimpleFrameBuffer::new(facade, cubemap.main_level()
+                                                                         .image(face)));
+        try!(framebuffer.draw(EmptyVertexAttributes { len: 4 },
+                              NoIndices(PrimitiveType::TriangleStrip), &program,
+                              &uniform! {
+                                  source: source,
+                                  face_forward: forward,
+                                  face_right: right,
+                                  face_up: up,
+                              }, &Default::default()));
+    }
+
+    unsafe { cubemap.generate_mipmaps(); }
+    Ok(cubemap)
+}
+
+/// Builds an irradiance cubemap from an environment cubemap.
+///
+/// Each texel contains the cosine-weighted integral of the incoming light over the hemisphere
+/// that faces its direction. The result is divided by `π`, so that it can be directly multiplied
+/// by the albedo of a lambertian surface.
+///
+/// If the environment has mipmaps, a level whose dimension is close to the dimension of the
+/// irradiance map is sampled, in order to avoid aliasing.
+pub fn convolve_irradiance<F: ?Sized>(facade: &F, environment: &Cubemap)
+                                      -> Result<Cubemap, EnvMapError> where F: Facade
+{
+    let cubemap = try!(Cubemap::empty_with_format(facade, UncompressedFloatFormat::F16F16F16F16,
+                                                  MipmapsOption::NoMipmap,
+                                                  IRRADIANCE_DIMENSION));
+
+    let program = try!(program!(facade,
+        140 => {
+            vertex: VERTEX_SHADER,
+            fragment: "
+                #version 140
+
+                uniform samplerCube source;
+                uniform float source_lod;
+                uniform vec3 face_forward;
+                uniform vec3 face_right;
+                uniform vec3 face_up;
+
+                in vec2 v_position;
+                out vec4 f_color;
+
+                const float PI = 3.14159265359;
+                const float SAMPLE_DELTA = 0.025;
+
+                void main() {
+                    vec3 normal = normalize(face_forward + v_position.x * face_right +
+                                            v_position.y * face_up);
+                    vec3 up = abs(normal.y) < 0.999 ? vec3(0.0, 1.0, 0.0) : vec3(0.0, 0.0, 1.0);
+                    vec3 right = normalize(cross(up, normal));
+                    up = cross(normal, right);
+
+                    vec3 irradiance = vec3(0.0);
+                    float num_samples = 0.0;
+
+                    for (float phi = 0.0; phi < 2.0 * PI; phi += SAMPLE_DELTA) {
+                        for (float theta = 0.0; theta < 0.5 * PI; theta += SAMPLE_DELTA) {
+                            vec3 tangent_sample = vec3(sin(theta) * cos(phi),
+                                                       sin(theta) * sin(phi),
+                                                       cos(theta));
+                            vec3 sample_dir = tangent_sample.x * right + tangent_sample.y * up +
+                                              tangent_sample.z * normal;
+
+                            irradiance += textureLod(source, sample_dir, source_lod).rgb *
+                                          cos(theta) * sin(theta);
+                            num_samples += 1.0;
+                        }
+                    }
+
+                    f_color = vec4(PI * irradiance / num_samples, 1.0);
+                }
+            "
+        },
+    ));
+
+    let max_lod = (environment.get_mipmap_levels() - 1) as f32;
+    let source_lod = (environment.get_width() as f32 / IRRADIANCE_DIMENSION as f32).log2();
+    let source_lod = source_lod.max(0.0).min(max_lod);
+
+    let source = environment.sampled()
+                            .minify_filter(MinifySamplerFilter::LinearMipmapLinear)
+                            .magnify_filter(MagnifySamplerFilter::Linear);
+
+    for &face in FACES.iter() {
+        let (forward, right, up) = face_basis(face);
+        let mut framebuffer = try!(SimpleFrameBuffer::new(facade, cubemap.main_level()
+                                                                         .image(face)));
+        try!(framebuffer.draw(EmptyVertexAttributes { len: 4 },
+                              NoIndices(PrimitiveType::TriangleStrip), &program,
+                              &uniform! {
+                                  source: source,
+                                  source_lod: source_lod,
+                                  face_forward: forward,
+                                  face_right: right,
+                                  face_up: up,
+                              }, &Default::default()));
+    }
+
+    Ok(cubemap)
+}
+
+/// Builds a prefiltered specular cubemap from an environment cubemap.
+///
+/// The environment is convolved with the GGX distribution by importance sampling. The mipmap
+/// level `n` of the result corresponds to a roughness of `n / (levels - 1)`, where `levels` is
+/// the number of mipmap levels of the returned cubemap (at most 5).
+///
+/// The dimension of the result is the dimension of the environment, clamped to 256. The
+/// environment should have mipmaps, as they are used to reduce the noise of high roughnesses.
+/// Cubemaps returned by `equirect_to_cubemap` always have mipmaps.
+pub fn prefilter_specular<F: ?Sized>(facade: &F, environment: &Cubemap)
+                                     -> Result<Cubemap, EnvMapError> where F: Facade
+{
+    let dimension = cmp::min(environment.get_width(), PREFILTERED_MAX_DIMENSION);
+    let cubemap = try!(Cubemap::empty_with_format(facade, UncompressedFloatFormat::F16F16F16F16,
+                                                  MipmapsOption::EmptyMipmapsMax(
+                                                      PREFILTERED_LEVELS - 1),
+                                                  dimension));
+
+    let program = try!(program!(facade,
+        140 => {
+            vertex: VERTEX_SHADER,
+            fragment: "
+                #version 140
+
+                uniform samplerCube source;
+                uniform float source_dimension;
+                uniform float roughness;
+                uniform vec3 face_forward;
+                uniform vec3 face_right;
+                uniform vec3 face_up;
+
+                in vec2 v_position;
+                out vec4 f_color;
+
+                const float PI = 3.14159265359;
+                const uint SAMPLE_COUNT = 1024u;
+
+                float radical_inverse(uint bits) {
+                    bits = (bits << 16u) | (bits >> 16u);
+                    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+                    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+                    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+                    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+                    return float(bits) * 2.3283064365386963e-10;
+                }
+
+                vec3 importance_sample_ggx(vec2 xi, vec3 normal, float a) {
+                    float phi = 2.0 * PI * xi.x;
+                    float cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+                    float sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+                    vec3 h = vec3(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+
+                    vec3 up = abs(normal.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+                    vec3 tangent = normalize(cross(up, normal));
+                    vec3 bitangent = cross(normal, tangent);
+                    return normalize(tangent * h.x + bitangent * h.y + normal * h.z);
+                }
+
+                void main() {
+                    vec3 normal = normalize(face_forward + v_position.x * face_right +
+                                            v_position.y * face_up);
+                    vec3 view = normal;
+
+                    float a = roughness * roughness;
+                    float texel_solid_angle = 4.0 * PI /
+                                              (6.0 * source_dimension * source_dimension);
+
+                    vec3 color = vec3(0.0);
+                    float total_weight = 0.0;
+
+                    for (uint i = 0u; i < SAMPLE_COUNT; ++i) {
+                        vec2 xi = vec2(float(i) / float(SAMPLE_COUNT), radical_inverse(i));
+                        vec3 h = importance_sample_ggx(xi, normal, a);
+                        vec3 l = normalize(2.0 * dot(view, h) * h - view);
+
+                        float n_dot_l = max(dot(normal, l), 0.0);
+                        if (n_dot_l > 0.0) {
+                            float n_dot_h = max(dot(normal, h), 0.0);
+                            float h_dot_v = max(dot(h, view), 0.0);
+                            float d = n_dot_h * n_dot_h * (a * a - 1.0) + 1.0;
+                            float distribution = a * a / (PI * d * d);
+                            float pdf = distribution * n_dot_h / (4.0 * h_dot_v) + 0.0001;
+                            float sample_solid_angle = 1.0 /
+                                                       (float(SAMPLE_COUNT) * pdf + 0.0001);
+                            float lod = roughness == 0.0 ? 0.0 :
+                                        0.5 * log2(sample_solid_angle / texel_solid_angle);
+
+                            color += textureLod(source, l, lod).rgb * n_dot_l;
+                            total_weight += n_dot_l;
+                        }
+                    }
+
+                    f_color = vec4(color / total_weight, 1.0);
+                }
+            "
+        },
+    ));
+
+    let source = environment.sampled()
+                            .minify_filter(MinifySamplerFilter::LinearMipmapLinear)
+                            .magnify_filter(MagnifySamplerFilter::Linear);
+    let source_dimension = environment.get_width() as f32;
+
+    let levels = cubemap.get_mipmap_levels();
+    for level in 0 .. levels {
+        let roughness = if levels >= 2 { level as f32 / (levels - 1) as f32 } else { 0.0 };
+        let mipmap = cubemap.mipmap(level).unwrap();
+
+        for &face in FACES.iter() {
+            let (forward, right, up) = face_basis(face);
+            let mut framebuffer = try!(SimpleFrameBuffer::new(facade, mipmap.image(face)));
+            try!(framebuffer.draw(EmptyVertexAttributes { len: 4 },
+                                  NoIndices(PrimitiveType::TriangleStrip), &program,
+                                  &uniform! {
+                                      source: source,
+                                      source_dimension: source_dimension,
+                                      roughness: roughness,
+                                      face_forward: forward,
+                                      face_right: right,
+                                      face_up: up,
+                                  }, &Default::default()));
+        }
+    }
+
+    Ok(cubemap)
+}
+
+/// Vertex shader shared by all the passes. Draws a quad that covers the whole framebuffer
+/// without any vertex attribute.
+const VERTEX_SHADER: &'static str = "
+    #version 140
+
+    out vec2 v_position;
+
+    void main() {
+        v_position = vec2(float(gl_VertexID & 1), float(gl_VertexID >> 1)) * 2.0 - 1.0;
+        gl_Position = vec4(v_position, 0.0, 1.0);
+    }
+";
+
+const FACES: [CubeLayer; 6] = [
+    CubeLayer::PositiveX, CubeLayer::NegativeX,
+    CubeLayer::PositiveY, CubeLayer::NegativeY,
+    CubeLayer::PositiveZ, CubeLayer::NegativeZ,
+];
+
+/// Returns the direction of the center of a face, and the directions that correspond to the
+/// increasing `s` and `t` texture coordinates of that face.
+fn face_basis(face: CubeLayer) -> ([f32; 3], [f32; 3], [f32; 3]) {
+    match face {
+        CubeLayer::PositiveX => ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, -1.0, 0.0]),
+        CubeLayer::NegativeX => ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, -1.0, 0.0]),
+        CubeLayer::PositiveY => ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        CubeLayer::NegativeY => ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+        CubeLayer::PositiveZ => ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+        CubeLayer::NegativeZ => ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    }
+}
+
+/// Error that can happen while building an environment map.
+#[derive(Debug)]
+pub enum EnvMapError {
+    /// Failed to create the destination cubemap.
+    TextureCreationError(TextureCreationError),
+
+    /// Failed to compile the shaders of the pass.
+    ProgramCreationError(ProgramChooserCreationError),
+
+    /// Failed to attach the cubemap to a framebuffer.
+    FramebufferCreationError(ValidationError),
+
+    /// Failed to draw on the cubemap.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for EnvMapError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for EnvMapError {
+    fn description(&self) -> &str {
+        use self::EnvMapError::*;
+        match *self {
+            TextureCreationError(_) =>
+                "Failed to create the destination cubemap",
+            ProgramCreationError(_) =>
+                "Failed to compile the shaders of the pass",
+            FramebufferCreationError(_) =>
+                "Failed to attach the cubemap to a framebuffer",
+            DrawError(_) =>
+                "Failed to draw on the cubemap",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        use self::EnvMapError::*;
+        match *self {
+            TextureCreationError(ref err) => Some(err),
+            ProgramCreationError(ref err) => Some(err),
+            FramebufferCreationError(ref err) => Some(err),
+            DrawError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<TextureCreationError> for EnvMapError {
+    #[inline]
+    fn from(err: TextureCreationError) -> EnvMapError {
+        EnvMapError::TextureCreationError(err)
+    }
+}
+
+impl From<ProgramChooserCreationError> for EnvMapError {
+    #[inline]
+    fn from(err: ProgramChooserCreationError) -> EnvMapError {
+        EnvMapError::ProgramCreationError(err)
+    }
+}
+
+impl From<ValidationError> for EnvMapError {
+    #[inline]
+    fn from(err: ValidationError) -> EnvMapError {
+        EnvMapError::FramebufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for EnvMapError {
+    #[inline]
+    fn from(err: DrawError) -> EnvMapError {
+        EnvMapError::DrawError(err)
+    }
+}
@@ -62,6 +62,7 @@ use smallvec::SmallVec;
 use CapabilitiesSource;
 use ContextExt;
 use GlObject;
+use Rect;
 use TextureExt;
 
 use texture::CubeLayer;
@@ -72,6 +73,7 @@ use framebuffer::RenderBufferAny;
 
 use gl;
 use context::CommandContext;
+use context::ResourceKind;
 use version::Version;
 use version::Api;
 
@@ -204,6 +206,7 @@ impl<'a> FramebufferAttachments<'a> {
                         },
                         dimensions: (width, height),
                         layers: layers,
+                        color_kinds: Vec::new(),
                         depth_buffer_bits: None,
                         stencil_buffer_bits: None,
                         marker: PhantomData,
@@ -298,6 +301,7 @@ impl<'a> FramebufferAttachments<'a> {
         let mut depth_bits = None;
         let mut stencil_bits = None;
         let mut samples = None;     // contains `0` if not multisampling and `None` if unknown
+        let mut color_kinds = Vec::with_capacity(colors.len());
 
         for &(index, LayeredAttachment(ref attachment)) in colors.iter() {
             if index >= max_color_attachments as u32 {
@@ -307,6 +311,7 @@ impl<'a> FramebufferAttachments<'a> {
                 });
             }
             raw_attachments.color.push((index, handle_tex!(attachment, dimensions, samples)));
+            color_kinds.push((index, attachment.get_texture().kind()));
         }
 
         match depth_stencil {
@@ -340,6 +345,7 @@ impl<'a> FramebufferAttachments<'a> {
             raw: raw_attachments,
             dimensions: dimensions,
             layers: None,       // FIXME: count layers
+            color_kinds: color_kinds,
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
             marker: PhantomData,
@@ -480,6 +486,8 @@ impl<'a> FramebufferAttachments<'a> {
         let mut stencil_bits = None;
         let mut samples = None;     // contains `0` if not multisampling and `None` if unknown
 
+        let mut color_kinds = Vec::with_capacity(colors.len());
+
         for &(index, ref attachment) in colors.iter() {
             if index >= max_color_attachments as u32 {
                 return Err(ValidationError::TooManyColorAttachments{
@@ -488,6 +496,7 @@ impl<'a> FramebufferAttachments<'a> {
                 });
             }
             raw_attachments.color.push((index, handle_atch!(attachment, dimensions, samples)));
+            color_kinds.push((index, attachment.kind()));
         }
 
         match depth_stencil {
@@ -519,6 +528,7 @@ impl<'a> FramebufferAttachments<'a> {
             raw: raw_attachments,
             dimensions: dimensions,
             layers: None,
+            color_kinds: color_kinds,
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
             marker: PhantomData,
@@ -532,6 +542,8 @@ pub struct ValidatedAttachments<'a> {
     raw: RawAttachments,
     dimensions: (u32, u32),
     layers: Option<u32>,
+    // index and kind of each color attachment
+    color_kinds: Vec<(u32, TextureKind)>,
     depth_buffer_bits: Option<u16>,
     stencil_buffer_bits: Option<u16>,
     marker: PhantomData<&'a ()>,
@@ -544,6 +556,31 @@ impl<'a> ValidatedAttachments<'a> {
         self.layers.is_some()
     }
 
+    /// Returns `true` if there is a color attachment that corresponds to the draw buffer of
+    /// index `index`.
+    #[inline]
+    pub fn has_color_attachment(&self, index: u32) -> bool {
+        self.raw.color.iter().any(|&(pos, _)| pos == index)
+    }
+
+    /// Returns the number of color attachments.
+    #[inline]
+    pub fn get_num_color_attachments(&self) -> usize {
+        self.raw.color.len()
+    }
+
+    /// Returns `true` if there is a depth or a depth-stencil attachment.
+    #[inline]
+    pub fn has_depth_attachment(&self) -> bool {
+        self.raw.depth.is_some() || self.raw.depth_stencil.is_some()
+    }
+
+    /// Returns the index and the kind (float, integral or unsigned) of each color attachment.
+    #[inline]
+    pub fn get_color_attachments_kinds(&self) -> &[(u32, TextureKind)] {
+        &self.color_kinds
+    }
+
     /// Returns the dimensions that the framebuffer will have if you use these attachments.
     #[inline]
     pub fn get_dimensions(&self) -> (u32, u32) {
@@ -563,6 +600,60 @@ impl<'a> ValidatedAttachments<'a> {
     pub fn get_stencil_buffer_bits(&self) -> Option<u16> {
         self.stencil_buffer_bits
     }
+
+    /// Returns where a texture is attached, or `None` if it isn't part of the attachments.
+    pub fn get_texture_attachment_point(&self, texture: gl::types::GLuint)
+                                        -> Option<AttachmentPoint>
+    {
+        let is_texture = |atch: &RawAttachment| {
+            match atch {
+                &RawAttachment::Texture { texture: id, .. } => id == texture,
+                _ => false
+            }
+        };
+
+        if let Some(&(index, _)) = self.raw.color.iter().find(|&&(_, ref a)| is_texture(a)) {
+            return Some(AttachmentPoint::Color(index));
+        }
+
+        if self.raw.depth.as_ref().map_or(false, |a| is_texture(a)) {
+            return Some(AttachmentPoint::Depth);
+        }
+
+        if self.raw.stencil.as_ref().map_or(false, |a| is_texture(a)) {
+            return Some(AttachmentPoint::Stencil);
+        }
+
+        if self.raw.depth_stencil.as_ref().map_or(false, |a| is_texture(a)) {
+            return Some(AttachmentPoint::DepthStencil);
+        }
+
+        None
+    }
+}
+
+/// Location of an attachment within a framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AttachmentPoint {
+    /// The color attachment that corresponds to the fragment output of this index.
+    Color(u32),
+    /// The depth attachment.
+    Depth,
+    /// The stencil attachment.
+    Stencil,
+    /// The depth-stencil attachment.
+    DepthStencil,
+}
+
+impl fmt::Display for AttachmentPoint {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &AttachmentPoint::Color(index) => write!(fmt, "color attachment {}", index),
+            &AttachmentPoint::Depth => write!(fmt, "depth attachment"),
+            &AttachmentPoint::Stencil => write!(fmt, "stencil attachment"),
+            &AttachmentPoint::DepthStencil => write!(fmt, "depth-stencil attachment"),
+        }
+    }
 }
 
 /// An error that can happen while validating attachments.
@@ -590,6 +681,9 @@ pub enum ValidationError {
         /// Number of attachments that were given.
         obtained: usize,
     },
+
+    /// The requested mipmap level or layer doesn't exist in the texture.
+    LayerOutOfRange,
 }
 
 impl fmt::Display for ValidationError {
@@ -618,6 +712,116 @@ impl Error for ValidationError {
                 "All attachments must have the same number of samples",
             TooManyColorAttachments {..} =>
                 "Backends only support a certain number of color attachments",
+            LayerOutOfRange =>
+                "The requested mipmap level or layer doesn't exist in the texture",
+        }
+    }
+}
+
+/// Reason why the OpenGL implementation considers a framebuffer to be incomplete.
+///
+/// This corresponds to the value returned by `glCheckFramebufferStatus`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IncompleteFramebufferReason {
+    /// One of the attachments is not framebuffer-attachment complete.
+    ///
+    /// This usually means that the format of one of the attachments is not renderable.
+    IncompleteAttachment,
+
+    /// The framebuffer doesn't have any attachment.
+    MissingAttachment,
+
+    /// One of the draw buffers points to a missing attachment.
+    IncompleteDrawBuffer,
+
+    /// The read buffer points to a missing attachment.
+    IncompleteReadBuffer,
+
+    /// The attachments don't have the same number of samples, or don't all use fixed sample
+    /// locations.
+    IncompleteMultisample,
+
+    /// Some attachments are layered and others are not, or the layered attachments don't have
+    /// the same target.
+    IncompleteLayerTargets,
+
+    /// The attachments don't have the same dimensions. Only reported by OpenGL ES 2.
+    IncompleteDimensions,
+
+    /// The combination of internal formats of the attachments is not supported by the
+    /// implementation.
+    Unsupported,
+
+    /// The default framebuffer doesn't exist.
+    Undefined,
+
+    /// The implementation returned a status that glium doesn't know about.
+    Unknown(u32),
+}
+
+impl IncompleteFramebufferReason {
+    /// Turns the value returned by `glCheckFramebufferStatus` into a reason.
+    ///
+    /// Returns `None` if the framebuffer is complete.
+    fn from_status(status: gl::types::GLenum) -> Option<IncompleteFramebufferReason> {
+        match status {
+            gl::FRAMEBUFFER_COMPLETE => None,
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT =>
+                Some(IncompleteFramebufferReason::IncompleteAttachment),
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT =>
+                Some(IncompleteFramebufferReason::MissingAttachment),
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER =>
+                Some(IncompleteFramebufferReason::IncompleteDrawBuffer),
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER =>
+                Some(IncompleteFramebufferReason::IncompleteReadBuffer),
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE =>
+                Some(IncompleteFramebufferReason::IncompleteMultisample),
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS =>
+                Some(IncompleteFramebufferReason::IncompleteLayerTargets),
+            gl::FRAMEBUFFER_INCOMPLETE_DIMENSIONS =>
+                Some(IncompleteFramebufferReason::IncompleteDimensions),
+            gl::FRAMEBUFFER_UNSUPPORTED => Some(IncompleteFramebufferReason::Unsupported),
+            gl::FRAMEBUFFER_UNDEFINED => Some(IncompleteFramebufferReason::Undefined),
+            other => Some(IncompleteFramebufferReason::Unknown(other)),
+        }
+    }
+}
+
+impl fmt::Display for IncompleteFramebufferReason {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IncompleteFramebufferReason::Unknown(status) =>
+                write!(fmt, "{}: 0x{:x}", self.description(), status),
+            _ =>
+                write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for IncompleteFramebufferReason {
+    fn description(&self) -> &str {
+        use self::IncompleteFramebufferReason::*;
+        match *self {
+            IncompleteAttachment =>
+                "One of the attachments is not framebuffer-attachment complete",
+            MissingAttachment =>
+                "The framebuffer doesn't have any attachment",
+            IncompleteDrawBuffer =>
+                "One of the draw buffers points to a missing attachment",
+            IncompleteReadBuffer =>
+                "The read buffer points to a missing attachment",
+            IncompleteMultisample =>
+                "The attachments don't have the same number of samples or sample locations",
+            IncompleteLayerTargets =>
+                "The layered attachments don't match",
+            IncompleteDimensions =>
+                "The attachments don't have the same dimensions",
+            Unsupported =>
+                "The combination of internal formats of the attachments is not supported",
+            Undefined =>
+                "The default framebuffer doesn't exist",
+            Unknown(_) =>
+                "The framebuffer is incomplete for an unknown reason",
         }
     }
 }
@@ -701,6 +905,27 @@ impl From<[u32; 4]> for ClearBufferData {
     }
 }
 
+impl From<f32> for ClearBufferData {
+    #[inline]
+    fn from(data: f32) -> ClearBufferData {
+        ClearBufferData::Depth(data)
+    }
+}
+
+impl From<i32> for ClearBufferData {
+    #[inline]
+    fn from(data: i32) -> ClearBufferData {
+        ClearBufferData::Stencil(data)
+    }
+}
+
+impl From<(f32, i32)> for ClearBufferData {
+    #[inline]
+    fn from(data: (f32, i32)) -> ClearBufferData {
+        ClearBufferData::DepthStencil(data.0, data.1)
+    }
+}
+
 /// Manages all the framebuffer objects.
 ///
 /// `cleanup` **must** be called when destroying the container, otherwise `Drop` will panic.
@@ -744,6 +969,28 @@ impl FramebuffersContainer {
         FramebuffersContainer::purge_if(ctxt, |a| a == &RawAttachment::RenderBuffer(renderbuffer));
     }
 
+    /// Returns the list of textures that are attached to a framebuffer object.
+    ///
+    /// Returns an empty list if the framebuffer object hasn't been created by this container.
+    pub fn get_attached_textures(ctxt: &CommandContext, framebuffer: gl::types::GLuint)
+                                 -> Vec<gl::types::GLuint>
+    {
+        let framebuffers = ctxt.framebuffer_objects.framebuffers.borrow();
+
+        let key = match framebuffers.iter().find(|&(_, fbo)| fbo.id == framebuffer) {
+            Some((key, _)) => key,
+            None => return Vec::new(),
+        };
+
+        key.color.iter().map(|&(_, ref atch)| atch)
+           .chain(key.depth.iter()).chain(key.stencil.iter()).chain(key.depth_stencil.iter())
+           .filter_map(|atch| match atch {
+               &RawAttachment::Texture { texture, .. } => Some(texture),
+               _ => None
+           })
+           .collect()
+    }
+
     /// Destroys all framebuffer objects that match a certain condition.
     fn purge_if<F>(mut ctxt: &mut CommandContext, condition: F)
                    where F: Fn(&RawAttachment) -> bool
@@ -814,6 +1061,33 @@ impl FramebuffersContainer {
         }
     }
 
+    /// Same as `get_framebuffer_for_drawing`, but returns an error if the framebuffer is
+    /// incomplete.
+    ///
+    /// The status of the framebuffer is only queried when the framebuffer object is created,
+    /// in other words the first time a given combination of attachments is used.
+    ///
+    /// # Unsafety
+    ///
+    /// After calling this function, you **must** make sure to call `purge_texture`
+    /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
+    pub fn get_complete_framebuffer_for_drawing(ctxt: &mut CommandContext,
+                                                attachments: Option<&ValidatedAttachments>)
+                                                -> Result<gl::types::GLuint,
+                                                          IncompleteFramebufferReason>
+    {
+        if let Some(attachments) = attachments {
+            let id = FramebuffersContainer::get_framebuffer(ctxt, attachments);
+            let framebuffers = ctxt.framebuffer_objects.framebuffers.borrow();
+            match framebuffers.get(&attachments.raw).unwrap().status {
+                Some(reason) => Err(reason),
+                None => Ok(id),
+            }
+        } else {
+            Ok(0)
+        }
+    }
+
     /// Binds the default framebuffer to `GL_READ_FRAMEBUFFER` or `GL_FRAMEBUFFER` so that it
     /// becomes the target of `glReadPixels`, `glCopyTexImage2D`, etc.
     // TODO: use an enum for the read buffer instead
@@ -854,6 +1128,8 @@ impl FramebuffersContainer {
 
     /// Calls `glClearBuffer` on a framebuffer that contains the attachment.
     ///
+    /// If `rect` is `Some`, only this rectangle is cleared.
+    ///
     /// # Panic
     ///
     /// Panicks if `data` is incompatible with the kind of attachment.
@@ -863,7 +1139,7 @@ impl FramebuffersContainer {
     /// After calling this function, you **must** make sure to call `purge_texture`
     /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
     pub unsafe fn clear_buffer<D>(ctxt: &mut CommandContext, attachment: &RegularAttachment,
-                                  data: D)
+                                  rect: Option<&Rect>, data: D)
         where D: Into<ClearBufferData>
     {
         // TODO: look for an existing framebuffer with this attachment
@@ -881,6 +1157,37 @@ impl FramebuffersContainer {
 
         bind_framebuffer(ctxt, fb, true, false);
 
+        // `glClearBuffer` is affected by the write masks, the scissor test and rasterizer
+        // discard, which may have been left by a previous draw
+        if ctxt.state.enabled_rasterizer_discard {
+            ctxt.gl.Disable(gl::RASTERIZER_DISCARD);
+            ctxt.state.enabled_rasterizer_discard = false;
+        }
+
+        if let Some(rect) = rect {
+            let rect = (rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
+                        rect.width as gl::types::GLsizei, rect.height as gl::types::GLsizei);
+
+            if ctxt.state.scissor != Some(rect) {
+                ctxt.gl.Scissor(rect.0, rect.1, rect.2, rect.3);
+                ctxt.state.scissor = Some(rect);
+            }
+
+            if !ctxt.state.enabled_scissor_test {
+                ctxt.gl.Enable(gl::SCISSOR_TEST);
+                ctxt.state.enabled_scissor_test = true;
+            }
+
+        } else if ctxt.state.enabled_scissor_test {
+            ctxt.gl.Disable(gl::SCISSOR_TEST);
+            ctxt.state.enabled_scissor_test = false;
+        }
+
+        if ctxt.state.color_mask != (1, 1, 1, 1) {
+            ctxt.state.color_mask = (1, 1, 1, 1);
+            ctxt.gl.ColorMask(1, 1, 1, 1);
+        }
+
         match (attachment.kind(), data) {
             (TextureKind::Float, ClearBufferData::Float(data)) => {
                 ctxt.gl.ClearBufferfv(gl::COLOR, 0, data.as_ptr());
@@ -940,6 +1247,8 @@ impl Drop for FramebuffersContainer {
 struct FrameBufferObject {
     id: gl::types::GLuint,
     current_read_buffer: gl::types::GLenum,
+    // `None` if the framebuffer is complete
+    status: Option<IncompleteFramebufferReason>,
 }
 
 impl FrameBufferObject {
@@ -983,6 +1292,8 @@ impl FrameBufferObject {
             id
         };
 
+        ctxt.resource_created(ResourceKind::Framebuffer, id);
+
         // framebuffer parameters
         // TODO: DSA
         if let Some(width) = attachments.default_width {
@@ -1107,16 +1418,65 @@ impl FrameBufferObject {
                                            raw_attachments.as_ptr());
                 }
 
+            } else if raw_attachments.is_empty() {
+                // OpenGL ES 2 doesn't have draw buffers, and framebuffers without any color
+                // attachment are complete there
+
             } else {
                 // OpenGL ES 2 and OpenGL 1 don't support calling `glDrawBuffers`
                 panic!("Using more than one attachment is not supported by the backend");
             }
         }
 
+        // framebuffers without any color attachment must have their read buffer set to
+        // `GL_NONE`, otherwise they are incomplete on implementations that don't support
+        // `GL_ARB_ES2_compatibility`
+        if raw_attachments.is_empty() {
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                unsafe { ctxt.gl.NamedFramebufferReadBuffer(id, gl::NONE) };
+
+            } else if ctxt.version >= &Version(Api::Gl, 1, 0) ||
+                      ctxt.version >= &Version(Api::GlEs, 3, 0)
+            {
+                unsafe {
+                    bind_framebuffer(&mut ctxt, id, false, true);
+                    ctxt.gl.ReadBuffer(gl::NONE);
+                }
+            }
+        }
+
+        // checking whether the framebuffer is complete
+        let status = unsafe {
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                ctxt.gl.CheckNamedFramebufferStatus(id, gl::DRAW_FRAMEBUFFER)
+
+            } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                      ctxt.extensions.gl_arb_framebuffer_object
+            {
+                bind_framebuffer(&mut ctxt, id, true, false);
+                ctxt.gl.CheckFramebufferStatus(gl::DRAW_FRAMEBUFFER)
+
+            } else if ctxt.version >= &Version(Api::GlEs, 2, 0) {
+                bind_framebuffer(&mut ctxt, id, true, false);
+                ctxt.gl.CheckFramebufferStatus(gl::FRAMEBUFFER)
+
+            } else if ctxt.extensions.gl_ext_framebuffer_object {
+                bind_framebuffer(&mut ctxt, id, true, false);
+                ctxt.gl.CheckFramebufferStatusEXT(gl::FRAMEBUFFER_EXT)
+
+            } else {
+                unreachable!();
+            }
+        };
 
         FrameBufferObject {
             id: id,
             current_read_buffer: gl::BACK,
+            status: IncompleteFramebufferReason::from_status(status),
         }
     }
 
@@ -1132,6 +1492,8 @@ impl FrameBufferObject {
         }
 
         // deleting
+        ctxt.resource_destroyed(ResourceKind::Framebuffer, self.id);
+
         if ctxt.version >= &Version(Api::Gl, 3, 0) ||
             ctxt.version >= &Version(Api::GlEs, 2, 0) ||
             ctxt.extensions.gl_arb_framebuffer_object
@@ -1225,6 +1587,41 @@ pub unsafe fn bind_framebuffer(ctxt: &mut CommandContext, fbo_id: gl::types::GLu
     }
 }
 
+/// Returns the framebuffer objects currently bound for drawing and for reading.
+///
+/// The values are taken from the state cache. If the cache doesn't know them, for example after
+/// the bindings have been invalidated, they are queried from OpenGL and the cache is updated.
+pub fn get_bound_framebuffers(ctxt: &mut CommandContext)
+                              -> (gl::types::GLuint, gl::types::GLuint)
+{
+    const UNKNOWN: gl::types::GLuint = !0;
+
+    let separate_bindings = ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                            ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                            ctxt.extensions.gl_arb_framebuffer_object;
+
+    unsafe {
+        if ctxt.state.draw_framebuffer == UNKNOWN {
+            let mut value = 0;
+            // `GL_FRAMEBUFFER_BINDING` has the same value as `GL_DRAW_FRAMEBUFFER_BINDING`
+            ctxt.gl.GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut value);
+            ctxt.state.draw_framebuffer = value as gl::types::GLuint;
+        }
+
+        if ctxt.state.read_framebuffer == UNKNOWN {
+            if separate_bindings {
+                let mut value = 0;
+                ctxt.gl.GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut value);
+                ctxt.state.read_framebuffer = value as gl::types::GLuint;
+            } else {
+                ctxt.state.read_framebuffer = ctxt.state.draw_framebuffer;
+            }
+        }
+    }
+
+    (ctxt.state.draw_framebuffer, ctxt.state.read_framebuffer)
+}
+
 /// Attaches something to a framebuffer object.
 ///
 /// # Panic
@@ -12,7 +12,9 @@ use Rect;
 use BlitTarget;
 use ContextExt;
 use ToGlEnum;
+use fbo::ClearBufferData;
 use ops;
+use texture::ReadError;
 use uniforms;
 
 use {Program, Surface};
@@ -63,6 +65,26 @@ impl Surface for DefaultFramebuffer {
         ops::clear(&self.context, None, None, color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_channel(&mut self, attachment: u32, channel: u32, value: f32) {
+        ops::clear_channel(&self.context, None, attachment, channel, value);
+    }
+
+    #[inline]
+    fn clear_color_integer(&mut self, color: [i32; 4]) {
+        ops::clear_color_integral(&self.context, None, ClearBufferData::Integral(color));
+    }
+
+    #[inline]
+    fn clear_color_unsigned(&mut self, color: [u32; 4]) {
+        ops::clear_color_integral(&self.context, None, ClearBufferData::Unsigned(color));
+    }
+
+    fn read_depth_rect(&self, rect: &Rect) -> Result<Vec<f32>, ReadError> {
+        let mut ctxt = self.context.make_current();
+        ops::read_depth(&mut ctxt, None, rect)
+    }
+
     fn get_dimensions(&self) -> (u32, u32) {
         self.context.get_framebuffer_dimensions()
     }
@@ -100,6 +122,19 @@ impl Surface for DefaultFramebuffer {
             }
         }
 
+        if let Some(viewport) = draw_parameters.viewport_f {
+            if viewport.width > self.context.capabilities().max_viewport_dims.0
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+            if viewport.height > self.context.capabilities().max_viewport_dims.1
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         // TODO: wrong attachment
         ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
                   uniforms, draw_parameters, self.get_dimensions())
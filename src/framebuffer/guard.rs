@@ -0,0 +1,111 @@
+//! Contains the types used to save and restore the framebuffer bindings.
+
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use ContextExt;
+
+use {fbo, gl};
+
+/// The framebuffer objects bound for drawing and for reading.
+///
+/// The id `0` corresponds to the default framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FramebufferBinding {
+    /// The framebuffer object bound to `GL_DRAW_FRAMEBUFFER`.
+    pub draw: gl::types::GLuint,
+
+    /// The framebuffer object bound to `GL_READ_FRAMEBUFFER`.
+    pub read: gl::types::GLuint,
+}
+
+impl FramebufferBinding {
+    /// Builds a binding that uses the same framebuffer object for drawing and for reading.
+    ///
+    /// You can obtain the id of a glium framebuffer with the `GlObject` trait.
+    #[inline]
+    pub fn new(id: gl::types::GLuint) -> FramebufferBinding {
+        FramebufferBinding {
+            draw: id,
+            read: id,
+        }
+    }
+
+    /// Builds a binding that corresponds to the default framebuffer.
+    #[inline]
+    pub fn default_framebuffer() -> FramebufferBinding {
+        FramebufferBinding::new(0)
+    }
+}
+
+/// Binds a framebuffer when created, and restores the previous bindings when destroyed.
+///
+/// This allows you to write render passes that don't modify the framebuffer bound by the code
+/// that calls them, for example if this code uses raw OpenGL calls.
+///
+/// ```no_run
+/// # use glium::GlObject;
+/// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+/// # let framebuffer: glium::framebuffer::SimpleFrameBuffer = unsafe { ::std::mem::uninitialized() };
+/// use glium::framebuffer::{FramebufferBinding, FramebufferGuard};
+///
+/// {
+///     let binding = FramebufferBinding::new(framebuffer.get_id());
+///     let _guard = unsafe { FramebufferGuard::new(&display, binding) };
+///     // ...
+/// }
+///
+/// // the previous bindings are restored here
+/// ```
+///
+/// Glium binds the framebuffers it needs before each operation, so the bindings of a guard are
+/// only visible to raw OpenGL calls.
+pub struct FramebufferGuard {
+    context: Rc<Context>,
+    previous: FramebufferBinding,
+}
+
+impl FramebufferGuard {
+    /// Saves the current bindings, then binds the given framebuffers.
+    ///
+    /// # Safety
+    ///
+    /// The ids must be valid framebuffer objects, or `0`.
+    pub unsafe fn new<F: ?Sized>(facade: &F, binding: FramebufferBinding) -> FramebufferGuard
+                                 where F: Facade
+    {
+        let context = facade.get_context().clone();
+        let previous = context.current_framebuffer();
+        bind(&context, binding);
+
+        FramebufferGuard {
+            context: context,
+            previous: previous,
+        }
+    }
+
+    /// Returns the bindings that will be restored when the guard is destroyed.
+    #[inline]
+    pub fn get_previous(&self) -> FramebufferBinding {
+        self.previous
+    }
+}
+
+impl Drop for FramebufferGuard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { bind(&self.context, self.previous) };
+    }
+}
+
+unsafe fn bind(context: &Context, binding: FramebufferBinding) {
+    let mut ctxt = context.make_current();
+
+    if binding.draw == binding.read {
+        fbo::bind_framebuffer(&mut ctxt, binding.draw, true, true);
+    } else {
+        fbo::bind_framebuffer(&mut ctxt, binding.draw, true, false);
+        fbo::bind_framebuffer(&mut ctxt, binding.read, false, true);
+    }
+}
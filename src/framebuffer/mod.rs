@@ -11,6 +11,20 @@ let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture)
 // framebuffer.draw(...);    // draws over `texture`
 ```
 
+By default the main level of the texture is used. You can draw to another mipmap level by
+passing the result of `mipmap(level)` instead of the texture. The dimensions of the framebuffer,
+and therefore the default viewport, are the dimensions of this level. This is how you can build
+a mipmap pyramid yourself, for example for bloom or hierarchical Z, by drawing each level while
+sampling the previous one.
+
+```no_run
+# let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+# let texture: glium::texture::Texture2d = unsafe { ::std::mem::uninitialized() };
+let level = texture.mipmap(2).expect("The texture doesn't have this mipmap level");
+let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, level);
+// framebuffer.draw(...);    // draws over the third level of `texture`
+```
+
 If, however, your shader wants to write to multiple color buffers at once, you must use
 a `MultiOutputFrameBuffer`.
 
@@ -61,15 +75,23 @@ Modern OpenGL implementations support empty framebuffers. This is handled by gli
 
 You can check whether they are supported by calling `EmptyFrameBuffer::is_supported(&display)`.
 
+An empty framebuffer has no attachment, but its dimensions, number of layers and number of
+samples are set with `glFramebufferParameteri`. Drawing to it runs the rasterizer at the given
+resolution, which is useful if your fragment shader only writes to images or buffers.
+
 # Layered framebuffers
 
 Not yet supported
 
 */
+use std::borrow::Cow;
 use std::rc::Rc;
 use smallvec::SmallVec;
 
+use texture::TextureAny;
 use texture::TextureAnyImage;
+use texture::Texture2dDataSink;
+use texture::ReadError;
 use TextureExt;
 
 use backend::Facade;
@@ -79,6 +101,7 @@ use version::Version;
 use version::Api;
 
 use FboAttachments;
+use GlObject;
 use Rect;
 use BlitTarget;
 use ContextExt;
@@ -92,13 +115,17 @@ use DrawError;
 use {fbo, gl};
 
 pub use self::default_fb::{DefaultFramebufferAttachment, DefaultFramebuffer};
+pub use self::guard::{FramebufferBinding, FramebufferGuard};
 pub use self::render_buffer::{RenderBuffer, RenderBufferAny, DepthRenderBuffer};
 pub use self::render_buffer::{StencilRenderBuffer, DepthStencilRenderBuffer};
 pub use self::render_buffer::CreationError as RenderBufferCreationError;
 pub use fbo::is_dimensions_mismatch_supported;
 pub use fbo::ValidationError;
+pub use fbo::AttachmentPoint;
+pub use fbo::IncompleteFramebufferReason;
 
 mod default_fb;
+mod guard;
 mod render_buffer;
 
 /// A framebuffer which has only one color attachment.
@@ -117,6 +144,33 @@ impl<'a> SimpleFrameBuffer<'a> {
         SimpleFrameBuffer::new_impl(facade, Some(color.to_color_attachment()), None, None, None)
     }
 
+    /// Creates a `SimpleFrameBuffer` whose color attachment is a single layer of a mipmap level
+    /// of a texture, and without any depth or stencil buffer.
+    ///
+    /// For a 3D texture, `layer` is the Z coordinate of the slice to draw on. For an array
+    /// texture, it is the index of the layer. This lets you render a volume slice by slice. The
+    /// dimensions of the framebuffer are those of the mipmap level.
+    ///
+    /// Returns `ValidationError::LayerOutOfRange` if `level` or `layer` are out of range, or if
+    /// the texture is a cubemap.
+    pub fn with_texture_layer<F: ?Sized>(facade: &F, texture: &'a TextureAny, level: u32,
+                                         layer: u32)
+                                         -> Result<SimpleFrameBuffer<'a>, ValidationError>
+                                         where F: Facade
+    {
+        let image = texture.mipmap(level)
+                           .and_then(|mipmap| mipmap.layer(layer))
+                           .and_then(|layer| layer.into_image(None));
+
+        let image = match image {
+            Some(image) => image,
+            None => return Err(ValidationError::LayerOutOfRange),
+        };
+
+        SimpleFrameBuffer::new_impl(facade, Some(ColorAttachment::Texture(image)), None, None,
+                                    None)
+    }
+
     /// Creates a `SimpleFrameBuffer` with a single color attachment and a depth
     /// buffer, but no stencil buffer.
     #[inline]
@@ -129,8 +183,10 @@ impl<'a> SimpleFrameBuffer<'a> {
                                     Some(depth.to_depth_attachment()), None, None)
     }
 
-    /// Creates a `SimpleFrameBuffer` with a single color attachment and no depth
-    /// nor stencil buffer.
+    /// Creates a `SimpleFrameBuffer` with a depth buffer and no color attachment.
+    ///
+    /// This is usually used to render shadow maps. The draw and read buffers of the framebuffer
+    /// are set to `GL_NONE`, and the fragment shader doesn't need to write any output.
     #[inline]
     pub fn depth_only<F: ?Sized, D>(facade: &F, depth: D)
                             -> Result<SimpleFrameBuffer<'a>, ValidationError>
@@ -155,8 +211,8 @@ impl<'a> SimpleFrameBuffer<'a> {
                                     Some(stencil.to_stencil_attachment()), None)
     }
 
-    /// Creates a `SimpleFrameBuffer` with a single color attachment and no depth
-    /// nor stencil buffer.
+    /// Creates a `SimpleFrameBuffer` with a depth buffer and a stencil buffer, but no color
+    /// attachment.
     #[inline]
     pub fn depth_and_stencil_only<F: ?Sized, D, S>(facade: &F, depth: D, stencil: S)
                                            -> Result<SimpleFrameBuffer<'a>, ValidationError>
@@ -179,8 +235,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                                     Some(stencil.to_stencil_attachment()), None)
     }
 
-    /// Creates a `SimpleFrameBuffer` with a single color attachment and a stencil
-    /// buffer, but no depth buffer.
+    /// Creates a `SimpleFrameBuffer` with a stencil buffer and no color attachment.
     #[inline]
     pub fn stencil_only<F: ?Sized, S>(facade: &F, stencil: S)
                               -> Result<SimpleFrameBuffer<'a>, ValidationError>
@@ -201,7 +256,7 @@ impl<'a> SimpleFrameBuffer<'a> {
                                     Some(depthstencil.to_depth_stencil_attachment()))
     }
 
-    /// Creates a `SimpleFrameBuffer` with a single color attachment and a depth-stencil buffer.
+    /// Creates a `SimpleFrameBuffer` with a depth-stencil buffer and no color attachment.
     #[inline]
     pub fn depth_stencil_only<F: ?Sized, D>(facade: &F, depthstencil: D)
                                     -> Result<SimpleFrameBuffer<'a>, ValidationError>
@@ -211,6 +266,17 @@ impl<'a> SimpleFrameBuffer<'a> {
                                     Some(depthstencil.to_depth_stencil_attachment()))
     }
 
+    /// Checks whether the OpenGL implementation considers the framebuffer complete, in other
+    /// words whether it can be drawn on.
+    ///
+    /// Drawing on an incomplete framebuffer returns `DrawError::FramebufferIncomplete`. This
+    /// function allows you to detect the problem as soon as the framebuffer is created.
+    pub fn check_completeness(&self) -> Result<(), IncompleteFramebufferReason> {
+        let mut ctxt = self.context.make_current();
+        fbo::FramebuffersContainer::get_complete_framebuffer_for_drawing(&mut ctxt,
+                                                                         Some(&self.attachments))
+                                                                         .map(|_| ())
+    }
 
     fn new_impl<F: ?Sized>(facade: &F, color: Option<ColorAttachment<'a>>,
                    depth: Option<DepthAttachment<'a>>, stencil: Option<StencilAttachment<'a>>,
@@ -273,6 +339,28 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
         ops::clear(&self.context, Some(&self.attachments), rect, color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_channel(&mut self, attachment: u32, channel: u32, value: f32) {
+        ops::clear_channel(&self.context, Some(&self.attachments), attachment, channel, value);
+    }
+
+    #[inline]
+    fn clear_color_integer(&mut self, color: [i32; 4]) {
+        ops::clear_color_integral(&self.context, Some(&self.attachments),
+                                  fbo::ClearBufferData::Integral(color));
+    }
+
+    #[inline]
+    fn clear_color_unsigned(&mut self, color: [u32; 4]) {
+        ops::clear_color_integral(&self.context, Some(&self.attachments),
+                                  fbo::ClearBufferData::Unsigned(color));
+    }
+
+    fn read_depth_rect(&self, rect: &Rect) -> Result<Vec<f32>, ReadError> {
+        let mut ctxt = self.context.make_current();
+        ops::read_depth(&mut ctxt, Some(&self.attachments), rect)
+    }
+
     #[inline]
     fn get_dimensions(&self) -> (u32, u32) {
         self.attachments.get_dimensions()
@@ -312,6 +400,19 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
             }
         }
 
+        if let Some(viewport) = draw_parameters.viewport_f {
+            if viewport.width > self.context.capabilities().max_viewport_dims.0
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+            if viewport.height > self.context.capabilities().max_viewport_dims.1
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         ops::draw(&self.context, Some(&self.attachments), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
@@ -350,6 +451,19 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
     }
 }
 
+/// The id of a `SimpleFrameBuffer` is the id of the framebuffer object that glium uses internally
+/// when drawing to it. This object is created the first time that the id is requested and is
+/// cached by the context. It must not be modified or deleted.
+impl<'a> GlObject for SimpleFrameBuffer<'a> {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        let mut ctxt = self.context.make_current();
+        fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, Some(&self.attachments))
+    }
+}
+
 impl<'a> FboAttachments for SimpleFrameBuffer<'a> {
     #[inline]
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments> {
@@ -513,6 +627,31 @@ impl<'a> MultiOutputFrameBuffer<'a> {
         })
     }
 
+    /// Reads the content of one of the color attachments.
+    ///
+    /// `index` is the position of the attachment in the list that was passed when creating
+    /// the framebuffer. Returns `None` if there is no color attachment at this index.
+    ///
+    /// Reading the framebuffer with `glReadPixels` would normally only ever give you the content
+    /// of the first attachment. This function lets you read any of them, for example the normals
+    /// or materials of a G-buffer.
+    pub fn read_from_attachment<T>(&self, index: usize) -> Option<T>
+                                   where T: Texture2dDataSink<(u8, u8, u8, u8)>
+    {
+        let attachment = match self.color_attachments.get(index) {
+            Some(&(_, ref attachment)) => attachment,
+            None => return None,
+        };
+
+        let (width, height) = self.example_attachments.get_dimensions();
+        let rect = Rect { left: 0, bottom: 0, width: width, height: height };
+
+        let mut ctxt = self.context.make_current();
+        let mut data = Vec::with_capacity(0);
+        ops::read(&mut ctxt, attachment, &rect, &mut data, false).unwrap();
+        Some(T::from_raw(Cow::Owned(data), width, height))
+    }
+
     fn build_attachments(&self, program: &Program) -> fbo::ValidatedAttachments {
         let mut colors = SmallVec::new();
 
@@ -541,6 +680,28 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
                    color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_channel(&mut self, attachment: u32, channel: u32, value: f32) {
+        ops::clear_channel(&self.context, Some(&self.example_attachments), attachment, channel, value);
+    }
+
+    #[inline]
+    fn clear_color_integer(&mut self, color: [i32; 4]) {
+        ops::clear_color_integral(&self.context, Some(&self.example_attachments),
+                                  fbo::ClearBufferData::Integral(color));
+    }
+
+    #[inline]
+    fn clear_color_unsigned(&mut self, color: [u32; 4]) {
+        ops::clear_color_integral(&self.context, Some(&self.example_attachments),
+                                  fbo::ClearBufferData::Unsigned(color));
+    }
+
+    fn read_depth_rect(&self, rect: &Rect) -> Result<Vec<f32>, ReadError> {
+        let mut ctxt = self.context.make_current();
+        ops::read_depth(&mut ctxt, Some(&self.example_attachments), rect)
+    }
+
     #[inline]
     fn get_dimensions(&self) -> (u32, u32) {
         self.example_attachments.get_dimensions()
@@ -580,6 +741,19 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
             }
         }
 
+        if let Some(viewport) = draw_parameters.viewport_f {
+            if viewport.width > self.context.capabilities().max_viewport_dims.0
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+            if viewport.height > self.context.capabilities().max_viewport_dims.1
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         ops::draw(&self.context, Some(&self.build_attachments(program)), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
@@ -710,6 +884,28 @@ impl Surface for EmptyFrameBuffer {
         ops::clear(&self.context, Some(&self.attachments), rect, color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_channel(&mut self, attachment: u32, channel: u32, value: f32) {
+        ops::clear_channel(&self.context, Some(&self.attachments), attachment, channel, value);
+    }
+
+    #[inline]
+    fn clear_color_integer(&mut self, color: [i32; 4]) {
+        ops::clear_color_integral(&self.context, Some(&self.attachments),
+                                  fbo::ClearBufferData::Integral(color));
+    }
+
+    #[inline]
+    fn clear_color_unsigned(&mut self, color: [u32; 4]) {
+        ops::clear_color_integral(&self.context, Some(&self.attachments),
+                                  fbo::ClearBufferData::Unsigned(color));
+    }
+
+    fn read_depth_rect(&self, rect: &Rect) -> Result<Vec<f32>, ReadError> {
+        let mut ctxt = self.context.make_current();
+        ops::read_depth(&mut ctxt, Some(&self.attachments), rect)
+    }
+
     #[inline]
     fn get_dimensions(&self) -> (u32, u32) {
         self.attachments.get_dimensions()
@@ -749,6 +945,19 @@ impl Surface for EmptyFrameBuffer {
             }
         }
 
+        if let Some(viewport) = draw_parameters.viewport_f {
+            if viewport.width > self.context.capabilities().max_viewport_dims.0
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+            if viewport.height > self.context.capabilities().max_viewport_dims.1
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         ops::draw(&self.context, Some(&self.attachments), vb,
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
@@ -787,6 +996,17 @@ impl Surface for EmptyFrameBuffer {
     }
 }
 
+/// See the documentation of the implementation for `SimpleFrameBuffer`.
+impl GlObject for EmptyFrameBuffer {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        let mut ctxt = self.context.make_current();
+        fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, Some(&self.attachments))
+    }
+}
+
 impl FboAttachments for EmptyFrameBuffer {
     #[inline]
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments> {
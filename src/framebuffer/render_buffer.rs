@@ -25,7 +25,11 @@ use GlObject;
 use fbo::FramebuffersContainer;
 use backend::Facade;
 use context::Context;
+use context::ResourceKind;
+use context::LabeledObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use ContextExt;
+use CapabilitiesSource;
 use version::Version;
 use version::Api;
 
@@ -34,6 +38,12 @@ use version::Api;
 pub enum CreationError {
     /// The requested format is not supported.
     FormatNotSupported,
+
+    /// Multisample render buffers are not supported by the backend.
+    MultisamplingNotSupported,
+
+    /// The requested number of samples is not supported for this format.
+    SamplesCountNotSupported,
 }
 
 impl fmt::Display for CreationError {
@@ -47,6 +57,8 @@ impl Error for CreationError {
         use self::CreationError::*;
         match *self {
             FormatNotSupported => "The requested format is not supported",
+            MultisamplingNotSupported => "Multisample render buffers are not supported by the backend",
+            SamplesCountNotSupported => "The requested number of samples is not supported for this format",
         }
     }
 }
@@ -76,6 +88,24 @@ impl RenderBuffer {
             buffer: RenderBufferAny::new(facade, format, TextureKind::Float, width, height, None)
         })
     }
+
+    /// Builds a new multisample render buffer.
+    ///
+    /// Multisample render buffers can't be read directly. Blit them to a regular texture or
+    /// render buffer in order to resolve them.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<RenderBuffer, CreationError>
+                                      where F: Facade
+    {
+        let format = image_format::TextureFormat::UncompressedFloat(format);
+        try!(check_multisample(facade, format, samples));
+        let format = image_format::TextureFormatRequest::Specific(format);
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+
+        Ok(RenderBuffer {
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Float, width, height, Some(samples))
+        })
+    }
 }
 
 impl<'a> ToColorAttachment<'a> for &'a RenderBuffer {
@@ -129,6 +159,24 @@ impl DepthRenderBuffer {
             buffer: RenderBufferAny::new(facade, format, TextureKind::Depth, width, height, None)
         })
     }
+
+    /// Builds a new multisample render buffer.
+    ///
+    /// Multisample render buffers can't be read directly. Blit them to a regular texture or
+    /// render buffer in order to resolve them.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: DepthFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<DepthRenderBuffer, CreationError>
+                                      where F: Facade
+    {
+        let format = image_format::TextureFormat::DepthFormat(format);
+        try!(check_multisample(facade, format, samples));
+        let format = image_format::TextureFormatRequest::Specific(format);
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+
+        Ok(DepthRenderBuffer {
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Depth, width, height, Some(samples))
+        })
+    }
 }
 
 impl<'a> ToDepthAttachment<'a> for &'a DepthRenderBuffer {
@@ -181,6 +229,24 @@ impl StencilRenderBuffer {
             buffer: RenderBufferAny::new(facade, format, TextureKind::Stencil, width, height, None)
         })
     }
+
+    /// Builds a new multisample render buffer.
+    ///
+    /// Multisample render buffers can't be read directly. Blit them to a regular texture or
+    /// render buffer in order to resolve them.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: StencilFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<StencilRenderBuffer, CreationError>
+                                      where F: Facade
+    {
+        let format = image_format::TextureFormat::StencilFormat(format);
+        try!(check_multisample(facade, format, samples));
+        let format = image_format::TextureFormatRequest::Specific(format);
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+
+        Ok(StencilRenderBuffer {
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Stencil, width, height, Some(samples))
+        })
+    }
 }
 
 impl<'a> ToStencilAttachment<'a> for &'a StencilRenderBuffer {
@@ -234,6 +300,24 @@ impl DepthStencilRenderBuffer {
             buffer: RenderBufferAny::new(facade, format, TextureKind::DepthStencil, width, height, None)
         })
     }
+
+    /// Builds a new multisample render buffer.
+    ///
+    /// Multisample render buffers can't be read directly. Blit them to a regular texture or
+    /// render buffer in order to resolve them.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: DepthStencilFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<DepthStencilRenderBuffer, CreationError>
+                                      where F: Facade
+    {
+        let format = image_format::TextureFormat::DepthStencilFormat(format);
+        try!(check_multisample(facade, format, samples));
+        let format = image_format::TextureFormatRequest::Specific(format);
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+
+        Ok(DepthStencilRenderBuffer {
+            buffer: RenderBufferAny::new(facade, format, TextureKind::DepthStencil, width, height, Some(samples))
+        })
+    }
 }
 
 impl<'a> ToDepthStencilAttachment<'a> for &'a DepthStencilRenderBuffer {
@@ -268,6 +352,40 @@ impl GlObject for DepthStencilRenderBuffer {
     }
 }
 
+/// Checks that the backend supports multisample render buffers with the given format and
+/// number of samples.
+fn check_multisample<F: ?Sized>(facade: &F, format: image_format::TextureFormat, samples: u32)
+                                -> Result<(), CreationError> where F: Facade
+{
+    let context = facade.get_context();
+    let version = context.get_version();
+    let extensions = context.get_extensions();
+
+    if !(version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+         extensions.gl_arb_direct_state_access || extensions.gl_apple_framebuffer_multisample ||
+         extensions.gl_angle_framebuffer_multisample ||
+         extensions.gl_ext_multisampled_render_to_texture ||
+         extensions.gl_nv_framebuffer_multisample ||
+         (extensions.gl_ext_framebuffer_object && extensions.gl_ext_framebuffer_multisample))
+    {
+        return Err(CreationError::MultisamplingNotSupported);
+    }
+
+    if samples == 0 {
+        return Err(CreationError::SamplesCountNotSupported);
+    }
+
+    // the list of supported samples counts is only available with `glGetInternalformativ`
+    let infos = context.get_capabilities().internal_formats_renderbuffers.get(&format);
+    if let Some(&Some(ref multisamples)) = infos.map(|i| &i.multisamples) {
+        if multisamples.iter().find(|&&s| s as u32 == samples).is_none() {
+            return Err(CreationError::SamplesCountNotSupported);
+        }
+    }
+
+    Ok(())
+}
+
 /// A RenderBuffer of indeterminate type.
 pub struct RenderBufferAny {
     context: Rc<Context>,
@@ -393,6 +511,8 @@ impl RenderBufferAny {
                 unreachable!();
             }
 
+            ctxt.resource_created(ResourceKind::Renderbuffer, id);
+
             RenderBufferAny {
                 context: facade.get_context().clone(),
                 id: id,
@@ -437,6 +557,7 @@ impl Drop for RenderBufferAny {
 
             // removing FBOs which contain this buffer
             FramebuffersContainer::purge_renderbuffer(&mut ctxt, self.id);
+            ctxt.resource_destroyed(ResourceKind::Renderbuffer, self.id);
 
             if ctxt.version >= &Version(Api::Gl, 3, 0) ||
                ctxt.version >= &Version(Api::GlEs, 2, 0)
@@ -461,6 +582,14 @@ impl Drop for RenderBufferAny {
     }
 }
 
+impl Labeled for RenderBufferAny {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.context.make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::RENDERBUFFER, self.id), label)
+    }
+}
+
 impl GlObject for RenderBufferAny {
     type Id = gl::types::GLuint;
 
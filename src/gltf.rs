@@ -0,0 +1,146 @@
+/*!
+Conversions from the numeric values used by glTF to glium types.
+
+*This module is only available if the `gltf` feature of glium is enabled.*
+
+This is not a glTF loader. It only maps the values of the `sampler` and `accessor` objects of a
+glTF file to the corresponding glium types, so that they can be used with the samplers and with
+the raw vertex buffer API.
+
+```
+use glium::uniforms::{SamplerWrapFunction, MinifySamplerFilter};
+use glium::vertex::AttributeType;
+
+assert_eq!(SamplerWrapFunction::from_gltf(33648), Some(SamplerWrapFunction::Mirror));
+assert_eq!(MinifySamplerFilter::from_gltf(9987), Some(MinifySamplerFilter::LinearMipmapLinear));
+
+// an accessor with `"componentType": 5126` and `"type": "VEC3"`
+let attribute = glium::gltf::accessor_to_attribute("position", 0, 5126, "VEC3", false).unwrap();
+assert_eq!(attribute.ty, AttributeType::F32F32F32);
+```
+
+*/
+use std::borrow::Cow;
+
+use uniforms::{SamplerWrapFunction, MinifySamplerFilter, MagnifySamplerFilter};
+use vertex::{AttributeType, VertexAttribDesc};
+
+use gl;
+
+/// Value of the `componentType` of an accessor that contains `i8`s.
+pub const BYTE: u32 = 5120;
+/// Value of the `componentType` of an accessor that contains `u8`s.
+pub const UNSIGNED_BYTE: u32 = 5121;
+/// Value of the `componentType` of an accessor that contains `i16`s.
+pub const SHORT: u32 = 5122;
+/// Value of the `componentType` of an accessor that contains `u16`s.
+pub const UNSIGNED_SHORT: u32 = 5123;
+/// Value of the `componentType` of an accessor that contains `u32`s.
+pub const UNSIGNED_INT: u32 = 5125;
+/// Value of the `componentType` of an accessor that contains `f32`s.
+pub const FLOAT: u32 = 5126;
+
+impl SamplerWrapFunction {
+    /// Returns the wrap function that corresponds to the `wrapS` or `wrapT` value of a glTF
+    /// sampler, or `None` if the value is unknown.
+    ///
+    /// *This function is only available if the `gltf` feature of glium is enabled.*
+    pub fn from_gltf(value: u32) -> Option<SamplerWrapFunction> {
+        match value {
+            gl::REPEAT => Some(SamplerWrapFunction::Repeat),
+            gl::MIRRORED_REPEAT => Some(SamplerWrapFunction::Mirror),
+            gl::CLAMP_TO_EDGE => Some(SamplerWrapFunction::Clamp),
+            _ => None,
+        }
+    }
+}
+
+impl MinifySamplerFilter {
+    /// Returns the filter that corresponds to the `minFilter` value of a glTF sampler, or `None`
+    /// if the value is unknown.
+    ///
+    /// *This function is only available if the `gltf` feature of glium is enabled.*
+    pub fn from_gltf(value: u32) -> Option<MinifySamplerFilter> {
+        match value {
+            gl::NEAREST => Some(MinifySamplerFilter::Nearest),
+            gl::LINEAR => Some(MinifySamplerFilter::Linear),
+            gl::NEAREST_MIPMAP_NEAREST => Some(MinifySamplerFilter::NearestMipmapNearest),
+            gl::LINEAR_MIPMAP_NEAREST => Some(MinifySamplerFilter::LinearMipmapNearest),
+            gl::NEAREST_MIPMAP_LINEAR => Some(MinifySamplerFilter::NearestMipmapLinear),
+            gl::LINEAR_MIPMAP_LINEAR => Some(MinifySamplerFilter::LinearMipmapLinear),
+            _ => None,
+        }
+    }
+}
+
+impl MagnifySamplerFilter {
+    /// Returns the filter that corresponds to the `magFilter` value of a glTF sampler, or `None`
+    /// if the value is unknown.
+    ///
+    /// *This function is only available if the `gltf` feature of glium is enabled.*
+    pub fn from_gltf(value: u32) -> Option<MagnifySamplerFilter> {
+        match value {
+            gl::NEAREST => Some(MagnifySamplerFilter::Nearest),
+            gl::LINEAR => Some(MagnifySamplerFilter::Linear),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the type of an attribute stored in an accessor, from the `componentType` and the
+/// `type` of the accessor.
+///
+/// Returns `None` if the combination is unknown. Matrices are only supported with `FLOAT`
+/// components, as the other component types require padding between the columns.
+pub fn accessor_attribute_type(component_type: u32, ty: &str) -> Option<AttributeType> {
+    let ty = match (component_type, ty) {
+        (BYTE, "SCALAR") => AttributeType::I8,
+        (BYTE, "VEC2") => AttributeType::I8I8,
+        (BYTE, "VEC3") => AttributeType::I8I8I8,
+        (BYTE, "VEC4") => AttributeType::I8I8I8I8,
+        (UNSIGNED_BYTE, "SCALAR") => AttributeType::U8,
+        (UNSIGNED_BYTE, "VEC2") => AttributeType::U8U8,
+        (UNSIGNED_BYTE, "VEC3") => AttributeType::U8U8U8,
+        (UNSIGNED_BYTE, "VEC4") => AttributeType::U8U8U8U8,
+        (SHORT, "SCALAR") => AttributeType::I16,
+        (SHORT, "VEC2") => AttributeType::I16I16,
+        (SHORT, "VEC3") => AttributeType::I16I16I16,
+        (SHORT, "VEC4") => AttributeType::I16I16I16I16,
+        (UNSIGNED_SHORT, "SCALAR") => AttributeType::U16,
+        (UNSIGNED_SHORT, "VEC2") => AttributeType::U16U16,
+        (UNSIGNED_SHORT, "VEC3") => AttributeType::U16U16U16,
+        (UNSIGNED_SHORT, "VEC4") => AttributeType::U16U16U16U16,
+        (UNSIGNED_INT, "SCALAR") => AttributeType::U32,
+        (UNSIGNED_INT, "VEC2") => AttributeType::U32U32,
+        (UNSIGNED_INT, "VEC3") => AttributeType::U32U32U32,
+        (UNSIGNED_INT, "VEC4") => AttributeType::U32U32U32U32,
+        (FLOAT, "SCALAR") => AttributeType::F32,
+        (FLOAT, "VEC2") => AttributeType::F32F32,
+        (FLOAT, "VEC3") => AttributeType::F32F32F32,
+        (FLOAT, "VEC4") => AttributeType::F32F32F32F32,
+        (FLOAT, "MAT2") => AttributeType::F32x2x2,
+        (FLOAT, "MAT3") => AttributeType::F32x3x3,
+        (FLOAT, "MAT4") => AttributeType::F32x4x4,
+        _ => return None,
+    };
+
+    Some(ty)
+}
+
+/// Builds the description of a vertex attribute stored in an accessor, to be used with
+/// `VertexBufferAny::from_raw`.
+///
+/// `offset` is the offset of the attribute from the start of the vertex. It is usually the
+/// `byteOffset` of the accessor if the buffer view is interleaved, and `0` otherwise.
+/// `component_type`, `ty` and `normalized` are the `componentType`, `type` and `normalized`
+/// values of the accessor.
+///
+/// Returns `None` in the same situations as `accessor_attribute_type`.
+pub fn accessor_to_attribute<S>(name: S, offset: usize, component_type: u32, ty: &str,
+                                normalized: bool) -> Option<VertexAttribDesc>
+                                where S: Into<Cow<'static, str>>
+{
+    accessor_attribute_type(component_type, ty).map(|ty| {
+        VertexAttribDesc::new(name, offset, ty, normalized)
+    })
+}
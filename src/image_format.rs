@@ -29,7 +29,7 @@ impl Error for FormatNotSupportedError {
 }
 
 /// Texture format request.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TextureFormatRequest {
     /// Request a specific format.
     Specific(TextureFormat),
@@ -1788,6 +1788,13 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         },
 
         TextureFormatRequest::Specific(TextureFormat::CompressedFormat(format)) => {
+            // OpenGL ES can't compress uncompressed data during the upload
+            if !is_client_compressed && rq_ty.get_client_format().is_some() &&
+               version.0 == Api::GlEs
+            {
+                return Err(FormatNotSupportedError);
+            }
+
             if format.is_supported(context) {
                 format.to_glenum()
             } else {
@@ -1860,6 +1867,13 @@ pub fn format_request_to_glenum(context: &Context, format: TextureFormatRequest,
         },
 
         TextureFormatRequest::Specific(TextureFormat::CompressedSrgbFormat(format)) => {
+            // OpenGL ES can't compress uncompressed data during the upload
+            if !is_client_compressed && rq_ty.get_client_format().is_some() &&
+               version.0 == Api::GlEs
+            {
+                return Err(FormatNotSupportedError);
+            }
+
             if format.is_supported(context) {
                 format.to_glenum()
             } else {
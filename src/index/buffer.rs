@@ -1,6 +1,7 @@
 use buffer::{Buffer, BufferSlice, BufferMutSlice, BufferAny, BufferType};
 use buffer::{BufferMode, BufferCreationError};
 use gl;
+use BufferExt;
 use GlObject;
 
 use backend::Facade;
@@ -10,7 +11,9 @@ use index::Index;
 use index::IndexType;
 use index::PrimitiveType;
 
+use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::{u8, u16, u32};
 use std::fmt;
 use std::error::Error;
 use utils::range::RangeArgument;
@@ -24,6 +27,18 @@ pub enum CreationError {
     /// The type of primitives is not supported by the backend.
     PrimitiveTypeNotSupported,
 
+    /// One of the indices doesn't fit in the requested type of index.
+    IndexTooLarge {
+        /// The value of the index.
+        value: u32,
+        /// The requested type of index.
+        ty: IndexType,
+    },
+
+    /// The mesh has more vertices than a 32-bit index can address, or more indices than can
+    /// be stored in memory.
+    TooManyVertices,
+
     /// An error happened while creating the buffer.
     BufferCreationError(BufferCreationError),
 }
@@ -42,6 +57,10 @@ impl Error for CreationError {
                 "The type of index is not supported by the backend",
             PrimitiveTypeNotSupported =>
                 "The type of primitives is not supported by the backend",
+            IndexTooLarge { .. } =>
+                "One of the indices doesn't fit in the requested type of index",
+            TooManyVertices =>
+                "The mesh has more vertices than a 32-bit index can address",
             BufferCreationError(_) =>
                 "An error happened while creating the buffer",
         }
@@ -89,6 +108,18 @@ impl<T> IndexBuffer<T> where T: Index {
         IndexBuffer::new_impl(facade, prim, data, BufferMode::Dynamic)
     }
 
+    /// Builds a new index buffer from a list of indices and a primitive type.
+    ///
+    /// The content of the buffer is intended to be replaced every frame. See
+    /// `BufferMode::Stream`.
+    #[inline]
+    pub fn stream<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[T])
+                     -> Result<IndexBuffer<T>, CreationError>
+                     where F: Facade
+    {
+        IndexBuffer::new_impl(facade, prim, data, BufferMode::Stream)
+    }
+
     /// Builds a new index buffer from a list of indices and a primitive type.
     #[inline]
     pub fn persistent<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[T])
@@ -99,6 +130,9 @@ impl<T> IndexBuffer<T> where T: Index {
     }
 
     /// Builds a new index buffer from a list of indices and a primitive type.
+    ///
+    /// The content of the buffer is not supposed to change. Modifying it is possible but very
+    /// slow. See `VertexBuffer::immutable` and `BufferMode::Immutable`.
     #[inline]
     pub fn immutable<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[T])
                         -> Result<IndexBuffer<T>, CreationError>
@@ -144,6 +178,15 @@ impl<T> IndexBuffer<T> where T: Index {
         IndexBuffer::empty_impl(facade, prim, len, BufferMode::Dynamic)
     }
 
+    /// Builds a new empty index buffer.
+    #[inline]
+    pub fn empty_stream<F: ?Sized>(facade: &F, prim: PrimitiveType, len: usize)
+                           -> Result<IndexBuffer<T>, CreationError>
+                           where F: Facade
+    {
+        IndexBuffer::empty_impl(facade, prim, len, BufferMode::Stream)
+    }
+
     /// Builds a new empty index buffer.
     #[inline]
     pub fn empty_persistent<F: ?Sized>(facade: &F, prim: PrimitiveType, len: usize)
@@ -194,15 +237,36 @@ impl<T> IndexBuffer<T> where T: Index {
         <T as Index>::get_type()
     }
 
-    /// Returns `None` if out of range.
+    /// Returns a source of indices that also contains the range of values of the indices in
+    /// this buffer. Drawing with it uses `glDrawRangeElements` when it is supported.
+    ///
+    /// `start` and `end` are the minimum and maximum values (inclusive) of the indices.
+    ///
+    /// ## Panic
+    ///
+    /// In debug builds, reads the content of the buffer and panics if one of the indices is
+    /// outside of the range.
+    #[inline]
+    pub fn with_range(&self, start: u32, end: u32) -> IndicesSource {
+        check_indices_range(&self.buffer.as_slice(), start, end);
+
+        IndicesSource::IndexBufferRange {
+            buffer: self.buffer.as_slice_any(),
+            data_type: self.get_indices_type(),
+            primitives: self.primitives,
+            start: start,
+            end: end,
+        }
+    }
+
+    /// Builds a slice of this index buffer. The slice can be written to and drawn from
+    /// independently, which allows you to share a single index buffer between multiple meshes.
+    ///
+    /// Returns `None` if out of range, or if the range doesn't start and end at a primitive
+    /// boundary (for example, a multiple of 3 for `TrianglesList`).
     #[inline]
     pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<IndexBufferSlice<T>> {
-        self.buffer.slice(range).map(|b| {
-            IndexBufferSlice {
-                buffer: b,
-                primitives: self.primitives,
-            }
-        })
+        self.buffer.slice(range).and_then(|b| IndexBufferSlice::from_slice(b, self.primitives))
     }
 }
 
@@ -279,14 +343,47 @@ impl<'a, T: 'a> IndexBufferSlice<'a, T> where T: Index {
         <T as Index>::get_type()
     }
 
-    /// Returns `None` if out of range.
+    /// Returns a source of indices that also contains the range of values of the indices in
+    /// this slice. See `IndexBuffer::with_range`.
+    #[inline]
+    pub fn with_range(&self, start: u32, end: u32) -> IndicesSource<'a> {
+        check_indices_range(&self.buffer, start, end);
+
+        IndicesSource::IndexBufferRange {
+            buffer: self.buffer.as_slice_any(),
+            data_type: self.get_indices_type(),
+            primitives: self.primitives,
+            start: start,
+            end: end,
+        }
+    }
+
+    /// Builds a slice of this slice.
+    ///
+    /// Returns `None` if out of range, or if the range doesn't start and end at a primitive
+    /// boundary. See `IndexBuffer::slice`.
     #[inline]
     pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<IndexBufferSlice<'a, T>> {
-        self.buffer.slice(range).map(|b| {
-            IndexBufferSlice {
-                buffer: b,
-                primitives: self.primitives,
+        self.buffer.slice(range).and_then(|b| IndexBufferSlice::from_slice(b, self.primitives))
+    }
+
+    /// Builds an `IndexBufferSlice`, checking that the slice covers whole primitives.
+    fn from_slice(buffer: BufferSlice<'a, [T]>, primitives: PrimitiveType)
+                  -> Option<IndexBufferSlice<'a, T>>
+    {
+        if let Some(per_primitive) = primitives.get_indices_per_primitive() {
+            let first = buffer.get_offset_bytes() / mem::size_of::<T>();
+
+            if per_primitive == 0 || first % per_primitive != 0 ||
+               buffer.len() % per_primitive != 0
+            {
+                return None;
             }
+        }
+
+        Some(IndexBufferSlice {
+            buffer: buffer,
+            primitives: primitives,
         })
     }
 }
@@ -347,6 +444,111 @@ pub struct IndexBufferAny {
 }
 
 impl IndexBufferAny {
+    /// Builds a new index buffer from a list of indices, using the smallest type of index that
+    /// can hold all the values.
+    ///
+    /// Call `get_indices_type()` on the result to know which type has been chosen.
+    #[inline]
+    pub fn new_auto<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[u32])
+                               -> Result<IndexBufferAny, CreationError>
+                               where F: Facade
+    {
+        let max = data.iter().cloned().max().unwrap_or(0);
+
+        let ty = if max <= u8::MAX as u32 {
+            IndexType::U8
+        } else if max <= u16::MAX as u32 {
+            IndexType::U16
+        } else {
+            IndexType::U32
+        };
+
+        IndexBufferAny::new_with_type(facade, prim, data, ty)
+    }
+
+    /// Builds a new index buffer from a list of indices, converting them to the given type.
+    ///
+    /// Returns `IndexTooLarge` if one of the indices doesn't fit in `ty`, instead of silently
+    /// truncating it.
+    pub fn new_with_type<F: ?Sized>(facade: &F, prim: PrimitiveType, data: &[u32],
+                                    ty: IndexType) -> Result<IndexBufferAny, CreationError>
+                                    where F: Facade
+    {
+        let max = match ty {
+            IndexType::U8 => u8::MAX as u32,
+            IndexType::U16 => u16::MAX as u32,
+            IndexType::U32 => u32::MAX,
+        };
+
+        if let Some(&value) = data.iter().find(|&&i| i > max) {
+            return Err(CreationError::IndexTooLarge { value: value, ty: ty });
+        }
+
+        Ok(match ty {
+            IndexType::U8 => {
+                let data = data.iter().map(|&i| i as u8).collect::<Vec<_>>();
+                try!(IndexBuffer::new(facade, prim, &data)).into()
+            },
+            IndexType::U16 => {
+                let data = data.iter().map(|&i| i as u16).collect::<Vec<_>>();
+                try!(IndexBuffer::new(facade, prim, &data)).into()
+            },
+            IndexType::U32 => {
+                try!(IndexBuffer::new(facade, prim, data)).into()
+            },
+        })
+    }
+
+    /// Builds a list of triangles that covers a grid of `cols` by `rows` cells, using the
+    /// smallest type of index that can hold all the values.
+    ///
+    /// The grid is made of `(cols + 1) * (rows + 1)` vertices. The vertex at column `x` and row
+    /// `y` must be at index `y * (cols + 1) + x` in the vertex buffer. If the columns go
+    /// towards the right and the rows go upwards, the triangles are counter-clockwise.
+    pub fn grid<F: ?Sized>(facade: &F, cols: u32, rows: u32)
+                           -> Result<IndexBufferAny, CreationError> where F: Facade
+    {
+        let width = try!(cols.checked_add(1).ok_or(CreationError::TooManyVertices));
+        let height = try!(rows.checked_add(1).ok_or(CreationError::TooManyVertices));
+        try!(width.checked_mul(height).ok_or(CreationError::TooManyVertices));
+        let num_indices = try!((cols as usize).checked_mul(rows as usize)
+                                              .and_then(|n| n.checked_mul(6))
+                                              .ok_or(CreationError::TooManyVertices));
+
+        let mut data = Vec::with_capacity(num_indices);
+        for y in 0 .. rows {
+            for x in 0 .. cols {
+                let bottom_left = y * width + x;
+                let top_left = bottom_left + width;
+                data.extend_from_slice(&[bottom_left, bottom_left + 1, top_left,
+                                         top_left, bottom_left + 1, top_left + 1]);
+            }
+        }
+
+        IndexBufferAny::new_auto(facade, PrimitiveType::TrianglesList, &data)
+    }
+
+    /// Builds a list of triangles that draws `quad_count` quads, using the smallest type of
+    /// index that can hold all the values.
+    ///
+    /// Each quad is made of four consecutive vertices and is drawn with the indices
+    /// `0, 1, 2, 0, 2, 3`. This is the layout that sprite batches usually use.
+    pub fn quads<F: ?Sized>(facade: &F, quad_count: u32)
+                            -> Result<IndexBufferAny, CreationError> where F: Facade
+    {
+        try!(quad_count.checked_mul(4).ok_or(CreationError::TooManyVertices));
+        let num_indices = try!((quad_count as usize).checked_mul(6)
+                                                    .ok_or(CreationError::TooManyVertices));
+
+        let mut data = Vec::with_capacity(num_indices);
+        for quad in 0 .. quad_count {
+            let first = quad * 4;
+            data.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+
+        IndexBufferAny::new_auto(facade, PrimitiveType::TrianglesList, &data)
+    }
+
     /// Returns the type of primitives associated with this index buffer.
     #[inline]
     pub fn get_primitives_type(&self) -> PrimitiveType {
@@ -399,3 +601,32 @@ impl<'a> From<&'a IndexBufferAny> for IndicesSource<'a> {
         }
     }
 }
+
+/// In debug builds, checks that all the indices of the buffer are within `start` and `end`.
+#[cfg(debug_assertions)]
+fn check_indices_range<T>(buffer: &BufferSlice<[T]>, start: u32, end: u32) where T: Index {
+    let data = match buffer.read() {
+        Ok(d) => d,
+        Err(_) => return,       // the buffer can't be read, so we can't check anything
+    };
+
+    for index in data.into_iter() {
+        // the `Index` trait guarantees that `T` corresponds to the type returned by `get_type`
+        let index = unsafe {
+            match T::get_type() {
+                IndexType::U8 => mem::transmute_copy::<T, u8>(&index) as u32,
+                IndexType::U16 => mem::transmute_copy::<T, u16>(&index) as u32,
+                IndexType::U32 => mem::transmute_copy::<T, u32>(&index),
+            }
+        };
+
+        if index < start || index > end {
+            panic!("The index {} is outside of the declared range {}..{}", index, start, end);
+        }
+    }
+}
+
+#[cfg(not(debug_assertions))]
+#[inline(always)]
+fn check_indices_range<T>(_: &BufferSlice<[T]>, _: u32, _: u32) where T: Index {
+}
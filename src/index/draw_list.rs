@@ -0,0 +1,240 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use buffer::BufferCreationError;
+
+use index::{Index, IndexBuffer};
+use index::multidraw::{DrawCommandIndices, DrawCommandsIndicesBuffer};
+use index::multidraw::is_multidraw_indirect_supported;
+use uniforms::Uniforms;
+use vertex::VertexBuffer;
+
+use DrawError;
+use DrawParameters;
+use Program;
+use Surface;
+
+/// How the items of a `DrawList` are submitted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SubmissionMode {
+    /// Each item is drawn with its own draw call.
+    Direct,
+
+    /// All the items are written to a buffer of draw commands and drawn with a single call to
+    /// `glMultiDrawElementsIndirect`. Submitting returns
+    /// `DrawError::IndirectDrawNotSupported` if the backend doesn't support it.
+    Indirect,
+
+    /// Uses `Indirect` if the backend supports it and if the buffer of draw commands can be
+    /// created, and `Direct` otherwise.
+    Automatic,
+}
+
+/// Error that can happen while submitting a `DrawList`.
+#[derive(Debug)]
+pub enum SubmitError {
+    /// Failed to create the buffer of draw commands.
+    BufferCreationError(BufferCreationError),
+
+    /// Failed to draw on the surface.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for SubmitError {
+    fn description(&self) -> &str {
+        use self::SubmitError::*;
+        match *self {
+            BufferCreationError(_) =>
+                "Failed to create the buffer of draw commands",
+            DrawError(_) =>
+                "Failed to draw on the surface",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        use self::SubmitError::*;
+        match *self {
+            BufferCreationError(ref err) => Some(err),
+            DrawError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<BufferCreationError> for SubmitError {
+    #[inline]
+    fn from(err: BufferCreationError) -> SubmitError {
+        SubmitError::BufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for SubmitError {
+    #[inline]
+    fn from(err: DrawError) -> SubmitError {
+        SubmitError::DrawError(err)
+    }
+}
+
+/// A list of ranges of the same index buffer and vertex buffer that are drawn with the same
+/// program, uniforms and draw parameters.
+///
+/// The list can be submitted either as one draw call per item or as a single multidraw indirect
+/// call, which lets you use the same code whether or not the hardware supports multidraw
+/// indirect, and compare the performances of both.
+///
+/// ## Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # use glium::Surface;
+/// # #[derive(Copy, Clone)] struct Vertex { position: [f32; 2] }
+/// # implement_vertex!(Vertex, position);
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+/// # let vertex_buffer: glium::VertexBuffer<Vertex> = unsafe { std::mem::uninitialized() };
+/// # let index_buffer: glium::IndexBuffer<u16> = unsafe { std::mem::uninitialized() };
+/// use glium::index::{DrawList, SubmissionMode};
+///
+/// let mut list = DrawList::new(&vertex_buffer, &index_buffer);
+/// list.push(0 .. 6, 0);      // first mesh
+/// list.push(6 .. 12, 4);     // second mesh, whose indices start at vertex 4
+///
+/// let mut target = display.draw();
+/// list.submit(&mut target, &program, &uniform!{}, &Default::default(),
+///             SubmissionMode::Automatic).unwrap();
+/// target.finish().unwrap();
+/// # }
+/// ```
+pub struct DrawList<'a, V: 'a, I: 'a> where V: Copy, I: Index {
+    vertex_buffer: &'a VertexBuffer<V>,
+    index_buffer: &'a IndexBuffer<I>,
+    commands: Vec<DrawCommandIndices>,
+    // buffer of the last indirect submission, reused if the number of items didn't change
+    indirect_buffer: RefCell<Option<DrawCommandsIndicesBuffer>>,
+}
+
+impl<'a, V, I> DrawList<'a, V, I> where V: Copy, I: Index {
+    /// Builds an empty list of items that draw from the given buffers.
+    #[inline]
+    pub fn new(vertex_buffer: &'a VertexBuffer<V>, index_buffer: &'a IndexBuffer<I>)
+               -> DrawList<'a, V, I>
+    {
+        DrawList {
+            vertex_buffer: vertex_buffer,
+            index_buffer: index_buffer,
+            commands: Vec::new(),
+            indirect_buffer: RefCell::new(None),
+        }
+    }
+
+    /// Adds an item that draws a range of the index buffer. The value of `base_vertex` is added
+    /// to each index.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the range of indices is out of bounds or doesn't start and end at a primitive
+    /// boundary, or if `base_vertex` is out of the bounds of the vertex buffer.
+    pub fn push(&mut self, indices: Range<usize>, base_vertex: usize) {
+        assert!(self.index_buffer.slice(indices.clone()).is_some(),
+                "The range of indices is out of bounds or doesn't match the primitives");
+        assert!(base_vertex < self.vertex_buffer.len(), "The base vertex is out of bounds");
+
+        self.commands.push(DrawCommandIndices {
+            count: (indices.end - indices.start) as u32,
+            instance_count: 1,
+            first_index: indices.start as u32,
+            base_vertex: base_vertex as u32,
+            base_instance: 0,
+        });
+    }
+
+    /// Removes all the items of the list.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Returns the number of items in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if the list doesn't contain any item.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Draws all the items of the list on a surface.
+    pub fn submit<S, U>(&self, surface: &mut S, program: &Program, uniforms: &U,
+                        draw_parameters: &DrawParameters, mode: SubmissionMode)
+                        -> Result<(), SubmitError>
+                        where S: Surface, U: Uniforms
+    {
+        if self.commands.is_empty() {
+            return Ok(());
+        }
+
+        let indirect_supported = {
+            let context = self.vertex_buffer.get_context();
+            is_multidraw_indirect_supported(&**context)
+        };
+
+        let indirect = match mode {
+            SubmissionMode::Direct => false,
+            SubmissionMode::Indirect if !indirect_supported => {
+                return Err(DrawError::IndirectDrawNotSupported.into());
+            },
+            SubmissionMode::Indirect => true,
+            SubmissionMode::Automatic => indirect_supported,
+        };
+
+        if !indirect {
+            return self.submit_direct(surface, program, uniforms, draw_parameters);
+        }
+
+        let mut buffer = self.indirect_buffer.borrow_mut();
+
+        if buffer.as_ref().map_or(true, |b| b.len() != self.commands.len()) {
+            let context = self.vertex_buffer.get_context();
+            *buffer = match DrawCommandsIndicesBuffer::empty_dynamic(context, self.commands.len()) {
+                Ok(b) => Some(b),
+                Err(_) if mode == SubmissionMode::Automatic => {
+                    return self.submit_direct(surface, program, uniforms, draw_parameters);
+                },
+                Err(err) => return Err(err.into()),
+            };
+        }
+
+        let buffer = buffer.as_ref().unwrap();
+        buffer.write(&self.commands);
+        try!(surface.draw(self.vertex_buffer, buffer.with_index_buffer(self.index_buffer),
+                          program, uniforms, draw_parameters));
+        Ok(())
+    }
+
+    /// Draws each item of the list with its own draw call.
+    fn submit_direct<S, U>(&self, surface: &mut S, program: &Program, uniforms: &U,
+                           draw_parameters: &DrawParameters) -> Result<(), SubmitError>
+                           where S: Surface, U: Uniforms
+    {
+        for command in self.commands.iter() {
+            let first = command.first_index as usize;
+            let indices = self.index_buffer.slice(first .. first + command.count as usize)
+                                           .unwrap();
+            let vertices = self.vertex_buffer.slice(command.base_vertex as usize ..).unwrap();
+            try!(surface.draw(vertices, indices, program, uniforms, draw_parameters));
+        }
+
+        Ok(())
+    }
+}
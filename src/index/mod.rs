@@ -20,7 +20,7 @@ There are two ways to specify the indices that must be used:
 
  - Passing a reference to an `IndexBuffer`, which contains a list of indices.
  - `NoIndices`, in which case the vertices will be used in the order in which they are in the
-   vertex buffer.
+   vertex buffer. `NoIndices::range` can be used to draw only a part of the vertices.
 
 ## Multidraw indirect
 
@@ -28,6 +28,10 @@ In addition to indices, you can also use **multidraw indirect** rendering.
 
 The idea is to put a list of things to render in a buffer, and pass that buffer to OpenGL.
 
+A `DrawList` lets you build a list of ranges of an index buffer and submit them either with a
+single multidraw indirect call or with one draw call per range, for example depending on
+whether `is_multidraw_indirect_supported` returns true.
+
 */
 use gl;
 use ToGlEnum;
@@ -38,13 +42,17 @@ use version::Version;
 use std::mem;
 
 use buffer::BufferAnySlice;
+use vertex::TransformFeedbackObject;
 
 pub use self::buffer::{IndexBuffer, IndexBufferSlice, IndexBufferAny};
 pub use self::buffer::CreationError as BufferCreationError;
 pub use self::multidraw::{DrawCommandsNoIndicesBuffer, DrawCommandNoIndices};
 pub use self::multidraw::{DrawCommandsIndicesBuffer, DrawCommandIndices};
+pub use self::multidraw::{is_shader_draw_parameters_supported, is_multidraw_indirect_supported};
+pub use self::draw_list::{DrawList, SubmissionMode, SubmitError};
 
 mod buffer;
+mod draw_list;
 mod multidraw;
 
 /// Describes a source of indices used for drawing.
@@ -60,6 +68,23 @@ pub enum IndicesSource<'a> {
         primitives: PrimitiveType,
     },
 
+    /// A buffer uploaded in video memory, and the range of values of the indices it contains.
+    ///
+    /// This is drawn with `glDrawRangeElements` when it is supported, which lets the driver know
+    /// which vertices are going to be used.
+    IndexBufferRange {
+        /// The buffer.
+        buffer: BufferAnySlice<'a>,
+        /// Type of indices in the buffer.
+        data_type: IndexType,
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+        /// Minimum value of the indices in the buffer.
+        start: u32,
+        /// Maximum value of the indices in the buffer.
+        end: u32,
+    },
+
     /// Use a multidraw indirect buffer without indices.
     MultidrawArray {
         /// The buffer.
@@ -86,6 +111,37 @@ pub enum IndicesSource<'a> {
         /// Type of primitives contained in the vertex source.
         primitives: PrimitiveType,
     },
+
+    /// Don't use indices, and only use a range of the vertices of the vertices source.
+    NoIndicesRange {
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+        /// Index of the first vertex to use.
+        first: usize,
+        /// Number of vertices to use.
+        count: usize,
+    },
+
+    /// Don't use indices, and draw the vertices that have been captured by a transform feedback
+    /// session with this object.
+    TransformFeedback {
+        /// The transform feedback object.
+        object: &'a TransformFeedbackObject,
+        /// The vertex stream whose vertices are drawn.
+        stream: u32,
+        /// Type of primitives contained in the vertex source.
+        primitives: PrimitiveType,
+    },
+
+    /// Don't use vertices at all, and launch mesh shader work groups with
+    /// `glDrawMeshTasksNV`. The program must have been built with
+    /// `ProgramCreationInput::MeshShaders`.
+    MeshTasks {
+        /// Index of the first work group.
+        first: u32,
+        /// Number of work groups.
+        count: u32,
+    },
 }
 
 impl<'a> IndicesSource<'a> {
@@ -94,9 +150,14 @@ impl<'a> IndicesSource<'a> {
     pub fn get_primitives_type(&self) -> PrimitiveType {
         match self {
             &IndicesSource::IndexBuffer { primitives, .. } => primitives,
+            &IndicesSource::IndexBufferRange { primitives, .. } => primitives,
             &IndicesSource::MultidrawArray { primitives, .. } => primitives,
             &IndicesSource::MultidrawElement { primitives, .. } => primitives,
             &IndicesSource::NoIndices { primitives } => primitives,
+            &IndicesSource::NoIndicesRange { primitives, .. } => primitives,
+            &IndicesSource::TransformFeedback { primitives, .. } => primitives,
+            // the primitives are chosen by the mesh shader
+            &IndicesSource::MeshTasks { .. } => PrimitiveType::TrianglesList,
         }
     }
 }
@@ -214,6 +275,20 @@ impl PrimitiveType {
             },
         }
     }
+
+    /// Returns the number of indices that make up one primitive, or `None` if the primitives
+    /// share their vertices (strips, fans and loops).
+    fn get_indices_per_primitive(&self) -> Option<usize> {
+        match self {
+            &PrimitiveType::Points => Some(1),
+            &PrimitiveType::LinesList => Some(2),
+            &PrimitiveType::LinesListAdjacency => Some(4),
+            &PrimitiveType::TrianglesList => Some(3),
+            &PrimitiveType::TrianglesListAdjacency => Some(6),
+            &PrimitiveType::Patches { vertices_per_patch } => Some(vertices_per_patch as usize),
+            _ => None,
+        }
+    }
 }
 
 impl ToGlEnum for PrimitiveType {
@@ -261,6 +336,79 @@ impl<'a, 'b> From<&'b NoIndices> for IndicesSource<'a> {
     }
 }
 
+impl NoIndices {
+    /// Builds a marker that only uses `count` vertices of the vertices source, starting with
+    /// the vertex at index `first`.
+    ///
+    /// This allows drawing a part of a large vertex buffer without slicing it.
+    #[inline]
+    pub fn range(primitives: PrimitiveType, first: usize, count: usize) -> NoIndicesRange {
+        NoIndicesRange {
+            primitives: primitives,
+            first: first,
+            count: count,
+        }
+    }
+}
+
+/// Marker that can be passed instead of a buffer to indicate that only a range of the vertices
+/// of the vertices sources must be used, in order.
+///
+/// Built with `NoIndices::range`.
+#[derive(Copy, Clone, Debug)]
+pub struct NoIndicesRange {
+    /// Type of primitives contained in the vertex source.
+    pub primitives: PrimitiveType,
+    /// Index of the first vertex to use.
+    pub first: usize,
+    /// Number of vertices to use.
+    pub count: usize,
+}
+
+impl<'a> From<NoIndicesRange> for IndicesSource<'a> {
+    #[inline]
+    fn from(marker: NoIndicesRange) -> IndicesSource<'a> {
+        IndicesSource::NoIndicesRange {
+            primitives: marker.primitives,
+            first: marker.first,
+            count: marker.count,
+        }
+    }
+}
+
+impl<'a, 'b> From<&'b NoIndicesRange> for IndicesSource<'a> {
+    #[inline]
+    fn from(marker: &'b NoIndicesRange) -> IndicesSource<'a> {
+        IndicesSource::NoIndicesRange {
+            primitives: marker.primitives,
+            first: marker.first,
+            count: marker.count,
+        }
+    }
+}
+
+/// Marker that can be passed instead of a buffer to launch mesh shader work groups instead of
+/// drawing vertices.
+///
+/// This is what `Surface::draw_mesh_tasks` uses.
+#[derive(Copy, Clone, Debug)]
+pub struct MeshTasks {
+    /// Index of the first work group.
+    pub first: u32,
+    /// Number of work groups.
+    pub count: u32,
+}
+
+impl<'a> From<MeshTasks> for IndicesSource<'a> {
+    #[inline]
+    fn from(marker: MeshTasks) -> IndicesSource<'a> {
+        IndicesSource::MeshTasks {
+            first: marker.first,
+            count: marker.count,
+        }
+    }
+}
+
 /// Type of the indices in an index source.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]    // GLenum
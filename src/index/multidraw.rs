@@ -1,5 +1,19 @@
 //! Allows one to draw multiple geometry located in the same buffer.
 //!
+//! # Per-draw data
+//!
+//! All the commands of a buffer are submitted with a single call to
+//! `glMultiDrawArraysIndirect` or `glMultiDrawElementsIndirect`. When
+//! `is_shader_draw_parameters_supported` returns true, the vertex shader can read
+//! `gl_DrawID` to know the index of the command that is being executed, and
+//! `gl_BaseVertex` and `gl_BaseInstance` to know the values of `base_vertex` and
+//! `base_instance` of this command. This lets you use `gl_DrawID` as an index into a
+//! uniform or storage buffer that contains per-draw data.
+//!
+//! With OpenGL 4.6 these variables are core. Otherwise the shader must enable the
+//! `GL_ARB_shader_draw_parameters` extension and use `gl_DrawIDARB`, `gl_BaseVertexARB` and
+//! `gl_BaseInstanceARB` instead.
+//!
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::os::raw;
@@ -8,6 +22,29 @@ use backend::Facade;
 use buffer::{BufferCreationError, BufferType, BufferMode, Buffer};
 use buffer::{BufferSlice, BufferMutSlice};
 use index::{IndicesSource, PrimitiveType, IndexBuffer, Index};
+use version::{Api, Version};
+use CapabilitiesSource;
+
+/// Returns true if the `gl_DrawID`, `gl_BaseVertex` and `gl_BaseInstance` shader built-ins
+/// are available.
+#[inline]
+pub fn is_shader_draw_parameters_supported<C: ?Sized>(caps: &C) -> bool
+    where C: CapabilitiesSource
+{
+    caps.get_version() >= &Version(Api::Gl, 4, 6) ||
+    caps.get_extensions().gl_arb_shader_draw_parameters
+}
+
+/// Returns true if the lists of draw commands can be drawn with `glMultiDrawArraysIndirect`
+/// and `glMultiDrawElementsIndirect`.
+#[inline]
+pub fn is_multidraw_indirect_supported<C: ?Sized>(caps: &C) -> bool
+    where C: CapabilitiesSource
+{
+    caps.get_version() >= &Version(Api::Gl, 4, 3) ||
+    caps.get_extensions().gl_arb_multi_draw_indirect ||
+    caps.get_extensions().gl_ext_multi_draw_indirect
+}
 
 /// Represents an element in a list of draw commands.
 #[repr(C)]
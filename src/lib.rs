@@ -97,12 +97,16 @@ extern crate lazy_static;
 
 #[cfg(feature = "backtrace")]
 extern crate backtrace;
+#[cfg(feature = "image")]
+extern crate image;
 extern crate smallvec;
 extern crate fnv;
 
 #[cfg(feature = "glutin")]
 pub use backend::glutin_backend::glutin;
-pub use context::Profile;
+pub use context::{ContextRequirements, Profile, GlError, GlErrorBehavior, GlErrorCheckInterval, StateTrust};
+pub use context::RequirementsNotMetError;
+pub use context::{ResourceKind, ResourceCallback};
 pub use draw_parameters::{Blend, BlendingFunction, LinearBlendingFactor, BackfaceCullingMode};
 pub use draw_parameters::{Depth, DepthTest, PolygonMode, DrawParameters, StencilTest, StencilOperation};
 pub use draw_parameters::{Smooth};
@@ -110,7 +114,7 @@ pub use index::IndexBuffer;
 pub use vertex::{VertexBuffer, Vertex, VertexFormat};
 pub use program::{Program, ProgramCreationError};
 pub use program::ProgramCreationError::{CompilationError, LinkingError, ShaderTypeNotSupported};
-pub use sync::{LinearSyncFence, SyncFence};
+pub use sync::{LinearSyncFence, SyncFence, SyncNotSupportedError};
 pub use texture::Texture2d;
 pub use version::{Api, Version, get_supported_glsl_version};
 
@@ -125,6 +129,8 @@ use fnv::FnvHasher;
 
 use context::Context;
 use context::CommandContext;
+use fbo::ClearBufferData;
+use ops::ReadError;
 
 #[macro_use]
 mod macros;
@@ -132,11 +138,23 @@ mod macros;
 pub mod backend;
 pub mod buffer;
 pub mod debug;
+#[cfg(feature = "decals")]
+pub mod decals;
 pub mod draw_parameters;
+#[cfg(feature = "env")]
+pub mod env;
 pub mod framebuffer;
+#[cfg(feature = "gltf")]
+pub mod gltf;
 pub mod index;
 pub mod pixel_buffer;
+#[cfg(feature = "postprocess")]
+pub mod postprocess;
 pub mod program;
+#[cfg(feature = "sprites")]
+pub mod sprites;
+#[cfg(feature = "text")]
+pub mod text;
 pub mod uniforms;
 pub mod vertex;
 pub mod texture;
@@ -176,6 +194,11 @@ pub trait CapabilitiesSource {
 }
 
 /// Trait for objects that are OpenGL objects.
+///
+/// The id can be passed to foreign code that performs raw OpenGL calls or to other APIs that
+/// interoperate with OpenGL. The object is still owned by glium and must not be deleted.
+/// Glium caches the state of the context, so foreign code should restore any binding that it
+/// modifies.
 pub trait GlObject {
     /// The type of identifier for this object.
     type Id;
@@ -186,7 +209,7 @@ pub trait GlObject {
 
 /// Handle to a shader or a program.
 // TODO: Handle(null()) is equal to Id(0)
-#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash, PartialOrd, Ord)]
 pub enum Handle {
     /// A numberic identifier.
     Id(gl::types::GLuint),
@@ -309,6 +332,13 @@ trait ProgramExt {
     fn get_shader_storage_blocks(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
 
     fn get_subroutine_data(&self) -> &program::SubroutineData;
+
+    /// Returns true if a warning about the lack of early fragment tests should be inserted in
+    /// the debug output, which only happens once per program.
+    #[inline]
+    fn take_early_fragment_tests_warning(&self) -> bool {
+        false
+    }
 }
 
 /// Internal trait for queries.
@@ -319,7 +349,7 @@ trait QueryExt {
 
     fn end_time_elapsed_query(ctxt: &mut CommandContext);
 
-    fn end_primitives_generated_query(ctxt: &mut CommandContext);
+    fn end_primitives_generated_query(ctxt: &mut CommandContext, stream: gl::types::GLuint);
 
     fn end_transform_feedback_primitives_written_query(ctxt: &mut CommandContext);
 
@@ -342,9 +372,29 @@ trait TextureExt {
     /// Returns the bind point of the texture.
     fn get_bind_point(&self) -> gl::types::GLenum;
 
+    /// Returns the number of mipmap levels of the texture, including the main level.
+    fn get_mipmap_levels(&self) -> u32;
+
     /// Makes sure that the texture is bound to the current texture unit and returns the
     /// bind point to use to access the texture (eg. `GL_TEXTURE_2D`, `GL_TEXTURE_3D`, etc.).
     fn bind_to_current(&self, &mut CommandContext) -> gl::types::GLenum;
+
+    /// Calls `glMemoryBarrier(GL_TEXTURE_FETCH_BARRIER_BIT)` if necessary.
+    fn prepare_for_sampling(&self, &mut CommandContext);
+
+    /// Calls `glMemoryBarrier(GL_SHADER_IMAGE_ACCESS_BARRIER_BIT)` if necessary. `write` must
+    /// be true if the shader is allowed to write to the texture.
+    fn prepare_for_image_unit(&self, ctxt: &mut CommandContext, write: bool);
+
+    /// Calls `glMemoryBarrier(GL_FRAMEBUFFER_BARRIER_BIT)` if necessary.
+    fn prepare_for_framebuffer(&self, &mut CommandContext);
+
+    /// Sets the filters and the wrap functions of the texture, which must be bound to the
+    /// current texture unit, to emulate a sampler where sampler objects are not supported. If
+    /// `behavior` is `None`, restores the parameters that the texture had before. Returns true
+    /// if a parameter has been changed.
+    fn emulate_sampler(&self, &mut CommandContext, behavior: Option<&uniforms::SamplerBehavior>)
+                       -> bool;
 }
 
 /// Internal trait for textures.
@@ -370,17 +420,31 @@ trait TransformFeedbackSessionExt {
     /// Ensures that transform feedback is disabled.
     fn unbind(&mut CommandContext);
 
+    /// Pauses the current transform feedback session so that draw commands don't capture
+    /// vertices, or ends it if pausing is not supported.
+    fn pause(&mut CommandContext);
+
     /// Ensures that a buffer isn't used by transform feedback.
     fn ensure_buffer_out_of_transform_feedback(&mut CommandContext, gl::types::GLuint);
+
+    /// Ensures that a transform feedback object isn't capturing, so that the vertices it has
+    /// captured can be drawn.
+    fn ensure_object_out_of_transform_feedback(&mut CommandContext, gl::types::GLuint);
+
+    /// Returns the id of the transform feedback object used by the session, or `0` for the
+    /// default object.
+    fn get_object_id(&self) -> gl::types::GLuint;
 }
 
 /// Internal trait for uniforms handling.
 trait UniformsExt {
     /// Binds the uniforms to a given program.
     ///
-    /// Will replace texture and buffer bind points.
+    /// Will replace texture and buffer bind points. Returns the texture units that are used by
+    /// the program.
     fn bind_uniforms<'a, P>(&'a self, &mut CommandContext, &P, &mut Vec<buffer::Inserter<'a>>)
-                            -> Result<(), DrawError> where P: ProgramExt;
+                            -> Result<utils::bitsfield::Bitsfield, DrawError>
+                            where P: ProgramExt;
 }
 
 
@@ -430,7 +494,7 @@ pub enum RawUniformValue {
 /// Area of a surface in pixels.
 ///
 /// In the OpenGL ecosystem, the (0,0) coordinate is at the bottom-left hand corner of the images.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Rect {
     /// Number of pixels between the left border of the surface and the left border of
     /// the rectangle.
@@ -672,6 +736,12 @@ pub struct BlitTarget {
 ///
 pub trait Surface {
     /// Clears some attachments of the target.
+    ///
+    /// All the attachments that are requested are cleared with a single call to `glClear`.
+    /// If `rect` is `Some`, only this rectangle is cleared.
+    ///
+    /// The color mask, depth write and stencil write mask of the `DrawParameters` used for
+    /// previous draws don't apply here: the requested attachments are always entirely written.
     fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
              depth: Option<f32>, stencil: Option<i32>);
 
@@ -730,9 +800,110 @@ pub trait Surface {
         self.clear(None, Some(color), true, Some(depth), Some(stencil));
     }
 
+    /// Clears a single channel of a color attachment of the target, leaving the other channels
+    /// untouched.
+    ///
+    /// `attachment` is the index of the color attachment. It is always `0` for surfaces with
+    /// a single color attachment, and is the position of the attachment in the list passed when
+    /// creating a `MultiOutputFrameBuffer`. `channel` is `0` for red, `1` for green, `2` for blue
+    /// and `3` for alpha.
+    ///
+    /// The value is written without any sRGB conversion. Only attachments whose format is
+    /// floating-point or normalized are supported.
+    ///
+    /// Clearing a single channel requires access to the attachments of the surface, so the
+    /// default implementation panics. All the surfaces of glium override it.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `channel` is superior or equal to 4.
+    /// - Panics if the surface doesn't have a color attachment at index `attachment`.
+    /// - Panics if the surface doesn't override this method.
+    fn clear_channel(&mut self, attachment: u32, channel: u32, value: f32) {
+        panic!("Clearing channel {} of color attachment {} to {} is not supported by this \
+                surface", channel, attachment, value);
+    }
+
+    /// Clears the color attachments of a target whose color attachments are all signed
+    /// integral, such as `IntegralTexture2d`s.
+    ///
+    /// The other clear functions take a floating-point color. When used on a target with
+    /// integral or unsigned attachments, they clear these attachments with the color converted
+    /// to integers.
+    ///
+    /// # Panic
+    ///
+    /// The default implementation converts the color to floating-point and calls `clear`, which
+    /// gives the same result for values that can be represented exactly as `f32`s.
+    ///
+    /// # Panic
+    ///
+    /// Panics if one of the color attachments is not signed integral. The default framebuffer
+    /// never has integral attachments.
+    fn clear_color_integer(&mut self, color: [i32; 4]) {
+        let color = (color[0] as f32, color[1] as f32, color[2] as f32, color[3] as f32);
+        self.clear(None, Some(color), false, None, None);
+    }
+
+    /// Clears the color attachments of a target whose color attachments are all unsigned
+    /// integral, such as `UnsignedTexture2d`s.
+    ///
+    /// # Panic
+    ///
+    /// Panics if one of the color attachments is not unsigned integral. The default framebuffer
+    /// never has unsigned attachments.
+    fn clear_color_unsigned(&mut self, color: [u32; 4]) {
+        let color = (color[0] as f32, color[1] as f32, color[2] as f32, color[3] as f32);
+        self.clear(None, Some(color), false, None, None);
+    }
+
+    /// Reads the depth values of a rectangle of the target, row by row starting with the
+    /// bottom row.
+    ///
+    /// The values are between `0.0` and `1.0`, and can be combined with the inverse of the
+    /// projection matrix to obtain the positions of the objects that have been drawn, for
+    /// example for picking.
+    ///
+    /// This function waits until all the previous draw commands have finished, which can take
+    /// a long time. If you read depth values every frame, you should copy the depth buffer to
+    /// a texture and read it asynchronously with a pixel buffer instead.
+    ///
+    /// Returns an error if the target doesn't have a depth buffer, or if the backend doesn't
+    /// support reading depth values (OpenGL ES without `GL_NV_read_depth`).
+    ///
+    /// The default implementation returns `ReadError::AttachmentTypeNotSupported`. All the
+    /// surfaces of glium override it.
+    fn read_depth_rect(&self, rect: &Rect) -> Result<Vec<f32>, ReadError> {
+        let _ = rect;
+        Err(ReadError::AttachmentTypeNotSupported)
+    }
+
+    /// Reads the depth value of a single pixel of the target.
+    ///
+    /// Like for `Rect`s, the coordinates start at the bottom-left hand corner of the target.
+    /// Window systems usually give the position of the cursor from the top-left hand corner,
+    /// in which case you must pass `height - 1 - y` as `y`.
+    ///
+    /// See `read_depth_rect`.
+    fn read_depth_pixel(&self, x: u32, y: u32) -> Result<f32, ReadError> {
+        let rect = Rect { left: x, bottom: y, width: 1, height: 1 };
+        self.read_depth_rect(&rect).map(|values| values[0])
+    }
+
     /// Returns the dimensions in pixels of the target.
     fn get_dimensions(&self) -> (u32, u32);
 
+    /// Returns the ratio between the width and the height of the target.
+    ///
+    /// For framebuffers with multiple attachments, this is computed from the dimensions of the
+    /// framebuffer as returned by `get_dimensions`, in other words the area shared by all
+    /// attachments.
+    #[inline]
+    fn aspect_ratio(&self) -> f32 {
+        let (width, height) = self.get_dimensions();
+        width as f32 / height as f32
+    }
+
     /// Returns the number of bits of each pixel of the depth buffer.
     ///
     /// Returns `None` if there is no depth buffer.
@@ -765,6 +936,53 @@ pub trait Surface {
         V: vertex::MultiVerticesSource<'b>, I: Into<index::IndicesSource<'a>>,
         U: uniforms::Uniforms;
 
+    /// Draws a single triangle that covers the whole viewport.
+    ///
+    /// No vertex buffer is bound. Three vertices are submitted with
+    /// `glDrawArrays(GL_TRIANGLES, 0, 3)` and the vertex shader is expected to compute the
+    /// positions from `gl_VertexID`. On core profiles an empty vertex array object is bound
+    /// for the draw.
+    ///
+    /// This is typically used for post-processing passes. The vertex shader to pair with this
+    /// function is:
+    ///
+    /// ```notrust
+    /// #version 140
+    ///
+    /// out vec2 v_tex_coords;
+    ///
+    /// void main() {
+    ///     v_tex_coords = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+    ///     gl_Position = vec4(v_tex_coords * 2.0 - 1.0, 0.0, 1.0);
+    /// }
+    /// ```
+    #[inline]
+    fn draw_fullscreen<U>(&mut self, program: &Program, uniforms: &U,
+                          draw_parameters: &DrawParameters) -> Result<(), DrawError>
+                          where U: uniforms::Uniforms
+    {
+        self.draw(vertex::EmptyVertexAttributes { len: 3 },
+                  index::NoIndices(index::PrimitiveType::TrianglesList),
+                  program, uniforms, draw_parameters)
+    }
+
+    /// Launches `count` work groups of the task shader of a program, or of its mesh shader if it
+    /// doesn't have a task shader, with `glDrawMeshTasksNV`.
+    ///
+    /// The program must have been built with `ProgramCreationInput::MeshShaders`. No vertex
+    /// buffer or vertex array object is bound.
+    ///
+    /// Returns `DrawError::MeshShadersNotSupported` if `GL_NV_mesh_shader` is not supported.
+    #[inline]
+    fn draw_mesh_tasks<U>(&mut self, count: u32, program: &Program, uniforms: &U,
+                          draw_parameters: &DrawParameters) -> Result<(), DrawError>
+                          where U: uniforms::Uniforms
+    {
+        self.draw(vertex::EmptyVertexAttributes { len: 0 },
+                  index::MeshTasks { first: 0, count: count },
+                  program, uniforms, draw_parameters)
+    }
+
     /// Blits from the default framebuffer.
     fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
                        filter: uniforms::MagnifySamplerFilter);
@@ -791,6 +1009,10 @@ pub trait Surface {
     ///
     /// Note that there is no alpha blending, depth/stencil checking, etc. This function just
     /// copies pixels.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `self` is a framebuffer without any color attachment.
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
                      filter: uniforms::MagnifySamplerFilter) where S: Surface;
 
@@ -813,6 +1035,27 @@ pub trait Surface {
         let target_rect = BlitTarget { left: 0, bottom: 0, width: target_dim.0 as i32, height: target_dim.1 as i32 };
         self.blit_color(&src_rect, target, &target_rect, filter)
     }
+
+    /// Copies the entire color buffer of `source` to the entire surface.
+    ///
+    /// This is the usual way to resolve a multisampled framebuffer or to copy an offscreen
+    /// buffer to the window. Both surfaces use the same bottom-left origin, so the image is not
+    /// flipped. If the dimensions of the two surfaces match, the pixels are copied with
+    /// `Nearest` filtering. Otherwise they are resized with `Linear` filtering.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `source` is a framebuffer without any color attachment.
+    #[inline]
+    fn blit_whole_color_from<S>(&self, source: &S) where S: Surface, Self: Sized {
+        let filter = if source.get_dimensions() == self.get_dimensions() {
+            uniforms::MagnifySamplerFilter::Nearest
+        } else {
+            uniforms::MagnifySamplerFilter::Linear
+        };
+
+        source.fill(self, filter)
+    }
 }
 
 /// Private trait for framebuffer-like objects that provide attachments.
@@ -871,6 +1114,16 @@ pub enum DrawError {
         err: uniforms::LayoutMismatchError,
     },
 
+    /// The number of bytes of a `UniformValue::Raw` doesn't match the size of its type.
+    RawUniformSizeMismatch {
+        /// Name of the uniform you are trying to bind.
+        name: String,
+        /// Number of bytes required by the type of the uniform.
+        expected: usize,
+        /// Number of bytes that have been passed.
+        obtained: usize,
+    },
+
     /// Tried to bind a subroutine uniform like a regular uniform value.
     SubroutineUniformToValue {
         /// Name of the uniform you are trying to bind.
@@ -905,9 +1158,32 @@ pub enum DrawError {
     /// Using a program which contains tessellation shaders, but without submitting patches.
     TessellationWithoutPatches,
 
-    /// Trying to use a sampler, but they are not supported by the backend.
+    /// Trying to use a sampler that can't be emulated with the parameters of the texture, but
+    /// sampler objects are not supported by the backend.
+    ///
+    /// This happens with depth texture comparison, with a reduction mode, or when the same
+    /// texture is sampled with different parameters by the same draw call.
     SamplersNotSupported,
 
+    /// One of the wrap functions of a sampler is not supported by the backend.
+    WrapFunctionNotSupported,
+
+    /// The reduction mode of a sampler is not supported by the backend.
+    ReductionModeNotSupported,
+
+    /// A rectangle texture is sampled with a wrap function other than `Clamp` or with a
+    /// minifying filter that uses mipmaps.
+    InvalidRectangleTextureSampler,
+
+    /// Trying to bind a texture to an image unit, but image units are not supported by the
+    /// backend.
+    ImageUnitsNotSupported,
+
+    /// A texture that doesn't have mipmaps is sampled with a minifying filter that uses mipmaps,
+    /// and the backend doesn't support limiting the mipmap levels of a texture. Sampling the
+    /// texture would return black.
+    MipmapFilterWithoutMipmaps,
+
     /// When you use instancing, all vertices sources must have the same size.
     InstancesCountMismatch,
 
@@ -932,11 +1208,96 @@ pub enum DrawError {
     /// Depth clamping isn't supported by the backend.
     DepthClampNotSupported,
 
+    /// Conservative rasterization, or the requested dilation, isn't supported by the backend.
+    ConservativeRasterizationNotSupported,
+
+    /// `GL_SAMPLE_MASK` isn't supported by the backend.
+    SampleMaskNotSupported,
+
     /// One of the blending parameters is not supported by the backend.
     BlendingParameterNotSupported,
 
+    /// Dual-source blending was requested while drawing to multiple color attachments.
+    DualSourceBlendingWithMultipleTargets,
+
     /// Restarting indices (multiple objects per draw call) is not supported by the backend.
     FixedIndexRestartingNotSupported,
+
+    /// The range of indices passed with `with_range` is invalid, or goes beyond the number of
+    /// vertices in the vertices sources.
+    InvalidIndicesRange,
+
+    /// The range of vertices passed with `NoIndices::range` goes beyond the number of vertices
+    /// in the vertices sources.
+    VerticesRangeOutOfBounds,
+
+    /// The transform feedback object used as the indices source hasn't captured any vertex yet,
+    /// or is capturing the output of the same draw command.
+    TransformFeedbackObjectNotUsed,
+
+    /// Drawing the vertices captured by a transform feedback object with instancing is not
+    /// supported by the backend.
+    TransformFeedbackInstancingNotSupported,
+
+    /// Multidraw indirect was requested, but is not supported by the backend.
+    IndirectDrawNotSupported,
+
+    /// Mesh tasks were requested, but mesh shaders are not supported by the backend.
+    MeshShadersNotSupported,
+
+    /// The program has mesh shaders but the draw command doesn't launch mesh tasks, or the
+    /// program doesn't have mesh shaders but the draw command launches mesh tasks.
+    MeshShaderMismatch,
+
+    /// The program uses more textures than the number of texture units of the backend.
+    TooManyTextures {
+        /// Number of textures used by the program.
+        count: usize,
+        /// Number of texture units available.
+        max: usize,
+    },
+
+    /// The program uses more vertex attributes than what the backend supports.
+    TooManyVertexAttributes {
+        /// Number of vertex attributes used by the program.
+        count: usize,
+        /// Maximum number of vertex attributes.
+        max: usize,
+    },
+
+    /// A uniform block of the program is larger than what the backend supports.
+    UniformBlockTooLarge {
+        /// Name of the block.
+        name: String,
+        /// Size of the block in bytes.
+        size: usize,
+        /// Maximum size of a uniform block in bytes.
+        max: usize,
+    },
+
+    /// The framebuffer has more color attachments than what the backend can draw to at once.
+    TooManyColorAttachments {
+        /// Number of color attachments of the framebuffer.
+        count: usize,
+        /// Maximum number of draw buffers.
+        max: usize,
+    },
+
+    /// A texture that is used by the program is also attached to the framebuffer you are
+    /// drawing on. Reading from a texture while writing to it gives undefined results.
+    ///
+    /// This is only checked in debug mode, and is allowed right after a call to
+    /// `Context::texture_barrier`.
+    FeedbackLoop {
+        /// OpenGL id of the texture.
+        texture_id: gl::types::GLuint,
+        /// Where the texture is attached to the framebuffer.
+        attachment: framebuffer::AttachmentPoint,
+    },
+
+    /// The OpenGL implementation considers that the framebuffer you are drawing on is
+    /// incomplete.
+    FramebufferIncomplete(framebuffer::IncompleteFramebufferReason),
 }
 
 impl Error for DrawError {
@@ -961,6 +1322,8 @@ impl Error for DrawError {
                 "Tried to bind a single uniform value to a uniform block",
             UniformBlockLayoutMismatch { .. } =>
                 "The layout of the content of the uniform buffer does not match the layout of the block",
+            RawUniformSizeMismatch { .. } =>
+                "The number of bytes of a raw uniform value doesn't match the size of its type",
             SubroutineUniformToValue { .. } =>
                 "Tried to bind a subroutine uniform like a regular uniform value",
             SubroutineUniformMissing { .. } =>
@@ -973,8 +1336,22 @@ impl Error for DrawError {
                 "Trying to use tessellation, but this is not supported by the underlying hardware",
             TessellationWithoutPatches =>
                 "Using a program which contains tessellation shaders, but without submitting patches",
-            SamplersNotSupported => "
-                Trying to use a sampler, but they are not supported by the backend",
+            SamplersNotSupported =>
+                "Trying to use a sampler that can't be emulated, but sampler objects are not \
+                 supported by the backend",
+            WrapFunctionNotSupported =>
+                "One of the wrap functions of a sampler is not supported by the backend",
+            ReductionModeNotSupported =>
+                "The reduction mode of a sampler is not supported by the backend",
+            InvalidRectangleTextureSampler =>
+                "A rectangle texture is sampled with a wrap function other than `Clamp` or with a \
+                 minifying filter that uses mipmaps",
+            ImageUnitsNotSupported =>
+                "Trying to bind a texture to an image unit, but image units are not supported \
+                 by the backend",
+            MipmapFilterWithoutMipmaps =>
+                "A texture that doesn't have mipmaps is sampled with a minifying filter that uses \
+                 mipmaps",
             InstancesCountMismatch =>
                 "When you use instancing, all vertices sources must have the same size",
             VerticesSourcesLengthMismatch =>
@@ -991,10 +1368,45 @@ impl Error for DrawError {
                 "Discarding rasterizer output is not supported by the backend",
             DepthClampNotSupported =>
                 "The depth clamp mode is not supported by the backend",
+            ConservativeRasterizationNotSupported =>
+                "Conservative rasterization is not supported by the backend",
+            SampleMaskNotSupported =>
+                "The sample mask is not supported by the backend",
             BlendingParameterNotSupported =>
                 "One the blending parameters is not supported by the backend",
+            DualSourceBlendingWithMultipleTargets =>
+                "Dual-source blending can't be used when drawing to multiple color attachments",
             FixedIndexRestartingNotSupported =>
                 "Restarting indices (multiple objects per draw call) is not supported by the backend",
+            InvalidIndicesRange =>
+                "The range of indices is invalid or goes beyond the number of vertices",
+            VerticesRangeOutOfBounds =>
+                "The range of vertices goes beyond the number of vertices",
+            TransformFeedbackObjectNotUsed =>
+                "The transform feedback object hasn't captured any vertex, or is capturing \
+                 the output of the same draw command",
+            TransformFeedbackInstancingNotSupported =>
+                "Drawing the vertices captured by a transform feedback object with instancing \
+                 is not supported by the backend",
+            IndirectDrawNotSupported =>
+                "Multidraw indirect is not supported by the backend",
+            MeshShadersNotSupported =>
+                "Mesh shaders are not supported by the backend",
+            MeshShaderMismatch =>
+                "Mesh shader programs can only be used to launch mesh tasks, and mesh tasks can \
+                 only be launched with mesh shader programs",
+            TooManyTextures { .. } =>
+                "The program uses more textures than the number of texture units",
+            TooManyVertexAttributes { .. } =>
+                "The program uses more vertex attributes than what the backend supports",
+            UniformBlockTooLarge { .. } =>
+                "A uniform block is larger than what the backend supports",
+            TooManyColorAttachments { .. } =>
+                "The framebuffer has more color attachments than what the backend can draw to",
+            FeedbackLoop { .. } =>
+                "A texture is sampled while being attached to the framebuffer",
+            FramebufferIncomplete(_) =>
+                "The framebuffer is incomplete",
         }
     }
 
@@ -1002,6 +1414,7 @@ impl Error for DrawError {
         use self::DrawError::*;
         match *self {
             UniformBlockLayoutMismatch { ref err, .. } => Some(err),
+            FramebufferIncomplete(ref reason) => Some(reason),
             _ => None,
         }
     }
@@ -1042,6 +1455,49 @@ impl fmt::Display for DrawError {
                     name,
                     err,
                 ),
+            RawUniformSizeMismatch { ref name, expected, obtained } =>
+                write!(
+                    fmt,
+                    "{}: `{}` has {} bytes, expected {}",
+                    self.description(),
+                    name,
+                    obtained,
+                    expected,
+                ),
+            TooManyTextures { count, max } |
+            TooManyVertexAttributes { count, max } |
+            TooManyColorAttachments { count, max } =>
+                write!(
+                    fmt,
+                    "{}: {} used, maximum is {}",
+                    self.description(),
+                    count,
+                    max,
+                ),
+            FeedbackLoop { texture_id, attachment } =>
+                write!(
+                    fmt,
+                    "{}: texture {} is the {}",
+                    self.description(),
+                    texture_id,
+                    attachment,
+                ),
+            UniformBlockTooLarge { ref name, size, max } =>
+                write!(
+                    fmt,
+                    "{}: `{}` is {} bytes, maximum is {}",
+                    self.description(),
+                    name,
+                    size,
+                    max,
+                ),
+            FramebufferIncomplete(ref reason) =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    reason,
+                ),
             _ =>
                 write!(fmt, "{}", self.description()),
         }
@@ -1091,7 +1547,8 @@ impl fmt::Display for SwapBuffersError {
 ///
 /// The back- and front-buffers are swapped when you call `finish`.
 ///
-/// You **must** call either `finish` or `set_finish` or else the destructor will panic.
+/// You **must** call either `finish`, `set_finish` or `finish_no_swap` or else the destructor
+/// will panic.
 pub struct Frame {
     context: Rc<Context>,
     dimensions: (u32, u32),
@@ -1130,6 +1587,61 @@ impl Frame {
         self.destroyed = true;
         self.context.swap_buffers()
     }
+
+    /// Stop drawing and consume the Frame, without swapping the buffers.
+    ///
+    /// The commands are flushed and the errors are checked like with `finish`, but nothing is
+    /// presented. This is useful when presenting is handled by something else, for example a
+    /// VR runtime or the application that embeds glium, or to draw a warm-up frame.
+    ///
+    /// Returns `Err(SwapBuffersError::AlreadySwapped)` if `set_finish()` has already been called.
+    #[inline]
+    pub fn finish_no_swap(mut self) -> Result<(), SwapBuffersError> {
+        if self.destroyed {
+            return Err(SwapBuffersError::AlreadySwapped);
+        }
+
+        self.destroyed = true;
+        self.context.end_frame_without_swap()
+    }
+
+    /// Copies a rectangle of what has been drawn so far on the frame to a texture.
+    ///
+    /// This lets you sample the current content of the frame in a later draw call, for
+    /// example to draw refracting materials, without having to draw the whole scene to an
+    /// offscreen texture.
+    ///
+    /// `dest_offset` is the position of the bottom-left corner of the rectangle in the texture.
+    /// Like the frame, textures have their origin at the bottom-left hand corner, so the copied
+    /// image is not flipped.
+    ///
+    /// ## Low-level information
+    ///
+    /// This function blits the default framebuffer to a framebuffer object that contains the
+    /// texture. If the default framebuffer is multisampled, the samples are resolved.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the source rectangle is out of the bounds of the frame, or if the copied
+    /// rectangle is out of the bounds of the texture.
+    pub fn copy_to_texture(&self, source_rect: &Rect, dest: &texture::Texture2d,
+                           dest_offset: (u32, u32))
+    {
+        assert!(source_rect.left + source_rect.width <= self.dimensions.0);
+        assert!(source_rect.bottom + source_rect.height <= self.dimensions.1);
+        assert!(dest_offset.0 + source_rect.width <= dest.get_width());
+        assert!(dest_offset.1 + source_rect.height <= dest.get_height().unwrap_or(1));
+
+        let target_rect = BlitTarget {
+            left: dest_offset.0,
+            bottom: dest_offset.1,
+            width: source_rect.width as i32,
+            height: source_rect.height as i32,
+        };
+
+        self.blit_color(source_rect, &dest.as_surface(), &target_rect,
+                        uniforms::MagnifySamplerFilter::Nearest);
+    }
 }
 
 impl Surface for Frame {
@@ -1140,6 +1652,26 @@ impl Surface for Frame {
         ops::clear(&self.context, None, rect, color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_channel(&mut self, attachment: u32, channel: u32, value: f32) {
+        ops::clear_channel(&self.context, None, attachment, channel, value);
+    }
+
+    #[inline]
+    fn clear_color_integer(&mut self, color: [i32; 4]) {
+        ops::clear_color_integral(&self.context, None, ClearBufferData::Integral(color));
+    }
+
+    #[inline]
+    fn clear_color_unsigned(&mut self, color: [u32; 4]) {
+        ops::clear_color_integral(&self.context, None, ClearBufferData::Unsigned(color));
+    }
+
+    fn read_depth_rect(&self, rect: &Rect) -> Result<Vec<f32>, ReadError> {
+        let mut ctxt = self.context.make_current();
+        ops::read_depth(&mut ctxt, None, rect)
+    }
+
     fn get_dimensions(&self) -> (u32, u32) {
         self.dimensions
     }
@@ -1177,6 +1709,19 @@ impl Surface for Frame {
             }
         }
 
+        if let Some(viewport) = draw_parameters.viewport_f {
+            if viewport.width > self.context.capabilities().max_viewport_dims.0
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+            if viewport.height > self.context.capabilities().max_viewport_dims.1
+                    as f32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
         ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
                   uniforms, draw_parameters, (self.dimensions.0 as u32, self.dimensions.1 as u32))
     }
@@ -1269,6 +1814,27 @@ pub trait DisplayBuild {
     unsafe fn build_glium_unchecked_debug(self, debug::DebugCallbackBehavior)
                                           -> Result<Self::Facade, Self::Err>;
 
+    /// Build a context that satisfies some requirements and a facade to draw on it.
+    ///
+    /// The requirements are passed to the backend when it supports them. Returns
+    /// `GliumCreationError::RequirementsNotMet` if the context that has been obtained doesn't
+    /// satisfy them. See `ContextRequirements`.
+    ///
+    /// Performs the same compatibility check as `build_glium`.
+    ///
+    /// The default implementation can't pass the requirements to the backend. It builds the
+    /// context with `build_glium` and returns a `RequirementsNotMetError` if it doesn't satisfy
+    /// them.
+    fn build_glium_with_requirements(self, requirements: ContextRequirements)
+                                     -> Result<Self::Facade, Self::Err>
+                                     where Self: Sized, Self::Err: From<RequirementsNotMetError>
+    {
+        let facade = try!(self.build_glium());
+        try!(requirements.check(backend::Facade::get_context(&facade))
+                         .map_err(RequirementsNotMetError));
+        Ok(facade)
+    }
+
     /// Changes the settings of an existing facade.
     fn rebuild_glium(self, &Self::Facade) -> Result<(), Self::Err>;
 }
@@ -1281,6 +1847,10 @@ pub enum GliumCreationError<T> {
 
     /// The OpenGL implementation is too old.
     IncompatibleOpenGl(String),
+
+    /// The context doesn't satisfy the requirements passed to `build_glium_with_requirements`.
+    /// Contains the list of the requirements that are not satisfied.
+    RequirementsNotMet(String),
 }
 
 impl<T: Error> fmt::Display for GliumCreationError<T> {
@@ -1297,6 +1867,8 @@ impl<T: Error> Error for GliumCreationError<T> {
                 "Error while creating the backend",
             GliumCreationError::IncompatibleOpenGl(..) =>
                 "The OpenGL implementation is too old to work with glium",
+            GliumCreationError::RequirementsNotMet(..) =>
+                "The context doesn't satisfy the requirements",
         }
     }
 
@@ -1305,6 +1877,7 @@ impl<T: Error> Error for GliumCreationError<T> {
         match *self {
             GliumCreationError::BackendCreationError(ref err) => Some(err),
             GliumCreationError::IncompatibleOpenGl(..) => None,
+            GliumCreationError::RequirementsNotMet(..) => None,
         }
     }
 }
@@ -1318,17 +1891,6 @@ impl<T: Error> std::convert::From<T> for GliumCreationError<T> {
 
 #[allow(dead_code)]
 #[inline]
-fn get_gl_error(ctxt: &mut context::CommandContext) -> Option<&'static str> {
-    match unsafe { ctxt.gl.GetError() } {
-        gl::NO_ERROR => None,
-        gl::INVALID_ENUM => Some("GL_INVALID_ENUM"),
-        gl::INVALID_VALUE => Some("GL_INVALID_VALUE"),
-        gl::INVALID_OPERATION => Some("GL_INVALID_OPERATION"),
-        gl::INVALID_FRAMEBUFFER_OPERATION => Some("GL_INVALID_FRAMEBUFFER_OPERATION"),
-        gl::OUT_OF_MEMORY => Some("GL_OUT_OF_MEMORY"),
-        gl::STACK_UNDERFLOW => Some("GL_STACK_UNDERFLOW"),
-        gl::STACK_OVERFLOW => Some("GL_STACK_OVERFLOW"),
-        gl::CONTEXT_LOST => Some("GL_CONTEXT_LOST"),
-        _ => Some("Unknown glGetError return value")
-    }
+fn get_gl_error(ctxt: &mut context::CommandContext) -> Option<GlError> {
+    GlError::from_glenum(unsafe { ctxt.gl.GetError() })
 }
@@ -93,6 +93,30 @@ macro_rules! uniform {
 /// # }
 /// ```
 ///
+/// A field can be followed by `normalize(true)`, in which case its integer values are converted
+/// to floating points between 0.0 and 1.0 (or -1.0 and 1.0 for signed integers) when read by the
+/// shader. This is typically used for colors stored as bytes. The other fields are not
+/// normalized. If `normalize` is repeated for the same field, the field is normalized as soon as
+/// one of the values is `true`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Vertex {
+///     position: [f32; 3],
+///     color: [u8; 4],
+/// }
+///
+/// implement_vertex!(Vertex, position, color normalize(true));
+/// # }
+/// ```
+///
+/// The macro doesn't specify the locations of the attributes, as attributes are matched by
+/// name. Use `layout(location = N)` in the shader, or pass the locations in
+/// `LinkOptions::attribute_locations` when building the program with
+/// `Program::with_link_options`, if you need specific locations.
 #[macro_export]
 macro_rules! implement_vertex {
     ($struct_name:ident, $($field_name:ident),+) => (
@@ -130,7 +154,7 @@ macro_rules! implement_vertex {
         }
     );
 
-    ($struct_name:ident, $($field_name:ident normalize($should_normalize:expr)),+) => {
+    ($struct_name:ident, $($field_name:ident $(normalize($should_normalize:expr))*),+) => {
         impl $crate::vertex::Vertex for $struct_name {
             #[inline]
             fn build_bindings() -> $crate::vertex::VertexFormat {
@@ -157,9 +181,7 @@ macro_rules! implement_vertex {
                                 let dummy: &$struct_name = unsafe { ::std::mem::transmute(0usize) };
                                 attr_type_of_val(&dummy.$field_name)
                             },
-                            {
-                                $should_normalize
-                            }
+                            false $(|| $should_normalize)*
                         )
                     ),+
                 ])
@@ -170,6 +192,10 @@ macro_rules! implement_vertex {
     ($struct_name:ident, $($field_name:ident),+,) => (
         implement_vertex!($struct_name, $($field_name),+);
     );
+
+    ($struct_name:ident, $($field_name:ident $(normalize($should_normalize:expr))*),+,) => (
+        implement_vertex!($struct_name, $($field_name $(normalize($should_normalize))*),+);
+    );
 }
 
 /// Implements the `glium::buffer::Content` trait for the given type.
@@ -421,6 +447,48 @@ macro_rules! implement_uniform_block {
     );
 }
 
+/// Implements the `glium::uniforms::UniformStruct` trait for the given type.
+///
+/// The parameters must be the name of the struct and the names of its fields. Each field must
+/// implement `AsUniformValue` and must have the same name as the member of the GLSL struct.
+///
+/// ## Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Material {
+///     diffuse: [f32; 3],
+///     shininess: f32,
+/// }
+///
+/// implement_uniform_struct!(Material, diffuse, shininess);
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_uniform_struct {
+    ($struct_name:ident, $($field_name:ident),+,) => (
+        implement_uniform_struct!($struct_name, $($field_name),+);
+    );
+
+    ($struct_name:ident, $($field_name:ident),+) => (
+        impl $crate::uniforms::UniformStruct for $struct_name {
+            #[inline]
+            fn visit_fields<'a, F>(&'a self, mut output: F)
+                                   where F: FnMut(&str, $crate::uniforms::UniformValue<'a>)
+            {
+                $(
+                    output(stringify!($field_name),
+                           $crate::uniforms::AsUniformValue::as_uniform_value(&self.$field_name));
+                )+
+            }
+        }
+    );
+}
+
 /// Builds a program depending on the GLSL version supported by the backend.
 ///
 /// This is implemented with successive calls to `is_glsl_version_supported()`.
@@ -15,6 +15,13 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
             target: Option<&ValidatedAttachments>, mask: gl::types::GLbitfield,
             src_rect: &Rect, target_rect: &BlitTarget, filter: gl::types::GLenum)
 {
+    if mask & gl::COLOR_BUFFER_BIT != 0 {
+        if let Some(source) = source {
+            assert!(source.get_num_color_attachments() >= 1,
+                    "The source of a color blit doesn't have any color attachment");
+        }
+    }
+
     unsafe {
         let mut ctxt = context.make_current();
 
@@ -85,5 +92,7 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
                 (target_rect.left as i32 + target_rect.width) as gl::types::GLint,
                 (target_rect.bottom as i32 + target_rect.height) as gl::types::GLint, mask, filter);
         }
+
+        ctxt.report_errors("blit");
     }
 }
@@ -1,8 +1,9 @@
-use fbo::{self, ValidatedAttachments};
+use fbo::{self, ClearBufferData, ValidatedAttachments};
 
 use context::Context;
 use ContextExt;
 use Rect;
+use texture::TextureKind;
 
 use Surface;
 
@@ -72,7 +73,36 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments>,
 
         let mut flags = 0;
 
-        if let Some(color) = color {
+        // the content of integral and unsigned attachments is undefined after `glClear`, so
+        // they are cleared one by one with the color converted to integers
+        let integral_attachments = framebuffer.map(|f| {
+            f.get_color_attachments_kinds().iter().any(|&(_, kind)| kind != TextureKind::Float)
+        }).unwrap_or(false);
+
+        if let (Some(color), true) = (color, integral_attachments) {
+            let color = [color.0, color.1, color.2, color.3];
+
+            for &(index, kind) in framebuffer.unwrap().get_color_attachments_kinds() {
+                let index = index as gl::types::GLint;
+
+                match kind {
+                    TextureKind::Integral => {
+                        let data = [color[0] as i32, color[1] as i32, color[2] as i32,
+                                    color[3] as i32];
+                        ctxt.gl.ClearBufferiv(gl::COLOR, index, data.as_ptr());
+                    },
+                    TextureKind::Unsigned => {
+                        let data = [color[0] as u32, color[1] as u32, color[2] as u32,
+                                    color[3] as u32];
+                        ctxt.gl.ClearBufferuiv(gl::COLOR, index, data.as_ptr());
+                    },
+                    _ => {
+                        ctxt.gl.ClearBufferfv(gl::COLOR, index, color.as_ptr());
+                    },
+                }
+            }
+
+        } else if let Some(color) = color {
             let color = (color.0 as gl::types::GLclampf, color.1 as gl::types::GLclampf,
                          color.2 as gl::types::GLclampf, color.3 as gl::types::GLclampf);
 
@@ -116,8 +146,158 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments>,
                 ctxt.gl.ClearStencil(stencil);
                 ctxt.state.clear_stencil = stencil;
             }
+
+            if ctxt.state.stencil_mask_front != 0xffffffff ||
+               ctxt.state.stencil_mask_back != 0xffffffff
+            {
+                ctxt.gl.StencilMask(0xffffffff);
+                ctxt.state.stencil_mask_front = 0xffffffff;
+                ctxt.state.stencil_mask_back = 0xffffffff;
+            }
+        }
+
+        if flags != 0 {
+            ctxt.gl.Clear(flags);
+        }
+
+        ctxt.report_errors("clear");
+    }
+}
+
+pub fn clear_channel(context: &Context, framebuffer: Option<&ValidatedAttachments>,
+                     attachment: u32, channel: u32, value: f32)
+{
+    assert!(channel < 4, "The channel index must be between 0 and 3");
+
+    match framebuffer {
+        Some(framebuffer) => assert!(framebuffer.has_color_attachment(attachment),
+                                     "The surface has no color attachment at index {}",
+                                     attachment),
+        None => assert!(attachment == 0,
+                        "The surface has no color attachment at index {}", attachment),
+    }
+
+    let single_attachment = framebuffer.map(|f| f.get_num_color_attachments() == 1)
+                                       .unwrap_or(true);
+
+    unsafe {
+        let mut ctxt = context.make_current();
+
+        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, framebuffer);
+        fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false);
+
+        if ctxt.state.enabled_rasterizer_discard {
+            ctxt.gl.Disable(gl::RASTERIZER_DISCARD);
+            ctxt.state.enabled_rasterizer_discard = false;
+        }
+
+        if ctxt.state.enabled_scissor_test {
+            ctxt.gl.Disable(gl::SCISSOR_TEST);
+            ctxt.state.enabled_scissor_test = false;
+        }
+
+        if ctxt.state.enabled_framebuffer_srgb {
+            ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
+            ctxt.state.enabled_framebuffer_srgb = false;
+        }
+
+        let mask = (if channel == 0 { 1 } else { 0 }, if channel == 1 { 1 } else { 0 },
+                    if channel == 2 { 1 } else { 0 }, if channel == 3 { 1 } else { 0 });
+        if ctxt.state.color_mask != mask {
+            ctxt.gl.ColorMask(mask.0, mask.1, mask.2, mask.3);
+            ctxt.state.color_mask = mask;
+        }
+
+        TimeElapsedQuery::end_conditional_render(&mut ctxt);
+
+        let mut data = [0.0; 4];
+        data[channel as usize] = value;
+
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+            ctxt.gl.ClearBufferfv(gl::COLOR, attachment as gl::types::GLint, data.as_ptr());
+
+        } else if single_attachment {
+            // without `glClearBuffer`, `glClear` can be used only if it doesn't touch other
+            // attachments
+            let color = (data[0], data[1], data[2], data[3]);
+            if ctxt.state.clear_color != color {
+                ctxt.gl.ClearColor(color.0, color.1, color.2, color.3);
+                ctxt.state.clear_color = color;
+            }
+
+            ctxt.gl.Clear(gl::COLOR_BUFFER_BIT);
+
+        } else {
+            panic!("Clearing one attachment of a framebuffer with multiple attachments is not \
+                    supported by the backend");
+        }
+
+        ctxt.report_errors("clear_channel");
+    }
+}
+
+/// Clears all the color attachments of a framebuffer whose attachments are all integral or all
+/// unsigned.
+///
+/// # Panic
+///
+/// Panics if `data` is not `Integral` or `Unsigned`, or if one of the color attachments
+/// doesn't match `data`.
+pub fn clear_color_integral(context: &Context, framebuffer: Option<&ValidatedAttachments>,
+                            data: ClearBufferData)
+{
+    let expected_kind = match data {
+        ClearBufferData::Integral(_) => TextureKind::Integral,
+        ClearBufferData::Unsigned(_) => TextureKind::Unsigned,
+        _ => panic!("Only integral and unsigned colors can be passed to `clear_color_integral`"),
+    };
+
+    let framebuffer = framebuffer.expect("The default framebuffer doesn't have integral or \
+                                          unsigned color attachments");
+
+    for &(index, kind) in framebuffer.get_color_attachments_kinds() {
+        assert!(kind == expected_kind, "The color attachment at index {} is a {:?} attachment, \
+                                        but the color is {:?}", index, kind, expected_kind);
+    }
+
+    unsafe {
+        let mut ctxt = context.make_current();
+
+        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt,
+                                                                            Some(framebuffer));
+        fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false);
+
+        if ctxt.state.enabled_rasterizer_discard {
+            ctxt.gl.Disable(gl::RASTERIZER_DISCARD);
+            ctxt.state.enabled_rasterizer_discard = false;
+        }
+
+        if ctxt.state.enabled_scissor_test {
+            ctxt.gl.Disable(gl::SCISSOR_TEST);
+            ctxt.state.enabled_scissor_test = false;
+        }
+
+        if ctxt.state.color_mask != (1, 1, 1, 1) {
+            ctxt.state.color_mask = (1, 1, 1, 1);
+            ctxt.gl.ColorMask(1, 1, 1, 1);
+        }
+
+        TimeElapsedQuery::end_conditional_render(&mut ctxt);
+
+        for &(index, _) in framebuffer.get_color_attachments_kinds() {
+            let index = index as gl::types::GLint;
+
+            match data {
+                ClearBufferData::Integral(ref data) => {
+                    ctxt.gl.ClearBufferiv(gl::COLOR, index, data.as_ptr());
+                },
+                ClearBufferData::Unsigned(ref data) => {
+                    ctxt.gl.ClearBufferuiv(gl::COLOR, index, data.as_ptr());
+                },
+                _ => unreachable!(),
+            }
         }
 
-        ctxt.gl.Clear(flags);
+        ctxt.report_errors("clear_color_integral");
     }
 }
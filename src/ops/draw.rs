@@ -4,6 +4,7 @@ use BufferExt;
 use BufferSliceExt;
 use ProgramExt;
 use DrawError;
+use GlObject;
 use UniformsExt;
 
 use context::Context;
@@ -12,15 +13,17 @@ use TransformFeedbackSessionExt;
 
 use fbo::{self, ValidatedAttachments};
 
-use uniforms::Uniforms;
+use uniforms::{Uniforms, UniformType};
 use {Program, ToGlEnum};
+use program::is_mesh_shader_supported;
 use index::{self, IndicesSource};
 use vertex::{MultiVerticesSource, VerticesSource, TransformFeedbackSession};
 use vertex_array_object::VertexAttributesSystem;
 
 use draw_parameters::DrawParameters;
+use utils::bitsfield::Bitsfield;
 
-use {gl, context, draw_parameters};
+use {gl, context, draw_parameters, sync};
 use version::Version;
 use version::Api;
 
@@ -65,16 +68,70 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         },
     };
 
+    // dual-source blending is forbidden with multiple render targets
+    if draw_parameters.blend.uses_dual_source() {
+        if let Some(framebuffer) = framebuffer {
+            if framebuffer.get_num_color_attachments() > 1 {
+                return Err(DrawError::DualSourceBlendingWithMultipleTargets);
+            }
+        }
+    }
+
+    // programs with mesh shaders can only launch mesh tasks, and the other programs can't
+    match indices {
+        IndicesSource::MeshTasks { .. } => {
+            if !is_mesh_shader_supported(context) {
+                return Err(DrawError::MeshShadersNotSupported);
+            }
+
+            if !program.has_mesh_shader() {
+                return Err(DrawError::MeshShaderMismatch);
+            }
+        },
+        _ => {
+            if program.has_mesh_shader() {
+                return Err(DrawError::MeshShaderMismatch);
+            }
+        },
+    }
+
+    // the vertices captured by a transform feedback object can only be drawn once the capture
+    // has ended
+    if let IndicesSource::TransformFeedback { object, .. } = indices {
+        if !object.has_been_used() {
+            return Err(DrawError::TransformFeedbackObjectNotUsed);
+        }
+
+        if let Some(ref tf) = draw_parameters.transform_feedback {
+            if tf.get_object_id() == object.get_id() {
+                return Err(DrawError::TransformFeedbackObjectNotUsed);
+            }
+        }
+    }
+
+    // the limits of the backend are only checked in debug mode, since exceeding them otherwise
+    // results in an OpenGL error
+    if cfg!(debug_assertions) {
+        try!(check_limits(context, framebuffer, program));
+    }
+
     // starting the state changes
     let mut ctxt = context.make_current();
 
+    // the uploads that have been issued until now can be waited for with `flush_uploads`
+    unsafe { sync::fence_pending_uploads(&mut ctxt); }
+
     // handling vertices source
     let (vertices_count, instances_count, base_vertex) = {
         let index_buffer = match indices {
             IndicesSource::IndexBuffer { buffer, .. } => Some(buffer),
+            IndicesSource::IndexBufferRange { buffer, .. } => Some(buffer),
             IndicesSource::MultidrawArray { .. } => None,
             IndicesSource::MultidrawElement { indices, .. } => Some(indices),
             IndicesSource::NoIndices { .. } => None,
+            IndicesSource::NoIndicesRange { .. } => None,
+            IndicesSource::TransformFeedback { .. } => None,
+            IndicesSource::MeshTasks { .. } => None,
         };
 
         // determining whether we can use the `base_vertex` variants for drawing
@@ -82,6 +139,9 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
             IndicesSource::MultidrawArray { .. } => false,
             IndicesSource::MultidrawElement { .. } => false,
             IndicesSource::NoIndices { .. } => true,
+            IndicesSource::NoIndicesRange { .. } => true,
+            IndicesSource::TransformFeedback { .. } => false,
+            IndicesSource::MeshTasks { .. } => false,
             _ => ctxt.version >= &Version(Api::Gl, 3, 2) ||
                  ctxt.version >= &Version(Api::GlEs, 3, 2) ||
                  ctxt.extensions.gl_arb_draw_elements_base_vertex ||
@@ -156,15 +216,61 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         (vertices_count, instances_count, binder.bind().unwrap_or(0))
     };
 
+    // checking the range of indices, if any
+    let indices_range = match indices {
+        IndicesSource::IndexBufferRange { start, end, .. } => {
+            if start > end {
+                return Err(DrawError::InvalidIndicesRange);
+            }
+
+            if let Some(vertices_count) = vertices_count {
+                if end as usize >= vertices_count {
+                    return Err(DrawError::InvalidIndicesRange);
+                }
+            }
+
+            Some((start, end))
+        },
+        _ => None,
+    };
+
     // binding the FBO to draw upon
     {
-        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, framebuffer);
+        let fbo_id = match fbo::FramebuffersContainer::get_complete_framebuffer_for_drawing(&mut ctxt,
+                                                                                          framebuffer)
+        {
+            Ok(id) => id,
+            Err(reason) => return Err(DrawError::FramebufferIncomplete(reason)),
+        };
         unsafe { fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false) };
     };
 
+    // the program can't be changed while transform feedback is active and not paused, so the
+    // current capture is paused beforehand if this draw command doesn't use transform feedback
+    // or switches to another program, and `bind` below resumes or replaces it
+    if draw_parameters.transform_feedback.is_none() || ctxt.state.program != program.get_id() {
+        TransformFeedbackSession::pause(&mut ctxt);
+    }
+
+    // the vertices captured by an object can only be drawn once its capture has ended
+    if let IndicesSource::TransformFeedback { object, .. } = indices {
+        TransformFeedbackSession::ensure_object_out_of_transform_feedback(&mut ctxt,
+                                                                          object.get_id());
+    }
+
     // binding the program and uniforms
     program.use_program(&mut ctxt);
-    try!(uniforms.bind_uniforms(&mut ctxt, program, &mut fences));
+    let texture_units = try!(uniforms.bind_uniforms(&mut ctxt, program, &mut fences));
+
+    // sampling a texture that we are drawing to gives undefined results, which is only checked
+    // in debug mode as well
+    if cfg!(debug_assertions) {
+        if let Some(framebuffer) = framebuffer {
+            try!(check_feedback_loop(&ctxt, framebuffer, &texture_units));
+        }
+
+        check_early_fragment_tests(&mut ctxt, program, draw_parameters);
+    }
 
     // sync-ing draw_parameters
     unsafe {
@@ -172,11 +278,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         sync_vertices_per_patch(&mut ctxt, vertices_per_patch);
 
         // TODO: make sure that the program is the right one
-        // TODO: changing the current transform feedback requires pausing/unbinding before changing the program
         if let Some(ref tf) = draw_parameters.transform_feedback {
             tf.bind(&mut ctxt, indices.get_primitives_type());
-        } else {
-            TransformFeedbackSession::unbind(&mut ctxt);
         }
     }
 
@@ -184,7 +287,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
     // TODO: make this code more readable
     {
         match &indices {
-            &IndicesSource::IndexBuffer { ref buffer, data_type, primitives } => {
+            &IndicesSource::IndexBuffer { ref buffer, data_type, primitives } |
+            &IndicesSource::IndexBufferRange { ref buffer, data_type, primitives, .. } => {
                 let ptr: *const u8 = ptr::null_mut();
                 let ptr = unsafe { ptr.offset(buffer.get_offset_bytes() as isize) };
 
@@ -231,7 +335,48 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                         }
 
                     } else {
-                        if base_vertex != 0 {
+                        if let (Some((start, end)), 0) = (indices_range, base_vertex) {
+                            if ctxt.version >= &Version(Api::Gl, 1, 2) ||
+                               ctxt.version >= &Version(Api::GlEs, 3, 0)
+                            {
+                                ctxt.gl.DrawRangeElements(primitives.to_glenum(), start, end,
+                                                          buffer.get_elements_count() as
+                                                          gl::types::GLsizei,
+                                                          data_type.to_glenum(),
+                                                          ptr as *const _);
+                            } else {
+                                ctxt.gl.DrawElements(primitives.to_glenum(),
+                                                     buffer.get_elements_count() as
+                                                     gl::types::GLsizei,
+                                                     data_type.to_glenum(),
+                                                     ptr as *const _);
+                            }
+
+                        } else if let Some((start, end)) = indices_range {
+                            if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                               ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                               ctxt.extensions.gl_arb_draw_elements_base_vertex
+                            {
+                                ctxt.gl.DrawRangeElementsBaseVertex(primitives.to_glenum(),
+                                                                    start, end,
+                                                                    buffer.get_elements_count() as
+                                                                    gl::types::GLsizei,
+                                                                    data_type.to_glenum(),
+                                                                    ptr as *const _,
+                                                                    base_vertex);
+                            } else if ctxt.extensions.gl_oes_draw_elements_base_vertex {
+                                ctxt.gl.DrawRangeElementsBaseVertexOES(primitives.to_glenum(),
+                                                                       start, end,
+                                                                       buffer.get_elements_count() as
+                                                                       gl::types::GLsizei,
+                                                                       data_type.to_glenum(),
+                                                                       ptr as *const _,
+                                                                       base_vertex);
+                            } else {
+                                unreachable!();
+                            }
+
+                        } else if base_vertex != 0 {
                             if ctxt.version >= &Version(Api::Gl, 3, 2) ||
                                ctxt.version >= &Version(Api::GlEs, 3, 2) ||
                                ctxt.extensions.gl_arb_draw_elements_base_vertex
@@ -304,6 +449,59 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                 }
             },
 
+            &IndicesSource::NoIndicesRange { primitives, first, count } => {
+                let vertices_count = match vertices_count {
+                    Some(c) => c,
+                    None => return Err(DrawError::VerticesSourcesLengthMismatch)
+                };
+
+                if first.checked_add(count).map_or(true, |end| end > vertices_count) {
+                    return Err(DrawError::VerticesRangeOutOfBounds);
+                }
+
+                let first = base_vertex + first as gl::types::GLint;
+
+                unsafe {
+                    if let Some(instances_count) = instances_count {
+                        ctxt.gl.DrawArraysInstanced(primitives.to_glenum(), first,
+                                                    count as gl::types::GLsizei,
+                                                    instances_count as gl::types::GLsizei);
+                    } else {
+                        ctxt.gl.DrawArrays(primitives.to_glenum(), first,
+                                           count as gl::types::GLsizei);
+                    }
+                }
+            },
+
+            &IndicesSource::TransformFeedback { object, stream, primitives } => {
+                debug_assert_eq!(base_vertex, 0);       // enforced earlier in this function
+
+                unsafe {
+                    if let Some(instances_count) = instances_count {
+                        if !(ctxt.version >= &Version(Api::Gl, 4, 2)) {
+                            return Err(DrawError::TransformFeedbackInstancingNotSupported);
+                        }
+
+                        ctxt.gl.DrawTransformFeedbackStreamInstanced(primitives.to_glenum(),
+                                                                     object.get_id(), stream,
+                                                                     instances_count as
+                                                                     gl::types::GLsizei);
+                    } else if stream != 0 {
+                        ctxt.gl.DrawTransformFeedbackStream(primitives.to_glenum(),
+                                                            object.get_id(), stream);
+                    } else {
+                        ctxt.gl.DrawTransformFeedback(primitives.to_glenum(), object.get_id());
+                    }
+                }
+            },
+
+            &IndicesSource::MeshTasks { first, count } => {
+                match ctxt.draw_mesh_tasks_nv {
+                    Some(draw_mesh_tasks) => draw_mesh_tasks(first, count),
+                    None => return Err(DrawError::MeshShadersNotSupported),
+                }
+            },
+
             &IndicesSource::NoIndices { primitives } => {
                 let vertices_count = match vertices_count {
                     Some(c) => c,
@@ -331,6 +529,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         fence.insert(&mut ctxt);
     }
 
+    ctxt.report_errors("draw");
+
     Ok(())
 }
 
@@ -343,3 +543,182 @@ unsafe fn sync_vertices_per_patch(ctxt: &mut context::CommandContext, vertices_p
         }
     }
 }
+
+/// Checks the resources used by a draw command against the limits of the backend.
+fn check_limits(context: &Context, framebuffer: Option<&ValidatedAttachments>,
+                program: &Program) -> Result<(), DrawError>
+{
+    let capabilities = context.capabilities();
+
+    let textures = program.uniforms()
+                          .filter(|&(_, u)| is_sampler(u.ty))
+                          .map(|(_, u)| u.size.unwrap_or(1))
+                          .sum::<usize>();
+    let max = capabilities.max_combined_texture_image_units as usize;
+    if textures > max {
+        return Err(DrawError::TooManyTextures { count: textures, max: max });
+    }
+
+    let attributes = program.attributes()
+                            .map(|(_, a)| a.size * a.ty.get_num_locations())
+                            .sum::<usize>();
+    let max = capabilities.max_vertex_attribs as usize;
+    if attributes > max {
+        return Err(DrawError::TooManyVertexAttributes { count: attributes, max: max });
+    }
+
+    if let Some(max) = capabilities.max_uniform_block_size {
+        for (name, block) in program.get_uniform_blocks() {
+            if block.size > max as usize {
+                return Err(DrawError::UniformBlockTooLarge {
+                    name: name.clone(),
+                    size: block.size,
+                    max: max as usize,
+                });
+            }
+        }
+    }
+
+    if let Some(framebuffer) = framebuffer {
+        let count = framebuffer.get_num_color_attachments();
+        let max = capabilities.max_draw_buffers as usize;
+        if count > max {
+            return Err(DrawError::TooManyColorAttachments { count: count, max: max });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether one of the texture units used by the current draw command contains a texture
+/// that is attached to the framebuffer.
+///
+/// `texture_units` are the units that have been bound by `bind_uniforms` for this draw command.
+fn check_feedback_loop(ctxt: &context::CommandContext, framebuffer: &ValidatedAttachments,
+                       texture_units: &Bitsfield) -> Result<(), DrawError>
+{
+    // reading the framebuffer is allowed right after a texture barrier
+    if ctxt.state.latest_texture_barrier == ctxt.state.next_draw_call_id {
+        return Ok(());
+    }
+
+    for (num, unit) in ctxt.state.texture_units.iter().enumerate() {
+        if !texture_units.is_used(num as u16) || unit.texture == 0 {
+            continue;
+        }
+
+        if let Some(attachment) = framebuffer.get_texture_attachment_point(unit.texture) {
+            return Err(DrawError::FeedbackLoop {
+                texture_id: unit.texture,
+                attachment: attachment,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Inserts a warning in the debug output if the program has side effects and relies on the depth
+/// test without declaring `layout(early_fragment_tests) in;`. The side effects would also happen
+/// for the fragments that fail the depth test.
+///
+/// The warning is only inserted the first time the program is used this way.
+fn check_early_fragment_tests(ctxt: &mut context::CommandContext, program: &Program,
+                              draw_parameters: &DrawParameters)
+{
+    if !draw_parameters.depth.test.requires_depth_buffer() {
+        return;
+    }
+
+    if program.has_early_fragment_tests() != Some(false) {
+        return;
+    }
+
+    if !program.uniforms().any(|(_, u)| is_image(u.ty)) &&
+       program.get_shader_storage_blocks().is_empty()
+    {
+        return;
+    }
+
+    if !program.take_early_fragment_tests_warning() {
+        return;
+    }
+
+    let message = "The program uses images or shader storage buffers together with a depth test, \
+                   but doesn't declare `layout(early_fragment_tests) in;`. Its side effects \
+                   also happen for the fragments that fail the depth test.";
+
+    unsafe {
+        if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+           (ctxt.version >= &Version(Api::Gl, 1, 0) && ctxt.extensions.gl_khr_debug)
+        {
+            ctxt.gl.DebugMessageInsert(gl::DEBUG_SOURCE_THIRD_PARTY,
+                                       gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR,
+                                       context::GLIUM_DEBUG_MESSAGE_ID,
+                                       gl::DEBUG_SEVERITY_MEDIUM,
+                                       message.len() as gl::types::GLsizei,
+                                       message.as_ptr() as *const _);
+
+        } else if ctxt.extensions.gl_khr_debug {
+            ctxt.gl.DebugMessageInsertKHR(gl::DEBUG_SOURCE_THIRD_PARTY,
+                                          gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR,
+                                       context::GLIUM_DEBUG_MESSAGE_ID,
+                                          gl::DEBUG_SEVERITY_MEDIUM,
+                                          message.len() as gl::types::GLsizei,
+                                          message.as_ptr() as *const _);
+
+        } else if ctxt.extensions.gl_arb_debug_output {
+            ctxt.gl.DebugMessageInsertARB(gl::DEBUG_SOURCE_THIRD_PARTY,
+                                          gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR,
+                                       context::GLIUM_DEBUG_MESSAGE_ID,
+                                          gl::DEBUG_SEVERITY_MEDIUM,
+                                          message.len() as gl::types::GLsizei,
+                                          message.as_ptr() as *const _);
+        }
+    }
+}
+
+/// Returns true if a uniform of this type is an image.
+fn is_image(ty: UniformType) -> bool {
+    match ty {
+        UniformType::Image1d | UniformType::IImage1d | UniformType::UImage1d |
+        UniformType::Image2d | UniformType::IImage2d | UniformType::UImage2d |
+        UniformType::Image3d | UniformType::IImage3d | UniformType::UImage3d |
+        UniformType::Image2dRect | UniformType::IImage2dRect | UniformType::UImage2dRect |
+        UniformType::ImageCube | UniformType::IImageCube | UniformType::UImageCube |
+        UniformType::ImageBuffer | UniformType::IImageBuffer | UniformType::UImageBuffer |
+        UniformType::Image1dArray | UniformType::IImage1dArray | UniformType::UImage1dArray |
+        UniformType::Image2dArray | UniformType::IImage2dArray | UniformType::UImage2dArray |
+        UniformType::Image2dMultisample | UniformType::IImage2dMultisample |
+        UniformType::UImage2dMultisample | UniformType::Image2dMultisampleArray |
+        UniformType::IImage2dMultisampleArray | UniformType::UImage2dMultisampleArray |
+        UniformType::ImageCubeArray | UniformType::IImageCubeArray |
+        UniformType::UImageCubeArray => true,
+        _ => false,
+    }
+}
+
+/// Returns true if a uniform of this type is bound to a texture unit.
+fn is_sampler(ty: UniformType) -> bool {
+    match ty {
+        UniformType::Sampler1d | UniformType::ISampler1d | UniformType::USampler1d |
+        UniformType::Sampler2d | UniformType::ISampler2d | UniformType::USampler2d |
+        UniformType::Sampler3d | UniformType::ISampler3d | UniformType::USampler3d |
+        UniformType::Sampler1dArray | UniformType::ISampler1dArray |
+        UniformType::USampler1dArray | UniformType::Sampler2dArray |
+        UniformType::ISampler2dArray | UniformType::USampler2dArray |
+        UniformType::SamplerCube | UniformType::ISamplerCube | UniformType::USamplerCube |
+        UniformType::Sampler2dRect | UniformType::ISampler2dRect |
+        UniformType::USampler2dRect | UniformType::Sampler2dRectShadow |
+        UniformType::SamplerCubeArray | UniformType::ISamplerCubeArray |
+        UniformType::USamplerCubeArray | UniformType::SamplerBuffer |
+        UniformType::ISamplerBuffer | UniformType::USamplerBuffer |
+        UniformType::Sampler2dMultisample | UniformType::ISampler2dMultisample |
+        UniformType::USampler2dMultisample | UniformType::Sampler2dMultisampleArray |
+        UniformType::ISampler2dMultisampleArray | UniformType::USampler2dMultisampleArray |
+        UniformType::Sampler1dShadow | UniformType::Sampler2dShadow |
+        UniformType::SamplerCubeShadow | UniformType::Sampler1dArrayShadow |
+        UniformType::Sampler2dArrayShadow | UniformType::SamplerCubeArrayShadow => true,
+        _ => false,
+    }
+}
@@ -1,7 +1,7 @@
 pub use self::blit::blit;
-pub use self::clear::clear;
+pub use self::clear::{clear, clear_channel, clear_color_integral};
 pub use self::draw::draw;
-pub use self::read::{read, ReadError, Source, Destination};
+pub use self::read::{read, read_depth, ReadError, Source, Destination};
 
 mod blit;
 mod clear;
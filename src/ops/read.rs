@@ -12,6 +12,7 @@ use fbo::FramebuffersContainer;
 
 use buffer::BufferAny;
 use BufferExt;
+use TextureExt;
 use Rect;
 use context::CommandContext;
 use gl;
@@ -37,6 +38,7 @@ impl<'a> From<&'a fbo::RegularAttachment<'a>> for Source<'a> {
 /// A destination for reading pixels.
 pub enum Destination<'a, P> where P: PixelValue {
     Memory(&'a mut Vec<P>),
+    Slice(&'a mut [P]),
     PixelBuffer(&'a PixelBuffer<P>),
     // TODO: texture with glCopyTexSubImage2D
 }
@@ -48,6 +50,13 @@ impl<'a, P> From<&'a mut Vec<P>> for Destination<'a, P> where P: PixelValue {
     }
 }
 
+impl<'a, P> From<&'a mut [P]> for Destination<'a, P> where P: PixelValue {
+    #[inline]
+    fn from(slice: &'a mut [P]) -> Destination<'a, P> {
+        Destination::Slice(slice)
+    }
+}
+
 impl<'a, P> From<&'a PixelBuffer<P>> for Destination<'a, P> where P: PixelValue {
     #[inline]
     fn from(pb: &'a PixelBuffer<P>) -> Destination<'a, P> {
@@ -73,6 +82,12 @@ pub enum ReadError {
     /// Clamping the values is not supported by the implementation.
     ClampingNotSupported,
 
+    /// The destination is too small to hold the pixels that are read.
+    DestinationTooSmall,
+
+    /// Trying to read depth values from a surface that doesn't have a depth buffer.
+    NoDepthBuffer,
+
     // TODO: context lost
 }
 
@@ -92,6 +107,10 @@ impl Error for ReadError {
                 "The implementation doesn't support reading a depth, depth-stencil or stencil attachment",
             ClampingNotSupported =>
                 "Clamping the values is not supported by the implementation",
+            DestinationTooSmall =>
+                "The destination is too small to hold the pixels that are read",
+            NoDepthBuffer =>
+                "Trying to read depth values from a surface that doesn't have a depth buffer",
         }
     }
 }
@@ -114,6 +133,12 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
 
     let pixels_to_read = rect.width * rect.height;
 
+    if let Destination::Slice(ref slice) = dest {
+        if slice.len() < pixels_to_read as usize {
+            return Err(ReadError::DestinationTooSmall);
+        }
+    }
+
     // checking that the output format is supported
     // OpenGL supported everything, while OpenGL ES only supports U8U8U8U8 plus an additional
     // implementation-defined format
@@ -124,16 +149,22 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
     }
 
     // handling clamping
-    if ctxt.version >= &Version(Api::Gl, 3, 0) {
-        unsafe {
-            if clamp && ctxt.state.clamp_color != gl::TRUE as gl::types::GLenum {
-                ctxt.gl.ClampColor(gl::CLAMP_READ_COLOR, gl::TRUE as gl::types::GLenum);
-                ctxt.state.clamp_color = gl::TRUE as gl::types::GLenum;
-
-            } else if !clamp && ctxt.state.clamp_color != gl::FALSE as gl::types::GLenum {
-                ctxt.gl.ClampColor(gl::CLAMP_READ_COLOR, gl::FALSE as gl::types::GLenum);
-                ctxt.state.clamp_color = gl::FALSE as gl::types::GLenum;
+    // the default value of `GL_CLAMP_READ_COLOR` is `GL_FIXED_ONLY`, but it can have been
+    // changed by the user, in which case the values of floating-point surfaces would be
+    // silently clamped to `[0, 1]`
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_color_buffer_float {
+        let value = if clamp { gl::TRUE } else { gl::FALSE } as gl::types::GLenum;
+
+        if ctxt.state.clamp_color != value {
+            unsafe {
+                if ctxt.version >= &Version(Api::Gl, 3, 0) {
+                    ctxt.gl.ClampColor(gl::CLAMP_READ_COLOR, value);
+                } else {
+                    ctxt.gl.ClampColorARB(gl::CLAMP_READ_COLOR_ARB, value);
+                }
             }
+
+            ctxt.state.clamp_color = value;
         }
     } else {
         if clamp {
@@ -146,6 +177,10 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
     // binding framebuffer
     match source {
         Source::Attachment(attachment) => {
+            if let &fbo::RegularAttachment::Texture(ref tex) = attachment {
+                tex.get_texture().prepare_for_framebuffer(&mut ctxt);
+            }
+
             unsafe { FramebuffersContainer::bind_framebuffer_for_reading(&mut ctxt, attachment) };
         },
         Source::DefaultFramebuffer(read_buffer) => {
@@ -219,35 +254,29 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
                 let mut buf = Vec::with_capacity(pixels_to_read as usize);
 
                 BufferAny::unbind_pixel_pack(ctxt);
+                set_pack_alignment(ctxt, rect.width as usize * output_pixel_format.get_size());
 
-                // adjusting data alignement
-                let ptr = buf.as_mut_ptr() as *mut D;
-                let ptr = ptr as usize;
-                if (ptr % 8) == 0 {
-                } else if (ptr % 4) == 0 && ctxt.state.pixel_store_pack_alignment != 4 {
-                    ctxt.state.pixel_store_pack_alignment = 4;
-                    ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 4);
-                } else if (ptr % 2) == 0 && ctxt.state.pixel_store_pack_alignment > 2 {
-                    ctxt.state.pixel_store_pack_alignment = 2;
-                    ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 2);
-                } else if ctxt.state.pixel_store_pack_alignment != 1 {
-                    ctxt.state.pixel_store_pack_alignment = 1;
-                    ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, 1);
-                }
-
-                ctxt.gl.ReadPixels(rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
-                                   rect.width as gl::types::GLsizei,
-                                   rect.height as gl::types::GLsizei, format, gltype,
-                                   buf.as_mut_ptr() as *mut _);
+                read_pixels_bounded(ctxt, rect, format, gltype, buf.as_mut_ptr() as *mut _,
+                                    pixels_to_read as usize * output_pixel_format.get_size());
                 buf.set_len(pixels_to_read as usize);
 
                 *dest = buf;
             },
 
+            Destination::Slice(dest) => {
+                BufferAny::unbind_pixel_pack(ctxt);
+                set_pack_alignment(ctxt, rect.width as usize * output_pixel_format.get_size());
+
+                read_pixels_bounded(ctxt, rect, format, gltype, dest.as_mut_ptr() as *mut _,
+                                    dest.len() * output_pixel_format.get_size());
+            },
+
             Destination::PixelBuffer(pixel_buffer) => {
                 assert!(pixel_buffer.len() >= pixels_to_read as usize);
 
                 pixel_buffer.prepare_and_bind_for_pixel_pack(&mut ctxt);
+                set_pack_alignment(ctxt, rect.width as usize * output_pixel_format.get_size());
+
                 ctxt.gl.ReadPixels(rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
                                    rect.width as gl::types::GLsizei,
                                    rect.height as gl::types::GLsizei, format, gltype,
@@ -261,6 +290,91 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
     Ok(())
 }
 
+/// Reads the depth values of a rectangle of a framebuffer, or of the default framebuffer if
+/// `framebuffer` is `None`.
+///
+/// The values are returned row by row, starting with the bottom row.
+pub fn read_depth(ctxt: &mut CommandContext, framebuffer: Option<&fbo::ValidatedAttachments>,
+                  rect: &Rect) -> Result<Vec<f32>, ReadError>
+{
+    // OpenGL ES doesn't support reading from depth buffers by default
+    if ctxt.version >= &Version(Api::GlEs, 2, 0) && !ctxt.extensions.gl_nv_read_depth {
+        return Err(ReadError::AttachmentTypeNotSupported);
+    }
+
+    let has_depth_buffer = match framebuffer {
+        Some(framebuffer) => framebuffer.has_depth_attachment(),
+        None => ctxt.capabilities.depth_bits.is_some(),
+    };
+
+    if !has_depth_buffer {
+        return Err(ReadError::NoDepthBuffer);
+    }
+
+    let pixels_to_read = (rect.width * rect.height) as usize;
+    let mut buf: Vec<f32> = Vec::with_capacity(pixels_to_read);
+
+    unsafe {
+        let fbo_id = FramebuffersContainer::get_framebuffer_for_drawing(ctxt, framebuffer);
+        fbo::bind_framebuffer(ctxt, fbo_id, false, true);
+
+        BufferAny::unbind_pixel_pack(ctxt);
+        set_pack_alignment(ctxt, rect.width as usize * 4);
+
+        read_pixels_bounded(ctxt, rect, gl::DEPTH_COMPONENT, gl::FLOAT,
+                            buf.as_mut_ptr() as *mut _, pixels_to_read * 4);
+        buf.set_len(pixels_to_read);
+    }
+
+    Ok(buf)
+}
+
+/// Calls `glReadnPixels` with the size of the destination if the backend supports robustness,
+/// so that the implementation never writes past the end of the destination. Otherwise calls
+/// `glReadPixels`, in which case the caller must have checked the size of the destination.
+unsafe fn read_pixels_bounded(ctxt: &mut CommandContext, rect: &Rect, format: gl::types::GLenum,
+                              gltype: gl::types::GLenum, dest: *mut gl::types::GLvoid,
+                              dest_size: usize)
+{
+    let (x, y) = (rect.left as gl::types::GLint, rect.bottom as gl::types::GLint);
+    let (width, height) = (rect.width as gl::types::GLsizei, rect.height as gl::types::GLsizei);
+    let dest_size = dest_size as gl::types::GLsizei;
+
+    if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+       ctxt.extensions.gl_khr_robustness
+    {
+        ctxt.gl.ReadnPixels(x, y, width, height, format, gltype, dest_size, dest);
+    } else if ctxt.extensions.gl_arb_robustness {
+        ctxt.gl.ReadnPixelsARB(x, y, width, height, format, gltype, dest_size, dest);
+    } else if ctxt.extensions.gl_ext_robustness {
+        ctxt.gl.ReadnPixelsEXT(x, y, width, height, format, gltype, dest_size, dest);
+    } else {
+        ctxt.gl.ReadPixels(x, y, width, height, format, gltype, dest);
+    }
+}
+
+/// Sets `GL_PACK_ALIGNMENT` so that rows of `row_size` bytes are written without any padding
+/// between them, which is what the destinations of `read` expect.
+///
+/// `GL_PACK_ROW_LENGTH` is never modified by glium and keeps its default value of 0, which means
+/// that the row length is the width of the rectangle that is read.
+unsafe fn set_pack_alignment(ctxt: &mut CommandContext, row_size: usize) {
+    let alignment = if row_size % 8 == 0 {
+        8
+    } else if row_size % 4 == 0 {
+        4
+    } else if row_size % 2 == 0 {
+        2
+    } else {
+        1
+    };
+
+    if ctxt.state.pixel_store_pack_alignment != alignment {
+        ctxt.state.pixel_store_pack_alignment = alignment;
+        ctxt.gl.PixelStorei(gl::PACK_ALIGNMENT, alignment);
+    }
+}
+
 fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
                             -> (gl::types::GLenum, gl::types::GLenum)
 {
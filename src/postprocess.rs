@@ -0,0 +1,147 @@
+/*!
+Helpers for post-processing passes.
+
+*This module is only available if the `postprocess` feature of glium is enabled.*
+
+Post-processing effects such as blurs, bloom or tone mapping are usually made of several passes,
+each pass reading the result of the previous one and writing to another texture. A `PingPong`
+owns the two textures that are needed for this, and swaps their roles after each pass.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let scene: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+# fn draw_pass(_: &glium::texture::Texture2d, _: &mut glium::framebuffer::SimpleFrameBuffer) {}
+use glium::postprocess::PingPong;
+use glium::texture::UncompressedFloatFormat;
+
+let mut targets = PingPong::with_framebuffer_dimensions(&display,
+                                                        UncompressedFloatFormat::F16F16F16F16)
+                                                        .unwrap();
+
+// each frame
+targets.resize_to_framebuffer().unwrap();
+draw_pass(&scene, &mut targets.dst());
+targets.swap();
+for _ in 0 .. 4 {
+    draw_pass(targets.src(), &mut targets.dst());
+    targets.swap();
+}
+// the result is now in `targets.src()`
+```
+
+*/
+use std::mem;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use framebuffer::SimpleFrameBuffer;
+use texture::{MipmapsOption, Texture2d, TextureCreationError, UncompressedFloatFormat};
+
+/// Two textures with the same format and dimensions, one being read and the other being
+/// written.
+///
+/// Both textures are always recreated together, so that they keep the same dimensions. The
+/// content of the textures is undefined after they have been created or resized.
+pub struct PingPong {
+    context: Rc<Context>,
+    format: UncompressedFloatFormat,
+    src: Texture2d,
+    dst: Texture2d,
+}
+
+impl PingPong {
+    /// Builds the two textures with the given format and dimensions.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32, height: u32)
+                          -> Result<PingPong, TextureCreationError> where F: Facade
+    {
+        let (src, dst) = try!(build_textures(facade.get_context(), format, width, height));
+
+        Ok(PingPong {
+            context: facade.get_context().clone(),
+            format: format,
+            src: src,
+            dst: dst,
+        })
+    }
+
+    /// Builds the two textures with the given format and the dimensions of the default
+    /// framebuffer.
+    #[inline]
+    pub fn with_framebuffer_dimensions<F: ?Sized>(facade: &F, format: UncompressedFloatFormat)
+                                                  -> Result<PingPong, TextureCreationError>
+                                                  where F: Facade
+    {
+        let (width, height) = facade.get_context().get_framebuffer_dimensions();
+        PingPong::new(facade, format, width, height)
+    }
+
+    /// Returns the texture that contains the result of the previous pass.
+    #[inline]
+    pub fn src(&self) -> &Texture2d {
+        &self.src
+    }
+
+    /// Returns a framebuffer that draws to the texture of the current pass.
+    ///
+    /// The framebuffer object is cached by glium, so calling this function for each pass is
+    /// cheap.
+    #[inline]
+    pub fn dst(&self) -> SimpleFrameBuffer {
+        self.dst.as_surface()
+    }
+
+    /// Returns the texture of the current pass.
+    #[inline]
+    pub fn dst_texture(&self) -> &Texture2d {
+        &self.dst
+    }
+
+    /// Exchanges the two textures, so that the texture that has just been written becomes the
+    /// source of the next pass.
+    #[inline]
+    pub fn swap(&mut self) {
+        mem::swap(&mut self.src, &mut self.dst);
+    }
+
+    /// Returns the dimensions of the textures.
+    #[inline]
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        (self.src.get_width(), self.src.get_height().unwrap())
+    }
+
+    /// Changes the dimensions of the textures.
+    ///
+    /// Nothing happens if the dimensions didn't change. Otherwise both textures are created
+    /// again, and their content is undefined. If an error happens, the old textures are kept.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), TextureCreationError> {
+        if self.get_dimensions() == (width, height) {
+            return Ok(());
+        }
+
+        let (src, dst) = try!(build_textures(&self.context, self.format, width, height));
+        self.src = src;
+        self.dst = dst;
+        Ok(())
+    }
+
+    /// Changes the dimensions of the textures to match the default framebuffer, for example
+    /// after the window has been resized.
+    ///
+    /// See `resize`.
+    #[inline]
+    pub fn resize_to_framebuffer(&mut self) -> Result<(), TextureCreationError> {
+        let (width, height) = self.context.get_framebuffer_dimensions();
+        self.resize(width, height)
+    }
+}
+
+fn build_textures(context: &Rc<Context>, format: UncompressedFloatFormat, width: u32,
+                  height: u32) -> Result<(Texture2d, Texture2d), TextureCreationError>
+{
+    let src = try!(Texture2d::empty_with_format(context, format, MipmapsOption::NoMipmap,
+                                                width, height));
+    let dst = try!(Texture2d::empty_with_format(context, format, MipmapsOption::NoMipmap,
+                                                width, height));
+    Ok((src, dst))
+}
@@ -4,6 +4,7 @@ use context::CommandContext;
 use backend::Facade;
 
 use std::fmt;
+use std::ops::{Deref, DerefMut};
 use std::collections::hash_map::{self, HashMap};
 use std::os::raw;
 use std::hash::BuildHasherDefault;
@@ -12,6 +13,7 @@ use fnv::FnvHasher;
 
 use CapabilitiesSource;
 use GlObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use ProgramExt;
 use Handle;
 use RawUniformValue;
@@ -24,7 +26,7 @@ use program::shader::{build_shader, check_shader_type_compatibility};
 
 use program::raw::RawProgram;
 
-use buffer::BufferSlice;
+use buffer::{Buffer, BufferSlice, BufferCreationError, BufferType, BufferMode};
 use uniforms::Uniforms;
 
 /// A combination of compute shaders linked together.
@@ -49,7 +51,8 @@ impl ComputeShader {
         let shader = try!(build_shader(facade, gl::COMPUTE_SHADER, src));
 
         Ok(ComputeShader {
-            raw: try!(RawProgram::from_shaders(facade, &[shader], false, false, false, None))
+            raw: try!(RawProgram::from_shaders(facade, &[shader], false, false, false, None, None,
+                                               &[], &[]))
         })
     }
 
@@ -65,6 +68,22 @@ impl ComputeShader {
         })
     }
 
+    /// Returns the info log of the linker, if it isn't empty.
+    ///
+    /// See `Program::info_log`.
+    #[inline]
+    pub fn info_log(&self) -> Option<&str> {
+        self.raw.get_info_log()
+    }
+
+    /// Returns the info log of the compiler, if it isn't empty.
+    ///
+    /// Returns `None` if the compute shader was created from a binary.
+    #[inline]
+    pub fn shader_info_log(&self) -> Option<&str> {
+        self.raw.get_shader_info_log(gl::COMPUTE_SHADER)
+    }
+
     /// Executes the compute shader.
     ///
     /// `x * y * z` work groups will be started. The current work group can be retreived with
@@ -77,7 +96,8 @@ impl ComputeShader {
 
     /// Executes the compute shader.
     ///
-    /// This is similar to `execute`, except that the parameters are stored in a buffer.
+    /// This is similar to `execute`, except that the parameters are stored in a buffer. Use
+    /// `ComputeCommandsBuffer::get` to obtain the command to execute.
     #[inline]
     pub fn execute_indirect<U>(&self, uniforms: U, buffer: BufferSlice<ComputeCommand>)
                                where U: Uniforms
@@ -94,6 +114,18 @@ impl ComputeShader {
         self.raw.get_binary()
     }
 
+    /// Returns informations about the resources used by each work group of the compute shader.
+    ///
+    /// Returns `None` if the backend can't report them.
+    #[inline]
+    pub fn resource_usage(&self) -> Option<ProgramResourceUsage> {
+        unsafe { self.raw.get_compute_work_group_size() }.map(|size| {
+            ProgramResourceUsage {
+                work_group_size: size,
+            }
+        })
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
@@ -148,6 +180,27 @@ impl ComputeShader {
     }
 }
 
+/// Resources used by each work group of a compute shader.
+///
+/// OpenGL doesn't provide a portable way to query the number of registers or the amount of
+/// shared memory used by a program, so only the values that can be introspected are reported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ProgramResourceUsage {
+    /// The local work group size declared in the shader with `layout(local_size_x = ...,
+    /// local_size_y = ..., local_size_z = ...) in;`.
+    ///
+    /// Each work group started by `execute` runs `x * y * z` invocations of the shader.
+    pub work_group_size: (u32, u32, u32),
+}
+
+impl ProgramResourceUsage {
+    /// Returns the number of invocations of the shader in each work group.
+    #[inline]
+    pub fn invocations(&self) -> u32 {
+        self.work_group_size.0 * self.work_group_size.1 * self.work_group_size.2
+    }
+}
+
 impl fmt::Debug for ComputeShader {
     #[inline]
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
@@ -155,6 +208,13 @@ impl fmt::Debug for ComputeShader {
     }
 }
 
+impl Labeled for ComputeShader {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        self.raw.label(label)
+    }
+}
+
 impl GlObject for ComputeShader {
     type Id = Handle;
 
@@ -235,3 +295,66 @@ pub struct ComputeCommand {
 }
 
 implement_uniform_block!(ComputeCommand, num_groups_x, num_groups_y, num_groups_z);
+
+/// A buffer containing a list of compute commands, to be used with `execute_indirect`.
+///
+/// The buffer can be filled by a previous compute shader, which allows the GPU to decide how many
+/// work groups are started without any synchronization with the CPU.
+pub struct ComputeCommandsBuffer {
+    buffer: Buffer<[ComputeCommand]>,
+}
+
+impl ComputeCommandsBuffer {
+    /// Builds a new buffer containing the given commands.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, data: &[ComputeCommand])
+                          -> Result<ComputeCommandsBuffer, BufferCreationError>
+                          where F: Facade
+    {
+        let buf = try!(Buffer::new(facade, data, BufferType::DispatchIndirectBuffer,
+                                   BufferMode::Default));
+        Ok(ComputeCommandsBuffer { buffer: buf })
+    }
+
+    /// Builds an empty buffer.
+    ///
+    /// The parameter indicates the number of elements.
+    #[inline]
+    pub fn empty<F: ?Sized>(facade: &F, elements: usize)
+                            -> Result<ComputeCommandsBuffer, BufferCreationError>
+                            where F: Facade
+    {
+        let buf = try!(Buffer::empty_array(facade, BufferType::DispatchIndirectBuffer,
+                                           elements, BufferMode::Default));
+        Ok(ComputeCommandsBuffer { buffer: buf })
+    }
+
+    /// Builds an empty buffer.
+    ///
+    /// The parameter indicates the number of elements.
+    #[inline]
+    pub fn empty_dynamic<F: ?Sized>(facade: &F, elements: usize)
+                                    -> Result<ComputeCommandsBuffer, BufferCreationError>
+                                    where F: Facade
+    {
+        let buf = try!(Buffer::empty_array(facade, BufferType::DispatchIndirectBuffer,
+                                           elements, BufferMode::Dynamic));
+        Ok(ComputeCommandsBuffer { buffer: buf })
+    }
+}
+
+impl Deref for ComputeCommandsBuffer {
+    type Target = Buffer<[ComputeCommand]>;
+
+    #[inline]
+    fn deref(&self) -> &Buffer<[ComputeCommand]> {
+        &self.buffer
+    }
+}
+
+impl DerefMut for ComputeCommandsBuffer {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Buffer<[ComputeCommand]> {
+        &mut self.buffer
+    }
+}
@@ -6,8 +6,10 @@ use CapabilitiesSource;
 use gl;
 use version::Api;
 use version::Version;
+use vertex::AttributeType;
 
-pub use self::compute::{ComputeShader, ComputeCommand};
+pub use self::compute::{ComputeShader, ComputeCommand, ComputeCommandsBuffer};
+pub use self::compute::ProgramResourceUsage;
 pub use self::program::Program;
 pub use self::reflection::{Uniform, UniformBlock, BlockLayout, OutputPrimitives};
 pub use self::reflection::{Attribute, TransformFeedbackVarying, TransformFeedbackBuffer, TransformFeedbackMode};
@@ -33,6 +35,13 @@ pub fn is_tessellation_shader_supported<C: ?Sized>(ctxt: &C) -> bool where C: Ca
     shader::check_shader_type_compatibility(ctxt, gl::TESS_CONTROL_SHADER)
 }
 
+/// Returns true if the backend supports mesh and task shaders, in other words
+/// `ProgramCreationInput::MeshShaders` and `Surface::draw_mesh_tasks`.
+#[inline]
+pub fn is_mesh_shader_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    shader::check_shader_type_compatibility(ctxt, shader::MESH_SHADER_NV)
+}
+
 /// Returns true if the backend supports creating and retreiving binary format.
 #[inline]
 pub fn is_binary_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
@@ -40,6 +49,13 @@ pub fn is_binary_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSou
         || ctxt.get_extensions().gl_arb_get_programy_binary
 }
 
+/// Returns true if the backend supports creating programs from SPIR-V binaries with
+/// `ProgramCreationInput::SpirV`.
+#[inline]
+pub fn is_spirv_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_version() >= &Version(Api::Gl, 4, 6) || ctxt.get_extensions().gl_arb_gl_spirv
+}
+
 /// Returns true if the backend supports shader subroutines.
 #[inline]
 pub fn is_subroutine_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
@@ -87,6 +103,24 @@ pub enum ProgramCreationError {
 
     /// The glium-specific binary header was not found or is corrupt.
     BinaryHeaderError,
+
+    /// You have requested dual-source blending outputs, but dual-source blending is not
+    /// supported by the backend.
+    DualSourceBlendingNotSupported,
+
+    /// You have passed SPIR-V binaries, but the backend doesn't support them.
+    SpirvNotSupported,
+
+    /// You have requested to bind the outputs of the fragment shader to specific locations,
+    /// but this is not supported by the backend.
+    FragDataLocationNotSupported,
+
+    /// One of the locations passed in `attribute_locations` or `frag_data_locations` is
+    /// out of range.
+    LocationOutOfRange,
+
+    /// The entry point of a SPIR-V module contains a nul byte.
+    InvalidEntryPoint,
 }
 
 impl fmt::Display for ProgramCreationError {
@@ -121,6 +155,16 @@ impl Error for ProgramCreationError {
                 "Point size is not supported by the backend.",
             BinaryHeaderError =>
                 "The glium-specific binary header was not found or is corrupt.",
+            DualSourceBlendingNotSupported =>
+                "Dual-source blending is not supported by the backend.",
+            SpirvNotSupported =>
+                "SPIR-V shaders are not supported by the backend.",
+            FragDataLocationNotSupported =>
+                "Binding fragment shader outputs to locations is not supported by the backend.",
+            LocationOutOfRange =>
+                "One of the attribute or fragment output locations is out of range.",
+            InvalidEntryPoint =>
+                "The entry point of a SPIR-V module contains a nul byte.",
         }
     }
 }
@@ -193,6 +237,60 @@ impl Error for GetBinaryError {
     }
 }
 
+/// Differences between the attributes of a program and a vertex format, as returned by
+/// `Program::verify_against`.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct MismatchReport {
+    /// Attributes of the program that are missing from the vertex format. Drawing would panic.
+    pub missing: Vec<String>,
+
+    /// Attributes of the vertex format that the program doesn't use.
+    ///
+    /// Note that the shader compiler removes the attributes that don't contribute to the
+    /// output, so an attribute can be in this list even if it is declared in the source code.
+    pub extra: Vec<String>,
+
+    /// Attributes whose number of components doesn't match, as `(name, type expected by the
+    /// program, type of the vertex format)`. Drawing would panic.
+    pub mismatched: Vec<(String, AttributeType, AttributeType)>,
+}
+
+impl MismatchReport {
+    /// Returns true if the vertex format and the program match.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+impl fmt::Display for MismatchReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(fmt, "{}", self.description()));
+
+        for name in self.missing.iter() {
+            try!(write!(fmt, "\n`{}` is missing from the vertex format", name));
+        }
+
+        for name in self.extra.iter() {
+            try!(write!(fmt, "\n`{}` is not used by the program", name));
+        }
+
+        for &(ref name, expected, obtained) in self.mismatched.iter() {
+            try!(write!(fmt, "\n`{}` has type {:?}, but the program expects {:?}", name,
+                        obtained, expected));
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for MismatchReport {
+    #[inline]
+    fn description(&self) -> &str {
+        "The vertex format doesn't match the attributes of the program"
+    }
+}
+
 /// Input when creating a program.
 pub enum ProgramCreationInput<'a> {
     /// Use GLSL source code.
@@ -239,7 +337,78 @@ pub enum ProgramCreationInput<'a> {
 
         /// Whether the shader uses point size.
         uses_point_size: bool,
-    }
+    },
+
+    /// Use SPIR-V binaries, for example compiled offline with glslang or shaderc.
+    ///
+    /// Requires OpenGL 4.6 or `GL_ARB_gl_spirv`. See `is_spirv_supported`.
+    ///
+    /// The names of the variables are optional in SPIR-V modules, and the reflection of the
+    /// attributes, uniforms and blocks of the program relies on them. Uniforms and attributes
+    /// whose names have been stripped, for example by an optimizer, are missing from the
+    /// reflection and can't be set by name. Their locations and bindings should be declared
+    /// explicitly in the shaders.
+    SpirV {
+        /// The vertex shader.
+        vertex_shader: SpirvEntryPoint<'a>,
+
+        /// The optional tessellation control shader.
+        tessellation_control_shader: Option<SpirvEntryPoint<'a>>,
+
+        /// The optional tessellation evaluation shader.
+        tessellation_evaluation_shader: Option<SpirvEntryPoint<'a>>,
+
+        /// The optional geometry shader.
+        geometry_shader: Option<SpirvEntryPoint<'a>>,
+
+        /// The fragment shader.
+        fragment_shader: SpirvEntryPoint<'a>,
+
+        /// See `SourceCode::outputs_srgb`.
+        outputs_srgb: bool,
+
+        /// Whether the shader uses point size.
+        uses_point_size: bool,
+    },
+
+    /// Use GLSL source code for a mesh shader pipeline, where the mesh shader generates the
+    /// primitives itself instead of reading vertex attributes.
+    ///
+    /// Requires `GL_NV_mesh_shader`, otherwise `ShaderTypeNotSupported` is returned. See
+    /// `is_mesh_shader_supported`. Programs built this way can only be drawn with
+    /// `Surface::draw_mesh_tasks`.
+    MeshShaders {
+        /// Source code of the optional task shader.
+        task_shader: Option<&'a str>,
+
+        /// Source code of the mesh shader.
+        mesh_shader: &'a str,
+
+        /// Source code of the fragment shader.
+        fragment_shader: &'a str,
+
+        /// See `SourceCode::outputs_srgb`.
+        outputs_srgb: bool,
+    },
+}
+
+/// A SPIR-V module and the entry point to use in it.
+#[derive(Debug, Copy, Clone)]
+pub struct SpirvEntryPoint<'a> {
+    /// The SPIR-V module.
+    pub binary: &'a [u8],
+
+    /// Name of the function to use as the entry point of the shader, usually `main`.
+    ///
+    /// Must not contain a nul byte, otherwise `ProgramCreationError::InvalidEntryPoint` is
+    /// returned.
+    pub entry_point: &'a str,
+
+    /// The values of the specialization constants, as a list of `(constant_id, value)`.
+    ///
+    /// The value is the binary representation of the constant, so you need to transmute floats
+    /// to `u32`. Constants that are not in this list keep their default value.
+    pub specialization_constants: &'a [(u32, u32)],
 }
 
 /// Represents the source code of a program.
@@ -279,6 +448,52 @@ impl<'a> From<SourceCode<'a>> for ProgramCreationInput<'a> {
     }
 }
 
+/// Options that are applied before linking a program built from GLSL source code.
+///
+/// Pass them to `Program::with_link_options`. `Program::new` uses the default options.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let vertex_source = ""; let fragment_source = "";
+/// let program = glium::Program::with_link_options(&display,
+///     glium::program::SourceCode {
+///         vertex_shader: vertex_source,
+///         tessellation_control_shader: None,
+///         tessellation_evaluation_shader: None,
+///         geometry_shader: None,
+///         fragment_shader: fragment_source,
+///     },
+///     glium::program::LinkOptions {
+///         dual_source_outputs: Some(("color", "blend_factor")),
+///         .. Default::default()
+///     });
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LinkOptions<'a> {
+    /// The names of the two outputs of the fragment shader to use for dual-source blending.
+    ///
+    /// The first output is bound to index 0 and the second output to index 1 of the first
+    /// color attachment, which allows the `SourceOne*` blending factors to read the second
+    /// output. Requires OpenGL 3.3 or `GL_ARB_blend_func_extended`.
+    pub dual_source_outputs: Option<(&'a str, &'a str)>,
+
+    /// Locations to bind vertex attributes to, as a list of `(name, location)`.
+    ///
+    /// This gives the same layout to the attributes of multiple programs, regardless of the
+    /// order in which they are declared. Locations must be inferior to
+    /// `GL_MAX_VERTEX_ATTRIBS`. Attributes that are not in the list, or that are not used by
+    /// the program, are ignored.
+    pub attribute_locations: &'a [(&'a str, u32)],
+
+    /// Color attachments to bind the outputs of the fragment shader to, as a list of
+    /// `(name, location)`.
+    ///
+    /// Locations must be inferior to `GL_MAX_DRAW_BUFFERS`. Requires OpenGL 3.0.
+    pub frag_data_locations: &'a [(&'a str, u32)],
+}
+
 /// Represents the compiled binary data of a program.
 pub struct Binary {
     /// An implementation-defined format.
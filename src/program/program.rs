@@ -1,12 +1,14 @@
 use gl;
 
 use context::CommandContext;
+use context::BindingPointsExhaustedError;
 use version::Version;
 use version::Api;
 
 use backend::Facade;
 use CapabilitiesSource;
 
+use std::cell::Cell;
 use std::fmt;
 use std::collections::hash_map::{self, HashMap};
 use std::hash::BuildHasherDefault;
@@ -14,37 +16,59 @@ use std::hash::BuildHasherDefault;
 use fnv::FnvHasher;
 
 use GlObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use ProgramExt;
 use Handle;
 use RawUniformValue;
 
 use program::{COMPILER_GLOBAL_LOCK, ProgramCreationInput, ProgramCreationError, Binary};
+use program::LinkOptions;
 use program::GetBinaryError;
+use program::MismatchReport;
+use program::is_spirv_supported;
 
 use program::reflection::{Uniform, UniformBlock, OutputPrimitives};
 use program::reflection::{Attribute, TransformFeedbackBuffer};
 use program::reflection::{SubroutineData, ShaderStage, SubroutineUniform};
-use program::shader::build_shader;
+use program::shader::{build_shader, build_spirv_shader, MESH_SHADER_NV, TASK_SHADER_NV};
 
 use program::raw::RawProgram;
 
-use vertex::VertexFormat;
+use vertex::{Vertex, VertexFormat};
 
 /// A combination of shaders linked together.
 pub struct Program {
     raw: RawProgram,
     outputs_srgb: bool,
     uses_point_size: bool,
+    early_fragment_tests: Option<bool>,
+    // true if a warning about the lack of early fragment tests has been inserted
+    warned_early_fragment_tests: Cell<bool>,
+    has_mesh_shader: bool,
 }
 
 impl Program {
     /// Builds a new program.
+    #[inline]
     pub fn new<'a, F: ?Sized, I>(facade: &F, input: I) -> Result<Program, ProgramCreationError>
                          where I: Into<ProgramCreationInput<'a>>, F: Facade
+    {
+        Program::with_link_options(facade, input, Default::default())
+    }
+
+    /// Builds a new program, and applies some options before linking it.
+    ///
+    /// The options are only used for programs built from GLSL source code. The locations of a
+    /// binary have been chosen when it was first linked, and SPIR-V modules must declare them
+    /// explicitly, so the options are ignored for these inputs.
+    pub fn with_link_options<'a, F: ?Sized, I>(facade: &F, input: I, options: LinkOptions<'a>)
+                                               -> Result<Program, ProgramCreationError>
+                                               where I: Into<ProgramCreationInput<'a>>, F: Facade
     {
         let input = input.into();
 
-        let (raw, outputs_srgb, uses_point_size) = match input {
+        let (raw, outputs_srgb, uses_point_size, early_fragment_tests,
+             has_mesh_shader) = match input {
             ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
                                                tessellation_evaluation_shader, geometry_shader,
                                                fragment_shader, transform_feedback_varyings,
@@ -86,6 +110,8 @@ impl Program {
                     return Err(ProgramCreationError::PointSizeNotSupported);
                 }
 
+                try!(check_link_options(facade, &options));
+
                 let _lock = COMPILER_GLOBAL_LOCK.lock();
 
                 let shaders_store = {
@@ -98,8 +124,12 @@ impl Program {
 
                 (try!(RawProgram::from_shaders(facade, &shaders_store, has_geometry_shader,
                                                has_tessellation_control_shader, has_tessellation_evaluation_shader,
-                                               transform_feedback_varyings)),
-                 outputs_srgb, uses_point_size)
+                                               transform_feedback_varyings,
+                                               options.dual_source_outputs,
+                                               options.attribute_locations,
+                                               options.frag_data_locations)),
+                 outputs_srgb, uses_point_size,
+                 Some(declares_early_fragment_tests(fragment_shader)), false)
             },
 
             ProgramCreationInput::Binary { data, outputs_srgb, uses_point_size } => {
@@ -107,13 +137,94 @@ impl Program {
                     return Err(ProgramCreationError::PointSizeNotSupported);
                 }
 
-                (try!(RawProgram::from_binary(facade, data)), outputs_srgb, uses_point_size)
+                (try!(RawProgram::from_binary(facade, data)), outputs_srgb, uses_point_size, None,
+                 false)
+            },
+
+            ProgramCreationInput::SpirV { vertex_shader, tessellation_control_shader,
+                                          tessellation_evaluation_shader, geometry_shader,
+                                          fragment_shader, outputs_srgb, uses_point_size } =>
+            {
+                if !is_spirv_supported(facade) {
+                    return Err(ProgramCreationError::SpirvNotSupported);
+                }
+
+                if uses_point_size && !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) {
+                    return Err(ProgramCreationError::PointSizeNotSupported);
+                }
+
+                let mut shaders = vec![
+                    (vertex_shader, gl::VERTEX_SHADER),
+                    (fragment_shader, gl::FRAGMENT_SHADER)
+                ];
+
+                if let Some(gs) = geometry_shader {
+                    shaders.push((gs, gl::GEOMETRY_SHADER));
+                }
+
+                if let Some(ts) = tessellation_control_shader {
+                    shaders.push((ts, gl::TESS_CONTROL_SHADER));
+                }
+
+                if let Some(ts) = tessellation_evaluation_shader {
+                    shaders.push((ts, gl::TESS_EVALUATION_SHADER));
+                }
+
+                let _lock = COMPILER_GLOBAL_LOCK.lock();
+
+                let shaders_store = {
+                    let mut shaders_store = Vec::new();
+                    for (spirv, ty) in shaders.into_iter() {
+                        shaders_store.push(try!(build_spirv_shader(facade, ty, &spirv)));
+                    }
+                    shaders_store
+                };
+
+                (try!(RawProgram::from_shaders(facade, &shaders_store, geometry_shader.is_some(),
+                                               tessellation_control_shader.is_some(),
+                                               tessellation_evaluation_shader.is_some(),
+                                               None, None, &[], &[])),
+                 outputs_srgb, uses_point_size, None, false)
+            },
+
+            ProgramCreationInput::MeshShaders { task_shader, mesh_shader, fragment_shader,
+                                                outputs_srgb } =>
+            {
+                let mut shaders = vec![
+                    (mesh_shader, MESH_SHADER_NV),
+                    (fragment_shader, gl::FRAGMENT_SHADER)
+                ];
+
+                if let Some(ts) = task_shader {
+                    shaders.push((ts, TASK_SHADER_NV));
+                }
+
+                try!(check_link_options(facade, &options));
+
+                let _lock = COMPILER_GLOBAL_LOCK.lock();
+
+                let shaders_store = {
+                    let mut shaders_store = Vec::new();
+                    for (src, ty) in shaders.into_iter() {
+                        shaders_store.push(try!(build_shader(facade, ty, src)));
+                    }
+                    shaders_store
+                };
+
+                (try!(RawProgram::from_shaders(facade, &shaders_store, false, false, false,
+                                               None, options.dual_source_outputs,
+                                               options.attribute_locations,
+                                               options.frag_data_locations)),
+                 outputs_srgb, false, Some(declares_early_fragment_tests(fragment_shader)), true)
             },
         };
         Ok(Program {
             raw: raw,
             outputs_srgb: outputs_srgb,
             uses_point_size: uses_point_size,
+            early_fragment_tests: early_fragment_tests,
+            warned_early_fragment_tests: Cell::new(false),
+            has_mesh_shader: has_mesh_shader,
         })
     }
 
@@ -153,6 +264,55 @@ impl Program {
         })
     }
 
+    /// Builds a program from an existing, externally created and linked OpenGL program.
+    ///
+    /// This is useful when mixing glium with code that performs raw OpenGL calls. The program
+    /// is introspected like any other program, and can then be used with `draw`.
+    ///
+    /// If `owned` is true, the program will be destroyed when the returned object is dropped.
+    /// Otherwise the program must be destroyed externally, but only after the returned object
+    /// has been dropped.
+    ///
+    /// Since glium doesn't know how the program was created, the program is considered to not
+    /// output sRGB and to not write `gl_PointSize`.
+    ///
+    /// # Safety
+    ///
+    /// The id must be the name of a valid program object in the share group of the context, and
+    /// the program must not be relinked or modified while it is being used by glium.
+    #[inline]
+    pub unsafe fn from_id<F: ?Sized>(facade: &F, id: Handle, owned: bool)
+                                     -> Result<Program, ProgramCreationError> where F: Facade
+    {
+        Ok(Program {
+            raw: try!(RawProgram::from_id(facade, id, owned)),
+            outputs_srgb: false,
+            uses_point_size: false,
+            early_fragment_tests: None,
+            warned_early_fragment_tests: Cell::new(false),
+            has_mesh_shader: false,
+        })
+    }
+
+    /// Returns the info log of the linker, if it isn't empty.
+    ///
+    /// The log is kept even if linking succeeded, in which case it may contain warnings
+    /// emitted by the driver, for example about performance issues or unused variables.
+    /// Its content is implementation-specific.
+    #[inline]
+    pub fn info_log(&self) -> Option<&str> {
+        self.raw.get_info_log()
+    }
+
+    /// Returns the info log of the compiler for the given stage, if it isn't empty.
+    ///
+    /// Returns `None` if the program doesn't have a shader for this stage, or if it was created
+    /// from a binary or from an existing OpenGL program.
+    #[inline]
+    pub fn shader_info_log(&self, stage: ShaderStage) -> Option<&str> {
+        self.raw.get_shader_info_log(stage.to_gl_enum())
+    }
+
     /// Returns the program's compiled binary.
     ///
     /// You can store the result in a file, then reload it later. This avoids having to compile
@@ -178,7 +338,33 @@ impl Program {
         self.raw.get_frag_data_location(name)
     }
 
+    /// Returns the *index* of an output fragment, if it exists.
+    ///
+    /// Together with the location, the index tells which input of the blending equation the
+    /// output is. It is `0` for regular outputs, and `1` for the second color of dual-source
+    /// blending, which is declared with `LinkOptions::dual_source_outputs` or with
+    /// `layout(location = 0, index = 1)`.
+    ///
+    /// Returns `None` if the output doesn't exist or if dual-source blending is not supported
+    /// by the backend.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// if program.get_frag_data_index("blend_factor") != Some(1) {
+    ///     println!("The program can't be used with dual-source blending");
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_frag_data_index(&self, name: &str) -> Option<u32> {
+        self.raw.get_frag_data_index(name)
+    }
+
     /// Returns informations about a uniform variable, if it exists.
+    ///
+    /// The locations of all the active uniforms are queried once when the program is linked.
+    /// Calling this function doesn't involve any OpenGL call.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.raw.get_uniform(name)
@@ -215,6 +401,30 @@ impl Program {
         self.raw.get_uniform_blocks()
     }
 
+    /// Binds each uniform block of the program to the binding point that the context's
+    /// `BindingPointRegistry` assigns to its name. Call this once after creating the program.
+    ///
+    /// Combined with `Buffer::bind_to_shared_uniform_block`, this allows you to share a uniform
+    /// block between all the programs without passing it as a uniform to each draw call.
+    ///
+    /// Passing a block as a uniform to a draw call changes the binding point of that block in
+    /// this program. Call this function again if you want to go back to the shared buffer.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// # let camera: glium::uniforms::UniformBuffer<[[f32; 4]; 4]> = unsafe { std::mem::uninitialized() };
+    /// program.bind_shared_uniform_blocks().unwrap();
+    ///
+    /// // once per frame
+    /// camera.bind_to_shared_uniform_block("Camera").unwrap();
+    /// ```
+    #[inline]
+    pub fn bind_shared_uniform_blocks(&self) -> Result<(), BindingPointsExhaustedError> {
+        self.raw.bind_shared_uniform_blocks()
+    }
+
     /// Returns the list of transform feedback varyings.
     #[inline]
     pub fn get_transform_feedback_buffers(&self) -> &[TransformFeedbackBuffer] {
@@ -285,6 +495,64 @@ impl Program {
         self.raw.attributes()
     }
 
+    /// Checks the attributes of the program against the format of a vertex type.
+    ///
+    /// Call this once after creating the program in order to detect the mistakes in the
+    /// definition of the vertex type, or in the source code of the vertex shader, early. The
+    /// attributes are compared by name, and their types must have the same number of
+    /// components.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # #[macro_use] extern crate glium;
+    /// # fn main() {
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// #[derive(Copy, Clone)]
+    /// struct Vertex {
+    ///     position: [f32; 2],
+    /// }
+    ///
+    /// implement_vertex!(Vertex, position);
+    ///
+    /// if let Err(report) = program.verify_against::<Vertex>() {
+    ///     println!("{}", report);
+    /// }
+    /// # }
+    /// ```
+    pub fn verify_against<V>(&self) -> Result<(), MismatchReport> where V: Vertex {
+        let bindings = V::build_bindings();
+        let mut report = MismatchReport::default();
+
+        for &(ref name, _, ty, _) in bindings.iter() {
+            match self.get_attribute(name) {
+                Some(attribute) => {
+                    if ty.get_num_components() != attribute.ty.get_num_components() ||
+                       attribute.size != 1
+                    {
+                        report.mismatched.push((name.to_string(), attribute.ty, ty));
+                    }
+                },
+                None => report.extra.push(name.to_string()),
+            }
+        }
+
+        for (name, _) in self.attributes() {
+            if !bindings.iter().any(|&(ref n, _, _, _)| n == name) {
+                report.missing.push(name.clone());
+            }
+        }
+
+        // the attributes of the program are stored in a hashmap
+        report.missing.sort();
+
+        if report.is_empty() {
+            Ok(())
+        } else {
+            Err(report)
+        }
+    }
+
     /// Returns true if the program has been configured to output sRGB instead of RGB.
     #[inline]
     pub fn has_srgb_output(&self) -> bool {
@@ -333,6 +601,33 @@ impl Program {
     pub fn uses_point_size(&self) -> bool {
       self.uses_point_size
     }
+
+    /// Returns true if the fragment shader declares `layout(early_fragment_tests) in;`.
+    ///
+    /// Without this qualifier, the implementation is allowed to perform the depth and stencil
+    /// tests after the fragment shader has been executed. The side effects of the shader, like
+    /// image stores or writes to shader storage buffers, then also happen for the fragments that
+    /// fail the depth test. There is no way to force early fragment tests with a draw parameter,
+    /// the qualifier has to be in the shader.
+    ///
+    /// In debug mode, drawing with a depth test and a program that uses images or shader storage
+    /// buffers without this qualifier inserts a warning in the debug output.
+    ///
+    /// This is only known for programs built from GLSL source code, and `None` is returned
+    /// otherwise.
+    #[inline]
+    pub fn has_early_fragment_tests(&self) -> Option<bool> {
+        self.early_fragment_tests
+    }
+
+    /// Returns true if the program has been built with `ProgramCreationInput::MeshShaders`.
+    ///
+    /// Such a program can only be drawn with `Surface::draw_mesh_tasks`, and can't be drawn
+    /// with vertex buffers.
+    #[inline]
+    pub fn has_mesh_shader(&self) -> bool {
+        self.has_mesh_shader
+    }
 }
 
 impl fmt::Debug for Program {
@@ -342,6 +637,13 @@ impl fmt::Debug for Program {
     }
 }
 
+impl Labeled for Program {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        self.raw.label(label)
+    }
+}
+
 impl GlObject for Program {
     type Id = Handle;
 
@@ -354,10 +656,13 @@ impl GlObject for Program {
 impl ProgramExt for Program {
     fn use_program(&self, ctxt: &mut CommandContext) {
         // compatibility was checked at program creation
+        // `glPointSize` is ignored while `GL_PROGRAM_POINT_SIZE` is enabled
         if self.uses_point_size && !ctxt.state.enabled_program_point_size {
             unsafe { ctxt.gl.Enable(gl::PROGRAM_POINT_SIZE); }
+            ctxt.state.enabled_program_point_size = true;
         } else if !self.uses_point_size && ctxt.state.enabled_program_point_size {
             unsafe { ctxt.gl.Disable(gl::PROGRAM_POINT_SIZE); }
+            ctxt.state.enabled_program_point_size = false;
         }
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
@@ -427,4 +732,97 @@ impl ProgramExt for Program {
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
     }
+
+    #[inline]
+    fn take_early_fragment_tests_warning(&self) -> bool {
+        let warned = self.warned_early_fragment_tests.get();
+        self.warned_early_fragment_tests.set(true);
+        !warned
+    }
+}
+
+/// Returns true if the source code of a fragment shader contains the
+/// `layout(early_fragment_tests) in;` declaration, ignoring comments.
+fn declares_early_fragment_tests(source: &str) -> bool {
+    // splitting the source code into identifiers and punctuation
+    let mut tokens: Vec<String> = Vec::new();
+    let mut in_identifier = false;
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().cloned()) {
+            ('/', Some('/')) => {
+                while let Some(c) = chars.next() {
+                    if c == '\n' { break; }
+                }
+                in_identifier = false;
+            },
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                while let Some(c) = chars.next() {
+                    if previous == '*' && c == '/' { break; }
+                    previous = c;
+                }
+                in_identifier = false;
+            },
+            (c, _) if c.is_alphanumeric() || c == '_' => {
+                if !in_identifier {
+                    tokens.push(String::new());
+                    in_identifier = true;
+                }
+                tokens.last_mut().unwrap().push(c);
+            },
+            (c, _) if c.is_whitespace() => in_identifier = false,
+            (c, _) => {
+                tokens.push(c.to_string());
+                in_identifier = false;
+            },
+        }
+    }
+
+    tokens.iter().enumerate().any(|(num, token)| {
+        if token != "layout" || tokens.get(num + 1).map(|t| &t[..]) != Some("(") {
+            return false;
+        }
+
+        let end = match tokens[num + 2 ..].iter().position(|t| t == ")") {
+            Some(p) => num + 2 + p,
+            None => return false,
+        };
+
+        tokens[num + 2 .. end].iter().any(|t| t == "early_fragment_tests") &&
+        tokens.get(end + 1).map(|t| &t[..]) == Some("in") &&
+        tokens.get(end + 2).map(|t| &t[..]) == Some(";")
+    })
+}
+
+/// Checks that the backend supports the link options.
+fn check_link_options<F: ?Sized>(facade: &F, options: &LinkOptions)
+                                 -> Result<(), ProgramCreationError> where F: Facade
+{
+    if options.dual_source_outputs.is_some() &&
+        !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 3)) &&
+        !facade.get_context().get_extensions().gl_arb_blend_func_extended
+    {
+        return Err(ProgramCreationError::DualSourceBlendingNotSupported);
+    }
+
+    if !options.frag_data_locations.is_empty() &&
+        !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0))
+    {
+        return Err(ProgramCreationError::FragDataLocationNotSupported);
+    }
+
+    let capabilities = facade.get_context().get_capabilities();
+
+    if options.attribute_locations.iter().any(|&(_, loc)| {
+        loc as gl::types::GLint >= capabilities.max_vertex_attribs
+    }) || options.frag_data_locations.iter().any(|&(_, loc)| {
+        loc as gl::types::GLint >= capabilities.max_draw_buffers
+    }) {
+        return Err(ProgramCreationError::LocationOutOfRange);
+    }
+
+    Ok(())
 }
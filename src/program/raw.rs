@@ -1,11 +1,15 @@
 use gl;
 
 use context::CommandContext;
+use context::ResourceKind;
+use context::LabeledObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use version::Version;
 use version::Api;
 
 use backend::Facade;
 use context::Context;
+use context::BindingPointsExhaustedError;
 use ContextExt;
 use UniformsExt;
 
@@ -66,6 +70,10 @@ pub struct RawProgram {
     has_geometry_shader: bool,
     has_tessellation_control_shader: bool,
     has_tessellation_evaluation_shader: bool,
+    info_log: Option<String>,
+    shaders_info_logs: Vec<(gl::types::GLenum, String)>,
+    /// If false, the program was created externally and will not be deleted on drop.
+    owned: bool,
 }
 
 impl RawProgram {
@@ -74,15 +82,19 @@ impl RawProgram {
     pub fn from_shaders<'a, F: ?Sized, I>(facade: &'a F, shaders: I, has_geometry_shader: bool,
                                   has_tessellation_control_shader: bool,
                                   has_tessellation_evaluation_shader: bool,
-                                  transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>)
+                                  transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>,
+                                  dual_source_outputs: Option<(&str, &str)>,
+                                  attribute_locations: &[(&str, u32)],
+                                  frag_data_locations: &[(&str, u32)])
                                   -> Result<RawProgram, ProgramCreationError>
                                   where F: Facade, I: IntoIterator<Item = &'a Shader>
     {
         let mut ctxt = facade.get_context().make_current();
 
-        let shaders_ids = shaders.into_iter().map(|s| s.get_id()).collect::<Vec<_>>();
+        let shaders = shaders.into_iter().collect::<Vec<_>>();
+        let shaders_ids = shaders.iter().map(|s| s.get_id()).collect::<Vec<_>>();
 
-        let id = unsafe {
+        let (id, info_log) = unsafe {
             let id = create_program(&mut ctxt);
 
             // attaching shaders
@@ -138,6 +150,46 @@ impl RawProgram {
                 }
             }
 
+            // dual-source blending outputs
+            if let Some((first, second)) = dual_source_outputs {
+                let id = match id {
+                    Handle::Id(id) => id,
+                    Handle::Handle(_) => unreachable!()     // checked in the frontend
+                };
+
+                let first = ffi::CString::new(first.as_bytes()).unwrap();
+                let second = ffi::CString::new(second.as_bytes()).unwrap();
+
+                ctxt.gl.BindFragDataLocationIndexed(id, 0, 0, first.as_ptr());
+                ctxt.gl.BindFragDataLocationIndexed(id, 0, 1, second.as_ptr());
+            }
+
+            // explicit attribute locations
+            for &(name, location) in attribute_locations {
+                let name = ffi::CString::new(name.as_bytes()).unwrap();
+
+                match id {
+                    Handle::Id(id) => {
+                        ctxt.gl.BindAttribLocation(id, location, name.as_ptr());
+                    },
+                    Handle::Handle(id) => {
+                        assert!(ctxt.extensions.gl_arb_vertex_shader);
+                        ctxt.gl.BindAttribLocationARB(id, location, name.as_ptr());
+                    }
+                }
+            }
+
+            // explicit fragment outputs locations
+            for &(name, location) in frag_data_locations {
+                let id = match id {
+                    Handle::Id(id) => id,
+                    Handle::Handle(_) => unreachable!()     // checked in the frontend
+                };
+
+                let name = ffi::CString::new(name.as_bytes()).unwrap();
+                ctxt.gl.BindFragDataLocation(id, location, name.as_ptr());
+            }
+
             // linking
             {
                 ctxt.report_debug_output_errors.set(false);
@@ -158,11 +210,15 @@ impl RawProgram {
             }
 
             // checking for errors
-            try!(check_program_link_errors(&mut ctxt, id));
+            let info_log = try!(check_program_link_errors(&mut ctxt, id));
 
-            id
+            (id, info_log)
         };
 
+        let shaders_info_logs = shaders.iter().filter_map(|s| {
+            s.get_info_log().map(|log| (s.get_type(), log.to_owned()))
+        }).collect();
+
         let uniforms = unsafe { reflect_uniforms(&mut ctxt, id) };
         let attributes = unsafe { reflect_attributes(&mut ctxt, id) };
         let blocks = unsafe { reflect_uniform_blocks(&mut ctxt, id) };
@@ -197,6 +253,9 @@ impl RawProgram {
             has_geometry_shader: has_geometry_shader,
             has_tessellation_control_shader: has_tessellation_control_shader,
             has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
+            info_log: info_log,
+            shaders_info_logs: shaders_info_logs,
+            owned: true,
         })
     }
 
@@ -213,7 +272,7 @@ impl RawProgram {
 
         let mut ctxt = facade.get_context().make_current();
 
-        let id = unsafe {
+        let (id, info_log) = unsafe {
             let id = create_program(&mut ctxt);
 
             match id {
@@ -227,9 +286,9 @@ impl RawProgram {
             };
 
             // checking for errors
-            try!(check_program_link_errors(&mut ctxt, id));
+            let info_log = try!(check_program_link_errors(&mut ctxt, id));
 
-            id
+            (id, info_log)
         };
 
         let (uniforms, attributes, blocks, tf_buffers, ssbos, subroutine_data) = unsafe {
@@ -268,9 +327,107 @@ impl RawProgram {
             has_geometry_shader: has_geometry_shader,
             has_tessellation_control_shader: has_tessellation_control_shader,
             has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
+            info_log: info_log,
+            shaders_info_logs: Vec::new(),
+            owned: true,
+        })
+    }
+
+    /// Builds a program from an existing, externally created and linked OpenGL program.
+    ///
+    /// If `owned` is true, the program will be destroyed when the returned object is dropped.
+    /// Otherwise the program must be destroyed externally, but only after the returned object
+    /// has been dropped.
+    ///
+    /// # Safety
+    ///
+    /// The id must be the name of a valid program object in the share group of the context, and
+    /// the program must not be relinked or modified while it is being used by glium.
+    pub unsafe fn from_id<F: ?Sized>(facade: &F, id: Handle, owned: bool)
+                                     -> Result<RawProgram, ProgramCreationError> where F: Facade
+    {
+        let mut ctxt = facade.get_context().make_current();
+
+        let info_log = try!(check_program_link_errors(&mut ctxt, id));
+
+        let mut has_geometry_shader = false;
+        let mut has_tessellation_control_shader = false;
+        let mut has_tessellation_evaluation_shader = false;
+
+        // `glGetAttachedObjectsARB` is not loaded, so programs created with
+        // `GL_ARB_shader_objects` are assumed to only have vertex and fragment shaders
+        if let Handle::Id(id) = id {
+            let mut num_shaders = 0;
+            ctxt.gl.GetProgramiv(id, gl::ATTACHED_SHADERS, &mut num_shaders);
+
+            let mut shaders: Vec<gl::types::GLuint> = Vec::with_capacity(num_shaders as usize);
+            let mut count = 0;
+            ctxt.gl.GetAttachedShaders(id, num_shaders, &mut count, shaders.as_mut_ptr());
+            shaders.set_len(count as usize);
+
+            for shader in shaders {
+                let mut ty = 0;
+                ctxt.gl.GetShaderiv(shader, gl::SHADER_TYPE, &mut ty);
+                match ty as gl::types::GLenum {
+                    gl::GEOMETRY_SHADER => has_geometry_shader = true,
+                    gl::TESS_CONTROL_SHADER => has_tessellation_control_shader = true,
+                    gl::TESS_EVALUATION_SHADER => has_tessellation_evaluation_shader = true,
+                    _ => ()
+                }
+            }
+        }
+
+        let uniforms = reflect_uniforms(&mut ctxt, id);
+        let attributes = reflect_attributes(&mut ctxt, id);
+        let blocks = reflect_uniform_blocks(&mut ctxt, id);
+        let tf_buffers = reflect_transform_feedback(&mut ctxt, id);
+        let ssbos = reflect_shader_storage_blocks(&mut ctxt, id);
+        let subroutine_data = reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
+                                                      has_tessellation_control_shader,
+                                                      has_tessellation_evaluation_shader);
+
+        let output_primitives = if has_geometry_shader {
+            Some(reflect_geometry_output_type(&mut ctxt, id))
+        } else if has_tessellation_evaluation_shader {
+            Some(reflect_tess_eval_output_type(&mut ctxt, id))
+        } else {
+            None
+        };
+
+        Ok(RawProgram {
+            context: facade.get_context().clone(),
+            id: id,
+            uniforms: uniforms,
+            uniform_values: UniformsStorage::new(),
+            uniform_blocks: blocks,
+            subroutine_data: subroutine_data,
+            attributes: attributes,
+            frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
+            tf_buffers: tf_buffers,
+            ssbos: ssbos,
+            output_primitives: output_primitives,
+            has_geometry_shader: has_geometry_shader,
+            has_tessellation_control_shader: has_tessellation_control_shader,
+            has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
+            info_log: info_log,
+            shaders_info_logs: Vec::new(),
+            owned: owned,
         })
     }
 
+    /// Returns the info log of the linker, if it isn't empty.
+    #[inline]
+    pub fn get_info_log(&self) -> Option<&str> {
+        self.info_log.as_ref().map(|l| &l[..])
+    }
+
+    /// Returns the info log of the compiler for the shader of the given type, if it isn't
+    /// empty.
+    #[inline]
+    pub fn get_shader_info_log(&self, ty: gl::types::GLenum) -> Option<&str> {
+        self.shaders_info_logs.iter().find(|&&(t, _)| t == ty).map(|&(_, ref l)| &l[..])
+    }
+
     /// Returns the program's compiled binary.
     ///
     /// You can store the result in a file, then reload it later. This avoids having to compile
@@ -358,7 +515,45 @@ impl RawProgram {
         location
     }
 
+    /// Returns the *index* of an output fragment, if it exists.
+    ///
+    /// The index is `0` for regular outputs and `1` for the second output of dual-source
+    /// blending. Returns `None` if the output doesn't exist or if dual-source blending is not
+    /// supported by the backend.
+    pub fn get_frag_data_index(&self, name: &str) -> Option<u32> {
+        let name_c = ffi::CString::new(name.as_bytes()).unwrap();
+
+        let ctxt = self.context.make_current();
+
+        if !(ctxt.version >= &Version(Api::Gl, 3, 3)) &&
+           !ctxt.extensions.gl_arb_blend_func_extended
+        {
+            return None;
+        }
+
+        let value = unsafe {
+            match self.id {
+                Handle::Id(id) => {
+                    ctxt.gl.GetFragDataIndex(id, name_c.as_bytes_with_nul().as_ptr()
+                                             as *const raw::c_char)
+                },
+                Handle::Handle(_) => {
+                    // not supported
+                    -1
+                }
+            }
+        };
+
+        match value {
+            -1 => None,
+            a => Some(a as u32),
+        }
+    }
+
     /// Returns informations about a uniform variable, if it exists.
+    ///
+    /// The locations of all the active uniforms are queried once when the program is linked.
+    /// Calling this function doesn't involve any OpenGL call.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.uniforms.get(name)
@@ -395,6 +590,24 @@ impl RawProgram {
         &self.uniform_blocks
     }
 
+    /// Binds each uniform block of the program to the binding point that the context's
+    /// `BindingPointRegistry` assigns to its name.
+    ///
+    /// See `Program::bind_shared_uniform_blocks`.
+    pub fn bind_shared_uniform_blocks(&self) -> Result<(), BindingPointsExhaustedError> {
+        let mut ctxt = self.context.make_current();
+        let registry = self.context.get_binding_point_registry();
+
+        for (name, block) in self.uniform_blocks.iter() {
+            let point = try!(registry.get_binding_point(name));
+            self.use_program(&mut ctxt);
+            self.uniform_values.set_uniform_block_binding(&mut ctxt, self.id,
+                                                          block.id as gl::types::GLuint, point);
+        }
+
+        Ok(())
+    }
+
     /// Returns the list of transform feedback varyings.
     #[inline]
     pub fn get_transform_feedback_buffers(&self) -> &[TransformFeedbackBuffer] {
@@ -506,6 +719,34 @@ impl RawProgram {
         &self.subroutine_data
     }
 
+    /// Assumes that the program contains a compute shader and returns the local work group
+    /// size that it declares with `layout(local_size_x = ...)`.
+    ///
+    /// Returns `None` if the backend doesn't support compute shaders.
+    ///
+    /// # Safety
+    ///
+    /// The program *must* contain a compute shader.
+    pub unsafe fn get_compute_work_group_size(&self) -> Option<(u32, u32, u32)> {
+        let ctxt = self.context.make_current();
+
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3) ||
+             ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+             ctxt.extensions.gl_arb_compute_shader)
+        {
+            return None;
+        }
+
+        let id = match self.id {
+            Handle::Id(id) => id,
+            Handle::Handle(_) => unreachable!()
+        };
+
+        let mut size: [gl::types::GLint; 3] = mem::uninitialized();
+        ctxt.gl.GetProgramiv(id, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr());
+        Some((size[0] as u32, size[1] as u32, size[2] as u32))
+    }
+
     /// Assumes that the program contains a compute shader and executes it.
     ///
     /// # Safety
@@ -567,13 +808,14 @@ impl RawProgram {
         // an error is generated if the offset is not a multiple of 4
         assert!(offset % 4 == 0);
 
+        let mut fences = Vec::with_capacity(0);
+
+        // the fence must be inserted after the command that reads the buffer
         if let Some(fence) = buffer.add_fence() {
-            fence.insert(&mut ctxt);
+            fences.push(fence);
         }
 
         self.use_program(&mut ctxt);
-
-        let mut fences = Vec::with_capacity(0);
         try!(uniforms.bind_uniforms(&mut ctxt, self, &mut fences));
 
         ctxt.gl.DispatchComputeIndirect(offset as gl::types::GLintptr);
@@ -593,6 +835,20 @@ impl fmt::Debug for RawProgram {
     }
 }
 
+impl Labeled for RawProgram {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let id = match self.id {
+            Handle::Id(id) => id,
+            // `GL_ARB_shader_objects` programs can't be named
+            Handle::Handle(_) => return Err(LabelingNotSupportedError),
+        };
+
+        let ctxt = self.context.make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::PROGRAM, id), label)
+    }
+}
+
 impl GlObject for RawProgram {
     type Id = Handle;
 
@@ -688,7 +944,10 @@ impl Drop for RawProgram {
                         ctxt.state.program = Handle::Id(0);
                     }
 
-                    ctxt.gl.DeleteProgram(id);
+                    if self.owned {
+                        ctxt.resource_destroyed(ResourceKind::Program, id);
+                        ctxt.gl.DeleteProgram(id);
+                    }
                 },
                 Handle::Handle(id) => {
                     assert!(ctxt.extensions.gl_arb_shader_objects);
@@ -698,7 +957,9 @@ impl Drop for RawProgram {
                         ctxt.state.program = Handle::Handle(0 as gl::types::GLhandleARB);
                     }
 
-                    ctxt.gl.DeleteObjectARB(id);
+                    if self.owned {
+                        ctxt.gl.DeleteObjectARB(id);
+                    }
                 }
             }
         }
@@ -721,11 +982,20 @@ unsafe fn create_program(ctxt: &mut CommandContext) -> Handle {
         panic!("glCreateProgram failed");
     }
 
+    // programs created through `GL_ARB_shader_objects` don't have a `GLuint` id
+    if let Handle::Id(id) = id {
+        ctxt.resource_created(ResourceKind::Program, id);
+    }
+
     id
 }
 
+/// Checks whether the program has been successfully linked.
+///
+/// Returns the info log of the program if it isn't empty, which may contain warnings even
+/// when linking succeeded.
 unsafe fn check_program_link_errors(ctxt: &mut CommandContext, id: Handle)
-                                    -> Result<(), ProgramCreationError>
+                                    -> Result<Option<String>, ProgramCreationError>
 {
     let mut link_success: gl::types::GLint = mem::uninitialized();
 
@@ -761,40 +1031,55 @@ unsafe fn check_program_link_errors(ctxt: &mut CommandContext, id: Handle)
             }
         };
 
-        let mut error_log_size: gl::types::GLint = mem::uninitialized();
+        let msg = String::from_utf8(get_program_info_log(ctxt, id)).unwrap();
+        return Err(LinkingError(msg));
+    }
 
-        match id {
-            Handle::Id(id) => {
-                assert!(ctxt.version >= &Version(Api::Gl, 2, 0));
-                ctxt.gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
-            },
-            Handle::Handle(id) => {
-                assert!(ctxt.extensions.gl_arb_shader_objects);
-                ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
-                                                &mut error_log_size);
-            }
-        }
+    let log = String::from_utf8_lossy(&get_program_info_log(ctxt, id)).into_owned();
+    if log.trim().is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(log))
+    }
+}
 
-        let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
+/// Reads the info log of a program.
+unsafe fn get_program_info_log(ctxt: &mut CommandContext, id: Handle) -> Vec<u8> {
+    let mut error_log_size: gl::types::GLint = mem::uninitialized();
 
-        match id {
-            Handle::Id(id) => {
-                assert!(ctxt.version >= &Version(Api::Gl, 2, 0));
-                ctxt.gl.GetProgramInfoLog(id, error_log_size, &mut error_log_size,
-                                          error_log.as_mut_ptr() as *mut gl::types::GLchar);
-            },
-            Handle::Handle(id) => {
-                assert!(ctxt.extensions.gl_arb_shader_objects);
-                ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
-                                      error_log.as_mut_ptr() as *mut gl::types::GLchar);
-            }
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
+                                            &mut error_log_size);
         }
+    }
 
-        error_log.set_len(error_log_size as usize);
+    if error_log_size <= 0 {
+        return Vec::new();
+    }
 
-        let msg = String::from_utf8(error_log).unwrap();
-        return Err(LinkingError(msg));
+    let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
+
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetProgramInfoLog(id, error_log_size, &mut error_log_size,
+                                      error_log.as_mut_ptr() as *mut gl::types::GLchar);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
+                                  error_log.as_mut_ptr() as *mut gl::types::GLchar);
+        }
     }
 
-    Ok(())
+    error_log.set_len(error_log_size as usize);
+    error_log
 }
@@ -264,7 +264,9 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
         }
 
         // We've got an array, first get the base of the name
-        let name_base = uniform.0.split('[').next().unwrap();
+        // (only the last subscript is removed, so that arrays inside of arrays of structs like
+        // `lights[2].factors[0]` keep their prefix)
+        let name_base = &uniform.0[.. uniform.0.len() - 3];
         let uniform_base = uniform.1;
 
         // Go over all the elements in the array
@@ -982,6 +984,7 @@ fn glenum_to_uniform_type(ty: gl::types::GLenum) -> UniformType {
         gl::IMAGE_2D_ARRAY => UniformType::Image2dArray,
         gl::IMAGE_2D_MULTISAMPLE => UniformType::Image2dMultisample,
         gl::IMAGE_2D_MULTISAMPLE_ARRAY => UniformType::Image2dMultisampleArray,
+        gl::IMAGE_CUBE_MAP_ARRAY => UniformType::ImageCubeArray,
         gl::INT_IMAGE_1D => UniformType::IImage1d,
         gl::INT_IMAGE_2D => UniformType::IImage2d,
         gl::INT_IMAGE_3D => UniformType::IImage3d,
@@ -992,6 +995,7 @@ fn glenum_to_uniform_type(ty: gl::types::GLenum) -> UniformType {
         gl::INT_IMAGE_2D_ARRAY => UniformType::IImage2dArray,
         gl::INT_IMAGE_2D_MULTISAMPLE => UniformType::IImage2dMultisample,
         gl::INT_IMAGE_2D_MULTISAMPLE_ARRAY => UniformType::IImage2dMultisampleArray,
+        gl::INT_IMAGE_CUBE_MAP_ARRAY => UniformType::IImageCubeArray,
         gl::UNSIGNED_INT_IMAGE_1D => UniformType::UImage1d,
         gl::UNSIGNED_INT_IMAGE_2D => UniformType::UImage2d,
         gl::UNSIGNED_INT_IMAGE_3D => UniformType::UImage3d,
@@ -1002,6 +1006,7 @@ fn glenum_to_uniform_type(ty: gl::types::GLenum) -> UniformType {
         gl::UNSIGNED_INT_IMAGE_2D_ARRAY => UniformType::UImage2dArray,
         gl::UNSIGNED_INT_IMAGE_2D_MULTISAMPLE => UniformType::UImage2dMultisample,
         gl::UNSIGNED_INT_IMAGE_2D_MULTISAMPLE_ARRAY => UniformType::UImage2dMultisampleArray,
+        gl::UNSIGNED_INT_IMAGE_CUBE_MAP_ARRAY => UniformType::UImageCubeArray,
         gl::UNSIGNED_INT_ATOMIC_COUNTER => UniformType::AtomicCounterUint,
         v => panic!("Unknown value returned by OpenGL uniform type: {}", v)
     }
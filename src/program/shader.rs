@@ -5,6 +5,7 @@ use version::Api;
 use CapabilitiesSource;
 use backend::Facade;
 use context::Context;
+use context::CommandContext;
 use ContextExt;
 
 use std::{ffi, mem, ptr};
@@ -13,12 +14,36 @@ use std::rc::Rc;
 use GlObject;
 use Handle;
 
-use program::ProgramCreationError;
+use program::{ProgramCreationError, SpirvEntryPoint};
+
+/// Value of `GL_MESH_SHADER_NV`, which is missing from the generated bindings.
+pub const MESH_SHADER_NV: gl::types::GLenum = 0x9559;
+
+/// Value of `GL_TASK_SHADER_NV`, which is missing from the generated bindings.
+pub const TASK_SHADER_NV: gl::types::GLenum = 0x955A;
 
 /// A single, compiled but unlinked, shader.
 pub struct Shader {
     context: Rc<Context>,
     id: Handle,
+    ty: gl::types::GLenum,
+    info_log: Option<String>,
+}
+
+impl Shader {
+    /// Returns the type of the shader, for example `GL_VERTEX_SHADER`.
+    #[inline]
+    pub fn get_type(&self) -> gl::types::GLenum {
+        self.ty
+    }
+
+    /// Returns the info log of the compiler, if it isn't empty.
+    ///
+    /// The compiler can emit warnings even if the compilation succeeded.
+    #[inline]
+    pub fn get_info_log(&self) -> Option<&str> {
+        self.info_log.as_ref().map(|l| &l[..])
+    }
 }
 
 impl GlObject for Shader {
@@ -131,47 +156,18 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
         };
 
         if compilation_success == 1 {
+            let log = String::from_utf8_lossy(&get_shader_info_log(&mut ctxt, id)).into_owned();
+
             Ok(Shader {
                 context: facade.get_context().clone(),
-                id: id
+                id: id,
+                ty: shader_type,
+                info_log: if log.trim().is_empty() { None } else { Some(log) },
             })
 
         } else {
             // compilation error
-            let mut error_log_size: gl::types::GLint = mem::uninitialized();
-
-            match id {
-                Handle::Id(id) => {
-                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                            ctxt.version >= &Version(Api::GlEs, 2, 0));
-                    ctxt.gl.GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
-                },
-                Handle::Handle(id) => {
-                    assert!(ctxt.extensions.gl_arb_shader_objects);
-                    ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
-                                                    &mut error_log_size);
-                }
-            }
-
-            let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
-
-            match id {
-                Handle::Id(id) => {
-                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                            ctxt.version >= &Version(Api::GlEs, 2, 0));
-                    ctxt.gl.GetShaderInfoLog(id, error_log_size, &mut error_log_size,
-                                             error_log.as_mut_ptr() as *mut gl::types::GLchar);
-                },
-                Handle::Handle(id) => {
-                    assert!(ctxt.extensions.gl_arb_shader_objects);
-                    ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
-                                          error_log.as_mut_ptr() as *mut gl::types::GLchar);
-                }
-            }
-
-            error_log.set_len(error_log_size as usize);
-
-            match String::from_utf8(error_log) {
+            match String::from_utf8(get_shader_info_log(&mut ctxt, id)) {
                 Ok(msg) => Err(ProgramCreationError::CompilationError(msg)),
                 Err(_) => Err(
                     ProgramCreationError::CompilationError("Could not convert the log \
@@ -182,6 +178,114 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
     }
 }
 
+/// Builds an individual shader from a SPIR-V module.
+pub fn build_spirv_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum,
+                                     spirv: &SpirvEntryPoint)
+                                     -> Result<Shader, ProgramCreationError> where F: Facade
+{
+    unsafe {
+        let mut ctxt = facade.get_context().make_current();
+
+        let specialize_shader = match ctxt.specialize_shader {
+            Some(f) => f,
+            None => return Err(ProgramCreationError::SpirvNotSupported),
+        };
+
+        if !check_shader_type_compatibility(&mut ctxt, shader_type) {
+            return Err(ProgramCreationError::ShaderTypeNotSupported);
+        }
+
+        let entry_point = match ffi::CString::new(spirv.entry_point.as_bytes()) {
+            Ok(s) => s,
+            Err(_) => return Err(ProgramCreationError::InvalidEntryPoint),
+        };
+
+        let id = ctxt.gl.CreateShader(shader_type);
+        if id == 0 {
+            return Err(ProgramCreationError::ShaderTypeNotSupported);
+        }
+
+        ctxt.gl.ShaderBinary(1, &id, gl::SHADER_BINARY_FORMAT_SPIR_V_ARB,
+                             spirv.binary.as_ptr() as *const _,
+                             spirv.binary.len() as gl::types::GLsizei);
+
+        let indices = spirv.specialization_constants.iter().map(|&(i, _)| i).collect::<Vec<_>>();
+        let values = spirv.specialization_constants.iter().map(|&(_, v)| v).collect::<Vec<_>>();
+
+        ctxt.report_debug_output_errors.set(false);
+        specialize_shader(id, entry_point.as_ptr(), indices.len() as gl::types::GLuint,
+                          indices.as_ptr(), values.as_ptr());
+        ctxt.report_debug_output_errors.set(true);
+
+        let mut compilation_success: gl::types::GLint = mem::uninitialized();
+        ctxt.gl.GetShaderiv(id, gl::COMPILE_STATUS, &mut compilation_success);
+
+        let error_log = get_shader_info_log(&mut ctxt, Handle::Id(id));
+
+        if compilation_success == 1 {
+            let log = String::from_utf8_lossy(&error_log).into_owned();
+
+            return Ok(Shader {
+                context: facade.get_context().clone(),
+                id: Handle::Id(id),
+                ty: shader_type,
+                info_log: if log.trim().is_empty() { None } else { Some(log) },
+            });
+        }
+
+        ctxt.gl.DeleteShader(id);
+
+        match String::from_utf8(error_log) {
+            Ok(msg) => Err(ProgramCreationError::CompilationError(msg)),
+            Err(_) => Err(
+                ProgramCreationError::CompilationError("Could not convert the log \
+                                                        message to UTF-8".to_owned())
+            ),
+        }
+    }
+}
+
+/// Reads the info log of a shader.
+unsafe fn get_shader_info_log(ctxt: &mut CommandContext, id: Handle) -> Vec<u8> {
+    let mut error_log_size: gl::types::GLint = mem::uninitialized();
+
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
+                                            &mut error_log_size);
+        }
+    }
+
+    if error_log_size <= 0 {
+        return Vec::new();
+    }
+
+    let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
+
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetShaderInfoLog(id, error_log_size, &mut error_log_size,
+                                     error_log.as_mut_ptr() as *mut gl::types::GLchar);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+            ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
+                                  error_log.as_mut_ptr() as *mut gl::types::GLchar);
+        }
+    }
+
+    error_log.set_len(error_log_size as usize);
+    error_log
+}
+
 pub fn check_shader_type_compatibility<C: ?Sized>(ctxt: &C, shader_type: gl::types::GLenum)
                                           -> bool where C: CapabilitiesSource
 {
@@ -215,6 +319,11 @@ pub fn check_shader_type_compatibility<C: ?Sized>(ctxt: &C, shader_type: gl::typ
                 return false;
             }
         },
+        MESH_SHADER_NV | TASK_SHADER_NV => {
+            if !ctxt.get_extensions().gl_nv_mesh_shader {
+                return false;
+            }
+        },
         _ => unreachable!()
     };
 
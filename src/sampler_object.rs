@@ -1,11 +1,14 @@
 use DrawError;
 
 use uniforms::SamplerBehavior;
+use uniforms::SamplerReductionMode;
 
 use gl;
 use context::CommandContext;
+use context::ResourceKind;
 use version::Version;
 use version::Api;
+use CapabilitiesSource;
 use GlObject;
 use ToGlEnum;
 
@@ -30,6 +33,8 @@ impl SamplerObject {
             sampler
         };
 
+        ctxt.resource_created(ResourceKind::Sampler, sampler);
+
         unsafe {
             ctxt.gl.SamplerParameteri(sampler, gl::TEXTURE_WRAP_S,
                                       behavior.wrap_function.0.to_glenum() as gl::types::GLint);
@@ -42,15 +47,32 @@ impl SamplerObject {
             ctxt.gl.SamplerParameteri(sampler, gl::TEXTURE_MAG_FILTER,
                                       behavior.magnify_filter.to_glenum() as gl::types::GLint);
 
+            // the parameter doesn't exist without the extension, and values below 1 are
+            // rejected with `GL_INVALID_VALUE`
             if let Some(max_value) = ctxt.capabilities.max_texture_max_anisotropy {
                 let value = if behavior.max_anisotropy as f32 > max_value {
                     max_value
+                } else if behavior.max_anisotropy < 1 {
+                    1.0
                 } else {
                     behavior.max_anisotropy as f32
                 };
 
                 ctxt.gl.SamplerParameterf(sampler, gl::TEXTURE_MAX_ANISOTROPY_EXT, value);
             }
+
+            if let Some(comparison) = behavior.depth_texture_comparison {
+                ctxt.gl.SamplerParameteri(sampler, gl::TEXTURE_COMPARE_MODE,
+                                          gl::COMPARE_REF_TO_TEXTURE as gl::types::GLint);
+                ctxt.gl.SamplerParameteri(sampler, gl::TEXTURE_COMPARE_FUNC,
+                                          comparison.to_glenum() as gl::types::GLint);
+            }
+
+            // `GL_TEXTURE_REDUCTION_MODE_ARB` only exists with the extension
+            if behavior.reduction_mode != SamplerReductionMode::WeightedAverage {
+                ctxt.gl.SamplerParameteri(sampler, gl::TEXTURE_REDUCTION_MODE_ARB,
+                                          behavior.reduction_mode.to_glenum() as gl::types::GLint);
+            }
         }
 
         SamplerObject {
@@ -63,6 +85,7 @@ impl SamplerObject {
     #[inline]
     pub fn destroy(mut self, ctxt: &mut CommandContext) {
         self.destroyed = true;
+        ctxt.resource_destroyed(ResourceKind::Sampler, self.id);
 
         unsafe {
             ctxt.gl.DeleteSamplers(1, [self.id].as_ptr());
@@ -86,13 +109,126 @@ impl Drop for SamplerObject {
     }
 }
 
+/// Returns true if the backend supports sampler objects.
+#[inline]
+pub fn is_sampler_object_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_version() >= &Version(Api::Gl, 3, 2) ||
+    ctxt.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    ctxt.get_extensions().gl_arb_sampler_objects
+}
+
+/// Parameters of a texture that emulate a sampler where sampler objects are not supported: the
+/// wrap functions of the three coordinates, the minifying and magnifying filters and the
+/// anisotropy. The parameters that the texture or the backend don't have are left to 0.
+pub type TextureParameters = [gl::types::GLint; 6];
+
+const TEXTURE_PARAMETERS: [gl::types::GLenum; 6] = [
+    gl::TEXTURE_WRAP_S, gl::TEXTURE_WRAP_T, gl::TEXTURE_WRAP_R,
+    gl::TEXTURE_MIN_FILTER, gl::TEXTURE_MAG_FILTER, gl::TEXTURE_MAX_ANISOTROPY_EXT,
+];
+
+/// Returns true if the parameter exists for textures bound to `bind_point`.
+fn is_parameter_used(ctxt: &CommandContext, bind_point: gl::types::GLenum,
+                     parameter: gl::types::GLenum) -> bool
+{
+    match parameter {
+        gl::TEXTURE_WRAP_R => match bind_point {
+            gl::TEXTURE_1D | gl::TEXTURE_2D | gl::TEXTURE_RECTANGLE => false,
+            _ => true,
+        },
+        gl::TEXTURE_MAX_ANISOTROPY_EXT => ctxt.capabilities.max_texture_max_anisotropy.is_some(),
+        _ => true,
+    }
+}
+
+/// Returns the parameters that a texture bound to `bind_point` must have to be sampled
+/// with `behavior`.
+pub fn get_emulated_parameters(ctxt: &CommandContext, bind_point: gl::types::GLenum,
+                               behavior: &SamplerBehavior) -> TextureParameters
+{
+    let anisotropy = match ctxt.capabilities.max_texture_max_anisotropy {
+        Some(max_value) if behavior.max_anisotropy as f32 > max_value => max_value as u16,
+        Some(_) if behavior.max_anisotropy < 1 => 1,
+        Some(_) => behavior.max_anisotropy,
+        None => 0,
+    };
+
+    let wrap_r = if is_parameter_used(ctxt, bind_point, gl::TEXTURE_WRAP_R) {
+        behavior.wrap_function.2.to_glenum() as gl::types::GLint
+    } else {
+        0
+    };
+
+    [
+        behavior.wrap_function.0.to_glenum() as gl::types::GLint,
+        behavior.wrap_function.1.to_glenum() as gl::types::GLint,
+        wrap_r,
+        behavior.minify_filter.to_glenum() as gl::types::GLint,
+        behavior.magnify_filter.to_glenum() as gl::types::GLint,
+        anisotropy as gl::types::GLint,
+    ]
+}
+
+/// Reads the parameters of the texture bound to `bind_point` on the current texture unit.
+pub fn read_texture_parameters(ctxt: &mut CommandContext, bind_point: gl::types::GLenum)
+                               -> TextureParameters
+{
+    let mut values = [0; 6];
+
+    for (value, &parameter) in values.iter_mut().zip(TEXTURE_PARAMETERS.iter()) {
+        if is_parameter_used(ctxt, bind_point, parameter) {
+            unsafe { ctxt.gl.GetTexParameteriv(bind_point, parameter, value); }
+        }
+    }
+
+    values
+}
+
+/// Changes the parameters of the texture bound to `bind_point` on the current texture unit from
+/// `previous` to `values`. Only the parameters that differ are set.
+pub fn write_texture_parameters(ctxt: &mut CommandContext, bind_point: gl::types::GLenum,
+                                values: &TextureParameters, previous: &TextureParameters)
+{
+    for (i, &parameter) in TEXTURE_PARAMETERS.iter().enumerate() {
+        if values[i] != previous[i] && is_parameter_used(ctxt, bind_point, parameter) {
+            unsafe { ctxt.gl.TexParameteri(bind_point, parameter, values[i]); }
+        }
+    }
+}
+
+/// Checks that a sampler with the given behavior can be built or emulated.
+pub fn check_behavior(ctxt: &CommandContext, behavior: &SamplerBehavior)
+                      -> Result<(), DrawError>
+{
+    // `GL_MIRROR_CLAMP_TO_EDGE` would trigger a `GL_INVALID_ENUM` where it is not supported
+    let (s, t, r) = behavior.wrap_function;
+    if !s.is_supported(ctxt) || !t.is_supported(ctxt) || !r.is_supported(ctxt) {
+        return Err(DrawError::WrapFunctionNotSupported);
+    }
+
+    if !behavior.reduction_mode.is_supported(ctxt) {
+        return Err(DrawError::ReductionModeNotSupported);
+    }
+
+    // the depth comparison and the reduction mode are not emulated with the parameters of the
+    // texture
+    if !is_sampler_object_supported(ctxt) &&
+       (behavior.depth_texture_comparison.is_some() ||
+        behavior.reduction_mode != SamplerReductionMode::WeightedAverage)
+    {
+        return Err(DrawError::SamplersNotSupported);
+    }
+
+    Ok(())
+}
+
 /// Returns the sampler corresponding to the given behavior, or a draw error if
 /// samplers are not supported.
 pub fn get_sampler(ctxt: &mut CommandContext, behavior: &SamplerBehavior)
                    -> Result<gl::types::GLuint, DrawError>
 {
     // checking for compatibility
-    if ctxt.version < &Version(Api::Gl, 3, 2) && !ctxt.extensions.gl_arb_sampler_objects {
+    if !is_sampler_object_supported(&*ctxt) {
         return Err(DrawError::SamplersNotSupported);
     }
 
@@ -102,6 +238,8 @@ pub fn get_sampler(ctxt: &mut CommandContext, behavior: &SamplerBehavior)
         None => ()
     };
 
+    try!(check_behavior(ctxt, behavior));
+
     // builds a new sampler
     let sampler = SamplerObject::new(ctxt, behavior);
     let id = sampler.get_id();
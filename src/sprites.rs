@@ -0,0 +1,372 @@
+/*!
+Pixel-perfect 2D rendering of textured sprites.
+
+*This module is only available if the `sprites` feature of glium is enabled.*
+
+`Pixels2d` draws textured rectangles whose coordinates are expressed in pixels of the target
+surface. The origin is the bottom-left hand corner of the surface, like for `Rect`s everywhere
+else in glium.
+
+The integer coordinates of a `Rect` designate the edges between pixels, not their centers.
+A sprite whose destination rectangle has the same dimensions as its source rectangle therefore
+covers exactly one pixel per texel, and the center of each pixel samples the center of a texel.
+No half-pixel offset is necessary, and adding one would make the sprite blurry or shifted by one
+pixel depending on the rounding of the hardware.
+
+Sprites are accumulated in a `SpriteBatch` and are sent to the GPU when the batch is finished.
+Consecutive sprites that use the same texture are drawn with a single draw call.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let texture: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+use glium::Surface;
+use glium::sprites::Pixels2d;
+
+let mut sprites = Pixels2d::new(&display).unwrap();
+
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 0.0, 1.0);
+{
+    let mut batch = sprites.begin(&mut frame);
+    let dest = glium::Rect { left: 10, bottom: 10, width: 32, height: 32 };
+    let source = glium::Rect { left: 0, bottom: 0, width: 32, height: 32 };
+    batch.draw_sprite(&texture, dest, source, (1.0, 1.0, 1.0, 1.0));
+    batch.finish().unwrap();
+}
+frame.finish().unwrap();
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use draw_parameters::{Blend, DrawParameters};
+use index::{NoIndices, PrimitiveType};
+use program::{Program, ProgramChooserCreationError};
+use texture::Texture2d;
+use uniforms::{MagnifySamplerFilter, MinifySamplerFilter, Sampler, SamplerBehavior};
+use uniforms::SamplerWrapFunction;
+use vertex::{BufferCreationError, DynamicVertexBuffer};
+use DrawError;
+use Rect;
+use Surface;
+
+/// Number of sprites that the vertex buffer can hold before it needs to grow.
+const INITIAL_CAPACITY: usize = 256;
+
+#[derive(Copy, Clone)]
+struct SpriteVertex {
+    position: [f32; 2],
+    tex_coords: [f32; 2],
+    color: [f32; 4],
+}
+
+implement_vertex!(SpriteVertex, position, tex_coords, color);
+
+/// Draws textured sprites with coordinates in pixels.
+///
+/// Building a `Pixels2d` compiles a program and allocates a vertex buffer, so you should build
+/// it once and reuse it for each frame.
+pub struct Pixels2d {
+    program: Program,
+    vertex_buffer: DynamicVertexBuffer<SpriteVertex>,
+}
+
+impl Pixels2d {
+    /// Builds the program and the vertex buffer used to draw sprites.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<Pixels2d, SpritesError> where F: Facade {
+        let program = try!(program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform vec2 target_size;
+
+                    in vec2 position;
+                    in vec2 tex_coords;
+                    in vec4 color;
+
+                    out vec2 v_tex_coords;
+                    out vec4 v_color;
+
+                    void main() {
+                        gl_Position = vec4(position / target_size * 2.0 - 1.0, 0.0, 1.0);
+                        v_tex_coords = tex_coords;
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    uniform sampler2D tex;
+
+                    in vec2 v_tex_coords;
+                    in vec4 v_color;
+
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = texture(tex, v_tex_coords) * v_color;
+                    }
+                "
+            },
+
+            110 => {
+                vertex: "
+                    #version 110
+
+                    uniform vec2 target_size;
+
+                    attribute vec2 position;
+                    attribute vec2 tex_coords;
+                    attribute vec4 color;
+
+                    varying vec2 v_tex_coords;
+                    varying vec4 v_color;
+
+                    void main() {
+                        gl_Position = vec4(position / target_size * 2.0 - 1.0, 0.0, 1.0);
+                        v_tex_coords = tex_coords;
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 110
+
+                    uniform sampler2D tex;
+
+                    varying vec2 v_tex_coords;
+                    varying vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = texture2D(tex, v_tex_coords) * v_color;
+                    }
+                "
+            },
+
+            100 => {
+                vertex: "
+                    #version 100
+
+                    uniform highp vec2 target_size;
+
+                    attribute highp vec2 position;
+                    attribute highp vec2 tex_coords;
+                    attribute lowp vec4 color;
+
+                    varying highp vec2 v_tex_coords;
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        gl_Position = vec4(position / target_size * 2.0 - 1.0, 0.0, 1.0);
+                        v_tex_coords = tex_coords;
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    uniform lowp sampler2D tex;
+
+                    varying highp vec2 v_tex_coords;
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = texture2D(tex, v_tex_coords) * v_color;
+                    }
+                "
+            },
+        ));
+
+        let vertex_buffer = try!(DynamicVertexBuffer::new(facade, INITIAL_CAPACITY * 6));
+
+        Ok(Pixels2d {
+            program: program,
+            vertex_buffer: vertex_buffer,
+        })
+    }
+
+    /// Starts drawing sprites on a surface.
+    ///
+    /// The sprites are only drawn when `finish` is called on the returned batch.
+    #[inline]
+    pub fn begin<'a, S>(&'a mut self, surface: &'a mut S) -> SpriteBatch<'a, S>
+                        where S: Surface
+    {
+        SpriteBatch {
+            renderer: self,
+            surface: surface,
+            vertices: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+}
+
+/// A list of sprites that are waiting to be drawn on a surface.
+///
+/// Call `finish` to draw the sprites. Dropping the batch without calling `finish` discards
+/// the sprites.
+pub struct SpriteBatch<'a, S: 'a> {
+    renderer: &'a mut Pixels2d,
+    surface: &'a mut S,
+    vertices: Vec<SpriteVertex>,
+    // texture of each draw call, and the index of its first vertex
+    batches: Vec<(&'a Texture2d, usize)>,
+}
+
+impl<'a, S> SpriteBatch<'a, S> where S: Surface {
+    /// Adds a sprite to the batch.
+    ///
+    /// `dest` is the rectangle of the surface to cover, in pixels. `source` is the rectangle of
+    /// the texture to draw, in texels. The color of each texel is multiplied by `color`, and
+    /// the result is alpha-blended with the content of the surface.
+    ///
+    /// The texture is sampled with the `Nearest` filter and the `Clamp` wrap function. If `dest`
+    /// and `source` have different dimensions, the sprite is scaled without any interpolation.
+    ///
+    /// Where sampler objects aren't supported, the filter and the wrap function are set on the
+    /// texture itself. Its own parameters are restored the next time it is drawn without a
+    /// sampler.
+    pub fn draw_sprite(&mut self, texture: &'a Texture2d, dest: Rect, source: Rect,
+                       color: (f32, f32, f32, f32))
+    {
+        let same_texture = match self.batches.last() {
+            Some(&(t, _)) => t as *const Texture2d == texture as *const Texture2d,
+            None => false,
+        };
+
+        if !same_texture {
+            self.batches.push((texture, self.vertices.len()));
+        }
+
+        let tex_width = texture.get_width() as f32;
+        let tex_height = texture.get_height().unwrap_or(1) as f32;
+
+        let left = dest.left as f32;
+        let right = (dest.left + dest.width) as f32;
+        let bottom = dest.bottom as f32;
+        let top = (dest.bottom + dest.height) as f32;
+
+        let u_left = source.left as f32 / tex_width;
+        let u_right = (source.left + source.width) as f32 / tex_width;
+        let v_bottom = source.bottom as f32 / tex_height;
+        let v_top = (source.bottom + source.height) as f32 / tex_height;
+
+        let color = [color.0, color.1, color.2, color.3];
+        let vertex = |x, y, u, v| SpriteVertex {
+            position: [x, y],
+            tex_coords: [u, v],
+            color: color,
+        };
+
+        self.vertices.extend_from_slice(&[
+            vertex(left, bottom, u_left, v_bottom),
+            vertex(right, bottom, u_right, v_bottom),
+            vertex(left, top, u_left, v_top),
+            vertex(left, top, u_left, v_top),
+            vertex(right, bottom, u_right, v_bottom),
+            vertex(right, top, u_right, v_top),
+        ]);
+    }
+
+    /// Uploads the sprites and draws them on the surface, in the order in which they have
+    /// been added.
+    pub fn finish(self) -> Result<(), SpritesError> {
+        let SpriteBatch { renderer, surface, vertices, batches } = self;
+
+        if vertices.is_empty() {
+            return Ok(());
+        }
+
+        renderer.vertex_buffer.clear();
+        try!(renderer.vertex_buffer.push_slice(&vertices));
+
+        let (width, height) = surface.get_dimensions();
+        let params = DrawParameters {
+            blend: Blend::alpha_blending(),
+            .. Default::default()
+        };
+        let behavior = SamplerBehavior::new(MinifySamplerFilter::Nearest,
+                                            MagnifySamplerFilter::Nearest,
+                                            SamplerWrapFunction::Clamp);
+
+        for (num, &(texture, start)) in batches.iter().enumerate() {
+            let end = batches.get(num + 1).map(|&(_, e)| e).unwrap_or(vertices.len());
+            let slice = renderer.vertex_buffer.slice(start .. end).unwrap();
+
+            let uniforms = uniform! {
+                target_size: [width as f32, height as f32],
+                tex: Sampler(texture, behavior),
+            };
+
+            try!(surface.draw(slice, NoIndices(PrimitiveType::TrianglesList),
+                              &renderer.program, &uniforms, &params));
+        }
+
+        Ok(())
+    }
+}
+
+/// Error that can happen while drawing sprites.
+#[derive(Debug)]
+pub enum SpritesError {
+    /// Failed to compile the shaders.
+    ProgramCreationError(ProgramChooserCreationError),
+
+    /// Failed to create or to grow the vertex buffer.
+    BufferCreationError(BufferCreationError),
+
+    /// Failed to draw on the surface.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for SpritesError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for SpritesError {
+    fn description(&self) -> &str {
+        use self::SpritesError::*;
+        match *self {
+            ProgramCreationError(_) =>
+                "Failed to compile the shaders",
+            BufferCreationError(_) =>
+                "Failed to create or to grow the vertex buffer",
+            DrawError(_) =>
+                "Failed to draw on the surface",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        use self::SpritesError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            BufferCreationError(ref err) => Some(err),
+            DrawError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramChooserCreationError> for SpritesError {
+    #[inline]
+    fn from(err: ProgramChooserCreationError) -> SpritesError {
+        SpritesError::ProgramCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for SpritesError {
+    #[inline]
+    fn from(err: BufferCreationError) -> SpritesError {
+        SpritesError::BufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for SpritesError {
+    #[inline]
+    fn from(err: DrawError) -> SpritesError {
+        SpritesError::DrawError(err)
+    }
+}
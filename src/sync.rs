@@ -5,9 +5,12 @@ use gl;
 
 use backend::Facade;
 use context::Context;
+use context::LabeledObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use ContextExt;
 use std::rc::Rc;
 
+use std::mem;
 use std::thread;
 
 /// Error that happens when sync functionnalities are not supported.
@@ -56,6 +59,14 @@ impl SyncFence {
     }
 }
 
+impl Labeled for SyncFence {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.context.make_current();
+        ctxt.set_object_label(LabeledObject::Sync(self.id.unwrap()), label)
+    }
+}
+
 impl Drop for SyncFence {
     #[inline]
     fn drop(&mut self) {
@@ -120,6 +131,26 @@ pub unsafe fn new_linear_sync_fence(ctxt: &mut CommandContext)
     }
 }
 
+/// Inserts a fence after the texture and buffer uploads that have been issued since the
+/// previous fence, and replaces the fence stored in `upload_fence` with it.
+///
+/// Does nothing if there is no pending upload. If fences are not supported, the uploads stay
+/// pending.
+pub unsafe fn fence_pending_uploads(ctxt: &mut CommandContext) {
+    if !ctxt.state.pending_uploads {
+        return;
+    }
+
+    if let Ok(fence) = new_linear_sync_fence(ctxt) {
+        let previous = mem::replace(&mut *ctxt.upload_fence, Some(fence));
+        if let Some(previous) = previous {
+            destroy_linear_sync_fence(ctxt, previous);
+        }
+
+        ctxt.state.pending_uploads = false;
+    }
+}
+
 /// Waits for this fence and destroys it, from within the commands context.
 #[inline]
 pub unsafe fn wait_linear_sync_fence_and_drop(mut fence: LinearSyncFence,
@@ -0,0 +1,493 @@
+/*!
+Batched rendering of glyphs from a glyph atlas.
+
+*This module is only available if the `text` feature of glium is enabled.*
+
+This module doesn't rasterize fonts. You are expected to rasterize the glyphs that you need into
+a texture, called an *atlas*, with the library of your choice, and to compute the layout of your
+text yourself. A `GlyphBatch` then takes care of sending the quads of the glyphs to the GPU and
+of drawing all of them with a single draw call.
+
+Coordinates are expressed in pixels of the target surface, and the origin is the bottom-left
+hand corner of the surface, like for `Rect`s everywhere else in glium. Texture coordinates are
+normalized coordinates in the atlas.
+
+## Atlas
+
+The atlas must contain premultiplied alpha, and the glyphs are blended with
+`Blend::premultiplied_alpha()`. The color of each texel is multiplied by the color of the glyph,
+which must be premultiplied as well. If your rasterizer produces coverage values, store each
+value `a` as `(a, a, a, a)` in the atlas.
+
+The atlas is sampled with the `Clamp` wrap function, and by default with the `Linear` filter.
+Use `set_filter` to switch to the `Nearest` filter for pixel fonts or for glyphs that are drawn
+at the exact size at which they have been rasterized. Where sampler objects aren't supported,
+the filter and the wrap function are set on the atlas itself. Its own parameters are restored the
+next time it is drawn without a sampler.
+
+## Instancing
+
+If the backend supports instancing, the glyphs are stored once per glyph in a per-instance
+vertex buffer and a quad is drawn for each instance. Otherwise each glyph is expanded into six
+vertices. In both cases the vertex buffer grows when needed, and the same shaders are used.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let atlas: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+use glium::Surface;
+use glium::text::{Glyph, GlyphBatch};
+
+let mut glyphs = GlyphBatch::new(&display).unwrap();
+glyphs.push(Glyph {
+    position: [10.0, 10.0],
+    size: [8.0, 12.0],
+    tex_coords: [0.0, 0.0, 0.125, 0.1875],
+    color: [1.0, 1.0, 1.0, 1.0],
+});
+
+let mut frame = display.draw();
+frame.clear_color(0.0, 0.0, 0.0, 1.0);
+glyphs.draw(&mut frame, &atlas).unwrap();
+frame.finish().unwrap();
+```
+
+## Custom shaders
+
+You can pass your own program to `GlyphBatch::with_program`, for example to render signed
+distance field fonts. The vertex shader receives the following attributes:
+
+ - `vec2 corner`: the corner of the quad, between `(0.0, 0.0)` and `(1.0, 1.0)`.
+ - `vec2 position`, `vec2 size`, `vec4 tex_coords` and `vec4 color`: the fields of the `Glyph`.
+
+The program receives the following uniforms:
+
+ - `vec2 target_size`: the dimensions of the surface in pixels.
+ - `sampler2D atlas`: the atlas.
+
+*/
+use std::error::Error;
+use std::fmt;
+
+use backend::Facade;
+use draw_parameters::{Blend, DrawParameters};
+use index::{NoIndices, PrimitiveType};
+use program::{Program, ProgramChooserCreationError};
+use texture::Texture2d;
+use uniforms::{MagnifySamplerFilter, MinifySamplerFilter, Sampler, SamplerBehavior};
+use uniforms::SamplerWrapFunction;
+use vertex::{BufferCreationError, DynamicVertexBuffer, VertexBuffer};
+use DrawError;
+use Surface;
+
+/// Number of glyphs that the vertex buffer can hold before it needs to grow.
+const INITIAL_CAPACITY: usize = 256;
+
+/// A glyph to draw.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Glyph {
+    /// Position of the bottom-left hand corner of the quad, in pixels.
+    pub position: [f32; 2],
+
+    /// Width and height of the quad, in pixels.
+    pub size: [f32; 2],
+
+    /// Texture coordinates of the glyph in the atlas, in the order left, bottom, right, top.
+    pub tex_coords: [f32; 4],
+
+    /// The color of each texel of the glyph is multiplied by this premultiplied color.
+    pub color: [f32; 4],
+}
+
+implement_vertex!(Glyph, position, size, tex_coords, color);
+
+#[derive(Copy, Clone)]
+struct QuadVertex {
+    corner: [f32; 2],
+}
+
+implement_vertex!(QuadVertex, corner);
+
+/// A glyph expanded into one of the six vertices of its quad, when instancing isn't supported.
+#[derive(Copy, Clone)]
+struct ExpandedVertex {
+    corner: [f32; 2],
+    position: [f32; 2],
+    size: [f32; 2],
+    tex_coords: [f32; 4],
+    color: [f32; 4],
+}
+
+implement_vertex!(ExpandedVertex, corner, position, size, tex_coords, color);
+
+/// Corners of the two triangles of a quad.
+const CORNERS: [[f32; 2]; 6] = [
+    [0.0, 0.0], [1.0, 0.0], [0.0, 1.0],
+    [0.0, 1.0], [1.0, 0.0], [1.0, 1.0],
+];
+
+enum Storage {
+    Instanced {
+        quad: VertexBuffer<QuadVertex>,
+        instances: DynamicVertexBuffer<Glyph>,
+    },
+    Expanded(DynamicVertexBuffer<ExpandedVertex>),
+}
+
+/// A list of glyphs that are drawn with a single draw call.
+///
+/// Building a `GlyphBatch` compiles a program and allocates vertex buffers, so you should build
+/// it once and reuse it. The glyphs stay in the batch after they have been drawn, which means
+/// that static text doesn't need to be pushed again every frame. They are only uploaded to the
+/// GPU again after the content of the batch has been modified.
+pub struct GlyphBatch {
+    program: Program,
+    storage: Storage,
+    glyphs: Vec<Glyph>,
+    // true if `glyphs` has been modified since the last upload
+    dirty: bool,
+    filter: MagnifySamplerFilter,
+}
+
+impl GlyphBatch {
+    /// Builds an empty batch that uses the default shaders.
+    pub fn new<F: ?Sized>(facade: &F) -> Result<GlyphBatch, TextError> where F: Facade {
+        let program = try!(program!(facade,
+            140 => {
+                vertex: "
+                    #version 140
+
+                    uniform vec2 target_size;
+
+                    in vec2 corner;
+                    in vec2 position;
+                    in vec2 size;
+                    in vec4 tex_coords;
+                    in vec4 color;
+
+                    out vec2 v_tex_coords;
+                    out vec4 v_color;
+
+                    void main() {
+                        vec2 pixel = position + corner * size;
+                        gl_Position = vec4(pixel / target_size * 2.0 - 1.0, 0.0, 1.0);
+                        v_tex_coords = mix(tex_coords.xy, tex_coords.zw, corner);
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 140
+
+                    uniform sampler2D atlas;
+
+                    in vec2 v_tex_coords;
+                    in vec4 v_color;
+
+                    out vec4 f_color;
+
+                    void main() {
+                        f_color = texture(atlas, v_tex_coords) * v_color;
+                    }
+                "
+            },
+
+            110 => {
+                vertex: "
+                    #version 110
+
+                    uniform vec2 target_size;
+
+                    attribute vec2 corner;
+                    attribute vec2 position;
+                    attribute vec2 size;
+                    attribute vec4 tex_coords;
+                    attribute vec4 color;
+
+                    varying vec2 v_tex_coords;
+                    varying vec4 v_color;
+
+                    void main() {
+                        vec2 pixel = position + corner * size;
+                        gl_Position = vec4(pixel / target_size * 2.0 - 1.0, 0.0, 1.0);
+                        v_tex_coords = mix(tex_coords.xy, tex_coords.zw, corner);
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 110
+
+                    uniform sampler2D atlas;
+
+                    varying vec2 v_tex_coords;
+                    varying vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = texture2D(atlas, v_tex_coords) * v_color;
+                    }
+                "
+            },
+
+            100 => {
+                vertex: "
+                    #version 100
+
+                    uniform highp vec2 target_size;
+
+                    attribute highp vec2 corner;
+                    attribute highp vec2 position;
+                    attribute highp vec2 size;
+                    attribute highp vec4 tex_coords;
+                    attribute lowp vec4 color;
+
+                    varying highp vec2 v_tex_coords;
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        highp vec2 pixel = position + corner * size;
+                        gl_Position = vec4(pixel / target_size * 2.0 - 1.0, 0.0, 1.0);
+                        v_tex_coords = mix(tex_coords.xy, tex_coords.zw, corner);
+                        v_color = color;
+                    }
+                ",
+                fragment: "
+                    #version 100
+
+                    uniform lowp sampler2D atlas;
+
+                    varying highp vec2 v_tex_coords;
+                    varying lowp vec4 v_color;
+
+                    void main() {
+                        gl_FragColor = texture2D(atlas, v_tex_coords) * v_color;
+                    }
+                "
+            },
+        ));
+
+        GlyphBatch::with_program(facade, program)
+    }
+
+    /// Builds an empty batch that draws the glyphs with a custom program.
+    ///
+    /// See the documentation of the module for the attributes and uniforms that the program
+    /// receives.
+    pub fn with_program<F: ?Sized>(facade: &F, program: Program)
+                                   -> Result<GlyphBatch, TextError> where F: Facade
+    {
+        let quad = try!(VertexBuffer::new(facade, &[
+            QuadVertex { corner: [0.0, 0.0] },
+            QuadVertex { corner: [1.0, 0.0] },
+            QuadVertex { corner: [0.0, 1.0] },
+            QuadVertex { corner: [1.0, 1.0] },
+        ]));
+
+        let storage = if quad.per_instance().is_ok() {
+            Storage::Instanced {
+                quad: quad,
+                instances: try!(DynamicVertexBuffer::new(facade, INITIAL_CAPACITY)),
+            }
+        } else {
+            Storage::Expanded(try!(DynamicVertexBuffer::new(facade, INITIAL_CAPACITY * 6)))
+        };
+
+        Ok(GlyphBatch {
+            program: program,
+            storage: storage,
+            glyphs: Vec::new(),
+            dirty: false,
+            filter: MagnifySamplerFilter::Linear,
+        })
+    }
+
+    /// Returns true if the glyphs are drawn with instancing.
+    #[inline]
+    pub fn is_instanced(&self) -> bool {
+        match self.storage {
+            Storage::Instanced { .. } => true,
+            Storage::Expanded(_) => false,
+        }
+    }
+
+    /// Sets the filter used to sample the atlas. The default value is `Linear`.
+    #[inline]
+    pub fn set_filter(&mut self, filter: MagnifySamplerFilter) {
+        self.filter = filter;
+    }
+
+    /// Adds a glyph at the end of the batch.
+    #[inline]
+    pub fn push(&mut self, glyph: Glyph) {
+        self.glyphs.push(glyph);
+        self.dirty = true;
+    }
+
+    /// Adds glyphs at the end of the batch.
+    #[inline]
+    pub fn push_slice(&mut self, glyphs: &[Glyph]) {
+        self.glyphs.extend_from_slice(glyphs);
+        self.dirty = true;
+    }
+
+    /// Removes all the glyphs from the batch.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.glyphs.clear();
+        self.dirty = true;
+    }
+
+    /// Returns the glyphs of the batch.
+    #[inline]
+    pub fn glyphs(&self) -> &[Glyph] {
+        &self.glyphs
+    }
+
+    /// Returns the number of glyphs in the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Returns true if the batch doesn't contain any glyph.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// Draws all the glyphs of the batch on a surface, in the order in which they have been
+    /// added, with a single draw call.
+    pub fn draw<S>(&mut self, surface: &mut S, atlas: &Texture2d) -> Result<(), TextError>
+                   where S: Surface
+    {
+        if self.dirty {
+            try!(self.upload());
+            self.dirty = false;
+        }
+
+        if self.glyphs.is_empty() {
+            return Ok(());
+        }
+
+        let (width, height) = surface.get_dimensions();
+
+        let minify_filter = match self.filter {
+            MagnifySamplerFilter::Nearest => MinifySamplerFilter::Nearest,
+            MagnifySamplerFilter::Linear => MinifySamplerFilter::Linear,
+        };
+
+        let behavior = SamplerBehavior::new(minify_filter, self.filter,
+                                            SamplerWrapFunction::Clamp);
+
+        let uniforms = uniform! {
+            target_size: [width as f32, height as f32],
+            atlas: Sampler(atlas, behavior),
+        };
+
+        let params = DrawParameters {
+            blend: Blend::premultiplied_alpha(),
+            .. Default::default()
+        };
+
+        match self.storage {
+            Storage::Instanced { ref quad, ref instances } => {
+                let instances = instances.finish();
+                let instances = instances.per_instance().unwrap();
+                try!(surface.draw((quad, instances), NoIndices(PrimitiveType::TriangleStrip),
+                                  &self.program, &uniforms, &params));
+            },
+            Storage::Expanded(ref vertices) => {
+                try!(surface.draw(vertices.finish(), NoIndices(PrimitiveType::TrianglesList),
+                                  &self.program, &uniforms, &params));
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Sends the glyphs to the vertex buffer.
+    fn upload(&mut self) -> Result<(), BufferCreationError> {
+        match self.storage {
+            Storage::Instanced { ref mut instances, .. } => {
+                instances.clear();
+                try!(instances.push_slice(&self.glyphs));
+            },
+            Storage::Expanded(ref mut vertices) => {
+                let data = self.glyphs.iter().flat_map(|glyph| {
+                    CORNERS.iter().map(move |corner| ExpandedVertex {
+                        corner: *corner,
+                        position: glyph.position,
+                        size: glyph.size,
+                        tex_coords: glyph.tex_coords,
+                        color: glyph.color,
+                    })
+                }).collect::<Vec<_>>();
+
+                vertices.clear();
+                try!(vertices.push_slice(&data));
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Error that can happen while building or drawing a `GlyphBatch`.
+#[derive(Debug)]
+pub enum TextError {
+    /// Failed to compile the shaders.
+    ProgramCreationError(ProgramChooserCreationError),
+
+    /// Failed to create or to grow a vertex buffer.
+    BufferCreationError(BufferCreationError),
+
+    /// Failed to draw on the surface.
+    DrawError(DrawError),
+}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for TextError {
+    fn description(&self) -> &str {
+        use self::TextError::*;
+        match *self {
+            ProgramCreationError(_) =>
+                "Failed to compile the shaders",
+            BufferCreationError(_) =>
+                "Failed to create or to grow a vertex buffer",
+            DrawError(_) =>
+                "Failed to draw on the surface",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        use self::TextError::*;
+        match *self {
+            ProgramCreationError(ref err) => Some(err),
+            BufferCreationError(ref err) => Some(err),
+            DrawError(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProgramChooserCreationError> for TextError {
+    #[inline]
+    fn from(err: ProgramChooserCreationError) -> TextError {
+        TextError::ProgramCreationError(err)
+    }
+}
+
+impl From<BufferCreationError> for TextError {
+    #[inline]
+    fn from(err: BufferCreationError) -> TextError {
+        TextError::BufferCreationError(err)
+    }
+}
+
+impl From<DrawError> for TextError {
+    #[inline]
+    fn from(err: DrawError) -> TextError {
+        TextError::DrawError(err)
+    }
+}
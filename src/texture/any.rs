@@ -5,6 +5,9 @@ use backend::Facade;
 use version::Version;
 use context::Context;
 use context::CommandContext;
+use context::ResourceKind;
+use context::LabeledObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use CapabilitiesSource;
 use ContextExt;
 use TextureExt;
@@ -13,15 +16,24 @@ use version::Api;
 use Rect;
 
 use image_format::{self, TextureFormatRequest, ClientFormatAny};
-use texture::Texture2dDataSink;
+use texture::{Texture2dDataSink, RawImage2d};
 use texture::TextureKind;
 use texture::{MipmapsOption, TextureFormat, TextureCreationError, CubeLayer};
-use texture::{get_format, InternalFormat, GetFormatError};
+use texture::{CopyImageError, ImageWriteNotSupportedError};
+use texture::{Swizzle, TextureSwizzleNotSupportedError};
+use texture::{DepthStencilMode, StencilTexturingNotSupportedError};
+use texture::LevelsRangeError;
+use texture;
+use texture::{get_format, InternalFormat, GetFormatError, TextureLevelInfo};
 use texture::pixel::PixelValue;
 use texture::pixel_buffer::PixelBuffer;
 
 use fbo::ClearBufferData;
 
+use uniforms::{ImageUnit, ImageUnitFormat, SamplerBehavior};
+
+use sampler_object::{self, TextureParameters};
+
 use buffer::BufferSlice;
 use buffer::BufferAny;
 use BufferExt;
@@ -37,6 +49,7 @@ use std::rc::Rc;
 use std::ops::Range;
 
 use ops;
+use ops::ReadError;
 use fbo;
 
 /// Type of a texture.
@@ -50,6 +63,7 @@ pub enum Dimensions {
     Texture2dMultisample { width: u32, height: u32, samples: u32 },
     Texture2dMultisampleArray { width: u32, height: u32, array_size: u32, samples: u32 },
     Texture3d { width: u32, height: u32, depth: u32 },
+    TextureRectangle { width: u32, height: u32 },
     Cubemap { dimension: u32 },
     CubemapArray { dimension: u32, array_size: u32 },
 }
@@ -72,6 +86,20 @@ pub struct TextureAny {
     /// Is automatic mipmap generation allowed for this texture?
     generate_mipmaps: bool,
 
+    /// Which component is returned when sampling a depth-stencil texture.
+    depth_stencil_mode: Cell<DepthStencilMode>,
+
+    /// Current values of `GL_TEXTURE_BASE_LEVEL` and `GL_TEXTURE_MAX_LEVEL`.
+    levels_range: Cell<(u32, u32)>,
+
+    /// Id of the draw call or compute dispatch that has last written to this texture through
+    /// an image unit, or 0 if it never happened.
+    latest_shader_write: Cell<u64>,
+
+    /// Parameters set on the texture to emulate a sampler where sampler objects are not
+    /// supported, and the parameters that the texture had before.
+    emulated_sampler: Cell<Option<(TextureParameters, TextureParameters)>>,
+
     /// Is this texture owned by us? If not, we won't clean it up on drop.
     owned: bool
 }
@@ -87,6 +115,7 @@ fn extract_dimensions(ty: Dimensions)
         Dimensions::Texture2dMultisample { width, height, samples } => (width, Some(height), None, None, Some(samples)),
         Dimensions::Texture2dMultisampleArray { width, height, array_size, samples } => (width, Some(height), None, Some(array_size), Some(samples)),
         Dimensions::Texture3d { width, height, depth } => (width, Some(height), Some(depth), None, None),
+        Dimensions::TextureRectangle { width, height } => (width, Some(height), None, None, None),
         Dimensions::Cubemap { dimension } => (dimension, Some(dimension), None, None, None),
         Dimensions::CubemapArray { dimension, array_size } => (dimension, Some(dimension), None, Some(array_size * 6), None),
     }
@@ -102,6 +131,7 @@ fn get_bind_point(ty: Dimensions) -> gl::types::GLenum {
         Dimensions::Texture2dMultisample { .. } => gl::TEXTURE_2D_MULTISAMPLE,
         Dimensions::Texture2dMultisampleArray { .. } => gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
         Dimensions::Texture3d { .. } => gl::TEXTURE_3D,
+        Dimensions::TextureRectangle { .. } => gl::TEXTURE_RECTANGLE,
         Dimensions::Cubemap { .. } => gl::TEXTURE_CUBE_MAP,
         Dimensions::CubemapArray { .. } => gl::TEXTURE_CUBE_MAP_ARRAY,
     }
@@ -148,6 +178,34 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         }
     }
 
+    // checking that multisample textures are supported, and that the number of samples
+    // doesn't exceed `GL_MAX_SAMPLES`
+    match ty {
+        Dimensions::Texture2dMultisample { .. } => {
+            if !texture::is_texture_2d_multisample_supported(&**facade.get_context()) {
+                return Err(TextureCreationError::TypeNotSupported);
+            }
+        },
+        Dimensions::Texture2dMultisampleArray { .. } => {
+            if !texture::is_texture_2d_multisample_array_supported(&**facade.get_context()) {
+                return Err(TextureCreationError::TypeNotSupported);
+            }
+        },
+        Dimensions::TextureRectangle { .. } => {
+            if !texture::is_texture_rectangle_supported(&**facade.get_context()) {
+                return Err(TextureCreationError::TypeNotSupported);
+            }
+        },
+        _ => ()
+    };
+
+    if let Some(samples) = samples {
+        let max = facade.get_context().capabilities().max_samples.unwrap_or(0);
+        if samples == 0 || samples > max as u32 {
+            return Err(TextureCreationError::SamplesNotSupported);
+        }
+    }
+
     // getting the `GLenum` corresponding to this texture type
     let bind_point = get_bind_point(ty);
     if bind_point == gl::TEXTURE_CUBE_MAP || bind_point == gl::TEXTURE_CUBE_MAP_ARRAY {
@@ -168,6 +226,11 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
     let should_generate_mipmaps = mipmaps.should_generate();
     let texture_levels = mipmaps.num_levels(width, height, depth) as gl::types::GLsizei;
 
+    // rectangle textures only have a main level
+    if bind_point == gl::TEXTURE_RECTANGLE && texture_levels > 1 {
+        return Err(TextureCreationError::MipmapsNotSupported);
+    }
+
     let teximg_internal_format = try!(image_format::format_request_to_glenum(facade.get_context(), format, image_format::RequestType::TexImage(data.as_ref().map(|&(c, _)| c))));
     let storage_internal_format = image_format::format_request_to_glenum(facade.get_context(), format, image_format::RequestType::TexStorage).ok();
 
@@ -202,6 +265,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         let has_mipmaps = texture_levels > 1;
         let data = data;
         let data_raw = if let Some((_, ref data)) = data {
+            ctxt.state.pending_uploads = true;
             data.as_ptr() as *const _
         } else {
             ptr::null()
@@ -216,15 +280,20 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
 
         let id: gl::types::GLuint = mem::uninitialized();
         ctxt.gl.GenTextures(1, mem::transmute(&id));
+        ctxt.resource_created(ResourceKind::Texture, id);
 
         {
             ctxt.gl.BindTexture(bind_point, id);
             let act = ctxt.state.active_texture as usize;
             ctxt.state.texture_units[act].texture = id;
+            ctxt.state.texture_units[act].target = bind_point;
         }
 
+        // `GL_REPEAT` is an invalid wrap mode for rectangle textures
+        let wrap = if bind_point == gl::TEXTURE_RECTANGLE { gl::CLAMP_TO_EDGE } else { gl::REPEAT };
+
         if !is_multisampled {
-            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_S, wrap as i32);
             ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAG_FILTER, filtering as i32);
         }
 
@@ -233,7 +302,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
             Dimensions::Texture2dMultisample { .. } => (),
             Dimensions::Texture2dMultisampleArray { .. } => (),
             _ => {
-                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+                ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_T, wrap as i32);
             },
         };
 
@@ -241,6 +310,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
             Dimensions::Texture1d { .. } => (),
             Dimensions::Texture2d { .. } => (),
             Dimensions::Texture2dMultisample { .. } => (),
+            Dimensions::TextureRectangle { .. } => (),
             _ => {
                 ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_WRAP_R, gl::REPEAT as i32);
             },
@@ -254,11 +324,11 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
                                   filtering as i32);
         }
 
-        if !has_mipmaps && (ctxt.version >= &Version(Api::Gl, 1, 2) ||
-                            ctxt.version >= &Version(Api::GlEs, 3, 0))
-        {
+        // limiting the mipmap chain to the levels that we allocate, otherwise the texture would
+        // be incomplete and sampling it would return black
+        if ctxt.version >= &Version(Api::Gl, 1, 2) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
             ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_BASE_LEVEL, 0);
-            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAX_LEVEL, 0);
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAX_LEVEL, texture_levels - 1);
         }
 
         if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY ||
@@ -309,7 +379,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
             }
 
         } else if bind_point == gl::TEXTURE_2D || bind_point == gl::TEXTURE_1D_ARRAY ||
-                  bind_point == gl::TEXTURE_CUBE_MAP
+                  bind_point == gl::TEXTURE_CUBE_MAP || bind_point == gl::TEXTURE_RECTANGLE
         {
             let mut data_raw = data_raw;
 
@@ -463,6 +533,10 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         ty: ty,
         levels: texture_levels as u32,
         generate_mipmaps: should_generate_mipmaps,
+        depth_stencil_mode: Cell::new(DepthStencilMode::Depth),
+        levels_range: Cell::new((0, texture_levels as u32 - 1)),
+        latest_shader_write: Cell::new(0),
+        emulated_sampler: Cell::new(None),
         owned: true
     })
 }
@@ -493,10 +567,112 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
         ty: ty,
         levels: mipmap_levels,
         generate_mipmaps: should_generate_mipmaps,
+        depth_stencil_mode: Cell::new(DepthStencilMode::Depth),
+        levels_range: Cell::new((0, mipmap_levels.saturating_sub(1))),
+        latest_shader_write: Cell::new(0),
+        emulated_sampler: Cell::new(None),
         owned: owned
     }
 }
 
+/// Builds a new two-dimensional texture with the dimensions of `rect`, and copies the content
+/// of this area of a framebuffer to it with `glCopyTexImage2D`.
+///
+/// `source` is `None` for the back buffer of the default framebuffer. Otherwise the first
+/// color attachment is read.
+///
+/// The texture is created with the unsized `GL_RGBA` internal format, which lets the
+/// implementation pick a format that matches the color buffer that is read.
+///
+/// # Panic
+///
+/// Panics if `rect` is out of the bounds of `dimensions`, which are the dimensions of the
+/// source, or if the source doesn't have a floating-point color attachment.
+pub fn new_from_framebuffer<F: ?Sized>(facade: &F, source: Option<&fbo::ValidatedAttachments>,
+                                       dimensions: (u32, u32), rect: &Rect) -> TextureAny
+                                       where F: Facade
+{
+    assert!(rect.left + rect.width <= dimensions.0, "The rectangle is out of the bounds of \
+                                                     the framebuffer");
+    assert!(rect.bottom + rect.height <= dimensions.1, "The rectangle is out of the bounds \
+                                                        of the framebuffer");
+
+    if let Some(source) = source {
+        match source.get_color_attachments_kinds().iter().find(|&&(index, _)| index == 0) {
+            Some(&(_, TextureKind::Float)) => (),
+            _ => panic!("The framebuffer doesn't have a floating-point color attachment")
+        }
+    }
+
+    let mut ctxt = facade.get_context().make_current();
+
+    let id = unsafe {
+        match source {
+            Some(_) => {
+                let fb = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt,
+                                                                                 source);
+                fbo::bind_framebuffer(&mut ctxt, fb, false, true);
+                ctxt.gl.ReadBuffer(gl::COLOR_ATTACHMENT0);     // TODO: cache
+            },
+            None if ctxt.version >= &Version(Api::Gl, 1, 0) => {
+                fbo::FramebuffersContainer::bind_default_framebuffer_for_reading(&mut ctxt,
+                                                                                 gl::BACK_LEFT);
+            },
+            None if ctxt.version >= &Version(Api::GlEs, 3, 0) => {
+                fbo::FramebuffersContainer::bind_default_framebuffer_for_reading(&mut ctxt,
+                                                                                 gl::BACK);
+            },
+            None => {
+                // OpenGL ES 2 doesn't have `glReadBuffer` and always reads from the back buffer
+                fbo::bind_framebuffer(&mut ctxt, 0, false, true);
+            },
+        }
+
+        let id: gl::types::GLuint = mem::uninitialized();
+        ctxt.gl.GenTextures(1, mem::transmute(&id));
+        ctxt.resource_created(ResourceKind::Texture, id);
+
+        {
+            ctxt.gl.BindTexture(gl::TEXTURE_2D, id);
+            let act = ctxt.state.active_texture as usize;
+            ctxt.state.texture_units[act].texture = id;
+            ctxt.state.texture_units[act].target = gl::TEXTURE_2D;
+        }
+
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+
+        if ctxt.version >= &Version(Api::Gl, 1, 2) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+            ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_BASE_LEVEL, 0);
+            ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, 0);
+        }
+
+        ctxt.gl.CopyTexImage2D(gl::TEXTURE_2D, 0, gl::RGBA,
+                               rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
+                               rect.width as gl::types::GLsizei,
+                               rect.height as gl::types::GLsizei, 0);
+
+        id
+    };
+
+    TextureAny {
+        context: facade.get_context().clone(),
+        id: id,
+        requested_format: TextureFormatRequest::AnyFloatingPoint,
+        actual_format: Cell::new(None),
+        ty: Dimensions::Texture2d { width: rect.width, height: rect.height },
+        levels: 1,
+        generate_mipmaps: false,
+        depth_stencil_mode: Cell::new(DepthStencilMode::Depth),
+        levels_range: Cell::new((0, 0)),
+        latest_shader_write: Cell::new(0),
+        emulated_sampler: Cell::new(None),
+        owned: true
+    }
+}
+
 impl TextureAny {
     /// Returns the width of the texture.
     #[inline]
@@ -509,6 +685,7 @@ impl TextureAny {
             Dimensions::Texture2dMultisample { width, .. } => width,
             Dimensions::Texture2dMultisampleArray { width, .. } => width,
             Dimensions::Texture3d { width, .. } => width,
+            Dimensions::TextureRectangle { width, .. } => width,
             Dimensions::Cubemap { dimension, .. } => dimension,
             Dimensions::CubemapArray { dimension, .. } => dimension,
         }
@@ -525,6 +702,7 @@ impl TextureAny {
             Dimensions::Texture2dMultisample { height, .. } => Some(height),
             Dimensions::Texture2dMultisampleArray { height, .. } => Some(height),
             Dimensions::Texture3d { height, .. } => Some(height),
+            Dimensions::TextureRectangle { height, .. } => Some(height),
             Dimensions::Cubemap { dimension, .. } => Some(dimension),
             Dimensions::CubemapArray { dimension, .. } => Some(dimension),
         }
@@ -588,6 +766,7 @@ impl TextureAny {
             Dimensions::Texture2dMultisample { .. } => None,
             Dimensions::Texture2dMultisampleArray { array_size, .. } => Some(array_size),
             Dimensions::Texture3d { .. } => None,
+            Dimensions::TextureRectangle { .. } => None,
             Dimensions::Cubemap { .. } => None,
             Dimensions::CubemapArray { array_size, .. } => Some(array_size),
         }
@@ -628,6 +807,14 @@ impl TextureAny {
         })
     }
 
+    /// Returns the OpenGL target of the texture, for example `GL_TEXTURE_2D`.
+    ///
+    /// This is the target that must be used when binding the texture with raw OpenGL calls.
+    #[inline]
+    pub fn get_texture_target(&self) -> gl::types::GLenum {
+        get_bind_point(self.ty)
+    }
+
     /// Returns the type of the texture (1D, 2D, 3D, etc.).
     #[inline]
     pub fn get_texture_type(&self) -> Dimensions {
@@ -654,6 +841,28 @@ impl TextureAny {
         self.levels
     }
 
+    /// Builds an `ImageUnit` that binds this texture to an image unit, so that it can be read
+    /// and written by shaders with `imageLoad` and `imageStore`.
+    #[inline]
+    pub fn image_unit(&self, format: ImageUnitFormat) -> ImageUnit {
+        ImageUnit::new(self, format)
+    }
+
+    /// Queries the dimensions and the format that the backend has allocated for a mipmap level
+    /// of this texture.
+    ///
+    /// Contrary to `get_internal_format`, the result isn't cached.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the level doesn't exist.
+    pub fn query_level(&self, level: u32) -> Result<TextureLevelInfo, GetFormatError> {
+        assert!(level < self.levels, "The mipmap level {} doesn't exist", level);
+
+        let mut ctxt = self.context.make_current();
+        get_format::query_level(&mut ctxt, self, level)
+    }
+
     /// Returns a structure that represents the main mipmap level of the texture.
     #[inline]
     pub fn main_level(&self) -> TextureAnyMipmap {
@@ -679,12 +888,296 @@ impl TextureAny {
         })
     }
 
-    /// Binds this texture and generates mipmaps.
+    /// Changes the source of the red, green, blue and alpha components of the colors returned
+    /// when sampling this texture.
+    ///
+    /// For example `(Swizzle::One, Swizzle::One, Swizzle::One, Swizzle::Red)` lets you sample a
+    /// single-channel texture, like a font atlas, as white with an alpha component.
+    ///
+    /// The swizzle mask is a property of the texture and not of the sampler, and applies to all
+    /// the draw calls that use the texture afterwards.
+    pub fn set_swizzle_mask(&self, mask: (Swizzle, Swizzle, Swizzle, Swizzle))
+                            -> Result<(), TextureSwizzleNotSupportedError>
+    {
+        if !texture::is_texture_swizzle_supported(&*self.context) {
+            return Err(TextureSwizzleNotSupportedError);
+        }
+
+        fn to_glenum(swizzle: Swizzle) -> gl::types::GLint {
+            (match swizzle {
+                Swizzle::Red => gl::RED,
+                Swizzle::Green => gl::GREEN,
+                Swizzle::Blue => gl::BLUE,
+                Swizzle::Alpha => gl::ALPHA,
+                Swizzle::Zero => gl::ZERO,
+                Swizzle::One => gl::ONE,
+            }) as gl::types::GLint
+        }
+
+        let mut ctxt = self.context.make_current();
+        let bind_point = self.bind_to_current(&mut ctxt);
+
+        unsafe {
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_SWIZZLE_R, to_glenum(mask.0));
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_SWIZZLE_G, to_glenum(mask.1));
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_SWIZZLE_B, to_glenum(mask.2));
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_SWIZZLE_A, to_glenum(mask.3));
+        }
+
+        Ok(())
+    }
+
+    /// Changes which component of a depth-stencil texture is returned when sampling it.
+    ///
+    /// With `DepthStencilMode::Stencil`, the texture must be bound to a `usampler*` uniform and
+    /// sampling it returns the stencil value in the red component.
+    ///
+    /// Like the swizzle mask, this is a property of the texture and not of the sampler. It has
+    /// no effect on textures that don't contain both depth and stencil data.
+    pub fn set_depth_stencil_mode(&self, mode: DepthStencilMode)
+                                  -> Result<(), StencilTexturingNotSupportedError>
+    {
+        if !texture::is_stencil_texturing_supported(&*self.context) {
+            return Err(StencilTexturingNotSupportedError);
+        }
+
+        let value = match mode {
+            DepthStencilMode::Depth => gl::DEPTH_COMPONENT,
+            DepthStencilMode::Stencil => gl::STENCIL_INDEX,
+        };
+
+        let mut ctxt = self.context.make_current();
+        let bind_point = self.bind_to_current(&mut ctxt);
+
+        unsafe {
+            ctxt.gl.TexParameteri(bind_point, gl::DEPTH_STENCIL_TEXTURE_MODE,
+                                  value as gl::types::GLint);
+        }
+
+        self.depth_stencil_mode.set(mode);
+        Ok(())
+    }
+
+    /// Returns which component of a depth-stencil texture is returned when sampling it.
     #[inline]
+    pub fn get_depth_stencil_mode(&self) -> DepthStencilMode {
+        self.depth_stencil_mode.get()
+    }
+
+    /// Restricts the mipmap levels that are used when sampling this texture to the range
+    /// `base_level ..= max_level`.
+    ///
+    /// This is useful when streaming textures: the coarse levels can be uploaded first and the
+    /// texture can be used immediately with `base_level` set to the finest level that has
+    /// been uploaded so far. The texture would be incomplete and would sample as black if it
+    /// included levels whose content hasn't been written.
+    ///
+    /// Like the swizzle mask, this is a property of the texture and not of the sampler. Both
+    /// levels must have been allocated when the texture was created. By default, all the
+    /// allocated levels are used.
+    pub fn set_levels_range(&self, base_level: u32, max_level: u32)
+                            -> Result<(), LevelsRangeError>
+    {
+        if !(self.context.get_version() >= &Version(Api::Gl, 1, 2)) &&
+           !(self.context.get_version() >= &Version(Api::GlEs, 3, 0))
+        {
+            return Err(LevelsRangeError::NotSupported);
+        }
+
+        if base_level > max_level || max_level >= self.levels {
+            return Err(LevelsRangeError::OutOfRange);
+        }
+
+        if self.levels_range.get() == (base_level, max_level) {
+            return Ok(());
+        }
+
+        let mut ctxt = self.context.make_current();
+        let bind_point = self.bind_to_current(&mut ctxt);
+
+        unsafe {
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_BASE_LEVEL,
+                                  base_level as gl::types::GLint);
+            ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAX_LEVEL,
+                                  max_level as gl::types::GLint);
+        }
+
+        self.levels_range.set((base_level, max_level));
+        Ok(())
+    }
+
+    /// Returns the range of mipmap levels that are used when sampling this texture, as a
+    /// `(base_level, max_level)` tuple.
+    #[inline]
+    pub fn get_levels_range(&self) -> (u32, u32) {
+        self.levels_range.get()
+    }
+
+    /// Clears the main level of the texture to a specific value.
+    ///
+    /// See `clear_rect` for more details.
+    #[inline]
+    pub fn clear<D>(&self, data: D) where D: Into<ClearBufferData> {
+        let rect = Rect {
+            left: 0,
+            bottom: 0,
+            width: self.get_width(),
+            height: self.get_height().unwrap_or(1),
+        };
+
+        self.clear_rect(0, &rect, data)
+    }
+
+    /// Clears a rectangle of a mipmap level of the texture to a specific value.
+    ///
+    /// All the layers of array textures, all the faces of cubemaps and all the slices of 3D
+    /// textures are cleared. The type of `data` must match the kind of texture: `[f32; 4]` for
+    /// regular textures, `[i32; 4]` for integral textures, `[u32; 4]` for unsigned textures,
+    /// `f32` for depth textures, `i32` for stencil textures and `(f32, i32)` for depth-stencil
+    /// textures.
+    ///
+    /// # Implementation
+    ///
+    /// Calls `glClearTexSubImage` if it is supported. Otherwise, each image of the texture is
+    /// attached to a framebuffer and cleared with `glClearBuffer`, which doesn't support depth
+    /// and stencil textures.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the level or the rectangle is out of range.
+    /// - Panics if `data` does not match the kind of texture.
+    ///
+    pub fn clear_rect<D>(&self, level: u32, rect: &Rect, data: D)
+                         where D: Into<ClearBufferData>
+    {
+        let data = data.into();
+
+        let mipmap = self.mipmap(level).expect("The mipmap level is out of range");
+        assert!(rect.left + rect.width <= mipmap.get_width());
+        assert!(rect.bottom + rect.height <= mipmap.get_height().unwrap_or(1));
+
+        match (self.kind(), data) {
+            (TextureKind::Float, ClearBufferData::Float(_)) => (),
+            (TextureKind::Integral, ClearBufferData::Integral(_)) => (),
+            (TextureKind::Unsigned, ClearBufferData::Unsigned(_)) => (),
+            (TextureKind::Depth, ClearBufferData::Depth(_)) => (),
+            (TextureKind::Stencil, ClearBufferData::Stencil(_)) => (),
+            (TextureKind::DepthStencil, ClearBufferData::DepthStencil(_, _)) => (),
+            _ => panic!("The data passed to `clear_rect` does not match the kind of texture"),
+        }
+
+        let mut ctxt = self.context.make_current();
+
+        if ctxt.version >= &Version(Api::Gl, 4, 4) || ctxt.extensions.gl_arb_clear_texture {
+            // for 1D array textures the layers are the second dimension
+            let (yoffset, height, depth) = match self.ty {
+                Dimensions::Texture1dArray { array_size, .. } => (0, array_size, 1),
+                Dimensions::Texture2dArray { array_size, .. } |
+                Dimensions::Texture2dMultisampleArray { array_size, .. } => {
+                    (rect.bottom, rect.height, array_size)
+                },
+                Dimensions::Texture3d { .. } => {
+                    (rect.bottom, rect.height, mipmap.get_depth().unwrap())
+                },
+                Dimensions::Cubemap { .. } => (rect.bottom, rect.height, 6),
+                Dimensions::CubemapArray { array_size, .. } => {
+                    (rect.bottom, rect.height, 6 * array_size)
+                },
+                _ => (rect.bottom, rect.height, 1),
+            };
+
+            let gl = ctxt.gl;
+            let id = self.id;
+            let clear = |format, ty, ptr: *const gl::types::GLvoid| unsafe {
+                gl.ClearTexSubImage(id, level as gl::types::GLint,
+                                    rect.left as gl::types::GLint, yoffset as gl::types::GLint,
+                                    0, rect.width as gl::types::GLsizei,
+                                    height as gl::types::GLsizei, depth as gl::types::GLsizei,
+                                    format, ty, ptr);
+            };
+
+            match data {
+                ClearBufferData::Float(ref value) => {
+                    clear(gl::RGBA, gl::FLOAT, value.as_ptr() as *const _)
+                },
+                ClearBufferData::Integral(ref value) => {
+                    clear(gl::RGBA_INTEGER, gl::INT, value.as_ptr() as *const _)
+                },
+                ClearBufferData::Unsigned(ref value) => {
+                    clear(gl::RGBA_INTEGER, gl::UNSIGNED_INT, value.as_ptr() as *const _)
+                },
+                ClearBufferData::Depth(ref value) => {
+                    clear(gl::DEPTH_COMPONENT, gl::FLOAT, value as *const f32 as *const _)
+                },
+                ClearBufferData::Stencil(ref value) => {
+                    clear(gl::STENCIL_INDEX, gl::INT, value as *const i32 as *const _)
+                },
+                ClearBufferData::DepthStencil(depth, stencil) => {
+                    let value: [u32; 2] = [unsafe { mem::transmute(depth) }, stencil as u32];
+                    clear(gl::DEPTH_STENCIL, gl::FLOAT_32_UNSIGNED_INT_24_8_REV,
+                          value.as_ptr() as *const _)
+                },
+            }
+
+        } else {
+            let faces: &[Option<CubeLayer>] = match self.ty {
+                Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } => {
+                    &[Some(CubeLayer::PositiveX), Some(CubeLayer::NegativeX),
+                      Some(CubeLayer::PositiveY), Some(CubeLayer::NegativeY),
+                      Some(CubeLayer::PositiveZ), Some(CubeLayer::NegativeZ)]
+                },
+                _ => &[None],
+            };
+
+            let layers = mipmap.get_depth().or(self.get_array_size()).unwrap_or(1);
+
+            for layer in 0 .. layers {
+                let layer = mipmap.layer(layer).unwrap();
+
+                for &face in faces {
+                    let attachment = fbo::RegularAttachment::Texture(layer.into_image(face)
+                                                                          .unwrap());
+                    unsafe {
+                        fbo::FramebuffersContainer::clear_buffer(&mut ctxt, &attachment,
+                                                                 Some(rect), data);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generates the content of the mipmaps from the current base level.
+    ///
+    /// With OpenGL 4.5 or `GL_ARB_direct_state_access`, this uses `glGenerateTextureMipmap`
+    /// and the texture doesn't need to be bound.
     pub unsafe fn generate_mipmaps(&self) {
         let mut ctxt = self.context.make_current();
-        self.bind_to_current(&mut ctxt);
-        generate_mipmaps(&ctxt, self.get_bind_point());
+
+        if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+           ctxt.extensions.gl_arb_direct_state_access
+        {
+            ctxt.gl.GenerateTextureMipmap(self.id);
+        } else if ctxt.extensions.gl_ext_direct_state_access {
+            ctxt.gl.GenerateTextureMipmapEXT(self.id, self.get_bind_point());
+        } else {
+            self.bind_to_current(&mut ctxt);
+            generate_mipmaps(&ctxt, self.get_bind_point());
+        }
+    }
+
+    /// Generates the content of the mipmaps `base_level + 1` to `max_level` from the mipmap
+    /// `base_level`.
+    ///
+    /// The levels range of the texture is temporarily changed to `(base_level, max_level)`,
+    /// and restored afterwards. The other mipmaps are left untouched.
+    pub unsafe fn generate_mipmaps_range(&self, base_level: u32, max_level: u32)
+                                         -> Result<(), LevelsRangeError>
+    {
+        let previous = self.levels_range.get();
+        try!(self.set_levels_range(base_level, max_level));
+        self.generate_mipmaps();
+        self.set_levels_range(previous.0, previous.1).unwrap();
+        Ok(())
     }
 }
 
@@ -704,6 +1197,11 @@ impl TextureExt for TextureAny {
         return get_bind_point(self.ty);
     }
 
+    #[inline]
+    fn get_mipmap_levels(&self) -> u32 {
+        self.levels
+    }
+
     fn bind_to_current(&self, ctxt: &mut CommandContext) -> gl::types::GLenum {
         let bind_point = self.get_bind_point();
 
@@ -711,10 +1209,68 @@ impl TextureExt for TextureAny {
         if ctxt.state.texture_units[texture_unit as usize].texture != self.id {
             unsafe { ctxt.gl.BindTexture(bind_point, self.id) };
             ctxt.state.texture_units[texture_unit as usize].texture = self.id;
+            ctxt.state.texture_units[texture_unit as usize].target = bind_point;
         }
 
         bind_point
     }
+
+    fn prepare_for_sampling(&self, ctxt: &mut CommandContext) {
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_texture_fetch {
+            unsafe { ctxt.gl.MemoryBarrier(gl::TEXTURE_FETCH_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_texture_fetch = ctxt.state.next_draw_call_id;
+        }
+    }
+
+    fn prepare_for_image_unit(&self, ctxt: &mut CommandContext, write: bool) {
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_shader_image_access {
+            unsafe { ctxt.gl.MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_shader_image_access = ctxt.state.next_draw_call_id;
+        }
+
+        if write {
+            self.latest_shader_write.set(ctxt.state.next_draw_call_id);
+        }
+    }
+
+    fn prepare_for_framebuffer(&self, ctxt: &mut CommandContext) {
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_framebuffer {
+            unsafe { ctxt.gl.MemoryBarrier(gl::FRAMEBUFFER_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_framebuffer = ctxt.state.next_draw_call_id;
+        }
+    }
+
+    fn emulate_sampler(&self, ctxt: &mut CommandContext, behavior: Option<&SamplerBehavior>)
+                       -> bool
+    {
+        // multisample textures are not filtered
+        if self.get_samples().is_some() {
+            return false;
+        }
+
+        let bind_point = self.bind_to_current(ctxt);
+
+        let (applied, original) = match self.emulated_sampler.get() {
+            Some(parameters) => parameters,
+            None if behavior.is_none() => return false,
+            None => {
+                let original = sampler_object::read_texture_parameters(ctxt, bind_point);
+                (original, original)
+            },
+        };
+
+        let parameters = match behavior {
+            Some(behavior) => sampler_object::get_emulated_parameters(ctxt, bind_point, behavior),
+            None => original,
+        };
+
+        if parameters != applied {
+            sampler_object::write_texture_parameters(ctxt, bind_point, &parameters, &applied);
+        }
+
+        self.emulated_sampler.set(behavior.map(|_| (parameters, original)));
+        parameters != applied
+    }
 }
 
 impl GlObject for TextureAny {
@@ -726,6 +1282,14 @@ impl GlObject for TextureAny {
     }
 }
 
+impl Labeled for TextureAny {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.context.make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::TEXTURE, self.id), label)
+    }
+}
+
 impl fmt::Debug for TextureAny {
     #[inline]
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
@@ -747,9 +1311,20 @@ impl Drop for TextureAny {
             if tex_unit.texture == self.id {
                 tex_unit.texture = 0;
             }
+
+            if tex_unit.pinned_texture == self.id {
+                tex_unit.pinned_texture = 0;
+            }
+        }
+
+        for image_unit in ctxt.state.image_units.iter_mut() {
+            if *image_unit == self.id {
+                *image_unit = 0;
+            }
         }
 
         if self.owned {
+            ctxt.resource_destroyed(ResourceKind::Texture, self.id);
             unsafe { ctxt.gl.DeleteTextures(1, [ self.id ].as_ptr()); }
         }
     }
@@ -982,6 +1557,7 @@ impl<'a> TextureAnyMipmap<'a> {
                                                   self.texture.requested_format, inverted).unwrap();
 
         let mut ctxt = self.texture.context.make_current();
+        ctxt.state.pending_uploads = true;
 
         // binds the pixel buffer
         source.prepare_and_bind_for_pixel_unpack(&mut ctxt);
@@ -1024,7 +1600,7 @@ impl<'a> TextureAnyMipmap<'a> {
             },
 
             Dimensions::Texture1dArray { .. } | Dimensions::Texture2d { .. } |
-            Dimensions::Texture2dMultisample { .. } |
+            Dimensions::Texture2dMultisample { .. } | Dimensions::TextureRectangle { .. } |
             Dimensions::Texture2dMultisampleArray { .. } => {
                 if ctxt.version >= &Version(Api::Gl, 4, 5) ||
                    ctxt.extensions.gl_arb_direct_state_access
@@ -1171,6 +1747,7 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
 
             BufferAny::unbind_pixel_unpack(&mut ctxt);
             let bind_point = self.texture.bind_to_current(&mut ctxt);
+            ctxt.state.pending_uploads = true;
 
             if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY {
                 unimplemented!();
@@ -1430,6 +2007,26 @@ impl<'a> TextureAnyImage<'a> {
         T::from_raw(Cow::Owned(data), self.width, self.height.unwrap_or(1))
     }
 
+    /// Reads the content of the image into a slice.
+    ///
+    /// Returns `ReadError::DestinationTooSmall` if `dest` can't hold all the pixels of `rect`.
+    /// If the backend supports robustness, the size of `dest` is also passed to
+    /// `glReadnPixels` so that the implementation never writes past its end.
+    ///
+    /// # Panic
+    ///
+    /// Panicks if the rect is out of range.
+    ///
+    pub fn raw_read_to_slice<P>(&self, rect: &Rect, dest: &mut [P]) -> Result<(), ReadError>
+        where P: PixelValue
+    {
+        assert!(rect.left + rect.width <= self.width);
+        assert!(rect.bottom + rect.height <= self.height.unwrap_or(1));
+
+        let mut ctxt = self.texture.context.make_current();
+        ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, dest, false)
+    }
+
     /// Reads the content of the image to a pixel buffer.
     ///
     /// # Panic
@@ -1462,7 +2059,208 @@ impl<'a> TextureAnyImage<'a> {
         unsafe {
             let mut ctxt = self.texture.context.make_current();
             let attachment = fbo::RegularAttachment::Texture(*self);
-            fbo::FramebuffersContainer::clear_buffer(&mut ctxt, &attachment, data);
+            fbo::FramebuffersContainer::clear_buffer(&mut ctxt, &attachment, None, data);
         }
     }
+
+    /// Uploads some data in a rectangle of the image.
+    ///
+    /// If this image belongs to the main level of a texture with automatically generated
+    /// mipmaps, the mipmaps are regenerated.
+    ///
+    /// Returns an error if the image belongs to a 3D texture or to a multisample texture, whose
+    /// images can't be written this way.
+    ///
+    /// # Panic
+    ///
+    /// - Panicks if the rect is out of range, or if its dimensions don't match the data.
+    /// - Panicks if the length of the data doesn't match its dimensions and format.
+    /// - Panicks if the client format of the data is not supported for this texture.
+    ///
+    pub fn raw_write<'d, P>(&self, rect: &Rect, data: RawImage2d<'d, P>)
+                            -> Result<(), ImageWriteNotSupportedError>
+        where P: Send + Copy + Clone + 'd
+    {
+        match self.texture.ty {
+            Dimensions::Texture3d { .. } | Dimensions::Texture2dMultisample { .. } |
+            Dimensions::Texture2dMultisampleArray { .. } => {
+                return Err(ImageWriteNotSupportedError);
+            },
+            _ => ()
+        }
+
+        assert_eq!(data.width, rect.width);
+        assert_eq!(data.height, rect.height);
+        assert!(rect.left + rect.width <= self.width);
+        assert!(rect.bottom + rect.height <= self.height.unwrap_or(1));
+
+        let client_format = ClientFormatAny::ClientFormat(data.format);
+        let data_bufsize = client_format.get_buffer_size(rect.width, Some(rect.height), None, None);
+        if data.data.len() * mem::size_of::<P>() != data_bufsize {
+            panic!("Texture data size mismatch");
+        }
+
+        let (client_format, client_type) =
+            image_format::client_format_to_glenum(&self.texture.context, client_format,
+                                                  self.texture.requested_format, false)
+                                                  .expect("Client format not supported");
+
+        let regen_mipmaps = self.level == 0 && self.texture.levels >= 2 &&
+                            self.texture.generate_mipmaps;
+
+        let mut ctxt = self.texture.context.make_current();
+
+        unsafe {
+            if ctxt.state.pixel_store_unpack_alignment != 1 {
+                ctxt.state.pixel_store_unpack_alignment = 1;
+                ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            }
+
+            BufferAny::unbind_pixel_unpack(&mut ctxt);
+            let bind_point = self.texture.bind_to_current(&mut ctxt);
+            ctxt.state.pending_uploads = true;
+
+            match bind_point {
+                gl::TEXTURE_1D => {
+                    ctxt.gl.TexSubImage1D(bind_point, self.level as gl::types::GLint,
+                                          rect.left as gl::types::GLint,
+                                          rect.width as gl::types::GLsizei,
+                                          client_format, client_type,
+                                          data.data.as_ptr() as *const _);
+                },
+                gl::TEXTURE_1D_ARRAY => {
+                    ctxt.gl.TexSubImage2D(bind_point, self.level as gl::types::GLint,
+                                          rect.left as gl::types::GLint,
+                                          self.layer as gl::types::GLint,
+                                          rect.width as gl::types::GLsizei, 1,
+                                          client_format, client_type,
+                                          data.data.as_ptr() as *const _);
+                },
+                gl::TEXTURE_2D | gl::TEXTURE_RECTANGLE | gl::TEXTURE_CUBE_MAP => {
+                    let target = match self.cube_layer {
+                        Some(face) => gl::TEXTURE_CUBE_MAP_POSITIVE_X +
+                                      face.get_layer_index() as gl::types::GLenum,
+                        None => bind_point,
+                    };
+
+                    ctxt.gl.TexSubImage2D(target, self.level as gl::types::GLint,
+                                          rect.left as gl::types::GLint,
+                                          rect.bottom as gl::types::GLint,
+                                          rect.width as gl::types::GLsizei,
+                                          rect.height as gl::types::GLsizei,
+                                          client_format, client_type,
+                                          data.data.as_ptr() as *const _);
+                },
+                gl::TEXTURE_2D_ARRAY | gl::TEXTURE_CUBE_MAP_ARRAY => {
+                    ctxt.gl.TexSubImage3D(bind_point, self.level as gl::types::GLint,
+                                          rect.left as gl::types::GLint,
+                                          rect.bottom as gl::types::GLint,
+                                          image_z(self) as gl::types::GLint,
+                                          rect.width as gl::types::GLsizei,
+                                          rect.height as gl::types::GLsizei, 1,
+                                          client_format, client_type,
+                                          data.data.as_ptr() as *const _);
+                },
+                _ => unreachable!()
+            }
+
+            if regen_mipmaps {
+                generate_mipmaps(&ctxt, bind_point);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copies a rectangle of this image to another image, without any conversion.
+    ///
+    /// `dest_offset` is the position of the bottom-left corner of the rectangle in the
+    /// destination. The images can belong to the same texture, to different mipmap levels or to
+    /// different layers. Contrary to blitting, the textures don't need to be attachable to a
+    /// framebuffer.
+    ///
+    /// The formats of both textures must have the same number of bits per texel. Compressed
+    /// formats are only compatible with themselves. The formats are checked before the support
+    /// of the backend, so that `NotSupported` is only returned for compatible formats.
+    ///
+    /// ## Low-level information
+    ///
+    /// This function uses `glCopyImageSubData` and requires OpenGL 4.3, OpenGL ES 3.2, or the
+    /// `GL_ARB_copy_image` extension.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the source rectangle is out of the bounds of this image, or if the copied
+    /// rectangle is out of the bounds of the destination.
+    ///
+    pub fn copy_to(&self, source_rect: &Rect, dest: TextureAnyImage, dest_offset: (u32, u32))
+                   -> Result<(), CopyImageError>
+    {
+        assert!(source_rect.left + source_rect.width <= self.width);
+        assert!(source_rect.bottom + source_rect.height <= self.height.unwrap_or(1));
+        assert!(dest_offset.0 + source_rect.width <= dest.width);
+        assert!(dest_offset.1 + source_rect.height <= dest.height.unwrap_or(1));
+
+        if self.texture.requested_format != dest.texture.requested_format {
+            let compressed = |f: TextureFormatRequest| match f {
+                TextureFormatRequest::Specific(TextureFormat::CompressedFormat(_)) => true,
+                TextureFormatRequest::Specific(TextureFormat::CompressedSrgbFormat(_)) => true,
+                TextureFormatRequest::AnyCompressed => true,
+                TextureFormatRequest::AnyCompressedSrgb => true,
+                _ => false,
+            };
+
+            if compressed(self.texture.requested_format) ||
+               compressed(dest.texture.requested_format)
+            {
+                return Err(CopyImageError::IncompatibleFormats);
+            }
+
+            // if the formats can't be retreived, we let OpenGL decide
+            if let (Ok(src), Ok(dst)) = (self.texture.get_internal_format(),
+                                         dest.texture.get_internal_format())
+            {
+                if src.get_total_bits() != dst.get_total_bits() {
+                    return Err(CopyImageError::IncompatibleFormats);
+                }
+            }
+        }
+
+        let src_z = image_z(self);
+        let dst_z = image_z(&dest);
+
+        let ctxt = self.texture.context.make_current();
+
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3)) &&
+           !(ctxt.version >= &Version(Api::GlEs, 3, 2)) &&
+           !ctxt.extensions.gl_arb_copy_image
+        {
+            return Err(CopyImageError::NotSupported);
+        }
+
+        unsafe {
+            ctxt.gl.CopyImageSubData(self.texture.id, self.texture.get_bind_point(),
+                                     self.level as gl::types::GLint,
+                                     source_rect.left as gl::types::GLint,
+                                     source_rect.bottom as gl::types::GLint,
+                                     src_z as gl::types::GLint,
+                                     dest.texture.id, dest.texture.get_bind_point(),
+                                     dest.level as gl::types::GLint,
+                                     dest_offset.0 as gl::types::GLint,
+                                     dest_offset.1 as gl::types::GLint,
+                                     dst_z as gl::types::GLint,
+                                     source_rect.width as gl::types::GLsizei,
+                                     source_rect.height as gl::types::GLsizei, 1);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the `z` coordinate of an image when passed to `glCopyImageSubData`.
+fn image_z(image: &TextureAnyImage) -> u32 {
+    match image.cube_layer {
+        Some(face) => image.layer * 6 + face.get_layer_index() as u32,
+        None => image.layer,
+    }
 }
@@ -56,6 +56,35 @@ currently doesn't check whether the type of your texture matches the expected ty
 do in the future). Binding the wrong type of texture may lead to undefined values when sampling
 the texture.
 
+# Arrays of handles
+
+If you have many materials, you can store the handles of all their textures in a single buffer
+with a `BindlessTexturesBuffer`, then index this array in your shader with a material id.
+
+```no_run
+#[macro_use]
+extern crate glium;
+
+# fn main() {
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let texture1: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+# let texture2: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+use glium::texture::bindless::{BindlessTexturesBuffer, HandlesLayout};
+
+let textures = BindlessTexturesBuffer::new(&display, &[&texture1, &texture2],
+                                           HandlesLayout::Std430).unwrap();
+let uniforms = uniform! { Materials: &textures };
+# }
+```
+
+The matching shader code would look like this:
+
+```notrust
+layout(std430) buffer Materials {
+    sampler2D textures[];
+};
+```
+
 */
 use texture::any::TextureAny;
 use TextureExt;
@@ -64,10 +93,19 @@ use GlObject;
 use ContextExt;
 use gl;
 
+use backend::Facade;
+use buffer::{Buffer, BufferAny, BufferType, BufferMode, BufferCreationError};
+use context::CommandContext;
+use context::Context;
+
+use std::error::Error;
+use std::fmt;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 
 use program::BlockLayout;
+use program;
 use uniforms::AsUniformValue;
 use uniforms::LayoutMismatchError;
 use uniforms::UniformBlock;
@@ -94,8 +132,7 @@ impl ResidentTexture {
             }
 
             let handle = unsafe { ctxt.gl.GetTextureHandleARB(texture.get_id()) };
-            unsafe { ctxt.gl.MakeTextureHandleResidentARB(handle) };
-            ctxt.resident_texture_handles.push(handle);
+            make_resident(&mut ctxt, handle);
             handle
         };
 
@@ -118,8 +155,7 @@ impl ResidentTexture {
 
         {
             let mut ctxt = texture.get_context().make_current();
-            unsafe { ctxt.gl.MakeTextureHandleNonResidentARB(self.handle) };
-            ctxt.resident_texture_handles.retain(|&t| t != self.handle);
+            make_non_resident(&mut ctxt, self.handle);
         }
 
         texture
@@ -149,6 +185,32 @@ impl Drop for ResidentTexture {
     }
 }
 
+/// Makes a texture handle resident, or increments the number of objects that need it if it is
+/// already resident. Making a handle resident twice is an error.
+fn make_resident(ctxt: &mut CommandContext, handle: gl::types::GLuint64) {
+    let count = ctxt.resident_texture_handles.entry(handle).or_insert(0);
+
+    if *count == 0 {
+        unsafe { ctxt.gl.MakeTextureHandleResidentARB(handle) };
+    }
+
+    *count += 1;
+}
+
+/// Decrements the number of objects that need a texture handle, and makes it non-resident when
+/// no object needs it anymore.
+fn make_non_resident(ctxt: &mut CommandContext, handle: gl::types::GLuint64) {
+    let remove = match ctxt.resident_texture_handles.get_mut(&handle) {
+        Some(count) => { *count -= 1; *count == 0 },
+        None => return,
+    };
+
+    if remove {
+        ctxt.resident_texture_handles.remove(&handle);
+        unsafe { ctxt.gl.MakeTextureHandleNonResidentARB(handle) };
+    }
+}
+
 /// Represents a handle to a texture. Contains a raw pointer to a texture that is hidden from you.
 #[derive(Copy, Clone)]
 pub struct TextureHandle<'a> {
@@ -290,6 +352,165 @@ impl<'a> UniformBlock for TextureHandle<'a> {
 #[derive(Debug, Copy, Clone)]
 pub struct BindlessTexturesNotSupportedError;
 
+/// Layout of the array of handles inside a `BindlessTexturesBuffer`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HandlesLayout {
+    /// The `std140` layout, where each element of an array is aligned to 16 bytes. This is the
+    /// layout to use for uniform blocks.
+    Std140,
+
+    /// The `std430` layout, where the handles are tightly packed. This is the layout to use for
+    /// shader storage blocks.
+    Std430,
+}
+
+/// Error that can happen when creating a `BindlessTexturesBuffer`.
+#[derive(Debug, Copy, Clone)]
+pub enum BindlessTexturesBufferCreationError {
+    /// Bindless textures are not supported.
+    BindlessTexturesNotSupported,
+
+    /// Error while creating the buffer.
+    BufferCreationError(BufferCreationError),
+}
+
+impl fmt::Display for BindlessTexturesBufferCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for BindlessTexturesBufferCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        use self::BindlessTexturesBufferCreationError::*;
+        match *self {
+            BindlessTexturesNotSupported => "Bindless textures are not supported",
+            BufferCreationError(_) => "Error while creating the buffer",
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&Error> {
+        use self::BindlessTexturesBufferCreationError::*;
+        match *self {
+            BufferCreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<BufferCreationError> for BindlessTexturesBufferCreationError {
+    #[inline]
+    fn from(err: BufferCreationError) -> BindlessTexturesBufferCreationError {
+        BindlessTexturesBufferCreationError::BufferCreationError(err)
+    }
+}
+
+/// A buffer that contains the handles of a list of textures.
+///
+/// The textures are made resident when the buffer is created, and stay resident as long as the
+/// buffer or any other `ResidentTexture` or `BindlessTexturesBuffer` that uses them is alive.
+/// The buffer borrows the textures, so they can't be destroyed in the meantime.
+///
+/// With `HandlesLayout::Std140`, the buffer is meant to be used as a uniform block. With
+/// `HandlesLayout::Std430`, it is meant to be used as a shader storage block. In both cases, the
+/// block must contain a single array of samplers, of `uint64_t` or of `uvec2`.
+pub struct BindlessTexturesBuffer<'a> {
+    context: Rc<Context>,
+    buffer: BufferAny,
+    len: usize,
+    // handles that this buffer needs to stay resident
+    resident_handles: Vec<gl::types::GLuint64>,
+    marker: PhantomData<&'a ()>,
+}
+
+impl<'a> BindlessTexturesBuffer<'a> {
+    /// Makes the textures resident and writes their handles in a new buffer.
+    ///
+    /// `textures` can contain any type of texture, like `Texture2d` or `ResidentTexture`.
+    pub fn new<F: ?Sized, T: ?Sized>(facade: &F, textures: &[&'a T], layout: HandlesLayout)
+                                     -> Result<BindlessTexturesBuffer<'a>,
+                                               BindlessTexturesBufferCreationError>
+                                     where F: Facade, T: Deref<Target = TextureAny>
+    {
+        let mut resident_handles = Vec::new();
+
+        let handles = {
+            let mut ctxt = facade.get_context().make_current();
+
+            if !ctxt.extensions.gl_arb_bindless_texture {
+                return Err(BindlessTexturesBufferCreationError::BindlessTexturesNotSupported);
+            }
+
+            textures.iter().map(|texture| {
+                let texture: &TextureAny = &***texture;
+                let handle = unsafe { ctxt.gl.GetTextureHandleARB(texture.get_id()) };
+                make_resident(&mut ctxt, handle);
+                resident_handles.push(handle);
+                handle
+            }).collect::<Vec<_>>()
+        };
+
+        let (data, ty) = match layout {
+            HandlesLayout::Std140 => {
+                (handles.iter().flat_map(|&h| vec![h, 0]).collect::<Vec<_>>(),
+                 BufferType::UniformBuffer)
+            },
+            HandlesLayout::Std430 => (handles, BufferType::ShaderStorageBuffer),
+        };
+
+        let buffer = match Buffer::<[u64]>::new(facade, &data, ty, BufferMode::Default) {
+            Ok(b) => b,
+            Err(err) => {
+                let mut ctxt = facade.get_context().make_current();
+                for &handle in &resident_handles {
+                    make_non_resident(&mut ctxt, handle);
+                }
+                return Err(err.into());
+            }
+        };
+
+        Ok(BindlessTexturesBuffer {
+            context: facade.get_context().clone(),
+            buffer: buffer.into(),
+            len: textures.len(),
+            resident_handles: resident_handles,
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of handles in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a> Drop for BindlessTexturesBuffer<'a> {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        for &handle in &self.resident_handles {
+            make_non_resident(&mut ctxt, handle);
+        }
+    }
+}
+
+impl<'a, 'b> AsUniformValue for &'b BindlessTexturesBuffer<'a> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        fn f(block: &program::UniformBlock) -> Result<(), LayoutMismatchError> {
+            <[TextureHandle] as UniformBlock>::matches(&block.layout, 0)
+                .or_else(|_| <[u64] as UniformBlock>::matches(&block.layout, 0))
+                .or_else(|_| <[[u32; 2]] as UniformBlock>::matches(&block.layout, 0))
+        }
+
+        UniformValue::Block(self.buffer.as_slice_any(), f)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::mem;
@@ -64,6 +64,7 @@ use version::Api;
 use backend::Facade;
 use context::Context;
 use context::CommandContext;
+use context::ResourceKind;
 use ContextExt;
 use GlObject;
 
@@ -78,6 +79,7 @@ use buffer::Content as BufferContent;
 
 use uniforms::AsUniformValue;
 use uniforms::UniformValue;
+use uniforms::SamplerBehavior;
 
 /// Error that can happen while building the texture part of a buffer texture.
 #[derive(Copy, Clone, Debug)]
@@ -399,6 +401,7 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
             unsafe {
                 let mut id = mem::uninitialized();
                 ctxt.gl.CreateTextures(gl::TEXTURE_BUFFER, 1, &mut id);
+                ctxt.resource_created(ResourceKind::Texture, id);
                 ctxt.gl.TextureBuffer(id, internal_format, buffer.get_id());
                 id
             }
@@ -408,6 +411,7 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
             let id = unsafe {
                 let mut id = mem::uninitialized();
                 ctxt.gl.GenTextures(1, &mut id);
+                ctxt.resource_created(ResourceKind::Texture, id);
                 id
             };
 
@@ -416,6 +420,7 @@ impl<T> BufferTexture<T> where [T]: BufferContent, T: TextureBufferContent + Cop
                 ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, id);
                 let act = ctxt.state.active_texture as usize;
                 ctxt.state.texture_units[act].texture = id;
+                ctxt.state.texture_units[act].target = gl::TEXTURE_BUFFER;
             }
 
             // binding the buffer
@@ -487,6 +492,7 @@ impl<T> Drop for BufferTexture<T> where [T]: BufferContent {
             }
         }
 
+        ctxt.resource_destroyed(ResourceKind::Texture, self.texture);
         unsafe { ctxt.gl.DeleteTextures(1, [ self.texture ].as_ptr()); }
     }
 }
@@ -551,11 +557,39 @@ impl<'a> TextureExt for BufferTextureRef<'a> {
         gl::TEXTURE_BUFFER
     }
 
+    #[inline]
+    fn get_mipmap_levels(&self) -> u32 {
+        1
+    }
+
     #[inline]
     fn bind_to_current(&self, ctxt: &mut CommandContext) -> gl::types::GLenum {
         unsafe { ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, self.texture); }
+        let act = ctxt.state.active_texture as usize;
+        ctxt.state.texture_units[act].texture = self.texture;
+        ctxt.state.texture_units[act].target = gl::TEXTURE_BUFFER;
         gl::TEXTURE_BUFFER
     }
+
+    #[inline]
+    fn prepare_for_sampling(&self, _: &mut CommandContext) {
+        // buffer textures are never bound to image units by glium
+    }
+
+    #[inline]
+    fn prepare_for_image_unit(&self, _: &mut CommandContext, _: bool) {
+    }
+
+    #[inline]
+    fn prepare_for_framebuffer(&self, _: &mut CommandContext) {
+        // buffer textures can't be attached to a framebuffer
+    }
+
+    #[inline]
+    fn emulate_sampler(&self, _: &mut CommandContext, _: Option<&SamplerBehavior>) -> bool {
+        // buffer textures are not filtered
+        false
+    }
 }
 
 ///
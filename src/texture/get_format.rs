@@ -273,3 +273,93 @@ pub fn get_format(ctxt: &mut CommandContext, texture: &TextureAny)
         Err(GetFormatError::NotSupported)
     }
 }
+
+/// Information about a mipmap level of a texture, as reported by the backend.
+///
+/// Contrary to the format that has been requested when creating the texture, these values
+/// describe what the implementation actually allocated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TextureLevelInfo {
+    /// Width of the level.
+    pub width: u32,
+    /// Height of the level, or `1` for one-dimensional textures.
+    pub height: u32,
+    /// Depth of the level, or `1` for textures that aren't three-dimensional.
+    pub depth: u32,
+    /// Value of `GL_TEXTURE_INTERNAL_FORMAT`.
+    pub internal_format: gl::types::GLenum,
+    /// Number of bits of the red component.
+    pub red_bits: u32,
+    /// Number of bits of the green component.
+    pub green_bits: u32,
+    /// Number of bits of the blue component.
+    pub blue_bits: u32,
+    /// Number of bits of the alpha component.
+    pub alpha_bits: u32,
+    /// Number of bits of the depth component.
+    pub depth_bits: u32,
+    /// Number of bits of the stencil component.
+    pub stencil_bits: u32,
+    /// If the level is compressed, contains its size in bytes.
+    ///
+    /// Always `None` on OpenGL ES, which can't query this value.
+    pub compressed_size: Option<usize>,
+}
+
+/// Queries the parameters of a mipmap level of a texture.
+pub fn query_level(ctxt: &mut CommandContext, texture: &TextureAny, level: u32)
+                   -> Result<TextureLevelInfo, GetFormatError>
+{
+    if !(ctxt.version >= &Version(Api::Gl, 1, 0) || ctxt.version >= &Version(Api::GlEs, 3, 1)) {
+        return Err(GetFormatError::NotSupported);
+    }
+
+    let id = texture.get_id();
+    let level = level as gl::types::GLint;
+
+    // without DSA, the parameters of a cubemap must be queried on one of its faces
+    let dsa = ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access;
+    let bind_point = if dsa {
+        0
+    } else {
+        match texture.bind_to_current(ctxt) {
+            gl::TEXTURE_CUBE_MAP => gl::TEXTURE_CUBE_MAP_POSITIVE_X,
+            bind_point => bind_point,
+        }
+    };
+
+    let gl = ctxt.gl;
+    let get = |pname| -> gl::types::GLint {
+        unsafe {
+            let mut value = 0;
+            if dsa {
+                gl.GetTextureLevelParameteriv(id, level, pname, &mut value);
+            } else {
+                gl.GetTexLevelParameteriv(bind_point, level, pname, &mut value);
+            }
+            value
+        }
+    };
+
+    let compressed_size = if ctxt.version >= &Version(Api::Gl, 1, 3) &&
+                             get(gl::TEXTURE_COMPRESSED) != 0
+    {
+        Some(get(gl::TEXTURE_COMPRESSED_IMAGE_SIZE) as usize)
+    } else {
+        None
+    };
+
+    Ok(TextureLevelInfo {
+        width: get(gl::TEXTURE_WIDTH) as u32,
+        height: get(gl::TEXTURE_HEIGHT) as u32,
+        depth: get(gl::TEXTURE_DEPTH) as u32,
+        internal_format: get(gl::TEXTURE_INTERNAL_FORMAT) as gl::types::GLenum,
+        red_bits: get(gl::TEXTURE_RED_SIZE) as u32,
+        green_bits: get(gl::TEXTURE_GREEN_SIZE) as u32,
+        blue_bits: get(gl::TEXTURE_BLUE_SIZE) as u32,
+        alpha_bits: get(gl::TEXTURE_ALPHA_SIZE) as u32,
+        depth_bits: get(gl::TEXTURE_DEPTH_SIZE) as u32,
+        stencil_bits: get(gl::TEXTURE_STENCIL_SIZE) as u32,
+        compressed_size: compressed_size,
+    })
+}
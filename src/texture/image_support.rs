@@ -0,0 +1,56 @@
+//! Conversions between glium textures and the types of the `image` crate.
+//!
+//! Images of the `image` crate start with their top-left pixel, while OpenGL textures start with
+//! their bottom-left pixel. All the conversions of this module flip the image vertically, so
+//! that an image loaded from a file doesn't appear upside-down.
+
+use image::{DynamicImage, ImageBuffer, RgbaImage};
+
+use backend::Facade;
+use texture::{ClientFormat, RawImage2d, Texture2d, TextureCreationError};
+
+impl<'a> From<RgbaImage> for RawImage2d<'a, u8> {
+    fn from(image: RgbaImage) -> RawImage2d<'a, u8> {
+        let dimensions = image.dimensions();
+        RawImage2d::from_raw_rgba_reversed(&image.into_raw(), dimensions)
+    }
+}
+
+impl Texture2d {
+    /// Builds a new texture from an image of the `image` crate.
+    ///
+    /// The image is converted to RGBA and flipped vertically, so that its top-left pixel ends
+    /// at the texture coordinates `(0.0, 1.0)`.
+    ///
+    /// *This function is only available if the `image` feature of glium is enabled.*
+    pub fn from_image<F: ?Sized>(facade: &F, image: &DynamicImage)
+                                 -> Result<Texture2d, TextureCreationError> where F: Facade
+    {
+        let image: RawImage2d<u8> = image.to_rgba().into();
+        Texture2d::new(facade, image)
+    }
+
+    /// Reads the content of the texture into an RGBA image of the `image` crate.
+    ///
+    /// This is the reverse operation of `from_image`: the texture is flipped vertically, so that
+    /// its texel at the coordinates `(0.0, 1.0)` becomes the top-left pixel of the image.
+    ///
+    /// *This function is only available if the `image` feature of glium is enabled.*
+    pub fn read_to_image(&self) -> RgbaImage {
+        let raw: RawImage2d<u8> = self.read();
+        debug_assert_eq!(raw.format, ClientFormat::U8U8U8U8);
+
+        let data = flip_rows(&raw.data, raw.width as usize * 4);
+        ImageBuffer::from_raw(raw.width, raw.height, data)
+                    .expect("The size of the data doesn't match the dimensions of the texture")
+    }
+}
+
+/// Reverses the order of the rows of the data.
+fn flip_rows(data: &[u8], row_length: usize) -> Vec<u8> {
+    if row_length == 0 {
+        return Vec::new();
+    }
+
+    data.chunks(row_length).rev().flat_map(|row| row.iter().cloned()).collect()
+}
@@ -124,22 +124,30 @@ pub use image_format::{CompressedFormat, DepthFormat, DepthStencilFormat, Stenci
 pub use image_format::{CompressedSrgbFormat, SrgbFormat};
 pub use self::any::{TextureAny, TextureAnyMipmap, TextureAnyLayer, TextureAnyLayerMipmap};
 pub use self::any::{TextureAnyImage, Dimensions};
+pub use ops::ReadError;
 pub use self::bindless::{ResidentTexture, TextureHandle, BindlessTexturesNotSupportedError};
 pub use self::get_format::{InternalFormat, InternalFormatType, GetFormatError};
+pub use self::get_format::TextureLevelInfo;
 pub use self::pixel::PixelValue;
+pub use self::resizable::{ResizableTexture2d, ResizableDepthTexture2d};
 pub use self::ty_support::{is_texture_1d_supported, is_texture_2d_supported};
 pub use self::ty_support::{is_texture_3d_supported, is_texture_1d_array_supported};
 pub use self::ty_support::{is_texture_2d_array_supported, is_texture_2d_multisample_supported};
 pub use self::ty_support::{is_texture_2d_multisample_array_supported, is_cubemaps_supported};
-pub use self::ty_support::is_cubemap_arrays_supported;
+pub use self::ty_support::{is_cubemap_arrays_supported, is_texture_swizzle_supported};
+pub use self::ty_support::{is_texture_rectangle_supported, is_stencil_texturing_supported};
 
 pub mod bindless;
 pub mod buffer_texture;
 pub mod pixel_buffer;
+pub mod sparse;
 
 mod any;
 mod get_format;
+#[cfg(feature = "image")]
+mod image_support;
 mod pixel;
+mod resizable;
 mod ty_support;
 
 include!(concat!(env!("OUT_DIR"), "/textures.rs"));
@@ -186,7 +194,115 @@ pub enum TextureKind {
     DepthStencil,
 }
 
+/// Source of a component of the color returned when sampling a texture.
+///
+/// See `TextureAny::set_swizzle_mask`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Swizzle {
+    /// The red component of the texture.
+    Red,
+    /// The green component of the texture.
+    Green,
+    /// The blue component of the texture.
+    Blue,
+    /// The alpha component of the texture.
+    Alpha,
+    /// The constant value `0`.
+    Zero,
+    /// The constant value `1`.
+    One,
+}
+
+/// Changing the swizzle mask of textures is not supported by the backend.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TextureSwizzleNotSupportedError;
+
+impl fmt::Display for TextureSwizzleNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for TextureSwizzleNotSupportedError {
+    #[inline]
+    fn description(&self) -> &str {
+        "Changing the swizzle mask of textures is not supported by the backend"
+    }
+}
+
+/// Which component of a depth-stencil texture is returned when sampling it.
+///
+/// See `TextureAny::set_depth_stencil_mode`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DepthStencilMode {
+    /// The depth component is returned. The texture must be sampled with a `sampler*` or a
+    /// `sampler*Shadow`. This is the default.
+    Depth,
+    /// The stencil component is returned. The texture must be sampled with a `usampler*`.
+    Stencil,
+}
+
+/// Sampling the stencil component of depth-stencil textures is not supported by the backend.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StencilTexturingNotSupportedError;
+
+impl fmt::Display for StencilTexturingNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for StencilTexturingNotSupportedError {
+    #[inline]
+    fn description(&self) -> &str {
+        "Sampling the stencil component of depth-stencil textures is not supported by the backend"
+    }
+}
+
+/// Error that can happen when calling `TextureAny::set_levels_range`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LevelsRangeError {
+    /// Changing the base and max levels of a texture is not supported by the backend.
+    NotSupported,
+
+    /// The base level is greater than the max level, or the max level has not been allocated.
+    OutOfRange,
+}
+
+impl fmt::Display for LevelsRangeError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for LevelsRangeError {
+    fn description(&self) -> &str {
+        use self::LevelsRangeError::*;
+        match *self {
+            NotSupported =>
+                "Changing the base and max levels of a texture is not supported by the backend",
+            OutOfRange =>
+                "The base level is greater than the max level, or the max level has not been \
+                 allocated",
+        }
+    }
+}
+
 /// Describes what to do about mipmaps during texture creation.
+///
+/// The number of levels that are allocated is fixed when the texture is created. Only these
+/// levels are used when sampling, so a texture created with `EmptyMipmapsMax(2)` will never try
+/// to read a fourth level.
+///
+/// ## Interaction with the minify filter
+///
+/// The `*Mipmap*` variants of `MinifySamplerFilter` read from the mipmap levels. If you use
+/// `EmptyMipmaps` or `EmptyMipmapsMax`, you must write all the levels yourself before drawing,
+/// otherwise these filters will read undefined data. With `NoMipmap`, these filters behave like
+/// `Nearest` or `Linear`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum MipmapsOption {
     /// No mipmap will be allocated or generated.
@@ -773,6 +889,16 @@ pub enum TextureCreationError {
 
     /// The texture format is not supported by the backend.
     TypeNotSupported,
+
+    /// The length of the data doesn't match the dimensions of the texture and the format of
+    /// the data.
+    DataSizeMismatch,
+
+    /// The requested number of samples is zero or is above `GL_MAX_SAMPLES`.
+    SamplesNotSupported,
+
+    /// Mipmaps have been requested for a texture type that can't have any.
+    MipmapsNotSupported,
 }
 
 impl fmt::Display for TextureCreationError {
@@ -791,10 +917,63 @@ impl Error for TextureCreationError {
                 "The requested texture dimensions are not supported",
             TypeNotSupported =>
                 "The texture format is not supported by the backend",
+            DataSizeMismatch =>
+                "The length of the data doesn't match the dimensions and format of the texture",
+            SamplesNotSupported =>
+                "The requested number of samples is not supported",
+            MipmapsNotSupported =>
+                "Mipmaps have been requested for a texture type that can't have any",
         }
     }
 }
 
+/// Error that can happen when copying an image of a texture to another one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CopyImageError {
+    /// Copying images is not supported by the backend.
+    NotSupported,
+
+    /// The formats of the two textures are not compatible.
+    IncompatibleFormats,
+}
+
+impl fmt::Display for CopyImageError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for CopyImageError {
+    fn description(&self) -> &str {
+        use self::CopyImageError::*;
+        match *self {
+            NotSupported =>
+                "Copying images is not supported by the backend",
+            IncompatibleFormats =>
+                "The formats of the two textures are not compatible",
+        }
+    }
+}
+
+/// Writing the images of this kind of texture with `TextureAnyImage::raw_write` is not
+/// supported.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ImageWriteNotSupportedError;
+
+impl fmt::Display for ImageWriteNotSupportedError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ImageWriteNotSupportedError {
+    #[inline]
+    fn description(&self) -> &str {
+        "The images of 3D textures and multisample textures can't be written"
+    }
+}
+
 impl From<FormatNotSupportedError> for TextureCreationError {
     #[inline]
     fn from(_: FormatNotSupportedError) -> TextureCreationError {
@@ -0,0 +1,92 @@
+use std::ops::Deref;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use ContextExt;
+
+use texture::{Texture2d, DepthTexture2d, MipmapsOption, TextureCreationError};
+use texture::{UncompressedFloatFormat, DepthFormat};
+
+macro_rules! resizable_texture {
+    ($(#[$attr:meta])* struct $name:ident, $tex:ident, $format:ident) => (
+        $(#[$attr])*
+        pub struct $name {
+            context: Rc<Context>,
+            texture: $tex,
+            format: $format,
+            mipmaps: MipmapsOption,
+        }
+
+        impl $name {
+            /// Builds a new texture with the given format and dimensions.
+            pub fn new<F: ?Sized>(facade: &F, format: $format, mipmaps: MipmapsOption,
+                                  width: u32, height: u32)
+                                  -> Result<$name, TextureCreationError> where F: Facade
+            {
+                let texture = try!($tex::empty_with_format(facade, format, mipmaps,
+                                                           width, height));
+
+                Ok($name {
+                    context: facade.get_context().clone(),
+                    texture: texture,
+                    format: format,
+                    mipmaps: mipmaps,
+                })
+            }
+
+            /// Changes the dimensions of the texture.
+            ///
+            /// Nothing happens if the dimensions didn't change. Otherwise a new texture is
+            /// created with the same format and mipmaps, and the old one is destroyed. The
+            /// content of the texture is undefined after it has been resized.
+            ///
+            /// If an error happens, the old texture is kept.
+            pub fn resize(&mut self, width: u32, height: u32) -> Result<(), TextureCreationError> {
+                if self.texture.get_width() == width && self.texture.get_height() == Some(height) {
+                    return Ok(());
+                }
+
+                self.texture = try!($tex::empty_with_format(&self.context, self.format,
+                                                            self.mipmaps, width, height));
+                Ok(())
+            }
+
+            /// Returns the texture.
+            #[inline]
+            pub fn get_texture(&self) -> &$tex {
+                &self.texture
+            }
+        }
+
+        impl Deref for $name {
+            type Target = $tex;
+
+            #[inline]
+            fn deref(&self) -> &$tex {
+                &self.texture
+            }
+        }
+    );
+}
+
+resizable_texture!(
+    /// A `Texture2d` that can be recreated with different dimensions, for example when the
+    /// window is resized.
+    ///
+    /// Textures are created with immutable storage whenever possible, so resizing creates a new
+    /// texture object. Framebuffers that use the texture must be created again after a resize,
+    /// which is cheap as glium caches framebuffer objects.
+    ///
+    /// Samplers are not part of the texture in glium, so the sampling parameters passed when
+    /// drawing don't need to be changed.
+    struct ResizableTexture2d, Texture2d, UncompressedFloatFormat
+);
+
+resizable_texture!(
+    /// A `DepthTexture2d` that can be recreated with different dimensions, for example when the
+    /// window is resized.
+    ///
+    /// See the documentation of `ResizableTexture2d`.
+    struct ResizableDepthTexture2d, DepthTexture2d, DepthFormat
+);
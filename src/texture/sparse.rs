@@ -0,0 +1,284 @@
+/*!
+Sparse textures are textures whose storage is only partially allocated.
+
+*Sparse textures are supported only by recent hardware and drivers, through the
+`GL_ARB_sparse_texture` extension.*
+
+The whole texture is addressable, but the video memory is allocated by chunks named *pages*.
+Calling `commit_region` allocates or deallocates the pages that cover a region of a mipmap level.
+Sampling a region that has no committed page returns undefined values, which are zero on most
+implementations.
+
+This is useful for very large textures, for example terrain textures, of which only a small part
+is needed at a given time.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+use glium::texture::sparse::SparseTexture2d;
+
+let texture = SparseTexture2d::new(&display, glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                   glium::texture::MipmapsOption::NoMipmap,
+                                   16384, 16384).unwrap();
+
+let (page_width, page_height) = texture.get_page_size();
+texture.commit_region(0, glium::Rect { left: 0, bottom: 0,
+                                        width: page_width, height: page_height }, true);
+```
+
+*/
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+
+use backend::Facade;
+use CapabilitiesSource;
+use ContextExt;
+use TextureExt;
+use Rect;
+use gl;
+use context::ResourceKind;
+
+use image_format::{self, TextureFormatRequest, RequestType};
+
+use texture::{Texture2d, Dimensions, MipmapsOption, UncompressedFloatFormat};
+
+/// A two-dimensional texture whose storage is allocated page by page.
+///
+/// See the documentation of the `sparse` module.
+pub struct SparseTexture2d {
+    texture: Texture2d,
+    page_size: (u32, u32),
+}
+
+impl SparseTexture2d {
+    /// Returns true if the backend supports sparse textures.
+    #[inline]
+    pub fn is_supported<F: ?Sized>(facade: &F) -> bool where F: Facade {
+        facade.get_context().get_extensions().gl_arb_sparse_texture
+    }
+
+    /// Returns the size of a page for the given format, or `None` if sparse textures with this
+    /// format are not supported.
+    pub fn get_page_size_for_format<F: ?Sized>(facade: &F, format: UncompressedFloatFormat)
+                                               -> Option<(u32, u32)> where F: Facade
+    {
+        if !SparseTexture2d::is_supported(facade) {
+            return None;
+        }
+
+        let format = TextureFormatRequest::Specific(format.to_texture_format());
+        let internal_format = match image_format::format_request_to_glenum(facade.get_context(),
+                                                                           format,
+                                                                           RequestType::TexStorage)
+        {
+            Ok(f) => f,
+            Err(_) => return None,
+        };
+
+        let ctxt = facade.get_context().make_current();
+
+        unsafe {
+            let mut num_sizes = 0;
+            ctxt.gl.GetInternalformativ(gl::TEXTURE_2D, internal_format,
+                                        gl::NUM_VIRTUAL_PAGE_SIZES_ARB, 1, &mut num_sizes);
+            if num_sizes == 0 {
+                return None;
+            }
+
+            let mut width = 0;
+            let mut height = 0;
+            ctxt.gl.GetInternalformativ(gl::TEXTURE_2D, internal_format,
+                                        gl::VIRTUAL_PAGE_SIZE_X_ARB, 1, &mut width);
+            ctxt.gl.GetInternalformativ(gl::TEXTURE_2D, internal_format,
+                                        gl::VIRTUAL_PAGE_SIZE_Y_ARB, 1, &mut height);
+
+            Some((width as u32, height as u32))
+        }
+    }
+
+    /// Builds a new sparse texture. No page is committed.
+    ///
+    /// Mipmaps are never generated automatically, as the pages of the main level are not
+    /// committed. `AutoGeneratedMipmaps` is treated like `EmptyMipmaps`.
+    ///
+    /// The width and the height must be multiples of the page size returned by
+    /// `get_page_size_for_format`, and must not be greater than `GL_MAX_SPARSE_TEXTURE_SIZE_ARB`.
+    pub fn new<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, mipmaps: MipmapsOption,
+                          width: u32, height: u32)
+                          -> Result<SparseTexture2d, SparseTextureCreationError> where F: Facade
+    {
+        if !SparseTexture2d::is_supported(facade) {
+            return Err(SparseTextureCreationError::NotSupported);
+        }
+
+        let page_size = match SparseTexture2d::get_page_size_for_format(facade, format) {
+            Some(s) => s,
+            None => return Err(SparseTextureCreationError::FormatNotSupported),
+        };
+
+        if width % page_size.0 != 0 || height % page_size.1 != 0 {
+            return Err(SparseTextureCreationError::UnalignedDimensions);
+        }
+
+        let max_size = unsafe {
+            let ctxt = facade.get_context().make_current();
+            let mut value = 0;
+            ctxt.gl.GetIntegerv(gl::MAX_SPARSE_TEXTURE_SIZE_ARB, &mut value);
+            value as u32
+        };
+
+        if width > max_size || height > max_size {
+            return Err(SparseTextureCreationError::DimensionsTooLarge);
+        }
+
+        let request = TextureFormatRequest::Specific(format.to_texture_format());
+        let internal_format = try!(image_format::format_request_to_glenum(facade.get_context(),
+                                                                          request,
+                                                                          RequestType::TexStorage)
+                                   .map_err(|_| SparseTextureCreationError::FormatNotSupported));
+
+        let levels = mipmaps.num_levels(width, Some(height), None);
+
+        let id = unsafe {
+            let mut ctxt = facade.get_context().make_current();
+
+            let mut id = 0;
+            ctxt.gl.GenTextures(1, &mut id);
+            ctxt.resource_created(ResourceKind::Texture, id);
+
+            ctxt.gl.BindTexture(gl::TEXTURE_2D, id);
+            let act = ctxt.state.active_texture as usize;
+            ctxt.state.texture_units[act].texture = id;
+            ctxt.state.texture_units[act].target = gl::TEXTURE_2D;
+
+            ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SPARSE_ARB, gl::TRUE as i32);
+            ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::VIRTUAL_PAGE_SIZE_INDEX_ARB, 0);
+            ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+            ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+            ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+
+            if levels > 1 {
+                ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER,
+                                      gl::LINEAR_MIPMAP_LINEAR as i32);
+            } else {
+                ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+                ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_BASE_LEVEL, 0);
+                ctxt.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, 0);
+            }
+
+            ctxt.gl.TexStorage2D(gl::TEXTURE_2D, levels as gl::types::GLsizei, internal_format,
+                                 width as gl::types::GLsizei, height as gl::types::GLsizei);
+
+            id
+        };
+
+        let mipmaps = if levels > 1 {
+            MipmapsOption::EmptyMipmapsMax(levels - 1)
+        } else {
+            MipmapsOption::NoMipmap
+        };
+
+        let texture = unsafe {
+            Texture2d::from_id(facade, format, id, true, mipmaps,
+                               Dimensions::Texture2d { width: width, height: height })
+        };
+
+        Ok(SparseTexture2d {
+            texture: texture,
+            page_size: page_size,
+        })
+    }
+
+    /// Returns the size of a page of this texture.
+    ///
+    /// The regions passed to `commit_region` must be aligned on this size.
+    #[inline]
+    pub fn get_page_size(&self) -> (u32, u32) {
+        self.page_size
+    }
+
+    /// Commits or decommits the pages that cover a region of a mipmap level.
+    ///
+    /// The content of newly-committed pages is undefined.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the level doesn't exist, if the region is out of the bounds of the level, or if
+    /// the region is not aligned on the page size. The width and height of the region can be
+    /// unaligned only if the region reaches the border of the level.
+    pub fn commit_region(&self, level: u32, region: Rect, committed: bool) {
+        let mipmap = self.texture.mipmap(level).expect("Mipmap level doesn't exist");
+        let level_width = mipmap.get_width();
+        let level_height = mipmap.get_height().unwrap_or(1);
+
+        assert!(region.left + region.width <= level_width);
+        assert!(region.bottom + region.height <= level_height);
+
+        let (page_width, page_height) = self.page_size;
+        assert!(region.left % page_width == 0);
+        assert!(region.bottom % page_height == 0);
+        assert!(region.width % page_width == 0 || region.left + region.width == level_width);
+        assert!(region.height % page_height == 0 ||
+                region.bottom + region.height == level_height);
+
+        let mut ctxt = self.texture.get_context().make_current();
+
+        unsafe {
+            let bind_point = self.texture.bind_to_current(&mut ctxt);
+            ctxt.gl.TexPageCommitmentARB(bind_point, level as gl::types::GLint,
+                                         region.left as gl::types::GLint,
+                                         region.bottom as gl::types::GLint, 0,
+                                         region.width as gl::types::GLsizei,
+                                         region.height as gl::types::GLsizei, 1,
+                                         if committed { gl::TRUE } else { gl::FALSE });
+        }
+    }
+}
+
+impl Deref for SparseTexture2d {
+    type Target = Texture2d;
+
+    #[inline]
+    fn deref(&self) -> &Texture2d {
+        &self.texture
+    }
+}
+
+/// Error that can happen when creating a sparse texture.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SparseTextureCreationError {
+    /// Sparse textures are not supported by the backend.
+    NotSupported,
+
+    /// Sparse textures with the requested format are not supported by the backend.
+    FormatNotSupported,
+
+    /// The width or the height is not a multiple of the page size of the format.
+    UnalignedDimensions,
+
+    /// The width or the height is greater than `GL_MAX_SPARSE_TEXTURE_SIZE_ARB`.
+    DimensionsTooLarge,
+}
+
+impl fmt::Display for SparseTextureCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for SparseTextureCreationError {
+    fn description(&self) -> &str {
+        use self::SparseTextureCreationError::*;
+        match *self {
+            NotSupported =>
+                "Sparse textures are not supported by the backend",
+            FormatNotSupported =>
+                "Sparse textures with the requested format are not supported by the backend",
+            UnalignedDimensions =>
+                "The dimensions of the texture are not multiples of the page size",
+            DimensionsTooLarge =>
+                "The dimensions of the texture are greater than the maximum size of sparse \
+                 textures",
+        }
+    }
+}
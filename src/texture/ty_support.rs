@@ -63,6 +63,12 @@ pub fn is_texture_2d_multisample_array_supported<C: ?Sized>(context: &C) -> bool
     context.get_extensions().gl_oes_texture_storage_multisample_2d_array      // FIXME: functions have an OES suffix, this isn't handled by glium
 }
 
+/// Returns true is rectangle textures are supported.
+#[inline]
+pub fn is_texture_rectangle_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 3, 1)
+}
+
 /// Returns true is cubemaps are supported.
 #[inline]
 pub fn is_cubemaps_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
@@ -80,3 +86,22 @@ pub fn is_cubemap_arrays_supported<C: ?Sized>(context: &C) -> bool where C: Capa
     context.get_extensions().gl_ext_texture_cube_map_array ||
     context.get_extensions().gl_oes_texture_cube_map_array
 }
+
+/// Returns true if the stencil component of depth-stencil textures can be sampled.
+#[inline]
+pub fn is_stencil_texturing_supported<C: ?Sized>(context: &C) -> bool
+                                                 where C: CapabilitiesSource
+{
+    context.get_version() >= &Version(Api::Gl, 4, 3) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 1) ||
+    context.get_extensions().gl_arb_stencil_texturing
+}
+
+/// Returns true if the swizzle mask of textures can be changed.
+#[inline]
+pub fn is_texture_swizzle_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 3, 3) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_arb_texture_swizzle ||
+    context.get_extensions().gl_ext_texture_swizzle
+}
@@ -17,10 +17,18 @@ use ProgramExt;
 use UniformsExt;
 use RawUniformValue;
 use TextureExt;
+use ToGlEnum;
 
 use uniforms::Uniforms;
 use uniforms::UniformValue;
+use uniforms::value::decode_raw_value;
 use uniforms::SamplerBehavior;
+use uniforms::{ImageUnitAccess, ImageUnitBehavior};
+use uniforms::{SamplerWrapFunction, MinifySamplerFilter};
+use texture::Dimensions;
+use texture::TextureAny;
+
+use GlObject;
 
 use context::CommandContext;
 use buffer::Inserter;
@@ -38,10 +46,11 @@ use version::Api;
 impl<U> UniformsExt for U where U: Uniforms {
     fn bind_uniforms<'a, P>(&'a self, mut ctxt: &mut CommandContext, program: &P,
                             fences: &mut Vec<Inserter<'a>>)
-                            -> Result<(), DrawError>
+                            -> Result<Bitsfield, DrawError>
                             where P: ProgramExt
     {
         let mut texture_bind_points = Bitsfield::new();
+        let mut image_bind_points = Bitsfield::new();
         let mut uniform_buffer_bind_points = Bitsfield::new();
         let mut shared_storage_buffer_bind_points = Bitsfield::new();
 
@@ -67,7 +76,7 @@ impl<U> UniformsExt for U where U: Uniforms {
                 }
 
                 match bind_uniform(&mut ctxt, &value, program, uniform.location,
-                                   &mut texture_bind_points, name)
+                                   &mut texture_bind_points, &mut image_bind_points, name)
                 {
                     Ok(_) => (),
                     Err(e) => {
@@ -125,7 +134,7 @@ impl<U> UniformsExt for U where U: Uniforms {
             }
         }
 
-        visiting_result
+        visiting_result.map(|_| texture_bind_points)
     }
 }
 
@@ -191,7 +200,12 @@ fn bind_uniform_block<'a, P>(ctxt: &mut context::CommandContext, value: &Uniform
             let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
             buffer_bind_points.set_used(bind_point);
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
+            if let Some(alignment) = ctxt.capabilities.uniform_buffer_offset_alignment {
+                assert!(alignment <= 0 || buffer.get_offset_bytes() % alignment as usize == 0,
+                        "The offset of the uniform buffer is not a multiple of \
+                         GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT");
+            }
+
             let fence = buffer.add_fence();
             let block_id = block.id as gl::types::GLuint;
 
@@ -244,7 +258,8 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext, value: &
 
 fn bind_uniform<P>(ctxt: &mut context::CommandContext,
                    value: &UniformValue, program: &P, location: gl::types::GLint,
-                   texture_bind_points: &mut Bitsfield, name: &str)
+                   texture_bind_points: &mut Bitsfield, image_bind_points: &mut Bitsfield,
+                   name: &str)
                    -> Result<(), DrawError> where P: ProgramExt
 {
     assert!(location >= 0);
@@ -442,6 +457,9 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
         UniformValue::DepthTexture2d(texture, sampler) => {
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
+        UniformValue::DepthStencilTexture2d(texture, sampler) => {
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+        },
         UniformValue::Texture2dMultisample(texture, sampler) => {
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
@@ -535,6 +553,15 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
         UniformValue::DepthTexture2dMultisampleArray(texture, sampler) => {
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
+        UniformValue::TextureRectangle(texture, sampler) => {
+            if let Some(ref sampler) = sampler {
+                if !is_rectangle_sampler_valid(sampler) {
+                    return Err(DrawError::InvalidRectangleTextureSampler);
+                }
+            }
+
+            bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
+        },
         UniformValue::Cubemap(texture, sampler) => {
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
@@ -580,30 +607,161 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
         UniformValue::BufferTexture(texture) => {
             bind_texture_uniform(ctxt, &texture, None, location, program, texture_bind_points)
         },
+        UniformValue::TextureWithSharedSampler(texture, sampler) => {
+            assert!(&**sampler.get_context() as *const _ ==
+                    &**texture.get_context() as *const _,
+                    "The sampler and the texture belong to different contexts");
+
+            if let Dimensions::TextureRectangle { .. } = texture.dimensions() {
+                if !is_rectangle_sampler_valid(sampler.get_behavior()) {
+                    return Err(DrawError::InvalidRectangleTextureSampler);
+                }
+            }
+
+            try!(check_mipmap_filter(ctxt, texture, sampler.get_behavior()));
+
+            bind_texture_uniform_with_sampler_id(ctxt, texture, sampler.get_id(), location,
+                                                 program, texture_bind_points).map(|_| ())
+        },
+        UniformValue::ImageUnit(texture, ref behavior) => {
+            bind_image_uniform(ctxt, texture, behavior, location, program, image_bind_points)
+        },
+        UniformValue::Raw(ty, data) => {
+            match decode_raw_value(ty, data) {
+                Some(Ok(value)) => {
+                    program.set_uniform(ctxt, location, &value);
+                    Ok(())
+                },
+                Some(Err(expected)) => {
+                    Err(DrawError::RawUniformSizeMismatch {
+                        name: name.to_owned(),
+                        expected: expected,
+                        obtained: data.len(),
+                    })
+                },
+                None => {
+                    Err(DrawError::UniformTypeMismatch {
+                        name: name.to_owned(),
+                        expected: ty,
+                    })
+                },
+            }
+        },
+    }
+}
+
+/// Rectangle textures can only be clamped and don't have mipmaps. Using anything else
+/// triggers a `GL_INVALID_ENUM` or makes the texture incomplete.
+fn is_rectangle_sampler_valid(sampler: &SamplerBehavior) -> bool {
+    let (s, t, r) = sampler.wrap_function;
+    if s != SamplerWrapFunction::Clamp || t != SamplerWrapFunction::Clamp ||
+       r != SamplerWrapFunction::Clamp
+    {
+        return false;
+    }
+
+    match sampler.minify_filter {
+        MinifySamplerFilter::Nearest | MinifySamplerFilter::Linear => true,
+        _ => false,
     }
 }
 
-fn bind_texture_uniform<P, T>(mut ctxt: &mut context::CommandContext,
+/// Checks that a sampler doesn't use a minifying filter that needs mipmaps with a texture that
+/// only has a main level.
+///
+/// Glium limits the mipmap chain of the textures it creates to the levels that exist, which makes
+/// this combination valid. This isn't possible on old backends however, where the texture is
+/// incomplete and sampling it silently returns black.
+fn check_mipmap_filter<T>(ctxt: &context::CommandContext, texture: &T, sampler: &SamplerBehavior)
+                          -> Result<(), DrawError> where T: TextureExt
+{
+    if ctxt.version >= &Version(Api::Gl, 1, 2) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+        return Ok(());
+    }
+
+    if texture.get_mipmap_levels() > 1 {
+        return Ok(());
+    }
+
+    match sampler.minify_filter {
+        MinifySamplerFilter::Nearest | MinifySamplerFilter::Linear => Ok(()),
+        _ => Err(DrawError::MipmapFilterWithoutMipmaps),
+    }
+}
+
+fn bind_texture_uniform<P, T>(ctxt: &mut context::CommandContext,
                               texture: &T, sampler: Option<SamplerBehavior>,
                               location: gl::types::GLint, program: &P,
                               texture_bind_points: &mut Bitsfield)
                               -> Result<(), DrawError> where P: ProgramExt, T: TextureExt
 {
+    // without sampler objects, the sampler is emulated with the parameters of the texture
+    if !::sampler_object::is_sampler_object_supported(&*ctxt) {
+        if let Some(ref sampler) = sampler {
+            try!(check_mipmap_filter(ctxt, texture, sampler));
+            try!(::sampler_object::check_behavior(ctxt, sampler));
+        }
+
+        // the parameters of a texture that is sampled more than once by the same draw call
+        // can't be different
+        let texture_id = texture.get_texture_id();
+        let already_bound = ctxt.state.texture_units.iter().enumerate().any(|(unit, content)| {
+            content.texture == texture_id && texture_bind_points.is_used(unit as u16)
+        });
+
+        let texture_unit = try!(bind_texture_uniform_with_sampler_id(ctxt, texture, 0, location,
+                                                                     program,
+                                                                     texture_bind_points));
+
+        if ctxt.state.active_texture != texture_unit as gl::types::GLenum {
+            unsafe { ctxt.gl.ActiveTexture(texture_unit as gl::types::GLenum + gl::TEXTURE0) };
+            ctxt.state.active_texture = texture_unit as gl::types::GLenum;
+        }
+
+        if texture.emulate_sampler(ctxt, sampler.as_ref()) && already_bound {
+            return Err(DrawError::SamplersNotSupported);
+        }
+
+        return Ok(());
+    }
+
     let sampler = if let Some(sampler) = sampler {
+        try!(check_mipmap_filter(ctxt, texture, &sampler));
         Some(try!(::sampler_object::get_sampler(ctxt, &sampler)))
     } else {
         None
     };
 
     let sampler = sampler.unwrap_or(0);
+    bind_texture_uniform_with_sampler_id(ctxt, texture, sampler, location, program,
+                                         texture_bind_points).map(|_| ())
+}
 
+fn bind_texture_uniform_with_sampler_id<P, T>(mut ctxt: &mut context::CommandContext,
+                                              texture: &T, sampler: gl::types::GLuint,
+                                              location: gl::types::GLint, program: &P,
+                                              texture_bind_points: &mut Bitsfield)
+                                              -> Result<u16, DrawError>
+                                              where P: ProgramExt, T: TextureExt
+{
     // finding an appropriate texture unit
+    // the units that already contain the texture, or to which it has been pinned, are tried
+    // first so that it doesn't need to be bound again
+    let texture_id = texture.get_texture_id();
     let texture_unit =
         ctxt.state.texture_units
             .iter().enumerate()
             .find(|&(unit, content)| {
-                content.texture == texture.get_texture_id() && (content.sampler == sampler ||
-                                                        !texture_bind_points.is_used(unit as u16))
+                content.texture == texture_id && (content.sampler == sampler ||
+                                                  !texture_bind_points.is_used(unit as u16))
+            })
+            .or_else(|| {
+                ctxt.state.texture_units
+                    .iter().enumerate()
+                    .find(|&(unit, content)| {
+                        content.pinned_texture == texture_id &&
+                        !texture_bind_points.is_used(unit as u16)
+                    })
             })
             .map(|(unit, _)| unit as u16)
             .or_else(|| {
@@ -615,6 +773,17 @@ fn bind_texture_uniform<P, T>(mut ctxt: &mut context::CommandContext,
                     None
                 }
             })
+            .or_else(|| {
+                // all the units are in use, so we replace the texture that has been used the
+                // least recently, leaving the pinned textures alone
+                ctxt.state.texture_units
+                    .iter().enumerate()
+                    .filter(|&(unit, content)| {
+                        content.pinned_texture == 0 && !texture_bind_points.is_used(unit as u16)
+                    })
+                    .min_by_key(|&(_, content)| content.latest_use)
+                    .map(|(unit, _)| unit as u16)
+            })
             .unwrap_or_else(|| {
                 texture_bind_points.get_unused().expect("Not enough texture units available")
             });
@@ -626,6 +795,9 @@ fn bind_texture_uniform<P, T>(mut ctxt: &mut context::CommandContext,
     program.set_uniform(ctxt, location,
                         &RawUniformValue::SignedInt(texture_unit as gl::types::GLint));
 
+    // waiting for the writes done through image units
+    texture.prepare_for_sampling(ctxt);
+
     // updating the state of the texture unit
     if ctxt.state.texture_units.len() <= texture_unit as usize {
         for _ in ctxt.state.texture_units.len() .. texture_unit as usize + 1 {
@@ -633,6 +805,8 @@ fn bind_texture_uniform<P, T>(mut ctxt: &mut context::CommandContext,
         }
     }
 
+    ctxt.state.texture_units[texture_unit as usize].latest_use = ctxt.state.next_draw_call_id;
+
     // TODO: do better
     if ctxt.state.texture_units[texture_unit as usize].texture != texture.get_texture_id() ||
        ctxt.state.texture_units[texture_unit as usize].sampler != sampler
@@ -655,5 +829,46 @@ fn bind_texture_uniform<P, T>(mut ctxt: &mut context::CommandContext,
         }
     }
 
+    Ok(texture_unit)
+}
+
+fn bind_image_uniform<P>(ctxt: &mut context::CommandContext, texture: &TextureAny,
+                         behavior: &ImageUnitBehavior, location: gl::types::GLint, program: &P,
+                         image_bind_points: &mut Bitsfield)
+                         -> Result<(), DrawError> where P: ProgramExt
+{
+    if !(ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+         ctxt.extensions.gl_arb_shader_image_load_store)
+    {
+        return Err(DrawError::ImageUnitsNotSupported);
+    }
+
+    let image_unit = image_bind_points.get_unused().expect("Not enough image units available");
+    assert!((image_unit as gl::types::GLint) < ctxt.capabilities.max_image_units,
+            "Not enough image units available");
+    image_bind_points.set_used(image_unit);
+
+    // updating the program to use the right unit
+    program.set_uniform(ctxt, location,
+                        &RawUniformValue::SignedInt(image_unit as gl::types::GLint));
+
+    texture.prepare_for_image_unit(ctxt, behavior.access != ImageUnitAccess::Read);
+
+    let layered = if behavior.layered { gl::TRUE } else { gl::FALSE };
+
+    unsafe {
+        ctxt.gl.BindImageTexture(image_unit as gl::types::GLuint, texture.get_texture_id(),
+                                 behavior.level as gl::types::GLint, layered,
+                                 behavior.layer as gl::types::GLint,
+                                 behavior.access.to_glenum(), behavior.format.to_glenum());
+    }
+
+    if ctxt.state.image_units.len() <= image_unit as usize {
+        for _ in ctxt.state.image_units.len() .. image_unit as usize + 1 {
+            ctxt.state.image_units.push(0);
+        }
+    }
+    ctxt.state.image_units[image_unit as usize] = texture.get_texture_id();
+
     Ok(())
 }
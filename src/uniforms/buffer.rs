@@ -1,11 +1,16 @@
 use buffer::{Content, Buffer, BufferAny, BufferType, BufferMode, BufferCreationError};
-use buffer::{BufferSlice, BufferMutSlice};
+use buffer::{BufferSlice, BufferMutSlice, BufferAnySlice};
 use uniforms::{AsUniformValue, UniformBlock, UniformValue, LayoutMismatchError};
 use program;
 
 use gl;
 use GlObject;
+use ContextExt;
 
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
 use std::ops::{Deref, DerefMut};
 
 use backend::Facade;
@@ -244,3 +249,130 @@ impl<'a, T: ?Sized> AsUniformValue for &'a UniformBuffer<T> where T: UniformBloc
         UniformValue::Block(self.buffer.as_slice_any(), f::<T>)
     }
 }
+
+impl<T> UniformBuffer<[T]> where T: UniformBlock + Copy {
+    /// Returns a range of `count` elements of the buffer starting at element `offset`, that can
+    /// be passed to `uniform!` instead of the whole buffer. The range is bound with
+    /// `glBindBufferRange` when drawing.
+    ///
+    /// If `count` is 1, the uniform block of the shader must have the layout of `T`. Otherwise
+    /// it must contain an array of `T`.
+    ///
+    /// The offset in bytes of the range must be a multiple of
+    /// `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`. Returns an error if this is not the case or if the
+    /// range is out of the buffer.
+    pub fn slice_blocks(&self, offset: usize, count: usize)
+                        -> Result<UniformBufferSlice<T>, UniformBufferSliceError>
+    {
+        if count == 0 || offset.checked_add(count).map_or(true, |end| end > self.buffer.len()) {
+            return Err(UniformBufferSliceError::OutOfRange);
+        }
+
+        let offset_bytes = offset * mem::size_of::<T>();
+
+        let alignment = self.buffer.get_context().capabilities()
+                            .uniform_buffer_offset_alignment.unwrap_or(1);
+        if alignment > 0 && offset_bytes % alignment as usize != 0 {
+            return Err(UniformBufferSliceError::MisalignedOffset {
+                offset: offset_bytes,
+                alignment: alignment as usize,
+            });
+        }
+
+        let slice = self.buffer.slice(offset .. offset + count).unwrap();
+
+        Ok(UniformBufferSlice {
+            slice: slice.as_slice_any(),
+            count: count,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// A range of elements of a `UniformBuffer`, returned by `UniformBuffer::slice_blocks`.
+///
+/// Can be used as the value of a uniform block in `uniform!`.
+#[derive(Copy, Clone)]
+pub struct UniformBufferSlice<'a, T> where T: UniformBlock + Copy + 'a {
+    slice: BufferAnySlice<'a>,
+    count: usize,
+    marker: PhantomData<&'a [T]>,
+}
+
+impl<'a, T> UniformBufferSlice<'a, T> where T: UniformBlock + Copy + 'a {
+    /// Returns the offset in bytes of the range within the buffer.
+    #[inline]
+    pub fn get_offset_bytes(&self) -> usize {
+        self.slice.get_offset_bytes()
+    }
+
+    /// Returns the number of elements in the range.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+}
+
+impl<'a, T> AsUniformValue for UniformBufferSlice<'a, T> where T: UniformBlock + Copy + 'a {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        #[inline]
+        fn single<T>(block: &program::UniformBlock)
+                     -> Result<(), LayoutMismatchError> where T: UniformBlock
+        {
+            T::matches(&block.layout, 0)
+        }
+
+        #[inline]
+        fn array<T>(block: &program::UniformBlock)
+                    -> Result<(), LayoutMismatchError> where T: UniformBlock
+        {
+            <[T] as UniformBlock>::matches(&block.layout, 0)
+        }
+
+        if self.count == 1 {
+            UniformValue::Block(self.slice, single::<T>)
+        } else {
+            UniformValue::Block(self.slice, array::<T>)
+        }
+    }
+}
+
+/// Error that can happen when calling `UniformBuffer::slice_blocks`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UniformBufferSliceError {
+    /// The range is empty or goes past the end of the buffer.
+    OutOfRange,
+
+    /// The offset in bytes of the range is not a multiple of
+    /// `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`.
+    MisalignedOffset {
+        /// Offset in bytes of the requested range.
+        offset: usize,
+        /// Value of `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`.
+        alignment: usize,
+    },
+}
+
+impl fmt::Display for UniformBufferSliceError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UniformBufferSliceError::MisalignedOffset { offset, alignment } => {
+                write!(fmt, "{} (offset: {}, alignment: {})", self.description(), offset,
+                       alignment)
+            },
+            _ => write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for UniformBufferSliceError {
+    fn description(&self) -> &str {
+        match *self {
+            UniformBufferSliceError::OutOfRange =>
+                "The range is empty or goes past the end of the buffer",
+            UniformBufferSliceError::MisalignedOffset { .. } =>
+                "The offset of the range is not a multiple of GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT",
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use ToGlEnum;
+use gl;
+
+use std::error::Error;
+use std::fmt;
+
+use texture::{Dimensions, TextureAny};
+use uniforms::{AsUniformValue, UniformValue};
+
+/// How a shader is allowed to access a texture bound to an image unit.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ImageUnitAccess {
+    /// The shader can only use `imageLoad` and the atomic functions.
+    Read,
+
+    /// The shader can only use `imageStore`.
+    Write,
+
+    /// The shader can both read and write.
+    ReadWrite,
+}
+
+impl ToGlEnum for ImageUnitAccess {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageUnitAccess::Read => gl::READ_ONLY,
+            ImageUnitAccess::Write => gl::WRITE_ONLY,
+            ImageUnitAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
+}
+
+/// Format in which the texels of a texture bound to an image unit are interpreted.
+///
+/// This corresponds to the format qualifier of the image in the shader, for example
+/// `layout(rgba8)`. The format must be compatible with the internal format of the texture.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ImageUnitFormat {
+    RGBA32F,
+    RGBA16F,
+    RG32F,
+    RG16F,
+    R11FG11FB10F,
+    R32F,
+    R16F,
+    RGBA32UI,
+    RGBA16UI,
+    RGB10A2UI,
+    RGBA8UI,
+    RG32UI,
+    RG16UI,
+    RG8UI,
+    R32UI,
+    R16UI,
+    R8UI,
+    RGBA32I,
+    RGBA16I,
+    RGBA8I,
+    RG32I,
+    RG16I,
+    RG8I,
+    R32I,
+    R16I,
+    R8I,
+    RGBA16,
+    RGB10A2,
+    RGBA8,
+    RG16,
+    RG8,
+    R16,
+    R8,
+    RGBA16snorm,
+    RGBA8snorm,
+    RG16snorm,
+    RG8snorm,
+    R16snorm,
+    R8snorm,
+}
+
+impl ToGlEnum for ImageUnitFormat {
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageUnitFormat::RGBA32F => gl::RGBA32F,
+            ImageUnitFormat::RGBA16F => gl::RGBA16F,
+            ImageUnitFormat::RG32F => gl::RG32F,
+            ImageUnitFormat::RG16F => gl::RG16F,
+            ImageUnitFormat::R11FG11FB10F => gl::R11F_G11F_B10F,
+            ImageUnitFormat::R32F => gl::R32F,
+            ImageUnitFormat::R16F => gl::R16F,
+            ImageUnitFormat::RGBA32UI => gl::RGBA32UI,
+            ImageUnitFormat::RGBA16UI => gl::RGBA16UI,
+            ImageUnitFormat::RGB10A2UI => gl::RGB10_A2UI,
+            ImageUnitFormat::RGBA8UI => gl::RGBA8UI,
+            ImageUnitFormat::RG32UI => gl::RG32UI,
+            ImageUnitFormat::RG16UI => gl::RG16UI,
+            ImageUnitFormat::RG8UI => gl::RG8UI,
+            ImageUnitFormat::R32UI => gl::R32UI,
+            ImageUnitFormat::R16UI => gl::R16UI,
+            ImageUnitFormat::R8UI => gl::R8UI,
+            ImageUnitFormat::RGBA32I => gl::RGBA32I,
+            ImageUnitFormat::RGBA16I => gl::RGBA16I,
+            ImageUnitFormat::RGBA8I => gl::RGBA8I,
+            ImageUnitFormat::RG32I => gl::RG32I,
+            ImageUnitFormat::RG16I => gl::RG16I,
+            ImageUnitFormat::RG8I => gl::RG8I,
+            ImageUnitFormat::R32I => gl::R32I,
+            ImageUnitFormat::R16I => gl::R16I,
+            ImageUnitFormat::R8I => gl::R8I,
+            ImageUnitFormat::RGBA16 => gl::RGBA16,
+            ImageUnitFormat::RGB10A2 => gl::RGB10_A2,
+            ImageUnitFormat::RGBA8 => gl::RGBA8,
+            ImageUnitFormat::RG16 => gl::RG16,
+            ImageUnitFormat::RG8 => gl::RG8,
+            ImageUnitFormat::R16 => gl::R16,
+            ImageUnitFormat::R8 => gl::R8,
+            ImageUnitFormat::RGBA16snorm => gl::RGBA16_SNORM,
+            ImageUnitFormat::RGBA8snorm => gl::RGBA8_SNORM,
+            ImageUnitFormat::RG16snorm => gl::RG16_SNORM,
+            ImageUnitFormat::RG8snorm => gl::RG8_SNORM,
+            ImageUnitFormat::R16snorm => gl::R16_SNORM,
+            ImageUnitFormat::R8snorm => gl::R8_SNORM,
+        }
+    }
+}
+
+/// Parameters of the binding of a texture to an image unit.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct ImageUnitBehavior {
+    /// The mipmap level to bind.
+    pub level: u32,
+
+    /// If true, all the layers of the texture are bound and `layer` is ignored. The texture
+    /// must be a 3D texture, an array texture or a cubemap, and the shader uses an `image3D`,
+    /// an `image2DArray`, an `imageCube`, etc.
+    ///
+    /// If false, only `layer` is bound and the shader sees it as a 1D or 2D image.
+    pub layered: bool,
+
+    /// The layer to bind if `layered` is false. Must be `0` for textures that don't have
+    /// layers.
+    pub layer: u32,
+
+    /// How the shader accesses the texture.
+    pub access: ImageUnitAccess,
+
+    /// How the texels are interpreted.
+    pub format: ImageUnitFormat,
+}
+
+impl ImageUnitBehavior {
+    /// Builds the parameters to bind the first layer of the main level of a texture for
+    /// reading and writing.
+    #[inline]
+    pub fn new(format: ImageUnitFormat) -> ImageUnitBehavior {
+        ImageUnitBehavior {
+            level: 0,
+            layered: false,
+            layer: 0,
+            access: ImageUnitAccess::ReadWrite,
+            format: format,
+        }
+    }
+}
+
+/// A texture bound to an image unit, to be passed as the value of an image uniform.
+///
+/// Image units require OpenGL 4.2, OpenGL ES 3.1 or the `GL_ARB_shader_image_load_store`
+/// extension. Drawing or dispatching a compute shader with an image unit returns a
+/// `DrawError::ImageUnitsNotSupported` otherwise.
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let texture: glium::texture::Texture3d = unsafe { std::mem::uninitialized() };
+/// use glium::uniforms::{ImageUnitAccess, ImageUnitFormat};
+///
+/// // binds all the layers of the texture to an `image3D`
+/// let voxels = texture.image_unit(ImageUnitFormat::RGBA8)
+///                     .set_access(ImageUnitAccess::Write)
+///                     .layered().unwrap();
+///
+/// let uniforms = uniform! {
+///     voxels: voxels,
+/// };
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ImageUnit<'t>(pub &'t TextureAny, pub ImageUnitBehavior);
+
+impl<'t> ImageUnit<'t> {
+    /// Builds a new `ImageUnit` with the default parameters of `ImageUnitBehavior::new`.
+    #[inline]
+    pub fn new(texture: &'t TextureAny, format: ImageUnitFormat) -> ImageUnit<'t> {
+        ImageUnit(texture, ImageUnitBehavior::new(format))
+    }
+
+    /// Changes how the shader accesses the texture.
+    #[inline]
+    pub fn set_access(mut self, access: ImageUnitAccess) -> ImageUnit<'t> {
+        self.1.access = access;
+        self
+    }
+
+    /// Changes the mipmap level to bind.
+    #[inline]
+    pub fn set_level(mut self, level: u32) -> ImageUnit<'t> {
+        self.1.level = level;
+        self
+    }
+
+    /// Binds a single layer of the texture.
+    #[inline]
+    pub fn set_layer(mut self, layer: u32) -> ImageUnit<'t> {
+        self.1.layered = false;
+        self.1.layer = layer;
+        self
+    }
+
+    /// Binds all the layers of the texture at once.
+    ///
+    /// Returns an error if the texture is not a 3D texture, an array texture or a cubemap.
+    pub fn layered(mut self) -> Result<ImageUnit<'t>, LayeredImageUnitError> {
+        match self.0.dimensions() {
+            Dimensions::Texture1dArray { .. } | Dimensions::Texture2dArray { .. } |
+            Dimensions::Texture2dMultisampleArray { .. } | Dimensions::Texture3d { .. } |
+            Dimensions::Cubemap { .. } | Dimensions::CubemapArray { .. } => (),
+            _ => return Err(LayeredImageUnitError),
+        }
+
+        self.1.layered = true;
+        self.1.layer = 0;
+        Ok(self)
+    }
+}
+
+impl<'t> AsUniformValue for ImageUnit<'t> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::ImageUnit(self.0, self.1)
+    }
+}
+
+/// Error that can happen when calling `ImageUnit::layered`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LayeredImageUnitError;
+
+impl fmt::Display for LayeredImageUnitError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for LayeredImageUnitError {
+    #[inline]
+    fn description(&self) -> &str {
+        "Only 3D textures, array textures and cubemaps can be bound as layered images"
+    }
+}
@@ -25,6 +25,23 @@ let uniforms = uniform! {
 
 In both situations, each field must implement the `UniformValue` trait.
 
+## Types
+
+Scalars, arrays and tuples of the primitive types are mapped to their GLSL equivalent:
+
+ - `f32`, `[f32; N]` and `(f32, ...)` to `float` and `vecN`.
+ - `i32`, `[i32; N]` and `(i32, ...)` to `int` and `ivecN`. They are uploaded with
+   `glUniform{1,2,3,4}iv`.
+ - `u32`, `[u32; N]` and `(u32, ...)` to `uint` and `uvecN`. They are uploaded with
+   `glUniform{1,2,3,4}uiv`.
+ - `bool`, `[bool; N]` and `(bool, ...)` to `bool` and `bvecN`. They are uploaded as integers
+   equal to `0` or `1`.
+ - `[[f32; N]; N]` to `matN`.
+
+The type of the value is checked against the type of the uniform declared in the program. If
+they don't match, drawing returns `DrawError::UniformTypeMismatch` instead of silently
+uploading data of the wrong type.
+
 ## Samplers
 
 In order to customize the way a texture is being sampled, you must use a `Sampler`.
@@ -43,6 +60,15 @@ let uniforms = uniform! {
 # }
 ```
 
+If the same sampler parameters are used by a lot of draw calls, you can build a `SharedSampler`
+once and pass a `(&texture, &shared_sampler)` tuple instead.
+
+## Image units
+
+Textures that are read or written with `imageLoad` and `imageStore` must be passed as an
+`ImageUnit`, which you can build by calling `image_unit` on a texture. Call `layered()` on it to
+bind all the layers of a 3D texture, an array texture or a cubemap at once.
+
 ## Blocks
 
 In GLSL, you can choose to use a uniform *block*. When you use a block, you first need to
@@ -140,10 +166,14 @@ let program = glium::Program::from_source(&display,
 # }
 ```
 */
-pub use self::buffer::UniformBuffer;
+pub use self::buffer::{UniformBuffer, UniformBufferSlice, UniformBufferSliceError};
+pub use self::image_unit::{ImageUnit, ImageUnitAccess, ImageUnitBehavior, ImageUnitFormat};
+pub use self::image_unit::LayeredImageUnitError;
+pub use self::ring::{UniformRing, UboBinding};
 pub use self::sampler::{SamplerWrapFunction, MagnifySamplerFilter, MinifySamplerFilter};
-pub use self::sampler::{Sampler, SamplerBehavior};
-pub use self::uniforms::{EmptyUniforms, UniformsStorage};
+pub use self::sampler::{DepthTextureComparison, SamplerReductionMode};
+pub use self::sampler::{Sampler, SamplerBehavior, SharedSampler};
+pub use self::uniforms::{EmptyUniforms, UniformsStorage, UniformSet};
 pub use self::value::{UniformValue, UniformType};
 
 use std::error::Error;
@@ -156,6 +186,8 @@ use program::BlockLayout;
 
 mod bind;
 mod buffer;
+mod image_unit;
+mod ring;
 mod sampler;
 mod uniforms;
 mod value;
@@ -168,6 +200,19 @@ pub trait Uniforms {
     fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, F);
 }
 
+/// Object that can be used as the value of a uniform whose type is a GLSL `struct`.
+///
+/// GLSL doesn't allow setting a struct uniform at once. Instead each member has its own
+/// location, named `name.member` or `name[index].member` for arrays of structs. Objects that
+/// implement this trait are expanded to one value per field by `UniformSet::set_struct` and
+/// `UniformSet::set_struct_array`.
+///
+/// You can implement this trait for your own types with the `implement_uniform_struct!` macro.
+pub trait UniformStruct {
+    /// Calls the parameter once with the name and value of each field.
+    fn visit_fields<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, F);
+}
+
 /// Error about a block layout mismatch.
 #[derive(Clone, Debug)]
 pub enum LayoutMismatchError {
@@ -0,0 +1,142 @@
+use buffer::{Buffer, BufferAnySlice, BufferType, BufferMode, BufferCreationError};
+use uniforms::{AsUniformValue, UniformBlock, UniformValue, LayoutMismatchError};
+use program;
+
+use backend::Facade;
+use BufferExt;
+use ContextExt;
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem;
+use std::slice;
+
+/// Ring of uniform blocks stored in a single persistent-mapped buffer.
+///
+/// When drawing many objects that each have a small uniform block, creating one
+/// `UniformBuffer` per object is wasteful. Instead, `push` writes the value of the block at the
+/// next free slot of the ring and returns a `UboBinding` that you can pass to `uniform!`.
+/// The binding is bound with `glBindBufferRange` when drawing.
+///
+/// Each slot starts at a multiple of `GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT`. Once all the slots
+/// have been used, `push` goes back to the first one. If the GPU is still using the data of that
+/// slot, `push` waits until it is done. Use a capacity large enough to hold all the blocks of a
+/// few frames in order to avoid this.
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// #[derive(Copy, Clone)]
+/// struct Object {
+///     color: [f32; 4],
+/// }
+///
+/// implement_uniform_block!(Object, color);
+///
+/// let ring = glium::uniforms::UniformRing::<Object>::new(&display, 1024).unwrap();
+/// let binding = ring.push(&Object { color: [1.0, 0.0, 0.0, 1.0] });
+/// let uniforms = uniform! { Object: binding };
+/// # }
+/// ```
+pub struct UniformRing<T> where T: UniformBlock + Copy {
+    buffer: Buffer<[u8]>,
+    stride: usize,
+    capacity: usize,
+    next: Cell<usize>,
+    marker: PhantomData<T>,
+}
+
+impl<T> UniformRing<T> where T: UniformBlock + Copy {
+    /// Builds a new ring that can hold `capacity` uniform blocks.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new<F: ?Sized>(facade: &F, capacity: usize)
+                          -> Result<UniformRing<T>, BufferCreationError> where F: Facade
+    {
+        assert!(capacity >= 1);
+
+        let alignment = facade.get_context().capabilities()
+                              .uniform_buffer_offset_alignment.unwrap_or(1) as usize;
+        let alignment = if alignment == 0 { 1 } else { alignment };
+        let size = mem::size_of::<T>();
+        let stride = (size + alignment - 1) / alignment * alignment;
+
+        let buffer = try!(Buffer::empty_array(facade, BufferType::UniformBuffer,
+                                              stride * capacity, BufferMode::Persistent));
+
+        Ok(UniformRing {
+            buffer: buffer,
+            stride: stride,
+            capacity: capacity,
+            next: Cell::new(0),
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of uniform blocks that the ring can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the distance in bytes between the start of two consecutive slots.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Writes a value in the next slot of the ring and returns a binding to it.
+    ///
+    /// Waits if the GPU is still using the content of this slot.
+    pub fn push(&self, value: &T) -> UboBinding<T> {
+        let slot = self.next.get();
+        self.next.set((slot + 1) % self.capacity);
+
+        let start = slot * self.stride;
+        let slice = self.buffer.slice(start .. start + mem::size_of::<T>()).unwrap();
+
+        let bytes = unsafe {
+            slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>())
+        };
+        slice.write(bytes);
+
+        UboBinding {
+            slice: slice.as_slice_any(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// A uniform block stored in a `UniformRing`.
+///
+/// Can be used as the value of a uniform block in `uniform!`.
+#[derive(Copy, Clone)]
+pub struct UboBinding<'a, T> where T: UniformBlock + Copy + 'a {
+    slice: BufferAnySlice<'a>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> UboBinding<'a, T> where T: UniformBlock + Copy + 'a {
+    /// Returns the offset in bytes of the block within the buffer of the ring.
+    #[inline]
+    pub fn get_offset_bytes(&self) -> usize {
+        self.slice.get_offset_bytes()
+    }
+}
+
+impl<'a, T> AsUniformValue for UboBinding<'a, T> where T: UniformBlock + Copy + 'a {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        #[inline]
+        fn f<T>(block: &program::UniformBlock)
+                -> Result<(), LayoutMismatchError> where T: UniformBlock
+        {
+            T::matches(&block.layout, 0)
+        }
+
+        UniformValue::Block(self.slice, f::<T>)
+    }
+}
@@ -1,6 +1,24 @@
 use ToGlEnum;
+use CapabilitiesSource;
+use DrawError;
+use GlObject;
 use gl;
 
+use std::ops::Deref;
+use std::rc::Rc;
+
+use backend::Facade;
+use context::Context;
+use context::LabeledObject;
+use debug::{Labeled, LabelingNotSupportedError};
+use ContextExt;
+use sampler_object;
+use texture::TextureAny;
+use uniforms::{AsUniformValue, UniformValue};
+
+use version::Api;
+use version::Version;
+
 /// Function to use for out-of-bounds samples.
 ///
 /// This is how GL must handle samples that are outside the texture.
@@ -15,10 +33,36 @@ pub enum SamplerWrapFunction {
     /// Samples at coord `x + 1` map to coord `1`.
     Clamp,
 
-    /// Same as Mirror, but only for one repetition,
+    /// Same as Mirror, but only for one repetition.
+    ///
+    /// Requires OpenGL 4.4, or one of the `GL_ARB_texture_mirror_clamp_to_edge`,
+    /// `GL_ATI_texture_mirror_once`, `GL_EXT_texture_mirror_clamp` or
+    /// `GL_EXT_texture_mirror_clamp_to_edge` extensions. Drawing with a sampler that uses it
+    /// returns a `DrawError::WrapFunctionNotSupported` otherwise.
     MirrorClamp
 }
 
+impl SamplerWrapFunction {
+    /// Returns true if this wrap function is supported by the backend.
+    pub fn is_supported<C: ?Sized>(&self, context: &C) -> bool where C: CapabilitiesSource {
+        let version = context.get_version();
+        let extensions = context.get_extensions();
+
+        match self {
+            &SamplerWrapFunction::Repeat => true,
+            &SamplerWrapFunction::Mirror => true,
+            &SamplerWrapFunction::Clamp => true,
+            &SamplerWrapFunction::MirrorClamp => {
+                version >= &Version(Api::Gl, 4, 4) ||
+                    extensions.gl_arb_texture_mirror_clamp_to_edge ||
+                    extensions.gl_ati_texture_mirror_once ||
+                    extensions.gl_ext_texture_mirror_clamp ||
+                    extensions.gl_ext_texture_mirror_clamp_to_edge
+            },
+        }
+    }
+}
+
 impl ToGlEnum for SamplerWrapFunction {
     #[inline]
     fn to_glenum(&self) -> gl::types::GLenum {
@@ -91,6 +135,87 @@ impl ToGlEnum for MinifySamplerFilter {
     }
 }
 
+/// The comparison to perform between the texture coordinate and the value of a depth texture
+/// when sampling it.
+///
+/// The result of the comparison is `1.0` if it passes and `0.0` otherwise. If the sampler uses
+/// a linear filter, the implementation is allowed to average the results of the neighbouring
+/// texels, which gives percentage-closer filtering for free.
+///
+/// Comparisons only have an effect on depth textures, and require the uniform to be declared
+/// as a shadow sampler (for example `sampler2DShadow`) in GLSL.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum DepthTextureComparison {
+    /// Passes if the coordinate is lower or equal to the value of the texture.
+    LessOrEqual,
+    /// Passes if the coordinate is greater or equal to the value of the texture.
+    GreaterOrEqual,
+    /// Passes if the coordinate is strictly lower than the value of the texture.
+    Less,
+    /// Passes if the coordinate is strictly greater than the value of the texture.
+    Greater,
+    /// Passes if the coordinate is equal to the value of the texture.
+    Equal,
+    /// Passes if the coordinate is different from the value of the texture.
+    NotEqual,
+    /// Always passes.
+    Always,
+    /// Never passes.
+    Never,
+}
+
+impl ToGlEnum for DepthTextureComparison {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            DepthTextureComparison::LessOrEqual => gl::LEQUAL,
+            DepthTextureComparison::GreaterOrEqual => gl::GEQUAL,
+            DepthTextureComparison::Less => gl::LESS,
+            DepthTextureComparison::Greater => gl::GREATER,
+            DepthTextureComparison::Equal => gl::EQUAL,
+            DepthTextureComparison::NotEqual => gl::NOTEQUAL,
+            DepthTextureComparison::Always => gl::ALWAYS,
+            DepthTextureComparison::Never => gl::NEVER,
+        }
+    }
+}
+
+/// How the texels fetched by a filter are combined into the returned value.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum SamplerReductionMode {
+    /// The texels are combined with a weighted average. This is the default.
+    WeightedAverage,
+
+    /// The component-wise minimum of the texels is returned.
+    Min,
+
+    /// The component-wise maximum of the texels is returned.
+    Max,
+}
+
+impl SamplerReductionMode {
+    /// Returns true if this reduction mode is supported by the backend.
+    pub fn is_supported<C: ?Sized>(&self, context: &C) -> bool where C: CapabilitiesSource {
+        match self {
+            &SamplerReductionMode::WeightedAverage => true,
+            &SamplerReductionMode::Min | &SamplerReductionMode::Max => {
+                context.get_extensions().gl_arb_texture_filter_minmax
+            },
+        }
+    }
+}
+
+impl ToGlEnum for SamplerReductionMode {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            SamplerReductionMode::WeightedAverage => gl::WEIGHTED_AVERAGE_ARB,
+            SamplerReductionMode::Min => gl::MIN,
+            SamplerReductionMode::Max => gl::MAX,
+        }
+    }
+}
+
 /// A sampler.
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct Sampler<'t, T: 't>(pub &'t T, pub SamplerBehavior);
@@ -101,6 +226,18 @@ impl<'t, T: 't> Sampler<'t, T> {
         Sampler(texture, Default::default())
     }
 
+    /// Changes both filters and the wrap functions of all three coordinates at once.
+    ///
+    /// The other parameters of the sampler are left untouched.
+    pub fn simple(mut self, minify: MinifySamplerFilter, magnify: MagnifySamplerFilter,
+                  wrap: SamplerWrapFunction) -> Sampler<'t, T>
+    {
+        self.1.minify_filter = minify;
+        self.1.magnify_filter = magnify;
+        self.1.wrap_function = (wrap, wrap, wrap);
+        self
+    }
+
     /// Changes the wrap functions of all three coordinates.
     pub fn wrap_function(mut self, function: SamplerWrapFunction) -> Sampler<'t, T> {
         self.1.wrap_function = (function, function, function);
@@ -119,11 +256,29 @@ impl<'t, T: 't> Sampler<'t, T> {
         self
     }
 
-    /// Changes the magnifying filter of the sampler.
+    /// Changes the maximum anisotropy of the sampler.
+    ///
+    /// See `SamplerBehavior::max_anisotropy`.
     pub fn anisotropy(mut self, level: u16) -> Sampler<'t, T> {
         self.1.max_anisotropy = level;
         self
     }
+
+    /// Changes the comparison performed when sampling a depth texture.
+    pub fn depth_texture_comparison(mut self, comparison: Option<DepthTextureComparison>)
+                                    -> Sampler<'t, T>
+    {
+        self.1.depth_texture_comparison = comparison;
+        self
+    }
+
+    /// Changes the way the texels fetched by the filters are combined.
+    ///
+    /// See `SamplerBehavior::reduction_mode`.
+    pub fn reduction_mode(mut self, mode: SamplerReductionMode) -> Sampler<'t, T> {
+        self.1.reduction_mode = mode;
+        self
+    }
 }
 
 impl<'t, T: 't> Copy for Sampler<'t, T> {}
@@ -135,8 +290,10 @@ impl<'t, T: 't> Clone for Sampler<'t, T> {
 }
 
 /// Behavior of a sampler.
-// TODO: GL_TEXTURE_BORDER_COLOR, GL_TEXTURE_MIN_LOD, GL_TEXTURE_MAX_LOD, GL_TEXTURE_LOD_BIAS,
-//       GL_TEXTURE_COMPARE_MODE, GL_TEXTURE_COMPARE_FUNC
+///
+/// The default behavior mirrors the texture on all axes and uses the mipmaps when minifying.
+/// See also the `repeat_linear`, `clamp_nearest` and `clamp_linear_no_mip` presets.
+// TODO: GL_TEXTURE_BORDER_COLOR, GL_TEXTURE_MIN_LOD, GL_TEXTURE_MAX_LOD, GL_TEXTURE_LOD_BIAS
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub struct SamplerBehavior {
     /// Functions to use for the X, Y, and Z coordinates.
@@ -153,11 +310,25 @@ pub struct SamplerBehavior {
     /// ## Compatibility
     ///
     /// This parameter is always available. However it is ignored on hardware that does
-    /// not support anisotropic filtering.
+    /// not support anisotropic filtering. Use `Context::get_max_anisotropy_support` to know
+    /// whether it is supported.
     ///
     /// If you set the value to a value higher than what the hardware supports, it will
-    /// be clamped.
+    /// be clamped. A value of `0` is treated like `1`.
     pub max_anisotropy: u16,
+
+    /// If `Some`, the value of a depth texture is compared with the coordinate instead of
+    /// being returned. See `DepthTextureComparison`.
+    pub depth_texture_comparison: Option<DepthTextureComparison>,
+
+    /// How the texels fetched by the minifying and magnifying filters are combined.
+    ///
+    /// ## Compatibility
+    ///
+    /// Values other than `WeightedAverage` require the `GL_ARB_texture_filter_minmax`
+    /// extension. Drawing with a sampler that uses them returns a
+    /// `DrawError::ReductionModeNotSupported` otherwise.
+    pub reduction_mode: SamplerReductionMode,
 }
 
 impl Default for SamplerBehavior {
@@ -172,6 +343,167 @@ impl Default for SamplerBehavior {
             minify_filter: MinifySamplerFilter::LinearMipmapLinear,
             magnify_filter: MagnifySamplerFilter::Linear,
             max_anisotropy: 1,
+            depth_texture_comparison: None,
+            reduction_mode: SamplerReductionMode::WeightedAverage,
+        }
+    }
+}
+
+impl SamplerBehavior {
+    /// Builds a behavior that uses these filters and this wrap function for all three
+    /// coordinates. The other parameters have their default value.
+    #[inline]
+    pub fn new(minify: MinifySamplerFilter, magnify: MagnifySamplerFilter,
+               wrap: SamplerWrapFunction) -> SamplerBehavior
+    {
+        SamplerBehavior {
+            wrap_function: (wrap, wrap, wrap),
+            minify_filter: minify,
+            magnify_filter: magnify,
+            .. Default::default()
+        }
+    }
+
+    /// Repeats the texture on all axes and uses trilinear filtering.
+    ///
+    /// Like the default behavior, this expects the texture to have mipmaps.
+    #[inline]
+    pub fn repeat_linear() -> SamplerBehavior {
+        SamplerBehavior {
+            wrap_function: (
+                SamplerWrapFunction::Repeat,
+                SamplerWrapFunction::Repeat,
+                SamplerWrapFunction::Repeat
+            ),
+            .. Default::default()
+        }
+    }
+
+    /// Clamps the coordinates on all axes and loads the nearest texel of the main level.
+    #[inline]
+    pub fn clamp_nearest() -> SamplerBehavior {
+        SamplerBehavior {
+            minify_filter: MinifySamplerFilter::Nearest,
+            magnify_filter: MagnifySamplerFilter::Nearest,
+            .. SamplerBehavior::clamp_linear_no_mip()
         }
     }
+
+    /// Clamps the coordinates on all axes and uses linear filtering on the main level only.
+    ///
+    /// This is what you want to sample textures that don't have mipmaps, like render targets.
+    /// The default behavior uses the mipmaps when minifying, and sampling a texture that
+    /// doesn't have any returns black.
+    #[inline]
+    pub fn clamp_linear_no_mip() -> SamplerBehavior {
+        SamplerBehavior {
+            wrap_function: (
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp,
+                SamplerWrapFunction::Clamp
+            ),
+            minify_filter: MinifySamplerFilter::Linear,
+            magnify_filter: MagnifySamplerFilter::Linear,
+            .. Default::default()
+        }
+    }
+}
+
+/// A sampler object whose parameters are looked up only once.
+///
+/// Each time a `Sampler` is used in a draw call, its `SamplerBehavior` is hashed in order to find
+/// the corresponding OpenGL sampler object. A `SharedSampler` does this lookup when it is
+/// created, which avoids the cost of hashing when the same parameters are used by a lot of
+/// draw calls.
+///
+/// Use a `(&texture, &shared_sampler)` tuple as a uniform value to sample a texture with it.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let texture: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+/// let behavior = glium::uniforms::SamplerBehavior {
+///     magnify_filter: glium::uniforms::MagnifySamplerFilter::Nearest,
+///     .. Default::default()
+/// };
+///
+/// let sampler = glium::uniforms::SharedSampler::new(&display, behavior).unwrap();
+///
+/// let uniforms = uniform! {
+///     color_texture: (&texture, &sampler)
+/// };
+/// # }
+/// ```
+pub struct SharedSampler {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    behavior: SamplerBehavior,
+}
+
+impl SharedSampler {
+    /// Builds a new shared sampler.
+    ///
+    /// The sampler object is taken from the same cache as the one used by `Sampler`, and lives
+    /// as long as the context.
+    ///
+    /// Returns the same errors as a draw call that uses a `Sampler` with this behavior.
+    pub fn new<F: ?Sized>(facade: &F, behavior: SamplerBehavior)
+                          -> Result<SharedSampler, DrawError> where F: Facade
+    {
+        let id = {
+            let mut ctxt = facade.get_context().make_current();
+            try!(sampler_object::get_sampler(&mut ctxt, &behavior))
+        };
+
+        Ok(SharedSampler {
+            context: facade.get_context().clone(),
+            id: id,
+            behavior: behavior,
+        })
+    }
+
+    /// Returns the parameters of the sampler.
+    #[inline]
+    pub fn get_behavior(&self) -> &SamplerBehavior {
+        &self.behavior
+    }
+
+    /// Returns the context this sampler belongs to.
+    #[inline]
+    pub fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+}
+
+impl ::std::fmt::Debug for SharedSampler {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
+        write!(fmt, "SharedSampler #{} ({:?})", self.id, self.behavior)
+    }
+}
+
+impl Labeled for SharedSampler {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.context.make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::SAMPLER, self.id), label)
+    }
+}
+
+impl GlObject for SharedSampler {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl<'a, T: 'a> AsUniformValue for (&'a T, &'a SharedSampler) where T: Deref<Target = TextureAny> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::TextureWithSharedSampler(&**self.0, self.1)
+    }
 }
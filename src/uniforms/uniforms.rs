@@ -1,4 +1,4 @@
-use uniforms::{Uniforms, UniformValue, AsUniformValue};
+use uniforms::{Uniforms, UniformValue, AsUniformValue, UniformStruct};
 
 /// Object that can be used when you don't have any uniforms.
 #[derive(Debug, Copy, Clone)]
@@ -53,3 +53,139 @@ impl<'n, T, R> Uniforms for UniformsStorage<'n, T, R> where T: AsUniformValue, R
         self.rest.visit_values(output);
     }
 }
+
+/// Retained set of uniforms that can be reused between multiple draw calls.
+///
+/// Contrary to `UniformsStorage` or the `uniform!` macro, the set is built once and then only
+/// the values that change between two draws (for example the model matrix) need to be updated
+/// with `set`. The other values (camera, lights, ...) stay in the set.
+///
+/// Uniform locations are resolved through the program's introspection data and are never
+/// queried from the driver while drawing. Values that didn't change since the last draw with the
+/// same program are not uploaded again.
+///
+/// ```no_run
+/// # let view: [[f32; 4]; 4] = unsafe { ::std::mem::uninitialized() };
+/// # let models: Vec<[[f32; 4]; 4]> = unsafe { ::std::mem::uninitialized() };
+/// let mut uniforms = glium::uniforms::UniformSet::new();
+/// uniforms.set("view", &view);
+///
+/// for model in models.iter() {
+///     uniforms.set("model", model);
+///     // frame.draw(&vertex_buffer, &indices, &program, &uniforms, &Default::default())
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct UniformSet<'a> {
+    values: Vec<(String, UniformValue<'a>)>,
+}
+
+impl<'a> UniformSet<'a> {
+    /// Builds a new empty set.
+    #[inline]
+    pub fn new() -> UniformSet<'a> {
+        UniformSet {
+            values: Vec::new(),
+        }
+    }
+
+    /// Sets the value of a uniform, replacing the previous value with the same name if any.
+    #[inline]
+    pub fn set<T: ?Sized>(&mut self, name: &str, value: &'a T) where T: AsUniformValue {
+        self.set_value(name, value.as_uniform_value());
+    }
+
+    /// Sets the raw value of a uniform, replacing the previous value with the same name if any.
+    pub fn set_value(&mut self, name: &str, value: UniformValue<'a>) {
+        for &mut (ref n, ref mut v) in self.values.iter_mut() {
+            if n == name {
+                *v = value;
+                return;
+            }
+        }
+
+        self.values.push((name.to_owned(), value));
+    }
+
+    /// Sets the value of each member of a uniform whose type is a GLSL `struct`.
+    ///
+    /// The field `member` of the value is set to the uniform named `name.member`.
+    pub fn set_struct<T: ?Sized>(&mut self, name: &str, value: &'a T) where T: UniformStruct {
+        value.visit_fields(|field, value| {
+            self.set_value(&format!("{}.{}", name, field), value);
+        });
+    }
+
+    /// Sets the value of each member of each element of a uniform whose type is an array of
+    /// GLSL `struct`s.
+    ///
+    /// The field `member` of the value at index `i` is set to the uniform named
+    /// `name[i].member`.
+    ///
+    /// ```no_run
+    /// # #[macro_use] extern crate glium;
+    /// # fn main() {
+    /// #[derive(Copy, Clone)]
+    /// struct Light {
+    ///     position: [f32; 3],
+    ///     color: [f32; 3],
+    /// }
+    ///
+    /// implement_uniform_struct!(Light, position, color);
+    ///
+    /// // matches `uniform Light lights[2];` in GLSL
+    /// let lights = [
+    ///     Light { position: [0.0, 1.0, 0.0], color: [1.0, 1.0, 1.0] },
+    ///     Light { position: [5.0, 1.0, 0.0], color: [1.0, 0.0, 0.0] },
+    /// ];
+    ///
+    /// let mut uniforms = glium::uniforms::UniformSet::new();
+    /// uniforms.set_struct_array("lights", &lights);
+    /// # }
+    /// ```
+    pub fn set_struct_array<T>(&mut self, name: &str, values: &'a [T]) where T: UniformStruct {
+        for (index, value) in values.iter().enumerate() {
+            value.visit_fields(|field, value| {
+                self.set_value(&format!("{}[{}].{}", name, index, field), value);
+            });
+        }
+    }
+
+    /// Removes a uniform from the set. Returns `true` if it was present.
+    pub fn remove(&mut self, name: &str) -> bool {
+        match self.values.iter().position(|&(ref n, _)| n == name) {
+            Some(index) => {
+                self.values.remove(index);
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Returns the number of uniforms in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if the set doesn't contain any uniform.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Removes all the uniforms from the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl<'s> Uniforms for UniformSet<'s> {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        for &(ref name, value) in self.values.iter() {
+            output(name, value);
+        }
+    }
+}
@@ -4,11 +4,17 @@ use program::ShaderStage;
 use texture;
 
 use uniforms::AsUniformValue;
+use uniforms::ImageUnitBehavior;
 use uniforms::LayoutMismatchError;
 use uniforms::UniformBlock;
 use uniforms::SamplerBehavior;
+use uniforms::SharedSampler;
 
 use buffer::BufferAnySlice;
+use RawUniformValue;
+
+use std::mem;
+use std::ptr;
 
 /// Type of a uniform in a program.
 #[allow(missing_docs)]
@@ -130,6 +136,9 @@ pub enum UniformType {
     Image2dMultisampleArray,
     IImage2dMultisampleArray,
     UImage2dMultisampleArray,
+    ImageCubeArray,
+    IImageCubeArray,
+    UImageCubeArray,
     AtomicCounterUint,
 }
 
@@ -194,6 +203,7 @@ pub enum UniformValue<'a> {
     IntegralTexture2d(&'a texture::IntegralTexture2d, Option<SamplerBehavior>),
     UnsignedTexture2d(&'a texture::UnsignedTexture2d, Option<SamplerBehavior>),
     DepthTexture2d(&'a texture::DepthTexture2d, Option<SamplerBehavior>),
+    DepthStencilTexture2d(&'a texture::DepthStencilTexture2d, Option<SamplerBehavior>),
     Texture2dMultisample(&'a texture::Texture2dMultisample, Option<SamplerBehavior>),
     SrgbTexture2dMultisample(&'a texture::SrgbTexture2dMultisample, Option<SamplerBehavior>),
     IntegralTexture2dMultisample(&'a texture::IntegralTexture2dMultisample, Option<SamplerBehavior>),
@@ -225,6 +235,7 @@ pub enum UniformValue<'a> {
     IntegralTexture2dMultisampleArray(&'a texture::IntegralTexture2dMultisampleArray, Option<SamplerBehavior>),
     UnsignedTexture2dMultisampleArray(&'a texture::UnsignedTexture2dMultisampleArray, Option<SamplerBehavior>),
     DepthTexture2dMultisampleArray(&'a texture::DepthTexture2dMultisampleArray, Option<SamplerBehavior>),
+    TextureRectangle(&'a texture::TextureRectangle, Option<SamplerBehavior>),
     Cubemap(&'a texture::Cubemap, Option<SamplerBehavior>),
     CompressedCubemap(&'a texture::CompressedCubemap, Option<SamplerBehavior>),
     SrgbCubemap(&'a texture::SrgbCubemap, Option<SamplerBehavior>),
@@ -240,6 +251,14 @@ pub enum UniformValue<'a> {
     UnsignedCubemapArray(&'a texture::UnsignedCubemapArray, Option<SamplerBehavior>),
     DepthCubemapArray(&'a texture::DepthCubemapArray, Option<SamplerBehavior>),
     BufferTexture(texture::buffer_texture::BufferTextureRef<'a>),
+    TextureWithSharedSampler(&'a texture::TextureAny, &'a SharedSampler),
+    ImageUnit(&'a texture::TextureAny, ImageUnitBehavior),
+    /// Value whose type is only known at runtime, for example a material parameter loaded from
+    /// a file. Contains the type and the bytes of the value in the native endianness.
+    ///
+    /// Only the non-opaque types are supported, and the number of bytes must be the size of the
+    /// type. Booleans are 32-bits integers.
+    Raw(UniformType, &'a [u8]),
 }
 
 impl<'a> Clone for UniformValue<'a> {
@@ -295,6 +314,13 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::UnsignedTexture2d(_, _), UniformType::USampler2d) => true,
             (&UniformValue::DepthTexture2d(_, _), UniformType::Sampler2d) => true,
             (&UniformValue::DepthTexture2d(_, _), UniformType::Sampler2dShadow) => true,
+            (&UniformValue::DepthStencilTexture2d(tex, _), UniformType::Sampler2d) |
+            (&UniformValue::DepthStencilTexture2d(tex, _), UniformType::Sampler2dShadow) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilMode::Depth
+            },
+            (&UniformValue::DepthStencilTexture2d(tex, _), UniformType::USampler2d) => {
+                tex.get_depth_stencil_mode() == texture::DepthStencilMode::Stencil
+            },
             (&UniformValue::Texture3d(_, _), UniformType::Sampler3d) => true,
             (&UniformValue::CompressedTexture3d(_, _), UniformType::Sampler3d) => true,
             (&UniformValue::SrgbTexture3d(_, _), UniformType::Sampler3d) => true,
@@ -348,11 +374,193 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::IntegralTexture2dMultisample(..), UniformType::ISampler2dMultisample) => true,
             (&UniformValue::UnsignedTexture2dMultisample(..), UniformType::USampler2dMultisample) => true,
             (&UniformValue::DepthTexture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
+            (&UniformValue::TextureRectangle(..), UniformType::Sampler2dRect) => true,
+            (&UniformValue::TextureWithSharedSampler(texture, _), ty) => {
+                is_texture_usable_with(texture, ty)
+            },
+            (&UniformValue::ImageUnit(texture, ref behavior), ty) => {
+                is_image_usable_with(texture, behavior.layered, ty)
+            },
+            (&UniformValue::Raw(raw_ty, data), ty) => {
+                raw_ty == ty && decode_raw_value(ty, data).is_some()
+            },
             _ => false,
         }
     }
 }
 
+/// Turns the bytes of a `UniformValue::Raw` into a value.
+///
+/// Returns `None` if the type is not supported, and `Some(Err(size))` if the length of `data`
+/// is not `size`.
+pub fn decode_raw_value(ty: UniformType, data: &[u8]) -> Option<Result<RawUniformValue, usize>> {
+    macro_rules! read {
+        ($variant:ident, $t:ty) => ({
+            let size = mem::size_of::<$t>();
+            if data.len() != size {
+                return Some(Err(size));
+            }
+
+            let mut value: $t = unsafe { mem::uninitialized() };
+            unsafe {
+                ptr::copy_nonoverlapping(data.as_ptr(), &mut value as *mut $t as *mut u8, size);
+            }
+            Some(Ok(RawUniformValue::$variant(value)))
+        });
+    }
+
+    match ty {
+        UniformType::Float => read!(Float, f32),
+        UniformType::FloatVec2 => read!(Vec2, [f32; 2]),
+        UniformType::FloatVec3 => read!(Vec3, [f32; 3]),
+        UniformType::FloatVec4 => read!(Vec4, [f32; 4]),
+        UniformType::FloatMat2 => read!(Mat2, [[f32; 2]; 2]),
+        UniformType::FloatMat3 => read!(Mat3, [[f32; 3]; 3]),
+        UniformType::FloatMat4 => read!(Mat4, [[f32; 4]; 4]),
+        UniformType::Double => read!(Double, f64),
+        UniformType::DoubleVec2 => read!(DoubleVec2, [f64; 2]),
+        UniformType::DoubleVec3 => read!(DoubleVec3, [f64; 3]),
+        UniformType::DoubleVec4 => read!(DoubleVec4, [f64; 4]),
+        UniformType::DoubleMat2 => read!(DoubleMat2, [[f64; 2]; 2]),
+        UniformType::DoubleMat3 => read!(DoubleMat3, [[f64; 3]; 3]),
+        UniformType::DoubleMat4 => read!(DoubleMat4, [[f64; 4]; 4]),
+        UniformType::Int | UniformType::Bool => read!(SignedInt, i32),
+        UniformType::IntVec2 | UniformType::BoolVec2 => read!(IntVec2, [i32; 2]),
+        UniformType::IntVec3 | UniformType::BoolVec3 => read!(IntVec3, [i32; 3]),
+        UniformType::IntVec4 | UniformType::BoolVec4 => read!(IntVec4, [i32; 4]),
+        UniformType::UnsignedInt => read!(UnsignedInt, u32),
+        UniformType::UnsignedIntVec2 => read!(UnsignedIntVec2, [u32; 2]),
+        UniformType::UnsignedIntVec3 => read!(UnsignedIntVec3, [u32; 3]),
+        UniformType::UnsignedIntVec4 => read!(UnsignedIntVec4, [u32; 4]),
+        UniformType::Int64 => read!(Int64, i64),
+        UniformType::Int64Vec2 => read!(Int64Vec2, [i64; 2]),
+        UniformType::Int64Vec3 => read!(Int64Vec3, [i64; 3]),
+        UniformType::Int64Vec4 => read!(Int64Vec4, [i64; 4]),
+        UniformType::UnsignedInt64 => read!(UnsignedInt64, u64),
+        UniformType::UnsignedInt64Vec2 => read!(UnsignedInt64Vec2, [u64; 2]),
+        UniformType::UnsignedInt64Vec3 => read!(UnsignedInt64Vec3, [u64; 3]),
+        UniformType::UnsignedInt64Vec4 => read!(UnsignedInt64Vec4, [u64; 4]),
+        _ => None,
+    }
+}
+
+/// Returns true if a texture whose type is only known at runtime can be used with a uniform of
+/// the given type.
+fn is_texture_usable_with(texture: &texture::TextureAny, ty: UniformType) -> bool {
+    use texture::DepthStencilMode;
+    use texture::Dimensions;
+    use texture::TextureKind;
+
+    // the float, signed, unsigned and shadow samplers corresponding to the dimensions
+    let (float, int, uint, shadow) = match texture.dimensions() {
+        Dimensions::Texture1d { .. } => {
+            (UniformType::Sampler1d, UniformType::ISampler1d, UniformType::USampler1d,
+             Some(UniformType::Sampler1dShadow))
+        },
+        Dimensions::Texture1dArray { .. } => {
+            (UniformType::Sampler1dArray, UniformType::ISampler1dArray,
+             UniformType::USampler1dArray, Some(UniformType::Sampler1dArrayShadow))
+        },
+        Dimensions::Texture2d { .. } => {
+            (UniformType::Sampler2d, UniformType::ISampler2d, UniformType::USampler2d,
+             Some(UniformType::Sampler2dShadow))
+        },
+        Dimensions::Texture2dArray { .. } => {
+            (UniformType::Sampler2dArray, UniformType::ISampler2dArray,
+             UniformType::USampler2dArray, Some(UniformType::Sampler2dArrayShadow))
+        },
+        Dimensions::Texture2dMultisample { .. } => {
+            (UniformType::Sampler2dMultisample, UniformType::ISampler2dMultisample,
+             UniformType::USampler2dMultisample, None)
+        },
+        Dimensions::Texture2dMultisampleArray { .. } => {
+            (UniformType::Sampler2dMultisampleArray, UniformType::ISampler2dMultisampleArray,
+             UniformType::USampler2dMultisampleArray, None)
+        },
+        Dimensions::Texture3d { .. } => {
+            (UniformType::Sampler3d, UniformType::ISampler3d, UniformType::USampler3d, None)
+        },
+        Dimensions::TextureRectangle { .. } => {
+            (UniformType::Sampler2dRect, UniformType::ISampler2dRect,
+             UniformType::USampler2dRect, Some(UniformType::Sampler2dRectShadow))
+        },
+        Dimensions::Cubemap { .. } => {
+            (UniformType::SamplerCube, UniformType::ISamplerCube, UniformType::USamplerCube,
+             Some(UniformType::SamplerCubeShadow))
+        },
+        Dimensions::CubemapArray { .. } => {
+            (UniformType::SamplerCubeArray, UniformType::ISamplerCubeArray,
+             UniformType::USamplerCubeArray, Some(UniformType::SamplerCubeArrayShadow))
+        },
+    };
+
+    match texture.kind() {
+        TextureKind::Float => ty == float,
+        TextureKind::Integral => ty == int,
+        TextureKind::Unsigned => ty == uint,
+        TextureKind::Depth => ty == float || Some(ty) == shadow,
+        TextureKind::DepthStencil => match texture.get_depth_stencil_mode() {
+            DepthStencilMode::Depth => ty == float || Some(ty) == shadow,
+            DepthStencilMode::Stencil => ty == uint,
+        },
+        TextureKind::Stencil => false,
+    }
+}
+
+/// Returns true if a texture bound to an image unit can be used with a uniform of the given
+/// type.
+fn is_image_usable_with(texture: &texture::TextureAny, layered: bool, ty: UniformType) -> bool {
+    use texture::Dimensions;
+    use texture::TextureKind;
+
+    // a single layer of a layered texture is seen as a 1D or 2D image
+    let (float, int, uint) = match (texture.dimensions(), layered) {
+        (Dimensions::Texture1d { .. }, _) | (Dimensions::Texture1dArray { .. }, false) => {
+            (UniformType::Image1d, UniformType::IImage1d, UniformType::UImage1d)
+        },
+        (Dimensions::Texture1dArray { .. }, true) => {
+            (UniformType::Image1dArray, UniformType::IImage1dArray, UniformType::UImage1dArray)
+        },
+        (Dimensions::Texture2d { .. }, _) | (Dimensions::Texture2dArray { .. }, false) |
+        (Dimensions::Texture3d { .. }, false) | (Dimensions::Cubemap { .. }, false) |
+        (Dimensions::CubemapArray { .. }, false) => {
+            (UniformType::Image2d, UniformType::IImage2d, UniformType::UImage2d)
+        },
+        (Dimensions::Texture2dArray { .. }, true) => {
+            (UniformType::Image2dArray, UniformType::IImage2dArray, UniformType::UImage2dArray)
+        },
+        (Dimensions::Texture2dMultisample { .. }, _) |
+        (Dimensions::Texture2dMultisampleArray { .. }, false) => {
+            (UniformType::Image2dMultisample, UniformType::IImage2dMultisample,
+             UniformType::UImage2dMultisample)
+        },
+        (Dimensions::Texture2dMultisampleArray { .. }, true) => {
+            (UniformType::Image2dMultisampleArray, UniformType::IImage2dMultisampleArray,
+             UniformType::UImage2dMultisampleArray)
+        },
+        (Dimensions::Texture3d { .. }, true) => {
+            (UniformType::Image3d, UniformType::IImage3d, UniformType::UImage3d)
+        },
+        (Dimensions::TextureRectangle { .. }, _) => {
+            (UniformType::Image2dRect, UniformType::IImage2dRect, UniformType::UImage2dRect)
+        },
+        (Dimensions::Cubemap { .. }, true) => {
+            (UniformType::ImageCube, UniformType::IImageCube, UniformType::UImageCube)
+        },
+        (Dimensions::CubemapArray { .. }, true) => {
+            (UniformType::ImageCubeArray, UniformType::IImageCubeArray,
+             UniformType::UImageCubeArray)
+        },
+    };
+
+    match texture.kind() {
+        TextureKind::Float => ty == float,
+        TextureKind::Integral => ty == int,
+        TextureKind::Unsigned => ty == uint,
+        TextureKind::Depth | TextureKind::DepthStencil | TextureKind::Stencil => false,
+    }
+}
+
 macro_rules! impl_uniform_block_basic {
     ($ty:ty, $uniform_ty:expr) => (
         impl UniformBlock for $ty {
@@ -976,6 +1184,14 @@ impl AsUniformValue for (u64, u64, u64, u64) {
 
 impl_uniform_block_basic!((u64, u64, u64, u64), UniformType::UnsignedInt64Vec4);
 
+// Raw bytes with a type only known at runtime
+impl<'a> AsUniformValue for (UniformType, &'a [u8]) {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Raw(self.0, self.1)
+    }
+}
+
 // Subroutines
 impl<'a> AsUniformValue for (&'a str, ShaderStage) {
     #[inline]
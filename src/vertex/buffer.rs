@@ -1,11 +1,13 @@
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 use std::ops::{Deref, DerefMut};
 use utils::range::RangeArgument;
 
 use buffer::{Buffer, BufferSlice, BufferMutSlice, BufferAny, BufferType, BufferMode, BufferCreationError, Content};
+use buffer::ReadError;
 use vertex::{Vertex, VerticesSource, IntoVerticesSource, PerInstance};
-use vertex::format::VertexFormat;
+use vertex::format::{AttributeType, VertexFormat};
 
 use gl;
 use GlObject;
@@ -25,6 +27,15 @@ pub enum CreationError {
 
     /// Error while creating the vertex buffer.
     BufferCreationError(BufferCreationError),
+
+    /// The stride is 0, or the length of the data is not a multiple of the stride.
+    InvalidStride,
+
+    /// One of the attributes doesn't fit inside of a vertex.
+    AttributeOutOfBounds,
+
+    /// The existing content couldn't be read back in order to be copied to a new buffer.
+    ContentReadError(ReadError),
 }
 
 impl From<BufferCreationError> for CreationError {
@@ -46,6 +57,11 @@ impl Error for CreationError {
         match *self {
             FormatNotSupported => "The vertex format is not supported by the backend",
             BufferCreationError(_) => "Error while creating the vertex buffer",
+            InvalidStride => "The stride is 0, or the length of the data is not a multiple of \
+                              the stride",
+            AttributeOutOfBounds => "One of the attributes doesn't fit inside of a vertex",
+            ContentReadError(_) => "The existing content couldn't be read back in order to be \
+                                    copied to a new buffer",
         }
     }
 
@@ -53,7 +69,8 @@ impl Error for CreationError {
         use self::CreationError::*;
         match *self {
             BufferCreationError(ref error) => Some(error),
-            FormatNotSupported => None,
+            ContentReadError(ref error) => Some(error),
+            _ => None,
         }
     }
 }
@@ -147,6 +164,17 @@ impl<T> VertexBuffer<T> where T: Vertex {
         VertexBuffer::new_impl(facade, data, BufferMode::Dynamic)
     }
 
+    /// Builds a new vertex buffer.
+    ///
+    /// This function will create a buffer whose content is intended to be replaced every frame.
+    /// See `BufferMode::Stream`.
+    #[inline]
+    pub fn stream<F: ?Sized>(facade: &F, data: &[T]) -> Result<VertexBuffer<T>, CreationError>
+                     where F: Facade
+    {
+        VertexBuffer::new_impl(facade, data, BufferMode::Stream)
+    }
+
     /// Builds a new vertex buffer.
     #[inline]
     pub fn persistent<F: ?Sized>(facade: &F, data: &[T]) -> Result<VertexBuffer<T>, CreationError>
@@ -155,7 +183,15 @@ impl<T> VertexBuffer<T> where T: Vertex {
         VertexBuffer::new_impl(facade, data, BufferMode::Persistent)
     }
 
-    /// Builds a new vertex buffer.
+    /// Builds a new vertex buffer whose content is not supposed to change, like a static mesh.
+    ///
+    /// The buffer is created with `glBufferStorage` without any flag if the backend supports
+    /// OpenGL 4.4 or `GL_ARB_buffer_storage`, which lets the driver put it in the most
+    /// appropriate memory, and with `glBufferData` and `GL_STATIC_DRAW` otherwise.
+    ///
+    /// Modifying the content of the buffer is still possible, but is very slow since the data
+    /// goes through a temporary buffer. Like for all the buffers created with
+    /// `BufferMode::Immutable`, `write` doesn't return an error.
     #[inline]
     pub fn immutable<F: ?Sized>(facade: &F, data: &[T]) -> Result<VertexBuffer<T>, CreationError>
                         where F: Facade
@@ -196,6 +232,16 @@ impl<T> VertexBuffer<T> where T: Vertex {
         VertexBuffer::empty_impl(facade, elements, BufferMode::Dynamic)
     }
 
+    /// Builds an empty vertex buffer.
+    ///
+    /// The parameter indicates the number of elements.
+    #[inline]
+    pub fn empty_stream<F: ?Sized>(facade: &F, elements: usize) -> Result<VertexBuffer<T>, CreationError>
+                           where F: Facade
+    {
+        VertexBuffer::empty_impl(facade, elements, BufferMode::Stream)
+    }
+
     /// Builds an empty vertex buffer.
     ///
     /// The parameter indicates the number of elements.
@@ -297,6 +343,10 @@ impl<T> VertexBuffer<T> where T: Copy {
     /// Accesses a slice of the buffer.
     ///
     /// Returns `None` if the slice is out of range.
+    ///
+    /// The slice can be used as a source of vertices when drawing. Calling `write` on the slice
+    /// only uploads the part of the buffer that it covers, which is cheaper than rewriting
+    /// the whole buffer when only a region changes.
     #[inline]
     pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<VertexBufferSlice<T>> {
         let slice = match self.buffer.slice(range) {
@@ -444,6 +494,75 @@ pub struct VertexBufferAny {
 }
 
 impl VertexBufferAny {
+    /// Builds a new vertex buffer from raw bytes and a format described at runtime.
+    ///
+    /// This is useful when the layout of the vertices is only known at runtime, for example
+    /// when loading a model from a file.
+    ///
+    /// `stride` is the number of bytes between two consecutive vertices in `data`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { ::std::mem::uninitialized() };
+    /// # let data: Vec<u8> = unsafe { ::std::mem::uninitialized() };
+    /// use glium::vertex::{AttributeType, VertexAttribDesc};
+    ///
+    /// let attributes = [
+    ///     VertexAttribDesc::new("position", 0, AttributeType::F32F32F32, false),
+    ///     VertexAttribDesc::new("color", 12, AttributeType::U8U8U8U8, true),
+    /// ];
+    ///
+    /// let vertex_buffer = glium::vertex::VertexBufferAny::from_raw(&display, &data, 16,
+    ///                                                              &attributes).unwrap();
+    /// ```
+    pub fn from_raw<F: ?Sized>(facade: &F, data: &[u8], stride: usize,
+                               attributes: &[VertexAttribDesc])
+                               -> Result<VertexBufferAny, CreationError>
+                               where F: Facade
+    {
+        VertexBufferAny::from_raw_impl(facade, data, stride, attributes, BufferMode::Default)
+    }
+
+    /// Dynamic version of `from_raw`.
+    #[inline]
+    pub fn from_raw_dynamic<F: ?Sized>(facade: &F, data: &[u8], stride: usize,
+                                       attributes: &[VertexAttribDesc])
+                                       -> Result<VertexBufferAny, CreationError>
+                                       where F: Facade
+    {
+        VertexBufferAny::from_raw_impl(facade, data, stride, attributes, BufferMode::Dynamic)
+    }
+
+    fn from_raw_impl<F: ?Sized>(facade: &F, data: &[u8], stride: usize,
+                                attributes: &[VertexAttribDesc], mode: BufferMode)
+                                -> Result<VertexBufferAny, CreationError>
+                                where F: Facade
+    {
+        if stride == 0 || data.len() % stride != 0 {
+            return Err(CreationError::InvalidStride);
+        }
+
+        for attribute in attributes {
+            if attribute.offset + attribute.ty.get_size_bytes() > stride {
+                return Err(CreationError::AttributeOutOfBounds);
+            }
+
+            if !attribute.ty.is_supported(facade) {
+                return Err(CreationError::FormatNotSupported);
+            }
+        }
+
+        let bindings = attributes.iter()
+                                 .map(|a| (a.name.clone(), a.offset, a.ty, a.normalize))
+                                 .collect::<Vec<_>>();
+
+        Ok(VertexBufferAny {
+            buffer: try!(BufferAny::new_raw(facade, data, stride, BufferType::ArrayBuffer, mode)),
+            bindings: Cow::Owned(bindings),
+        })
+    }
+
     /// Returns the number of bytes between two consecutive elements in the buffer.
     #[inline]
     pub fn get_elements_size(&self) -> usize {
@@ -509,6 +628,36 @@ impl<'a> IntoVerticesSource<'a> for &'a VertexBufferAny {
     }
 }
 
+/// Describes one attribute of a vertex whose format is only known at runtime.
+///
+/// See `VertexBufferAny::from_raw`.
+#[derive(Debug, Clone)]
+pub struct VertexAttribDesc {
+    /// Name of the attribute in the program.
+    pub name: Cow<'static, str>,
+    /// Offset in bytes of the attribute from the start of the vertex.
+    pub offset: usize,
+    /// Type and number of components of the attribute.
+    pub ty: AttributeType,
+    /// Whether integer values must be normalized to the `[0, 1]` or `[-1, 1]` range.
+    pub normalize: bool,
+}
+
+impl VertexAttribDesc {
+    /// Builds a new description of an attribute.
+    #[inline]
+    pub fn new<S>(name: S, offset: usize, ty: AttributeType, normalize: bool) -> VertexAttribDesc
+                  where S: Into<Cow<'static, str>>
+    {
+        VertexAttribDesc {
+            name: name.into(),
+            offset: offset,
+            ty: ty,
+            normalize: normalize,
+        }
+    }
+}
+
 /// Instancing is not supported by the backend.
 #[derive(Debug, Copy, Clone)]
 pub struct InstancingNotSupported;
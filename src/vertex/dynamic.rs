@@ -0,0 +1,152 @@
+use std::cmp;
+
+use buffer::Buffer;
+use utils::range::RangeArgument;
+use vertex::{Vertex, VertexBuffer, VertexBufferSlice};
+use vertex::buffer::CreationError;
+
+use backend::Facade;
+
+/// A vertex buffer whose content is rebuilt regularly and that grows when needed.
+///
+/// This is the kind of buffer that immediate-mode renderers need: call `clear()` at the start
+/// of each frame, push your vertices with `push_slice()`, then draw the slice returned by
+/// `finish()`.
+///
+/// The buffer keeps track of the number of vertices that have been pushed separately from its
+/// capacity. When the pushed data doesn't fit, a new buffer that is at least twice as large is
+/// allocated, similar to what `Vec` does.
+///
+/// # Implementation
+///
+/// `clear()` invalidates the content of the buffer (orphaning), so that the next writes don't
+/// need to wait for the draw calls that use the previous content to finish.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// #[derive(Copy, Clone)]
+/// struct Vertex { position: [f32; 2] }
+/// implement_vertex!(Vertex, position);
+///
+/// let mut buffer = glium::vertex::DynamicVertexBuffer::new(&display, 64).unwrap();
+///
+/// buffer.clear();
+/// buffer.push_slice(&[Vertex { position: [0.0, 0.0] }, Vertex { position: [1.0, 0.0] }]).unwrap();
+/// let slice = buffer.finish();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DynamicVertexBuffer<T> where T: Copy {
+    buffer: VertexBuffer<T>,
+    len: usize,
+}
+
+impl<T> DynamicVertexBuffer<T> where T: Vertex {
+    /// Builds an empty buffer that can hold `capacity` vertices before it needs to grow.
+    #[inline]
+    pub fn new<F: ?Sized>(facade: &F, capacity: usize)
+                          -> Result<DynamicVertexBuffer<T>, CreationError> where F: Facade
+    {
+        let buffer = try!(VertexBuffer::empty_dynamic(facade, cmp::max(capacity, 1)));
+
+        Ok(DynamicVertexBuffer {
+            buffer: buffer,
+            len: 0,
+        })
+    }
+
+    /// Appends vertices at the end of the buffer.
+    ///
+    /// If the vertices don't fit in the buffer, a larger buffer is allocated and the vertices
+    /// that have already been pushed are copied to it. If the backend can neither copy between
+    /// buffers nor read them back, `ContentReadError` is returned and the buffer is left
+    /// untouched.
+    pub fn push_slice(&mut self, data: &[T]) -> Result<(), CreationError> {
+        let new_len = self.len + data.len();
+
+        if new_len > self.capacity() {
+            let capacity = cmp::max(self.capacity() * 2, new_len);
+            let new_buffer = try!(VertexBuffer::empty_dynamic(self.buffer.get_context(),
+                                                              capacity));
+
+            if self.len != 0 {
+                let old: &Buffer<[T]> = &self.buffer;
+                let new: &Buffer<[T]> = &new_buffer;
+                let old = old.slice(0 .. self.len).unwrap();
+                let new = new.slice(0 .. self.len).unwrap();
+
+                // `glCopyBufferSubData` is not available everywhere, in which case we go
+                // through the client memory instead
+                if old.copy_to(new).is_err() {
+                    let content = try!(old.read().map_err(CreationError::ContentReadError));
+                    new.write(&content);
+                }
+            }
+
+            self.buffer = new_buffer;
+        }
+
+        if !data.is_empty() {
+            let buffer: &Buffer<[T]> = &self.buffer;
+            buffer.slice(self.len .. new_len).unwrap().write(data);
+        }
+
+        self.len = new_len;
+        Ok(())
+    }
+}
+
+impl<T> DynamicVertexBuffer<T> where T: Copy {
+    /// Removes all the vertices from the buffer.
+    ///
+    /// The capacity of the buffer is left untouched, but its content is invalidated.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.buffer.invalidate();
+    }
+
+    /// Returns a slice containing the vertices that have been pushed since the last call to
+    /// `clear()`.
+    #[inline]
+    pub fn finish(&self) -> VertexBufferSlice<T> {
+        self.buffer.slice(0 .. self.len).unwrap()
+    }
+
+    /// Returns a slice of the vertices that have been pushed since the last call to `clear()`.
+    ///
+    /// Returns `None` if the slice is out of range.
+    #[inline]
+    pub fn slice<R: RangeArgument<usize>>(&self, range: R) -> Option<VertexBufferSlice<T>> {
+        let start = range.start().map_or(0, |e| *e);
+        let end = range.end().map_or(self.len, |e| *e);
+
+        if start > end || end > self.len {
+            return None;
+        }
+
+        self.buffer.slice(start .. end)
+    }
+
+    /// Returns the number of vertices that have been pushed since the last call to `clear()`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no vertex has been pushed since the last call to `clear()`.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of vertices that the buffer can hold before it needs to grow.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+}
@@ -12,7 +12,7 @@ use cgmath;
 use nalgebra;
 
 #[allow(missing_docs)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum AttributeType {
     I8,
     I8I8,
@@ -401,6 +401,31 @@ impl AttributeType {
             AttributeType::FixedFloatI16U16 => 1,
         }
     }
+
+    /// Returns the number of vertex attribute locations that an attribute of this type uses.
+    ///
+    /// Matrices use one location per column, and vectors of three or four 64-bits values use
+    /// two locations.
+    pub fn get_num_locations(&self) -> usize {
+        match *self {
+            AttributeType::I64I64I64 | AttributeType::I64I64I64I64 |
+            AttributeType::U64U64U64 | AttributeType::U64U64U64U64 |
+            AttributeType::F64F64F64 | AttributeType::F64F64F64F64 => 2,
+            AttributeType::F16x2x2 | AttributeType::F16x3x2 | AttributeType::F16x4x2 => 2,
+            AttributeType::F16x2x3 | AttributeType::F16x3x3 | AttributeType::F16x4x3 => 3,
+            AttributeType::F16x2x4 | AttributeType::F16x3x4 | AttributeType::F16x4x4 => 4,
+            AttributeType::F32x2x2 | AttributeType::F32x3x2 | AttributeType::F32x4x2 => 2,
+            AttributeType::F32x2x3 | AttributeType::F32x3x3 | AttributeType::F32x4x3 => 3,
+            AttributeType::F32x2x4 | AttributeType::F32x3x4 | AttributeType::F32x4x4 => 4,
+            AttributeType::F64x2x2 => 2,
+            AttributeType::F64x2x3 => 3,
+            AttributeType::F64x2x4 => 4,
+            AttributeType::F64x3x2 | AttributeType::F64x4x2 => 4,
+            AttributeType::F64x3x3 | AttributeType::F64x4x3 => 6,
+            AttributeType::F64x3x4 | AttributeType::F64x4x4 => 8,
+            _ => 1,
+        }
+    }
 }
 
 /// Describes the layout of each vertex in a vertex buffer.
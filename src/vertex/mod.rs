@@ -120,6 +120,13 @@ be the same, or a `DrawError::VerticesSourcesLengthMismatch` will be produced.
 In all situation, the length of all per-instance sources must match, or
 `DrawError::InstancesCountMismatch` will be retured.
 
+When you draw a slice of a vertex buffer with an index buffer, the indices are relative to the
+start of the slice. If the backend supports it, glium passes the start of the slice as the base
+vertex of `glDrawElementsBaseVertex`, or of `glDrawElementsInstancedBaseVertex` if there is a
+per-instance source, instead of rebinding the vertex attributes. This means that you can store
+many meshes in a single vertex buffer and a single index buffer, and draw or instance each of
+them by passing a slice of each buffer.
+
 # Transform feedback
 
 Transform feedback allows you to write in a buffer the list of primitives that are generated by
@@ -136,15 +143,19 @@ use std::iter::Chain;
 use std::option::IntoIter;
 
 pub use self::buffer::{VertexBuffer, VertexBufferAny};
-pub use self::buffer::VertexBufferSlice;
+pub use self::buffer::{VertexBufferSlice, VertexAttribDesc};
 pub use self::buffer::CreationError as BufferCreationError;
+pub use self::dynamic::DynamicVertexBuffer;
 pub use self::format::{AttributeType, VertexFormat};
 pub use self::transform_feedback::{is_transform_feedback_supported, TransformFeedbackSession};
+pub use self::transform_feedback::{is_transform_feedback_object_supported, TransformFeedbackObject};
+pub use self::transform_feedback::TransformFeedbackObjectCreationError;
 
 use buffer::BufferAnySlice;
 use CapabilitiesSource;
 
 mod buffer;
+mod dynamic;
 mod format;
 mod transform_feedback;
 
@@ -183,6 +194,10 @@ impl<'a> IntoVerticesSource<'a> for VerticesSource<'a> {
 }
 
 /// Marker that can be passed instead of a buffer to indicate an empty list of buffers.
+///
+/// This is used to draw with a vertex shader that doesn't have any input and that computes
+/// the positions from `gl_VertexID`. Core profiles require a vertex array object to be bound
+/// even for these draws, so glium binds an empty one that is shared by all the programs.
 pub struct EmptyVertexAttributes {
     /// Number of phantom vertices.
     pub len: usize,
@@ -302,8 +317,44 @@ impl_for_tuple!(A, B, C, D, E, F, G);
 // TODO: this should be `unsafe`, but that would break the syntax extension
 pub trait Vertex: Copy + Sized {
     /// Builds the `VertexFormat` representing the layout of this element.
+    ///
+    /// This can be used to inspect the format produced by `implement_vertex!`, for example to
+    /// compare it with the attributes of a program.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate glium;
+    /// # fn main() {
+    /// use glium::vertex::{AttributeType, Vertex};
+    ///
+    /// #[derive(Copy, Clone)]
+    /// struct MyVertex {
+    ///     position: [f32; 3],
+    ///     color: [u8; 4],
+    /// }
+    ///
+    /// implement_vertex!(MyVertex, position, color);
+    ///
+    /// let format = MyVertex::build_bindings();
+    /// assert_eq!(format[0].0, "position");
+    /// assert_eq!(format[0].2, AttributeType::F32F32F32);
+    /// assert_eq!(format[1].1, 12);
+    /// # }
+    /// ```
     fn build_bindings() -> VertexFormat;
 
+    /// Returns the list of attributes of this element, in the same form as the one that is
+    /// passed to `VertexBufferAny::from_raw`.
+    fn attributes() -> Vec<VertexAttribDesc> {
+        Self::build_bindings().iter().map(|&(ref name, offset, ty, normalize)| {
+            VertexAttribDesc {
+                name: name.clone(),
+                offset: offset,
+                ty: ty,
+                normalize: normalize,
+            }
+        }).collect()
+    }
+
     /// Returns true if the backend supports this vertex format.
     fn is_supported<C: ?Sized>(caps: &C) -> bool where C: CapabilitiesSource {
         let format = Self::build_bindings();
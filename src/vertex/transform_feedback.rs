@@ -1,9 +1,15 @@
 use std::{ mem, fmt };
+use std::cell::Cell;
 use std::error::Error;
+use std::rc::Rc;
 
 use version::Api;
 use version::Version;
 use context::CommandContext;
+use context::Context;
+use context::ResourceKind;
+use context::LabeledObject;
+use debug::{Labeled, LabelingNotSupportedError};
 use backend::Facade;
 use BufferExt;
 use GlObject;
@@ -11,6 +17,7 @@ use ContextExt;
 use CapabilitiesSource;
 use TransformFeedbackSessionExt;
 use buffer::{Buffer, BufferAnySlice};
+use index::IndicesSource;
 use index::PrimitiveType;
 use program::OutputPrimitives;
 use program::Program;
@@ -43,7 +50,8 @@ use gl;
 /// - The transform feedback process doesn't necessarily fill the whole buffer. To retrieve the
 ///   number of vertices that are written to the buffer, use a query object (see the
 ///   `draw_parameters` module). It is however usually easy to determine in advance the number of
-///   vertices that will be written based on the input data.
+///   vertices that will be written based on the input data. If you only need to draw the
+///   vertices that have been written, create the session with a `TransformFeedbackObject`.
 ///
 /// - The buffer will obtain either a list of points, a list of lines (two vertices), or a list of
 ///   triangles (three vertices). If you draw a triangle strip or a triangle fan for example,
@@ -94,6 +102,7 @@ use gl;
 pub struct TransformFeedbackSession<'a> {
     buffer: BufferAnySlice<'a>,
     program: &'a Program,
+    object: Option<&'a TransformFeedbackObject>,
 }
 
 /// Error that can happen when creating a `TransformFeedbackSession`.
@@ -156,19 +165,31 @@ impl<'a> TransformFeedbackSession<'a> {
         Ok(TransformFeedbackSession {
             buffer: buffer.as_slice_any(),
             program: program,
+            object: None,
         })
     }
+
+    /// Builds a new transform feedback session that records the number of vertices it
+    /// captures in a transform feedback object.
+    ///
+    /// Once the session has been destroyed, you can draw the vertices of `buffer` without
+    /// knowing how many of them have been written by passing `object.indices(..)` as the
+    /// indices source.
+    pub fn with_object<F: ?Sized, V>(facade: &F, program: &'a Program,
+                                     buffer: &'a mut Buffer<[V]>,
+                                     object: &'a TransformFeedbackObject)
+                     -> Result<TransformFeedbackSession<'a>, TransformFeedbackSessionCreationError>
+                     where F: Facade, V: Vertex + Copy + Send + 'static
+    {
+        let mut session = try!(TransformFeedbackSession::new(facade, program, buffer));
+        session.object = Some(object);
+        Ok(session)
+    }
 }
 
 impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
     fn bind(&self, mut ctxt: &mut CommandContext, draw_primitives: PrimitiveType) {
-        // TODO: check that the state matches what is required
-        if ctxt.state.transform_feedback_enabled.is_some() {
-            unimplemented!();
-        }
-
-        // FIXME: use the memory barrier system
-        self.buffer.bind_to_transform_feedback(ctxt, 0);
+        bind_object(ctxt, self.get_object_id());
 
         unsafe {
             let primitives = match (self.program.get_output_primitives(), draw_primitives) {
@@ -190,9 +211,32 @@ impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
                 (None, PrimitiveType::Patches { .. }) => unreachable!(),
             };
 
-            ctxt.gl.BeginTransformFeedback(primitives);
-            ctxt.state.transform_feedback_enabled = Some(primitives);
-            ctxt.state.transform_feedback_paused = false;
+            // if this session is already capturing, the vertices are appended after the ones
+            // that have been written by the previous draw commands
+            let same_session = ctxt.state.transform_feedback_enabled == Some(primitives) &&
+                               ctxt.state.transform_feedback_buffer == self.buffer.get_id();
+
+            if same_session {
+                if ctxt.state.transform_feedback_paused {
+                    ctxt.gl.ResumeTransformFeedback();
+                    ctxt.state.transform_feedback_paused = false;
+                }
+
+            } else {
+                TransformFeedbackSession::unbind(ctxt);
+
+                // FIXME: use the memory barrier system
+                self.buffer.bind_to_transform_feedback(ctxt, 0);
+
+                ctxt.gl.BeginTransformFeedback(primitives);
+                ctxt.state.transform_feedback_enabled = Some(primitives);
+                ctxt.state.transform_feedback_paused = false;
+                ctxt.state.transform_feedback_buffer = self.buffer.get_id();
+            }
+        }
+
+        if let Some(object) = self.object {
+            object.used.set(true);
         }
     }
 
@@ -206,26 +250,58 @@ impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
             ctxt.gl.EndTransformFeedback();
             ctxt.state.transform_feedback_enabled = None;
             ctxt.state.transform_feedback_paused = false;
+            ctxt.state.transform_feedback_buffer = 0;
+        }
+    }
+
+    fn pause(mut ctxt: &mut CommandContext) {
+        if ctxt.state.transform_feedback_enabled.is_none() ||
+           ctxt.state.transform_feedback_paused
+        {
+            return;
+        }
+
+        if ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+            unsafe { ctxt.gl.PauseTransformFeedback(); }
+            ctxt.state.transform_feedback_paused = true;
+        } else {
+            TransformFeedbackSession::unbind(ctxt);
         }
     }
 
     fn ensure_buffer_out_of_transform_feedback(mut ctxt: &mut CommandContext, buffer: gl::types::GLuint) {
+        // the buffer may be captured by an object that isn't bound
+        let paused_object = ctxt.state.paused_transform_feedback_objects.iter()
+                                .find(|&&(_, b, _)| b == buffer).map(|&(id, _, _)| id);
+        if let Some(id) = paused_object {
+            TransformFeedbackSession::ensure_object_out_of_transform_feedback(ctxt, id);
+        }
+
         if ctxt.state.transform_feedback_enabled.is_none() {
             return;
         }
 
-        let mut needs_unbind = false;
-        for elem in ctxt.state.indexed_transform_feedback_buffer_bindings.iter_mut() {
-            if elem.buffer == buffer {
-                needs_unbind = true;
-                break;
-            }
+        if ctxt.state.transform_feedback_buffer == buffer {
+            TransformFeedbackSession::unbind(ctxt);
         }
+    }
 
-        if needs_unbind {
+    fn ensure_object_out_of_transform_feedback(mut ctxt: &mut CommandContext,
+                                               object: gl::types::GLuint)
+    {
+        if ctxt.state.paused_transform_feedback_objects.iter().any(|&(id, _, _)| id == object) {
+            bind_object(ctxt, object);
+        }
+
+        if ctxt.state.transform_feedback_object == object {
             TransformFeedbackSession::unbind(ctxt);
         }
     }
+
+    #[inline]
+    fn get_object_id(&self) -> gl::types::GLuint {
+        self.object.map(|o| o.id).unwrap_or(0)
+    }
 }
 
 impl<'a> Drop for TransformFeedbackSession<'a> {
@@ -238,3 +314,241 @@ impl<'a> Drop for TransformFeedbackSession<'a> {
         Self::ensure_buffer_out_of_transform_feedback(&mut ctxt, self.buffer.get_id());
     }
 }
+
+/// Error that can happen when creating a `TransformFeedbackObject`.
+#[derive(Debug, Clone)]
+pub enum TransformFeedbackObjectCreationError {
+    /// Transform feedback objects are not supported by the OpenGL implementation.
+    NotSupported,
+}
+
+impl fmt::Display for TransformFeedbackObjectCreationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for TransformFeedbackObjectCreationError {
+    fn description(&self) -> &str {
+        use self::TransformFeedbackObjectCreationError::*;
+        match *self {
+            NotSupported =>
+                "Transform feedback objects are not supported by the OpenGL implementation",
+        }
+    }
+}
+
+/// Returns true if transform feedback objects, and drawing the vertices captured by them, are
+/// supported by the OpenGL implementation.
+#[inline]
+pub fn is_transform_feedback_object_supported<F: ?Sized>(facade: &F) -> bool where F: Facade {
+    facade.get_context().get_version() >= &Version(Api::Gl, 4, 0)
+}
+
+/// A transform feedback object keeps track of the number of vertices that have been captured by
+/// a transform feedback session.
+///
+/// This allows you to draw the captured vertices without knowing how many of them have been
+/// written, and without reading the result of a query. This is typically used by particle
+/// systems, where the output of a draw command is fed back as the input of the next one.
+///
+/// Each object retains the state of its own capture. You can alternate draw commands between
+/// sessions that use different objects, for example one per particle system: when another
+/// object is used, the capture of the previous one is paused, and it is resumed when a draw
+/// command uses its session again. The vertices are then appended after the ones that have
+/// already been written. The capture ends when the session is destroyed or when you draw the
+/// vertices captured by the object.
+///
+/// Double-buffered simulations can use two objects and two buffers, and swap them every frame:
+/// one object captures the new state of the particles while the vertices captured by the other
+/// one are drawn.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use]
+/// # extern crate glium;
+/// # use glium::Surface;
+/// # fn main() {
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+/// # let vb: glium::vertex::VertexBufferAny = unsafe { std::mem::uninitialized() };
+/// # #[derive(Copy, Clone)] struct Vertex { output_val: (f32, f32) }
+/// # implement_vertex!(Vertex, output_val);
+/// use glium::index::PrimitiveType;
+///
+/// let object = glium::vertex::TransformFeedbackObject::new(&display).unwrap();
+/// let mut out_buffer: glium::VertexBuffer<Vertex> =
+///                                         glium::VertexBuffer::empty(&display, 64).unwrap();
+///
+/// {
+///     let session = glium::vertex::TransformFeedbackSession::with_object(&display, &program,
+///                                                                        &mut out_buffer,
+///                                                                        &object).unwrap();
+///
+///     let params = glium::DrawParameters {
+///         transform_feedback: Some(&session),
+///         .. Default::default()
+///     };
+///
+///     display.draw().draw(&vb, glium::index::NoIndices(PrimitiveType::Points), &program,
+///                         &uniform!{}, &params).unwrap();
+/// }
+///
+/// // draws exactly the vertices that have been captured
+/// display.draw().draw(&out_buffer, object.indices(PrimitiveType::Points, 0), &program,
+///                     &uniform!{}, &Default::default()).unwrap();
+/// # }
+/// ```
+pub struct TransformFeedbackObject {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    used: Cell<bool>,
+}
+
+impl TransformFeedbackObject {
+    /// Builds a new transform feedback object.
+    pub fn new<F: ?Sized>(facade: &F)
+                          -> Result<TransformFeedbackObject, TransformFeedbackObjectCreationError>
+                          where F: Facade
+    {
+        if !is_transform_feedback_object_supported(facade) {
+            return Err(TransformFeedbackObjectCreationError::NotSupported);
+        }
+
+        let ctxt = facade.get_context().make_current();
+
+        let id = unsafe {
+            let mut id = mem::uninitialized();
+            ctxt.gl.GenTransformFeedbacks(1, &mut id);
+            id
+        };
+
+        ctxt.resource_created(ResourceKind::TransformFeedback, id);
+
+        Ok(TransformFeedbackObject {
+            context: facade.get_context().clone(),
+            id: id,
+            used: Cell::new(false),
+        })
+    }
+
+    /// Returns true if a transform feedback session has already captured vertices with this
+    /// object.
+    ///
+    /// Drawing with `indices` returns an error if this is false.
+    #[inline]
+    pub fn has_been_used(&self) -> bool {
+        self.used.get()
+    }
+
+    /// Builds an indices source that draws the vertices captured by the latest transform
+    /// feedback session that used this object, in order.
+    ///
+    /// `stream` is the vertex stream whose vertices are drawn. Streams other than `0` are only
+    /// written by geometry shaders that use multiple streams. If the vertices sources contain
+    /// per-instance data, then the draw is instanced, which requires OpenGL 4.2.
+    ///
+    /// The vertices sources that you draw with must contain the buffer that has been captured
+    /// to. The number of vertices is not checked against their length.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `stream` is superior or equal to 4.
+    #[inline]
+    pub fn indices(&self, primitives: PrimitiveType, stream: u32) -> IndicesSource {
+        assert!(stream < 4, "Only the vertex streams 0 to 3 are supported");
+
+        IndicesSource::TransformFeedback {
+            object: self,
+            stream: stream,
+            primitives: primitives,
+        }
+    }
+}
+
+impl fmt::Debug for TransformFeedbackObject {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "Transform feedback object #{}", self.id)
+    }
+}
+
+impl Labeled for TransformFeedbackObject {
+    #[inline]
+    fn label(&self, label: &str) -> Result<(), LabelingNotSupportedError> {
+        let ctxt = self.context.make_current();
+        ctxt.set_object_label(LabeledObject::Name(gl::TRANSFORM_FEEDBACK, self.id), label)
+    }
+}
+
+impl GlObject for TransformFeedbackObject {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl Drop for TransformFeedbackObject {
+    fn drop(&mut self) {
+        let mut ctxt = self.context.make_current();
+
+        TransformFeedbackSession::ensure_object_out_of_transform_feedback(&mut ctxt, self.id);
+
+        if ctxt.state.transform_feedback_object == self.id {
+            bind_object(&mut ctxt, 0);
+        }
+
+        ctxt.resource_destroyed(ResourceKind::TransformFeedback, self.id);
+
+        unsafe {
+            ctxt.gl.DeleteTransformFeedbacks(1, [self.id].as_ptr());
+        }
+    }
+}
+
+/// Makes sure that a transform feedback object is bound.
+///
+/// If the current object is changed while it is capturing, its capture is paused so that it can
+/// be resumed once it is bound again. The capture of the default object is ended instead.
+fn bind_object(mut ctxt: &mut CommandContext, id: gl::types::GLuint) {
+    if ctxt.state.transform_feedback_object == id {
+        return;
+    }
+
+    let current = ctxt.state.transform_feedback_object;
+    match ctxt.state.transform_feedback_enabled {
+        Some(primitives) if current != 0 => {
+            TransformFeedbackSession::pause(ctxt);
+            let buffer = ctxt.state.transform_feedback_buffer;
+            ctxt.state.paused_transform_feedback_objects.push((current, buffer, primitives));
+            ctxt.state.transform_feedback_enabled = None;
+            ctxt.state.transform_feedback_paused = false;
+            ctxt.state.transform_feedback_buffer = 0;
+        },
+        _ => TransformFeedbackSession::unbind(ctxt),
+    }
+
+    unsafe {
+        ctxt.gl.BindTransformFeedback(gl::TRANSFORM_FEEDBACK, id);
+    }
+
+    ctxt.state.transform_feedback_object = id;
+
+    // the buffer bindings are part of the state of the transform feedback object
+    for point in ctxt.state.indexed_transform_feedback_buffer_bindings.iter_mut() {
+        point.buffer = 0;
+    }
+
+    // restoring the capture of the object if it had been paused
+    let paused = ctxt.state.paused_transform_feedback_objects.iter()
+                     .position(|&(object, _, _)| object == id);
+    if let Some(index) = paused {
+        let (_, buffer, primitives) = ctxt.state.paused_transform_feedback_objects.remove(index);
+        ctxt.state.transform_feedback_enabled = Some(primitives);
+        ctxt.state.transform_feedback_paused = true;
+        ctxt.state.transform_feedback_buffer = buffer;
+    }
+}
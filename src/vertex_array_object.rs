@@ -15,6 +15,7 @@ use BufferExt;
 
 use gl;
 use context::CommandContext;
+use context::ResourceKind;
 use version::Api;
 use version::Version;
 
@@ -23,6 +24,16 @@ pub struct VertexAttributesSystem {
     // we maintain a list of VAOs for each vertexbuffer-indexbuffer-program association
     // the key is a (buffers-list-with-offset, program) ; the buffers list must be sorted
     vaos: RefCell<HashMap<(Vec<(gl::types::GLuint, usize)>, Handle), VertexArrayObject>>,
+
+    // when `glVertexAttribBinding` is supported, VAOs are instead associated to a
+    // layout-indexbuffer-program association ; the vertex buffers are then bound with
+    // `glBindVertexBuffer` without having to respecify the format of the attributes
+    layout_vaos: RefCell<HashMap<(Vec<(VertexFormat, usize, Option<u32>)>, gl::types::GLuint, Handle),
+                                 VertexArrayObject>>,
+
+    // VAO without any attribute, shared by all the attribute-less draws ; core profiles require
+    // a VAO to be bound even if the vertex shader doesn't have any input
+    empty_vao: RefCell<Option<VertexArrayObject>>,
 }
 
 /// Object allowing one to bind vertex attributes to the current context.
@@ -40,6 +51,8 @@ impl VertexAttributesSystem {
     pub fn new() -> VertexAttributesSystem {
         VertexAttributesSystem {
             vaos: RefCell::new(HashMap::with_hasher(Default::default())),
+            layout_vaos: RefCell::new(HashMap::with_hasher(Default::default())),
+            empty_vao: RefCell::new(None),
         }
     }
 
@@ -71,14 +84,23 @@ impl VertexAttributesSystem {
     pub fn purge_buffer(ctxt: &mut CommandContext, id: gl::types::GLuint) {
         VertexAttributesSystem::purge_if(ctxt, |&(ref buffers, _)| {
             buffers.iter().find(|&&(b, _)| b == id).is_some()
-        })
+        });
+
+        VertexAttributesSystem::purge_layouts_if(ctxt, |&(_, index_buffer, _)| index_buffer == id);
+
+        // the id of the buffer may be reused by a future buffer, so we make sure that the
+        // VAOs will bind it again
+        for (_, vao) in ctxt.vertex_array_objects.layout_vaos.borrow().iter() {
+            vao.forget_vertex_buffer(id);
+        }
     }
 
     /// This function *must* be called whenever you destroy a program so that the system can
     /// purge its VAOs cache.
     #[inline]
     pub fn purge_program(ctxt: &mut CommandContext, program: Handle) {
-        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program)
+        VertexAttributesSystem::purge_if(ctxt, |&(_, p)| p == program);
+        VertexAttributesSystem::purge_layouts_if(ctxt, |&(_, _, p)| p == program);
     }
 
     /// Purges the VAOs cache.
@@ -89,6 +111,17 @@ impl VertexAttributesSystem {
         for (_, vao) in vaos {
             vao.destroy(ctxt);
         }
+
+        let vaos = mem::replace(&mut *ctxt.vertex_array_objects.layout_vaos.borrow_mut(),
+                                HashMap::with_hasher(Default::default()));
+
+        for (_, vao) in vaos {
+            vao.destroy(ctxt);
+        }
+
+        if let Some(vao) = ctxt.vertex_array_objects.empty_vao.borrow_mut().take() {
+            vao.destroy(ctxt);
+        }
     }
 
     /// Purges the VAOs cache. Contrary to `purge_all`, this function expects the system to be
@@ -100,13 +133,26 @@ impl VertexAttributesSystem {
         for (_, vao) in vaos {
             vao.destroy(ctxt);
         }
+
+        let vaos = mem::replace(&mut *ctxt.vertex_array_objects.layout_vaos.borrow_mut(),
+                                HashMap::with_hasher(Default::default()));
+
+        for (_, vao) in vaos {
+            vao.destroy(ctxt);
+        }
+
+        if let Some(vao) = ctxt.vertex_array_objects.empty_vao.borrow_mut().take() {
+            vao.destroy(ctxt);
+        }
     }
 
     /// Tells the VAOs system that the currently bound element array buffer will change.
     pub fn hijack_current_element_array_buffer(ctxt: &mut CommandContext) {
         let vaos = ctxt.vertex_array_objects.vaos.borrow_mut();
+        let layout_vaos = ctxt.vertex_array_objects.layout_vaos.borrow_mut();
+        let empty_vao = ctxt.vertex_array_objects.empty_vao.borrow_mut();
 
-        for (_, vao) in vaos.iter() {
+        for vao in vaos.values().chain(layout_vaos.values()).chain(empty_vao.iter()) {
             if vao.id == ctxt.state.vertex_array {
                 vao.element_array_buffer_hijacked.set(true);
                 return;
@@ -131,6 +177,25 @@ impl VertexAttributesSystem {
             vaos.remove(&key).unwrap().destroy(ctxt);
         }
     }
+
+    /// Purges the VAOs associated to a layout that match a certain condition.
+    fn purge_layouts_if<F>(ctxt: &mut CommandContext, mut condition: F)
+                           where F: FnMut(&(Vec<(VertexFormat, usize, Option<u32>)>,
+                                            gl::types::GLuint, Handle)) -> bool
+    {
+        let mut vaos = ctxt.vertex_array_objects.layout_vaos.borrow_mut();
+
+        let mut keys = Vec::with_capacity(4);
+        for (key, _) in &*vaos {
+            if condition(key) {
+                keys.push(key.clone());
+            }
+        }
+
+        for key in keys {
+            vaos.remove(&key).unwrap().destroy(ctxt);
+        }
+    }
 }
 
 impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
@@ -168,6 +233,27 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
         {
             // VAOs are supported
 
+            // attribute-less draws all use the same empty VAO
+            if self.vertex_buffers.is_empty() && self.element_array_buffer.is_none() &&
+               self.program.attributes().next().is_none()
+            {
+                let base_vertex = if self.base_vertex { Some(0) } else { None };
+
+                if let Some(ref vao) = *ctxt.vertex_array_objects.empty_vao.borrow() {
+                    vao.bind(ctxt);
+                    return base_vertex;
+                }
+
+                let new_vao = unsafe {
+                    VertexArrayObject::new(ctxt, &[], None, self.program, false)
+                };
+
+                new_vao.bind(ctxt);
+                *ctxt.vertex_array_objects.empty_vao.borrow_mut() = Some(new_vao);
+
+                return base_vertex;
+            }
+
             // finding the base vertex
             let base_vertex = if self.base_vertex {
                 Some(self.vertex_buffers.iter()
@@ -185,6 +271,35 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
                 }
             }
 
+            if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+               ctxt.extensions.gl_arb_vertex_attrib_binding
+            {
+                // the format of the attributes is stored in the VAO, and the buffers are
+                // simply rebound if they changed
+                let layout: Vec<_> = self.vertex_buffers.iter()
+                                         .map(|&(_, ref f, _, s, d)| (f.clone(), s, d))
+                                         .collect();
+                let index_buffer = self.element_array_buffer.map(|b| b.get_id()).unwrap_or(0);
+                let key = (layout, index_buffer, self.program.get_id());
+
+                if let Some(value) = ctxt.vertex_array_objects.layout_vaos.borrow_mut().get(&key) {
+                    value.bind(ctxt);
+                    unsafe { value.bind_vertex_buffers(ctxt, &self.vertex_buffers) };
+                    return base_vertex.map(|v| v as gl::types::GLint);
+                }
+
+                let new_vao = unsafe {
+                    VertexArrayObject::new(ctxt, &self.vertex_buffers,
+                                           self.element_array_buffer, self.program, true)
+                };
+
+                unsafe { new_vao.bind_vertex_buffers(ctxt, &self.vertex_buffers) };
+                ctxt.vertex_array_objects.layout_vaos.borrow_mut().insert(key, new_vao);
+
+                return base_vertex.map(|v| v as gl::types::GLint);
+            }
+
             let mut buffers_list: Vec<_> = self.vertex_buffers.iter()
                                                               .map(|&(v, _, o, s, _)| (v, o))
                                                               .collect();
@@ -204,7 +319,7 @@ impl<'a, 'b, 'c> Binder<'a, 'b, 'c> {
             // if not found, building a new one
             let new_vao = unsafe {
                 VertexArrayObject::new(ctxt, &self.vertex_buffers,
-                                       self.element_array_buffer, self.program)
+                                       self.element_array_buffer, self.program, false)
             };
 
             new_vao.bind(ctxt);
@@ -246,6 +361,9 @@ struct VertexArrayObject {
     destroyed: bool,
     element_array_buffer: gl::types::GLuint,
     element_array_buffer_hijacked: Cell<bool>,
+    // if the VAO was built with a separate attribute format, contains the buffer, offset and
+    // stride currently bound to each binding index
+    bound_vertex_buffers: Option<RefCell<SmallVec<[(gl::types::GLuint, usize, usize); 2]>>>,
 }
 
 impl VertexArrayObject {
@@ -253,9 +371,14 @@ impl VertexArrayObject {
     ///
     /// The vertex buffer, index buffer and program must not outlive the
     /// VAO, and the VB & program attributes must not change.
+    ///
+    /// If `separate_format` is true, only the format of the attributes is stored in the VAO
+    /// with `glVertexAttribFormat` and the vertex buffers must be bound afterwards with
+    /// `bind_vertex_buffers`.
     unsafe fn new(mut ctxt: &mut CommandContext,
                   vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
-                  index_buffer: Option<BufferAnySlice>, program: &Program) -> VertexArrayObject
+                  index_buffer: Option<BufferAnySlice>, program: &Program, separate_format: bool)
+                  -> VertexArrayObject
     {
         // checking the attributes types
         for &(_, ref bindings, _, _, _) in vertex_buffers {
@@ -308,6 +431,8 @@ impl VertexArrayObject {
             id
         };
 
+        ctxt.resource_created(ResourceKind::VertexArray, id);
+
         // we don't use DSA as we're going to make multiple calls for this VAO
         // and we're likely going to use the VAO right after it's been created
         bind_vao(&mut ctxt, id);
@@ -317,8 +442,15 @@ impl VertexArrayObject {
             index_buffer.bind_to_element_array(&mut ctxt);
         }
 
-        for &(vertex_buffer, ref bindings, offset, stride, divisor) in vertex_buffers {
-            bind_attribute(ctxt, program, vertex_buffer, bindings, offset, stride, divisor);
+        if separate_format {
+            for (binding_index, &(_, ref bindings, _, _, divisor)) in vertex_buffers.iter().enumerate() {
+                bind_attribute_format(ctxt, program, binding_index as gl::types::GLuint, bindings,
+                                      divisor);
+            }
+        } else {
+            for &(vertex_buffer, ref bindings, offset, stride, divisor) in vertex_buffers {
+                bind_attribute(ctxt, program, vertex_buffer, bindings, offset, stride, divisor);
+            }
         }
 
         VertexArrayObject {
@@ -326,6 +458,78 @@ impl VertexArrayObject {
             destroyed: false,
             element_array_buffer: index_buffer.map(|b| b.get_id()).unwrap_or(0),
             element_array_buffer_hijacked: Cell::new(false),
+            bound_vertex_buffers: if separate_format {
+                Some(RefCell::new(vertex_buffers.iter().map(|_| (0, 0, 0)).collect()))
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Binds the vertex buffers to the binding indices of this VAO with `glBindVertexBuffer`,
+    /// or with a single call to `glBindVertexBuffers` if it is supported.
+    ///
+    /// The VAO must be the current one and must have been built with a separate format.
+    /// Buffers that are already bound are skipped.
+    unsafe fn bind_vertex_buffers(&self, ctxt: &mut CommandContext,
+                                  vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize,
+                                                     usize, Option<u32>)])
+    {
+        debug_assert_eq!(ctxt.state.vertex_array, self.id);
+
+        let mut bound = self.bound_vertex_buffers.as_ref().unwrap().borrow_mut();
+        debug_assert_eq!(bound.len(), vertex_buffers.len());
+
+        // range of binding indices whose buffer has changed
+        let first = vertex_buffers.iter().zip(bound.iter())
+                                  .position(|(&(b, _, o, s, _), &current)| current != (b, o, s));
+        let first = match first {
+            Some(f) => f,
+            None => return,
+        };
+        let last = vertex_buffers.iter().zip(bound.iter())
+                                 .rposition(|(&(b, _, o, s, _), &current)| current != (b, o, s))
+                                 .unwrap();
+
+        if first != last && ctxt.version >= &Version(Api::Gl, 4, 4) {
+            let range = &vertex_buffers[first .. last + 1];
+            let buffers = range.iter().map(|&(b, _, _, _, _)| b)
+                               .collect::<SmallVec<[gl::types::GLuint; 4]>>();
+            let offsets = range.iter().map(|&(_, _, o, _, _)| o as gl::types::GLintptr)
+                               .collect::<SmallVec<[gl::types::GLintptr; 4]>>();
+            let strides = range.iter().map(|&(_, _, _, s, _)| s as gl::types::GLsizei)
+                               .collect::<SmallVec<[gl::types::GLsizei; 4]>>();
+
+            ctxt.gl.BindVertexBuffers(first as gl::types::GLuint,
+                                      range.len() as gl::types::GLsizei, buffers.as_ptr(),
+                                      offsets.as_ptr(), strides.as_ptr());
+
+            for (binding_index, &(buffer, _, offset, stride, _)) in range.iter().enumerate() {
+                bound[first + binding_index] = (buffer, offset, stride);
+            }
+
+            return;
+        }
+
+        for (binding_index, &(buffer, _, offset, stride, _)) in vertex_buffers.iter().enumerate() {
+            if bound[binding_index] == (buffer, offset, stride) {
+                continue;
+            }
+
+            ctxt.gl.BindVertexBuffer(binding_index as gl::types::GLuint, buffer,
+                                     offset as gl::types::GLintptr, stride as gl::types::GLsizei);
+            bound[binding_index] = (buffer, offset, stride);
+        }
+    }
+
+    /// Makes sure that the given buffer will be bound again the next time this VAO is used.
+    fn forget_vertex_buffer(&self, id: gl::types::GLuint) {
+        if let Some(ref bound) = self.bound_vertex_buffers {
+            for entry in bound.borrow_mut().iter_mut() {
+                if entry.0 == id {
+                    *entry = (0, 0, 0);
+                }
+            }
         }
     }
 
@@ -362,6 +566,8 @@ impl VertexArrayObject {
         }
 
         // deleting
+        ctxt.resource_destroyed(ResourceKind::VertexArray, self.id);
+
         if ctxt.version >= &Version(Api::Gl, 3, 0) ||
             ctxt.version >= &Version(Api::GlEs, 3, 0) ||
             ctxt.extensions.gl_arb_vertex_array_object
@@ -577,3 +783,61 @@ unsafe fn bind_attribute(ctxt: &mut CommandContext, program: &Program,
         }
     }
 }
+
+/// Specifies the format of the attributes of a vertex buffer in the current VAO, and associates
+/// them to the given binding index.
+unsafe fn bind_attribute_format(ctxt: &mut CommandContext, program: &Program,
+                                binding_index: gl::types::GLuint, bindings: &VertexFormat,
+                                divisor: Option<u32>)
+{
+    for &(ref name, offset, ty, normalize) in bindings.iter() {
+        let (data_type, elements_count, instances_count) = vertex_binding_type_to_gl(ty);
+
+        let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
+            Some(a) => a,
+            None => continue
+        };
+
+        if attribute.location == -1 {
+            continue;
+        }
+
+        let (attribute_ty, _, _) = vertex_binding_type_to_gl(attribute.ty);
+
+        for i in 0..instances_count {
+            let location = (attribute.location + i) as gl::types::GLuint;
+
+            if normalize {
+                ctxt.gl.VertexAttribFormat(location, elements_count as gl::types::GLint,
+                                           data_type, 1,
+                                           (offset + (i * elements_count * 4) as usize) as u32);
+            } else {
+                match attribute_ty {
+                    gl::BYTE | gl::UNSIGNED_BYTE | gl::SHORT | gl::UNSIGNED_SHORT |
+                    gl::INT | gl::UNSIGNED_INT =>
+                        ctxt.gl.VertexAttribIFormat(location, elements_count as gl::types::GLint,
+                                                    data_type, offset as u32),
+
+                    gl::FLOAT =>
+                        ctxt.gl.VertexAttribFormat(location, elements_count as gl::types::GLint,
+                                                   data_type, 0,
+                                                   (offset + (i * elements_count * 4) as usize) as u32),
+
+                    gl::DOUBLE | gl::INT64_NV | gl::UNSIGNED_INT64_NV =>
+                        ctxt.gl.VertexAttribLFormat(location, elements_count as gl::types::GLint,
+                                                    data_type,
+                                                    (offset + (i * elements_count * 8) as usize) as u32),
+
+                    _ => unreachable!()
+                }
+            }
+
+            ctxt.gl.VertexAttribBinding(location, binding_index);
+            ctxt.gl.EnableVertexAttribArray(location);
+        }
+    }
+
+    if let Some(divisor) = divisor {
+        ctxt.gl.VertexBindingDivisor(binding_index, divisor);
+    }
+}
@@ -307,3 +307,58 @@ attribute_test!(attribute_vec3_i32, [i32; 3], "vec3", [0, 0, 0], "vec4(field1, 1
 attribute_test!(attribute_vec3_tuple_i32, (i32, i32, i32), "vec3", (0, 0, 0), "vec4(field1, 1.0)");
 attribute_test!(attribute_vec4_i32, [i32; 4], "vec4", [0, 0, 0, 0], "field1");
 attribute_test!(attribute_vec4_tuple_i32, (i32, i32, i32, i32), "vec4", (0, 0, 0, 0), "field1");
+
+#[test]
+fn verify_against_vertex() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Good {
+        position: [f32; 2],
+        color: [f32; 3],
+    }
+
+    implement_vertex!(Good, position, color);
+
+    #[derive(Copy, Clone)]
+    struct Bad {
+        position: [f32; 4],
+        normal: [f32; 3],
+    }
+
+    implement_vertex!(Bad, position, normal);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+            attribute vec3 color;
+            varying vec3 v_color;
+
+            void main() {
+                v_color = color;
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            varying vec3 v_color;
+
+            void main() {
+                gl_FragColor = vec4(v_color, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    assert!(program.verify_against::<Good>().is_ok());
+
+    let report = program.verify_against::<Bad>().unwrap_err();
+    assert_eq!(report.missing, vec!["color".to_owned()]);
+    assert_eq!(report.extra, vec!["normal".to_owned()]);
+    assert_eq!(report.mismatched.len(), 1);
+    assert_eq!(report.mismatched[0].0, "position");
+
+    display.assert_no_error(None);
+}
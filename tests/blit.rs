@@ -56,3 +56,135 @@ fn blit_texture_to_window() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn blit_whole_color_from() {
+    let display = support::build_display();
+
+    let texture = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+
+    let target = support::build_renderable_texture(&display);
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    target.as_surface().blit_whole_color_from(&texture.as_surface());
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn copy_image_sub_data() {
+    let display = support::build_display();
+
+    let source = support::build_renderable_texture(&display);
+    source.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let dest = support::build_renderable_texture(&display);
+    dest.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let src_image = source.main_level().first_layer().into_image(None).unwrap();
+    let dest_image = dest.main_level().first_layer().into_image(None).unwrap();
+
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    match src_image.copy_to(&rect, dest_image, (1, 1)) {
+        Ok(()) => (),
+        Err(glium::texture::CopyImageError::NotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = dest.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+    assert_eq!(data[1][1], (0, 255, 0, 255));
+    assert_eq!(data[2][2], (0, 255, 0, 255));
+    assert_eq!(data[3][3], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn copy_region_from() {
+    let display = support::build_display();
+
+    let source = glium::Texture2d::empty(&display, 4, 4).unwrap();
+    source.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let atlas = glium::Texture2d::empty(&display, 8, 8).unwrap();
+    atlas.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let rect = Rect { left: 0, bottom: 0, width: 4, height: 4 };
+    atlas.copy_region_from(&source, &rect, (4, 2)).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = atlas.read();
+    assert_eq!(data[1][4], (0, 0, 0, 0));
+    assert_eq!(data[2][3], (0, 0, 0, 0));
+    assert_eq!(data[2][4], (0, 255, 0, 255));
+    assert_eq!(data[5][7], (0, 255, 0, 255));
+    assert_eq!(data[6][7], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_copy_to_texture() {
+    let display = support::build_display();
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 1.0);
+    frame.clear(Some(&Rect { left: 0, bottom: 0, width: 2, height: 2 }),
+                Some((1.0, 0.0, 0.0, 1.0)), false, None, None);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    frame.copy_to_texture(&Rect { left: 0, bottom: 0, width: 4, height: 4 }, &texture, (1, 1));
+    frame.finish().unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+    assert_eq!(data[1][1], (255, 0, 0, 255));
+    assert_eq!(data[2][2], (255, 0, 0, 255));
+    assert_eq!(data[3][3], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_new_from_framebuffer() {
+    let display = support::build_display();
+
+    let source = support::build_renderable_texture(&display);
+    source.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+    source.as_surface().clear(Some(&Rect { left: 2, bottom: 1, width: 1, height: 1 }),
+                              Some((1.0, 0.0, 0.0, 1.0)), false, None, None);
+
+    let texture = glium::texture::Texture2d::new_from_framebuffer(&display,
+                                                  &source.as_surface(),
+                                                  &Rect { left: 1, bottom: 1, width: 3, height: 2 });
+    assert_eq!(texture.get_width(), 3);
+    assert_eq!(texture.get_height(), Some(2));
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 255));
+    assert_eq!(data[0][1], (255, 0, 0, 255));
+    assert_eq!(data[1][1], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn texture_new_from_framebuffer_out_of_bounds() {
+    let display = support::build_display();
+
+    let source = support::build_renderable_texture(&display);
+    let (width, height) = source.as_surface().get_dimensions();
+
+    glium::texture::Texture2d::new_from_framebuffer(&display, &source.as_surface(),
+                                                    &Rect { left: 1, bottom: 0,
+                                                            width: width, height: height });
+}
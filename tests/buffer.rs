@@ -203,6 +203,70 @@ fn buffer_dynamic_mapping_write() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn buffer_dynamic_mapping_write_only() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+        field2: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1, field2);
+
+    let mut vb = glium::VertexBuffer::dynamic(&display,
+        &[
+            Vertex { field1: [ 2,  3], field2: [ 5,  7] },
+            Vertex { field1: [12, 13], field2: [15, 17] },
+        ]
+    ).unwrap();
+
+    {
+        let mut mapping = vb.map_write();
+        mapping.set(0, Vertex { field1: [0, 1], field2: [2, 3] });
+        mapping.set(1, Vertex { field1: [4, 5], field2: [6, 7] });
+    }
+
+    let mapping = vb.map_read();
+    assert_eq!(mapping[0].field1, [0, 1]);
+    assert_eq!(mapping[1].field2, [6, 7]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_immutable_mapping_read_write() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+        field2: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1, field2);
+
+    let mut vb = glium::VertexBuffer::immutable(&display,
+        &[
+            Vertex { field1: [ 2,  3], field2: [ 5,  7] },
+            Vertex { field1: [12, 13], field2: [15, 17] },
+        ]
+    ).unwrap();
+
+    {
+        let mut mapping = vb.map_read_write();
+        assert_eq!(mapping[1].field1, [12, 13]);
+        mapping[0].field1 = [0, 1];
+    }
+
+    let mapping = vb.map_read();
+    assert_eq!(mapping[0].field1, [0, 1]);
+    assert_eq!(mapping[1].field2, [15, 17]);
+
+    display.assert_no_error(None);
+}
+
 // TODO: uncomment after std::thread::scoped has been stabilized
 /*#[test]
 fn buffer_mapping_multithread() {
@@ -295,6 +359,28 @@ fn buffer_dynamic_read() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn buffer_read_supported() {
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::new(&display, &[1u32, 2, 3, 4],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            BufferMode::Default).unwrap();
+
+    match buffer.slice(1 .. 3).unwrap().read() {
+        Ok(data) => {
+            assert!(glium::buffer::is_buffer_read_supported(&display));
+            assert_eq!(data, vec![2, 3]);
+        },
+        Err(glium::buffer::ReadError::NotSupported) => {
+            assert!(!glium::buffer::is_buffer_read_supported(&display));
+        },
+        e => { e.unwrap(); }
+    };
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn buffer_immutable_read_slice() {
     let display = support::build_display();
@@ -379,6 +465,22 @@ fn buffer_slice_out_of_bounds() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn buffer_get_element() {
+    let display = support::build_display();
+
+    let buffer = glium::buffer::Buffer::new(&display, &[1u32, 2, 3, 4][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            BufferMode::Default).unwrap();
+
+    assert_eq!(buffer.get(2).unwrap().read().unwrap(), 3);
+    assert_eq!(buffer.slice(1 .. 4).unwrap().get(0).unwrap().read().unwrap(), 2);
+    assert!(buffer.get(4).is_none());
+    assert!(buffer.slice(1 .. 4).unwrap().get(3).is_none());
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn buffer_immutable_write() {
     let display = support::build_display();
@@ -455,6 +557,81 @@ fn buffer_dynamic_write() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn buffer_stream_write() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+        field2: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1, field2);
+
+    let vb = glium::VertexBuffer::empty_stream(&display, 2).unwrap();
+
+    for frame in 0 .. 3u8 {
+        vb.write(&[
+            Vertex { field1: [frame, 3], field2: [5, 7] },
+            Vertex { field1: [12, 13], field2: [15, frame] }
+        ]);
+
+        let data = match vb.read() {
+            Ok(r) => r,
+            Err(glium::buffer::ReadError::NotSupported) => return,
+            e => e.unwrap()
+        };
+
+        assert_eq!(data[0].field1, [frame, 3]);
+        assert_eq!(data[1].field2, [15, frame]);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn buffer_dynamic_write_from_iter() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        field1: [u8; 2],
+        field2: [u8; 2],
+    }
+
+    implement_vertex!(Vertex, field1, field2);
+
+    let mut vb = glium::VertexBuffer::empty_dynamic(&display, 3).unwrap();
+
+    vb.write_from_iter((0 .. 3u8).map(|i| Vertex { field1: [i, i + 1], field2: [i * 2, i * 3] }));
+
+    let data = match vb.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data[0].field1, [0, 1]);
+    assert_eq!(data[1].field2, [2, 3]);
+    assert_eq!(data[2].field1, [2, 3]);
+    assert_eq!(data[2].field2, [4, 6]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn buffer_write_from_iter_wrong_length() {
+    let display = support::build_display();
+
+    let mut buffer = glium::buffer::Buffer::<[u32]>::empty_array(&display,
+                                                    glium::buffer::BufferType::ArrayBuffer,
+                                                    3, BufferMode::Dynamic).unwrap();
+
+    buffer.write_from_iter(0 .. 2);
+}
+
 #[test]
 fn buffer_immutable_write_slice() {
     let display = support::build_display();
@@ -757,6 +934,55 @@ fn immutable_mapping_forget_then_draw() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn persistent_mapping_mark_dirty() {
+    let display = support::build_display();
+
+    let mut buf = match glium::buffer::Buffer::new(&display, &[0u32; 16][..],
+                                                   glium::buffer::BufferType::ArrayBuffer,
+                                                   BufferMode::Persistent)
+    {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    {
+        let mut mapping = buf.map_write();
+        mapping.set(2, 5);
+        mapping.set(3, 6);
+        mapping.set(10, 7);
+        mapping.mark_dirty(2 .. 4);
+        mapping.mark_dirty(3 .. 4);
+        mapping.flush();
+        mapping.mark_dirty(10 .. 11);
+    }
+
+    let data = match buf.read() {
+        Ok(d) => d,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    assert_eq!(data[2], 5);
+    assert_eq!(data[3], 6);
+    assert_eq!(data[10], 7);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn mapping_mark_dirty_out_of_range() {
+    let display = support::build_display();
+
+    let mut buf = glium::buffer::Buffer::new(&display, &[0u32; 4][..],
+                                             glium::buffer::BufferType::ArrayBuffer,
+                                             BufferMode::Dynamic).unwrap();
+
+    let mut mapping = buf.map_write();
+    mapping.mark_dirty(2 .. 5);
+}
+
 #[test]
 fn persistent_mapping_forget_then_remap() {
     let display = support::build_display();
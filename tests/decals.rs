@@ -0,0 +1,64 @@
+#![cfg(feature = "decals")]
+
+extern crate glium;
+
+use glium::Surface;
+
+mod support;
+
+#[test]
+fn decal_on_plane() {
+    let display = support::build_display();
+
+    let decals = match glium::decals::DecalPass::new(&display) {
+        Ok(d) => d,
+        Err(glium::decals::DecalError::ProgramCreationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    // the scene is a plane at a depth of 0.5
+    let depth_data = vec![vec![0.5f32; 32]; 32];
+    let scene_depth = match glium::texture::DepthTexture2d::new(&display, depth_data) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let color = glium::Texture2d::empty(&display, 32, 32).unwrap();
+    let depth_stencil = match glium::framebuffer::DepthStencilRenderBuffer::new(&display,
+                                            glium::texture::DepthStencilFormat::I24I8, 32, 32)
+    {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    let mut target = glium::framebuffer::SimpleFrameBuffer::with_depth_stencil_buffer(&display,
+                                                            &color, &depth_stencil).unwrap();
+    target.clear_all((0.0, 0.0, 0.0, 1.0), 0.5, 0);
+
+    let decal = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+
+    // right-handed world whose camera looks towards negative Z
+    let view_projection = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, -1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0f32],
+    ];
+
+    // a box of size 1 around the origin
+    let decal_projection = [
+        [2.0, 0.0, 0.0, 0.0],
+        [0.0, 2.0, 0.0, 0.0],
+        [0.0, 0.0, 2.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0f32],
+    ];
+
+    decals.draw(&mut target, &scene_depth, view_projection, decal_projection, &decal).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = color.read();
+    assert_eq!(data[16][16], (0, 255, 0, 255));
+    assert_eq!(data[0][0], (0, 0, 0, 255));
+    assert_eq!(data[31][31], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
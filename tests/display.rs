@@ -14,6 +14,168 @@ fn get_opengl_version() {
     assert!(version.1 >= 1);
 }
 
+#[test]
+fn context_requirements() {
+    let display = support::build_display();
+    let version = *display.get_opengl_version();
+
+    let requirements = glium::ContextRequirements::new().with_gl_version_request(version);
+    assert!(requirements.check(&display).is_ok());
+
+    let too_recent = glium::Version(version.0, 99, 0);
+    let requirements = glium::ContextRequirements::new().with_gl_version_request(too_recent);
+    let err = requirements.check(&display).unwrap_err();
+    assert!(err.contains(&format!("{:?}", version)));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn format_support() {
+    let display = support::build_display();
+
+    let format = glium::texture::TextureFormat::UncompressedFloat(
+                                        glium::texture::UncompressedFloatFormat::U8U8U8U8);
+
+    let support = match display.get_context().format_support(format) {
+        Some(s) => s,
+        None => return
+    };
+
+    assert!(support.supported);
+    assert_eq!(support.framebuffer_renderable, glium::backend::FormatSupportLevel::Full);
+    assert!(support.filterable);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn capture_gl_state() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let before = display.get_context().capture_gl_state();
+    assert!(before.diff(&display.get_context().capture_gl_state()).is_empty());
+
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+        blend: glium::Blend::alpha_blending(),
+        .. Default::default()
+    }).unwrap();
+
+    let after = display.get_context().capture_gl_state();
+    assert!(after.blend);
+    assert!(before.diff(&after).iter().any(|d| d.starts_with("blend:")));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn save_render_state() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    let before = display.get_context().capture_gl_state();
+
+    {
+        let _guard = display.get_context().save_render_state();
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+            blend: glium::Blend::alpha_blending(),
+            backface_culling: glium::BackfaceCullingMode::CullClockwise,
+            .. Default::default()
+        }).unwrap();
+    }
+
+    let after = display.get_context().capture_gl_state();
+    assert_eq!(before.blend, after.blend);
+    assert_eq!(before.cull_face, after.cull_face);
+    assert_eq!(before.program, after.program);
+    assert_eq!(before.viewport, after.viewport);
+
+    // glium's cache must have been updated as well
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn bound_textures() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    let output = support::build_renderable_texture(&display);
+
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &texture },
+                             &Default::default()).unwrap();
+
+    let bound = display.get_context().bound_textures();
+    let info = bound.iter().filter_map(|u| u.as_ref())
+                    .find(|u| u.texture == texture.get_id()).unwrap();
+    assert_eq!(info.target, 0x0DE1);       // GL_TEXTURE_2D
+    assert!(!info.feedback_loop);
+
+    // `texture` stays bound to its unit while we render to it
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &output },
+                              &Default::default()).unwrap();
+
+    let bound = display.get_context().bound_textures();
+    let info = bound.iter().filter_map(|u| u.as_ref())
+                    .find(|u| u.texture == texture.get_id()).unwrap();
+    assert!(info.feedback_loop);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn set_hint() {
+    use glium::backend::HintTarget;
+    use glium::draw_parameters::Smooth;
+
+    let display = support::build_display();
+
+    for &target in &[HintTarget::FragmentShaderDerivative, HintTarget::TextureCompression,
+                     HintTarget::GenerateMipmap]
+    {
+        if display.get_context().set_hint(target, Smooth::Nicest).is_err() {
+            continue;
+        }
+
+        display.get_context().set_hint(target, Smooth::Nicest).unwrap();
+        display.get_context().set_hint(target, Smooth::Fastest).unwrap();
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn clear_color() {
     let display = support::build_display();
@@ -57,6 +219,43 @@ fn clear_color_rect() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn clear_channel() {
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(1.0, 0.0, 1.0, 0.0);
+    texture.as_surface().clear_channel(0, 1, 1.0);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 255, 0));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn clear_channel_out_of_range() {
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_channel(0, 4, 1.0);
+}
+
+#[test]
+#[should_panic]
+fn clear_channel_wrong_attachment() {
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_channel(1, 0, 1.0);
+}
+
 #[test]
 fn release_shader_compiler() {
     let display = support::build_display();
@@ -64,6 +263,19 @@ fn release_shader_compiler() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn video_memory_info() {
+    let display = support::build_display();
+
+    if let Some(info) = display.get_context().video_memory_info() {
+        if let Some(total) = info.total {
+            assert!(info.available <= total);
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn timestamp_query() {
     let display = support::build_display();
@@ -94,6 +306,86 @@ fn sync() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn max_frames_in_flight() {
+    let display = support::build_display();
+
+    if display.get_context().set_max_frames_in_flight(Some(1)).is_err() {
+        return;
+    }
+
+    for _ in 0 .. 4 {
+        let mut frame = display.draw();
+        frame.clear_color(0.0, 0.0, 0.0, 0.0);
+        frame.finish().unwrap();
+    }
+
+    assert_eq!(display.get_context().get_max_frames_in_flight(), Some(1));
+    display.get_context().set_max_frames_in_flight(None).unwrap();
+    assert_eq!(display.get_context().get_last_frame_wait_time(),
+               std::time::Duration::new(0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn frame_finish_no_swap() {
+    let display = support::build_display();
+
+    let mut frame = display.draw();
+    frame.clear_color(1.0, 0.0, 0.0, 1.0);
+    frame.finish_no_swap().unwrap();
+
+    let mut frame = display.draw();
+    frame.set_finish().unwrap();
+    match frame.finish_no_swap() {
+        Err(glium::SwapBuffersError::AlreadySwapped) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn flush_uploads() {
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.write(glium::Rect { left: 0, bottom: 0, width: 1, height: 1 },
+                  vec![vec![(0u8, 255u8, 0u8, 255u8)]]);
+    display.flush_uploads();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn flush_uploads_after_draw() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.write(glium::Rect { left: 0, bottom: 0, width: 1, height: 1 },
+                  vec![vec![(0u8, 255u8, 0u8, 255u8)]]);
+
+    // the fence is inserted before this draw, which isn't waited for
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    display.flush_uploads();
+    display.flush_uploads();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn multiple_displays() {
     let display1 = support::build_display();
@@ -141,6 +433,30 @@ fn debug_string() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn debug_labels() {
+    use glium::debug::Labeled;
+
+    // tests that naming objects doesn't trigger an OpenGL error
+    let display = support::build_display();
+
+    let texture = support::build_renderable_texture(&display);
+    let (vb, _) = support::build_rectangle_vb_ib(&display);
+
+    if texture.label("texture").is_err() {
+        return;
+    }
+
+    vb.label("vertex buffer").unwrap();
+
+    if let Ok(fence) = glium::SyncFence::new(&display) {
+        fence.label("fence").unwrap();
+        fence.wait();
+    }
+
+    display.assert_no_error(None);
+}
+
 
 #[test]
 fn is_context_lost() {
@@ -149,3 +465,301 @@ fn is_context_lost() {
     display.is_context_lost();
     display.assert_no_error(None);
 }
+
+#[test]
+fn error_behavior_panic_no_error() {
+    // tests that valid operations don't panic when errors are checked after each operation
+    let display = support::build_display();
+    display.set_error_behavior(glium::GlErrorBehavior::Panic);
+    assert_eq!(display.get_error_behavior(), glium::GlErrorBehavior::Panic);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.set_error_behavior(glium::GlErrorBehavior::Ignore);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn error_check_interval_no_error() {
+    // tests that valid operations don't panic when errors are only checked from time to time
+    let display = support::build_display();
+    display.set_error_behavior(glium::GlErrorBehavior::Panic);
+    display.set_error_check_interval(glium::GlErrorCheckInterval::Operations(2));
+    assert_eq!(display.get_error_check_interval(), glium::GlErrorCheckInterval::Operations(2));
+
+    let texture = support::build_renderable_texture(&display);
+    for _ in 0 .. 5 {
+        texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+    }
+
+    display.set_error_check_interval(glium::GlErrorCheckInterval::EachFrame);
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let mut frame = display.draw();
+    frame.clear_color(0.0, 0.0, 0.0, 1.0);
+    frame.finish().unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.set_error_behavior(glium::GlErrorBehavior::Ignore);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn exec_gl_invalidate_bindings() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    unsafe {
+        // glium marks its bindings as unknown afterwards
+        display.exec_gl(|load| support::call_gl(load, "glUseProgram", 0));
+    }
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn exec_gl_state_trust() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    assert_eq!(display.get_state_trust(), glium::StateTrust::Verify);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    // with `Verify`, glium invalidates its cache by itself
+    unsafe { display.exec_gl(|load| support::call_gl(load, "glUseProgram", 0)); }
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    // with `TrustCache`, the cache must be invalidated manually
+    display.set_state_trust(glium::StateTrust::TrustCache);
+    unsafe { display.exec_gl(|load| support::call_gl(load, "glUseProgram", 0)); }
+    display.invalidate_state();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.set_state_trust(glium::StateTrust::Verify);
+    display.assert_no_error(None);
+}
+
+/// Draws `texture` with a program that samples it and returns true if glium's cache says that
+/// it is bound to a texture unit.
+fn draw_and_check_bound(display: &glium::Display, texture: &glium::Texture2d) -> bool {
+    let (vb, ib) = support::build_rectangle_vb_ib(display);
+    let program = support::build_sampling_program(display, (0.5, 0.5));
+    let output = support::build_renderable_texture(display);
+
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: texture },
+                             &Default::default()).unwrap();
+
+    is_bound(display, texture)
+}
+
+fn is_bound(display: &glium::Display, texture: &glium::Texture2d) -> bool {
+    use glium::GlObject;
+
+    display.get_context().bound_textures().iter().filter_map(|u| u.as_ref())
+           .any(|u| u.texture == texture.get_id())
+}
+
+#[test]
+fn state_trust_cache_after_exec_gl() {
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    // with `Verify`, the bindings are unknown after running raw OpenGL code
+    assert!(draw_and_check_bound(&display, &texture));
+    unsafe { display.exec_gl(|_| ()); }
+    assert!(!is_bound(&display, &texture));
+
+    // with `TrustCache`, they are kept until `invalidate_state` is called
+    display.set_state_trust(glium::StateTrust::TrustCache);
+    assert!(draw_and_check_bound(&display, &texture));
+    unsafe { display.exec_gl(|_| ()); }
+    unsafe { display.exec_in_context(|| ()); }
+    assert!(is_bound(&display, &texture));
+    display.invalidate_state();
+    assert!(!is_bound(&display, &texture));
+
+    display.set_state_trust(glium::StateTrust::Verify);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn state_trust_cache_after_context_switch() {
+    let display1 = support::build_display();
+    let display2 = support::build_display();
+    let texture1 = support::build_renderable_texture(&display1);
+    let texture2 = support::build_renderable_texture(&display2);
+
+    // with `Verify`, the state is queried again after another context was current
+    assert!(draw_and_check_bound(&display1, &texture1));
+    assert!(draw_and_check_bound(&display2, &texture2));
+    assert!(!is_bound(&display1, &texture1));
+
+    // with `TrustCache`, the cache is kept
+    display1.set_state_trust(glium::StateTrust::TrustCache);
+    assert!(draw_and_check_bound(&display1, &texture1));
+    assert!(draw_and_check_bound(&display2, &texture2));
+    assert!(is_bound(&display1, &texture1));
+
+    // unless we tell glium that someone else used the context
+    display1.notify_made_current();
+    assert!(!is_bound(&display1, &texture1));
+
+    display1.set_state_trust(glium::StateTrust::Verify);
+    display1.assert_no_error(None);
+    display2.assert_no_error(None);
+}
+
+#[test]
+#[should_panic(expected = "GL_INVALID_ENUM during the last 1 operation")]
+fn error_check_interval_logs_all_operations() {
+    // operations other than draws, clears and blits are logged as well
+    let display = support::build_display();
+    display.set_error_behavior(glium::GlErrorBehavior::Panic);
+    display.set_error_check_interval(glium::GlErrorCheckInterval::Operations(1));
+    unsafe { display.exec_gl(|load| support::call_gl(load, "glEnable", 0)); }
+}
+
+#[test]
+fn last_error() {
+    let display = support::build_display();
+    assert_eq!(display.last_error(), None);
+
+    // `GL_INVALID_ENUM`, twice
+    unsafe {
+        display.exec_gl(|load| {
+            support::call_gl(load, "glEnable", 0);
+            support::call_gl(load, "glEnable", 0);
+        });
+    }
+    assert_eq!(display.last_error(), Some(glium::GlError::InvalidEnum));
+    assert_eq!(display.last_error(), None);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic(expected = "GL_INVALID_ENUM")]
+fn assert_no_error_panics() {
+    let display = support::build_display();
+    unsafe { display.exec_gl(|load| support::call_gl(load, "glEnable", 0)); }
+    display.assert_no_error(None);
+}
+
+#[test]
+fn notify_made_current() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    display.set_state_trust(glium::StateTrust::TrustCache);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    // simulates a foreign library that used the context while it wasn't glium's turn
+    unsafe { display.exec_gl(|load| support::call_gl(load, "glUseProgram", 0)); }
+    display.notify_made_current();
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.set_state_trust(glium::StateTrust::Verify);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn notify_made_current_reloads_state() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    display.set_state_trust(glium::StateTrust::TrustCache);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    // simulates a foreign library that changed the state that glium doesn't bind again
+    unsafe {
+        display.exec_gl(|load| {
+            support::call_gl(load, "glEnable", 0x0B44);         // GL_CULL_FACE
+            support::call_gl(load, "glCullFace", 0x0408);       // GL_FRONT_AND_BACK
+        });
+    }
+    display.notify_made_current();
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.set_state_trust(glium::StateTrust::Verify);
+    display.assert_no_error(None);
+}
+
+#[test]
+fn resource_callbacks() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use glium::GlObject;
+
+    let display = support::build_display();
+
+    let created = Rc::new(RefCell::new(Vec::new()));
+    let destroyed = Rc::new(RefCell::new(Vec::new()));
+
+    {
+        let created = created.clone();
+        display.set_resource_created_callback(Some(Box::new(move |kind, id| {
+            created.borrow_mut().push((kind, id));
+        })));
+    }
+
+    {
+        let destroyed = destroyed.clone();
+        display.set_resource_destroyed_callback(Some(Box::new(move |kind, id| {
+            destroyed.borrow_mut().push((kind, id));
+        })));
+    }
+
+    let buffer = glium::buffer::Buffer::new(&display, &[1u32, 2, 3, 4][..],
+                                            glium::buffer::BufferType::ArrayBuffer,
+                                            glium::buffer::BufferMode::Default).unwrap();
+    let id = buffer.get_id();
+    assert!(created.borrow().contains(&(glium::ResourceKind::Buffer, id)));
+    assert!(!destroyed.borrow().contains(&(glium::ResourceKind::Buffer, id)));
+
+    drop(buffer);
+    assert!(destroyed.borrow().contains(&(glium::ResourceKind::Buffer, id)));
+
+    display.set_resource_created_callback(None);
+    display.set_resource_destroyed_callback(None);
+    display.assert_no_error(None);
+}
@@ -81,6 +81,95 @@ fn wrong_depth_range() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn nan_depth_range() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        depth: glium::Depth {
+            range: (0.0, ::std::f32::NAN),
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut frame = display.draw();
+    match frame.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::InvalidDepthRange) => (),
+        a => panic!("{:?}", a)
+    };
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn reversed_depth_range() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.5, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let texture = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    let depth = match glium::framebuffer::DepthRenderBuffer::new(&display,
+                                            glium::texture::DepthFormat::F32, 16, 16)
+    {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                    &texture, &depth).unwrap();
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 0.7);
+
+    // a z of 0.5 is mapped to 0.625 with a reversed range, and to 0.875 otherwise
+    let params = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: true,
+            range: (1.0, 0.5),
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    match framebuffer.read_depth_pixel(8, 8) {
+        Ok(value) => assert_eq!(value, 0.625),
+        Err(glium::texture::ReadError::AttachmentTypeNotSupported) => (),
+        Err(e) => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn scissor() {
     let display = support::build_display();
@@ -217,6 +306,116 @@ fn viewport() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn viewport_f() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    // covers the centers of the first two pixels, while the rounded viewport only covers
+    // the first one
+    let params = glium::DrawParameters {
+        viewport_f: Some(glium::draw_parameters::Viewportf {
+            x: 0.4,
+            y: 0.4,
+            width: 1.2,
+            height: 1.2,
+        }),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    let floating = {
+        let context = display.get_context();
+        *context.get_version() >= glium::Version(glium::Api::Gl, 4, 1) ||
+        context.get_extensions().gl_arb_viewport_array
+    };
+
+    let second = if floating { (255, 0, 0, 255) } else { (0, 0, 0, 0) };
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[1][0], second);
+    assert_eq!(data[0][1], second);
+    assert_eq!(data[1][1], second);
+    assert_eq!(data[2][2], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn viewport_f_negative_origin() {
+    let display = support::build_display();
+
+    // the viewport goes from -1 to 2, which covers the first two pixels whether the values
+    // are rounded or not
+    let params = glium::DrawParameters {
+        viewport_f: Some(glium::draw_parameters::Viewportf {
+            x: -1.0,
+            y: -1.0,
+            width: 3.0,
+            height: 3.0,
+        }),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[1][1], (255, 0, 0, 255));
+    assert_eq!(data[2][2], (0, 0, 0, 0));
+    assert_eq!(data[0][2], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn default_viewport_follows_target() {
+    let display = support::build_display();
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    // drawing on a large texture with a custom viewport first
+    let large = support::build_renderable_texture(&display);
+    let params = glium::DrawParameters {
+        viewport: Some(glium::Rect {
+            left: 512,
+            bottom: 512,
+            width: 512,
+            height: 512,
+        }),
+        .. Default::default()
+    };
+    large.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                            &params).unwrap();
+
+    // the default viewport must then cover the whole small texture
+    let small = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    small.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    small.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                            &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = small.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn dont_draw_primitives() {
     let display = support::build_display();
@@ -668,6 +867,10 @@ fn cull_counterclockwise_trianglestrip() {
 
 macro_rules! blending_test {
     ($name:ident, $func:expr, $source:expr, $dest:expr, $result:expr) => (
+        blending_test!($name, $func, (1.0, 1.0, 1.0, 1.0), $source, $dest, $result);
+    );
+
+    ($name:ident, $func:expr, $constant:expr, $source:expr, $dest:expr, $result:expr) => (
         #[test]
         fn $name() {
             let display = support::build_display();
@@ -676,7 +879,7 @@ macro_rules! blending_test {
                 blend: glium::Blend {
                     color: $func,
                     alpha: $func,
-                    constant_value: (1.0, 1.0, 1.0, 1.0)
+                    constant_value: $constant
                 },
                 .. Default::default()
             };
@@ -771,67 +974,95 @@ blending_test!(one_plus_one, glium::BlendingFunction::Addition {
                },
                (0.0, 1.0, 1.0, 0.0), (1.0, 0.0, 0.0, 1.0), (255, 255, 255, 255));
 
+blending_test!(constant_color, glium::BlendingFunction::Addition {
+                   source: glium::LinearBlendingFactor::ConstantColor,
+                   destination: glium::LinearBlendingFactor::Zero,
+               },
+               (0.0, 1.0, 1.0, 1.0),
+               (0.0, 1.0, 1.0, 0.0), (1.0, 0.0, 0.0, 1.0), (0, 0, 0, 255));
+
+blending_test!(constant_alpha_crossfade, glium::BlendingFunction::Addition {
+                   source: glium::LinearBlendingFactor::OneMinusConstantAlpha,
+                   destination: glium::LinearBlendingFactor::ConstantAlpha,
+               },
+               (0.0, 0.0, 0.0, 1.0),
+               (0.0, 1.0, 1.0, 0.0), (1.0, 0.0, 0.0, 1.0), (0, 255, 255, 0));
 
 #[test]
-fn provoking_vertex_last() {
+fn blend_targets() {
     let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
 
-    let vertex_buffer = {
-        #[derive(Copy, Clone)]
-        struct Vertex {
-            value: (f32, f32),
-        }
-
-        implement_vertex!(Vertex, value);
-
-        glium::VertexBuffer::new(&display, &[
-            Vertex { value: (-1.0, 1.0) },
-            Vertex { value: (1.0, -1.0) },
-            Vertex { value: (-1.0, -1.0) },
-        ]).unwrap()
-    };
-
-    let program = glium::Program::from_source(&display, "
-            #version 100
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
 
-            attribute lowp vec2 value;
-            varying lowp flat float v_value;
+            attribute vec2 position;
 
             void main() {
-                v_value = value.y;
-                gl_Position = vec4(value, 0.0, 1.0);
+                gl_Position = vec4(position, 0.0, 1.0);
             }
         ",
         "
-            #version 100
+            #version 330
 
-            varying lowp flat float v_value;
+            layout(location = 0) out vec4 color1;
+            layout(location = 1) out vec4 color2;
 
             void main() {
-                gl_FragColor = vec4(1.0, (v_value + 1.0) * 0.5, 0.0, 1.0);
+                color1 = vec4(1.0, 0.0, 0.0, 0.0);
+                color2 = vec4(1.0, 0.0, 0.0, 0.0);
             }
-        ", None);
-    let program = match program {
-        Err(_) => return,
-        Ok(p) => p
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
     };
 
-    let texture = support::build_renderable_texture(&display);
-    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
-    texture.as_surface().draw(&vertex_buffer,
-        &glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList), &program,
-        &glium::uniforms::EmptyUniforms,
-        &glium::DrawParameters {
-            provoking_vertex: glium::draw_parameters::ProvokingVertex::LastVertex,
-            .. Default::default()
-        }).unwrap();
+    let color1 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               64, 64).unwrap();
+    let color2 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               64, 64).unwrap();
 
-    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("color2", &color2)].iter().cloned()).unwrap();
+    framebuffer.clear_color(0.0, 0.0, 1.0, 1.0);
 
-    // only the bottom-left half of the screen is filled
-    for row in data.iter().take(256) {
-        for pixel in row.iter().take(256) {
-            assert_eq!(pixel, &(255, 0, 0, 255));
+    let params = glium::DrawParameters {
+        blend: glium::Blend::additive(),
+        blend_targets: Some(0b10),
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Ok(_) => (),
+        Err(glium::DrawError::BlendingParameterNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    }
+
+    let state = display.capture_gl_state();
+    assert_eq!(state.blend_indexed[0], false);
+    assert_eq!(state.blend_indexed[1], true);
+
+    // the first attachment is written without blending
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 0));
+        }
+    }
+
+    // the second attachment is blended with its previous content
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 255, 255));
         }
     }
 
@@ -839,73 +1070,346 @@ fn provoking_vertex_last() {
 }
 
 #[test]
-fn provoking_vertex_first() {
+fn dual_source_blending() {
     let display = support::build_display();
 
-    let vertex_buffer = {
-        #[derive(Copy, Clone)]
-        struct Vertex {
-            value: (f32, f32),
-        }
-
-        implement_vertex!(Vertex, value);
-
-        glium::VertexBuffer::new(&display, &[
-            Vertex { value: (-1.0, 1.0) },
-            Vertex { value: (1.0, -1.0) },
-            Vertex { value: (-1.0, -1.0) },
-        ]).unwrap()
-    };
-
-    let program = glium::Program::from_source(&display, "
-            #version 100
+    let program = glium::Program::with_link_options(&display, glium::program::SourceCode {
+        vertex_shader: "
+            #version 140
 
-            attribute lowp vec2 value;
-            varying lowp flat float v_value;
+            in vec2 position;
 
             void main() {
-                v_value = value.y;
-                gl_Position = vec4(value, 0.0, 1.0);
+                gl_Position = vec4(position, 0.0, 1.0);
             }
         ",
-        "
-            #version 100
+        fragment_shader: "
+            #version 140
 
-            varying lowp flat float v_value;
+            out vec4 color;
+            out vec4 factor;
 
             void main() {
-                gl_FragColor = vec4(1.0, (v_value + 1.0) * 0.5, 0.0, 1.0);
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+                factor = vec4(0.0, 1.0, 0.0, 1.0);
             }
-        ", None);
+        ",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+    }, glium::program::LinkOptions {
+        dual_source_outputs: Some(("color", "factor")),
+        .. Default::default()
+    });
+
     let program = match program {
-        Err(_) => return,
-        Ok(p) => p
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::DualSourceBlendingNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
     };
 
-    let texture = support::build_renderable_texture(&display);
-    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
-    let res = texture.as_surface().draw(&vertex_buffer,
-        &glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList), &program,
-        &glium::uniforms::EmptyUniforms,
-        &glium::DrawParameters {
-            provoking_vertex: glium::draw_parameters::ProvokingVertex::FirstVertex,
-            .. Default::default()
-        });
-
-    match res {
-        Ok(_) => (),
-        Err(glium::DrawError::ProvokingVertexNotSupported) => {
-            display.assert_no_error(None);
-            return;
+    let params = glium::DrawParameters {
+        blend: glium::Blend {
+            color: glium::BlendingFunction::Addition {
+                source: glium::LinearBlendingFactor::One,
+                destination: glium::LinearBlendingFactor::SourceOneColor,
+            },
+            alpha: glium::BlendingFunction::Addition {
+                source: glium::LinearBlendingFactor::Zero,
+                destination: glium::LinearBlendingFactor::One,
+            },
+            constant_value: (0.0, 0.0, 0.0, 0.0),
         },
-        e => e.unwrap(),
-    }
+        .. Default::default()
+    };
 
-    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
 
-    // only the bottom-left half of the screen is filled
-    for row in data.iter().take(256) {
-        for pixel in row.iter().take(256) {
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 1.0, 1.0, 1.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dual_source_frag_data_index() {
+    let display = support::build_display();
+
+    let program = glium::Program::with_link_options(&display, glium::program::SourceCode {
+        vertex_shader: "
+            #version 140
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 140
+
+            out vec4 color;
+            out vec4 factor;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+                factor = vec4(0.0, 1.0, 0.0, 1.0);
+            }
+        ",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+    }, glium::program::LinkOptions {
+        dual_source_outputs: Some(("color", "factor")),
+        .. Default::default()
+    });
+
+    let program = match program {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::DualSourceBlendingNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert_eq!(program.get_frag_data_location("color"), Some(0));
+    assert_eq!(program.get_frag_data_index("color"), Some(0));
+    assert_eq!(program.get_frag_data_location("factor"), Some(0));
+    assert_eq!(program.get_frag_data_index("factor"), Some(1));
+    assert_eq!(program.get_frag_data_index("unexisting"), None);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blend_presets() {
+    let display = support::build_display();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                uniform vec4 source;
+                out vec4 color;
+
+                void main() {
+                    color = source;
+                }
+            "
+        },
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform vec4 source;
+
+                void main() {
+                    gl_FragColor = source;
+                }
+            "
+        },
+    ).unwrap();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    let check = |blend, clear: (f32, f32, f32, f32), source: [f32; 4], expected: (u8, u8, u8, u8)| {
+        texture.as_surface().clear_color(clear.0, clear.1, clear.2, clear.3);
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{ source: source },
+                                  &glium::DrawParameters {
+                                      blend: blend,
+                                      .. Default::default()
+                                  }).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        let pixel = data[0][0];
+        assert!((pixel.0 as i32 - expected.0 as i32).abs() <= 1, "{:?}", pixel);
+        assert!((pixel.1 as i32 - expected.1 as i32).abs() <= 1, "{:?}", pixel);
+        assert!((pixel.2 as i32 - expected.2 as i32).abs() <= 1, "{:?}", pixel);
+        assert!((pixel.3 as i32 - expected.3 as i32).abs() <= 1, "{:?}", pixel);
+    };
+
+    // the alpha channel is blended like the colors
+    check(glium::Blend::alpha_blending(), (0.0, 0.0, 0.0, 0.0), [1.0, 0.0, 0.0, 0.5],
+          (128, 0, 0, 64));
+    // the alpha of the target is the coverage of both layers
+    check(glium::Blend::straight_alpha(), (0.0, 0.0, 0.0, 0.0), [1.0, 0.0, 0.0, 0.5],
+          (128, 0, 0, 128));
+    check(glium::Blend::premultiplied_alpha(), (0.0, 0.0, 1.0, 1.0), [0.5, 0.0, 0.0, 0.5],
+          (128, 0, 128, 255));
+    check(glium::Blend::additive(), (0.0, 0.0, 0.5, 0.5), [0.5, 0.0, 0.0, 0.0],
+          (128, 0, 128, 128));
+    check(glium::Blend::multiply(), (0.5, 1.0, 1.0, 1.0), [0.5, 0.0, 1.0, 0.5],
+          (64, 0, 255, 255));
+    check(glium::Blend::screen(), (0.5, 0.0, 0.0, 1.0), [0.5, 0.0, 0.0, 1.0],
+          (191, 0, 0, 255));
+    check(glium::Blend::disabled(), (0.0, 0.0, 0.0, 0.0), [0.0, 1.0, 0.0, 0.5],
+          (0, 255, 0, 128));
+
+    display.assert_no_error(None);
+}
+
+
+#[test]
+fn provoking_vertex_last() {
+    let display = support::build_display();
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            value: (f32, f32),
+        }
+
+        implement_vertex!(Vertex, value);
+
+        glium::VertexBuffer::new(&display, &[
+            Vertex { value: (-1.0, 1.0) },
+            Vertex { value: (1.0, -1.0) },
+            Vertex { value: (-1.0, -1.0) },
+        ]).unwrap()
+    };
+
+    let program = glium::Program::from_source(&display, "
+            #version 100
+
+            attribute lowp vec2 value;
+            varying lowp flat float v_value;
+
+            void main() {
+                v_value = value.y;
+                gl_Position = vec4(value, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 100
+
+            varying lowp flat float v_value;
+
+            void main() {
+                gl_FragColor = vec4(1.0, (v_value + 1.0) * 0.5, 0.0, 1.0);
+            }
+        ", None);
+    let program = match program {
+        Err(_) => return,
+        Ok(p) => p
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vertex_buffer,
+        &glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList), &program,
+        &glium::uniforms::EmptyUniforms,
+        &glium::DrawParameters {
+            provoking_vertex: glium::draw_parameters::ProvokingVertex::LastVertex,
+            .. Default::default()
+        }).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    // only the bottom-left half of the screen is filled
+    for row in data.iter().take(256) {
+        for pixel in row.iter().take(256) {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn provoking_vertex_first() {
+    let display = support::build_display();
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            value: (f32, f32),
+        }
+
+        implement_vertex!(Vertex, value);
+
+        glium::VertexBuffer::new(&display, &[
+            Vertex { value: (-1.0, 1.0) },
+            Vertex { value: (1.0, -1.0) },
+            Vertex { value: (-1.0, -1.0) },
+        ]).unwrap()
+    };
+
+    let program = glium::Program::from_source(&display, "
+            #version 100
+
+            attribute lowp vec2 value;
+            varying lowp flat float v_value;
+
+            void main() {
+                v_value = value.y;
+                gl_Position = vec4(value, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 100
+
+            varying lowp flat float v_value;
+
+            void main() {
+                gl_FragColor = vec4(1.0, (v_value + 1.0) * 0.5, 0.0, 1.0);
+            }
+        ", None);
+    let program = match program {
+        Err(_) => return,
+        Ok(p) => p
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    let res = texture.as_surface().draw(&vertex_buffer,
+        &glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList), &program,
+        &glium::uniforms::EmptyUniforms,
+        &glium::DrawParameters {
+            provoking_vertex: glium::draw_parameters::ProvokingVertex::FirstVertex,
+            .. Default::default()
+        });
+
+    match res {
+        Ok(_) => (),
+        Err(glium::DrawError::ProvokingVertexNotSupported) => {
+            display.assert_no_error(None);
+            return;
+        },
+        e => e.unwrap(),
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    // only the bottom-left half of the screen is filled
+    for row in data.iter().take(256) {
+        for pixel in row.iter().take(256) {
             assert_eq!(pixel, &(255, 255, 0, 255));
         }
     }
@@ -1407,4 +1911,521 @@ fn primitive_restart_index() {
     }
 
     display.assert_no_error(None);
-}
\ No newline at end of file
+}
+
+#[test]
+fn conservative_rasterization() {
+    let display = support::build_display();
+
+    let params = glium::DrawParameters {
+        conservative_rasterization: true,
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::ConservativeRasterizationNotSupported) => return,
+        Ok(_) => (),
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn pipeline_state_key() {
+    use glium::draw_parameters::PipelineStateKey;
+    use std::collections::HashSet;
+
+    let display = support::build_display();
+    let (_, _, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_unicolor_texture2d(&display, 1.0, 0.0, 0.0);
+
+    let blending = glium::DrawParameters {
+        blend: glium::Blend::alpha_blending(),
+        .. Default::default()
+    };
+
+    let mut key1 = PipelineStateKey::new(&program, &Default::default());
+    key1.add_texture(&texture);
+    let mut key2 = PipelineStateKey::new(&program, &Default::default());
+    key2.add_texture(&texture);
+    let key3 = PipelineStateKey::new(&program, &blending);
+
+    assert_eq!(key1, key2);
+    assert!(key1 != key3);
+
+    let mut set = HashSet::new();
+    set.insert(key1.clone());
+    set.insert(key2);
+    set.insert(key3.clone());
+    assert_eq!(set.len(), 2);
+
+    let mut keys = vec![key3.clone(), key1.clone(), key3.clone()];
+    keys.sort();
+    assert!(keys[0] == keys[1] || keys[1] == keys[2]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clear_stencil_ignores_write_mask() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let stencil = match glium::framebuffer::StencilRenderBuffer::new(&display,
+                                                    glium::texture::StencilFormat::I8,
+                                                    texture.get_width(),
+                                                    texture.get_height().unwrap())
+    {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_stencil_buffer(&display,
+                                                                    &texture, &stencil).unwrap();
+
+    // drawing with a stencil write mask of 0, which must not affect the clear below
+    framebuffer.clear_color_and_stencil((0.0, 0.0, 0.0, 0.0), 0);
+    framebuffer.draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+        stencil: glium::draw_parameters::Stencil {
+            write_mask_clockwise: 0,
+            write_mask_counter_clockwise: 0,
+            .. Default::default()
+        },
+        .. Default::default()
+    }).unwrap();
+
+    framebuffer.clear_color_and_stencil((0.0, 0.0, 0.0, 0.0), 1);
+    framebuffer.draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+        stencil: glium::draw_parameters::Stencil {
+            test_clockwise: glium::StencilTest::IfEqual { mask: 0xff },
+            reference_value_clockwise: 1,
+            test_counter_clockwise: glium::StencilTest::IfEqual { mask: 0xff },
+            reference_value_counter_clockwise: 1,
+            .. Default::default()
+        },
+        .. Default::default()
+    }).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clear_depth_ignores_depth_write() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let depth = match glium::framebuffer::DepthRenderBuffer::new(&display,
+                                                    glium::texture::DepthFormat::I24,
+                                                    texture.get_width(),
+                                                    texture.get_height().unwrap())
+    {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                    &texture, &depth).unwrap();
+
+    // drawing with depth writes disabled, which must not affect the clear below
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 1.0);
+    framebuffer.draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::Overwrite,
+            write: false,
+            .. Default::default()
+        },
+        color_mask: (false, false, false, false),
+        .. Default::default()
+    }).unwrap();
+
+    framebuffer.clear_depth(0.0);
+    framebuffer.draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            .. Default::default()
+        },
+        .. Default::default()
+    }).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_test_without_depth_write() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            uniform float z;
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, z, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let texture = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    let depth = match glium::framebuffer::DepthRenderBuffer::new(&display,
+                                            glium::texture::DepthFormat::F32, 16, 16)
+    {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                    &texture, &depth).unwrap();
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 1.0);
+
+    // opaque object, at a depth of 0.5
+    framebuffer.draw(&vb, &ib, &program, &uniform!{ z: 0.0f32, color: [1.0, 0.0, 0.0, 1.0f32] },
+                     &glium::DrawParameters {
+                         depth: glium::Depth {
+                             test: glium::DepthTest::IfLess,
+                             write: true,
+                             .. Default::default()
+                         },
+                         .. Default::default()
+                     }).unwrap();
+
+    // transparent object in front of it, which must not write its depth of 0.25
+    let transparent = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: false,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+    framebuffer.draw(&vb, &ib, &program, &uniform!{ z: -0.5f32, color: [0.0, 1.0, 0.0, 1.0f32] },
+                     &transparent).unwrap();
+
+    // transparent object behind the opaque one, which must be hidden
+    framebuffer.draw(&vb, &ib, &program, &uniform!{ z: 0.5f32, color: [0.0, 0.0, 1.0, 1.0f32] },
+                     &transparent).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[8][8], (0, 255, 0, 255));
+
+    match framebuffer.read_depth_pixel(8, 8) {
+        Ok(value) => assert_eq!(value, 0.5),
+        Err(glium::texture::ReadError::AttachmentTypeNotSupported) => (),
+        Err(e) => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clear_buffer_ignores_color_mask() {
+    let display = support::build_display();
+
+    // `glClearBuffer` is required
+    if display.get_opengl_version() < &glium::Version(glium::Api::Gl, 3, 0) &&
+       display.get_opengl_version() < &glium::Version(glium::Api::GlEs, 3, 0)
+    {
+        return;
+    }
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+        color_mask: (false, false, false, false),
+        .. Default::default()
+    }).unwrap();
+
+    texture.main_level().first_layer().into_image(None).unwrap()
+           .raw_clear_buffer([0.0f32, 1.0, 0.0, 1.0]);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn smooth_points() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [0.0, 0.0] },
+    ]).unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let params = glium::DrawParameters {
+        smooth: Some(glium::Smooth::Nicest),
+        blend: glium::Blend::alpha_blending(),
+        point_size: Some(8.0),
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    match texture.as_surface().draw(&vb, &glium::index::NoIndices(PrimitiveType::Points),
+                                    &program, &uniform!{}, &params)
+    {
+        Ok(_) => (),
+        Err(glium::DrawError::SmoothingNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn smooth_polygons() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        smooth: Some(glium::Smooth::Nicest),
+        blend: glium::Blend::alpha_blending(),
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    // polygon smoothing is still available in the core profile, only OpenGL ES lacks it
+    match texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params) {
+        Ok(_) => (),
+        Err(glium::DrawError::SmoothingNotSupported) => {
+            assert!(display.get_opengl_version().0 != glium::Api::Gl);
+            return;
+        },
+        Err(e) => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn point_size() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let range = display.get_context().get_capabilities().point_size_range;
+    assert!(range.0 <= 1.0 && range.1 >= 1.0);
+    if range.1 < 8.0 || display.get_context().get_version().0 == glium::Api::GlEs {
+        return;
+    }
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [0.0, 0.0] },
+    ]).unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let params = glium::DrawParameters {
+        point_size: Some(8.0),
+        .. Default::default()
+    };
+
+    let texture = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &glium::index::NoIndices(PrimitiveType::Points),
+                              &program, &uniform!{}, &params).unwrap();
+
+    // the point covers the pixels 4 to 11 in both directions
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[3][3], (0, 0, 0, 0));
+    assert_eq!(data[4][4], (255, 0, 0, 255));
+    assert_eq!(data[11][11], (255, 0, 0, 255));
+    assert_eq!(data[12][12], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn no_state_leak_between_draws() {
+    // draws with each pair of parameters in sequence and checks that the second draw renders
+    // as if it were the only one
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    // the triangles of the fullscreen pipeline are clockwise
+    let params = [
+        (glium::DrawParameters::default(), (255, 0, 0, 255)),
+        (glium::DrawParameters {
+            backface_culling: glium::BackfaceCullingMode::CullClockwise,
+            .. Default::default()
+        }, (0, 0, 0, 0)),
+        (glium::DrawParameters {
+            backface_culling: glium::BackfaceCullingMode::CullCounterClockwise,
+            .. Default::default()
+        }, (255, 0, 0, 255)),
+        (glium::DrawParameters {
+            color_mask: (false, true, true, true),
+            .. Default::default()
+        }, (0, 0, 0, 255)),
+        (glium::DrawParameters {
+            backface_culling: glium::BackfaceCullingMode::CullCounterClockwise,
+            color_mask: (true, false, false, false),
+            .. Default::default()
+        }, (255, 0, 0, 0)),
+    ];
+
+    let texture = glium::Texture2d::empty(&display, 16, 16).unwrap();
+
+    for &(ref first, _) in params.iter() {
+        for &(ref second, expected) in params.iter() {
+            texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+            texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                      first).unwrap();
+
+            texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+            texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                      second).unwrap();
+
+            let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+            for row in data.iter() {
+                for pixel in row.iter() {
+                    assert_eq!(pixel, &expected);
+                }
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_mask_zero() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let multisampled = match glium::texture::Texture2dMultisample::empty(&display, 64, 64, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let params = glium::DrawParameters {
+        sample_mask: Some(0),
+        .. Default::default()
+    };
+
+    multisampled.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    match multisampled.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                         &params)
+    {
+        Err(glium::DrawError::SampleMaskNotSupported) => return,
+        Ok(_) => (),
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let texture = glium::Texture2d::empty(&display, 64, 64).unwrap();
+    texture.as_surface().clear_color(1.0, 1.0, 1.0, 1.0);
+    multisampled.as_surface().fill(&texture.as_surface(),
+                                   glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 0, 0, 0));
+        }
+    }
+
+    display.assert_no_error(None);
+}
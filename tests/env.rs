@@ -0,0 +1,33 @@
+#![cfg(feature = "env")]
+
+#[macro_use]
+extern crate glium;
+
+mod support;
+
+#[test]
+fn equirect_to_cubemap_and_prefilter() {
+    let display = support::build_display();
+
+    let panorama = glium::texture::Texture2d::new(&display, vec![
+        vec![(0.5f32, 0.5f32, 0.5f32, 1.0f32); 16];
+        8
+    ]).unwrap();
+
+    let environment = match glium::env::equirect_to_cubemap(&display, &panorama) {
+        Ok(t) => t,
+        Err(glium::env::EnvMapError::ProgramCreationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+    assert_eq!(environment.get_width(), 4);
+
+    let irradiance = glium::env::convolve_irradiance(&display, &environment).unwrap();
+    assert_eq!(irradiance.get_width(), 32);
+    assert_eq!(irradiance.get_mipmap_levels(), 1);
+
+    let specular = glium::env::prefilter_specular(&display, &environment).unwrap();
+    assert_eq!(specular.get_width(), 4);
+    assert!(specular.get_mipmap_levels() >= 2);
+
+    display.assert_no_error(None);
+}
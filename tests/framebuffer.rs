@@ -98,6 +98,47 @@ fn simple_render_to_texture() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn multisample_render_buffers_resolve() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let color = match glium::framebuffer::RenderBuffer::new_multisample(&display,
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                            128, 128, 4)
+    {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    let depth = match glium::framebuffer::DepthRenderBuffer::new_multisample(&display,
+                                            glium::texture::DepthFormat::I24, 128, 128, 4)
+    {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    assert_eq!(color.get_samples(), Some(4));
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                                   &color, &depth).unwrap();
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    // resolving the multisample buffer
+    let texture = glium::Texture2d::empty_with_format(&display,
+                                              glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                              glium::texture::MipmapsOption::NoMipmap,
+                                              128, 128).unwrap();
+    framebuffer.fill(&texture.as_surface(), glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back[127][127], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn depth_texture2d() {
     use std::iter;
@@ -254,6 +295,68 @@ fn multioutput() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn multioutput_read_from_attachment() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            out vec4 color1;
+            out vec4 color2;
+
+            void main() {
+                color1 = vec4(1.0, 1.0, 1.0, 1.0);
+                color2 = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+    let color2 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap();
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("color2", &color2)].iter().cloned()).unwrap();
+    framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = framebuffer.read_from_attachment(1).unwrap();
+    assert_eq!(read_back.len(), 128);
+    for row in read_back.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    let out_of_range: Option<Vec<Vec<(u8, u8, u8, u8)>>> = framebuffer.read_from_attachment(2);
+    assert!(out_of_range.is_none());
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn array_level() {
     let display = support::build_display();
@@ -322,6 +425,54 @@ fn multi_color_attachments_maximum() {
     glium::framebuffer::MultiOutputFrameBuffer::new(&display, colors).unwrap();
 }
 
+#[test]
+fn multi_color_attachments_above_draw_buffers() {
+    use glium::CapabilitiesSource;
+
+    // the limits are only checked in debug mode
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    // the test only works if there can be more attachments than draw buffers
+    let (max_draw_buffers, max_color_attachments) = {
+        let caps = display.get_context().get_capabilities();
+        (caps.max_draw_buffers as usize, caps.max_color_attachments as usize)
+    };
+
+    if max_color_attachments <= max_draw_buffers {
+        return;
+    }
+
+    let color_textures = (0 .. max_draw_buffers + 1)
+        .map(|_| {
+            glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               128, 128).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let colors = (0 .. color_textures.len()).map(|i| {("attachment", &color_textures[i])} );
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display, colors)
+                                                                                    .unwrap();
+
+    match framebuffer.draw(&vb, &ib, &program, &uniform!{}, &Default::default()) {
+        Err(err @ glium::DrawError::TooManyColorAttachments { .. }) => {
+            assert_eq!(err.to_string(),
+                       format!("The framebuffer has more color attachments than what the \
+                                backend can draw to: {} used, maximum is {}",
+                               max_draw_buffers + 1, max_draw_buffers));
+        },
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
 #[test]
 #[should_panic]
 fn empty_framebuffer_wrong_layers() {
@@ -447,3 +598,502 @@ fn empty_framebuffer_simple_draw() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn simple_framebuffer_get_id() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+    assert_eq!(texture.get_texture_target(), 0x0DE1);       // GL_TEXTURE_2D
+
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    let id = framebuffer.get_id();
+    assert!(id != 0);
+    assert_eq!(framebuffer.get_id(), id);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn framebuffer_guard_restores_binding() {
+    use glium::GlObject;
+    use glium::backend::Facade;
+    use glium::framebuffer::{FramebufferBinding, FramebufferGuard};
+
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+
+    // creating the framebuffer object may change the bindings
+    let binding = FramebufferBinding::new(framebuffer.get_id());
+    let before = display.get_context().current_framebuffer();
+
+    {
+        let guard = unsafe { FramebufferGuard::new(&display, binding) };
+        assert_eq!(guard.get_previous(), before);
+        assert_eq!(display.get_context().current_framebuffer(), binding);
+    }
+
+    assert_eq!(display.get_context().current_framebuffer(), before);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_only_framebuffer() {
+    let display = support::build_display();
+
+    let depth = match glium::texture::DepthTexture2d::empty(&display, 128, 128) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::depth_only(&display,
+                                                                            &depth).unwrap();
+    assert_eq!(framebuffer.check_completeness(), Ok(()));
+    assert_eq!(framebuffer.get_dimensions(), (128, 128));
+
+    framebuffer.clear_depth(1.0);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let params = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn aspect_ratio() {
+    let display = support::build_display();
+
+    let color = glium::Texture2d::empty(&display, 256, 128).unwrap();
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &color).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (256, 128));
+    assert_eq!(framebuffer.aspect_ratio(), 2.0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn mipmap_level_attachment() {
+    let display = support::build_display();
+
+    let texture = glium::Texture2d::empty_with_mipmaps(&display,
+                                                       glium::texture::MipmapsOption::EmptyMipmaps,
+                                                       128, 64).unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let level = texture.mipmap(1).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, level).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (64, 32));
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let rect = glium::Rect { left: 0, bottom: 0, width: 64, height: 32 };
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = level.first_layer().into_image(None).unwrap()
+                                                .raw_read(&rect);
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    // the main level is untouched
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn generate_mipmaps_after_drawing() {
+    let display = support::build_display();
+
+    let texture = glium::Texture2d::empty_with_mipmaps(&display,
+                                                       glium::texture::MipmapsOption::EmptyMipmaps,
+                                                       64, 64).unwrap();
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+    texture.generate_mipmaps();
+
+    let level = texture.mipmap(1).unwrap();
+    let rect = glium::Rect { left: 0, bottom: 0, width: 32, height: 32 };
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = level.first_layer().into_image(None).unwrap()
+                                                .raw_read(&rect);
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    // only the second level is regenerated
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+    match texture.generate_mipmaps_range(0, 1) {
+        Ok(_) => (),
+        Err(_) => return
+    };
+    assert_eq!(texture.get_levels_range(), (0, texture.get_mipmap_levels() - 1));
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = level.first_layer().into_image(None).unwrap()
+                                                .raw_read(&rect);
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    let level = texture.mipmap(2).unwrap();
+    let rect = glium::Rect { left: 0, bottom: 0, width: 16, height: 16 };
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = level.first_layer().into_image(None).unwrap()
+                                                .raw_read(&rect);
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn empty_framebuffer_draw() {
+    use glium::framebuffer::EmptyFrameBuffer;
+
+    let display = support::build_display();
+
+    // ignore the test
+    if !EmptyFrameBuffer::is_supported(&display) {
+        return;
+    }
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut framebuffer = EmptyFrameBuffer::new(&display, 64, 32, None, None, true).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (64, 32));
+
+    let params = glium::DrawParameters {
+        samples_passed_query: Some((&query).into()),
+        .. Default::default()
+    };
+
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+    assert_eq!(query.get(), 64 * 32);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clear_color_unsigned() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::UnsignedTexture2d::empty_with_format(&display,
+                                        glium::texture::UncompressedUintFormat::U32U32U32U32,
+                                        glium::texture::MipmapsOption::NoMipmap, 4, 4)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color_unsigned([1, 2, 3, 70000]);
+
+    let texture: &glium::texture::TextureAny = &texture;
+    let rect = glium::Rect { left: 0, bottom: 0, width: 4, height: 4 };
+    let image = texture.main_level().first_layer().into_image(None).unwrap();
+    let data: Vec<Vec<(u32, u32, u32, u32)>> = image.raw_read(&rect);
+    assert_eq!(data[2][3], (1, 2, 3, 70000));
+
+    // floating-point colors are converted
+    framebuffer.clear_color(5.0, 6.0, 7.0, 8.0);
+    let data: Vec<Vec<(u32, u32, u32, u32)>> = image.raw_read(&rect);
+    assert_eq!(data[2][3], (5, 6, 7, 8));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_depth() {
+    let display = support::build_display();
+
+    let color = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    let depth = match glium::framebuffer::DepthRenderBuffer::new(&display,
+                                            glium::texture::DepthFormat::F32, 16, 16)
+    {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                        &color, &depth).unwrap();
+    framebuffer.clear_depth(0.25);
+    framebuffer.clear(Some(&glium::Rect { left: 8, bottom: 0, width: 8, height: 16 }),
+                      None, false, Some(0.75), None);
+
+    match framebuffer.read_depth_pixel(2, 5) {
+        Ok(value) => assert_eq!(value, 0.25),
+        Err(glium::texture::ReadError::AttachmentTypeNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    }
+
+    let values = framebuffer.read_depth_rect(&glium::Rect { left: 6, bottom: 3, width: 4,
+                                                              height: 2 }).unwrap();
+    assert_eq!(values, vec![0.25, 0.25, 0.75, 0.75, 0.25, 0.25, 0.75, 0.75]);
+
+    let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &color).unwrap();
+    match framebuffer.read_depth_pixel(0, 0) {
+        Err(glium::texture::ReadError::NoDepthBuffer) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn feedback_loop_detected() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    let other = support::build_renderable_texture(&display);
+
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &other },
+                              &Default::default()).unwrap();
+
+    match texture.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &texture },
+                                    &Default::default())
+    {
+        Err(glium::DrawError::FeedbackLoop { texture_id, attachment }) => {
+            assert_eq!(texture_id, texture.get_id());
+            assert_eq!(attachment, glium::framebuffer::AttachmentPoint::Color(0));
+        },
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn feedback_loop_not_detected_after_compute() {
+    let display = support::build_display();
+
+    if !glium::program::ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let compute = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        uniform sampler2D tex;
+        layout(rgba8) uniform writeonly image2D img;
+
+        void main() {
+            imageStore(img, ivec2(0, 0), texelFetch(tex, ivec2(0, 0), 0));
+        }
+    ").unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    let output = glium::texture::Texture2d::empty_with_format(&display,
+                                        glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                        glium::texture::MipmapsOption::NoMipmap, 1, 1).unwrap();
+
+    let image = output.image_unit(glium::uniforms::ImageUnitFormat::RGBA8)
+                      .set_access(glium::uniforms::ImageUnitAccess::Write);
+    compute.execute(uniform! { tex: &texture, img: image }, 1, 1, 1);
+
+    // the texture is still bound to a texture unit, but the program below doesn't sample it
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn feedback_loop_not_detected_after_failed_draw() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &texture },
+                                    &Default::default())
+    {
+        Err(glium::DrawError::FeedbackLoop { .. }) => (),
+        _ => panic!()
+    };
+
+    // the failed draw left the texture bound, which must not be reported for the next draw
+    let (vb, ib, red) = support::build_fullscreen_red_pipeline(&display);
+    texture.as_surface().draw(&vb, &ib, &red, &uniform!{}, &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_barrier_programmable_blending() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                vec4 current = texture2D(texture, gl_FragCoord.xy / 1024.0);
+                gl_FragColor = vec4(current.r, 1.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+
+    if display.texture_barrier().is_err() {
+        return;
+    }
+
+    let sampler = texture.sampled().magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest)
+                         .minify_filter(glium::uniforms::MinifySamplerFilter::Nearest);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: sampler },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_3d_slice_attachment() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture3d::empty(&display, 16, 16, 4) {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    {
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_texture_layer(
+                                                                &display, &texture, 0, 1).unwrap();
+        assert_eq!(framebuffer.get_dimensions(), (16, 16));
+        framebuffer.clear_color(1.0, 0.0, 0.0, 1.0);
+    }
+
+    {
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_texture_layer(
+                                                                &display, &texture, 0, 2).unwrap();
+        framebuffer.clear_color(0.0, 1.0, 0.0, 1.0);
+    }
+
+    let rect = glium::Rect { left: 0, bottom: 0, width: 16, height: 16 };
+    let mipmap = glium::texture::TextureAny::mipmap(&texture, 0).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = mipmap.layer(1).unwrap().into_image(None).unwrap()
+                                                 .raw_read(&rect);
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = mipmap.layer(2).unwrap().into_image(None).unwrap()
+                                                 .raw_read(&rect);
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    match glium::framebuffer::SimpleFrameBuffer::with_texture_layer(&display, &texture, 0, 4) {
+        Err(glium::framebuffer::ValidationError::LayerOutOfRange) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_on_incomplete_framebuffer() {
+    let display = support::build_display();
+
+    // shared-exponent textures can be sampled but are never color-renderable
+    let texture = match glium::texture::Texture2d::empty_with_format(&display,
+                                        glium::texture::UncompressedFloatFormat::F9F9F9,
+                                        glium::texture::MipmapsOption::NoMipmap, 16, 16)
+    {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    let reason = match framebuffer.check_completeness() {
+        Err(reason) => reason,
+        Ok(_) => panic!()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                           &Default::default())
+    {
+        Err(glium::DrawError::FramebufferIncomplete(r)) => assert_eq!(r, reason),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "gltf")]
+
+extern crate glium;
+
+use glium::uniforms::{SamplerWrapFunction, MinifySamplerFilter, MagnifySamplerFilter};
+use glium::vertex::AttributeType;
+
+#[test]
+fn sampler_from_gltf() {
+    assert_eq!(SamplerWrapFunction::from_gltf(10497), Some(SamplerWrapFunction::Repeat));
+    assert_eq!(SamplerWrapFunction::from_gltf(33071), Some(SamplerWrapFunction::Clamp));
+    assert_eq!(SamplerWrapFunction::from_gltf(0), None);
+
+    assert_eq!(MinifySamplerFilter::from_gltf(9984), Some(MinifySamplerFilter::NearestMipmapNearest));
+    assert_eq!(MinifySamplerFilter::from_gltf(1), None);
+
+    assert_eq!(MagnifySamplerFilter::from_gltf(9729), Some(MagnifySamplerFilter::Linear));
+    assert_eq!(MagnifySamplerFilter::from_gltf(9987), None);
+}
+
+#[test]
+fn accessor_to_attribute() {
+    let attr = glium::gltf::accessor_to_attribute("color", 12, glium::gltf::UNSIGNED_BYTE,
+                                                  "VEC4", true).unwrap();
+    assert_eq!(attr.name, "color");
+    assert_eq!(attr.offset, 12);
+    assert_eq!(attr.ty, AttributeType::U8U8U8U8);
+    assert!(attr.normalize);
+
+    assert_eq!(glium::gltf::accessor_attribute_type(glium::gltf::FLOAT, "MAT4"),
+               Some(AttributeType::F32x4x4));
+    assert_eq!(glium::gltf::accessor_attribute_type(glium::gltf::SHORT, "MAT2"), None);
+    assert_eq!(glium::gltf::accessor_attribute_type(glium::gltf::FLOAT, "VEC5"), None);
+}
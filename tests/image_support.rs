@@ -0,0 +1,44 @@
+#![cfg(feature = "image")]
+
+#[macro_use]
+extern crate glium;
+extern crate image;
+
+mod support;
+
+fn build_image() -> image::RgbaImage {
+    // the top row is red, the bottom row is green
+    image::ImageBuffer::from_fn(2, 2, |_, y| {
+        if y == 0 { image::Rgba([255, 0, 0, 255]) } else { image::Rgba([0, 255, 0, 255]) }
+    })
+}
+
+#[test]
+fn texture_2d_from_image() {
+    let display = support::build_display();
+
+    let image = image::DynamicImage::ImageRgba8(build_image());
+    let texture = glium::texture::Texture2d::from_image(&display, &image).unwrap();
+
+    // the first row of the texture is the bottom row of the image
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[1][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_read_to_image() {
+    let display = support::build_display();
+
+    let image = build_image();
+    let raw: glium::texture::RawImage2d<u8> = image.clone().into();
+    let texture = glium::texture::Texture2d::new(&display, raw).unwrap();
+
+    let read_back = texture.read_to_image();
+    assert_eq!(read_back.dimensions(), (2, 2));
+    assert_eq!(read_back.into_raw(), image.into_raw());
+
+    display.assert_no_error(None);
+}
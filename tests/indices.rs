@@ -187,6 +187,134 @@ fn get_indices_type_u32() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn new_auto_indices_type() {
+    let display = support::build_display();
+
+    let indices = glium::index::IndexBufferAny::new_auto(&display, PrimitiveType::TrianglesList,
+                                                         &[0, 1, 255]).unwrap();
+    assert_eq!(indices.get_indices_type(), glium::index::IndexType::U8);
+
+    let indices = glium::index::IndexBufferAny::new_auto(&display, PrimitiveType::TrianglesList,
+                                                         &[0, 1, 256]).unwrap();
+    assert_eq!(indices.get_indices_type(), glium::index::IndexType::U16);
+
+    let indices = glium::index::IndexBufferAny::new_auto(&display, PrimitiveType::TrianglesList,
+                                                         &[0, 1, 65536]);
+    match indices {
+        Err(glium::index::BufferCreationError::IndexTypeNotSupported) => (),
+        Ok(i) => assert_eq!(i.get_indices_type(), glium::index::IndexType::U32),
+        Err(e) => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn new_with_type_index_too_large() {
+    let display = support::build_display();
+
+    let indices = glium::index::IndexBufferAny::new_with_type(&display,
+                                                              PrimitiveType::TrianglesList,
+                                                              &[0, 1, 65536],
+                                                              glium::index::IndexType::U16);
+
+    match indices {
+        Err(glium::index::BufferCreationError::IndexTooLarge { value: 65536, .. }) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn new_auto_draw() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::index::IndexBufferAny::new_auto(&display, PrimitiveType::TrianglesList,
+                                                         &[0, 1, 2, 2, 1, 3]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &indices, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn triangles_list_with_range() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 3]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, indices.with_range(0, 3), &program,
+                              &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn with_range_out_of_bounds() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 3]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, indices.with_range(0, 4), &program,
+                                    &glium::uniforms::EmptyUniforms, &Default::default())
+    {
+        Err(glium::DrawError::InvalidIndicesRange) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+#[cfg(debug_assertions)]
+fn with_range_doesnt_bound_indices() {
+    let display = support::build_display();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 3]).unwrap();
+
+    indices.with_range(0, 2);
+}
+
 #[test]
 fn triangles_list_noindices() {
     let display = support::build_display();
@@ -215,6 +343,56 @@ fn triangles_list_noindices() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn triangles_list_noindices_range() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    // the first three vertices are outside of the viewport
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [2.0, 2.0] }, Vertex { position: [2.0, 3.0] },
+        Vertex { position: [3.0, 2.0] },
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [1.0,  1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, index::NoIndices::range(PrimitiveType::TrianglesList, 3, 6),
+                              &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn noindices_range_out_of_bounds() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, index::NoIndices::range(PrimitiveType::TriangleStrip, 1, 4),
+                                    &program, &glium::uniforms::EmptyUniforms,
+                                    &Default::default())
+    {
+        Err(glium::DrawError::VerticesRangeOutOfBounds) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn triangle_strip_noindices() {
     let display = support::build_display();
@@ -293,6 +471,20 @@ fn indexbuffer_slice_out_of_range() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn indexbuffer_slice_primitive_boundary() {
+    let display = support::build_display();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 3]).unwrap();
+
+    assert!(indices.slice(1 .. 4).is_none());
+    assert!(indices.slice(0 .. 4).is_none());
+    assert!(indices.slice(3 .. 6).unwrap().slice(1 .. 3).is_none());
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn indexbuffer_slice_draw() {
     let display = support::build_display();
@@ -329,6 +521,34 @@ fn indexbuffer_slice_draw() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn indexbuffer_slice_write() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 0, 0, 0, 0, 0]).unwrap();
+
+    let slice = indices.slice(3 .. 6).unwrap();
+    slice.write(&[0, 1, 3]);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &slice, &program,
+                &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn multidraw_array() {
     let display = support::build_display();
@@ -412,3 +632,202 @@ fn multidraw_elements() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn draw_list_submission_modes() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [0.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [0.0, -1.0] },
+        Vertex { position: [ 0.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [ 0.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 1, 3, 2]).unwrap();
+
+    let mut list = index::DrawList::new(&vb, &indices);
+    list.push(0 .. 6, 0);
+    list.push(0 .. 6, 4);
+    assert_eq!(list.len(), 2);
+
+    for &mode in &[index::SubmissionMode::Direct, index::SubmissionMode::Automatic,
+                   index::SubmissionMode::Indirect]
+    {
+        let texture = support::build_renderable_texture(&display);
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+        match list.submit(&mut texture.as_surface(), &program, &uniform!{},
+                          &Default::default(), mode)
+        {
+            Ok(()) => (),
+            Err(index::SubmitError::DrawError(glium::DrawError::IndirectDrawNotSupported)) => {
+                assert_eq!(mode, index::SubmissionMode::Indirect);
+                assert!(!index::is_multidraw_indirect_supported(&display));
+                continue;
+            },
+            Err(e) => panic!("{:?}", e),
+        }
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        for row in data.iter() {
+            for pixel in row.iter() {
+                assert_eq!(pixel, &(255, 0, 0, 255));
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multidraw_draw_id() {
+    let display = support::build_display();
+
+    if !index::is_shader_draw_parameters_supported(&display) {
+        return;
+    }
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 430
+            #extension GL_ARB_shader_draw_parameters : require
+
+            in vec2 position;
+            flat out int draw_id;
+
+            void main() {
+                draw_id = gl_DrawIDARB;
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 430
+
+            flat in int draw_id;
+            out vec4 color;
+
+            void main() {
+                color = draw_id == 0 ? vec4(1.0, 0.0, 0.0, 1.0) : vec4(0.0, 1.0, 0.0, 1.0);
+            }
+        ",
+        None);
+
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    // the left half of the screen, then the right half
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [0.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [0.0, -1.0] },
+        Vertex { position: [ 0.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [ 0.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let multidraw = match glium::index::DrawCommandsNoIndicesBuffer::empty(&display, 2) {
+        Ok(buf) => buf,
+        Err(_) => return
+    };
+
+    multidraw.write(&[
+        glium::index::DrawCommandNoIndices {
+            count: 4,
+            instance_count: 1,
+            first_index: 0,
+            base_instance: 0,
+        },
+        glium::index::DrawCommandNoIndices {
+            count: 4,
+            instance_count: 1,
+            first_index: 4,
+            base_instance: 0,
+        },
+    ]);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, multidraw.with_primitive_type(PrimitiveType::TriangleStrip),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        assert_eq!(row[0], (255, 0, 0, 255));
+        assert_eq!(row[row.len() - 1], (0, 255, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn grid_draw() {
+    let display = support::build_display();
+    let program = build_program(&display);
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [0.0, -1.0] },
+        Vertex { position: [1.0, -1.0] },
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [0.0,  1.0] },
+        Vertex { position: [1.0,  1.0] },
+    ]).unwrap();
+
+    let indices = glium::index::IndexBufferAny::grid(&display, 2, 1).unwrap();
+    assert_eq!(indices.get_indices_type(), glium::index::IndexType::U8);
+    assert_eq!(indices.get_elements_count(), 12);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &indices, &program, &glium::uniforms::EmptyUniforms,
+                              &glium::DrawParameters {
+                                  backface_culling: glium::BackfaceCullingMode::CullClockwise,
+                                  .. Default::default()
+                              }).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn grid_too_many_vertices() {
+    let display = support::build_display();
+
+    match glium::index::IndexBufferAny::grid(&display, 1 << 16, 1 << 16) {
+        Err(glium::index::BufferCreationError::TooManyVertices) => (),
+        _ => panic!()
+    };
+
+    match glium::index::IndexBufferAny::grid(&display, u32::max_value(), 0) {
+        Err(glium::index::BufferCreationError::TooManyVertices) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn quads_indices_type() {
+    let display = support::build_display();
+
+    let indices = glium::index::IndexBufferAny::quads(&display, 64).unwrap();
+    assert_eq!(indices.get_indices_type(), glium::index::IndexType::U8);
+    assert_eq!(indices.get_elements_count(), 64 * 6);
+
+    let indices = glium::index::IndexBufferAny::quads(&display, 65).unwrap();
+    assert_eq!(indices.get_indices_type(), glium::index::IndexType::U16);
+
+    match glium::index::IndexBufferAny::quads(&display, 1 << 30) {
+        Err(glium::index::BufferCreationError::TooManyVertices) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
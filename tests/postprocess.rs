@@ -0,0 +1,45 @@
+#![cfg(feature = "postprocess")]
+
+extern crate glium;
+
+use glium::Surface;
+use glium::postprocess::PingPong;
+use glium::texture::UncompressedFloatFormat;
+
+mod support;
+
+#[test]
+fn ping_pong_swap() {
+    let display = support::build_display();
+
+    let mut targets = PingPong::new(&display, UncompressedFloatFormat::U8U8U8U8, 8, 8).unwrap();
+
+    targets.dst().clear_color(1.0, 0.0, 0.0, 1.0);
+    targets.swap();
+    targets.dst().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = targets.src().read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    targets.swap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = targets.src().read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn ping_pong_resize() {
+    let display = support::build_display();
+
+    let mut targets = PingPong::new(&display, UncompressedFloatFormat::U8U8U8U8, 8, 8).unwrap();
+    targets.resize(16, 4).unwrap();
+
+    assert_eq!(targets.get_dimensions(), (16, 4));
+    assert_eq!(targets.src().get_width(), 16);
+    assert_eq!(targets.dst_texture().get_height(), Some(4));
+    assert_eq!(targets.dst().get_dimensions(), (16, 4));
+
+    display.assert_no_error(None);
+}
@@ -190,6 +190,91 @@ fn primitives_generated() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn primitives_generated_indexed() {
+    let display = support::build_display();
+
+    let query0 = match glium::draw_parameters::PrimitivesGeneratedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let query1 = match glium::draw_parameters::PrimitivesGeneratedQuery::new_indexed(&display, 1) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    assert_eq!(query1.get_stream(), 1);
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            primitives_generated_query: Some(&query0),
+            stream_primitives_generated_queries: &[&query1],
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+               .unwrap();
+    }
+
+    // without a geometry shader, all the primitives go to the stream 0
+    assert_eq!(query0.get(), 2);
+    assert_eq!(query1.get(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn primitives_generated_same_stream() {
+    let display = support::build_display();
+
+    let query0 = match glium::draw_parameters::PrimitivesGeneratedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let query1 = match glium::draw_parameters::PrimitivesGeneratedQuery::new_indexed(&display, 0) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+
+    let params = glium::DrawParameters {
+        primitives_generated_query: Some(&query0),
+        stream_primitives_generated_queries: &[&query1],
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::WrongQueryOperation) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn primitives_generated_invalid_stream() {
+    let display = support::build_display();
+
+    match glium::draw_parameters::PrimitivesGeneratedQuery::new_indexed(&display, 4) {
+        Err(glium::draw_parameters::QueryCreationError::NotSupported) => (),
+        Ok(_) => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
 // FIXME: add test for transform feedback query
 
 // FIXME: add more tests for conditional rendering
@@ -79,3 +79,428 @@ fn magnify_nearest_filtering() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn same_texture_different_samplers() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.4, 0.0));
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                uniform lowp sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.4, 0.0));
+                }
+            ",
+        }).unwrap();
+
+    let texture_data = vec![vec![(0u8, 0, 0), (255, 255, 255)]];
+    let texture = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+
+    // each draw must use its own filtering, and not the one of the previous draw
+    for &(filter, linear) in &[(glium::uniforms::MagnifySamplerFilter::Nearest, false),
+                               (glium::uniforms::MagnifySamplerFilter::Linear, true),
+                               (glium::uniforms::MagnifySamplerFilter::Nearest, false)]
+    {
+        let uniforms = uniform! {
+            texture: texture.sampled()
+                            .magnify_filter(filter)
+                            .minify_filter(match filter {
+                                glium::uniforms::MagnifySamplerFilter::Nearest =>
+                                    glium::uniforms::MinifySamplerFilter::Nearest,
+                                glium::uniforms::MagnifySamplerFilter::Linear =>
+                                    glium::uniforms::MinifySamplerFilter::Linear,
+                            })
+        };
+
+        output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+        output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        if linear {
+            assert!(data[0][0].0 > 0 && data[0][0].0 < 255);
+        } else {
+            assert_eq!(data[0][0], (0, 0, 0, 255));
+        }
+    }
+
+    // drawing without a sampler must use the `Linear` magnifying filter of the texture again
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform! { texture: &texture },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert!(data[0][0].0 > 0 && data[0][0].0 < 255);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_texture_comparison() {
+    use std::iter;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2DShadow depth;
+            uniform float reference;
+
+            void main() {
+                float lit = shadow2D(depth, vec3(0.5, 0.5, reference)).r;
+                gl_FragColor = vec4(lit, lit, lit, 1.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    // depth texture with a value of 0.5 everywhere
+    let depth_data = iter::repeat(iter::repeat(0.5f32).take(16).collect::<Vec<_>>())
+                                  .take(16).collect::<Vec<_>>();
+    let depth = match glium::texture::DepthTexture2d::new(&display, depth_data) {
+        Err(_) => return,
+        Ok(t) => t
+    };
+
+    let sampler = depth.sampled()
+                       .minify_filter(glium::uniforms::MinifySamplerFilter::Nearest)
+                       .magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest)
+                       .depth_texture_comparison(Some(glium::uniforms::DepthTextureComparison::LessOrEqual));
+
+    let output = support::build_renderable_texture(&display);
+
+    for &(reference, expected) in &[(0.25f32, 255u8), (0.75, 0)] {
+        let uniforms = uniform! {
+            depth: sampler,
+            reference: reference,
+        };
+
+        output.as_surface().clear_color(0.5, 0.5, 0.5, 0.5);
+        match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+            Ok(_) => (),
+            Err(glium::DrawError::SamplersNotSupported) => return,
+            Err(e) => panic!("{:?}", e)
+        };
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        assert_eq!(data[0][0], (expected, expected, expected, 255));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn mirror_clamp_wrap_function() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = support::build_sampling_program(&display, (-0.5, 0.5));
+
+    let texture_data = vec![vec![(255u8, 0, 0), (255, 0, 0)]];
+    let texture = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let wrap = glium::uniforms::SamplerWrapFunction::MirrorClamp;
+    let uniforms = uniform! {
+        texture: texture.sampled().wrap_function(wrap)
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Ok(_) => assert!(wrap.is_supported(&display)),
+        Err(glium::DrawError::SamplersNotSupported) => return,
+        Err(glium::DrawError::WrapFunctionNotSupported) => {
+            assert!(!wrap.is_supported(&display));
+            return;
+        },
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn anisotropy_out_of_range() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let program = support::build_sampling_program(&display, (0.5, 0.5));
+
+    let texture = glium::texture::Texture2d::new(&display, vec![vec![(255u8, 0, 0)]]).unwrap();
+    let output = support::build_renderable_texture(&display);
+
+    // 0 and 65535 are outside of the range accepted by OpenGL
+    for &level in &[0, 1, 16, 65535] {
+        output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+        let uniforms = uniform! {
+            texture: texture.sampled().anisotropy(level)
+        };
+
+        match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+            Ok(_) => (),
+            Err(glium::DrawError::SamplersNotSupported) => return,
+            Err(e) => panic!("{:?}", e)
+        };
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        assert_eq!(data[0][0], (255, 0, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn reduction_mode_max() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = support::build_sampling_program(&display, (0.5, 0.5));
+
+    let texture_data = vec![vec![(255u8, 0, 0), (0, 255, 0)]];
+    let texture = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let mode = glium::uniforms::SamplerReductionMode::Max;
+    let uniforms = uniform! {
+        texture: texture.sampled()
+                        .minify_filter(glium::uniforms::MinifySamplerFilter::Linear)
+                        .magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear)
+                        .reduction_mode(mode)
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Ok(_) => assert!(mode.is_supported(&display)),
+        Err(glium::DrawError::SamplersNotSupported) => return,
+        Err(glium::DrawError::ReductionModeNotSupported) => {
+            assert!(!mode.is_supported(&display));
+            return;
+        },
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn shared_sampler() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = support::build_sampling_program(&display, (0.5, 0.5));
+
+    let texture = glium::texture::Texture2d::new(&display, vec![vec![(255u8, 0, 0)]]).unwrap();
+
+    let behavior = glium::uniforms::SamplerBehavior {
+        minify_filter: glium::uniforms::MinifySamplerFilter::Nearest,
+        magnify_filter: glium::uniforms::MagnifySamplerFilter::Nearest,
+        .. Default::default()
+    };
+
+    let sampler = match glium::uniforms::SharedSampler::new(&display, behavior) {
+        Ok(s) => s,
+        Err(glium::DrawError::SamplersNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    for _ in 0 .. 2 {
+        output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: (&texture, &sampler) },
+                                 &Default::default()).unwrap();
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn shared_sampler_type_mismatch() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                uniform isampler2D texture;
+                out vec4 color;
+
+                void main() {
+                    color = vec4(texture(texture, vec2(0.5, 0.5)));
+                }
+            ",
+        },
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler1D texture;
+
+                void main() {
+                    gl_FragColor = texture1D(texture, 0.5);
+                }
+            ",
+        }).unwrap();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![vec![(255u8, 0, 0)]]).unwrap();
+
+    let sampler = match glium::uniforms::SharedSampler::new(&display, Default::default()) {
+        Ok(s) => s,
+        Err(glium::DrawError::SamplersNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let output = support::build_renderable_texture(&display);
+
+    match output.as_surface().draw(&vb, &ib, &program,
+                                   &uniform!{ texture: (&texture, &sampler) },
+                                   &Default::default())
+    {
+        Err(glium::DrawError::UniformTypeMismatch { .. }) => (),
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_render_target_clamp_linear_no_mip() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = support::build_sampling_program(&display, (0.5, 0.5));
+
+    let source = support::build_renderable_texture(&display);
+    source.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+
+    let behavior = glium::uniforms::SamplerBehavior::clamp_linear_no_mip();
+    let uniforms = uniform! {
+        texture: glium::uniforms::Sampler(&source, behavior)
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Ok(_) => (),
+        Err(glium::DrawError::SamplersNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn mipmap_filter_without_mipmaps() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = support::build_sampling_program(&display, (0.5, 0.5));
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                                      glium::texture::MipmapsOption::NoMipmap,
+                                                      2, 2).unwrap();
+    let output = support::build_renderable_texture(&display);
+
+    let uniforms = uniform! {
+        texture: texture.sampled()
+                        .minify_filter(glium::uniforms::MinifySamplerFilter::LinearMipmapLinear)
+    };
+
+    match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Ok(_) => (),
+        Err(glium::DrawError::SamplersNotSupported) => (),
+        Err(glium::DrawError::MipmapFilterWithoutMipmaps) => {
+            let version = *display.get_opengl_version();
+            assert!(version < glium::Version(glium::Api::Gl, 1, 2));
+        },
+        Err(e) => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
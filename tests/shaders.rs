@@ -79,6 +79,47 @@ fn program_compilation_error() {
 
 // This test is disabled because some OpenGL drivers don't catch
 // the linking error (even though they are supposed to)
+#[test]
+fn program_info_log() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            uniform float unused;
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    // the content of the logs is implementation-specific, but empty logs are never returned
+    if let Some(log) = program.info_log() {
+        assert!(!log.trim().is_empty());
+    }
+
+    for &stage in &[glium::program::ShaderStage::Vertex, glium::program::ShaderStage::Fragment] {
+        if let Some(log) = program.shader_info_log(stage) {
+            assert!(!log.trim().is_empty());
+        }
+    }
+
+    assert!(program.shader_info_log(glium::program::ShaderStage::Geometry).is_none());
+
+    display.assert_no_error(None);
+}
+
 #[test]
 #[ignore]
 fn program_linking_error() {
@@ -119,6 +160,70 @@ fn program_linking_error() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn spirv_invalid_binary() {
+    let display = support::build_display();
+
+    let garbage = glium::program::SpirvEntryPoint {
+        binary: &[0, 1, 2, 3, 4, 5, 6, 7],
+        entry_point: "main",
+        specialization_constants: &[(0, 1)],
+    };
+
+    let program = glium::Program::new(&display, glium::program::ProgramCreationInput::SpirV {
+        vertex_shader: garbage,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_shader: garbage,
+        outputs_srgb: false,
+        uses_point_size: false,
+    });
+
+    match program {
+        Err(glium::ProgramCreationError::SpirvNotSupported) => {
+            assert!(!glium::program::is_spirv_supported(&display));
+        },
+        Err(glium::CompilationError(_)) => {
+            assert!(glium::program::is_spirv_supported(&display));
+        },
+        _ => panic!()
+    };
+}
+
+#[test]
+fn spirv_entry_point_with_nul() {
+    let display = support::build_display();
+
+    let module = glium::program::SpirvEntryPoint {
+        binary: &[0, 1, 2, 3, 4, 5, 6, 7],
+        entry_point: "ma\0in",
+        specialization_constants: &[],
+    };
+
+    let program = glium::Program::new(&display, glium::program::ProgramCreationInput::SpirV {
+        vertex_shader: module,
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_shader: module,
+        outputs_srgb: false,
+        uses_point_size: false,
+    });
+
+    match program {
+        Err(glium::ProgramCreationError::SpirvNotSupported) => {
+            assert!(!glium::program::is_spirv_supported(&display));
+        },
+        Err(glium::ProgramCreationError::InvalidEntryPoint) => {
+            assert!(glium::program::is_spirv_supported(&display));
+        },
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_frag_data_location() {
     let display = support::build_display();
@@ -310,6 +415,32 @@ fn program_binary_reload() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn program_from_id_not_owned() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let wrapped = unsafe {
+        glium::Program::from_id(&display, program.get_id(), false).unwrap()
+    };
+    assert!(wrapped.get_attribute("position").is_some());
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &wrapped, &uniform!{}, &Default::default()).unwrap();
+
+    // the original program must still be usable after the wrapper is dropped
+    drop(wrapped);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn program_binary_working() {
     let display = support::build_display();
@@ -355,6 +486,148 @@ fn program_binary_working() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn explicit_attribute_locations() {
+    let display = support::build_display();
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+        transform_feedback_varyings: None,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+            attribute vec3 color;
+
+            varying vec3 v_color;
+
+            void main() {
+                v_color = color;
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 110
+
+            varying vec3 v_color;
+
+            void main() {
+                gl_FragColor = vec4(v_color, 1.0);
+            }
+        ",
+    };
+
+    let options = glium::program::LinkOptions {
+        attribute_locations: &[("position", 3), ("color", 1)],
+        .. Default::default()
+    };
+
+    let program = glium::Program::with_link_options(&display, source, options).unwrap();
+
+    assert_eq!(program.get_attribute("position").unwrap().location, 3);
+    assert_eq!(program.get_attribute("color").unwrap().location, 1);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn explicit_attribute_location_out_of_range() {
+    let display = support::build_display();
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+        transform_feedback_varyings: None,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+    };
+
+    let options = glium::program::LinkOptions {
+        attribute_locations: &[("position", 100000)],
+        .. Default::default()
+    };
+
+    match glium::Program::with_link_options(&display, source, options) {
+        Err(glium::program::ProgramCreationError::LocationOutOfRange) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn explicit_frag_data_locations() {
+    let display = support::build_display();
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+        transform_feedback_varyings: None,
+
+        vertex_shader: "
+            #version 130
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color2;
+            out vec4 color1;
+
+            void main() {
+                color1 = vec4(1.0, 0.0, 0.0, 1.0);
+                color2 = vec4(0.0, 1.0, 0.0, 1.0);
+            }
+        ",
+    };
+
+    let options = glium::program::LinkOptions {
+        frag_data_locations: &[("color1", 0), ("color2", 1)],
+        .. Default::default()
+    };
+
+    let program = match glium::Program::with_link_options(&display, source, options) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::FragDataLocationNotSupported) => return,
+        Err(glium::program::ProgramCreationError::LocationOutOfRange) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert_eq!(program.get_frag_data_location("color1"), Some(0));
+    assert_eq!(program.get_frag_data_location("color2"), Some(1));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_transform_feedback_varyings() {
     let display = support::build_display();
@@ -430,6 +703,72 @@ fn get_transform_feedback_varyings() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn mesh_shaders() {
+    let display = support::build_display();
+
+    let program = glium::Program::new(&display, glium::program::ProgramCreationInput::MeshShaders {
+        task_shader: None,
+        mesh_shader: "
+            #version 450
+            #extension GL_NV_mesh_shader : require
+
+            layout(local_size_x = 1) in;
+            layout(triangles, max_vertices = 3, max_primitives = 1) out;
+
+            void main() {
+                gl_MeshVerticesNV[0].gl_Position = vec4(-1.0, -1.0, 0.0, 1.0);
+                gl_MeshVerticesNV[1].gl_Position = vec4(3.0, -1.0, 0.0, 1.0);
+                gl_MeshVerticesNV[2].gl_Position = vec4(-1.0, 3.0, 0.0, 1.0);
+                gl_PrimitiveIndicesNV[0] = 0;
+                gl_PrimitiveIndicesNV[1] = 1;
+                gl_PrimitiveIndicesNV[2] = 2;
+                gl_PrimitiveCountNV = 1;
+            }
+        ",
+        fragment_shader: "
+            #version 450
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        outputs_srgb: false,
+    });
+
+    let program = match program {
+        Ok(p) => p,
+        Err(glium::ShaderTypeNotSupported) => {
+            assert!(!glium::program::is_mesh_shader_supported(&display));
+            return;
+        },
+        Err(e) => panic!("{:?}", e)
+    };
+    assert!(program.has_mesh_shader());
+
+    let texture = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+    texture.as_surface().draw_mesh_tasks(1, &program, &uniform!{},
+                                         &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    // mesh shader programs can't be used with the regular draw commands
+    match texture.as_surface().draw_fullscreen(&program, &uniform!{}, &Default::default()) {
+        Err(glium::DrawError::MeshShaderMismatch) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_output_primitives_simple() {
     let display = support::build_display();
@@ -856,3 +1195,267 @@ fn array_layout_offsets() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn compute_shader_resource_usage() {
+    let display = support::build_display();
+
+    if !glium::program::ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 8, local_size_y = 4, local_size_z = 2) in;
+
+        void main() {
+        }
+    ").unwrap();
+
+    let usage = program.resource_usage().unwrap();
+    assert_eq!(usage.work_group_size, (8, 4, 2));
+    assert_eq!(usage.invocations(), 64);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compute_shader_layered_image_unit() {
+    let display = support::build_display();
+
+    if !glium::program::ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        layout(rgba8) uniform writeonly image2DArray layers;
+
+        void main() {
+            ivec3 coords = ivec3(gl_GlobalInvocationID);
+            imageStore(layers, coords, vec4(float(coords.z), 1.0, 0.0, 1.0));
+        }
+    ").unwrap();
+
+    let texture = glium::texture::Texture2dArray::empty_with_format(&display,
+                                        glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                        glium::texture::MipmapsOption::NoMipmap, 2, 2, 2).unwrap();
+
+    let image = texture.image_unit(glium::uniforms::ImageUnitFormat::RGBA8)
+                       .set_access(glium::uniforms::ImageUnitAccess::Write)
+                       .layered().unwrap();
+
+    program.execute(uniform! { layers: image }, 2, 2, 2);
+
+    // glium must call `glMemoryBarrier` before reading
+    let texture: &glium::texture::TextureAny = &texture;
+    let rect = glium::Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    for layer in 0 .. 2 {
+        let image = texture.main_level().layer(layer).unwrap().into_image(None).unwrap();
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = image.raw_read(&rect);
+        assert_eq!(data[1][1], (layer as u8 * 255, 255, 0, 255));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compute_shader_image_unit_then_sample() {
+    let display = support::build_display();
+
+    if !glium::program::ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let compute = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        layout(rgba8) uniform writeonly image2D img;
+
+        void main() {
+            imageStore(img, ivec2(gl_GlobalInvocationID.xy), vec4(0.0, 1.0, 0.0, 1.0));
+        }
+    ").unwrap();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                                        glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                        glium::texture::MipmapsOption::NoMipmap, 2, 2).unwrap();
+
+    let image = texture.image_unit(glium::uniforms::ImageUnitFormat::RGBA8)
+                       .set_access(glium::uniforms::ImageUnitAccess::Write);
+    compute.execute(uniform! { img: image }, 2, 2, 1);
+
+    // no explicit memory barrier, glium must insert one before sampling the texture
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                uniform sampler2D tex;
+                out vec4 color;
+
+                void main() {
+                    color = texture(tex, vec2(0.5, 0.5));
+                }
+            "
+        }).unwrap();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+    let target = support::build_renderable_texture(&display);
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    target.as_surface().draw(&vb, &ib, &program, &uniform! { tex: &texture },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.unbind_image(0);
+    display.unbind_image(7);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_unit_layered_requires_layers() {
+    let display = support::build_display();
+    let texture = support::build_renderable_texture(&display);
+
+    let image = texture.image_unit(glium::uniforms::ImageUnitFormat::RGBA8);
+    assert_eq!(image.layered().err(), Some(glium::uniforms::LayeredImageUnitError));
+
+    if let Ok(cubemaps) = glium::texture::CubemapArray::empty(&display, 2, 2) {
+        let image = cubemaps.image_unit(glium::uniforms::ImageUnitFormat::RGBA8);
+        assert!(image.layered().is_ok());
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compute_shader_layered_cubemap_array_image_unit() {
+    let display = support::build_display();
+
+    if !glium::program::ComputeShader::is_supported(&display) ||
+       !glium::texture::is_cubemap_arrays_supported(&display)
+    {
+        return;
+    }
+
+    let program = glium::program::ComputeShader::from_source(&display, "
+        #version 430
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        layout(rgba8) uniform writeonly imageCubeArray cubes;
+
+        void main() {
+            ivec3 coords = ivec3(gl_GlobalInvocationID);
+
+            // the third coordinate is `layer * 6 + face`
+            if (coords.z == 7) {
+                imageStore(cubes, coords, vec4(0.0, 1.0, 0.0, 1.0));
+            } else {
+                imageStore(cubes, coords, vec4(1.0, 0.0, 0.0, 1.0));
+            }
+        }
+    ").unwrap();
+
+    let texture = glium::texture::CubemapArray::empty_with_format(&display,
+                                        glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                        glium::texture::MipmapsOption::NoMipmap, 2, 2).unwrap();
+
+    let image = texture.image_unit(glium::uniforms::ImageUnitFormat::RGBA8)
+                       .set_access(glium::uniforms::ImageUnitAccess::Write)
+                       .layered().unwrap();
+
+    program.execute(uniform! { cubes: image }, 2, 2, 12);
+
+    let texture: &glium::texture::TextureAny = &texture;
+    let rect = glium::Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let image = texture.main_level().layer(1).unwrap()
+                       .into_image(Some(glium::texture::CubeLayer::NegativeX)).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = image.raw_read(&rect);
+    assert_eq!(data[1][1], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn early_fragment_tests_detected() {
+    let display = support::build_display();
+
+    let vertex = "
+        #version 110
+
+        void main() {
+            gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+        }
+    ";
+
+    let program = glium::Program::from_source(&display, vertex, "
+        #version 110
+
+        void main() {
+            gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+        }
+    ", None).unwrap();
+    assert_eq!(program.has_early_fragment_tests(), Some(false));
+
+    if display.get_opengl_version() >= &glium::Version(glium::Api::Gl, 4, 2) {
+        let program = glium::Program::from_source(&display, "
+            #version 420
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ", "
+            #version 420
+
+            layout(early_fragment_tests) in;
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ", None).unwrap();
+        assert_eq!(program.has_early_fragment_tests(), Some(true));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn early_fragment_tests_in_comment() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display, "
+        #version 110
+
+        void main() {
+            gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+        }
+    ", "
+        #version 110
+
+        // layout(early_fragment_tests) in;
+        /* layout(early_fragment_tests) in; */
+
+        void main() {
+            gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+        }
+    ", None).unwrap();
+    assert_eq!(program.has_early_fragment_tests(), Some(false));
+
+    display.assert_no_error(None);
+}
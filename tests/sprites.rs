@@ -0,0 +1,49 @@
+#![cfg(feature = "sprites")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+
+mod support;
+
+#[test]
+fn draw_sprite_pixel_perfect() {
+    let display = support::build_display();
+
+    let mut sprites = match glium::sprites::Pixels2d::new(&display) {
+        Ok(s) => s,
+        Err(glium::sprites::SpritesError::ProgramCreationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let sprite = glium::Texture2d::new(&display, vec![
+        vec![(255u8, 0u8, 0u8, 255u8), (0u8, 255u8, 0u8, 255u8)],
+        vec![(0u8, 0u8, 255u8, 255u8), (255u8, 255u8, 255u8, 255u8)],
+    ]).unwrap();
+
+    let texture = glium::Texture2d::empty(&display, 8, 8).unwrap();
+    let mut surface = texture.as_surface();
+    surface.clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let mut batch = sprites.begin(&mut surface);
+        batch.draw_sprite(&sprite, glium::Rect { left: 3, bottom: 2, width: 2, height: 2 },
+                          glium::Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                          (1.0, 1.0, 1.0, 1.0));
+        batch.finish().unwrap();
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[2][3], (255, 0, 0, 255));
+    assert_eq!(data[2][4], (0, 255, 0, 255));
+    assert_eq!(data[3][3], (0, 0, 255, 255));
+    assert_eq!(data[3][4], (255, 255, 255, 255));
+    assert_eq!(data[2][2], (0, 0, 0, 0));
+    assert_eq!(data[1][3], (0, 0, 0, 0));
+    assert_eq!(data[4][4], (0, 0, 0, 0));
+    assert_eq!(data[3][5], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
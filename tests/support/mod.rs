@@ -10,6 +10,8 @@ use glium::backend::Facade;
 use glium::index::PrimitiveType;
 
 use std::env;
+use std::mem;
+use std::os::raw::c_void;
 
 /// Builds a headless display for tests.
 pub fn build_display() -> glium::Display {
@@ -169,7 +171,49 @@ pub fn build_rectangle_vb_ib<F: ?Sized>(facade: &F)
     )
 }
 
+/// Builds a program that draws, on the whole output, the value of the `sampler2D` uniform
+/// named `texture` at the given texture coordinates.
+///
+/// The program is meant to be used with `build_rectangle_vb_ib`.
+pub fn build_sampling_program<F: ?Sized>(facade: &F, coords: (f32, f32)) -> glium::Program
+    where F: Facade
+{
+    let fragment = format!("
+        #version 110
+
+        uniform sampler2D texture;
+
+        void main() {{
+            gl_FragColor = texture2D(texture, vec2({:?}, {:?}));
+        }}
+    ", coords.0, coords.1);
+
+    program!(facade,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: &fragment,
+        },
+    ).unwrap()
+}
+
 /// Builds a texture suitable for rendering.
 pub fn build_renderable_texture<F: ?Sized>(facade: &F) -> glium::Texture2d where F: Facade {
     glium::Texture2d::empty(facade, 1024, 1024).unwrap()
 }
+
+/// Calls an OpenGL function that takes a single `GLenum`, `GLuint` or `GLbitfield`, like
+/// `glEnable` or `glUseProgram`, with the loader passed by `Context::exec_gl`.
+pub unsafe fn call_gl(load: &Fn(&str) -> *const c_void, name: &str, value: u32) {
+    let ptr = load(name);
+    assert!(!ptr.is_null(), "{} is not available", name);
+    let function: extern "system" fn(u32) = mem::transmute(ptr);
+    function(value);
+}
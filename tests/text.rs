@@ -0,0 +1,83 @@
+#![cfg(feature = "text")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::Surface;
+use glium::text::{Glyph, GlyphBatch, TextError};
+use glium::uniforms::MagnifySamplerFilter;
+
+mod support;
+
+#[test]
+fn draw_glyphs() {
+    let display = support::build_display();
+
+    let mut glyphs = match GlyphBatch::new(&display) {
+        Ok(g) => g,
+        Err(TextError::ProgramCreationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+    glyphs.set_filter(MagnifySamplerFilter::Nearest);
+
+    // the left half of the atlas is opaque white, and the right half is transparent
+    let atlas = glium::Texture2d::new(&display, vec![
+        vec![(255u8, 255u8, 255u8, 255u8), (0u8, 0u8, 0u8, 0u8)],
+        vec![(255u8, 255u8, 255u8, 255u8), (0u8, 0u8, 0u8, 0u8)],
+    ]).unwrap();
+
+    glyphs.push_slice(&[
+        Glyph {
+            position: [0.0, 0.0],
+            size: [4.0, 4.0],
+            tex_coords: [0.0, 0.0, 0.5, 1.0],
+            color: [1.0, 0.0, 0.0, 1.0],
+        },
+        Glyph {
+            position: [4.0, 4.0],
+            size: [4.0, 4.0],
+            tex_coords: [0.5, 0.0, 1.0, 1.0],
+            color: [0.0, 1.0, 0.0, 1.0],
+        },
+        Glyph {
+            position: [4.0, 0.0],
+            size: [4.0, 4.0],
+            tex_coords: [0.0, 0.0, 0.5, 1.0],
+            color: [0.0, 0.0, 1.0, 1.0],
+        },
+    ]);
+    assert_eq!(glyphs.len(), 3);
+
+    let texture = glium::Texture2d::empty(&display, 8, 8).unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+    glyphs.draw(&mut texture.as_surface(), &atlas).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[1][1], (255, 0, 0, 255));
+    assert_eq!(data[1][5], (0, 0, 255, 255));
+    assert_eq!(data[5][5], (0, 0, 0, 255));
+    assert_eq!(data[5][1], (0, 0, 0, 255));
+
+    // the glyphs are kept after being drawn
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+    glyphs.draw(&mut texture.as_surface(), &atlas).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[1][1], (255, 0, 0, 255));
+
+    // growing the buffer beyond its initial capacity
+    glyphs.clear();
+    for _ in 0 .. 1000 {
+        glyphs.push(Glyph {
+            position: [0.0, 0.0],
+            size: [8.0, 8.0],
+            tex_coords: [0.0, 0.0, 0.5, 1.0],
+            color: [0.0, 1.0, 0.0, 1.0],
+        });
+    }
+
+    glyphs.draw(&mut texture.as_surface(), &atlas).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[5][5], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
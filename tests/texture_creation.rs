@@ -51,6 +51,35 @@ fn get_format_u8u8u8u8() {
     }
 }
 
+#[test]
+fn query_level_u8u8u8u8() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                                                       glium::texture::UncompressedFloatFormat::
+                                                       U8U8U8U8,
+                                                       glium::texture::MipmapsOption::
+                                                       EmptyMipmapsMax(1),
+                                                       128, 64).unwrap();
+
+    let info = match texture.query_level(1) {
+        Err(_) => return,
+        Ok(i) => i
+    };
+
+    assert_eq!(info.width, 64);
+    assert_eq!(info.height, 32);
+    assert_eq!(info.depth, 1);
+    assert!(info.red_bits >= 8);
+    assert!(info.green_bits >= 8);
+    assert!(info.blue_bits >= 8);
+    assert!(info.alpha_bits >= 8);
+    assert_eq!(info.depth_bits, 0);
+    assert_eq!(info.compressed_size, None);
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn depth_texture_1d_creation() {
     let display = support::build_display();
@@ -86,6 +115,47 @@ fn texture_2d_creation() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn texture_2d_creation_raw_bytes() {
+    let display = support::build_display();
+
+    // RGB data uploaded into an RGBA texture, 3 pixels wide so that rows aren't aligned
+    let data = [255u8, 0, 0, 0, 255, 0, 0, 0, 255,
+                255, 255, 0, 0, 255, 255, 255, 0, 255];
+
+    let texture = glium::texture::Texture2d::with_format_raw(&display, &data, 3, 2,
+                                    glium::texture::ClientFormat::U8U8U8,
+                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                    glium::texture::MipmapsOption::NoMipmap).unwrap();
+
+    assert_eq!(texture.get_width(), 3);
+    assert_eq!(texture.get_height(), Some(2));
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][2], (0, 0, 255, 255));
+    assert_eq!(read_back[1][0], (255, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_creation_raw_bytes_size_mismatch() {
+    let display = support::build_display();
+
+    let data = [0u8; 10];
+
+    match glium::texture::Texture2d::with_format_raw(&display, &data, 2, 2,
+                                    glium::texture::ClientFormat::U8U8U8,
+                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                    glium::texture::MipmapsOption::NoMipmap)
+    {
+        Err(glium::texture::TextureCreationError::DataSizeMismatch) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn empty_texture2d_u8u8u8u8() {
     let display = support::build_display();
@@ -154,6 +224,36 @@ fn compressed_texture_2d_creation() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn compressed_texture_2d_online_compression() {
+    let display = support::build_display();
+
+    let format = glium::texture::CompressedFormat::S3tcDxt1Alpha;
+    if !format.is_supported(&display) {
+        return;
+    }
+
+    let data = vec![vec![(255, 0, 0, 255u8); 4]; 4];
+    let texture = match glium::texture::CompressedTexture2d::with_format(&display, data, format,
+                                        glium::texture::CompressedMipmapsOption::NoMipmap)
+    {
+        Ok(t) => t,
+        Err(glium::texture::TextureCreationError::FormatNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    let (read_format, blocks) = texture.read_compressed_data().unwrap();
+    assert_eq!(read_format, format);
+    assert_eq!(blocks.len(), 8);
+
+    let cached = glium::texture::CompressedTexture2d::with_compressed_data(&display, &blocks,
+                                        4, 4, read_format,
+                                        glium::texture::CompressedMipmapsOption::NoMipmap).unwrap();
+    assert_eq!(cached.read_compressed_data().unwrap().1, blocks);
+
+    display.assert_no_error(None);
+}
+
 macro_rules! empty_texture_test {
     ($test_name:ident, $tex_ty:ident, [$($dims:expr),+],
      $w:expr, $h:expr, $d:expr, $s:expr) =>
@@ -384,3 +484,110 @@ fn upload_from_pixel_buffer_inverted() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn resizable_texture_2d() {
+    let display = support::build_display();
+
+    let mut texture = glium::texture::ResizableTexture2d::new(&display,
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                            glium::texture::MipmapsOption::NoMipmap,
+                                            64, 32).unwrap();
+    assert_eq!(texture.get_width(), 64);
+    assert_eq!(texture.get_height(), Some(32));
+
+    texture.resize(128, 16).unwrap();
+    assert_eq!(texture.get_width(), 128);
+    assert_eq!(texture.get_height(), Some(16));
+
+    texture.as_surface().clear_color(1.0, 0.0, 0.0, 1.0);
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data.len(), 16);
+    assert_eq!(data[0].len(), 128);
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sparse_texture_2d_commit() {
+    use glium::texture::sparse::{SparseTexture2d, SparseTextureCreationError};
+
+    let display = support::build_display();
+
+    let texture = match SparseTexture2d::new(&display,
+                                             glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                             glium::texture::MipmapsOption::NoMipmap,
+                                             4096, 4096)
+    {
+        Ok(t) => t,
+        Err(SparseTextureCreationError::NotSupported) => return,
+        Err(SparseTextureCreationError::FormatNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    let (page_width, page_height) = texture.get_page_size();
+    let region = glium::Rect { left: page_width, bottom: 0, width: page_width,
+                               height: page_height };
+    texture.commit_region(0, region, true);
+    texture.commit_region(0, region, false);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sparse_texture_2d_invalid_dimensions() {
+    use glium::texture::sparse::{SparseTexture2d, SparseTextureCreationError};
+
+    let display = support::build_display();
+
+    let format = glium::texture::UncompressedFloatFormat::U8U8U8U8;
+    let (page_width, page_height) = match SparseTexture2d::get_page_size_for_format(&display,
+                                                                                    format)
+    {
+        Some(s) => s,
+        None => return,
+    };
+
+    let texture = SparseTexture2d::new(&display, format, glium::texture::MipmapsOption::NoMipmap,
+                                       page_width + 1, page_height);
+    assert_eq!(texture.err(), Some(SparseTextureCreationError::UnalignedDimensions));
+
+    let texture = SparseTexture2d::new(&display, format, glium::texture::MipmapsOption::NoMipmap,
+                                       page_width * 65536, page_height);
+    assert_eq!(texture.err(), Some(SparseTextureCreationError::DimensionsTooLarge));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_multisample_array_creation() {
+    let display = support::build_display();
+
+    if !glium::texture::is_texture_2d_multisample_array_supported(&display) {
+        return;
+    }
+
+    let texture = glium::texture::Texture2dMultisampleArray::empty(&display, 64, 64, 4, 2).unwrap();
+    assert_eq!(texture.get_width(), 64);
+    assert_eq!(texture.get_array_size(), Some(4));
+    assert_eq!(texture.get_samples(), Some(2));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_multisample_array_too_many_samples() {
+    let display = support::build_display();
+
+    if !glium::texture::is_texture_2d_multisample_array_supported(&display) {
+        return;
+    }
+
+    match glium::texture::Texture2dMultisampleArray::empty(&display, 64, 64, 4, 4096) {
+        Err(glium::texture::TextureCreationError::SamplesNotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
@@ -40,6 +40,27 @@ fn texture_2d_read_pixelbuffer() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn texture_2d_read_rgb_odd_width() {
+    let display = support::build_display();
+
+    // rows of 3 RGB pixels are 9 bytes long, which is not a multiple of the default alignment
+    let data = vec![
+        vec![(0u8, 1u8, 2u8), (4u8, 8u8, 16u8), (32u8, 64u8, 128u8)],
+        vec![(1u8, 2u8, 4u8), (8u8, 16u8, 32u8), (64u8, 128u8, 255u8)],
+        vec![(2u8, 4u8, 8u8), (16u8, 32u8, 64u8), (128u8, 255u8, 0u8)],
+    ];
+
+    let texture = glium::texture::Texture2d::with_format(&display, data.clone(),
+                                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                                    glium::texture::MipmapsOption::NoMipmap).unwrap();
+
+    let read_back: Vec<Vec<(u8, u8, u8)>> = unsafe { texture.unchecked_read() };
+    assert_eq!(read_back, data);
+
+    display.assert_no_error(None);
+}
+
 macro_rules! read_texture_test {
     ($test_name:ident, $tex_ty:ident, $data_ty:ty, $data:expr) => (
         #[test]
@@ -134,3 +155,83 @@ read_texture_test!(read_unsignedtexture2darray, UnsignedTexture2dArray, (u8, u8,
 read_texture_test!(read_unsignedtexture3d, UnsignedTexture3d, (u8, u8, u8, u8),
     vec![vec![vec![(0, 1, 2, 3), (4, 5, 6, 7)], vec![(8, 9, 10, 11), (12, 13, 14, 15)]]]);*/
 // TODO: srgb textures
+
+#[test]
+fn texture_2d_read_to_slice() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8), (4u8, 8u8, 16u8)],
+        vec![(32u8, 64u8, 128u8), (32u8, 16u8, 4u8)],
+    ]).unwrap();
+
+    let image: glium::texture::TextureAnyImage = texture.main_level().into();
+    let rect = glium::Rect { left: 0, bottom: 0, width: 2, height: 2 };
+
+    let mut data = [(0u8, 0u8, 0u8, 0u8); 4];
+    image.raw_read_to_slice(&rect, &mut data[..]).unwrap();
+    assert_eq!(data[0], (0, 1, 2, 255));
+    assert_eq!(data[1], (4, 8, 16, 255));
+    assert_eq!(data[2], (32, 64, 128, 255));
+    assert_eq!(data[3], (32, 16, 4, 255));
+
+    let mut too_small = [(0u8, 0u8, 0u8, 0u8); 3];
+    match image.raw_read_to_slice(&rect, &mut too_small[..]) {
+        Err(glium::texture::ReadError::DestinationTooSmall) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_read_float_unclamped() {
+    let display = support::build_display();
+
+    let texture = match glium::Texture2d::empty_with_format(&display,
+                                        glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                                        glium::texture::MipmapsOption::NoMipmap, 8, 8)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let (vb, ib, _) = support::build_fullscreen_red_pipeline(&display);
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(2.0, 16.0, -1.0, 0.5);
+                }
+            ",
+        }).unwrap();
+
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let image: glium::texture::TextureAnyImage = texture.main_level().into();
+    let rect = glium::Rect { left: 0, bottom: 0, width: 8, height: 8 };
+
+    let mut data = [(0.0f32, 0.0f32, 0.0f32, 0.0f32); 64];
+    match image.raw_read_to_slice(&rect, &mut data[..]) {
+        Err(glium::texture::ReadError::OutputFormatNotSupported) => return,
+        res => res.unwrap()
+    };
+
+    for pixel in data.iter() {
+        assert_eq!(pixel, &(2.0, 16.0, -1.0, 0.5));
+    }
+
+    display.assert_no_error(None);
+}
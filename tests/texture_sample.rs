@@ -130,3 +130,564 @@ fn bindless_texture() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn bindless_textures_buffer() {
+    use glium::texture::bindless::{BindlessTexturesBuffer, BindlessTexturesBufferCreationError};
+    use glium::texture::bindless::HandlesLayout;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let red = glium::texture::Texture2d::new(&display, vec![
+        vec![(255, 0, 0, 255), (255, 0, 0, 255u8)],
+    ]).unwrap();
+
+    let green = glium::texture::Texture2d::new(&display, vec![
+        vec![(0, 255, 0, 255), (0, 255, 0, 255u8)],
+    ]).unwrap();
+
+    let buffer = match BindlessTexturesBuffer::new(&display, &[&red, &green],
+                                                   HandlesLayout::Std140)
+    {
+        Ok(b) => b,
+        Err(BindlessTexturesBufferCreationError::BindlessTexturesNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert_eq!(buffer.len(), 2);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 100
+
+            attribute lowp vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 400
+            #extension GL_ARB_bindless_texture : require
+
+            layout(std140) uniform Samplers {
+                sampler2D textures[2];
+            };
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = texture(textures[1], vec2(0.0, 0.0));
+            }
+        ",
+        None).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ Samplers: &buffer },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn bindless_textures_buffer_shared_residency() {
+    use glium::texture::bindless::{BindlessTexturesBuffer, BindlessTexturesBufferCreationError};
+    use glium::texture::bindless::HandlesLayout;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let green = glium::texture::Texture2d::new(&display, vec![
+        vec![(0, 255, 0, 255), (0, 255, 0, 255u8)],
+    ]).unwrap();
+
+    let first = match BindlessTexturesBuffer::new(&display, &[&green], HandlesLayout::Std140) {
+        Ok(b) => b,
+        Err(BindlessTexturesBufferCreationError::BindlessTexturesNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    // the handle is already resident, but must stay resident after `first` is destroyed
+    let second = BindlessTexturesBuffer::new(&display, &[&green], HandlesLayout::Std140).unwrap();
+    drop(first);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 100
+
+            attribute lowp vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 400
+            #extension GL_ARB_bindless_texture : require
+
+            layout(std140) uniform Samplers {
+                sampler2D textures[1];
+            };
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = texture(textures[0], vec2(0.0, 0.0));
+            }
+        ",
+        None).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ Samplers: &second },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn swizzle_mask() {
+    use glium::texture::Swizzle;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(255, 0, 0, 255), (255, 0, 0, 255)],
+        vec![(255, 0, 0, 255), (255, 0, 0, 255u8)],
+    ]).unwrap();
+
+    match texture.set_swizzle_mask((Swizzle::Zero, Swizzle::Red, Swizzle::One, Swizzle::Alpha)) {
+        Ok(_) => (),
+        Err(_) => return
+    };
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &texture },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_limited_mipmaps() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                            glium::texture::MipmapsOption::EmptyMipmapsMax(1),
+                                            4, 4).unwrap();
+    assert_eq!(texture.get_mipmap_levels(), 2);
+
+    let level0 = vec![vec![(255u8, 0u8, 0u8, 255u8); 4]; 4];
+    let level1 = vec![vec![(255u8, 0u8, 0u8, 255u8); 2]; 2];
+    texture.mipmap(0).unwrap().write(glium::Rect { left: 0, bottom: 0, width: 4, height: 4 },
+                                     level0);
+    texture.mipmap(1).unwrap().write(glium::Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                                     level1);
+
+    // sampling a level that hasn't been allocated, which must be clamped to the last level
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 130
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+
+            uniform sampler2D texture;
+            out vec4 color;
+
+            void main() {
+                color = textureLod(texture, vec2(0.5, 0.5), 2.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let sampler = texture.sampled()
+        .minify_filter(glium::uniforms::MinifySamplerFilter::NearestMipmapNearest);
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: sampler },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_levels_range() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = glium::texture::Texture2d::empty_with_mipmaps(&display,
+                                            glium::texture::MipmapsOption::EmptyMipmapsMax(1),
+                                            4, 4).unwrap();
+
+    // only the second level is written, like a streamed texture whose finest level hasn't been
+    // uploaded yet
+    let level1 = vec![vec![(0u8, 255u8, 0u8, 255u8); 2]; 2];
+    texture.mipmap(1).unwrap().write(glium::Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                                     level1);
+
+    assert_eq!(texture.get_levels_range(), (0, 1));
+    assert_eq!(texture.set_levels_range(1, 0), Err(glium::texture::LevelsRangeError::OutOfRange));
+    assert_eq!(texture.set_levels_range(0, 2), Err(glium::texture::LevelsRangeError::OutOfRange));
+
+    match texture.set_levels_range(1, 1) {
+        Ok(_) => (),
+        Err(_) => return
+    };
+    assert_eq!(texture.get_levels_range(), (1, 1));
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture;
+
+            void main() {
+                gl_FragColor = texture2D(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let sampler = texture.sampled()
+        .minify_filter(glium::uniforms::MinifySamplerFilter::Nearest)
+        .magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: sampler },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_rectangle_draw() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = match glium::texture::TextureRectangle::new(&display, vec![
+        vec![(0, 255, 0, 255), (255, 0, 0, 255)],
+        vec![(0, 255, 0, 255), (255, 0, 0, 255u8)],
+    ]) {
+        Ok(t) => t,
+        Err(glium::texture::TextureCreationError::TypeNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 140
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            uniform sampler2DRect texture;
+            out vec4 color;
+
+            void main() {
+                color = texture(texture, vec2(1.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: &texture },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_rectangle_invalid_sampler() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = match glium::texture::TextureRectangle::empty(&display, 4, 4) {
+        Ok(t) => t,
+        Err(glium::texture::TextureCreationError::TypeNotSupported) => return,
+        Err(e) => panic!("{:?}", e),
+    };
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 140
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            uniform sampler2DRect texture;
+            out vec4 color;
+
+            void main() {
+                color = texture(texture, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+
+    // the default sampler uses mirroring and mipmaps
+    match output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: texture.sampled() },
+                                   &Default::default())
+    {
+        Err(glium::DrawError::InvalidRectangleTextureSampler) => (),
+        Err(glium::DrawError::SamplersNotSupported) => (),
+        e => panic!("{:?}", e),
+    }
+
+    let sampler = texture.sampled()
+                         .wrap_function(glium::uniforms::SamplerWrapFunction::Clamp)
+                         .minify_filter(glium::uniforms::MinifySamplerFilter::Linear);
+
+    match output.as_surface().draw(&vb, &ib, &program, &uniform!{ texture: sampler },
+                                   &Default::default())
+    {
+        Ok(_) | Err(glium::DrawError::SamplersNotSupported) => (),
+        Err(e) => panic!("{:?}", e),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_stencil_texture_sample_stencil() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let texture = match glium::texture::DepthStencilTexture2d::empty(&display, 2, 2) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    match texture.set_depth_stencil_mode(glium::texture::DepthStencilMode::Stencil) {
+        Ok(_) => (),
+        Err(glium::texture::StencilTexturingNotSupportedError) => return,
+    };
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 140
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            uniform usampler2D texture;
+            out vec4 color;
+
+            void main() {
+                color = vec4(float(texelFetch(texture, ivec2(0, 0), 0).r) / 255.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    // writing a known value in the stencil part of the texture
+    {
+        let scratch = glium::Texture2d::empty(&display, 2, 2).unwrap();
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_stencil_buffer(
+                                                            &display, &scratch, &texture).unwrap();
+        framebuffer.clear_stencil(128);
+    }
+
+    // stencil textures are incomplete with linear filtering
+    let uniforms = uniform! {
+        texture: texture.sampled()
+                        .minify_filter(glium::uniforms::MinifySamplerFilter::Nearest)
+                        .magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest)
+    };
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (128, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn pinned_texture_draw() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let red = glium::texture::Texture2d::new(&display, vec![
+        vec![(255, 0, 0, 255), (255, 0, 0, 255)],
+        vec![(255, 0, 0, 255), (255, 0, 0, 255u8)],
+    ]).unwrap();
+
+    let green = glium::texture::Texture2d::new(&display, vec![
+        vec![(0, 255, 0, 255), (0, 255, 0, 255)],
+        vec![(0, 255, 0, 255), (0, 255, 0, 255u8)],
+    ]).unwrap();
+
+    display.pin_texture(&red, 1);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D texture1;
+            uniform sampler2D texture2;
+
+            void main() {
+                gl_FragColor = texture2D(texture1, vec2(0.5, 0.5)) +
+                               texture2D(texture2, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+    let mut green_unit = None;
+
+    for &(first, second) in [(&green, &red), (&red, &green), (&green, &green)].iter() {
+        output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        output.as_surface().draw(&vb, &ib, &program,
+                                 &uniform!{ texture1: first, texture2: second },
+                                 &Default::default()).unwrap();
+
+        // neither texture has been rebound: `red` never leaves its pinned unit and `green`
+        // stays in the unit it was bound to by the first draw
+        let units = display.get_context().capture_gl_state().textures_2d;
+        assert_eq!(units.iter().filter(|&&t| t == red.get_id()).count(), 1);
+        assert_eq!(units[1], red.get_id());
+        assert_eq!(units.iter().filter(|&&t| t == green.get_id()).count(), 1);
+        let unit = units.iter().position(|&t| t == green.get_id());
+        assert!(green_unit.is_none() || green_unit == unit);
+        green_unit = unit;
+
+        let expected = if first as *const _ == second as *const _ {
+            (0, 255, 0, 255)
+        } else {
+            (255, 255, 0, 255)
+        };
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        for row in data.iter() {
+            for pixel in row.iter() {
+                assert_eq!(pixel, &expected);
+            }
+        }
+    }
+
+    display.unpin_texture(&red);
+
+    display.assert_no_error(None);
+}
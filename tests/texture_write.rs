@@ -24,3 +24,98 @@ fn texture_2d_write() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn texture_2d_clear() {
+    let display = support::build_display();
+
+    // the fallback requires `glClearBuffer`
+    if display.get_opengl_version() < &glium::Version(glium::Api::Gl, 3, 0) &&
+       display.get_opengl_version() < &glium::Version(glium::Api::GlEs, 3, 0)
+    {
+        return;
+    }
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8), (4u8, 8u8, 16u8)],
+        vec![(32u8, 64u8, 128u8), (32u8, 16u8, 4u8)],
+    ]).unwrap();
+
+    texture.clear([0.0f32, 1.0, 0.0, 1.0]);
+    texture.clear_rect(0, &glium::Rect { bottom: 1, left: 1, width: 1, height: 1 },
+                       [1.0f32, 0.0, 0.0, 1.0]);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (0, 255, 0, 255));
+    assert_eq!(read_back[0][1], (0, 255, 0, 255));
+    assert_eq!(read_back[1][0], (0, 255, 0, 255));
+    assert_eq!(read_back[1][1], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn texture_2d_clear_wrong_kind() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 1u8, 2u8), (4u8, 8u8, 16u8)],
+    ]).unwrap();
+
+    texture.clear([0u32, 1, 0, 1]);
+}
+
+#[test]
+fn cubemap_write_face() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::Cubemap::empty(&display, 2) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    texture.write_face(glium::texture::CubeLayer::PositiveX, vec![
+        vec![(255u8, 0u8, 0u8, 255u8), (255u8, 0u8, 0u8, 255u8)],
+        vec![(255u8, 0u8, 0u8, 255u8), (255u8, 0u8, 0u8, 255u8)],
+    ]);
+
+    texture.write_face(glium::texture::CubeLayer::NegativeZ, vec![
+        vec![(0u8, 255u8, 0u8, 255u8), (0u8, 255u8, 0u8, 255u8)],
+        vec![(0u8, 255u8, 0u8, 255u8), (0u8, 255u8, 0u8, 255u8)],
+    ]);
+
+    let texture: &glium::texture::TextureAny = &texture;
+    let rect = glium::Rect { left: 0, bottom: 0, width: 2, height: 2 };
+
+    for &(face, color) in &[(glium::texture::CubeLayer::PositiveX, (255, 0, 0, 255)),
+                            (glium::texture::CubeLayer::NegativeZ, (0, 255, 0, 255))]
+    {
+        let image = texture.main_level().first_layer().into_image(Some(face)).unwrap();
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = image.raw_read(&rect);
+        for row in data.iter() {
+            for pixel in row.iter() {
+                assert_eq!(pixel, &color);
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multisample_image_raw_write() {
+    let display = support::build_display();
+
+    let texture = match glium::texture::Texture2dMultisample::empty(&display, 2, 2, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let image = texture.main_level().first_layer().into_image(None).unwrap();
+    let data = glium::texture::RawImage2d::from_raw_rgba(vec![0u8; 16], (2, 2));
+    let rect = glium::Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    assert!(image.raw_write(&rect, data).is_err());
+
+    display.assert_no_error(None);
+}
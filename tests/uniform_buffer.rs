@@ -158,6 +158,79 @@ fn block() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn shared_block() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform Camera {
+                vec3 color;
+            };
+
+            void main() {
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: (f32, f32, f32),
+    }
+
+    implement_uniform_block!(Data, color);
+
+    let buffer = match glium::uniforms::UniformBuffer::new(&display, Data { color: (1.0f32, 1.0f32, 0.0f32) }) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let registry = display.get_binding_point_registry();
+    let lights = registry.get_binding_point("Lights").unwrap();
+    let camera = registry.get_binding_point("Camera").unwrap();
+    assert!(lights != camera);
+    assert_eq!(registry.get_binding_point("Camera").unwrap(), camera);
+    assert_eq!(registry.get_assigned_binding_point("Camera"), Some(camera));
+
+    program.bind_shared_uniform_blocks().unwrap();
+    buffer.bind_to_shared_uniform_block("Camera").unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn block_wrong_type() {
     let display = support::build_display();
@@ -338,3 +411,191 @@ fn empty_uniform_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn uniform_ring() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                vec3 color;
+            };
+
+            void main() {
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: (f32, f32, f32),
+    }
+
+    implement_uniform_block!(Data, color);
+
+    let ring = match glium::uniforms::UniformRing::<Data>::new(&display, 2) {
+        Err(_) => return,
+        Ok(r) => r
+    };
+
+    let red = ring.push(&Data { color: (1.0, 0.0, 0.0) });
+    let green = ring.push(&Data { color: (0.0, 1.0, 0.0) });
+    assert_eq!(green.get_offset_bytes(), ring.stride());
+
+    let texture1 = support::build_renderable_texture(&display);
+    let texture2 = support::build_renderable_texture(&display);
+    texture1.as_surface().draw(&vb, &ib, &program, &uniform!{ MyBlock: red },
+                               &Default::default()).unwrap();
+    texture2.as_surface().draw(&vb, &ib, &program, &uniform!{ MyBlock: green },
+                               &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture1.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture2.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn slice_blocks() {
+    let display = support::build_display();
+
+    let buffer: glium::uniforms::UniformBuffer<[[f32; 4]]> =
+        match glium::uniforms::UniformBuffer::empty_unsized(&display, 16 * 64) {
+            Err(_) => return,
+            Ok(b) => b
+        };
+
+    let slice = buffer.slice_blocks(0, 4).unwrap();
+    assert_eq!(slice.get_offset_bytes(), 0);
+    assert_eq!(slice.len(), 4);
+
+    assert_eq!(buffer.slice_blocks(60, 5).err(),
+               Some(glium::uniforms::UniformBufferSliceError::OutOfRange));
+    assert_eq!(buffer.slice_blocks(0, 0).err(),
+               Some(glium::uniforms::UniformBufferSliceError::OutOfRange));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn slice_blocks_draw() {
+    use glium::CapabilitiesSource;
+
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                vec4 color;
+            };
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: [f32; 4],
+    }
+
+    implement_uniform_block!(Data, color);
+
+    let buffer: glium::uniforms::UniformBuffer<[Data]> =
+        match glium::uniforms::UniformBuffer::empty_unsized(&display, 16 * 64) {
+            Err(_) => return,
+            Ok(b) => b
+        };
+
+    let alignment = display.get_context().get_capabilities()
+                           .uniform_buffer_offset_alignment.unwrap_or(1) as usize;
+
+    // first element after the start of the buffer whose offset is correctly aligned
+    let aligned = match (1 .. 64).find(|i| (i * 16) % alignment == 0) {
+        Some(i) => i,
+        None => return
+    };
+
+    let mut data = vec![Data { color: [1.0, 0.0, 0.0, 1.0] }; 64];
+    data[aligned] = Data { color: [0.0, 1.0, 0.0, 1.0] };
+    buffer.write(&data[..]);
+
+    let slice = buffer.slice_blocks(aligned, 1).unwrap();
+    assert_eq!(slice.get_offset_bytes(), aligned * 16);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniform!{ MyBlock: slice },
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    if alignment > 16 {
+        assert_eq!(buffer.slice_blocks(1, 1).err(),
+                   Some(glium::uniforms::UniformBufferSliceError::MisalignedOffset {
+                       offset: 16,
+                       alignment: alignment,
+                   }));
+    }
+
+    display.assert_no_error(None);
+}
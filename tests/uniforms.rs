@@ -133,6 +133,122 @@ fn uniforms_storage_ignore_inactive_uniforms() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn uniform_set_reused() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+            uniform float alpha;
+
+            void main() {
+                gl_FragColor = vec4(color.rgb, alpha);
+            }
+        ",
+        None).unwrap();
+
+    let color = [1.0, 0.0, 0.0, 1.0f32];
+    let alpha1 = 0.5f32;
+    let alpha2 = 1.0f32;
+
+    let mut uniforms = glium::uniforms::UniformSet::new();
+    uniforms.set("color", &color);
+    uniforms.set("alpha", &alpha1);
+    assert_eq!(uniforms.len(), 2);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 128));
+
+    uniforms.set("alpha", &alpha2);
+    assert_eq!(uniforms.len(), 2);
+
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_struct_array() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    #[derive(Copy, Clone)]
+    struct Light {
+        color: [f32; 3],
+        intensity: f32,
+    }
+
+    implement_uniform_struct!(Light, color, intensity);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            struct Light {
+                vec3 color;
+                float intensity;
+            };
+
+            uniform Light lights[2];
+
+            void main() {
+                vec3 color = lights[0].color * lights[0].intensity +
+                             lights[1].color * lights[1].intensity;
+                gl_FragColor = vec4(color, 1.0);
+            }
+        ",
+        None).unwrap();
+
+    let lights = [
+        Light { color: [1.0, 0.0, 0.0], intensity: 1.0 },
+        Light { color: [0.0, 0.0, 2.0], intensity: 0.5 },
+    ];
+
+    let mut uniforms = glium::uniforms::UniformSet::new();
+    uniforms.set_struct_array("lights", &lights);
+    assert_eq!(uniforms.len(), 4);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn uniform_wrong_type() {
     let display = support::build_display();
@@ -172,6 +288,53 @@ fn uniform_wrong_type() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn uniform_raw_bytes() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let color = [1.0, 0.0, 0.0, 0.5f32];
+    let bytes = unsafe { std::slice::from_raw_parts(color.as_ptr() as *const u8, 16) };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let uniforms = uniform!{ color: (glium::uniforms::UniformType::FloatVec4, &bytes[..]) };
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 128));
+
+    let uniforms = uniform!{ color: (glium::uniforms::UniformType::FloatVec4, &bytes[..12]) };
+    match texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Err(glium::DrawError::RawUniformSizeMismatch { expected: 16, obtained: 12, .. }) => (),
+        a => panic!("{:?}", a)
+    };
+
+    display.assert_no_error(None);
+}
+
 macro_rules! uniform_test(
     ($name:ident, $glsl_ty:expr, $value:expr) => (
         #[test]
@@ -88,3 +88,439 @@ fn transform_feedback() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn transform_feedback_object_draw() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let object = match glium::vertex::TransformFeedbackObject::new(&display) {
+        Ok(o) => o,
+        Err(glium::vertex::TransformFeedbackObjectCreationError::NotSupported) => return,
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let capture = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let capture = glium::Program::new(&display, capture).unwrap();
+
+    let draw = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 output_val;
+
+                void main() {
+                    gl_Position = vec4(output_val, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 6).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::with_object(&display, &capture,
+                                                                           &mut out_buffer,
+                                                                           &object).unwrap();
+
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &capture, &uniform!{}, &params).unwrap();
+    }
+
+    assert!(object.has_been_used());
+
+    texture.as_surface().draw(&out_buffer,
+                              object.indices(glium::index::PrimitiveType::TrianglesList, 0),
+                              &draw, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_multiple_objects() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (object1, object2) = match (glium::vertex::TransformFeedbackObject::new(&display),
+                                    glium::vertex::TransformFeedbackObject::new(&display))
+    {
+        (Ok(o1), Ok(o2)) => (o1, o2),
+        _ => return,
+    };
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let capture = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let capture = glium::Program::new(&display, capture).unwrap();
+
+    let mut buffer1: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 12).unwrap();
+    let mut buffer2: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 12).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    {
+        let session1 = glium::vertex::TransformFeedbackSession::with_object(&display, &capture,
+                                                                            &mut buffer1,
+                                                                            &object1).unwrap();
+        let session2 = glium::vertex::TransformFeedbackSession::with_object(&display, &capture,
+                                                                            &mut buffer2,
+                                                                            &object2).unwrap();
+
+        let params1 = glium::DrawParameters {
+            transform_feedback: Some(&session1),
+            .. Default::default()
+        };
+
+        let params2 = glium::DrawParameters {
+            transform_feedback: Some(&session2),
+            .. Default::default()
+        };
+
+        // the capture of `object1` is paused while `object2` is used, then resumed
+        texture.as_surface().draw(&vb, &ib, &capture, &uniform!{}, &params1).unwrap();
+        texture.as_surface().draw(&vb, &ib, &capture, &uniform!{}, &params2).unwrap();
+        texture.as_surface().draw(&vb, &ib, &capture, &uniform!{}, &params1).unwrap();
+    }
+
+    let result1 = match buffer1.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+    let result2 = buffer2.read().unwrap();
+
+    let expected = [(-1.0, 1.0), (1.0, 1.0), (-1.0, -1.0), (-1.0, -1.0), (1.0, 1.0), (1.0, -1.0)];
+    for (num, value) in expected.iter().enumerate() {
+        assert_eq!(result1[num].output_val, *value);
+        assert_eq!(result1[num + 6].output_val, *value);
+        assert_eq!(result2[num].output_val, *value);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_two_programs() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let build_capture = |factor: &str| {
+        let vertex_shader = format!("
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {{
+                output_val = position * {};
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }}
+        ", factor);
+
+        let source = glium::program::ProgramCreationInput::SourceCode {
+            tessellation_control_shader: None,
+            tessellation_evaluation_shader: None,
+            geometry_shader: None,
+            outputs_srgb: false,
+            uses_point_size: false,
+
+            vertex_shader: &vertex_shader,
+            fragment_shader: "
+                #version 130
+
+                out vec4 color;
+
+                void main() {
+                    color = vec4(1.0, 1.0, 1.0, 1.0);
+                }
+            ",
+
+            transform_feedback_varyings: Some((
+                vec!["output_val".to_string()],
+                glium::program::TransformFeedbackMode::Separate
+            )),
+        };
+
+        glium::Program::new(&display, source)
+    };
+
+    let capture1 = match build_capture("1.0") {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+    let capture2 = build_capture("2.0").unwrap();
+
+    let mut buffer1: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 6).unwrap();
+    let mut buffer2: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 6).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    {
+        let session1 = glium::vertex::TransformFeedbackSession::new(&display, &capture1,
+                                                                    &mut buffer1).unwrap();
+        let session2 = glium::vertex::TransformFeedbackSession::new(&display, &capture2,
+                                                                    &mut buffer2).unwrap();
+
+        let params1 = glium::DrawParameters {
+            transform_feedback: Some(&session1),
+            .. Default::default()
+        };
+
+        let params2 = glium::DrawParameters {
+            transform_feedback: Some(&session2),
+            .. Default::default()
+        };
+
+        // the program changes while the capture of `session1` is active
+        texture.as_surface().draw(&vb, &ib, &capture1, &uniform!{}, &params1).unwrap();
+        texture.as_surface().draw(&vb, &ib, &capture2, &uniform!{}, &params2).unwrap();
+        texture.as_surface().draw(&vb, &ib, &capture1, &uniform!{}, &params1).unwrap();
+    }
+
+    let result1 = match buffer1.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+    let result2 = buffer2.read().unwrap();
+
+    let expected = [(-1.0, 1.0), (1.0, 1.0), (-1.0, -1.0), (-1.0, -1.0), (1.0, 1.0), (1.0, -1.0)];
+    for (num, &(x, y)) in expected.iter().enumerate() {
+        assert_eq!(result1[num].output_val, (x, y));
+        assert_eq!(result2[num].output_val, (x * 2.0, y * 2.0));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_object_not_used() {
+    let display = support::build_display();
+
+    let object = match glium::vertex::TransformFeedbackObject::new(&display) {
+        Ok(o) => o,
+        Err(glium::vertex::TransformFeedbackObjectCreationError::NotSupported) => return,
+    };
+
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    match texture.as_surface().draw(&vb,
+                                    object.indices(glium::index::PrimitiveType::TrianglesList, 0),
+                                    &program, &uniform!{}, &Default::default())
+    {
+        Err(glium::DrawError::TransformFeedbackObjectNotUsed) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn vertex_format_reflection() {
+    use glium::vertex::{AttributeType, Vertex};
+
+    #[derive(Copy, Clone)]
+    struct MyVertex {
+        position: [f32; 3],
+        tex_coords: [f32; 2],
+        color: (u8, u8, u8, u8),
+    }
+
+    implement_vertex!(MyVertex, position, tex_coords, color);
+
+    let format = MyVertex::build_bindings();
+    assert_eq!(format.len(), 3);
+    assert_eq!(format[0].0, "position");
+    assert_eq!(format[0].2, AttributeType::F32F32F32);
+    assert_eq!(format[1].0, "tex_coords");
+    assert_eq!(format[1].2, AttributeType::F32F32);
+    assert_eq!(format[2].0, "color");
+    assert_eq!(format[2].2, AttributeType::U8U8U8U8);
+
+    let attributes = MyVertex::attributes();
+    assert_eq!(attributes.len(), format.len());
+    for (attribute, binding) in attributes.iter().zip(format.iter()) {
+        assert_eq!(attribute.name, binding.0);
+        assert_eq!(attribute.offset, binding.1);
+        assert_eq!(attribute.ty, binding.2);
+        assert_eq!(attribute.normalize, binding.3);
+        assert!(attribute.offset + attribute.ty.get_size_bytes() <=
+                std::mem::size_of::<MyVertex>());
+    }
+}
+
+#[test]
+fn vertex_format_per_field_normalization() {
+    use glium::vertex::{AttributeType, Vertex};
+
+    #[derive(Copy, Clone)]
+    struct MyVertex {
+        position: [f32; 3],
+        color: [u8; 4],
+        tex_coords: [u16; 2],
+    }
+
+    implement_vertex!(MyVertex, position, color normalize(true), tex_coords normalize(false));
+
+    let format = MyVertex::build_bindings();
+    assert_eq!(format.len(), 3);
+    assert_eq!(format[0].0, "position");
+    assert_eq!(format[0].3, false);
+    assert_eq!(format[1].0, "color");
+    assert_eq!(format[1].2, AttributeType::U8U8U8U8);
+    assert_eq!(format[1].3, true);
+    assert_eq!(format[2].0, "tex_coords");
+    assert_eq!(format[2].3, false);
+}
+
+#[test]
+fn dynamic_vertex_buffer_growth() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let mut buffer = glium::vertex::DynamicVertexBuffer::new(&display, 2).unwrap();
+    assert_eq!(buffer.len(), 0);
+    assert_eq!(buffer.capacity(), 2);
+
+    let data = (0 .. 5).map(|i| Vertex { position: [i as f32, 0.0] }).collect::<Vec<_>>();
+
+    buffer.push_slice(&data[0 .. 2]).unwrap();
+    assert_eq!(buffer.capacity(), 2);
+    buffer.push_slice(&data[2 .. 5]).unwrap();
+    assert_eq!(buffer.len(), 5);
+    assert!(buffer.capacity() >= 5);
+
+    let content = match buffer.finish().read() {
+        Ok(c) => c,
+        Err(_) => return
+    };
+    assert_eq!(content, data);
+
+    buffer.clear();
+    assert_eq!(buffer.len(), 0);
+    assert!(buffer.capacity() >= 5);
+    buffer.push_slice(&data[4 .. 5]).unwrap();
+    assert_eq!(buffer.finish().len(), 1);
+
+    display.assert_no_error(None);
+}
@@ -112,6 +112,164 @@ fn multiple_buffers_source() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn swap_buffers_same_layout() {
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+        color: [f32; 3],
+    }
+
+    implement_vertex!(Vertex, position, color);
+
+    let display = support::build_display();
+
+    let red = glium::VertexBuffer::new(&display,
+        &[
+            Vertex { position: [-1.0,  1.0], color: [1.0, 0.0, 0.0] },
+            Vertex { position: [ 1.0,  1.0], color: [1.0, 0.0, 0.0] },
+            Vertex { position: [-1.0, -1.0], color: [1.0, 0.0, 0.0] },
+            Vertex { position: [ 1.0, -1.0], color: [1.0, 0.0, 0.0] },
+        ]
+    ).unwrap();
+
+    let green = glium::VertexBuffer::new(&display,
+        &[
+            Vertex { position: [-1.0,  1.0], color: [0.0, 1.0, 0.0] },
+            Vertex { position: [ 1.0,  1.0], color: [0.0, 1.0, 0.0] },
+            Vertex { position: [-1.0, -1.0], color: [0.0, 1.0, 0.0] },
+            Vertex { position: [ 1.0, -1.0], color: [0.0, 1.0, 0.0] },
+        ]
+    ).unwrap();
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                               &[0u16, 1, 2, 3]).unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec3 color;
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+                varying vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            ",
+        }).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    texture.as_surface().draw(&red, &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    texture.as_surface().draw(&green, &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    texture.as_surface().draw(&red, &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn swap_multiple_buffers_same_layout() {
+    #[derive(Copy, Clone)]
+    struct Position {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Position, position);
+
+    #[derive(Copy, Clone)]
+    struct Color {
+        color: [f32; 3],
+    }
+
+    implement_vertex!(Color, color);
+
+    let display = support::build_display();
+
+    let positions = [
+        Position { position: [-1.0,  1.0] }, Position { position: [ 1.0,  1.0] },
+        Position { position: [-1.0, -1.0] }, Position { position: [ 1.0, -1.0] },
+    ];
+
+    let positions1 = glium::VertexBuffer::new(&display, &positions).unwrap();
+    let positions2 = glium::VertexBuffer::new(&display, &positions).unwrap();
+    let red = glium::VertexBuffer::new(&display, &[Color { color: [1.0, 0.0, 0.0] }; 4]).unwrap();
+    let green = glium::VertexBuffer::new(&display, &[Color { color: [0.0, 1.0, 0.0] }; 4]).unwrap();
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                               &[0u16, 1, 2, 3]).unwrap();
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec3 color;
+
+                varying vec3 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+                varying vec3 v_color;
+
+                void main() {
+                    gl_FragColor = vec4(v_color, 1.0);
+                }
+            ",
+        }).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    // all the streams change between each draw call
+    texture.as_surface().draw((&positions1, &red), &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    texture.as_surface().draw((&positions2, &green), &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    // only the last stream changes
+    texture.as_surface().draw((&positions2, &red), &index_buffer, &program, &uniform!{},
+                              &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn slice_draw_indices() {
     #[derive(Copy, Clone)]
@@ -180,6 +338,80 @@ fn slice_draw_indices() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn slice_write_then_draw() {
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let display = support::build_display();
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            ",
+        }).unwrap();
+
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [0.0, 0.0] },
+        Vertex { position: [ 0.0,  0.0] }, Vertex { position: [0.0, 0.0] },
+    ]).unwrap();
+
+    // only the last three vertices are uploaded again
+    vb.slice(1 .. 4).unwrap().write(&[
+        Vertex { position: [1.0,  1.0] }, Vertex { position: [-1.0, -1.0] },
+        Vertex { position: [1.0, -1.0] },
+    ]);
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(vb.slice(1 .. 4).unwrap(), &indices, &program,
+                &glium::uniforms::EmptyUniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data.last().unwrap()[0], (0, 0, 0, 0));
+    assert_eq!(data[0].last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn slice_draw_noindices() {
     #[derive(Copy, Clone)]
@@ -447,6 +679,108 @@ fn attributes_marker_indices() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn attributes_marker_multiple_programs() {
+    let display = support::build_display();
+
+    let build_program = |red: f32| {
+        glium::Program::from_source(&display,
+            "
+                #version 140
+
+                void main() {
+                    if (gl_VertexID == 0) {
+                        gl_Position = vec4(-1.0, 1.0, 0.0, 1.0);
+                    } else if (gl_VertexID == 1) {
+                        gl_Position = vec4(1.0, 1.0, 0.0, 1.0);
+                    } else if (gl_VertexID == 2) {
+                        gl_Position = vec4(-1.0, -1.0, 0.0, 1.0);
+                    } else if (gl_VertexID == 3) {
+                        gl_Position = vec4(1.0, -1.0, 0.0, 1.0);
+                    }
+                }
+            ",
+            &format!("
+                #version 140
+
+                out vec4 color;
+
+                void main() {{
+                    color = vec4({:.1}, 0.0, 0.0, 1.0);
+                }}
+            ", red),
+            None)
+    };
+
+    let (program1, program2) = match (build_program(1.0), build_program(0.0)) {
+        (Ok(p1), Ok(p2)) => (p1, p2),
+        _ => return
+    };
+
+    let (vb, ib, red_program) = support::build_fullscreen_red_pipeline(&display);
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleStrip);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    texture.as_surface().draw(glium::vertex::EmptyVertexAttributes { len: 4 }, &indices,
+                              &program2, &uniform!{}, &Default::default()).unwrap();
+    texture.as_surface().draw(&vb, &ib, &red_program, &uniform!{},
+                              &Default::default()).unwrap();
+    texture.as_surface().draw(glium::vertex::EmptyVertexAttributes { len: 4 }, &indices,
+                              &program2, &uniform!{}, &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 255));
+
+    texture.as_surface().draw(glium::vertex::EmptyVertexAttributes { len: 4 }, &indices,
+                              &program1, &uniform!{}, &Default::default()).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn draw_fullscreen() {
+    let display = support::build_display();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 140
+
+            void main() {
+                vec2 pos = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+                gl_Position = vec4(pos * 2.0 - 1.0, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None) {
+        Ok(p) => p,
+        _ => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw_fullscreen(&program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn instancing() {
     let display = support::build_display();
@@ -544,6 +878,103 @@ fn instancing() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn instancing_slice_draw_indices() {
+    let display = support::build_display();
+
+    let buffer1 = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display,
+            &[
+                Vertex { position: [ 0.0,  0.0] },
+                Vertex { position: [ 0.0,  0.0] },
+                Vertex { position: [-1.0,  1.0] },
+                Vertex { position: [ 1.0,  1.0] },
+                Vertex { position: [-1.0, -1.0] },
+                Vertex { position: [ 1.0, -1.0] },
+            ]
+        ).unwrap()
+    };
+
+    let buffer2 = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            color: [f32; 3],
+        }
+
+        implement_vertex!(Vertex, color);
+
+        glium::vertex::VertexBuffer::new(&display,
+            &[
+                Vertex { color: [0.0, 0.0, 1.0] },
+                Vertex { color: [1.0, 0.0, 0.0] },
+            ]
+        ).unwrap()
+    };
+
+    let buffer2 = match buffer2.per_instance() {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    let index_buffer = glium::IndexBuffer::new(&display, PrimitiveType::TriangleStrip,
+                                               &[0u16, 1, 2, 3]).unwrap();
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 330
+
+            in vec2 position;
+            in vec3 color;
+
+            out vec3 v_color;
+            flat out int instance;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+                v_color = color;
+                instance = gl_InstanceID;
+            }
+        ",
+        "
+            #version 330
+            in vec3 v_color;
+            flat in int instance;
+
+            void main() {
+                if (instance != 1) {
+                    discard;
+                }
+
+                gl_FragColor = vec4(v_color, 1.0);
+            }
+        ",
+        None) {
+        Ok(p) => p,
+        _ => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw((buffer1.slice(2 .. 6).unwrap(), buffer2), &index_buffer,
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn per_instance_length_mismatch() {
     let display = support::build_display();
@@ -762,3 +1193,91 @@ fn normalized_vertex() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn raw_bytes_vertex_buffer() {
+    use glium::vertex::{AttributeType, VertexAttribDesc};
+
+    let display = support::build_display();
+
+    // each vertex is made of two `f32`s for the position followed by four `u8`s for the color
+    let mut data = Vec::new();
+    for &(x, y) in &[(-1.0f32, 1.0f32), (1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)] {
+        for value in &[x, y] {
+            let bits: u32 = unsafe { std::mem::transmute(*value) };
+            data.extend_from_slice(&[bits as u8, (bits >> 8) as u8, (bits >> 16) as u8,
+                                     (bits >> 24) as u8]);
+        }
+        data.extend_from_slice(&[255, 0, 0, 255]);
+    }
+
+    let attributes = [
+        VertexAttribDesc::new("position", 0, AttributeType::F32F32, false),
+        VertexAttribDesc::new("color", 8, AttributeType::U8U8U8U8, true),
+    ];
+
+    let vertex_buffer = glium::vertex::VertexBufferAny::from_raw(&display, &data, 12,
+                                                                 &attributes).unwrap();
+    assert_eq!(vertex_buffer.len(), 4);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+                attribute vec4 color;
+
+                varying vec4 v_color;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                    v_color = color;
+                }
+            ",
+            fragment: "
+                #version 110
+                varying vec4 v_color;
+
+                void main() {
+                    gl_FragColor = v_color;
+                }
+            ",
+        }).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vertex_buffer,
+                              &glium::index::NoIndices(PrimitiveType::TriangleStrip),
+                              &program, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn raw_bytes_attribute_out_of_bounds() {
+    use glium::vertex::{AttributeType, VertexAttribDesc};
+
+    let display = support::build_display();
+
+    let attributes = [VertexAttribDesc::new("position", 4, AttributeType::F32F32, false)];
+
+    match glium::vertex::VertexBufferAny::from_raw(&display, &[0; 16], 8, &attributes) {
+        Err(glium::vertex::BufferCreationError::AttributeOutOfBounds) => (),
+        _ => panic!()
+    };
+
+    match glium::vertex::VertexBufferAny::from_raw(&display, &[0; 15], 8, &[]) {
+        Err(glium::vertex::BufferCreationError::InvalidStride) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}